@@ -9,6 +9,7 @@ use serde_json::json;
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
 use tempfile::TempDir;
 use uuid::Uuid;
 
@@ -259,6 +260,7 @@ fn bench_load_session_messages(c: &mut Criterion) {
                 rt.block_on(async {
                     claude_code_history_viewer_lib::commands::session::load_session_messages(
                         black_box(path_str.clone()),
+                        None,
                     )
                     .await
                 })
@@ -352,7 +354,6 @@ fn bench_get_session_message_count(c: &mut Criterion) {
 
 /// Benchmark: Search messages
 fn bench_search_messages(c: &mut Criterion) {
-    let rt = tokio::runtime::Runtime::new().unwrap();
     let mut group = c.benchmark_group("search_messages");
 
     // Create a project structure for search
@@ -370,14 +371,14 @@ fn bench_search_messages(c: &mut Criterion) {
     for (name, query) in &queries {
         group.bench_with_input(BenchmarkId::new("query", name), query, |b, &q| {
             b.iter(|| {
-                rt.block_on(async {
-                    claude_code_history_viewer_lib::commands::session::search_messages(
-                        black_box(base_path.to_string_lossy().to_string()),
-                        black_box(q.to_string()),
-                        black_box(serde_json::json!({})),
-                    )
-                    .await
-                })
+                claude_code_history_viewer_lib::commands::session::run_message_search(
+                    black_box(&base_path.to_string_lossy()),
+                    black_box(q),
+                    black_box(&serde_json::json!({})),
+                    None,
+                    None,
+                    &AtomicBool::new(false),
+                )
             });
         });
     }
@@ -405,6 +406,7 @@ fn bench_load_project_sessions(c: &mut Criterion) {
                         claude_code_history_viewer_lib::commands::session::load_project_sessions(
                             black_box(path_str.clone()),
                             black_box(Some(false)),
+                            None,
                         )
                         .await
                     })
@@ -503,6 +505,8 @@ fn bench_get_global_stats_summary(c: &mut Criterion) {
                     rt.block_on(async {
                         claude_code_history_viewer_lib::commands::stats::get_global_stats_summary(
                             black_box(path_str.clone()),
+                            None,
+                            None,
                         )
                         .await
                     })
@@ -0,0 +1,338 @@
+//! Cost anomaly detection
+//!
+//! Builds the daily cost and token series of all sessions and flags days
+//! whose value is several standard deviations above the rolling mean of the
+//! preceding days.
+
+use crate::commands::retry_loops::total_tokens;
+use crate::commands::stats::{read_raw_log_entries, resolve_scope_session_files};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{CostAnomaly, CostAnomalyReport, DailyCost};
+use crate::pricing::estimate_cost_usd;
+use chrono::{DateTime, NaiveDate, Utc};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+/// Number of preceding days forming the baseline of a day
+const ROLLING_WINDOW_DAYS: usize = 14;
+
+/// Days of history needed before a day can be flagged
+const MIN_BASELINE_DAYS: usize = 7;
+
+/// Z-score from which a day counts as anomalous
+const Z_SCORE_THRESHOLD: f64 = 3.0;
+
+/// Lower bound on the baseline standard deviation, relative to its mean, so
+/// that small changes after a perfectly flat baseline are not flagged
+const MIN_RELATIVE_STD_DEV: f64 = 0.25;
+
+/// Event emitted to the frontend for each newly detected anomaly
+pub const COST_ANOMALY_EVENT: &str = "cost-anomaly";
+
+type AnomalyListener = Box<dyn Fn(&CostAnomaly) + Send + Sync>;
+
+/// Forwards anomaly notifications to the frontend (unset in tests)
+static ANOMALY_LISTENER: OnceLock<AnomalyListener> = OnceLock::new();
+
+/// Days already notified, so repeated checks don't notify twice
+static NOTIFIED_DATES: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+/// Register the callback that emits anomaly notifications to the frontend
+pub fn set_anomaly_listener(listener: impl Fn(&CostAnomaly) + Send + Sync + 'static) {
+    let _ = ANOMALY_LISTENER.set(Box::new(listener));
+}
+
+/// Notify the anomalies that were not notified before
+fn notify_new_anomalies(anomalies: &[CostAnomaly]) {
+    let Ok(mut notified) = NOTIFIED_DATES
+        .get_or_init(|| Mutex::new(HashSet::new()))
+        .lock()
+    else {
+        return;
+    };
+
+    for anomaly in anomalies {
+        if notified.insert(anomaly.date.clone()) {
+            if let Some(listener) = ANOMALY_LISTENER.get() {
+                listener(anomaly);
+            }
+        }
+    }
+}
+
+/// Cost and tokens per UTC day of one session file (one count per response id)
+fn session_daily_costs(session_path: &Path) -> BTreeMap<NaiveDate, (f64, u64)> {
+    let mut days: BTreeMap<NaiveDate, (f64, u64)> = BTreeMap::new();
+    let mut counted_responses: HashSet<String> = HashSet::new();
+
+    for entry in read_raw_log_entries(session_path) {
+        let Some(message) = &entry.message else {
+            continue;
+        };
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+        let Some(date) = entry
+            .timestamp
+            .as_deref()
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            .map(|ts| ts.with_timezone(&Utc).date_naive())
+        else {
+            continue;
+        };
+        if message
+            .id
+            .as_ref()
+            .is_some_and(|id| !counted_responses.insert(id.clone()))
+        {
+            continue;
+        }
+
+        let day = days.entry(date).or_default();
+        day.0 += estimate_cost_usd(message.model.as_deref(), entry.cwd.as_deref(), usage);
+        day.1 += total_tokens(usage);
+    }
+
+    days
+}
+
+/// Every day from `first` to `last` (inclusive), with zero for idle days
+fn dense_daily_series(
+    days: &BTreeMap<NaiveDate, (f64, u64)>,
+    first: NaiveDate,
+    last: NaiveDate,
+) -> Vec<DailyCost> {
+    first
+        .iter_days()
+        .take_while(|date| *date <= last)
+        .map(|date| {
+            let (cost_usd, total_tokens) = days.get(&date).copied().unwrap_or_default();
+            DailyCost {
+                date: date.format("%Y-%m-%d").to_string(),
+                cost_usd,
+                total_tokens,
+            }
+        })
+        .collect()
+}
+
+/// Mean of `baseline` and the z-score of `value` against it
+fn z_score(value: f64, baseline: &[f64]) -> (f64, Option<f64>) {
+    let n = baseline.len() as f64;
+    let mean = baseline.iter().sum::<f64>() / n;
+    if mean <= 0.0 {
+        return (mean, None);
+    }
+    let variance = baseline.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    let std_dev = variance.sqrt().max(mean * MIN_RELATIVE_STD_DEV);
+    (mean, Some((value - mean) / std_dev))
+}
+
+/// Days of `daily` (a dense series) far above their rolling baseline
+fn detect_anomalies(daily: &[DailyCost]) -> Vec<CostAnomaly> {
+    let mut anomalies = Vec::new();
+
+    for (idx, day) in daily.iter().enumerate().skip(MIN_BASELINE_DAYS) {
+        let window = &daily[idx.saturating_sub(ROLLING_WINDOW_DAYS)..idx];
+        let costs: Vec<f64> = window.iter().map(|d| d.cost_usd).collect();
+        let tokens: Vec<f64> = window.iter().map(|d| d.total_tokens as f64).collect();
+
+        let (baseline_cost_usd, cost_z_score) = z_score(day.cost_usd, &costs);
+        let (baseline_tokens, token_z_score) = z_score(day.total_tokens as f64, &tokens);
+        let is_anomaly = [cost_z_score, token_z_score]
+            .into_iter()
+            .flatten()
+            .any(|z| z >= Z_SCORE_THRESHOLD);
+        if !is_anomaly {
+            continue;
+        }
+
+        anomalies.push(CostAnomaly {
+            date: day.date.clone(),
+            cost_usd: day.cost_usd,
+            total_tokens: day.total_tokens,
+            baseline_cost_usd,
+            baseline_tokens,
+            cost_z_score,
+            token_z_score,
+            cost_ratio: (baseline_cost_usd > 0.0).then(|| day.cost_usd / baseline_cost_usd),
+        });
+    }
+
+    anomalies
+}
+
+fn parse_date(value: Option<&str>, name: &str) -> Result<Option<NaiveDate>, String> {
+    value
+        .map(|v| {
+            NaiveDate::parse_from_str(v, "%Y-%m-%d")
+                .map_err(|e| format!("Invalid {name} '{v}' (expected YYYY-MM-DD): {e}"))
+        })
+        .transpose()
+}
+
+/// Find days with unusually high spend or token usage across all projects
+///
+/// `start_date` and `end_date` (YYYY-MM-DD, inclusive) limit the reported
+/// range; days before it still feed the baseline. With `notify`, anomalies not
+/// seen before are also emitted as `cost-anomaly` events.
+#[tauri::command]
+pub async fn get_cost_anomalies(
+    claude_path: String,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    notify: Option<bool>,
+) -> Result<CostAnomalyReport, AppError> {
+    let _timer = OperationTimer::start("get_cost_anomalies");
+
+    let start = parse_date(start_date.as_deref(), "start date")?;
+    let end = parse_date(end_date.as_deref(), "end date")?;
+    if let (Some(start), Some(end)) = (start, end) {
+        if start > end {
+            return Err(AppError::invalid_input(
+                "Start date must not be after end date",
+            ));
+        }
+    }
+
+    let session_files = resolve_scope_session_files("global", &claude_path)?;
+    let days = session_files
+        .par_iter()
+        .map(|path| session_daily_costs(path))
+        .reduce(BTreeMap::new, |mut acc, session_days| {
+            for (date, (cost, tokens)) in session_days {
+                let day = acc.entry(date).or_default();
+                day.0 += cost;
+                day.1 += tokens;
+            }
+            acc
+        });
+
+    let mut report = CostAnomalyReport {
+        window_days: ROLLING_WINDOW_DAYS,
+        z_threshold: Z_SCORE_THRESHOLD,
+        daily: Vec::new(),
+        anomalies: Vec::new(),
+    };
+    let (Some(first), Some(last)) = (days.keys().next(), days.keys().next_back()) else {
+        return Ok(report);
+    };
+    let end = end.unwrap_or(*last);
+    let start = start.unwrap_or(*first);
+    if end < *first {
+        return Ok(report);
+    }
+
+    let series = dense_daily_series(&days, *first, end);
+    let in_range = |date: &str| {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok_and(|d| d >= start && d <= end)
+    };
+    report.anomalies = detect_anomalies(&series)
+        .into_iter()
+        .filter(|a| in_range(&a.date))
+        .collect();
+    report.daily = series.into_iter().filter(|d| in_range(&d.date)).collect();
+
+    if notify == Some(true) {
+        notify_new_anomalies(&report.anomalies);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(costs: &[f64]) -> Vec<DailyCost> {
+        let first = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        costs
+            .iter()
+            .zip(first.iter_days())
+            .map(|(&cost_usd, date)| DailyCost {
+                date: date.format("%Y-%m-%d").to_string(),
+                cost_usd,
+                total_tokens: (cost_usd * 1_000.0) as u64,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_anomalies_flags_runaway_day() {
+        let mut costs = vec![1.0, 1.2, 0.9, 1.1, 1.0, 0.8, 1.3, 1.0, 1.1, 0.9];
+        costs.push(20.0);
+        costs.push(1.1);
+
+        let anomalies = detect_anomalies(&series(&costs));
+
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].date, "2025-01-11");
+        assert!(anomalies[0].cost_ratio.unwrap() > 15.0);
+        assert!(anomalies[0].cost_z_score.unwrap() >= Z_SCORE_THRESHOLD);
+    }
+
+    #[test]
+    fn test_detect_anomalies_needs_history_and_spend() {
+        // Spike before enough history, then the first spend after idle days
+        let anomalies = detect_anomalies(&series(&[1.0, 1.0, 50.0]));
+        assert!(anomalies.is_empty());
+        let anomalies = detect_anomalies(&series(
+            &[0.0; 10].iter().chain(&[5.0]).copied().collect::<Vec<_>>(),
+        ));
+        assert!(anomalies.is_empty());
+
+        // A flat baseline tolerates small changes
+        let anomalies = detect_anomalies(&series(&[2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 2.5]));
+        assert!(anomalies.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_cost_anomalies_range() {
+        use crate::test_utils::{create_jsonl_content, MessageBuilder};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let project_dir = temp.path().join("projects").join("demo");
+        std::fs::create_dir_all(&project_dir).unwrap();
+        let messages: Vec<MessageBuilder> = (1..=12)
+            .map(|day| {
+                let output = if day == 11 { 500_000 } else { 10_000 };
+                MessageBuilder::assistant()
+                    .with_message_id(&format!("msg_{day}"))
+                    .with_timestamp(&format!("2025-01-{day:02}T12:00:00Z"))
+                    .with_usage(1_000, output)
+            })
+            .collect();
+        std::fs::write(
+            project_dir.join("session.jsonl"),
+            create_jsonl_content(&messages),
+        )
+        .unwrap();
+        let claude_path = temp.path().to_string_lossy().to_string();
+
+        let report = get_cost_anomalies(claude_path.clone(), None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(report.daily.len(), 12);
+        assert_eq!(report.anomalies.len(), 1);
+        assert_eq!(report.anomalies[0].date, "2025-01-11");
+
+        let report = get_cost_anomalies(
+            claude_path.clone(),
+            Some("2025-01-12".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.daily.len(), 1);
+        assert!(report.anomalies.is_empty());
+
+        assert!(
+            get_cost_anomalies(claude_path, Some("yesterday".to_string()), None, None)
+                .await
+                .is_err()
+        );
+    }
+}
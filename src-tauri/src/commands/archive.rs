@@ -0,0 +1,682 @@
+//! Automatic archiving of old sessions
+//!
+//! With the `archive` setting enabled, a background task exports session
+//! files not modified for `olderThanDays` into a new zip or tar.zst archive,
+//! once after startup and then every `intervalHours`. Each run is appended
+//! to `archive-manifest.json` in the archive folder; files recorded there
+//! with the same size and modification time are not archived again. The
+//! `<session-id>/` folder next to a session file (subagent transcripts, tool
+//! results) is archived with it. Session files are only deleted when
+//! `removeOriginals` is set, after the archive has been written and synced.
+
+use crate::commands::journal::record_operation;
+use crate::commands::metadata::get_metadata_folder;
+use crate::commands::usage_metrics::{write_json_atomic, OperationTimer};
+use crate::errors::AppError;
+use crate::models::{ArchiveRun, ArchiveSettings, ArchivedSession};
+use crate::utils::{collect_session_files, display_path, long_path};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Record of every run, kept in the archive folder
+const MANIFEST_FILE: &str = "archive-manifest.json";
+
+/// How often the scheduler checks whether a run is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+const SECONDS_PER_HOUR: u64 = 60 * 60;
+
+/// zstd compression level of tar.zst archives
+const ZSTD_LEVEL: i32 = 9;
+
+static ARCHIVE_SETTINGS: RwLock<Option<ArchiveSettings>> = RwLock::new(None);
+
+/// Start of the last scheduled run
+static LAST_RUN: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Held while archiving, so scheduled and manual runs never overlap
+static RUN_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArchiveFormat {
+    Zip,
+    TarZst,
+}
+
+impl ArchiveFormat {
+    fn parse(format: &str) -> Result<Self, String> {
+        match format {
+            "zip" => Ok(Self::Zip),
+            "tar.zst" => Ok(Self::TarZst),
+            other => Err(format!("Unknown archive format: {other}")),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Zip => "zip",
+            Self::TarZst => "tar.zst",
+        }
+    }
+}
+
+/// A session file due for archiving
+struct DueSession {
+    path: PathBuf,
+    entry_name: String,
+    size: u64,
+    last_modified: String,
+    session_dir: Option<PathBuf>, // `<project>/<session-id>/`, archived along
+    files: Vec<(PathBuf, String)>, // Files of `session_dir` with their entry names
+    files_bytes: u64,
+}
+
+/// Archive being written
+enum ArchiveWriter {
+    Zip(ZipWriter<BufWriter<File>>),
+    TarZst(tar::Builder<zstd::Encoder<'static, BufWriter<File>>>),
+}
+
+impl ArchiveWriter {
+    fn create(format: ArchiveFormat, writer: BufWriter<File>) -> io::Result<Self> {
+        Ok(match format {
+            ArchiveFormat::Zip => Self::Zip(ZipWriter::new(writer)),
+            ArchiveFormat::TarZst => {
+                Self::TarZst(tar::Builder::new(zstd::Encoder::new(writer, ZSTD_LEVEL)?))
+            }
+        })
+    }
+
+    fn append(&mut self, entry_name: &str, source: &mut File) -> Result<(), String> {
+        let write_error = |e: &dyn std::fmt::Display| format!("Failed to write archive: {e}");
+        match self {
+            Self::Zip(zip) => {
+                let options = SimpleFileOptions::default()
+                    .compression_method(CompressionMethod::Deflated)
+                    .large_file(true);
+                zip.start_file(entry_name, options)
+                    .map_err(|e| write_error(&e))?;
+                io::copy(source, zip).map_err(|e| write_error(&e))?;
+            }
+            Self::TarZst(tar) => tar
+                .append_file(entry_name, source)
+                .map_err(|e| write_error(&e))?,
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<BufWriter<File>, String> {
+        let write_error = |e: &dyn std::fmt::Display| format!("Failed to write archive: {e}");
+        match self {
+            Self::Zip(zip) => zip.finish().map_err(|e| write_error(&e)),
+            Self::TarZst(tar) => {
+                let encoder = tar.into_inner().map_err(|e| write_error(&e))?;
+                encoder.finish().map_err(|e| write_error(&e))
+            }
+        }
+    }
+}
+
+/// Check the `archive` setting
+pub fn validate_archive_settings(settings: Option<&ArchiveSettings>) -> Result<(), String> {
+    let Some(settings) = settings else {
+        return Ok(());
+    };
+    ArchiveFormat::parse(&settings.format)?;
+    if settings.older_than_days == 0 {
+        return Err("Sessions must be at least one day old to be archived".to_string());
+    }
+    if settings.interval_hours == Some(0) {
+        return Err("The archive interval must be at least one hour".to_string());
+    }
+    Ok(())
+}
+
+/// Apply the `archive` setting
+pub fn set_archive_settings(settings: Option<ArchiveSettings>) {
+    if let Ok(mut current) = ARCHIVE_SETTINGS.write() {
+        *current = settings;
+    }
+}
+
+fn archive_settings() -> Option<ArchiveSettings> {
+    ARCHIVE_SETTINGS
+        .read()
+        .ok()
+        .and_then(|settings| settings.clone())
+}
+
+fn archive_directory(settings: &ArchiveSettings) -> Result<PathBuf, String> {
+    match &settings.directory {
+        Some(directory) => Ok(PathBuf::from(directory)),
+        None => Ok(get_metadata_folder()?.join("archives")),
+    }
+}
+
+fn projects_directory(settings: &ArchiveSettings) -> Result<PathBuf, String> {
+    let claude_path = match &settings.claude_path {
+        Some(path) => PathBuf::from(path),
+        None => dirs::home_dir()
+            .ok_or("Could not find home directory")?
+            .join(".claude"),
+    };
+    Ok(claude_path.join("projects"))
+}
+
+/// Runs recorded in the manifest of an archive folder
+fn read_manifest(directory: &Path) -> Result<Vec<ArchiveRun>, String> {
+    let path = directory.join(MANIFEST_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read archive manifest: {e}"))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse archive manifest: {e}"))
+}
+
+/// `path` relative to `base` with `/` separators
+fn entry_name(base: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(base).unwrap_or(path);
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The `<project>/<session-id>/` folder next to a session file, if any
+fn session_dir_of(path: &Path) -> Option<PathBuf> {
+    let dir = path.with_extension("");
+    dir.is_dir().then_some(dir)
+}
+
+/// Whether `path` lies in the folder of a session file of `project` (and is
+/// archived with that session)
+fn in_session_dir(projects_path: &Path, project: &str, path: &Path) -> bool {
+    let project_dir = projects_path.join(project);
+    let Ok(relative) = path.strip_prefix(&project_dir) else {
+        return false;
+    };
+    let mut components = relative.components();
+    match (components.next(), components.next()) {
+        (Some(session_dir), Some(_)) => {
+            let session_dir = session_dir.as_os_str().to_string_lossy();
+            project_dir.join(format!("{session_dir}.jsonl")).is_file()
+        }
+        _ => false,
+    }
+}
+
+/// Files under `session_dir` with their archive entry names, sorted
+fn session_dir_files(projects_path: &Path, session_dir: &Path) -> (Vec<(PathBuf, String)>, u64) {
+    let mut bytes = 0;
+    let files = WalkDir::new(session_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| {
+            bytes += entry.metadata().map_or(0, |m| m.len());
+            let name = entry_name(projects_path, entry.path());
+            (entry.into_path(), name)
+        })
+        .collect();
+    (files, bytes)
+}
+
+/// Session files last modified before `cutoff` and not archived yet
+fn due_sessions(
+    projects_path: &Path,
+    cutoff: DateTime<Utc>,
+    archived: &HashSet<(String, u64, String)>,
+) -> Result<Vec<DueSession>, String> {
+    let mut due = Vec::new();
+    for (project, path) in collect_session_files(projects_path)? {
+        if in_session_dir(projects_path, &project, &path) {
+            continue;
+        }
+        let Ok(metadata) = fs::metadata(long_path(&path)) else {
+            continue;
+        };
+        let Ok(modified) = metadata.modified() else {
+            continue;
+        };
+        let modified = DateTime::<Utc>::from(modified);
+        if modified >= cutoff {
+            continue;
+        }
+        let file_path = display_path(&path);
+        let last_modified = modified.to_rfc3339();
+        if archived.contains(&(file_path, metadata.len(), last_modified.clone())) {
+            continue;
+        }
+        let session_dir = session_dir_of(&path);
+        let (files, files_bytes) = session_dir
+            .as_deref()
+            .map(|dir| session_dir_files(projects_path, dir))
+            .unwrap_or_default();
+        due.push(DueSession {
+            entry_name: entry_name(projects_path, &path),
+            path,
+            size: metadata.len(),
+            last_modified,
+            session_dir,
+            files,
+            files_bytes,
+        });
+    }
+    due.sort_by(|a, b| a.entry_name.cmp(&b.entry_name));
+    Ok(due)
+}
+
+/// Write the readable sessions into an archive at `path`, returning the
+/// sessions written with whether their folder was archived completely, and
+/// the "path: error" of the others
+fn write_archive(
+    format: ArchiveFormat,
+    path: &Path,
+    sessions: Vec<DueSession>,
+) -> Result<(Vec<(DueSession, bool)>, Vec<String>), String> {
+    let write_error = |e: &dyn std::fmt::Display| format!("Failed to write archive: {e}");
+    let writer = BufWriter::new(File::create(long_path(path)).map_err(|e| write_error(&e))?);
+    let mut archive = ArchiveWriter::create(format, writer).map_err(|e| write_error(&e))?;
+    let mut written = Vec::new();
+    let mut failed = Vec::new();
+
+    for session in sessions {
+        let mut source = match File::open(long_path(&session.path)) {
+            Ok(source) => source,
+            Err(e) => {
+                failed.push(format!("{}: {e}", display_path(&session.path)));
+                continue;
+            }
+        };
+        archive.append(&session.entry_name, &mut source)?;
+        let mut complete = true;
+        for (file, name) in &session.files {
+            match File::open(long_path(file)) {
+                Ok(mut source) => archive.append(name, &mut source)?,
+                Err(e) => {
+                    failed.push(format!("{}: {e}", display_path(file)));
+                    complete = false;
+                }
+            }
+        }
+        written.push((session, complete));
+    }
+
+    let file = archive.finish()?;
+    let file = file.into_inner().map_err(|e| write_error(&e))?;
+    file.sync_all().map_err(|e| write_error(&e))?;
+    Ok((written, failed))
+}
+
+/// Delete an archived session file and its folder
+fn remove_session(session: &DueSession) -> io::Result<()> {
+    if let Some(dir) = &session.session_dir {
+        fs::remove_dir_all(long_path(dir))?;
+    }
+    fs::remove_file(long_path(&session.path))
+}
+
+/// Archive the sessions due under `settings` and record the run
+///
+/// Nothing is written (and no run recorded) when no session is due.
+pub fn archive_sessions(
+    settings: &ArchiveSettings,
+    trigger: &str,
+    now: DateTime<Utc>,
+) -> Result<ArchiveRun, String> {
+    let format = ArchiveFormat::parse(&settings.format)?;
+    let directory = archive_directory(settings)?;
+    let projects_path = projects_directory(settings)?;
+    let cutoff = now - ChronoDuration::days(i64::from(settings.older_than_days));
+    let _running = RUN_LOCK
+        .lock()
+        .map_err(|e| format!("Archive lock poisoned: {e}"))?;
+
+    let mut runs = read_manifest(&directory)?;
+    let archived: HashSet<(String, u64, String)> = runs
+        .iter()
+        .flat_map(|run| &run.sessions)
+        .map(|s| (s.file_path.clone(), s.size, s.last_modified.clone()))
+        .collect();
+    let due = if projects_path.is_dir() {
+        due_sessions(&projects_path, cutoff, &archived)?
+    } else {
+        Vec::new()
+    };
+
+    let mut run = ArchiveRun {
+        archive_path: None,
+        format: format.extension().to_string(),
+        trigger: trigger.to_string(),
+        started_at: now.to_rfc3339(),
+        cutoff: cutoff.to_rfc3339(),
+        session_count: 0,
+        original_bytes: 0,
+        archive_bytes: 0,
+        sessions: Vec::new(),
+        failed: Vec::new(),
+    };
+    if due.is_empty() {
+        return Ok(run);
+    }
+
+    fs::create_dir_all(&directory).map_err(|e| format!("Failed to create archive folder: {e}"))?;
+    let name = format!(
+        "sessions-{}.{}",
+        now.format("%Y%m%d-%H%M%S"),
+        format.extension()
+    );
+    let archive_path = directory.join(&name);
+    let partial_path = directory.join(format!("{name}.partial"));
+    let (written, failed) = match write_archive(format, &partial_path, due) {
+        Ok(result) => result,
+        Err(e) => {
+            let _ = fs::remove_file(&partial_path);
+            return Err(e);
+        }
+    };
+    fs::rename(&partial_path, &archive_path)
+        .map_err(|e| format!("Failed to finish archive: {e}"))?;
+
+    run.failed = failed;
+    for (session, complete) in written {
+        let removed = settings.remove_originals
+            && complete
+            && match remove_session(&session) {
+                Ok(()) => true,
+                Err(e) => {
+                    run.failed.push(format!(
+                        "{}: archived but not removed: {e}",
+                        display_path(&session.path)
+                    ));
+                    false
+                }
+            };
+        run.original_bytes += session.size + session.files_bytes;
+        run.sessions.push(ArchivedSession {
+            file_path: display_path(&session.path),
+            entry_name: session.entry_name,
+            size: session.size,
+            last_modified: session.last_modified,
+            removed,
+        });
+    }
+    run.session_count = run.sessions.len();
+    run.archive_bytes = fs::metadata(&archive_path).map_or(0, |m| m.len());
+    run.archive_path = Some(display_path(&archive_path));
+
+    runs.push(run.clone());
+    write_json_atomic(&directory.join(MANIFEST_FILE), &runs)?;
+    let removed = run
+        .sessions
+        .iter()
+        .filter(|session| session.removed)
+        .count();
+    record_operation(
+        "run_archive",
+        format!(
+            "Archived {} sessions ({removed} originals removed, {trigger})",
+            run.session_count
+        ),
+        None,
+    );
+    Ok(run)
+}
+
+/// Trigger of the scheduled run due at `now`, if any
+fn scheduled_trigger(
+    settings: &ArchiveSettings,
+    last_run: Option<Instant>,
+    now: Instant,
+) -> Option<&'static str> {
+    if !settings.enabled {
+        return None;
+    }
+    let Some(last_run) = last_run else {
+        return Some("startup");
+    };
+    let interval = Duration::from_secs(u64::from(settings.interval_hours?) * SECONDS_PER_HOUR);
+    (now.duration_since(last_run) >= interval).then_some("interval")
+}
+
+fn run_scheduled_archive() {
+    let Some(settings) = archive_settings() else {
+        return;
+    };
+    let trigger = {
+        let Ok(mut last_run) = LAST_RUN.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        let Some(trigger) = scheduled_trigger(&settings, *last_run, now) else {
+            return;
+        };
+        *last_run = Some(now);
+        trigger
+    };
+
+    if let Err(e) = archive_sessions(&settings, trigger, Utc::now()) {
+        eprintln!("Failed to archive sessions: {e}");
+    }
+}
+
+/// Start checking in the background whether an archive run is due
+///
+/// The first run happens once the settings are loaded with archiving enabled.
+pub fn start_archive_scheduler() {
+    tauri::async_runtime::spawn(async {
+        loop {
+            let _ = tauri::async_runtime::spawn_blocking(run_scheduled_archive).await;
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Archive the due sessions now, with the configured `archive` setting
+#[tauri::command]
+pub async fn run_archive() -> Result<ArchiveRun, AppError> {
+    let _timer = OperationTimer::start("run_archive");
+
+    let settings = archive_settings().ok_or("Archiving is not configured")?;
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        archive_sessions(&settings, "manual", Utc::now())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??)
+}
+
+/// Runs recorded in the manifest of the configured archive folder, oldest first
+#[tauri::command]
+pub async fn get_archive_history() -> Result<Vec<ArchiveRun>, AppError> {
+    let _timer = OperationTimer::start("get_archive_history");
+
+    let settings = archive_settings().ok_or("Archiving is not configured")?;
+    Ok(
+        tauri::async_runtime::spawn_blocking(move || read_manifest(&archive_directory(&settings)?))
+            .await
+            .map_err(|e| format!("Task join error: {e}"))??,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    fn settings(dir: &Path, format: &str) -> ArchiveSettings {
+        ArchiveSettings {
+            enabled: true,
+            older_than_days: 30,
+            format: format.to_string(),
+            directory: Some(display_path(&dir.join("archives"))),
+            claude_path: Some(display_path(&dir.join(".claude"))),
+            interval_hours: Some(24),
+            remove_originals: false,
+        }
+    }
+
+    /// Create a session file last modified `days_ago` days ago
+    fn session_file(dir: &Path, name: &str, days_ago: u64) -> PathBuf {
+        let path = dir.join(".claude").join("projects").join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            format!("{{\"type\":\"user\",\"file\":\"{name}\"}}\n"),
+        )
+        .unwrap();
+        let modified = SystemTime::now() - Duration::from_secs(days_ago * 24 * SECONDS_PER_HOUR);
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        path
+    }
+
+    #[test]
+    fn test_archive_sessions_zip() {
+        let dir = TempDir::new().unwrap();
+        let old = session_file(dir.path(), "-repo-a/old.jsonl", 60);
+        session_file(dir.path(), "-repo-a/new.jsonl", 1);
+        let settings = settings(dir.path(), "zip");
+
+        let run = archive_sessions(&settings, "manual", Utc::now()).unwrap();
+
+        assert_eq!(run.session_count, 1);
+        assert_eq!(run.sessions[0].entry_name, "-repo-a/old.jsonl");
+        assert!(!run.sessions[0].removed);
+        assert!(old.exists());
+        let archive_path = PathBuf::from(run.archive_path.clone().unwrap());
+        let mut archive = zip::ZipArchive::new(File::open(&archive_path).unwrap()).unwrap();
+        let mut content = String::new();
+        archive
+            .by_name("-repo-a/old.jsonl")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert!(content.contains("old.jsonl"));
+
+        // Recorded sessions are not archived again
+        let again = archive_sessions(&settings, "interval", Utc::now()).unwrap();
+        assert_eq!(again.session_count, 0);
+        assert!(again.archive_path.is_none());
+        assert_eq!(
+            read_manifest(&archive_directory(&settings).unwrap()).unwrap(),
+            vec![run]
+        );
+    }
+
+    #[test]
+    fn test_archive_sessions_tar_zst_removes_originals() {
+        let dir = TempDir::new().unwrap();
+        let old = session_file(dir.path(), "-repo-b/abc/subagents/agent-1.jsonl", 90);
+        let settings = ArchiveSettings {
+            remove_originals: true,
+            ..settings(dir.path(), "tar.zst")
+        };
+
+        let run = archive_sessions(&settings, "startup", Utc::now()).unwrap();
+
+        assert_eq!(run.session_count, 1);
+        assert!(run.sessions[0].removed);
+        assert!(!old.exists());
+        let archive_path = PathBuf::from(run.archive_path.unwrap());
+        assert!(archive_path.to_string_lossy().ends_with(".tar.zst"));
+        let decoder = zstd::Decoder::new(File::open(&archive_path).unwrap()).unwrap();
+        let mut archive = tar::Archive::new(decoder);
+        let names: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, ["-repo-b/abc/subagents/agent-1.jsonl"]);
+    }
+
+    #[test]
+    fn test_archive_sessions_includes_session_folder() {
+        let dir = TempDir::new().unwrap();
+        let old = session_file(dir.path(), "-repo-c/abc.jsonl", 60);
+        session_file(dir.path(), "-repo-c/abc/subagents/agent-1.jsonl", 60);
+        let tool_result = dir
+            .path()
+            .join(".claude/projects/-repo-c/abc/tool-results/toolu_1.txt");
+        fs::create_dir_all(tool_result.parent().unwrap()).unwrap();
+        fs::write(&tool_result, "output").unwrap();
+        let settings = ArchiveSettings {
+            remove_originals: true,
+            ..settings(dir.path(), "zip")
+        };
+
+        let run = archive_sessions(&settings, "manual", Utc::now()).unwrap();
+
+        assert_eq!(run.session_count, 1);
+        assert_eq!(run.sessions[0].entry_name, "-repo-c/abc.jsonl");
+        assert!(run.sessions[0].removed);
+        assert!(!old.exists());
+        assert!(!old.with_extension("").exists());
+        let archive_path = PathBuf::from(run.archive_path.unwrap());
+        let archive = zip::ZipArchive::new(File::open(&archive_path).unwrap()).unwrap();
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort_unstable();
+        assert_eq!(
+            names,
+            [
+                "-repo-c/abc.jsonl",
+                "-repo-c/abc/subagents/agent-1.jsonl",
+                "-repo-c/abc/tool-results/toolu_1.txt",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scheduled_trigger() {
+        let dir = TempDir::new().unwrap();
+        let mut settings = settings(dir.path(), "zip");
+        let start = Instant::now();
+        let day = Duration::from_secs(24 * SECONDS_PER_HOUR);
+
+        assert_eq!(scheduled_trigger(&settings, None, start), Some("startup"));
+        assert_eq!(scheduled_trigger(&settings, Some(start), start), None);
+        assert_eq!(
+            scheduled_trigger(&settings, Some(start), start + day),
+            Some("interval")
+        );
+
+        settings.interval_hours = None;
+        assert_eq!(scheduled_trigger(&settings, Some(start), start + day), None);
+        settings.enabled = false;
+        assert_eq!(scheduled_trigger(&settings, None, start), None);
+    }
+
+    #[test]
+    fn test_validate_archive_settings() {
+        let dir = TempDir::new().unwrap();
+        let valid = settings(dir.path(), "tar.zst");
+        assert!(validate_archive_settings(None).is_ok());
+        assert!(validate_archive_settings(Some(&valid)).is_ok());
+        assert!(validate_archive_settings(Some(&ArchiveSettings {
+            format: "rar".to_string(),
+            ..valid.clone()
+        }))
+        .is_err());
+        assert!(validate_archive_settings(Some(&ArchiveSettings {
+            older_than_days: 0,
+            ..valid
+        }))
+        .is_err());
+    }
+}
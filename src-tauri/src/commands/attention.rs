@@ -0,0 +1,522 @@
+//! Detection of watched sessions waiting for user input
+//!
+//! Claude Code writes a tool call to the session file before asking for
+//! permission, and the matching tool result only after the user answered.
+//! A tool call that stays unanswered while the file is quiet therefore means
+//! the session is blocked on a permission prompt, a plan approval
+//! (`ExitPlanMode`) or a question (`AskUserQuestion`).
+//!
+//! Watched session files are polled in the background; each blocked tool
+//! call is reported once through `SESSION_AWAITING_INPUT_EVENT`. When a run
+//! ends (the final response has `end_turn` and no call is pending), a summary
+//! of the run is reported once through `SESSION_RUN_FINISHED_EVENT`.
+
+use crate::commands::prompt_quality::prompt_text;
+use crate::commands::retry_loops::{extract_tool_calls, total_tokens};
+use crate::commands::session::is_genuine_user_text;
+use crate::commands::stats::{read_raw_log_entries, ResponseUsageTracker};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::freshness::FileStamp;
+use crate::models::{ClaudeMessage, RawLogEntry, SessionAwaitingInput, SessionRunSummary};
+use crate::utils::resolve_session_file;
+use chrono::DateTime;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Event emitted when a watched session starts waiting for input
+pub const SESSION_AWAITING_INPUT_EVENT: &str = "session-awaiting-input";
+
+/// Event emitted with the summary of a run a watched session just finished
+pub const SESSION_RUN_FINISHED_EVENT: &str = "session-run-finished";
+
+/// Interval between two checks of the watched session files
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Time a session file must stay unchanged before an unanswered tool call
+/// counts as waiting (auto-approved tools answer well within it)
+const QUIET_PERIOD: Duration = Duration::from_secs(5);
+
+/// Tools whose `file_path` (or `notebook_path`) input is modified
+const EDIT_TOOLS: &[&str] = &["Edit", "MultiEdit", "NotebookEdit", "Write"];
+
+/// Watch state of one session file
+struct WatchedSession {
+    session_id: String,
+    checked: Option<FileStamp>, // Stamp the file was last checked at
+    notified: Option<String>,   // Tool call last reported as waiting
+    summarized: Option<String>, // Final response of the run last reported as finished
+}
+
+/// Sessions that changed state during one poll
+#[derive(Default)]
+struct PollUpdates {
+    waiting: Vec<SessionAwaitingInput>,
+    finished: Vec<SessionRunSummary>,
+}
+
+type AttentionListener = Box<dyn Fn(&SessionAwaitingInput) + Send + Sync>;
+type RunSummaryListener = Box<dyn Fn(&SessionRunSummary) + Send + Sync>;
+
+/// Forwards waiting notifications to the frontend (unset in tests)
+static ATTENTION_LISTENER: OnceLock<AttentionListener> = OnceLock::new();
+
+/// Forwards run summaries to the frontend (unset in tests)
+static RUN_SUMMARY_LISTENER: OnceLock<RunSummaryListener> = OnceLock::new();
+
+/// Session files currently watched
+static WATCHED_SESSIONS: OnceLock<Mutex<HashMap<PathBuf, WatchedSession>>> = OnceLock::new();
+
+fn watched_sessions() -> &'static Mutex<HashMap<PathBuf, WatchedSession>> {
+    WATCHED_SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Register the callback that emits waiting notifications to the frontend
+pub fn set_attention_listener(listener: impl Fn(&SessionAwaitingInput) + Send + Sync + 'static) {
+    let _ = ATTENTION_LISTENER.set(Box::new(listener));
+}
+
+/// Register the callback that emits run summaries to the frontend
+pub fn set_run_summary_listener(listener: impl Fn(&SessionRunSummary) + Send + Sync + 'static) {
+    let _ = RUN_SUMMARY_LISTENER.set(Box::new(listener));
+}
+
+/// Start polling the watched session files in the background
+pub fn start_attention_watcher() {
+    tauri::async_runtime::spawn(async {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let Ok(updates) = tauri::async_runtime::spawn_blocking(poll_watched_sessions).await
+            else {
+                continue;
+            };
+            if let Some(listener) = ATTENTION_LISTENER.get() {
+                updates.waiting.iter().for_each(listener);
+            }
+            if let Some(listener) = RUN_SUMMARY_LISTENER.get() {
+                updates.finished.iter().for_each(listener);
+            }
+        }
+    });
+}
+
+fn waiting_reason(tool_name: &str) -> &'static str {
+    match tool_name {
+        "ExitPlanMode" => "plan_approval",
+        "AskUserQuestion" => "question",
+        _ => "permission",
+    }
+}
+
+/// The oldest main-chain tool call still waiting for its result
+///
+/// A later prompt typed by the user means the call was interrupted.
+fn unanswered_tool_call(entries: &[RawLogEntry]) -> Option<(String, String, String)> {
+    // (tool_use_id, tool_name, timestamp), in call order
+    let mut pending: Vec<(String, String, String)> = Vec::new();
+
+    for entry in entries {
+        if entry.is_sidechain == Some(true) {
+            continue;
+        }
+        let Some(message) = &entry.message else {
+            continue;
+        };
+        let items = match &message.content {
+            serde_json::Value::Array(items) => items.as_slice(),
+            serde_json::Value::String(_) if entry.message_type == "user" => {
+                pending.clear();
+                continue;
+            }
+            _ => continue,
+        };
+
+        for item in items {
+            match (
+                entry.message_type.as_str(),
+                item.get("type").and_then(|v| v.as_str()),
+            ) {
+                ("assistant", Some("tool_use")) => {
+                    let Some(id) = item.get("id").and_then(|v| v.as_str()) else {
+                        continue;
+                    };
+                    let name = item
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("unknown");
+                    pending.push((
+                        id.to_string(),
+                        name.to_string(),
+                        entry.timestamp.clone().unwrap_or_default(),
+                    ));
+                }
+                ("user", Some("tool_result")) => {
+                    let id = item.get("tool_use_id").and_then(|v| v.as_str());
+                    pending.retain(|(pending_id, _, _)| Some(pending_id.as_str()) != id);
+                }
+                ("user", Some("text")) => pending.clear(),
+                _ => {}
+            }
+        }
+    }
+
+    pending.into_iter().next()
+}
+
+/// What a session is waiting for, if its last tool call is unanswered
+fn awaiting_input(
+    session_id: &str,
+    session_path: &Path,
+    entries: &[RawLogEntry],
+) -> Option<SessionAwaitingInput> {
+    let (tool_use_id, tool_name, since) = unanswered_tool_call(entries)?;
+    Some(SessionAwaitingInput {
+        session_id: session_id.to_string(),
+        file_path: session_path.to_string_lossy().to_string(),
+        reason: waiting_reason(&tool_name).to_string(),
+        tool_name,
+        tool_use_id,
+        since,
+    })
+}
+
+/// Index of the prompt that started the latest run (0 without prompts)
+fn run_start(entries: &[RawLogEntry]) -> usize {
+    entries
+        .iter()
+        .rposition(|entry| {
+            entry.message_type == "user"
+                && entry.is_sidechain != Some(true)
+                && entry.is_meta != Some(true)
+                && entry
+                    .message
+                    .as_ref()
+                    .and_then(|message| prompt_text(&message.content))
+                    .is_some_and(|text| is_genuine_user_text(&text))
+        })
+        .unwrap_or(0)
+}
+
+/// Summary of the latest run, if the session finished it
+fn finished_run(
+    session_id: &str,
+    session_path: &Path,
+    entries: &[RawLogEntry],
+) -> Option<SessionRunSummary> {
+    let last = entries
+        .iter()
+        .rev()
+        .find(|entry| entry.is_sidechain != Some(true) && entry.message.is_some())?;
+    let finished = last.message_type == "assistant"
+        && last.message.as_ref()?.stop_reason.as_deref() == Some("end_turn")
+        && unanswered_tool_call(entries).is_none();
+    if !finished {
+        return None;
+    }
+
+    let run = &entries[run_start(entries)..];
+    let started_at = run.first()?.timestamp.clone().unwrap_or_default();
+    let ended_at = last.timestamp.clone().unwrap_or_default();
+    let duration_seconds = DateTime::parse_from_rfc3339(&started_at)
+        .ok()
+        .zip(DateTime::parse_from_rfc3339(&ended_at).ok())
+        .map_or(0, |(start, end)| (end - start).num_seconds().max(0));
+
+    let mut tracker = ResponseUsageTracker::default();
+    let (mut response_count, mut tokens, mut cost_usd) = (0, 0, 0.0);
+    for entry in run.iter().filter(|entry| entry.message_type == "assistant") {
+        let cwd = entry.cwd.clone();
+        let Ok(message) = ClaudeMessage::try_from(entry.clone()) else {
+            continue;
+        };
+        let (usage, cost) = tracker.usage_and_cost_of(&message, cwd.as_deref());
+        if cost > 0.0 || total_tokens(&usage) > 0 {
+            response_count += 1;
+            tokens += total_tokens(&usage);
+            cost_usd += cost;
+        }
+    }
+
+    let calls = extract_tool_calls(run);
+    let files_changed: BTreeSet<&str> = calls
+        .iter()
+        .filter(|call| !call.failed && EDIT_TOOLS.contains(&call.name.as_str()))
+        .filter_map(|call| {
+            call.input
+                .get("file_path")
+                .or_else(|| call.input.get("notebook_path"))
+                .and_then(|v| v.as_str())
+        })
+        .collect();
+
+    Some(SessionRunSummary {
+        session_id: session_id.to_string(),
+        file_path: session_path.to_string_lossy().to_string(),
+        last_message_uuid: last.uuid.clone().unwrap_or_default(),
+        started_at,
+        ended_at,
+        duration_seconds,
+        response_count,
+        total_tokens: tokens,
+        cost_usd,
+        tool_call_count: calls.len(),
+        error_count: calls.iter().filter(|call| call.failed).count(),
+        files_changed: files_changed.into_iter().map(ToString::to_string).collect(),
+    })
+}
+
+fn is_quiet(stamp: FileStamp) -> bool {
+    stamp
+        .modified
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age >= QUIET_PERIOD)
+}
+
+/// Check the watched files that changed and settled since the last poll,
+/// returning the sessions that started waiting for input or finished a run
+fn poll_watched_sessions() -> PollUpdates {
+    let Ok(mut watched) = watched_sessions().lock() else {
+        return PollUpdates::default();
+    };
+
+    let mut updates = PollUpdates::default();
+    for (path, session) in watched.iter_mut() {
+        let Some(stamp) = FileStamp::of(path) else {
+            continue;
+        };
+        if session.checked == Some(stamp) || !is_quiet(stamp) {
+            continue;
+        }
+        session.checked = Some(stamp);
+
+        let entries = read_raw_log_entries(path);
+        let Some(event) = awaiting_input(&session.session_id, path, &entries) else {
+            session.notified = None;
+            if let Some(summary) = finished_run(&session.session_id, path, &entries) {
+                if session.summarized.as_ref() != Some(&summary.last_message_uuid) {
+                    session.summarized = Some(summary.last_message_uuid.clone());
+                    updates.finished.push(summary);
+                }
+            }
+            continue;
+        };
+        if session.notified.as_ref() != Some(&event.tool_use_id) {
+            session.notified = Some(event.tool_use_id.clone());
+            updates.waiting.push(event);
+        }
+    }
+    updates
+}
+
+/// Start watching a session for input requests
+///
+/// Returns what the session is waiting for right now, if anything; that call
+/// is not reported again by the watcher.
+#[tauri::command]
+pub async fn watch_session(
+    session_id: String,
+    project_path: String,
+) -> Result<Option<SessionAwaitingInput>, AppError> {
+    let _timer = OperationTimer::start("watch_session");
+
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+    let entries = read_raw_log_entries(&session_path);
+    // A call the session is still writing around may yet be auto-approved
+    let current = FileStamp::of(&session_path)
+        .filter(|stamp| is_quiet(*stamp))
+        .and_then(|_| awaiting_input(&session_id, &session_path, &entries));
+    // Runs finished before watching started are not reported
+    let summarized =
+        finished_run(&session_id, &session_path, &entries).map(|summary| summary.last_message_uuid);
+
+    let mut watched = watched_sessions()
+        .lock()
+        .map_err(|e| format!("Failed to lock watched sessions: {e}"))?;
+    watched.insert(
+        session_path,
+        WatchedSession {
+            session_id,
+            checked: None,
+            notified: current.as_ref().map(|event| event.tool_use_id.clone()),
+            summarized,
+        },
+    );
+    Ok(current)
+}
+
+/// Stop watching a session
+#[tauri::command]
+pub async fn unwatch_session(session_id: String, project_path: String) -> Result<(), AppError> {
+    let _timer = OperationTimer::start("unwatch_session");
+
+    let session_path = Path::new(&project_path).join(format!("{session_id}.jsonl"));
+    watched_sessions()
+        .lock()
+        .map_err(|e| format!("Failed to lock watched sessions: {e}"))?
+        .remove(&session_path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::raw_entry;
+    use serde_json::json;
+    use std::fs;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    fn tool_use(id: &str, name: &str) -> serde_json::Value {
+        json!({
+            "type": "assistant", "uuid": format!("a-{id}"), "timestamp": "2025-01-01T00:00:00Z",
+            "message": {"role": "assistant", "content": [
+                {"type": "tool_use", "id": id, "name": name, "input": {}}
+            ]}
+        })
+    }
+
+    fn tool_result(id: &str) -> serde_json::Value {
+        json!({
+            "type": "user", "uuid": format!("r-{id}"), "timestamp": "2025-01-01T00:00:01Z",
+            "message": {"role": "user", "content": [
+                {"type": "tool_result", "tool_use_id": id, "content": "ok"}
+            ]}
+        })
+    }
+
+    #[test]
+    fn test_unanswered_tool_call_reasons() {
+        let answered = [
+            raw_entry(tool_use("t1", "Bash")),
+            raw_entry(tool_result("t1")),
+        ];
+        assert_eq!(unanswered_tool_call(&answered), None);
+
+        let blocked = [
+            raw_entry(tool_use("t1", "Bash")),
+            raw_entry(tool_result("t1")),
+            raw_entry(tool_use("t2", "ExitPlanMode")),
+        ];
+        let event = awaiting_input("s1", Path::new("/p/s1.jsonl"), &blocked).unwrap();
+        assert_eq!(event.reason, "plan_approval");
+        assert_eq!(event.tool_use_id, "t2");
+
+        // A new prompt means the pending call was interrupted
+        let interrupted = [
+            raw_entry(tool_use("t1", "Write")),
+            raw_entry(json!({
+                "type": "user", "timestamp": "2025-01-01T00:00:05Z",
+                "message": {"role": "user", "content": "never mind"}
+            })),
+        ];
+        assert_eq!(unanswered_tool_call(&interrupted), None);
+    }
+
+    #[test]
+    fn test_finished_run_summary() {
+        let prompt = json!({
+            "type": "user", "uuid": "p1", "timestamp": "2025-01-01T00:00:00Z",
+            "message": {"role": "user", "content": "fix the build"}
+        });
+        let edit = json!({
+            "type": "assistant", "uuid": "a-t1", "timestamp": "2025-01-01T00:00:10Z",
+            "message": {"id": "msg_1", "role": "assistant", "model": "claude-sonnet-4-20250514",
+                "content": [
+                    {"type": "tool_use", "id": "t1", "name": "Edit", "input":
+                        {"file_path": "/repo/src/lib.rs", "old_string": "a", "new_string": "b"}},
+                    {"type": "tool_use", "id": "t2", "name": "Write", "input":
+                        {"file_path": "/repo/README.md", "content": "# Demo"}}
+                ],
+                "usage": {"input_tokens": 1000, "output_tokens": 100}}
+        });
+        let results = json!({
+            "type": "user", "uuid": "r-t1", "timestamp": "2025-01-01T00:00:11Z",
+            "message": {"role": "user", "content": [
+                {"type": "tool_result", "tool_use_id": "t1", "content": "boom", "is_error": true},
+                {"type": "tool_result", "tool_use_id": "t2", "content": "ok"}
+            ]}
+        });
+        let done = |stop_reason: &str| {
+            json!({
+                "type": "assistant", "uuid": "a-done", "timestamp": "2025-01-01T00:01:30Z",
+                "message": {"id": "msg_2", "role": "assistant", "model": "claude-sonnet-4-20250514",
+                    "stop_reason": stop_reason,
+                    "content": [{"type": "text", "text": "Done"}],
+                    "usage": {"input_tokens": 2000, "output_tokens": 50}}
+            })
+        };
+        let entries: Vec<RawLogEntry> = [prompt.clone(), edit.clone(), results, done("end_turn")]
+            .into_iter()
+            .map(raw_entry)
+            .collect();
+
+        let summary = finished_run("s1", Path::new("/p/s1.jsonl"), &entries).unwrap();
+        assert_eq!(summary.last_message_uuid, "a-done");
+        assert_eq!(summary.duration_seconds, 90);
+        assert_eq!(summary.response_count, 2);
+        assert_eq!(summary.total_tokens, 3150);
+        assert!(summary.cost_usd > 0.0);
+        assert_eq!(summary.tool_call_count, 2);
+        assert_eq!(summary.error_count, 1);
+        // The failed edit changed nothing
+        assert_eq!(summary.files_changed, ["/repo/README.md"]);
+
+        // Still streaming, or blocked on a tool call
+        let streaming = [raw_entry(prompt.clone()), raw_entry(done("tool_use"))];
+        assert_eq!(
+            finished_run("s1", Path::new("/p/s1.jsonl"), &streaming),
+            None
+        );
+        let blocked = [raw_entry(prompt), raw_entry(edit)];
+        assert_eq!(finished_run("s1", Path::new("/p/s1.jsonl"), &blocked), None);
+    }
+
+    #[test]
+    fn test_poll_reports_each_waiting_call_once() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("watched.jsonl");
+        let write_quiet = |lines: &[serde_json::Value]| {
+            let lines: Vec<String> = lines.iter().map(ToString::to_string).collect();
+            fs::write(&path, lines.join("\n")).unwrap();
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(SystemTime::now() - QUIET_PERIOD * 2)
+                .unwrap();
+        };
+        let waiting_in_file = || -> Vec<String> {
+            poll_watched_sessions()
+                .waiting
+                .into_iter()
+                .filter(|event| event.file_path == path.to_string_lossy())
+                .map(|event| event.reason)
+                .collect()
+        };
+
+        write_quiet(&[tool_use("t1", "Bash")]);
+        watched_sessions().lock().unwrap().insert(
+            path.clone(),
+            WatchedSession {
+                session_id: "watched".to_string(),
+                checked: None,
+                notified: None,
+                summarized: None,
+            },
+        );
+
+        assert_eq!(waiting_in_file(), vec!["permission"]);
+        assert!(waiting_in_file().is_empty());
+
+        write_quiet(&[
+            tool_use("t1", "Bash"),
+            tool_result("t1"),
+            tool_use("t2", "AskUserQuestion"),
+        ]);
+        assert_eq!(waiting_in_file(), vec!["question"]);
+
+        watched_sessions().lock().unwrap().remove(&path);
+    }
+}
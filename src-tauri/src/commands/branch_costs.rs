@@ -0,0 +1,246 @@
+//! Cost attribution by git branch
+//!
+//! Claude Code records the checked-out branch on every entry. Responses
+//! are attributed to the branch of their own entry, or to the last branch
+//! seen earlier in the session when the entry has none.
+
+use crate::commands::export::session_project_name;
+use crate::commands::retry_loops::total_tokens;
+use crate::commands::stats::{
+    read_raw_log_entries, resolve_scope_session_files, ResponseUsageTracker,
+};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{BranchCost, BranchCostReport, ClaudeMessage, TokenUsage};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rayon::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+/// One priced response with the branch it was written on
+struct BranchResponse {
+    branch: Option<String>,
+    session_id: String,
+    project_name: Option<String>,
+    timestamp: DateTime<Utc>,
+    usage: TokenUsage,
+    cost_usd: f64,
+}
+
+fn session_responses(session_path: &Path) -> Vec<BranchResponse> {
+    let project_name = session_project_name(session_path);
+    let mut tracker = ResponseUsageTracker::default();
+    let mut current_branch: Option<String> = None;
+
+    read_raw_log_entries(session_path)
+        .into_iter()
+        .filter_map(|mut entry| {
+            let cwd = entry.cwd.take();
+            let message = ClaudeMessage::try_from(entry).ok()?;
+            if let Some(branch) = message.git_branch.as_ref().filter(|b| !b.is_empty()) {
+                current_branch = Some(branch.clone());
+            }
+            let timestamp = DateTime::parse_from_rfc3339(&message.timestamp)
+                .ok()?
+                .with_timezone(&Utc);
+            let (usage, cost_usd) = tracker.usage_and_cost_of(&message, cwd.as_deref());
+            (cost_usd > 0.0 || total_tokens(&usage) > 0).then(|| BranchResponse {
+                branch: current_branch.clone(),
+                session_id: message.session_id,
+                project_name: project_name.clone(),
+                timestamp,
+                usage,
+                cost_usd,
+            })
+        })
+        .collect()
+}
+
+/// Totals of one branch while walking the responses
+struct BranchTotals {
+    cost: BranchCost,
+    sessions: BTreeSet<String>,
+    projects: BTreeSet<String>,
+    first: DateTime<Utc>,
+    last: DateTime<Utc>,
+}
+
+impl BranchTotals {
+    fn new(branch: Option<String>, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            cost: BranchCost {
+                branch,
+                project_names: Vec::new(),
+                session_count: 0,
+                response_count: 0,
+                input_tokens: 0,
+                output_tokens: 0,
+                cache_creation_tokens: 0,
+                cache_read_tokens: 0,
+                total_tokens: 0,
+                cost_usd: 0.0,
+                first_activity: String::new(),
+                last_activity: String::new(),
+            },
+            sessions: BTreeSet::new(),
+            projects: BTreeSet::new(),
+            first: timestamp,
+            last: timestamp,
+        }
+    }
+
+    fn add(&mut self, response: BranchResponse) {
+        let usage = &response.usage;
+        let cost = &mut self.cost;
+        cost.response_count += 1;
+        cost.input_tokens += u64::from(usage.input_tokens.unwrap_or(0));
+        cost.output_tokens += u64::from(usage.output_tokens.unwrap_or(0));
+        cost.cache_creation_tokens += u64::from(usage.cache_creation_input_tokens.unwrap_or(0));
+        cost.cache_read_tokens += u64::from(usage.cache_read_input_tokens.unwrap_or(0));
+        cost.total_tokens += total_tokens(usage);
+        cost.cost_usd += response.cost_usd;
+        self.first = self.first.min(response.timestamp);
+        self.last = self.last.max(response.timestamp);
+        self.sessions.insert(response.session_id);
+        self.projects.extend(response.project_name);
+    }
+
+    fn finish(self) -> BranchCost {
+        BranchCost {
+            project_names: self.projects.into_iter().collect(),
+            session_count: self.sessions.len(),
+            first_activity: self.first.to_rfc3339_opts(SecondsFormat::Secs, true),
+            last_activity: self.last.to_rfc3339_opts(SecondsFormat::Secs, true),
+            ..self.cost
+        }
+    }
+}
+
+/// Group `responses` by branch, most expensive first
+fn branch_costs(responses: Vec<BranchResponse>) -> Vec<BranchCost> {
+    let mut totals: HashMap<Option<String>, BranchTotals> = HashMap::new();
+    for response in responses {
+        totals
+            .entry(response.branch.clone())
+            .or_insert_with(|| BranchTotals::new(response.branch.clone(), response.timestamp))
+            .add(response);
+    }
+
+    let mut branches: Vec<BranchCost> = totals.into_values().map(BranchTotals::finish).collect();
+    branches.sort_by(|a, b| {
+        b.cost_usd
+            .total_cmp(&a.cost_usd)
+            .then_with(|| b.total_tokens.cmp(&a.total_tokens))
+            .then_with(|| a.branch.cmp(&b.branch))
+    });
+    branches
+}
+
+/// Token usage and estimated cost grouped by git branch
+///
+/// `scope` is "session", "project" or "global" with `path` pointing at the
+/// session file, project folder or Claude folder. Responses without any
+/// recorded branch are grouped under a `null` branch.
+#[tauri::command]
+pub async fn get_branch_cost_report(
+    scope: String,
+    path: String,
+) -> Result<BranchCostReport, AppError> {
+    let _timer = OperationTimer::start("get_branch_cost_report");
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let session_files = resolve_scope_session_files(&scope, &path)?;
+        let responses: Vec<BranchResponse> = session_files
+            .par_iter()
+            .flat_map_iter(|path| session_responses(path))
+            .collect();
+
+        let branches = branch_costs(responses);
+        Ok(BranchCostReport {
+            scope,
+            total_cost_usd: branches.iter().map(|branch| branch.cost_usd).sum(),
+            branches,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RawLogEntry;
+    use serde_json::{json, Value};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn entry(id: &str, branch: Option<&str>, output_tokens: u32) -> Value {
+        let mut entry = json!({
+            "uuid": id,
+            "sessionId": "s1",
+            "timestamp": "2025-03-01T09:40:00Z",
+            "type": "assistant",
+            "message": {
+                "id": id,
+                "role": "assistant",
+                "model": "claude-sonnet-4-20250514",
+                "content": [{"type": "text", "text": "ok"}],
+                "usage": {"input_tokens": 0, "output_tokens": output_tokens}
+            }
+        });
+        if let Some(branch) = branch {
+            entry["gitBranch"] = json!(branch);
+        }
+        entry
+    }
+
+    #[test]
+    fn test_git_branch_is_parsed_into_messages() {
+        let raw: RawLogEntry =
+            serde_json::from_value(entry("msg_1", Some("feature/login"), 1)).unwrap();
+        let message = ClaudeMessage::try_from(raw).unwrap();
+        assert_eq!(message.git_branch.as_deref(), Some("feature/login"));
+    }
+
+    #[tokio::test]
+    async fn test_branch_cost_report_groups_responses() {
+        let temp = TempDir::new().unwrap();
+        let project_dir = temp.path().join("-Users-me-demo");
+        fs::create_dir_all(&project_dir).unwrap();
+        let lines = [
+            entry("msg_0", None, 50),
+            entry("msg_1", Some("main"), 100),
+            entry("msg_2", Some("feature/login"), 1_000_000),
+            // No branch recorded: attributed to the previous one
+            entry("msg_3", None, 200),
+            entry("msg_4", Some(""), 300),
+        ]
+        .map(|line| line.to_string());
+        fs::write(project_dir.join("s1.jsonl"), lines.join("\n")).unwrap();
+
+        let report = get_branch_cost_report(
+            "project".to_string(),
+            project_dir.to_string_lossy().to_string(),
+        )
+        .await
+        .unwrap();
+
+        let branches: Vec<(Option<&str>, u64)> = report
+            .branches
+            .iter()
+            .map(|b| (b.branch.as_deref(), b.output_tokens))
+            .collect();
+        assert_eq!(
+            branches,
+            [
+                (Some("feature/login"), 1_000_500),
+                (Some("main"), 100),
+                (None, 50),
+            ]
+        );
+        assert_eq!(report.branches[0].response_count, 3);
+        assert_eq!(report.branches[0].project_names, ["demo"]);
+        assert_eq!(report.branches[0].session_count, 1);
+        assert!((report.total_cost_usd - 15.00975).abs() < 1e-9);
+    }
+}
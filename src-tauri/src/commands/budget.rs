@@ -0,0 +1,384 @@
+//! Monthly project budgets
+//!
+//! Budgets are stored in the project metadata and mirrored here for the
+//! background check, which emits `BUDGET_THRESHOLD_EVENT` whenever a project
+//! crosses one of its thresholds while the app is open. Month-end burn is
+//! projected from the average daily usage of the last days.
+
+use crate::commands::cost_report::{last_day_of_month, session_costs};
+use crate::commands::stats::resolve_scope_session_files;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{
+    BudgetStatus, BudgetThresholdCrossed, BudgetUsage, ProjectBudget, ProjectMetadata,
+};
+use crate::utils::{extract_project_name, file_name_string};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::Duration;
+
+/// Event emitted when a project crosses a budget threshold
+pub const BUDGET_THRESHOLD_EVENT: &str = "budget-threshold-crossed";
+
+/// Interval between two background budget checks
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Warning thresholds (percent) of budgets that set none
+const DEFAULT_THRESHOLDS: &[u32] = &[80, 100];
+
+/// Number of recent days the month-end projection is based on
+const TREND_DAYS: u32 = 7;
+
+/// Budgets by project path
+static BUDGETS: RwLock<BTreeMap<String, ProjectBudget>> = RwLock::new(BTreeMap::new());
+
+/// (project path, month, metric)
+type ThresholdKey = (String, String, &'static str);
+
+/// Highest threshold reported per key; None until the first check, which
+/// records the thresholds already crossed at startup
+static REPORTED: Mutex<Option<HashMap<ThresholdKey, u32>>> = Mutex::new(None);
+
+type BudgetListener = Box<dyn Fn(&BudgetThresholdCrossed) + Send + Sync>;
+
+/// Forwards threshold crossings to the frontend (unset in tests)
+static BUDGET_LISTENER: OnceLock<BudgetListener> = OnceLock::new();
+
+/// Check a project budget
+pub fn validate_budget(budget: &ProjectBudget) -> Result<(), String> {
+    if budget.monthly_tokens == Some(0) {
+        return Err("Monthly token budget must be positive".to_string());
+    }
+    if budget
+        .monthly_cost_usd
+        .is_some_and(|cost| !cost.is_finite() || cost <= 0.0)
+    {
+        return Err("Monthly cost budget must be positive".to_string());
+    }
+    if let Some(threshold) = budget
+        .thresholds
+        .iter()
+        .find(|&&threshold| threshold == 0 || threshold > 1000)
+    {
+        return Err(format!("Invalid budget threshold: {threshold}%"));
+    }
+    Ok(())
+}
+
+/// Apply the budgets of the project metadata
+pub fn set_budgets<'a>(projects: impl IntoIterator<Item = (&'a String, &'a ProjectMetadata)>) {
+    let budgets = projects
+        .into_iter()
+        .filter_map(|(path, project)| Some((path.clone(), project.budget.clone()?)))
+        .collect();
+    if let Ok(mut current) = BUDGETS.write() {
+        *current = budgets;
+    }
+}
+
+fn budgets() -> BTreeMap<String, ProjectBudget> {
+    BUDGETS.read().map(|b| b.clone()).unwrap_or_default()
+}
+
+/// Register the callback that emits threshold crossings to the frontend
+pub fn set_budget_listener(listener: impl Fn(&BudgetThresholdCrossed) + Send + Sync + 'static) {
+    let _ = BUDGET_LISTENER.set(Box::new(listener));
+}
+
+/// Tokens and cost of a project per day of the month of `today`
+fn daily_usage(project_path: &str, today: NaiveDate) -> BTreeMap<NaiveDate, (u64, f64)> {
+    let Some(month_start) = today.with_day(1) else {
+        return BTreeMap::new();
+    };
+    let month_start_time = month_start.and_time(chrono::NaiveTime::MIN).and_utc();
+    // Session files are append-only: older files hold nothing of this month
+    let session_files: Vec<_> = resolve_scope_session_files("project", project_path)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|path| {
+            fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .map_or(true, |modified| {
+                    DateTime::<Utc>::from(modified) >= month_start_time
+                })
+        })
+        .collect();
+
+    session_files
+        .par_iter()
+        .map(|path| session_costs(path))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .flat_map(|session| session.responses)
+        .filter(|response| response.date >= month_start && response.date <= today)
+        .fold(BTreeMap::new(), |mut days, response| {
+            let day: &mut (u64, f64) = days.entry(response.date).or_default();
+            day.0 += response.tokens;
+            day.1 += response.cost_usd;
+            days
+        })
+}
+
+/// Consumption of `budget` given this month's `used` total and the total of
+/// the last `trend_days` days
+fn budget_usage(
+    used: f64,
+    budget: f64,
+    recent: f64,
+    trend_days: u32,
+    days_left: u32,
+) -> BudgetUsage {
+    let projected = used + recent / f64::from(trend_days.max(1)) * f64::from(days_left);
+    BudgetUsage {
+        used,
+        budget,
+        percent: used / budget * 100.0,
+        projected,
+        projected_percent: projected / budget * 100.0,
+    }
+}
+
+/// Highest of `thresholds` reached at `percent`
+fn crossed_threshold(thresholds: &[u32], percent: f64) -> Option<u32> {
+    let thresholds = if thresholds.is_empty() {
+        DEFAULT_THRESHOLDS
+    } else {
+        thresholds
+    };
+    thresholds
+        .iter()
+        .copied()
+        .filter(|&threshold| percent >= f64::from(threshold))
+        .max()
+}
+
+fn budget_status(
+    project_path: &str,
+    budget: &ProjectBudget,
+    days: &BTreeMap<NaiveDate, (u64, f64)>,
+    today: NaiveDate,
+) -> BudgetStatus {
+    let days_in_month = last_day_of_month(today.year(), today.month()).map_or(30, |d| d.day());
+    let days_elapsed = today.day();
+    let trend_days = days_elapsed.min(TREND_DAYS);
+    let trend_start = today - chrono::Days::new(u64::from(trend_days - 1));
+
+    let total = |recent_only: bool| {
+        days.iter()
+            .filter(|(day, _)| !recent_only || **day >= trend_start)
+            .fold((0u64, 0.0), |(tokens, cost), (_, day)| {
+                (tokens + day.0, cost + day.1)
+            })
+    };
+    let (used_tokens, used_cost) = total(false);
+    let (recent_tokens, recent_cost) = total(true);
+    let days_left = days_in_month - days_elapsed;
+
+    #[allow(clippy::cast_precision_loss)]
+    let tokens = budget.monthly_tokens.map(|limit| {
+        budget_usage(
+            used_tokens as f64,
+            limit as f64,
+            recent_tokens as f64,
+            trend_days,
+            days_left,
+        )
+    });
+    let cost = budget
+        .monthly_cost_usd
+        .map(|limit| budget_usage(used_cost, limit, recent_cost, trend_days, days_left));
+    let crossed_threshold = tokens
+        .iter()
+        .chain(cost.iter())
+        .filter_map(|usage| crossed_threshold(&budget.thresholds, usage.percent))
+        .max();
+
+    BudgetStatus {
+        project_path: project_path.to_string(),
+        project_name: file_name_string(Path::new(project_path))
+            .map(|name| extract_project_name(&name))
+            .unwrap_or_else(|| project_path.to_string()),
+        month: today.format("%Y-%m").to_string(),
+        days_elapsed,
+        days_in_month,
+        tokens,
+        cost,
+        crossed_threshold,
+    }
+}
+
+fn budget_statuses(today: NaiveDate) -> Vec<BudgetStatus> {
+    budgets()
+        .iter()
+        .map(|(path, budget)| budget_status(path, budget, &daily_usage(path, today), today))
+        .collect()
+}
+
+/// Thresholds newly crossed since the last check
+fn newly_crossed(
+    statuses: &[BudgetStatus],
+    budgets: &BTreeMap<String, ProjectBudget>,
+    reported: &mut HashMap<ThresholdKey, u32>,
+) -> Vec<BudgetThresholdCrossed> {
+    let mut crossed = Vec::new();
+    for status in statuses {
+        let thresholds = budgets
+            .get(&status.project_path)
+            .map(|budget| budget.thresholds.as_slice())
+            .unwrap_or_default();
+        for (metric, usage) in [("tokens", &status.tokens), ("cost", &status.cost)] {
+            let Some(usage) = usage else {
+                continue;
+            };
+            let Some(threshold) = crossed_threshold(thresholds, usage.percent) else {
+                continue;
+            };
+            let key = (status.project_path.clone(), status.month.clone(), metric);
+            if reported.get(&key).is_some_and(|&last| last >= threshold) {
+                continue;
+            }
+            reported.insert(key, threshold);
+            crossed.push(BudgetThresholdCrossed {
+                project_path: status.project_path.clone(),
+                project_name: status.project_name.clone(),
+                month: status.month.clone(),
+                metric: metric.to_string(),
+                threshold,
+                usage: usage.clone(),
+            });
+        }
+    }
+    crossed
+}
+
+fn check_budgets() -> Vec<BudgetThresholdCrossed> {
+    let budgets = budgets();
+    if budgets.is_empty() {
+        return Vec::new();
+    }
+    let statuses = budget_statuses(Utc::now().date_naive());
+
+    let Ok(mut reported) = REPORTED.lock() else {
+        return Vec::new();
+    };
+    let first_check = reported.is_none();
+    let crossed = newly_crossed(
+        &statuses,
+        &budgets,
+        reported.get_or_insert_with(HashMap::new),
+    );
+    if first_check {
+        Vec::new()
+    } else {
+        crossed
+    }
+}
+
+/// Start checking the budgets in the background
+pub fn start_budget_watcher() {
+    tauri::async_runtime::spawn(async {
+        loop {
+            if let Ok(crossed) = tauri::async_runtime::spawn_blocking(check_budgets).await {
+                if let Some(listener) = BUDGET_LISTENER.get() {
+                    crossed.iter().for_each(listener);
+                }
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Budget consumption of every project with a budget, this month
+#[tauri::command]
+pub async fn get_budget_status() -> Result<Vec<BudgetStatus>, AppError> {
+    let _timer = OperationTimer::start("get_budget_status");
+
+    Ok(
+        tauri::async_runtime::spawn_blocking(|| budget_statuses(Utc::now().date_naive()))
+            .await
+            .map_err(|e| format!("Task join error: {e}"))?,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_budget_status_projects_recent_trend() {
+        let budget = ProjectBudget {
+            monthly_tokens: Some(1_000),
+            monthly_cost_usd: Some(100.0),
+            thresholds: Vec::new(),
+        };
+        // A slow start, then 10 USD a day over the last week
+        let mut days = BTreeMap::from([(date(2025, 4, 1), (500, 5.0))]);
+        for day in 4..=10 {
+            days.insert(date(2025, 4, day), (0, 10.0));
+        }
+
+        let status = budget_status("/p/-Users-me-demo", &budget, &days, date(2025, 4, 10));
+        assert_eq!(status.month, "2025-04");
+        assert_eq!(status.project_name, "demo");
+        assert_eq!((status.days_elapsed, status.days_in_month), (10, 30));
+
+        let cost = status.cost.unwrap();
+        assert!((cost.used - 75.0).abs() < 1e-9);
+        assert!((cost.projected - 275.0).abs() < 1e-9);
+        let tokens = status.tokens.unwrap();
+        assert!((tokens.percent - 50.0).abs() < 1e-9);
+        assert!((tokens.projected - 500.0).abs() < 1e-9);
+        assert_eq!(status.crossed_threshold, None);
+    }
+
+    #[test]
+    fn test_thresholds_are_reported_once() {
+        let budget = ProjectBudget {
+            monthly_cost_usd: Some(10.0),
+            thresholds: vec![50, 90],
+            ..ProjectBudget::default()
+        };
+        let budgets = BTreeMap::from([("/p/demo".to_string(), budget.clone())]);
+        let status = |cost: f64| {
+            let days = BTreeMap::from([(date(2025, 4, 2), (0, cost))]);
+            budget_status("/p/demo", &budget, &days, date(2025, 4, 2))
+        };
+        let mut reported = HashMap::new();
+
+        assert!(newly_crossed(&[status(4.0)], &budgets, &mut reported).is_empty());
+        let crossed = newly_crossed(&[status(6.0)], &budgets, &mut reported);
+        assert_eq!(crossed.len(), 1);
+        assert_eq!(
+            (crossed[0].metric.as_str(), crossed[0].threshold),
+            ("cost", 50)
+        );
+        assert!(newly_crossed(&[status(7.0)], &budgets, &mut reported).is_empty());
+        assert_eq!(
+            newly_crossed(&[status(9.5)], &budgets, &mut reported)[0].threshold,
+            90
+        );
+    }
+
+    #[test]
+    fn test_validate_budget() {
+        assert!(validate_budget(&ProjectBudget::default()).is_ok());
+        assert!(validate_budget(&ProjectBudget {
+            monthly_cost_usd: Some(-1.0),
+            ..ProjectBudget::default()
+        })
+        .is_err());
+        assert!(validate_budget(&ProjectBudget {
+            thresholds: vec![0],
+            ..ProjectBudget::default()
+        })
+        .is_err());
+    }
+}
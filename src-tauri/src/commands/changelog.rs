@@ -0,0 +1,303 @@
+//! Daily changelog of a project
+//!
+//! Lists, as Markdown ready to paste into standup notes, every file changed
+//! by successful Edit/MultiEdit/Write calls on one day, with line stats and
+//! the prompts that led to the changes (the latest prompt of the session
+//! before each edit).
+
+use crate::commands::churn::{file_edits, replay, FileEdit};
+use crate::commands::prompt_quality::prompt_text;
+use crate::commands::retry_loops::{extract_tool_calls, truncate_chars, ToolCall};
+use crate::commands::session::is_genuine_user_text;
+use crate::commands::stats::{read_raw_log_entries, resolve_scope_session_files};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::RawLogEntry;
+use crate::utils::{extract_project_name, file_name_string};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Maximum characters of a prompt listed under a file
+const MAX_PROMPT_CHARS: usize = 120;
+
+/// Characters of the session ID shown next to a prompt
+const SHORT_SESSION_ID_CHARS: usize = 8;
+
+/// Tool calls and prompts of one session
+struct SessionActivity {
+    session_id: String,
+    cwd: Option<String>,
+    calls: Vec<ToolCall>,
+    prompts: Vec<(DateTime<Utc>, String)>, // In time order
+}
+
+/// Genuine prompts of the main conversation, with their time
+fn session_prompts(entries: &[RawLogEntry]) -> Vec<(DateTime<Utc>, String)> {
+    let mut prompts: Vec<(DateTime<Utc>, String)> = entries
+        .iter()
+        .filter(|entry| {
+            entry.message_type == "user"
+                && entry.is_sidechain != Some(true)
+                && entry.is_meta != Some(true)
+        })
+        .filter_map(|entry| {
+            let text = prompt_text(&entry.message.as_ref()?.content)?;
+            if !is_genuine_user_text(&text) {
+                return None;
+            }
+            let time = DateTime::parse_from_rfc3339(entry.timestamp.as_deref()?).ok()?;
+            let first_line = text.lines().find(|line| !line.trim().is_empty())?;
+            Some((
+                time.with_timezone(&Utc),
+                truncate_chars(first_line.trim(), MAX_PROMPT_CHARS),
+            ))
+        })
+        .collect();
+    prompts.sort_by_key(|(time, _)| *time);
+    prompts
+}
+
+fn session_activity(session_path: &Path) -> SessionActivity {
+    let entries = read_raw_log_entries(session_path);
+    SessionActivity {
+        session_id: session_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        cwd: entries.iter().find_map(|entry| entry.cwd.clone()),
+        calls: extract_tool_calls(&entries),
+        prompts: session_prompts(&entries),
+    }
+}
+
+/// Edits of a session made on `date`, where days start at UTC + `offset`
+fn day_edits(session: &SessionActivity, date: NaiveDate, offset: Duration) -> Vec<FileEdit<'_>> {
+    file_edits(&session.calls)
+        .into_iter()
+        .filter(|edit| {
+            edit.timestamp
+                .is_some_and(|time| (time + offset).date_naive() == date)
+        })
+        .collect()
+}
+
+/// Latest prompt of the session sent at or before `time`
+fn prompt_before(session: &SessionActivity, time: Option<DateTime<Utc>>) -> Option<&str> {
+    let time = time?;
+    session
+        .prompts
+        .iter()
+        .take_while(|(prompt_time, _)| *prompt_time <= time)
+        .last()
+        .map(|(_, text)| text.as_str())
+}
+
+/// Path of `file` relative to the session's working directory, if inside it
+pub(crate) fn relative_path<'a>(file: &'a str, cwd: Option<&str>) -> &'a str {
+    cwd.and_then(|cwd| file.strip_prefix(cwd))
+        .and_then(|rest| rest.strip_prefix(['/', '\\']))
+        .unwrap_or(file)
+}
+
+pub(crate) fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{count} {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+/// Changes of one file on the day
+#[derive(Default)]
+struct FileLog<'a> {
+    display_path: &'a str,
+    edit_count: usize,
+    prompts: Vec<(&'a str, &'a str)>, // (prompt, session ID), first occurrence order
+}
+
+fn render_changelog(
+    project_name: &str,
+    date: NaiveDate,
+    offset: Duration,
+    sessions: &[SessionActivity],
+) -> String {
+    let mut edits: Vec<(&SessionActivity, FileEdit)> = sessions
+        .iter()
+        .flat_map(|session| {
+            day_edits(session, date, offset)
+                .into_iter()
+                .map(move |edit| (session, edit))
+        })
+        .collect();
+    edits.sort_by_key(|(_, edit)| edit.timestamp);
+
+    let mut out = format!("# {project_name} changelog — {date}\n");
+    if edits.is_empty() {
+        out.push_str("\nNo files were changed on this day.\n");
+        return out;
+    }
+
+    let mut logs: HashMap<&str, FileLog> = HashMap::new();
+    for (session, edit) in &edits {
+        let log = logs.entry(edit.file_path).or_insert_with(|| FileLog {
+            display_path: relative_path(edit.file_path, session.cwd.as_deref()),
+            ..FileLog::default()
+        });
+        log.edit_count += 1;
+        if let Some(prompt) = prompt_before(session, edit.timestamp) {
+            let source = (prompt, session.session_id.as_str());
+            if !log.prompts.contains(&source) {
+                log.prompts.push(source);
+            }
+        }
+    }
+    let session_count = sessions
+        .iter()
+        .filter(|session| edits.iter().any(|(s, _)| std::ptr::eq(*s, *session)))
+        .count();
+
+    let (total, mut files) = replay(edits.iter().map(|(_, edit)| edit), None);
+    files.sort_by(|a, b| {
+        (b.stats.lines_added + b.stats.lines_removed)
+            .cmp(&(a.stats.lines_added + a.stats.lines_removed))
+            .then_with(|| a.file_path.cmp(&b.file_path))
+    });
+
+    let _ = writeln!(
+        out,
+        "\n{} changed in {} · +{} / -{} lines",
+        plural(files.len(), "file"),
+        plural(session_count, "session"),
+        total.lines_added,
+        total.lines_removed
+    );
+    for file in &files {
+        let Some(log) = logs.get(file.file_path.as_str()) else {
+            continue;
+        };
+        let _ = writeln!(
+            out,
+            "\n## `{}`\n\n+{} / -{} lines · {}",
+            log.display_path,
+            file.stats.lines_added,
+            file.stats.lines_removed,
+            plural(log.edit_count, "edit")
+        );
+        if !log.prompts.is_empty() {
+            out.push('\n');
+        }
+        for (prompt, session_id) in &log.prompts {
+            let short_id: String = session_id.chars().take(SHORT_SESSION_ID_CHARS).collect();
+            let _ = writeln!(out, "- {prompt} _(session {short_id})_");
+        }
+    }
+    out
+}
+
+/// Markdown changelog of the files changed in a project on one day
+///
+/// `date` is `YYYY-MM-DD`. Days are in UTC unless `utc_offset_minutes`
+/// gives the local offset.
+#[tauri::command]
+pub async fn generate_daily_changelog(
+    project_path: String,
+    date: String,
+    utc_offset_minutes: Option<i32>,
+) -> Result<String, AppError> {
+    let _timer = OperationTimer::start("generate_daily_changelog");
+    let day = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date {date}: {e}"))?;
+    let offset = Duration::minutes(i64::from(utc_offset_minutes.unwrap_or(0)));
+    let project_name = file_name_string(Path::new(&project_path))
+        .map(|name| extract_project_name(&name))
+        .unwrap_or_else(|| "Unknown".to_string());
+    let session_files = resolve_scope_session_files("project", &project_path)?;
+
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let sessions: Vec<SessionActivity> = session_files
+            .par_iter()
+            .map(|path| session_activity(path))
+            .filter(|session| !day_edits(session, day, offset).is_empty())
+            .collect();
+        render_changelog(&project_name, day, offset, &sessions)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(timestamp)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn edit(timestamp: &str, file_path: &str, old: &str, new: &str) -> ToolCall {
+        ToolCall {
+            name: "Edit".to_string(),
+            input: json!({"file_path": file_path, "old_string": old, "new_string": new}),
+            message_uuid: "m".to_string(),
+            timestamp: timestamp.to_string(),
+            tokens: 0,
+            cost_usd: 0.0,
+            failed: false,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_render_changelog_lists_files_with_prompts() {
+        let session = SessionActivity {
+            session_id: "abcdef1234567890".to_string(),
+            cwd: Some("/repo".to_string()),
+            calls: vec![
+                edit("2025-03-01T23:30:00Z", "/repo/src/old.rs", "a", "b"),
+                edit("2025-03-02T09:00:00Z", "/repo/src/main.rs", "a", "b\nc"),
+                edit("2025-03-02T09:05:00Z", "/repo/src/main.rs", "c", "d"),
+                edit("2025-03-02T10:00:00Z", "/elsewhere/notes.md", "", "x"),
+            ],
+            prompts: vec![
+                (at("2025-03-02T08:59:00Z"), "Fix the parser".to_string()),
+                (at("2025-03-02T09:59:00Z"), "Write notes".to_string()),
+            ],
+        };
+        let date = NaiveDate::from_ymd_opt(2025, 3, 2).unwrap();
+
+        let changelog = render_changelog("demo", date, Duration::zero(), &[session]);
+
+        assert_eq!(
+            changelog,
+            "# demo changelog — 2025-03-02\n\
+             \n2 files changed in 1 session · +4 / -2 lines\n\
+             \n## `src/main.rs`\n\n+3 / -2 lines · 2 edits\n\
+             \n- Fix the parser _(session abcdef12)_\n\
+             \n## `/elsewhere/notes.md`\n\n+1 / -0 lines · 1 edit\n\
+             \n- Write notes _(session abcdef12)_\n"
+        );
+    }
+
+    #[test]
+    fn test_day_edits_use_utc_offset() {
+        let session = SessionActivity {
+            session_id: "s1".to_string(),
+            cwd: None,
+            calls: vec![edit("2025-03-01T23:30:00Z", "/a.rs", "a", "b")],
+            prompts: Vec::new(),
+        };
+        let march_2 = NaiveDate::from_ymd_opt(2025, 3, 2).unwrap();
+
+        assert!(day_edits(&session, march_2, Duration::zero()).is_empty());
+        assert_eq!(day_edits(&session, march_2, Duration::hours(1)).len(), 1);
+        assert!(
+            render_changelog("demo", march_2, Duration::zero(), &[session])
+                .ends_with("No files were changed on this day.\n")
+        );
+    }
+}
@@ -0,0 +1,438 @@
+//! Diff churn metric
+//!
+//! Replays the successful Edit/MultiEdit/Write calls of a session and counts
+//! the lines that were added and later removed again — thrash caused by
+//! back-and-forth corrections. Lines are compared trimmed and blank lines are
+//! ignored. Within a session every later removal counts; across a project
+//! only removals within a time window of the addition do.
+
+use crate::commands::retry_loops::{extract_tool_calls, ToolCall};
+use crate::commands::stats::{read_raw_log_entries, resolve_scope_session_files};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ChurnStats, FileChurn, ProjectChurn, SessionChurn};
+use crate::utils::{display_path, extract_project_name, file_name_string, resolve_session_file};
+use chrono::{DateTime, Duration, Utc};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Default window within which a later session's removal counts as churn
+const DEFAULT_WINDOW_HOURS: u32 = 24;
+
+/// Maximum number of files listed in a project report
+const MAX_PROJECT_FILES: usize = 20;
+
+/// What an edit did to a file, as trimmed non-blank lines
+pub(crate) enum LineChange<'a> {
+    /// Edit/MultiEdit: `old_string` replaced by `new_string`
+    Replace {
+        removed: Vec<&'a str>,
+        added: Vec<&'a str>,
+    },
+    /// Write: the whole file replaced by this content
+    Overwrite(Vec<&'a str>),
+}
+
+/// One file modification made by a successful tool call
+pub(crate) struct FileEdit<'a> {
+    pub file_path: &'a str,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub change: LineChange<'a>,
+}
+
+fn content_lines(text: &str) -> Vec<&str> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Lines of `from` missing from `to`, counting duplicates
+fn multiset_difference<'a>(from: &[&'a str], to: &[&'a str]) -> Vec<&'a str> {
+    let mut remaining: HashMap<&str, usize> = HashMap::new();
+    for line in to {
+        *remaining.entry(line).or_insert(0) += 1;
+    }
+    from.iter()
+        .filter(|line| match remaining.get_mut(*line) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .copied()
+        .collect()
+}
+
+fn replace_change<'a>(old: &'a str, new: &'a str) -> LineChange<'a> {
+    let (old, new) = (content_lines(old), content_lines(new));
+    LineChange::Replace {
+        removed: multiset_difference(&old, &new),
+        added: multiset_difference(&new, &old),
+    }
+}
+
+/// File modifications of the successful edit calls, in call order
+pub(crate) fn file_edits(calls: &[ToolCall]) -> Vec<FileEdit<'_>> {
+    let mut edits = Vec::new();
+    for call in calls.iter().filter(|call| !call.failed) {
+        let input = &call.input;
+        let Some(file_path) = input.get("file_path").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let timestamp = DateTime::parse_from_rfc3339(&call.timestamp)
+            .ok()
+            .map(|t| t.with_timezone(&Utc));
+
+        let changes: Vec<LineChange> = match call.name.as_str() {
+            "Edit" => input
+                .get("old_string")
+                .and_then(|v| v.as_str())
+                .zip(input.get("new_string").and_then(|v| v.as_str()))
+                .map(|(old, new)| replace_change(old, new))
+                .into_iter()
+                .collect(),
+            "MultiEdit" => input
+                .get("edits")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|edit| {
+                    let old = edit.get("old_string")?.as_str()?;
+                    let new = edit.get("new_string")?.as_str()?;
+                    Some(replace_change(old, new))
+                })
+                .collect(),
+            "Write" => input
+                .get("content")
+                .and_then(|v| v.as_str())
+                .map(|content| LineChange::Overwrite(content_lines(content)))
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
+        };
+        edits.extend(changes.into_iter().map(|change| FileEdit {
+            file_path,
+            timestamp,
+            change,
+        }));
+    }
+    edits
+}
+
+/// Lines added to one file that have not been removed yet
+#[derive(Default)]
+struct LiveLines<'a> {
+    added_at: HashMap<&'a str, Vec<Option<DateTime<Utc>>>>,
+    stats: ChurnStats,
+}
+
+impl<'a> LiveLines<'a> {
+    /// Remove a line; counts as churn if it was added within the window
+    fn remove(&mut self, line: &'a str, at: Option<DateTime<Utc>>, window: Option<Duration>) {
+        self.stats.lines_removed += 1;
+        let Some(stack) = self.added_at.get_mut(line) else {
+            return;
+        };
+        let Some(added) = stack.pop() else {
+            return;
+        };
+        let within_window = match (window, added, at) {
+            (Some(window), Some(added), Some(at)) => at - added <= window,
+            _ => true,
+        };
+        if within_window {
+            self.stats.churned_lines += 1;
+        }
+    }
+
+    fn add(&mut self, line: &'a str, at: Option<DateTime<Utc>>) {
+        self.stats.lines_added += 1;
+        self.added_at.entry(line).or_default().push(at);
+    }
+
+    fn apply(&mut self, edit: &FileEdit<'a>, window: Option<Duration>) {
+        match &edit.change {
+            LineChange::Replace { removed, added } => {
+                for line in removed {
+                    self.remove(line, edit.timestamp, window);
+                }
+                for line in added {
+                    self.add(line, edit.timestamp);
+                }
+            }
+            LineChange::Overwrite(content) => {
+                // Only lines this replay added are known; the rest of the
+                // previous content is not in the log
+                let live: Vec<&str> = self
+                    .added_at
+                    .iter()
+                    .flat_map(|(line, stack)| std::iter::repeat(*line).take(stack.len()))
+                    .collect();
+                for line in multiset_difference(&live, content) {
+                    self.remove(line, edit.timestamp, window);
+                }
+                for line in multiset_difference(content, &live) {
+                    self.add(line, edit.timestamp);
+                }
+            }
+        }
+    }
+}
+
+fn with_ratio(mut stats: ChurnStats) -> ChurnStats {
+    stats.churn_ratio = if stats.lines_added == 0 {
+        0.0
+    } else {
+        stats.churned_lines as f64 / stats.lines_added as f64
+    };
+    stats
+}
+
+fn add_stats(total: &mut ChurnStats, stats: &ChurnStats) {
+    total.lines_added += stats.lines_added;
+    total.lines_removed += stats.lines_removed;
+    total.churned_lines += stats.churned_lines;
+}
+
+/// Replay edits in order and return (total, per-file) churn, most churned first
+pub(crate) fn replay<'a>(
+    edits: impl IntoIterator<Item = &'a FileEdit<'a>>,
+    window: Option<Duration>,
+) -> (ChurnStats, Vec<FileChurn>) {
+    let mut files: HashMap<&str, LiveLines> = HashMap::new();
+    for edit in edits {
+        files.entry(edit.file_path).or_default().apply(edit, window);
+    }
+
+    let mut total = ChurnStats::default();
+    let mut file_churn: Vec<FileChurn> = files
+        .into_iter()
+        .map(|(file_path, live)| {
+            add_stats(&mut total, &live.stats);
+            FileChurn {
+                file_path: file_path.to_string(),
+                stats: with_ratio(live.stats),
+            }
+        })
+        .collect();
+    file_churn.sort_by(|a, b| {
+        b.stats
+            .churned_lines
+            .cmp(&a.stats.churned_lines)
+            .then_with(|| a.file_path.cmp(&b.file_path))
+    });
+    (with_ratio(total), file_churn)
+}
+
+fn session_churn(session_path: &Path, calls: &[ToolCall]) -> SessionChurn {
+    let edits = file_edits(calls);
+    let (stats, files) = replay(&edits, None);
+    SessionChurn {
+        session_id: session_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        file_path: display_path(session_path),
+        edit_count: edits.len(),
+        stats,
+        files,
+    }
+}
+
+/// Line churn of the edits made in a session
+#[tauri::command]
+pub async fn get_session_churn(
+    session_id: String,
+    project_path: String,
+) -> Result<SessionChurn, AppError> {
+    let _timer = OperationTimer::start("get_session_churn");
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let calls = extract_tool_calls(&read_raw_log_entries(&session_path));
+        session_churn(&session_path, &calls)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?)
+}
+
+/// Build the project report from each session's tool calls
+fn project_churn(
+    project_name: String,
+    sessions: &[(PathBuf, Vec<ToolCall>)],
+    window_hours: u32,
+) -> ProjectChurn {
+    let mut session_reports: Vec<SessionChurn> = sessions
+        .iter()
+        .map(|(path, calls)| session_churn(path, calls))
+        .filter(|report| report.edit_count > 0)
+        .collect();
+    session_reports.sort_by(|a, b| {
+        b.stats
+            .churned_lines
+            .cmp(&a.stats.churned_lines)
+            .then_with(|| a.session_id.cmp(&b.session_id))
+    });
+
+    let mut edits: Vec<FileEdit> = sessions
+        .iter()
+        .flat_map(|(_, calls)| file_edits(calls))
+        .collect();
+    // Stable sort: edits without a timestamp keep their session order
+    edits.sort_by_key(|edit| edit.timestamp);
+    let (stats, mut files) = replay(&edits, Some(Duration::hours(i64::from(window_hours))));
+    files.truncate(MAX_PROJECT_FILES);
+
+    ProjectChurn {
+        project_name,
+        window_hours,
+        session_count: session_reports.len(),
+        stats,
+        sessions: session_reports,
+        files,
+    }
+}
+
+/// Line churn of a project, per session and across sessions
+///
+/// Across sessions a removed line only counts as churn if it was added within
+/// `window_hours` (default 24) before.
+#[tauri::command]
+pub async fn get_project_churn(
+    project_path: String,
+    window_hours: Option<u32>,
+) -> Result<ProjectChurn, AppError> {
+    let _timer = OperationTimer::start("get_project_churn");
+    let project_name = file_name_string(Path::new(&project_path))
+        .map(|name| extract_project_name(&name))
+        .unwrap_or_else(|| "Unknown".to_string());
+    let session_files = resolve_scope_session_files("project", &project_path)?;
+
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let sessions: Vec<(PathBuf, Vec<ToolCall>)> = session_files
+            .into_par_iter()
+            .map(|path| {
+                let calls = extract_tool_calls(&read_raw_log_entries(&path));
+                (path, calls)
+            })
+            .collect();
+        project_churn(
+            project_name,
+            &sessions,
+            window_hours.unwrap_or(DEFAULT_WINDOW_HOURS),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn call(name: &str, timestamp: &str, input: serde_json::Value) -> ToolCall {
+        ToolCall {
+            name: name.to_string(),
+            input,
+            message_uuid: "m".to_string(),
+            timestamp: timestamp.to_string(),
+            tokens: 0,
+            cost_usd: 0.0,
+            failed: false,
+            error: None,
+        }
+    }
+
+    fn edit(timestamp: &str, old: &str, new: &str) -> ToolCall {
+        call(
+            "Edit",
+            timestamp,
+            json!({"file_path": "/src/a.rs", "old_string": old, "new_string": new}),
+        )
+    }
+
+    #[test]
+    fn test_session_churn_counts_added_lines_removed_later() {
+        let mut failed = edit("2025-01-01T00:00:03Z", "let c = 3;", "");
+        failed.failed = true;
+        let calls = vec![
+            edit(
+                "2025-01-01T00:00:00Z",
+                "fn f() {}",
+                "fn f() {\n    let a = 1;\n    let b = 2;\n}",
+            ),
+            // Unchanged context lines are neither removed nor added
+            edit(
+                "2025-01-01T00:00:01Z",
+                "    let a = 1;\n    let b = 2;",
+                "    let a = 1;\n    let b = 3;",
+            ),
+            failed,
+            call(
+                "Write",
+                "2025-01-01T00:00:04Z",
+                json!({"file_path": "/src/b.rs", "content": "x\ny"}),
+            ),
+        ];
+
+        let report = session_churn(Path::new("/p/s1.jsonl"), &calls);
+
+        assert_eq!(report.session_id, "s1");
+        assert_eq!(report.edit_count, 3);
+        assert_eq!(report.stats.lines_added, 7);
+        assert_eq!(report.stats.lines_removed, 2);
+        assert_eq!(report.stats.churned_lines, 1);
+        assert_eq!(report.files[0].file_path, "/src/a.rs");
+        assert!((report.files[0].stats.churn_ratio - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_overwrite_removes_previously_written_lines() {
+        let calls = vec![
+            call(
+                "Write",
+                "2025-01-01T00:00:00Z",
+                json!({"file_path": "/a", "content": "a\nb\nc"}),
+            ),
+            call(
+                "Write",
+                "2025-01-01T00:00:01Z",
+                json!({"file_path": "/a", "content": "a\nc\nd"}),
+            ),
+        ];
+        let report = session_churn(Path::new("/p/s1.jsonl"), &calls);
+        assert_eq!(report.stats.lines_added, 4);
+        assert_eq!(report.stats.churned_lines, 1);
+    }
+
+    #[test]
+    fn test_project_churn_respects_window_across_sessions() {
+        let sessions = vec![
+            (
+                PathBuf::from("/p/s1.jsonl"),
+                vec![edit("2025-01-01T00:00:00Z", "", "let a = 1;\nlet b = 2;")],
+            ),
+            (
+                PathBuf::from("/p/s2.jsonl"),
+                vec![edit("2025-01-01T10:00:00Z", "let a = 1;", "")],
+            ),
+            (
+                PathBuf::from("/p/s3.jsonl"),
+                vec![edit("2025-01-03T00:00:00Z", "let b = 2;", "")],
+            ),
+        ];
+
+        let report = project_churn("demo".to_string(), &sessions, 24);
+
+        assert_eq!(report.session_count, 3);
+        assert_eq!(report.stats.lines_added, 2);
+        assert_eq!(report.stats.lines_removed, 2);
+        // s3 removes its line more than 24 hours after it was added
+        assert_eq!(report.stats.churned_lines, 1);
+        assert!(report.sessions.iter().all(|s| s.stats.churned_lines == 0));
+    }
+}
@@ -0,0 +1,252 @@
+//! Side-by-side comparison of two sessions
+//!
+//! Unlike `get_session_comparison`, which ranks a session against its
+//! project, this compares an arbitrary pair: tokens, cost, duration and
+//! the models, tools and files each one used.
+
+use crate::commands::export::session_project_name;
+use crate::commands::retry_loops::{extract_tool_calls, total_tokens};
+use crate::commands::session::file_tool_calls;
+use crate::commands::stats::{read_raw_log_entries, ResponseUsageTracker};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::counting::count_rules;
+use crate::errors::AppError;
+use crate::models::{ClaudeMessage, ComparedSession, SessionPairComparison, ToolUsageStats};
+use crate::utils::display_path;
+use chrono::DateTime;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Model name Claude Code records for messages it generated itself
+const SYNTHETIC_MODEL: &str = "<synthetic>";
+
+fn compared_session(session_path: &Path) -> ComparedSession {
+    let entries = read_raw_log_entries(session_path);
+
+    // (calls, failed calls) per tool
+    let mut tools: BTreeMap<String, (u32, u32)> = BTreeMap::new();
+    for call in extract_tool_calls(&entries) {
+        let counts = tools.entry(call.name).or_default();
+        counts.0 += 1;
+        counts.1 += u32::from(call.failed);
+    }
+    let files_touched: BTreeSet<String> = entries
+        .iter()
+        .flat_map(file_tool_calls)
+        .map(|(_, _, path)| path.to_string())
+        .collect();
+
+    let mut session = ComparedSession {
+        session_id: session_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        project_name: session_project_name(session_path),
+        file_path: display_path(session_path),
+        message_count: 0,
+        input_tokens: 0,
+        output_tokens: 0,
+        cache_creation_tokens: 0,
+        cache_read_tokens: 0,
+        total_tokens: 0,
+        cost_usd: 0.0,
+        first_message_time: None,
+        last_message_time: None,
+        duration_seconds: 0,
+        models: Vec::new(),
+        tools: Vec::new(),
+        files_touched: files_touched.into_iter().collect(),
+    };
+
+    let rules = count_rules();
+    let mut tracker = ResponseUsageTracker::default();
+    let mut models: BTreeSet<String> = BTreeSet::new();
+    let mut first = None;
+    let mut last = None;
+    for mut entry in entries {
+        let counted = rules.counts_entry(&entry);
+        let cwd = entry.cwd.take();
+        let Ok(message) = ClaudeMessage::try_from(entry) else {
+            continue;
+        };
+        if counted {
+            session.message_count += 1;
+        }
+
+        let (usage, cost_usd) = tracker.usage_and_cost_of(&message, cwd.as_deref());
+        session.input_tokens += u64::from(usage.input_tokens.unwrap_or(0));
+        session.output_tokens += u64::from(usage.output_tokens.unwrap_or(0));
+        session.cache_creation_tokens += u64::from(usage.cache_creation_input_tokens.unwrap_or(0));
+        session.cache_read_tokens += u64::from(usage.cache_read_input_tokens.unwrap_or(0));
+        session.total_tokens += total_tokens(&usage);
+        session.cost_usd += cost_usd;
+
+        if let Some(model) = message
+            .model
+            .filter(|m| !m.is_empty() && m != SYNTHETIC_MODEL)
+        {
+            models.insert(model);
+        }
+        if let Ok(timestamp) = DateTime::parse_from_rfc3339(&message.timestamp) {
+            if first.as_ref().map_or(true, |(first, _)| timestamp < *first) {
+                first = Some((timestamp, message.timestamp.clone()));
+            }
+            if last.as_ref().map_or(true, |(last, _)| timestamp > *last) {
+                last = Some((timestamp, message.timestamp));
+            }
+        }
+    }
+
+    if let (Some((first_time, _)), Some((last_time, _))) = (&first, &last) {
+        session.duration_seconds = (*last_time - *first_time).num_seconds();
+    }
+    session.first_message_time = first.map(|(_, text)| text);
+    session.last_message_time = last.map(|(_, text)| text);
+    session.models = models.into_iter().collect();
+    session.tools = tools
+        .into_iter()
+        .map(|(tool_name, (calls, failed))| ToolUsageStats {
+            tool_name,
+            usage_count: calls,
+            success_rate: (calls - failed) as f32 / calls as f32 * 100.0,
+            avg_execution_time: None,
+        })
+        .collect();
+    session
+        .tools
+        .sort_by(|a, b| b.usage_count.cmp(&a.usage_count));
+    session
+}
+
+/// Items of both lists, of `a` only and of `b` only (each sorted)
+fn split_sets<'a>(
+    a: impl IntoIterator<Item = &'a String>,
+    b: impl IntoIterator<Item = &'a String>,
+) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let a: BTreeSet<&String> = a.into_iter().collect();
+    let b: BTreeSet<&String> = b.into_iter().collect();
+    let owned = |items: Vec<&&String>| items.into_iter().map(|s| (*s).clone()).collect();
+    (
+        owned(a.intersection(&b).collect()),
+        owned(a.difference(&b).collect()),
+        owned(b.difference(&a).collect()),
+    )
+}
+
+fn difference(a: u64, b: u64) -> i64 {
+    i64::try_from(b).unwrap_or(i64::MAX) - i64::try_from(a).unwrap_or(i64::MAX)
+}
+
+fn compare(a: ComparedSession, b: ComparedSession) -> SessionPairComparison {
+    let (shared_tools, tools_only_in_a, tools_only_in_b) = split_sets(
+        a.tools.iter().map(|t| &t.tool_name),
+        b.tools.iter().map(|t| &t.tool_name),
+    );
+    let (shared_files, files_only_in_a, files_only_in_b) =
+        split_sets(&a.files_touched, &b.files_touched);
+
+    SessionPairComparison {
+        token_difference: difference(a.total_tokens, b.total_tokens),
+        cost_difference_usd: b.cost_usd - a.cost_usd,
+        duration_difference_seconds: b.duration_seconds - a.duration_seconds,
+        message_difference: difference(a.message_count as u64, b.message_count as u64),
+        shared_tools,
+        tools_only_in_a,
+        tools_only_in_b,
+        shared_files,
+        files_only_in_a,
+        files_only_in_b,
+        a,
+        b,
+    }
+}
+
+/// Compare two sessions: token totals, cost, duration, models, tools used
+/// and files touched, with `b - a` differences
+///
+/// `a` and `b` are session file paths, from the same project or not.
+#[tauri::command]
+pub async fn compare_sessions(a: String, b: String) -> Result<SessionPairComparison, AppError> {
+    let _timer = OperationTimer::start("compare_sessions");
+
+    for path in [&a, &b] {
+        if !Path::new(path).is_file() {
+            return Err(AppError::not_found(format!(
+                "Session file not found: {path}"
+            )));
+        }
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let (a, b) = rayon::join(
+            || compared_session(Path::new(&a)),
+            || compared_session(Path::new(&b)),
+        );
+        compare(a, b)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}").into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{response_line, tool_use_block, write_jsonl};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_compare_sessions() {
+        let temp = TempDir::new().unwrap();
+        let tool_response = |id: &str, time: &str, tool: &str, file: &str, output_tokens: u32| {
+            let block = tool_use_block(&format!("toolu_{id}"), tool, json!({"file_path": file}));
+            response_line(id, time, json!([block]), 0, output_tokens)
+        };
+        let a = temp.path().join("a.jsonl");
+        write_jsonl(
+            &a,
+            &[
+                tool_response("m1", "2025-03-01T09:00:00Z", "Read", "/src/lib.rs", 100),
+                tool_response("m2", "2025-03-01T09:10:00Z", "Edit", "/src/main.rs", 100),
+            ],
+        );
+        let b = temp.path().join("b.jsonl");
+        write_jsonl(
+            &b,
+            &[
+                tool_response("m3", "2025-03-02T09:00:00Z", "Read", "/src/lib.rs", 1_000),
+                tool_response("m4", "2025-03-02T09:01:00Z", "Write", "/README.md", 500),
+            ],
+        );
+
+        let comparison = compare_sessions(
+            a.to_string_lossy().to_string(),
+            b.to_string_lossy().to_string(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(comparison.a.session_id, "a");
+        assert_eq!(comparison.a.output_tokens, 200);
+        assert_eq!(comparison.a.duration_seconds, 600);
+        assert_eq!(comparison.a.models, ["claude-sonnet-4-20250514"]);
+        assert_eq!(comparison.token_difference, 1_300);
+        assert_eq!(comparison.duration_difference_seconds, -540);
+        assert!(comparison.cost_difference_usd > 0.0);
+        assert_eq!(comparison.shared_tools, ["Read"]);
+        assert_eq!(comparison.tools_only_in_a, ["Edit"]);
+        assert_eq!(comparison.tools_only_in_b, ["Write"]);
+        assert_eq!(comparison.shared_files, ["/src/lib.rs"]);
+        assert_eq!(comparison.files_only_in_a, ["/src/main.rs"]);
+        assert_eq!(comparison.files_only_in_b, ["/README.md"]);
+    }
+
+    #[tokio::test]
+    async fn test_compare_sessions_missing_file() {
+        let result = compare_sessions(
+            "/nonexistent/a.jsonl".to_string(),
+            "/nonexistent/b.jsonl".to_string(),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::NotFound { .. })));
+    }
+}
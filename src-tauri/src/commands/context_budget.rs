@@ -0,0 +1,212 @@
+//! Context budget of a session
+//!
+//! The current context size is estimated from the last response of the main
+//! conversation: its prompt (input and cache tokens) plus its output, which
+//! the next request carries along. A compaction resets the estimate until the
+//! next response; a microcompaction only shows up once the next response
+//! reports the smaller prompt.
+
+use crate::commands::retry_loops::total_tokens;
+use crate::commands::stats::read_raw_log_entries;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{CompactionEvent, RawLogEntry, SessionBudget};
+use crate::utils::resolve_session_file;
+
+/// Context window of Claude models
+const DEFAULT_CONTEXT_LIMIT: u64 = 200_000;
+
+/// Context window of models running with the 1M context beta
+const EXTENDED_CONTEXT_LIMIT: u64 = 1_000_000;
+
+/// Context window of a model
+///
+/// Widened to 1M tokens when the model name asks for it ("[1m]") or when the
+/// session already went past the default window.
+pub(crate) fn context_limit(model: Option<&str>, peak_tokens: u64) -> u64 {
+    if model.is_some_and(|model| model.to_lowercase().contains("[1m]"))
+        || peak_tokens > DEFAULT_CONTEXT_LIMIT
+    {
+        EXTENDED_CONTEXT_LIMIT
+    } else {
+        DEFAULT_CONTEXT_LIMIT
+    }
+}
+
+fn compaction_event(entry: &RawLogEntry) -> Option<CompactionEvent> {
+    let (kind, metadata) = match entry.subtype.as_deref()? {
+        "compact_boundary" => ("compact", entry.compact_metadata.as_ref()),
+        "microcompact_boundary" => ("microcompact", entry.microcompact_metadata.as_ref()),
+        _ => return None,
+    };
+    Some(CompactionEvent {
+        uuid: entry.uuid.clone(),
+        timestamp: entry.timestamp.clone(),
+        kind: kind.to_string(),
+        trigger: metadata
+            .and_then(|m| m.get("trigger"))
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string),
+        pre_tokens: metadata
+            .and_then(|m| m.get("preTokens"))
+            .and_then(serde_json::Value::as_u64),
+    })
+}
+
+/// Budget of a session from its log entries, in file order
+pub(crate) fn session_budget(session_id: String, entries: &[RawLogEntry]) -> SessionBudget {
+    let mut compactions: Vec<CompactionEvent> = Vec::new();
+    let mut context_tokens = 0;
+    let mut peak_tokens = 0;
+    let mut since_compaction = false;
+    let mut model = None;
+    let mut last_updated = None;
+
+    for entry in entries.iter().filter(|e| e.is_sidechain != Some(true)) {
+        if let Some(event) = compaction_event(entry) {
+            if event.kind == "compact" {
+                context_tokens = 0;
+                since_compaction = true;
+            }
+            compactions.push(event);
+            continue;
+        }
+        if entry.message_type != "assistant" {
+            continue;
+        }
+        let Some(message) = &entry.message else {
+            continue;
+        };
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+        // Error responses are written under a "<synthetic>" model with zero usage
+        if message.model.as_deref() == Some("<synthetic>") {
+            continue;
+        }
+        context_tokens = total_tokens(usage);
+        peak_tokens = peak_tokens.max(context_tokens);
+        since_compaction = false;
+        model.clone_from(&message.model);
+        last_updated.clone_from(&entry.timestamp);
+    }
+
+    let context_limit = context_limit(model.as_deref(), peak_tokens);
+    let percent_used = (context_tokens as f64 / context_limit as f64 * 100.0).min(100.0);
+    let last_auto_compact_tokens = compactions
+        .iter()
+        .rev()
+        .find(|c| c.kind == "compact" && c.trigger.as_deref() == Some("auto"))
+        .and_then(|c| c.pre_tokens);
+
+    SessionBudget {
+        session_id,
+        model,
+        context_tokens,
+        context_limit,
+        percent_used,
+        remaining_tokens: context_limit.saturating_sub(context_tokens),
+        since_compaction,
+        last_auto_compact_tokens,
+        compactions,
+        last_updated,
+    }
+}
+
+/// Current context size, context window and compactions of a session
+#[tauri::command]
+pub async fn get_session_budget(
+    session_id: String,
+    project_path: String,
+) -> Result<SessionBudget, AppError> {
+    let _timer = OperationTimer::start("get_session_budget");
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+
+    let entries = tauri::async_runtime::spawn_blocking(move || read_raw_log_entries(&session_path))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?;
+    Ok(session_budget(session_id, &entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::raw_entry;
+    use serde_json::json;
+
+    fn response(n: u32, model: &str, input: u32, cache_read: u32, sidechain: bool) -> RawLogEntry {
+        raw_entry(json!({
+            "uuid": format!("a{n}"),
+            "timestamp": format!("2025-01-01T00:00:0{n}Z"),
+            "type": "assistant",
+            "isSidechain": sidechain,
+            "message": {
+                "id": format!("msg_{n}"),
+                "role": "assistant",
+                "model": model,
+                "content": [],
+                "usage": {"input_tokens": input, "output_tokens": 1_000, "cache_read_input_tokens": cache_read}
+            }
+        }))
+    }
+
+    fn compact(n: u32, subtype: &str, trigger: &str, pre_tokens: u64) -> RawLogEntry {
+        let metadata_key = if subtype == "compact_boundary" {
+            "compactMetadata"
+        } else {
+            "microcompactMetadata"
+        };
+        raw_entry(json!({
+            "uuid": format!("c{n}"),
+            "timestamp": format!("2025-01-01T00:00:0{n}Z"),
+            "type": "system",
+            "subtype": subtype,
+            metadata_key: {"trigger": trigger, "preTokens": pre_tokens}
+        }))
+    }
+
+    #[test]
+    fn test_budget_tracks_last_main_response() {
+        let entries = vec![
+            response(1, "claude-sonnet-4-5", 1_000, 20_000, false),
+            response(2, "claude-sonnet-4-5", 2_000, 47_000, false),
+            response(3, "claude-haiku-4-5", 100_000, 0, true),
+        ];
+        let budget = session_budget("s1".to_string(), &entries);
+        assert_eq!(budget.context_tokens, 50_000);
+        assert_eq!(budget.context_limit, DEFAULT_CONTEXT_LIMIT);
+        assert_eq!(budget.remaining_tokens, 150_000);
+        assert!((budget.percent_used - 25.0).abs() < 1e-9);
+        assert_eq!(budget.model.as_deref(), Some("claude-sonnet-4-5"));
+        assert_eq!(budget.last_updated.as_deref(), Some("2025-01-01T00:00:02Z"));
+        assert!(!budget.since_compaction);
+    }
+
+    #[test]
+    fn test_budget_resets_on_compaction() {
+        let entries = vec![
+            response(1, "claude-opus-4-1", 1_000, 150_000, false),
+            compact(2, "microcompact_boundary", "auto", 151_000),
+            compact(3, "compact_boundary", "auto", 151_000),
+        ];
+        let budget = session_budget("s1".to_string(), &entries);
+        assert_eq!(budget.context_tokens, 0);
+        assert!(budget.since_compaction);
+        assert_eq!(budget.compactions.len(), 2);
+        assert_eq!(budget.compactions[0].kind, "microcompact");
+        assert_eq!(budget.last_auto_compact_tokens, Some(151_000));
+
+        let mut entries = entries;
+        entries.push(response(4, "claude-opus-4-1", 500, 9_500, false));
+        let budget = session_budget("s1".to_string(), &entries);
+        assert_eq!(budget.context_tokens, 11_000);
+        assert!(!budget.since_compaction);
+    }
+
+    #[test]
+    fn test_context_limit_widens_for_long_context() {
+        assert_eq!(context_limit(Some("claude-sonnet-4-5"), 150_000), 200_000);
+        assert_eq!(context_limit(Some("claude-sonnet-4-5[1m]"), 0), 1_000_000);
+        assert_eq!(context_limit(Some("claude-sonnet-4-5"), 250_000), 1_000_000);
+    }
+}
@@ -0,0 +1,368 @@
+//! Cost rollups by calendar month, ISO week and billing cycle
+//!
+//! Complements the daily token stats with the periods invoices are based
+//! on. A billing cycle starts on a configurable day of the month, or on the
+//! last day of months too short for it. Dates are UTC, like `DailyStats`.
+
+use crate::commands::retry_loops::total_tokens;
+use crate::commands::stats::{
+    read_raw_log_entries, resolve_scope_session_files, ResponseUsageTracker,
+};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ClaudeMessage, CostPeriod, CostReport, ProjectCost};
+use crate::utils::{extract_project_name, file_name_string};
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc, Weekday};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+/// One priced response
+pub(crate) struct ResponseCost {
+    pub date: NaiveDate,
+    pub cost_usd: f64,
+    pub tokens: u64,
+}
+
+/// Priced responses of one session file
+pub(crate) struct SessionCosts {
+    pub project_name: String,
+    pub responses: Vec<ResponseCost>,
+}
+
+pub(crate) fn session_costs(session_path: &Path) -> SessionCosts {
+    let project_name = session_path
+        .parent()
+        .and_then(file_name_string)
+        .map(|name| extract_project_name(&name))
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    let mut tracker = ResponseUsageTracker::default();
+    let responses = read_raw_log_entries(session_path)
+        .into_iter()
+        .filter_map(|mut entry| {
+            let cwd = entry.cwd.take();
+            let message = ClaudeMessage::try_from(entry).ok()?;
+            let date = DateTime::parse_from_rfc3339(&message.timestamp)
+                .ok()?
+                .with_timezone(&Utc)
+                .date_naive();
+            let (usage, cost_usd) = tracker.usage_and_cost_of(&message, cwd.as_deref());
+            let tokens = total_tokens(&usage);
+            (cost_usd > 0.0 || tokens > 0).then_some(ResponseCost {
+                date,
+                cost_usd,
+                tokens,
+            })
+        })
+        .collect();
+
+    SessionCosts {
+        project_name,
+        responses,
+    }
+}
+
+fn next_month(year: i32, month: u32) -> (i32, u32) {
+    if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    }
+}
+
+fn previous_month(year: i32, month: u32) -> (i32, u32) {
+    if month == 1 {
+        (year - 1, 12)
+    } else {
+        (year, month - 1)
+    }
+}
+
+pub(crate) fn last_day_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+    let (year, month) = next_month(year, month);
+    NaiveDate::from_ymd_opt(year, month, 1)?.pred_opt()
+}
+
+/// First day of the billing cycle that starts in `year`/`month`
+fn cycle_start(year: i32, month: u32, anchor_day: u32) -> Option<NaiveDate> {
+    let last_day = last_day_of_month(year, month)?.day();
+    NaiveDate::from_ymd_opt(year, month, anchor_day.min(last_day))
+}
+
+#[derive(Debug, Clone, Copy)]
+enum PeriodKind {
+    Month,
+    IsoWeek,
+    Billing { anchor_day: u32 },
+}
+
+impl PeriodKind {
+    /// Label, first and last day of the period containing `date`
+    fn period_of(self, date: NaiveDate) -> Option<(String, NaiveDate, NaiveDate)> {
+        match self {
+            Self::Month => {
+                let start = date.with_day(1)?;
+                let end = last_day_of_month(date.year(), date.month())?;
+                Some((start.format("%Y-%m").to_string(), start, end))
+            }
+            Self::IsoWeek => {
+                let week = date.iso_week();
+                let start = NaiveDate::from_isoywd_opt(week.year(), week.week(), Weekday::Mon)?;
+                let end = start.checked_add_days(Days::new(6))?;
+                Some((format!("{}-W{:02}", week.year(), week.week()), start, end))
+            }
+            Self::Billing { anchor_day } => {
+                let this_month = cycle_start(date.year(), date.month(), anchor_day)?;
+                let start = if date >= this_month {
+                    this_month
+                } else {
+                    let (year, month) = previous_month(date.year(), date.month());
+                    cycle_start(year, month, anchor_day)?
+                };
+                let (year, month) = next_month(start.year(), start.month());
+                let end = cycle_start(year, month, anchor_day)?.pred_opt()?;
+                Some((start.format("%Y-%m-%d").to_string(), start, end))
+            }
+        }
+    }
+}
+
+/// Running totals of one period
+struct PeriodTotals<'a> {
+    period: String,
+    end: NaiveDate,
+    cost_usd: f64,
+    total_tokens: u64,
+    response_count: u64,
+    sessions: HashSet<usize>,
+    projects: HashMap<&'a str, (f64, u64)>, // (cost, tokens)
+}
+
+/// Sum the responses of `sessions` into periods of `kind`, oldest first
+fn roll_up(sessions: &[SessionCosts], kind: PeriodKind) -> Vec<CostPeriod> {
+    let mut periods: BTreeMap<NaiveDate, PeriodTotals> = BTreeMap::new();
+
+    for (index, session) in sessions.iter().enumerate() {
+        for response in &session.responses {
+            let Some((period, start, end)) = kind.period_of(response.date) else {
+                continue;
+            };
+            let totals = periods.entry(start).or_insert_with(|| PeriodTotals {
+                period,
+                end,
+                cost_usd: 0.0,
+                total_tokens: 0,
+                response_count: 0,
+                sessions: HashSet::new(),
+                projects: HashMap::new(),
+            });
+            totals.cost_usd += response.cost_usd;
+            totals.total_tokens += response.tokens;
+            totals.response_count += 1;
+            totals.sessions.insert(index);
+            let project = totals
+                .projects
+                .entry(session.project_name.as_str())
+                .or_default();
+            project.0 += response.cost_usd;
+            project.1 += response.tokens;
+        }
+    }
+
+    periods
+        .into_iter()
+        .map(|(start, totals)| {
+            let mut projects: Vec<ProjectCost> = totals
+                .projects
+                .into_iter()
+                .map(|(project_name, (cost_usd, total_tokens))| ProjectCost {
+                    project_name: project_name.to_string(),
+                    cost_usd,
+                    total_tokens,
+                })
+                .collect();
+            projects.sort_by(|a, b| {
+                b.cost_usd
+                    .total_cmp(&a.cost_usd)
+                    .then_with(|| a.project_name.cmp(&b.project_name))
+            });
+
+            CostPeriod {
+                period: totals.period,
+                start_date: start.format("%Y-%m-%d").to_string(),
+                end_date: totals.end.format("%Y-%m-%d").to_string(),
+                cost_usd: totals.cost_usd,
+                total_tokens: totals.total_tokens,
+                response_count: totals.response_count,
+                session_count: totals.sessions.len(),
+                projects,
+            }
+        })
+        .collect()
+}
+
+/// Cost by calendar month, ISO week and billing cycle
+///
+/// `scope` is "session", "project" or "global" with `path` pointing at the
+/// session file, project folder or Claude folder. Billing cycles start on
+/// `billing_anchor_day` (1-31, default 1). Recorded costs are used where the
+/// log has them, estimates otherwise.
+#[tauri::command]
+pub async fn get_cost_report(
+    scope: String,
+    path: String,
+    billing_anchor_day: Option<u32>,
+) -> Result<CostReport, AppError> {
+    let _timer = OperationTimer::start("get_cost_report");
+
+    let anchor_day = billing_anchor_day.unwrap_or(1);
+    if !(1..=31).contains(&anchor_day) {
+        return Err(AppError::invalid_input(format!(
+            "Invalid billing anchor day: {anchor_day}"
+        )));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let session_files = resolve_scope_session_files(&scope, &path)?;
+        let sessions: Vec<SessionCosts> = session_files
+            .par_iter()
+            .map(|path| session_costs(path))
+            .collect();
+
+        let total_cost_usd = sessions
+            .iter()
+            .flat_map(|session| &session.responses)
+            .map(|response| response.cost_usd)
+            .sum();
+
+        Ok(CostReport {
+            scope,
+            billing_anchor_day: anchor_day,
+            total_cost_usd,
+            months: roll_up(&sessions, PeriodKind::Month),
+            weeks: roll_up(&sessions, PeriodKind::IsoWeek),
+            billing_periods: roll_up(&sessions, PeriodKind::Billing { anchor_day }),
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{response_line, write_jsonl};
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn period(kind: PeriodKind, day: NaiveDate) -> (String, NaiveDate, NaiveDate) {
+        kind.period_of(day).unwrap()
+    }
+
+    #[test]
+    fn test_month_and_iso_week_periods() {
+        assert_eq!(
+            period(PeriodKind::Month, date(2024, 2, 10)),
+            ("2024-02".to_string(), date(2024, 2, 1), date(2024, 2, 29))
+        );
+        // 2025-12-31 is in the first ISO week of 2026
+        assert_eq!(
+            period(PeriodKind::IsoWeek, date(2025, 12, 31)),
+            ("2026-W01".to_string(), date(2025, 12, 29), date(2026, 1, 4))
+        );
+    }
+
+    #[test]
+    fn test_billing_periods_follow_anchor_day() {
+        let mid_month = PeriodKind::Billing { anchor_day: 15 };
+        assert_eq!(
+            period(mid_month, date(2025, 3, 14)),
+            (
+                "2025-02-15".to_string(),
+                date(2025, 2, 15),
+                date(2025, 3, 14)
+            )
+        );
+        assert_eq!(
+            period(mid_month, date(2025, 12, 20)),
+            (
+                "2025-12-15".to_string(),
+                date(2025, 12, 15),
+                date(2026, 1, 14)
+            )
+        );
+
+        // Short months start the cycle on their last day
+        let month_end = PeriodKind::Billing { anchor_day: 31 };
+        assert_eq!(
+            period(month_end, date(2025, 3, 5)),
+            (
+                "2025-02-28".to_string(),
+                date(2025, 2, 28),
+                date(2025, 3, 30)
+            )
+        );
+        assert_eq!(
+            period(month_end, date(2025, 3, 31)),
+            (
+                "2025-03-31".to_string(),
+                date(2025, 3, 31),
+                date(2025, 4, 29)
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cost_report_rolls_up_project() {
+        let temp = TempDir::new().unwrap();
+        let project_dir = temp.path().join("-Users-me-demo");
+        fs::create_dir_all(&project_dir).unwrap();
+        let lines = [
+            response_line("msg_1", "2025-01-20T10:00:00Z", json!("ok"), 0, 1_000_000),
+            // Second part of the same streamed response
+            response_line("msg_1", "2025-01-20T10:00:01Z", json!("ok"), 0, 1_000_000),
+            response_line("msg_2", "2025-02-03T10:00:00Z", json!("ok"), 0, 100_000),
+        ];
+        write_jsonl(&project_dir.join("s1.jsonl"), &lines);
+
+        let report = get_cost_report(
+            "project".to_string(),
+            project_dir.to_string_lossy().to_string(),
+            Some(25),
+        )
+        .await
+        .unwrap();
+
+        assert!((report.total_cost_usd - 16.5).abs() < 1e-9);
+        assert_eq!(
+            report
+                .months
+                .iter()
+                .map(|p| p.period.as_str())
+                .collect::<Vec<_>>(),
+            ["2025-01", "2025-02"]
+        );
+        assert!((report.months[0].cost_usd - 15.0).abs() < 1e-9);
+        assert_eq!(report.months[0].response_count, 1);
+        assert_eq!(report.months[0].projects[0].project_name, "demo");
+        assert_eq!(report.weeks.len(), 2);
+        // Jan 20 falls in the cycle starting Dec 25, Feb 3 in the one starting Jan 25
+        assert_eq!(report.billing_periods.len(), 2);
+        assert_eq!(report.billing_periods[0].start_date, "2024-12-25");
+        assert_eq!(report.billing_periods[1].end_date, "2025-02-24");
+
+        assert!(get_cost_report(
+            "project".to_string(),
+            project_dir.to_string_lossy().to_string(),
+            Some(0)
+        )
+        .await
+        .is_err());
+    }
+}
@@ -0,0 +1,812 @@
+//! Weekly digest
+//!
+//! Sums up one week (Monday to Sunday, UTC) across all projects: spend
+//! against the week before, top sessions, files changed, notable errors and
+//! the activity streak, as Markdown or HTML ready to email or post in a team
+//! channel. With the `weeklyDigest` setting enabled, a background task writes
+//! the digest of each finished week to a folder; the same digest can be
+//! printed headlessly with `--weekly-digest`.
+
+use crate::commands::changelog::{plural, relative_path};
+use crate::commands::churn::{file_edits, replay};
+use crate::commands::error_report::collect_errors;
+use crate::commands::export::escape_html;
+use crate::commands::metadata::{apply_saved_settings, get_metadata_folder};
+use crate::commands::prompt_quality::prompt_text;
+use crate::commands::retry_loops::{extract_tool_calls, truncate_chars, ToolCall};
+use crate::commands::session::{is_genuine_user_text, write_file_atomic};
+use crate::commands::stats::{
+    read_raw_log_entries, resolve_scope_session_files, ResponseUsageTracker,
+};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ClaudeMessage, DigestSettings, RawLogEntry, WeeklyDigest};
+use crate::utils::{extract_project_name, file_name_string};
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc};
+use rayon::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// How often the scheduler checks whether a digest is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Maximum number of projects, sessions and files listed
+const MAX_PROJECTS: usize = 5;
+const MAX_TOP_SESSIONS: usize = 5;
+const MAX_FILES: usize = 10;
+
+/// Maximum characters of a session title
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Characters of the session ID shown next to a session
+const SHORT_SESSION_ID_CHARS: usize = 8;
+
+static DIGEST_SETTINGS: RwLock<Option<DigestSettings>> = RwLock::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DigestFormat {
+    Markdown,
+    Html,
+}
+
+impl DigestFormat {
+    fn parse(format: &str) -> Result<Self, String> {
+        match format {
+            "markdown" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            other => Err(format!("Unknown digest format: {other}")),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Markdown => "markdown",
+            Self::Html => "html",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+        }
+    }
+}
+
+/// Monday and Sunday of the week `range` designates: `last_week` (default),
+/// `this_week` or a `YYYY-MM-DD` date within the week
+fn week_bounds(range: Option<&str>, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), String> {
+    let day = match range.unwrap_or("last_week") {
+        "last_week" => today - Days::new(7),
+        "this_week" => today,
+        date => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|e| format!("Invalid digest range {date}: {e}"))?,
+    };
+    let start = day - Days::new(u64::from(day.weekday().num_days_from_monday()));
+    Ok((start, start + Days::new(6)))
+}
+
+/// What one session contributed to the digest
+#[derive(Default)]
+struct SessionDigest {
+    session_id: String,
+    project_name: String,
+    cwd: Option<String>,
+    title: Option<String>,
+    active_days: BTreeSet<NaiveDate>, // Whole history, for streaks
+    message_count: usize,             // Within the week, like the fields below
+    cost_usd: f64,
+    previous_cost_usd: f64, // The week before
+    calls: Vec<ToolCall>,
+    errors: Vec<(&'static str, String)>, // (category, message)
+}
+
+fn entry_date(entry: &RawLogEntry) -> Option<NaiveDate> {
+    DateTime::parse_from_rfc3339(entry.timestamp.as_deref()?)
+        .ok()
+        .map(|time| time.with_timezone(&Utc).date_naive())
+}
+
+/// First line of the first genuine prompt of the main conversation
+fn session_title(entries: &[RawLogEntry]) -> Option<String> {
+    entries
+        .iter()
+        .filter(|entry| {
+            entry.message_type == "user"
+                && entry.is_sidechain != Some(true)
+                && entry.is_meta != Some(true)
+        })
+        .filter_map(|entry| prompt_text(&entry.message.as_ref()?.content))
+        .filter(|text| is_genuine_user_text(text))
+        .find_map(|text| {
+            let line = text.lines().find(|line| !line.trim().is_empty())?;
+            Some(truncate_chars(line.trim(), MAX_TITLE_CHARS))
+        })
+}
+
+fn session_digest(entries: Vec<RawLogEntry>, start: NaiveDate, end: NaiveDate) -> SessionDigest {
+    let previous_start = start - Days::new(7);
+    let mut digest = SessionDigest {
+        cwd: entries.iter().find_map(|entry| entry.cwd.clone()),
+        ..SessionDigest::default()
+    };
+    let mut week_entries = Vec::new();
+    let mut tracker = ResponseUsageTracker::default();
+    for mut entry in entries {
+        let Some(date) = entry_date(&entry) else {
+            continue;
+        };
+        let in_week = (start..=end).contains(&date);
+        if matches!(entry.message_type.as_str(), "user" | "assistant")
+            && entry.is_sidechain != Some(true)
+        {
+            digest.active_days.insert(date);
+            digest.message_count += usize::from(in_week);
+        }
+        if date < previous_start || date > end {
+            continue;
+        }
+        if in_week {
+            week_entries.push(entry.clone());
+        }
+        let cwd = entry.cwd.take();
+        let Ok(message) = ClaudeMessage::try_from(entry) else {
+            continue;
+        };
+        let (_, cost_usd) = tracker.usage_and_cost_of(&message, cwd.as_deref());
+        if in_week {
+            digest.cost_usd += cost_usd;
+        } else {
+            digest.previous_cost_usd += cost_usd;
+        }
+    }
+
+    digest.title = session_title(&week_entries);
+    digest.calls = extract_tool_calls(&week_entries);
+    digest.errors = collect_errors(&week_entries)
+        .errors
+        .into_iter()
+        .map(|error| (error.category, error.message))
+        .collect();
+    digest
+}
+
+fn read_session_digest(session_path: &Path, start: NaiveDate, end: NaiveDate) -> SessionDigest {
+    SessionDigest {
+        session_id: session_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        project_name: session_path
+            .parent()
+            .and_then(file_name_string)
+            .map(|name| extract_project_name(&name))
+            .unwrap_or_else(|| "Unknown".to_string()),
+        ..session_digest(read_raw_log_entries(session_path), start, end)
+    }
+}
+
+/// (current, longest) streak of consecutive active days up to `until`
+///
+/// The current streak is still running if its last day is `until` or the
+/// day before.
+fn streaks(days: &BTreeSet<NaiveDate>, until: NaiveDate) -> (u32, u32) {
+    let days: Vec<NaiveDate> = days.range(..=until).copied().collect();
+    let streaks: Vec<&[NaiveDate]> = days.chunk_by(|a, b| (*b - *a).num_days() == 1).collect();
+    let longest = streaks.iter().map(|streak| streak.len()).max().unwrap_or(0);
+    let current = streaks
+        .last()
+        .filter(|streak| (until - streak[streak.len() - 1]).num_days() <= 1)
+        .map_or(0, |streak| streak.len());
+    (current as u32, longest as u32)
+}
+
+fn error_label(category: &str) -> &str {
+    match category {
+        "tool_failure" => "tool failure",
+        "api_error" => "API error",
+        "rate_limit" => "rate limit",
+        "hook_failure" => "hook failure",
+        "system_error" => "system error",
+        other => other,
+    }
+}
+
+fn format_cost(cost_usd: f64) -> String {
+    format!("${cost_usd:.2}")
+}
+
+/// A section of the digest, rendered as Markdown or HTML
+struct Section {
+    title: &'static str,
+    summary: Option<String>,
+    items: Vec<String>,
+}
+
+/// The digest before rendering
+struct Digest {
+    start: NaiveDate,
+    end: NaiveDate,
+    summary: String,
+    sections: Vec<Section>,
+    session_count: usize,
+    project_count: usize,
+    cost_usd: f64,
+    files_changed: usize,
+    error_count: usize,
+    current_streak_days: u32,
+}
+
+fn spend_section(sessions: &[&SessionDigest], cost_usd: f64, previous_cost_usd: f64) -> Section {
+    let mut projects: HashMap<&str, (f64, usize)> = HashMap::new();
+    for session in sessions {
+        let project = projects.entry(&session.project_name).or_default();
+        project.0 += session.cost_usd;
+        project.1 += 1;
+    }
+    let mut projects: Vec<(&str, (f64, usize))> = projects.into_iter().collect();
+    projects.sort_by(|a, b| b.1 .0.total_cmp(&a.1 .0).then_with(|| a.0.cmp(b.0)));
+
+    let change = if previous_cost_usd > 0.0 {
+        let percent = (cost_usd - previous_cost_usd) / previous_cost_usd * 100.0;
+        format!("{percent:+.0}% vs the previous week")
+    } else {
+        "nothing spent the previous week".to_string()
+    };
+    Section {
+        title: "Spend",
+        summary: Some(format!("{} ({change})", format_cost(cost_usd))),
+        items: projects
+            .iter()
+            .take(MAX_PROJECTS)
+            .map(|(name, (cost, count))| {
+                format!(
+                    "{name}: {} in {}",
+                    format_cost(*cost),
+                    plural(*count, "session")
+                )
+            })
+            .collect(),
+    }
+}
+
+fn top_sessions_section(sessions: &[&SessionDigest]) -> Section {
+    let mut top: Vec<&&SessionDigest> = sessions.iter().collect();
+    top.sort_by(|a, b| {
+        b.cost_usd
+            .total_cmp(&a.cost_usd)
+            .then_with(|| b.message_count.cmp(&a.message_count))
+            .then_with(|| a.session_id.cmp(&b.session_id))
+    });
+    Section {
+        title: "Top sessions",
+        summary: None,
+        items: top
+            .iter()
+            .take(MAX_TOP_SESSIONS)
+            .map(|session| {
+                let short_id: String = session
+                    .session_id
+                    .chars()
+                    .take(SHORT_SESSION_ID_CHARS)
+                    .collect();
+                format!(
+                    "{} — {} · {} · {} (session {short_id})",
+                    session.title.as_deref().unwrap_or("Untitled session"),
+                    session.project_name,
+                    format_cost(session.cost_usd),
+                    plural(session.message_count, "message"),
+                )
+            })
+            .collect(),
+    }
+}
+
+fn files_section(sessions: &[&SessionDigest]) -> (Section, usize) {
+    let mut edits: Vec<_> = sessions
+        .iter()
+        .flat_map(|session| {
+            file_edits(&session.calls)
+                .into_iter()
+                .map(move |edit| (session.cwd.as_deref(), edit))
+        })
+        .collect();
+    edits.sort_by_key(|(_, edit)| edit.timestamp);
+    let cwds: HashMap<&str, Option<&str>> = edits
+        .iter()
+        .map(|(cwd, edit)| (edit.file_path, *cwd))
+        .collect();
+
+    let (total, mut files) = replay(edits.iter().map(|(_, edit)| edit), None);
+    files.sort_by(|a, b| {
+        (b.stats.lines_added + b.stats.lines_removed)
+            .cmp(&(a.stats.lines_added + a.stats.lines_removed))
+            .then_with(|| a.file_path.cmp(&b.file_path))
+    });
+    let section = Section {
+        title: "Files changed",
+        summary: Some(format!(
+            "{} · +{} / -{} lines",
+            plural(files.len(), "file"),
+            total.lines_added,
+            total.lines_removed
+        )),
+        items: files
+            .iter()
+            .take(MAX_FILES)
+            .map(|file| {
+                let cwd = cwds.get(file.file_path.as_str()).copied().flatten();
+                format!(
+                    "{} +{} / -{}",
+                    relative_path(&file.file_path, cwd),
+                    file.stats.lines_added,
+                    file.stats.lines_removed
+                )
+            })
+            .collect(),
+    };
+    (section, files.len())
+}
+
+fn errors_section(sessions: &[&SessionDigest]) -> (Section, usize) {
+    // category -> message -> count
+    let mut categories: HashMap<&str, HashMap<&str, usize>> = HashMap::new();
+    for (category, message) in sessions.iter().flat_map(|session| &session.errors) {
+        *categories
+            .entry(category)
+            .or_default()
+            .entry(message)
+            .or_default() += 1;
+    }
+    let mut categories: Vec<(&str, usize, (&str, usize))> = categories
+        .into_iter()
+        .map(|(category, messages)| {
+            let count = messages.values().sum();
+            let most_frequent = messages
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+                .unwrap_or_default();
+            (category, count, most_frequent)
+        })
+        .collect();
+    categories.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let error_count = categories.iter().map(|(_, count, _)| count).sum();
+    let section = Section {
+        title: "Notable errors",
+        summary: Some(plural(error_count, "error")),
+        items: categories
+            .iter()
+            .map(|(category, count, (message, times))| {
+                format!(
+                    "{}, most often ({times}×): {message}",
+                    plural(*count, error_label(category))
+                )
+            })
+            .collect(),
+    };
+    (section, error_count)
+}
+
+fn build_digest(
+    sessions: &[SessionDigest],
+    start: NaiveDate,
+    end: NaiveDate,
+    today: NaiveDate,
+) -> Digest {
+    let week: Vec<&SessionDigest> = sessions
+        .iter()
+        .filter(|session| session.message_count > 0)
+        .collect();
+    let project_count = week
+        .iter()
+        .map(|session| session.project_name.as_str())
+        .collect::<BTreeSet<_>>()
+        .len();
+    let cost_usd: f64 = week.iter().map(|session| session.cost_usd).sum();
+    let previous_cost_usd: f64 = sessions.iter().map(|s| s.previous_cost_usd).sum();
+
+    let active_days: BTreeSet<NaiveDate> = sessions
+        .iter()
+        .flat_map(|session| session.active_days.iter().copied())
+        .collect();
+    let (current_streak_days, longest_streak_days) = streaks(&active_days, end.min(today));
+    let days_this_week = active_days.range(start..=end).count();
+
+    let (files, files_changed) = files_section(&week);
+    let (errors, error_count) = errors_section(&week);
+    let mut sections = vec![
+        Section {
+            title: "Streak",
+            summary: Some(format!(
+                "Active {} of 7 days · current streak {} · longest {}",
+                days_this_week,
+                plural(current_streak_days as usize, "day"),
+                plural(longest_streak_days as usize, "day"),
+            )),
+            items: Vec::new(),
+        },
+        spend_section(&week, cost_usd, previous_cost_usd),
+        top_sessions_section(&week),
+        files,
+        errors,
+    ];
+    if week.is_empty() {
+        sections.truncate(1);
+    }
+
+    Digest {
+        start,
+        end,
+        summary: if week.is_empty() {
+            "No sessions this week.".to_string()
+        } else {
+            format!(
+                "{} in {} · {}",
+                plural(week.len(), "session"),
+                plural(project_count, "project"),
+                plural(week.iter().map(|s| s.message_count).sum(), "message"),
+            )
+        },
+        sections,
+        session_count: week.len(),
+        project_count,
+        cost_usd,
+        files_changed,
+        error_count,
+        current_streak_days,
+    }
+}
+
+fn digest_title(digest: &Digest) -> String {
+    format!("Weekly digest — {} to {}", digest.start, digest.end)
+}
+
+fn render_markdown(digest: &Digest) -> String {
+    let mut out = format!("# {}\n\n{}\n", digest_title(digest), digest.summary);
+    for section in &digest.sections {
+        let _ = writeln!(out, "\n## {}\n", section.title);
+        if let Some(summary) = &section.summary {
+            let _ = writeln!(out, "{summary}");
+            if !section.items.is_empty() {
+                out.push('\n');
+            }
+        }
+        for item in &section.items {
+            let _ = writeln!(out, "- {item}");
+        }
+    }
+    out
+}
+
+fn render_html(digest: &Digest) -> String {
+    let title = escape_html(&digest_title(digest));
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n<body>\n<h1>{title}</h1>\n<p>{}</p>\n",
+        escape_html(&digest.summary)
+    );
+    for section in &digest.sections {
+        let _ = writeln!(out, "<h2>{}</h2>", escape_html(section.title));
+        if let Some(summary) = &section.summary {
+            let _ = writeln!(out, "<p>{}</p>", escape_html(summary));
+        }
+        if !section.items.is_empty() {
+            out.push_str("<ul>\n");
+            for item in &section.items {
+                let _ = writeln!(out, "<li>{}</li>", escape_html(item));
+            }
+            out.push_str("</ul>\n");
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Digest of the week `range` designates, over all projects of `claude_path`
+fn weekly_digest(
+    claude_path: &Path,
+    range: Option<&str>,
+    format: Option<&str>,
+    today: NaiveDate,
+) -> Result<WeeklyDigest, String> {
+    let format = DigestFormat::parse(format.unwrap_or("markdown"))?;
+    let (start, end) = week_bounds(range, today)?;
+    let session_files = resolve_scope_session_files("global", &claude_path.to_string_lossy())?;
+    let sessions: Vec<SessionDigest> = session_files
+        .par_iter()
+        .map(|path| read_session_digest(path, start, end))
+        .collect();
+
+    let digest = build_digest(&sessions, start, end, today);
+    let content = match format {
+        DigestFormat::Markdown => render_markdown(&digest),
+        DigestFormat::Html => render_html(&digest),
+    };
+    Ok(WeeklyDigest {
+        start_date: start.to_string(),
+        end_date: end.to_string(),
+        format: format.name().to_string(),
+        content,
+        session_count: digest.session_count,
+        project_count: digest.project_count,
+        total_cost_usd: digest.cost_usd,
+        files_changed: digest.files_changed,
+        error_count: digest.error_count,
+        current_streak_days: digest.current_streak_days,
+    })
+}
+
+/// Check a `weeklyDigest` setting
+pub fn validate_digest_settings(settings: Option<&DigestSettings>) -> Result<(), String> {
+    if let Some(settings) = settings {
+        DigestFormat::parse(&settings.format)?;
+    }
+    Ok(())
+}
+
+/// Apply the `weeklyDigest` setting
+pub fn set_digest_settings(settings: Option<DigestSettings>) {
+    if let Ok(mut current) = DIGEST_SETTINGS.write() {
+        *current = settings;
+    }
+}
+
+fn digest_settings() -> Option<DigestSettings> {
+    DIGEST_SETTINGS
+        .read()
+        .ok()
+        .and_then(|settings| settings.clone())
+}
+
+fn default_claude_folder() -> Result<PathBuf, String> {
+    Ok(dirs::home_dir()
+        .ok_or("Could not find home directory")?
+        .join(".claude"))
+}
+
+/// Digest of last week into the digest folder, unless already written
+fn write_due_digest(
+    settings: &DigestSettings,
+    today: NaiveDate,
+) -> Result<Option<PathBuf>, String> {
+    if !settings.enabled {
+        return Ok(None);
+    }
+    let format = DigestFormat::parse(&settings.format)?;
+    let (start, _) = week_bounds(None, today)?;
+    let directory = match &settings.directory {
+        Some(directory) => PathBuf::from(directory),
+        None => get_metadata_folder()?.join("digests"),
+    };
+    let path = directory.join(format!(
+        "weekly-digest-{}.{}",
+        start.format("%G-W%V"),
+        format.extension()
+    ));
+    if path.exists() {
+        return Ok(None);
+    }
+
+    let claude_path = match &settings.claude_path {
+        Some(path) => PathBuf::from(path),
+        None => default_claude_folder()?,
+    };
+    let digest = weekly_digest(
+        &claude_path,
+        Some(&start.to_string()),
+        Some(format.name()),
+        today,
+    )?;
+    write_file_atomic(&path, &digest.content)?;
+    Ok(Some(path))
+}
+
+fn run_scheduled_digest() {
+    let Some(settings) = digest_settings() else {
+        return;
+    };
+    if let Err(e) = write_due_digest(&settings, Utc::now().date_naive()) {
+        eprintln!("Failed to write weekly digest: {e}");
+    }
+}
+
+/// Start checking in the background whether last week's digest is written
+pub fn start_digest_scheduler() {
+    tauri::async_runtime::spawn(async {
+        loop {
+            let _ = tauri::async_runtime::spawn_blocking(run_scheduled_digest).await;
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+fn weekly_digest_cli(args: &[String]) -> Result<(), String> {
+    let mut options: HashMap<&str, &str> = HashMap::new();
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let name = flag
+            .strip_prefix("--")
+            .filter(|name| ["range", "format", "claude-path", "output"].contains(name))
+            .ok_or_else(|| format!("Unknown option: {flag}"))?;
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for {flag}"))?;
+        options.insert(name, value);
+    }
+
+    apply_saved_settings();
+    let claude_path = match options.get("claude-path") {
+        Some(path) => PathBuf::from(path),
+        None => default_claude_folder()?,
+    };
+    let digest = weekly_digest(
+        &claude_path,
+        options.get("range").copied(),
+        options.get("format").copied(),
+        Utc::now().date_naive(),
+    )?;
+    if let Some(path) = options.get("output") {
+        return write_file_atomic(Path::new(path), &digest.content);
+    }
+    print!("{}", digest.content);
+    Ok(())
+}
+
+/// Headless run: `--weekly-digest [--range R] [--format F] [--claude-path DIR] [--output FILE]`
+///
+/// Prints the digest unless `--output` is given, and returns the exit code.
+/// Windows release builds have no console of their own; `main` attaches to
+/// the launching terminal's first.
+pub fn run_weekly_digest_cli(args: &[String]) -> i32 {
+    match weekly_digest_cli(args) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Failed to generate weekly digest: {e}");
+            1
+        }
+    }
+}
+
+/// Digest of one week across all projects, as Markdown or HTML
+///
+/// `range` is `last_week` (default), `this_week` or a `YYYY-MM-DD` date
+/// within the week; weeks run Monday to Sunday (UTC). `format` is
+/// "markdown" (default) or "html".
+#[tauri::command]
+pub async fn generate_weekly_digest(
+    claude_path: String,
+    range: Option<String>,
+    format: Option<String>,
+) -> Result<WeeklyDigest, AppError> {
+    let _timer = OperationTimer::start("generate_weekly_digest");
+    let today = Utc::now().date_naive();
+    week_bounds(range.as_deref(), today).map_err(AppError::invalid_input)?;
+    if let Some(format) = &format {
+        DigestFormat::parse(format).map_err(AppError::invalid_input)?;
+    }
+
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        weekly_digest(
+            Path::new(&claude_path),
+            range.as_deref(),
+            format.as_deref(),
+            today,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        prompt_line, response_line, tool_result_line, tool_use_block, write_jsonl,
+    };
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_week_bounds() {
+        let today = date("2025-03-05"); // Wednesday
+        assert_eq!(
+            week_bounds(None, today).unwrap(),
+            (date("2025-02-24"), date("2025-03-02"))
+        );
+        assert_eq!(
+            week_bounds(Some("this_week"), today).unwrap(),
+            (date("2025-03-03"), date("2025-03-09"))
+        );
+        assert_eq!(
+            week_bounds(Some("2025-01-01"), today).unwrap(),
+            (date("2024-12-30"), date("2025-01-05"))
+        );
+        assert!(week_bounds(Some("yesterday"), today).is_err());
+    }
+
+    #[test]
+    fn test_streaks() {
+        let days: BTreeSet<NaiveDate> = ["2025-03-01", "2025-03-02", "2025-03-04", "2025-03-05"]
+            .into_iter()
+            .map(date)
+            .collect();
+        assert_eq!(streaks(&days, date("2025-03-06")), (2, 2));
+        assert_eq!(streaks(&days, date("2025-03-02")), (2, 2));
+        assert_eq!(streaks(&days, date("2025-03-08")), (0, 2));
+    }
+
+    #[test]
+    fn test_weekly_digest_sections() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join("projects").join("-Users-me-repo");
+        fs::create_dir_all(&project).unwrap();
+        let mut first_prompt = prompt_line("u0", "2025-02-27T10:00:00Z", "Set up the project");
+        first_prompt["cwd"] = json!("/repo");
+        let write = json!({"file_path": "/repo/src/login.ts", "content": "a\nb\n"});
+        let read = json!({"file_path": "/repo/missing.ts"});
+        let lines = [
+            // Previous week
+            first_prompt,
+            response_line("msg_0", "2025-02-27T10:01:00Z", json!("Done"), 1_000_000, 0),
+            // Digest week
+            prompt_line(
+                "u1",
+                "2025-03-03T09:00:00Z",
+                "Fix the login bug\nIt fails on Safari",
+            ),
+            response_line(
+                "msg_1",
+                "2025-03-03T09:01:00Z",
+                json!([tool_use_block("t1", "Write", write)]),
+                1_000_000,
+                0,
+            ),
+            tool_result_line("2025-03-03T09:01:01Z", "t1", "File not found", false),
+            response_line(
+                "msg_2",
+                "2025-03-04T09:00:00Z",
+                json!([tool_use_block("t2", "Read", read)]),
+                1_000_000,
+                0,
+            ),
+            tool_result_line("2025-03-04T09:00:01Z", "t2", "File not found", true),
+        ];
+        write_jsonl(&project.join("s1.jsonl"), &lines);
+
+        let digest =
+            weekly_digest(temp.path(), Some("2025-03-05"), None, date("2025-03-10")).unwrap();
+        assert_eq!(digest.start_date, "2025-03-03");
+        assert_eq!(digest.session_count, 1);
+        assert_eq!(digest.files_changed, 1);
+        assert_eq!(digest.error_count, 1);
+        assert_eq!(digest.current_streak_days, 0);
+        assert!((digest.total_cost_usd - 6.0).abs() < 1e-9);
+
+        let content = &digest.content;
+        assert!(content.starts_with("# Weekly digest — 2025-03-03 to 2025-03-09"));
+        assert!(content.contains("$6.00 (+100% vs the previous week)"));
+        assert!(content.contains("- Fix the login bug — repo · $6.00 · 5 messages"));
+        assert!(content.contains("- src/login.ts +2 / -0"));
+        assert!(content.contains("1 tool failure, most often (1×): File not found"));
+        assert!(content.contains("Active 2 of 7 days · current streak 0 days · longest 2 days"));
+
+        let html = weekly_digest(
+            temp.path(),
+            Some("2025-03-05"),
+            Some("html"),
+            date("2025-03-10"),
+        )
+        .unwrap()
+        .content;
+        assert!(html.contains("<h2>Files changed</h2>"));
+        assert!(html.contains("<li>src/login.ts +2 / -0</li>"));
+    }
+}
@@ -0,0 +1,560 @@
+//! Cross-project entity graph
+//!
+//! Extracts lightweight entities (file paths, package names, URLs, issue IDs)
+//! from conversation text and links sessions that mention the same entity.
+
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::io_limit::acquire_file_permit;
+use crate::models::{EntityGraph, EntityRef, EntitySessionRef, RawLogEntry, RelatedEntity};
+use crate::utils::{collect_session_files, extract_project_name, find_line_ranges, long_path};
+use memchr::memmem;
+use memmap2::Mmap;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Maximum number of related entities returned for a query
+const MAX_RELATED_ENTITIES: usize = 50;
+
+/// Characters that separate candidate tokens in free text
+const TOKEN_DELIMITERS: &[char] = &[
+    ' ', '\t', '\n', '\r', '`', '"', '\'', '(', ')', '[', ']', '{', '}', '<', '>', ',', '|', '*',
+];
+
+/// Punctuation stripped from the end of a token
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ':', ';', '!', '?'];
+
+/// Package manager commands whose arguments are package names
+const PACKAGE_COMMANDS: &[(&str, &str)] = &[
+    ("cargo", "add"),
+    ("npm", "install"),
+    ("npm", "i"),
+    ("pnpm", "add"),
+    ("yarn", "add"),
+    ("bun", "add"),
+    ("pip", "install"),
+];
+
+/// Rust path roots that are not external crates
+const NON_CRATE_ROOTS: &[&str] = &["std", "core", "alloc", "crate", "self", "super"];
+
+/// Extensions accepted for bare file names (without a directory)
+const KNOWN_FILE_EXTENSIONS: &[&str] = &[
+    "rs", "ts", "tsx", "js", "jsx", "mjs", "py", "go", "java", "kt", "swift", "c", "h", "cpp",
+    "rb", "sh", "toml", "json", "jsonl", "yaml", "yml", "md", "css", "html", "sql", "lock",
+];
+
+/// Uppercase prefixes that look like issue keys but are standards (e.g. UTF-8)
+const ISSUE_KEY_DENYLIST: &[&str] = &[
+    "UTF", "SHA", "ISO", "RFC", "HTTP", "TLS", "SSL", "AES", "ES", "GPT",
+];
+
+/// Package manager flags that take a value (`--features derive`)
+const VALUE_FLAGS: &[&str] = &[
+    "--features",
+    "-F",
+    "--package",
+    "-p",
+    "--rename",
+    "--registry",
+    "--git",
+    "--branch",
+    "--tag",
+    "--rev",
+    "--path",
+    "--target",
+    "-r",
+    "--requirement",
+];
+
+/// Tool input fields scanned for entities
+const TOOL_INPUT_FIELDS: &[&str] = &["file_path", "notebook_path", "path", "url", "command"];
+
+fn entity(kind: &str, value: &str) -> EntityRef {
+    EntityRef {
+        kind: kind.to_string(),
+        value: value.to_string(),
+    }
+}
+
+fn is_package_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 214
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '@' | '/'))
+        && name.chars().any(|c| c.is_ascii_alphabetic())
+}
+
+/// Strip version specifiers from a package argument (`serde@1.0`, `requests==2.0`)
+fn strip_package_version(arg: &str) -> &str {
+    let arg = arg.split(['=', '<', '>', '~']).next().unwrap_or(arg);
+    match arg.rfind('@') {
+        Some(pos) if pos > 0 => &arg[..pos],
+        _ => arg,
+    }
+}
+
+fn classify_issue(token: &str) -> Option<EntityRef> {
+    // "#123" or "owner/repo#123"
+    if let Some(pos) = token.rfind('#') {
+        let number = &token[pos + 1..];
+        let prefix = &token[..pos];
+        let valid_prefix = prefix.is_empty()
+            || (prefix.matches('/').count() == 1
+                && !prefix.starts_with('/')
+                && !prefix.ends_with('/'));
+        if valid_prefix
+            && (1..=7).contains(&number.len())
+            && number.chars().all(|c| c.is_ascii_digit())
+        {
+            return Some(entity("issue", token));
+        }
+        return None;
+    }
+
+    // Tracker keys such as "PROJ-123"
+    let (key, number) = token.split_once('-')?;
+    if (2..=10).contains(&key.len())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        && key.starts_with(|c: char| c.is_ascii_uppercase())
+        && !ISSUE_KEY_DENYLIST.contains(&key)
+        && !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit())
+    {
+        return Some(entity("issue", token));
+    }
+    None
+}
+
+fn classify_file(token: &str) -> Option<EntityRef> {
+    // Drop ":line" / ":line:col" suffixes
+    let mut path = token;
+    while let Some((head, tail)) = path.rsplit_once(':') {
+        if !tail.is_empty() && tail.chars().all(|c| c.is_ascii_digit()) {
+            path = head;
+        } else {
+            break;
+        }
+    }
+
+    if path.contains("://") || (path.contains(':') && !path.contains(":\\")) {
+        return None;
+    }
+
+    let file_name = path.rsplit(['/', '\\']).next()?;
+    let (stem, extension) = file_name.rsplit_once('.')?;
+    let valid_extension = (1..=8).contains(&extension.len())
+        && extension.starts_with(|c: char| c.is_ascii_alphabetic())
+        && extension.chars().all(|c| c.is_ascii_alphanumeric());
+    if !valid_extension {
+        return None;
+    }
+
+    let has_directory = path.contains('/') || path.contains('\\');
+    if has_directory || (!stem.is_empty() && KNOWN_FILE_EXTENSIONS.contains(&extension)) {
+        Some(entity("file", path))
+    } else {
+        None
+    }
+}
+
+/// Extract entities from a piece of free text
+pub fn extract_entities(text: &str) -> Vec<EntityRef> {
+    let tokens: Vec<&str> = text
+        .split(TOKEN_DELIMITERS)
+        .map(|t| t.trim_end_matches(TRAILING_PUNCTUATION))
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    let mut entities = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token.starts_with("http://") || token.starts_with("https://") {
+            if token.len() > "https://".len() {
+                entities.push(entity("url", token.trim_end_matches('/')));
+            }
+            continue;
+        }
+
+        // Package manager invocations: take the following non-flag arguments
+        if i + 1 < tokens.len() && PACKAGE_COMMANDS.contains(&(*token, tokens[i + 1])) {
+            let mut skip_next = false;
+            for arg in tokens[i + 2..]
+                .iter()
+                .take_while(|a| !matches!(**a, "&&" | ";" | "||"))
+            {
+                if std::mem::take(&mut skip_next) {
+                    continue;
+                }
+                if arg.starts_with('-') {
+                    skip_next = VALUE_FLAGS.contains(arg);
+                    continue;
+                }
+                let name = strip_package_version(arg);
+                if is_package_name(name) {
+                    entities.push(entity("package", name));
+                }
+            }
+            continue;
+        }
+
+        // Rust imports: `use serde_json::Value;`
+        if *token == "use" {
+            if let Some(root) = tokens
+                .get(i + 1)
+                .and_then(|next| next.split_once("::"))
+                .map(|(root, _)| root)
+            {
+                if is_package_name(root) && !NON_CRATE_ROOTS.contains(&root) {
+                    entities.push(entity("package", root));
+                }
+            }
+            continue;
+        }
+
+        if let Some(issue) = classify_issue(token) {
+            entities.push(issue);
+        } else if let Some(file) = classify_file(token) {
+            entities.push(file);
+        }
+    }
+
+    entities
+}
+
+/// Extract entities from the conversational parts of a log entry
+fn extract_entities_from_entry(entry: &RawLogEntry) -> Vec<EntityRef> {
+    if entry.message_type != "user" && entry.message_type != "assistant" {
+        return Vec::new();
+    }
+    let Some(content) = entry.message.as_ref().map(|m| &m.content) else {
+        return Vec::new();
+    };
+
+    let mut entities = Vec::new();
+    match content {
+        serde_json::Value::String(text) => entities.extend(extract_entities(text)),
+        serde_json::Value::Array(items) => {
+            for item in items {
+                match item.get("type").and_then(|v| v.as_str()) {
+                    Some("text") => {
+                        if let Some(text) = item.get("text").and_then(|v| v.as_str()) {
+                            entities.extend(extract_entities(text));
+                        }
+                    }
+                    Some("tool_use") => {
+                        let Some(input) = item.get("input") else {
+                            continue;
+                        };
+                        for field in TOOL_INPUT_FIELDS {
+                            if let Some(value) = input.get(*field).and_then(|v| v.as_str()) {
+                                entities.extend(extract_entities(value));
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+    entities
+}
+
+/// Whether an extracted entity matches the user's query
+///
+/// Matching is exact, except that file queries also match by path suffix
+/// (`load.rs` matches `src/commands/session/load.rs`).
+fn entity_matches(entity: &EntityRef, query: &str) -> bool {
+    entity.value == query
+        || (entity.kind == "file"
+            && (entity.value.ends_with(&format!("/{query}"))
+                || entity.value.ends_with(&format!("\\{query}"))))
+}
+
+/// Per-session entity mentions: entity -> (mention count, first mention timestamp)
+struct SessionEntities {
+    session_id: String,
+    project_name: String,
+    file_path: PathBuf,
+    entities: HashMap<EntityRef, (usize, Option<String>)>,
+}
+
+#[allow(unsafe_code)] // Required for mmap performance optimization
+fn process_session_file_for_entities(
+    raw_project_name: &str,
+    session_path: &Path,
+    query: &str,
+) -> Option<SessionEntities> {
+    let _permit = acquire_file_permit();
+    let file = fs::File::open(long_path(session_path)).ok()?;
+
+    // SAFETY: We're only reading the file, and the file handle is kept open
+    // for the duration of the mmap's lifetime. Session files are append-only.
+    let mmap = unsafe { Mmap::map(&file) }.ok()?;
+
+    // Cheap pre-filter: the query text must appear somewhere in the file
+    memmem::find(&mmap, query.as_bytes())?;
+
+    let mut session_id: Option<String> = None;
+    let mut entities: HashMap<EntityRef, (usize, Option<String>)> = HashMap::new();
+
+    for (start, end) in find_line_ranges(&mmap) {
+        let mut line_bytes = mmap[start..end].to_vec();
+        let Ok(entry) = simd_json::serde::from_slice::<RawLogEntry>(&mut line_bytes) else {
+            continue;
+        };
+
+        if session_id.is_none() {
+            session_id.clone_from(&entry.session_id);
+        }
+
+        for found in extract_entities_from_entry(&entry) {
+            let slot = entities.entry(found).or_insert((0, None));
+            slot.0 += 1;
+            if slot.1.is_none() {
+                slot.1.clone_from(&entry.timestamp);
+            }
+        }
+    }
+
+    if !entities.keys().any(|e| entity_matches(e, query)) {
+        return None;
+    }
+
+    Some(SessionEntities {
+        session_id: session_id.unwrap_or_else(|| {
+            session_path.file_stem().map_or_else(
+                || "unknown-session".to_string(),
+                |s| s.to_string_lossy().into_owned(),
+            )
+        }),
+        project_name: extract_project_name(raw_project_name),
+        file_path: session_path.to_path_buf(),
+        entities,
+    })
+}
+
+/// Link matching sessions and rank co-occurring entities
+fn build_entity_graph(
+    query: &str,
+    matching: Vec<SessionEntities>,
+    total_sessions_scanned: usize,
+) -> EntityGraph {
+    let mut related: HashMap<EntityRef, usize> = HashMap::new();
+    let mut sessions = Vec::with_capacity(matching.len());
+
+    for session in matching {
+        let mut matched: Vec<EntityRef> = Vec::new();
+        let mut mention_count = 0;
+        let mut first_mentioned_at: Option<String> = None;
+
+        for (found, (count, first_seen)) in &session.entities {
+            if entity_matches(found, query) {
+                matched.push(found.clone());
+                mention_count += count;
+                if let Some(ts) = first_seen {
+                    match &first_mentioned_at {
+                        Some(current) if current <= ts => {}
+                        _ => first_mentioned_at = Some(ts.clone()),
+                    }
+                }
+            } else {
+                *related.entry(found.clone()).or_insert(0) += 1;
+            }
+        }
+        matched.sort();
+
+        sessions.push(EntitySessionRef {
+            session_id: session.session_id,
+            project_name: session.project_name,
+            file_path: session.file_path.to_string_lossy().to_string(),
+            mention_count,
+            first_mentioned_at,
+            matched,
+        });
+    }
+
+    sessions.sort_by(|a, b| {
+        b.mention_count
+            .cmp(&a.mention_count)
+            .then_with(|| a.session_id.cmp(&b.session_id))
+    });
+
+    let mut related_entities: Vec<RelatedEntity> = related
+        .into_iter()
+        .map(|(entity, shared_session_count)| RelatedEntity {
+            entity,
+            shared_session_count,
+        })
+        .collect();
+    related_entities.sort_by(|a, b| {
+        b.shared_session_count
+            .cmp(&a.shared_session_count)
+            .then_with(|| a.entity.cmp(&b.entity))
+    });
+    related_entities.truncate(MAX_RELATED_ENTITIES);
+
+    EntityGraph {
+        query: query.to_string(),
+        sessions,
+        related_entities,
+        total_sessions_scanned,
+    }
+}
+
+/// Find all sessions (across projects) that mention an entity
+#[tauri::command]
+pub async fn get_entity_graph(
+    claude_path: String,
+    entity: String,
+) -> Result<EntityGraph, AppError> {
+    let _timer = OperationTimer::start("get_entity_graph");
+    let query = entity.trim().to_string();
+    if query.is_empty() {
+        return Err(AppError::invalid_input("Entity must not be empty"));
+    }
+
+    let projects_path = PathBuf::from(&claude_path).join("projects");
+    if !projects_path.exists() {
+        return Err(AppError::not_found("Projects directory not found"));
+    }
+
+    let session_files = collect_session_files(&projects_path)?;
+    let matching: Vec<SessionEntities> = session_files
+        .par_iter()
+        .filter_map(|(project_name, path)| {
+            process_session_file_for_entities(project_name, path, &query)
+        })
+        .collect();
+
+    Ok(build_entity_graph(&query, matching, session_files.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{create_jsonl_content, MessageBuilder};
+    use tempfile::TempDir;
+
+    fn values(entities: &[EntityRef], kind: &str) -> Vec<String> {
+        entities
+            .iter()
+            .filter(|e| e.kind == kind)
+            .map(|e| e.value.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_extract_files_and_urls() {
+        let entities = extract_entities(
+            "Edit `src/commands/stats.rs:42` and Cargo.toml, see https://docs.rs/memchr/.",
+        );
+
+        assert_eq!(
+            values(&entities, "file"),
+            vec!["src/commands/stats.rs", "Cargo.toml"]
+        );
+        assert_eq!(values(&entities, "url"), vec!["https://docs.rs/memchr"]);
+    }
+
+    #[test]
+    fn test_extract_packages() {
+        let entities =
+            extract_entities("Run cargo add serde@1.0 --features derive && npm i @tauri-apps/api");
+        assert_eq!(
+            values(&entities, "package"),
+            vec!["serde", "@tauri-apps/api"]
+        );
+
+        let entities = extract_entities("use serde_json::Value;\nuse std::fs;");
+        assert_eq!(values(&entities, "package"), vec!["serde_json"]);
+    }
+
+    #[test]
+    fn test_extract_issues() {
+        let entities = extract_entities("Fixes #123 and owner/repo#45, see PROJ-9 (UTF-8 only)");
+        assert_eq!(
+            values(&entities, "issue"),
+            vec!["#123", "owner/repo#45", "PROJ-9"]
+        );
+    }
+
+    #[test]
+    fn test_ignores_non_entities() {
+        let entities = extract_entities("e.g. version 1.5 and/or 3.14, done. Ratio: 2:1");
+        assert!(entities.is_empty(), "unexpected entities: {entities:?}");
+    }
+
+    #[test]
+    fn test_entity_matches_file_suffix() {
+        let file = entity("file", "src/commands/session/load.rs");
+        assert!(entity_matches(&file, "load.rs"));
+        assert!(entity_matches(&file, "session/load.rs"));
+        assert!(!entity_matches(&file, "ad.rs"));
+        assert!(!entity_matches(
+            &entity("url", "https://x.dev/load.rs"),
+            "load.rs"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_entity_graph_links_sessions_across_projects() {
+        let temp = TempDir::new().unwrap();
+        let project_a = temp.path().join("projects").join("-Users-me-alpha");
+        let project_b = temp.path().join("projects").join("-Users-me-beta");
+        fs::create_dir_all(&project_a).unwrap();
+        fs::create_dir_all(&project_b).unwrap();
+        let prompt = |session_id: &str, text: &str| {
+            create_jsonl_content(&[MessageBuilder::user()
+                .with_session_id(session_id)
+                .with_text_content(text)])
+        };
+        fs::write(
+            project_a.join("a.jsonl"),
+            prompt("sa", "Look at src/lib.rs and #12"),
+        )
+        .unwrap();
+        fs::write(
+            project_b.join("b.jsonl"),
+            prompt("sb", "Crash in src/lib.rs, see #12"),
+        )
+        .unwrap();
+        fs::write(
+            project_b.join("c.jsonl"),
+            prompt("sc", "Unrelated question"),
+        )
+        .unwrap();
+
+        let graph = get_entity_graph(
+            temp.path().to_string_lossy().to_string(),
+            "lib.rs".to_string(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(graph.total_sessions_scanned, 3);
+        let ids: Vec<&str> = graph
+            .sessions
+            .iter()
+            .map(|s| s.session_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["sa", "sb"]);
+        assert_eq!(graph.sessions[0].matched[0].value, "src/lib.rs");
+        assert_eq!(graph.related_entities[0].entity.value, "#12");
+        assert_eq!(graph.related_entities[0].shared_session_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_entity_graph_rejects_empty_entity() {
+        let temp = TempDir::new().unwrap();
+        let result =
+            get_entity_graph(temp.path().to_string_lossy().to_string(), "  ".to_string()).await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,472 @@
+//! Error analysis
+//!
+//! `ClaudeSession.has_errors` only tells whether a tool wrote to stderr. This
+//! pass collects the errors Claude Code records — failed tool results, API
+//! error messages and entries, rate-limit notices and hook failures — and
+//! sorts them into categories with a few example messages each.
+
+use crate::commands::export::session_project_name;
+use crate::commands::prompt_quality::prompt_text;
+use crate::commands::retry_loops::{tool_result_text, truncate_chars};
+use crate::commands::session::subagent_session_file;
+use crate::commands::stats::{read_raw_log_entries, resolve_scope_session_files};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{
+    ErrorCategoryCount, ErrorExample, ErrorReport, RawLogEntry, SessionErrors, ToolErrorCount,
+};
+use crate::utils::display_path;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const TOOL_FAILURE: &str = "tool_failure";
+const API_ERROR: &str = "api_error";
+const RATE_LIMIT: &str = "rate_limit";
+const HOOK_FAILURE: &str = "hook_failure";
+const SYSTEM_ERROR: &str = "system_error";
+
+/// Examples kept per category
+const MAX_EXAMPLES: usize = 3;
+
+/// Maximum characters kept of an error message
+const MAX_MESSAGE_CHARS: usize = 200;
+
+/// Lowercase phrases of rate-limit and usage-limit notices
+const RATE_LIMIT_PHRASES: [&str; 5] = [
+    "rate limit",
+    "rate_limit",
+    "usage limit",
+    "limit reached",
+    "api error: 429",
+];
+
+/// Lowercase phrases of tool results rejected by a hook
+const HOOK_ERROR_PHRASES: [&str; 3] = ["hook error", "blocked by hook", "hook failed"];
+
+/// An error found in a session file
+#[derive(Debug)]
+pub(crate) struct FoundError {
+    pub category: &'static str,
+    pub message_uuid: Option<String>,
+    pub timestamp: Option<String>,
+    pub tool_name: Option<String>,
+    pub message: String,
+}
+
+/// Errors and tool calls (per tool) of one session file
+#[derive(Debug, Default)]
+pub(crate) struct FileErrors {
+    pub errors: Vec<FoundError>,
+    pub tool_calls: HashMap<String, usize>,
+}
+
+fn contains_any(text: &str, phrases: &[&str]) -> bool {
+    let lower = text.to_lowercase();
+    phrases.iter().any(|phrase| lower.contains(phrase))
+}
+
+/// Message of an `api_error` entry's error: the innermost `message`, with
+/// the HTTP status when recorded
+fn api_error_message(error: &serde_json::Value) -> String {
+    let mut message = None;
+    let mut current = Some(error);
+    while let Some(value) = current {
+        if let Some(text) = value.get("message").and_then(|v| v.as_str()) {
+            message = Some(text);
+        }
+        current = value.get("error");
+    }
+    let message = message.map_or_else(|| error.to_string(), str::to_string);
+    match error.get("status").and_then(serde_json::Value::as_u64) {
+        Some(status) => format!("{status}: {message}"),
+        None => message,
+    }
+}
+
+fn file_errors(path: &Path) -> FileErrors {
+    collect_errors(&read_raw_log_entries(path))
+}
+
+/// Errors and tool calls of log entries, in file order
+pub(crate) fn collect_errors(entries: &[RawLogEntry]) -> FileErrors {
+    let mut errors = Vec::new();
+    let mut tool_calls: HashMap<String, usize> = HashMap::new();
+    let mut tool_names: HashMap<String, String> = HashMap::new();
+    for entry in entries {
+        let mut push = |category: &'static str, tool_name: Option<String>, message: &str| {
+            errors.push(FoundError {
+                category,
+                message_uuid: entry.uuid.clone(),
+                timestamp: entry.timestamp.clone(),
+                tool_name,
+                message: truncate_chars(message.trim(), MAX_MESSAGE_CHARS),
+            });
+        };
+
+        match entry.message_type.as_str() {
+            "assistant" => {
+                let Some(message) = &entry.message else {
+                    continue;
+                };
+                let text = prompt_text(&message.content).unwrap_or_default();
+                let synthetic = message.model.as_deref() == Some("<synthetic>");
+                if entry.is_api_error_message == Some(true)
+                    || (synthetic && text.starts_with("API Error"))
+                {
+                    let category = if contains_any(&text, &RATE_LIMIT_PHRASES) {
+                        RATE_LIMIT
+                    } else {
+                        API_ERROR
+                    };
+                    push(category, None, &text);
+                } else if synthetic && contains_any(&text, &RATE_LIMIT_PHRASES) {
+                    push(RATE_LIMIT, None, &text);
+                }
+
+                let tool_uses = message
+                    .content
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter(|b| b.get("type").and_then(|v| v.as_str()) == Some("tool_use"));
+                for tool_use in tool_uses {
+                    let name = tool_use
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("unknown");
+                    *tool_calls.entry(name.to_string()).or_default() += 1;
+                    if let Some(id) = tool_use.get("id").and_then(|v| v.as_str()) {
+                        tool_names.insert(id.to_string(), name.to_string());
+                    }
+                }
+            }
+            "user" => {
+                let Some(blocks) = entry.message.as_ref().and_then(|m| m.content.as_array()) else {
+                    continue;
+                };
+                for block in blocks {
+                    if block.get("type").and_then(|v| v.as_str()) != Some("tool_result")
+                        || block.get("is_error").and_then(serde_json::Value::as_bool) != Some(true)
+                    {
+                        continue;
+                    }
+                    let text = tool_result_text(block).unwrap_or_default();
+                    let tool_name = block
+                        .get("tool_use_id")
+                        .and_then(|v| v.as_str())
+                        .and_then(|id| tool_names.get(id))
+                        .cloned();
+                    let category = if contains_any(&text, &HOOK_ERROR_PHRASES) {
+                        HOOK_FAILURE
+                    } else {
+                        TOOL_FAILURE
+                    };
+                    push(category, tool_name, &text);
+                }
+            }
+            "system" => system_errors(entry, push),
+            _ => {}
+        }
+    }
+    FileErrors { errors, tool_calls }
+}
+
+/// Errors of a system entry: API errors, failed Stop hooks and other
+/// error-level notices
+fn system_errors(entry: &RawLogEntry, mut push: impl FnMut(&'static str, Option<String>, &str)) {
+    let content = entry
+        .content
+        .as_ref()
+        .and_then(|c| c.as_str())
+        .unwrap_or_default();
+    match entry.subtype.as_deref() {
+        Some("api_error") => {
+            let message = entry
+                .error
+                .as_ref()
+                .map_or_else(|| content.to_string(), api_error_message);
+            let category =
+                if contains_any(&message, &RATE_LIMIT_PHRASES) || message.starts_with("429") {
+                    RATE_LIMIT
+                } else {
+                    API_ERROR
+                };
+            push(category, None, &message);
+        }
+        Some("stop_hook_summary") => {
+            let hook_errors = entry.hook_errors.as_ref().and_then(|e| e.as_array());
+            for error in hook_errors.into_iter().flatten() {
+                match error.as_str() {
+                    Some(text) => push(HOOK_FAILURE, None, text),
+                    None => push(HOOK_FAILURE, None, &error.to_string()),
+                }
+            }
+        }
+        _ if entry.level.as_deref() == Some("error") => {
+            let category = if contains_any(content, &RATE_LIMIT_PHRASES) {
+                RATE_LIMIT
+            } else if content.to_lowercase().contains("hook") {
+                HOOK_FAILURE
+            } else {
+                SYSTEM_ERROR
+            };
+            push(category, None, content);
+        }
+        _ => {}
+    }
+}
+
+/// Counts per category, most errors first, with the first distinct messages
+fn categorize<'a>(
+    errors: impl IntoIterator<Item = (&'a str, &'a FoundError)>,
+) -> Vec<ErrorCategoryCount> {
+    let mut categories: Vec<ErrorCategoryCount> = Vec::new();
+    for (session_id, error) in errors {
+        let index = categories
+            .iter()
+            .position(|c| c.category == error.category)
+            .unwrap_or_else(|| {
+                categories.push(ErrorCategoryCount {
+                    category: error.category.to_string(),
+                    count: 0,
+                    examples: Vec::new(),
+                });
+                categories.len() - 1
+            });
+        let category = &mut categories[index];
+        category.count += 1;
+        if category.examples.len() < MAX_EXAMPLES
+            && !category.examples.iter().any(|e| e.message == error.message)
+        {
+            category.examples.push(ErrorExample {
+                session_id: session_id.to_string(),
+                message_uuid: error.message_uuid.clone(),
+                timestamp: error.timestamp.clone(),
+                tool_name: error.tool_name.clone(),
+                message: error.message.clone(),
+            });
+        }
+    }
+    categories.sort_by(|a, b| b.count.cmp(&a.count).then(a.category.cmp(&b.category)));
+    categories
+}
+
+fn error_report(scope: String, files: Vec<(PathBuf, FileErrors)>) -> ErrorReport {
+    // Sub-agent files count towards their session
+    let mut sessions: Vec<(PathBuf, String, FileErrors)> = Vec::new();
+    let mut session_index: HashMap<PathBuf, usize> = HashMap::new();
+    for (path, errors) in files {
+        let session_path = subagent_session_file(&path).unwrap_or(path);
+        if let Some(&index) = session_index.get(&session_path) {
+            let session = &mut sessions[index].2;
+            session.errors.extend(errors.errors);
+            for (tool, calls) in errors.tool_calls {
+                *session.tool_calls.entry(tool).or_default() += calls;
+            }
+            continue;
+        }
+        let session_id = session_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        session_index.insert(session_path.clone(), sessions.len());
+        sessions.push((session_path, session_id, errors));
+    }
+
+    // (calls, failures) per tool
+    let mut tools: HashMap<&str, (usize, usize)> = HashMap::new();
+    for (_, _, session) in &sessions {
+        for (tool, &calls) in &session.tool_calls {
+            tools.entry(tool).or_default().0 += calls;
+        }
+        for error in &session.errors {
+            if let Some(tool) = &error.tool_name {
+                tools.entry(tool).or_default().1 += 1;
+            }
+        }
+    }
+    let mut tools: Vec<ToolErrorCount> = tools
+        .into_iter()
+        .filter(|&(_, (_, errors))| errors > 0)
+        .map(|(tool_name, (call_count, error_count))| ToolErrorCount {
+            tool_name: tool_name.to_string(),
+            call_count,
+            error_count,
+            error_rate: if call_count > 0 {
+                error_count as f64 / call_count as f64
+            } else {
+                0.0
+            },
+        })
+        .collect();
+    tools.sort_by(|a, b| {
+        b.error_count
+            .cmp(&a.error_count)
+            .then_with(|| a.tool_name.cmp(&b.tool_name))
+    });
+
+    let categories = categorize(sessions.iter().flat_map(|(_, session_id, session)| {
+        session
+            .errors
+            .iter()
+            .map(move |error| (session_id.as_str(), error))
+    }));
+    let mut session_errors: Vec<SessionErrors> = sessions
+        .iter()
+        .filter(|(_, _, session)| !session.errors.is_empty())
+        .map(|(path, session_id, session)| SessionErrors {
+            session_id: session_id.clone(),
+            project_name: session_project_name(path),
+            file_path: display_path(path),
+            error_count: session.errors.len(),
+            categories: categorize(session.errors.iter().map(|e| (session_id.as_str(), e))),
+        })
+        .collect();
+    session_errors.sort_by(|a, b| {
+        b.error_count
+            .cmp(&a.error_count)
+            .then_with(|| a.file_path.cmp(&b.file_path))
+    });
+
+    ErrorReport {
+        scope,
+        session_count: sessions.len(),
+        sessions_with_errors: session_errors.len(),
+        total_errors: categories.iter().map(|c| c.count).sum(),
+        categories,
+        tools,
+        sessions: session_errors,
+    }
+}
+
+/// Errors of a session, project or all projects, by category: tool failures,
+/// API errors, rate limits, hook failures and other error-level notices
+///
+/// `scope` is "session", "project" or "global" with `path` pointing at the
+/// session file, project folder or Claude folder.
+#[tauri::command]
+pub async fn get_error_report(scope: String, path: String) -> Result<ErrorReport, AppError> {
+    let _timer = OperationTimer::start("get_error_report");
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let session_files = resolve_scope_session_files(&scope, &path)?;
+        let files: Vec<(PathBuf, FileErrors)> = session_files
+            .into_par_iter()
+            .map(|path| {
+                let errors = file_errors(&path);
+                (path, errors)
+            })
+            .collect();
+        Ok(error_report(scope, files))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_error_report_categorizes_errors() {
+        let temp = TempDir::new().unwrap();
+        let project_dir = temp.path().join("-Users-me-demo");
+        fs::create_dir_all(&project_dir).unwrap();
+
+        let tool_use = |id: &str, name: &str| {
+            json!({
+                "type": "assistant", "uuid": format!("a-{id}"),
+                "message": {"role": "assistant", "content": [{"type": "tool_use", "id": id, "name": name, "input": {}}]}
+            })
+        };
+        let tool_result = |id: &str, text: &str| {
+            json!({
+                "type": "user", "uuid": format!("r-{id}"),
+                "message": {"role": "user", "content": [{"type": "tool_result", "tool_use_id": id, "is_error": true, "content": text}]}
+            })
+        };
+        let api_error = |uuid: &str, text: &str| {
+            json!({
+                "type": "assistant", "uuid": uuid, "isApiErrorMessage": true,
+                "message": {"role": "assistant", "model": "<synthetic>", "content": [{"type": "text", "text": text}]}
+            })
+        };
+        let lines = [
+            tool_use("t1", "Bash"),
+            tool_result("t1", "Exit code 1\nerror: could not compile"),
+            tool_use("t2", "Bash"),
+            tool_use("t3", "Edit"),
+            tool_result(
+                "t3",
+                "PreToolUse:Edit hook error: [./check.sh]: protected file",
+            ),
+            api_error("e1", "API Error: 529 Overloaded"),
+            api_error("e2", "API Error: 429 rate_limit_error"),
+            json!({
+                "type": "system", "subtype": "api_error", "level": "error", "uuid": "s1",
+                "error": {"status": 500, "error": {"error": {"type": "api_error", "message": "Internal server error"}}}
+            }),
+            json!({
+                "type": "system", "subtype": "stop_hook_summary", "uuid": "s2",
+                "hookErrors": ["lint.sh exited with code 2"]
+            }),
+        ];
+        let lines: Vec<String> = lines.iter().map(ToString::to_string).collect();
+        fs::write(project_dir.join("s1.jsonl"), lines.join("\n")).unwrap();
+        fs::write(
+            project_dir.join("s2.jsonl"),
+            tool_use("t9", "Read").to_string(),
+        )
+        .unwrap();
+
+        let report = get_error_report(
+            "project".to_string(),
+            project_dir.to_string_lossy().to_string(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.session_count, 2);
+        assert_eq!(report.sessions_with_errors, 1);
+        assert_eq!(report.total_errors, 6);
+        let counts: Vec<(&str, usize)> = report
+            .categories
+            .iter()
+            .map(|c| (c.category.as_str(), c.count))
+            .collect();
+        assert_eq!(
+            counts,
+            [
+                ("api_error", 2),
+                ("hook_failure", 2),
+                ("rate_limit", 1),
+                ("tool_failure", 1),
+            ]
+        );
+        let api_messages: Vec<&str> = report.categories[0]
+            .examples
+            .iter()
+            .map(|e| e.message.as_str())
+            .collect();
+        assert_eq!(
+            api_messages,
+            ["API Error: 529 Overloaded", "500: Internal server error"]
+        );
+        let tool_failure = &report.categories[3].examples[0];
+        assert_eq!(tool_failure.tool_name.as_deref(), Some("Bash"));
+        assert_eq!(tool_failure.message_uuid.as_deref(), Some("r-t1"));
+
+        let tools: Vec<(&str, usize, usize)> = report
+            .tools
+            .iter()
+            .map(|t| (t.tool_name.as_str(), t.call_count, t.error_count))
+            .collect();
+        assert_eq!(tools, [("Bash", 2, 1), ("Edit", 1, 1)]);
+        assert_eq!(report.sessions[0].session_id, "s1");
+        assert_eq!(report.sessions[0].project_name.as_deref(), Some("demo"));
+    }
+}
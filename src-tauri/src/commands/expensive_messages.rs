@@ -0,0 +1,274 @@
+//! Leaderboard of the most expensive individual responses
+//!
+//! Ranks API responses by estimated cost so the turns dominating the bill
+//! (huge cache writes, giant outputs) can be opened in their session.
+
+use crate::commands::retry_loops::truncate_chars;
+use crate::commands::stats::{read_raw_log_entries, resolve_scope_session_files};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ExpensiveMessage, RawLogEntry, TokenUsage};
+use crate::pricing::estimate_cost_usd;
+use crate::utils::{display_path, extract_project_name, file_name_string, stable_line_id};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Default number of messages returned by `get_top_expensive_messages`
+const DEFAULT_TOP_MESSAGES: usize = 20;
+
+/// Maximum characters kept in a message preview
+const PREVIEW_MAX_CHARS: usize = 200;
+
+/// Cost of each usage component, labelled like `ExpensiveMessage::dominant_cost`
+fn cost_components(
+    model: Option<&str>,
+    project: Option<&str>,
+    usage: &TokenUsage,
+) -> [(&'static str, f64); 4] {
+    let cost_of = |only: TokenUsage| estimate_cost_usd(model, project, &only);
+    [
+        (
+            "input",
+            cost_of(TokenUsage {
+                input_tokens: usage.input_tokens,
+                ..TokenUsage::default()
+            }),
+        ),
+        (
+            "output",
+            cost_of(TokenUsage {
+                output_tokens: usage.output_tokens,
+                ..TokenUsage::default()
+            }),
+        ),
+        (
+            "cache_write",
+            cost_of(TokenUsage {
+                cache_creation_input_tokens: usage.cache_creation_input_tokens,
+                ..TokenUsage::default()
+            }),
+        ),
+        (
+            "cache_read",
+            cost_of(TokenUsage {
+                cache_read_input_tokens: usage.cache_read_input_tokens,
+                ..TokenUsage::default()
+            }),
+        ),
+    ]
+}
+
+/// Text and tool calls of one entry's content, for previews
+fn preview_parts(content: &serde_json::Value) -> Vec<String> {
+    match content {
+        serde_json::Value::String(text) => vec![text.clone()],
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|block| match block.get("type").and_then(|t| t.as_str()) {
+                Some("text") => block
+                    .get("text")
+                    .and_then(|t| t.as_str())
+                    .map(str::to_string),
+                Some("tool_use") => block
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .map(|name| format!("[{name}]")),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Every priced response of one session file (one entry per response id)
+fn session_expensive_messages(session_path: &Path) -> Vec<ExpensiveMessage> {
+    let entries: Vec<RawLogEntry> = read_raw_log_entries(session_path);
+    let file_path = display_path(session_path);
+    let project_name = session_path
+        .parent()
+        .and_then(file_name_string)
+        .map(|name| extract_project_name(&name))
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    let mut messages: Vec<ExpensiveMessage> = Vec::new();
+    let mut previews: Vec<Vec<String>> = Vec::new();
+    let mut by_response_id: HashMap<String, usize> = HashMap::new();
+
+    for (line_num, entry) in entries.iter().enumerate() {
+        let Some(message) = &entry.message else {
+            continue;
+        };
+        // Streamed responses span several entries; only the first is priced
+        if let Some(&idx) = message.id.as_ref().and_then(|id| by_response_id.get(id)) {
+            previews[idx].extend(preview_parts(&message.content));
+            continue;
+        }
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+
+        let components = cost_components(message.model.as_deref(), entry.cwd.as_deref(), usage);
+        let cost_usd: f64 = components.iter().map(|(_, cost)| cost).sum();
+        if cost_usd <= 0.0 {
+            continue;
+        }
+        let dominant_cost = components
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or("input", |(name, _)| name);
+
+        if let Some(id) = &message.id {
+            by_response_id.insert(id.clone(), messages.len());
+        }
+        previews.push(preview_parts(&message.content));
+        messages.push(ExpensiveMessage {
+            project_name: project_name.clone(),
+            session_id: entry
+                .session_id
+                .clone()
+                .unwrap_or_else(|| "unknown-session".to_string()),
+            file_path: file_path.clone(),
+            message_uuid: entry.uuid.clone().unwrap_or_else(|| {
+                stable_line_id(entry.session_id.as_deref(), session_path, line_num)
+            }),
+            timestamp: entry.timestamp.clone().unwrap_or_default(),
+            model: message.model.clone(),
+            input_tokens: u64::from(usage.input_tokens.unwrap_or(0)),
+            output_tokens: u64::from(usage.output_tokens.unwrap_or(0)),
+            cache_creation_tokens: u64::from(usage.cache_creation_input_tokens.unwrap_or(0)),
+            cache_read_tokens: u64::from(usage.cache_read_input_tokens.unwrap_or(0)),
+            cost_usd,
+            dominant_cost: dominant_cost.to_string(),
+            preview: String::new(),
+        });
+    }
+
+    for (message, parts) in messages.iter_mut().zip(previews) {
+        message.preview = truncate_chars(&parts.join(" "), PREVIEW_MAX_CHARS);
+    }
+    messages
+}
+
+/// Rank individual responses by estimated cost, most expensive first
+///
+/// `scope` is "session", "project" or "global" with `path` pointing at the
+/// session file, project folder or Claude folder. Returns the top `n`
+/// (default 20).
+#[tauri::command]
+pub async fn get_top_expensive_messages(
+    scope: String,
+    path: String,
+    n: Option<usize>,
+) -> Result<Vec<ExpensiveMessage>, AppError> {
+    let _timer = OperationTimer::start("get_top_expensive_messages");
+    let n = n.unwrap_or(DEFAULT_TOP_MESSAGES);
+
+    let session_files = resolve_scope_session_files(&scope, &path)?;
+    let mut messages: Vec<ExpensiveMessage> = session_files
+        .par_iter()
+        .flat_map(|path| {
+            let mut session = session_expensive_messages(path);
+            session.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+            session.truncate(n);
+            session
+        })
+        .collect();
+
+    messages.sort_by(|a, b| {
+        b.cost_usd
+            .total_cmp(&a.cost_usd)
+            .then_with(|| a.timestamp.cmp(&b.timestamp))
+    });
+    messages.truncate(n);
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn response(
+        n: usize,
+        id: &str,
+        content: serde_json::Value,
+        usage: serde_json::Value,
+    ) -> String {
+        json!({
+            "uuid": format!("a{n}"),
+            "sessionId": "s1",
+            "timestamp": format!("2025-01-01T00:00:{n:02}Z"),
+            "type": "assistant",
+            "message": {
+                "id": id,
+                "role": "assistant",
+                "model": "claude-sonnet-4-20250514",
+                "content": content,
+                "usage": usage
+            }
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_top_expensive_messages_ranks_responses() {
+        let temp = TempDir::new().unwrap();
+        let project_dir = temp.path().join("-Users-me-demo");
+        fs::create_dir_all(&project_dir).unwrap();
+        let cache_write = json!({"input_tokens": 10, "cache_creation_input_tokens": 400_000, "output_tokens": 100});
+        let lines = [
+            response(
+                1,
+                "msg_1",
+                json!([{"type": "text", "text": "Small answer"}]),
+                json!({"input_tokens": 100, "output_tokens": 50}),
+            ),
+            response(
+                2,
+                "msg_2",
+                json!([{"type": "text", "text": "Reading everything"}]),
+                cache_write.clone(),
+            ),
+            // Second part of the same streamed response
+            response(
+                3,
+                "msg_2",
+                json!([{"type": "tool_use", "id": "t1", "name": "Read", "input": {}}]),
+                cache_write,
+            ),
+            response(
+                4,
+                "msg_3",
+                json!([{"type": "text", "text": "Long essay"}]),
+                json!({"input_tokens": 10, "output_tokens": 60_000}),
+            ),
+        ];
+        fs::write(project_dir.join("s1.jsonl"), lines.join("\n")).unwrap();
+
+        let top = get_top_expensive_messages(
+            "project".to_string(),
+            project_dir.to_string_lossy().to_string(),
+            Some(2),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].message_uuid, "a2");
+        assert_eq!(top[0].project_name, "demo");
+        assert_eq!(top[0].dominant_cost, "cache_write");
+        assert_eq!(top[0].preview, "Reading everything [Read]");
+        assert_eq!(top[1].message_uuid, "a4");
+        assert_eq!(top[1].dominant_cost, "output");
+        assert!(top[0].cost_usd > top[1].cost_usd);
+
+        assert!(
+            get_top_expensive_messages("galaxy".to_string(), String::new(), None)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,282 @@
+//! claude.ai conversation export
+//!
+//! Produces the structure used by claude.ai's `conversations.json` data export
+//! so a CLI session can be referenced or continued in the web UI. The web
+//! format has no tool calls, so they are flattened into assistant text:
+//! - Consecutive entries of the same sender are merged into one chat message
+//! - `tool_use` blocks become `[Tool: Name]` lines with their JSON input
+//! - `tool_result` blocks are folded into the assistant turn that requested them
+//! - Thinking, sidechain, system and progress entries are dropped
+
+use super::document::{parse_export_messages, with_session_data};
+use super::ordering::sort_messages_for_export;
+use crate::commands::retry_loops::{tool_result_text, truncate_chars};
+use crate::commands::session::is_genuine_user_text;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{
+    ClaudeAiChatMessage, ClaudeAiContent, ClaudeAiConversation, ClaudeMessage, MessageSelection,
+};
+use crate::utils::resolve_session_file;
+
+/// Maximum characters kept from a single tool result
+const MAX_TOOL_RESULT_CHARS: usize = 2000;
+
+/// Maximum characters of the first prompt used as the conversation name
+const MAX_NAME_CHARS: usize = 80;
+
+fn text_block(text: String) -> ClaudeAiContent {
+    ClaudeAiContent {
+        content_type: "text".to_string(),
+        text,
+    }
+}
+
+/// Convert one message into (sender, content blocks); None if nothing is exportable
+fn convert_message(message: &ClaudeMessage) -> Option<(&'static str, Vec<ClaudeAiContent>)> {
+    match message.message_type.as_str() {
+        "user" => match message.content.as_ref()? {
+            serde_json::Value::String(text) => is_genuine_user_text(text)
+                .then(|| ("human", vec![text_block(text.trim().to_string())])),
+            serde_json::Value::Array(items) => {
+                let mut human = Vec::new();
+                let mut tool_results = Vec::new();
+                for item in items {
+                    match item.get("type").and_then(|v| v.as_str()) {
+                        Some("text") => {
+                            if let Some(text) = item.get("text").and_then(|v| v.as_str()) {
+                                if is_genuine_user_text(text) {
+                                    human.push(text_block(text.trim().to_string()));
+                                }
+                            }
+                        }
+                        Some("tool_result") => {
+                            let text = tool_result_text(item).unwrap_or_default();
+                            let label = if item.get("is_error").and_then(serde_json::Value::as_bool)
+                                == Some(true)
+                            {
+                                "Tool error"
+                            } else {
+                                "Tool result"
+                            };
+                            tool_results.push(text_block(format!(
+                                "[{label}]\n{}",
+                                truncate_chars(text.trim(), MAX_TOOL_RESULT_CHARS)
+                            )));
+                        }
+                        _ => {}
+                    }
+                }
+                // Tool results are the CLI's side of the assistant's turn
+                if !human.is_empty() {
+                    Some(("human", human))
+                } else if !tool_results.is_empty() {
+                    Some(("assistant", tool_results))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        },
+        "assistant" => {
+            let blocks: Vec<ClaudeAiContent> = match message.content.as_ref()? {
+                serde_json::Value::String(text) => vec![text_block(text.clone())],
+                serde_json::Value::Array(items) => items
+                    .iter()
+                    .filter_map(|item| match item.get("type").and_then(|v| v.as_str()) {
+                        Some("text") => item
+                            .get("text")
+                            .and_then(|v| v.as_str())
+                            .filter(|text| !text.trim().is_empty())
+                            .map(|text| text_block(text.to_string())),
+                        Some("tool_use") => {
+                            let name = item
+                                .get("name")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("unknown");
+                            let input = item
+                                .get("input")
+                                .map(|input| {
+                                    serde_json::to_string_pretty(input).unwrap_or_default()
+                                })
+                                .unwrap_or_default();
+                            Some(text_block(format!("[Tool: {name}]\n{input}")))
+                        }
+                        _ => None,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            (!blocks.is_empty()).then_some(("assistant", blocks))
+        }
+        _ => None,
+    }
+}
+
+/// Build a claude.ai conversation from a session's messages
+pub fn build_claude_ai_conversation(
+    session_id: &str,
+    messages: &[ClaudeMessage],
+) -> ClaudeAiConversation {
+    let mut ordered: Vec<ClaudeMessage> = messages
+        .iter()
+        .filter(|m| !m.is_sidechain.unwrap_or(false))
+        .cloned()
+        .collect();
+    sort_messages_for_export(&mut ordered);
+
+    let mut chat_messages: Vec<ClaudeAiChatMessage> = Vec::new();
+    for message in &ordered {
+        let Some((sender, content)) = convert_message(message) else {
+            continue;
+        };
+
+        match chat_messages.last_mut() {
+            Some(last) if last.sender == sender => {
+                last.content.extend(content);
+                last.updated_at.clone_from(&message.timestamp);
+            }
+            _ => chat_messages.push(ClaudeAiChatMessage {
+                uuid: message.uuid.clone(),
+                text: String::new(),
+                content,
+                sender: sender.to_string(),
+                created_at: message.timestamp.clone(),
+                updated_at: message.timestamp.clone(),
+                attachments: Vec::new(),
+                files: Vec::new(),
+            }),
+        }
+    }
+
+    for chat_message in &mut chat_messages {
+        chat_message.text = chat_message
+            .content
+            .iter()
+            .map(|block| block.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+    }
+
+    let name = chat_messages
+        .iter()
+        .find(|m| m.sender == "human")
+        .and_then(|m| m.text.lines().find(|line| !line.trim().is_empty()))
+        .map(|line| truncate_chars(line.trim(), MAX_NAME_CHARS))
+        .unwrap_or_else(|| format!("Claude Code session {session_id}"));
+
+    ClaudeAiConversation {
+        uuid: session_id.to_string(),
+        name,
+        summary: String::new(),
+        created_at: ordered
+            .first()
+            .map(|m| m.timestamp.clone())
+            .unwrap_or_default(),
+        updated_at: ordered
+            .last()
+            .map(|m| m.timestamp.clone())
+            .unwrap_or_default(),
+        chat_messages,
+    }
+}
+
+/// Export a session in the claude.ai conversation import format
+///
+/// The result is a single conversation; wrap it in an array to produce a
+/// `conversations.json` file. Secrets are masked (see `crate::redaction`)
+/// when `redact` is true. `selection`
+/// limits the export to part of the session.
+#[tauri::command]
+pub async fn export_session_claude_ai(
+    session_id: String,
+    project_path: String,
+    redact: Option<bool>,
+    selection: Option<MessageSelection>,
+) -> Result<ClaudeAiConversation, AppError> {
+    let _timer = OperationTimer::start("export_session_claude_ai");
+
+    let redact = redact.unwrap_or(false);
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+    with_session_data(&session_path, move |session_path, data| {
+        let messages = parse_export_messages(session_path, data, redact, selection.as_ref())?;
+        Ok(build_claude_ai_conversation(&session_id, &messages))
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MessageBuilder;
+    use serde_json::json;
+
+    #[test]
+    fn test_conversation_merges_tool_turns_into_assistant() {
+        let messages = vec![
+            MessageBuilder::user()
+                .with_uuid("u1")
+                .with_timestamp("2025-01-01T00:00:00Z")
+                .with_text_content("Fix the build\nIt fails on CI")
+                .build(),
+            MessageBuilder::assistant()
+                .with_uuid("a1")
+                .with_timestamp("2025-01-01T00:00:01Z")
+                .with_content(json!([
+                    {"type": "thinking", "thinking": "hmm"},
+                    {"type": "text", "text": "Let me look."},
+                    {"type": "tool_use", "id": "t1", "name": "Bash", "input": {"command": "cargo build"}}
+                ]))
+                .build(),
+            MessageBuilder::user()
+                .with_uuid("u2")
+                .with_timestamp("2025-01-01T00:00:02Z")
+                .with_content(json!([
+                    {"type": "tool_result", "tool_use_id": "t1", "content": "error[E0308]", "is_error": true}
+                ]))
+                .build(),
+            MessageBuilder::assistant()
+                .with_uuid("a2")
+                .with_timestamp("2025-01-01T00:00:03Z")
+                .with_text_content("Fixed.")
+                .build(),
+        ];
+
+        let conversation = build_claude_ai_conversation("s1", &messages);
+
+        assert_eq!(conversation.name, "Fix the build");
+        assert_eq!(conversation.created_at, "2025-01-01T00:00:00Z");
+        assert_eq!(conversation.chat_messages.len(), 2);
+
+        let assistant = &conversation.chat_messages[1];
+        assert_eq!(assistant.sender, "assistant");
+        assert_eq!(assistant.uuid, "a1");
+        assert_eq!(assistant.content.len(), 4);
+        assert!(assistant.text.contains("[Tool: Bash]"));
+        assert!(assistant.text.contains("[Tool error]\nerror[E0308]"));
+        assert!(!assistant.text.contains("hmm"));
+        assert_eq!(assistant.updated_at, "2025-01-01T00:00:03Z");
+    }
+
+    #[test]
+    fn test_conversation_skips_system_and_sidechain_messages() {
+        let mut sidechain = MessageBuilder::assistant()
+            .with_text_content("subagent work")
+            .build();
+        sidechain.is_sidechain = Some(true);
+
+        let messages = vec![
+            MessageBuilder::user()
+                .with_text_content("<command-name>/clear</command-name>")
+                .build(),
+            sidechain,
+            MessageBuilder::assistant().with_text_content("Hi").build(),
+        ];
+
+        let conversation = build_claude_ai_conversation("s1", &messages);
+
+        assert_eq!(conversation.chat_messages.len(), 1);
+        assert_eq!(conversation.chat_messages[0].text, "Hi");
+        assert_eq!(conversation.name, "Claude Code session s1");
+    }
+}
@@ -0,0 +1,270 @@
+//! CSV statistics export
+//!
+//! Writes per-session token stats, daily stats and model stats as CSV files
+//! (RFC 4180 quoting, header row first) for spreadsheets and BI tools.
+
+use super::document::save_with_dialog;
+use crate::commands::stats::{
+    extract_session_token_stats_sync, get_global_stats_summary, get_project_stats_summary,
+};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{DailyStats, ModelStats, SessionTokenStats};
+use crate::redaction::redact_text;
+use crate::utils::collect_jsonl_files_async;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// A stats struct that can be written as a CSV row
+trait CsvRecord {
+    const HEADER: &'static [&'static str];
+
+    fn fields(&self) -> Vec<String>;
+}
+
+impl CsvRecord for SessionTokenStats {
+    const HEADER: &'static [&'static str] = &[
+        "session_id",
+        "project_name",
+        "summary",
+        "first_message_time",
+        "last_message_time",
+        "message_count",
+        "raw_message_count",
+        "input_tokens",
+        "output_tokens",
+        "cache_creation_tokens",
+        "cache_read_tokens",
+        "total_tokens",
+        "cost_usd",
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.session_id.clone(),
+            self.project_name.clone(),
+            self.summary.clone().unwrap_or_default(),
+            self.first_message_time.clone(),
+            self.last_message_time.clone(),
+            self.message_count.to_string(),
+            self.raw_message_count.to_string(),
+            self.total_input_tokens.to_string(),
+            self.total_output_tokens.to_string(),
+            self.total_cache_creation_tokens.to_string(),
+            self.total_cache_read_tokens.to_string(),
+            self.total_tokens.to_string(),
+            format!("{:.6}", self.total_cost_usd),
+        ]
+    }
+}
+
+impl CsvRecord for DailyStats {
+    const HEADER: &'static [&'static str] = &[
+        "date",
+        "total_tokens",
+        "input_tokens",
+        "output_tokens",
+        "message_count",
+        "session_count",
+        "active_hours",
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.date.clone(),
+            self.total_tokens.to_string(),
+            self.input_tokens.to_string(),
+            self.output_tokens.to_string(),
+            self.message_count.to_string(),
+            self.session_count.to_string(),
+            self.active_hours.to_string(),
+        ]
+    }
+}
+
+impl CsvRecord for ModelStats {
+    const HEADER: &'static [&'static str] = &[
+        "model_name",
+        "message_count",
+        "token_count",
+        "input_tokens",
+        "output_tokens",
+        "cache_creation_tokens",
+        "cache_read_tokens",
+        "cost_usd",
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.model_name.clone(),
+            self.message_count.to_string(),
+            self.token_count.to_string(),
+            self.input_tokens.to_string(),
+            self.output_tokens.to_string(),
+            self.cache_creation_tokens.to_string(),
+            self.cache_read_tokens.to_string(),
+            format!("{:.6}", self.cost_usd),
+        ]
+    }
+}
+
+/// Quote a field if it contains a delimiter, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn csv_line<S: AsRef<str>>(fields: &[S]) -> String {
+    let fields: Vec<String> = fields.iter().map(|f| csv_field(f.as_ref())).collect();
+    fields.join(",") + "\r\n"
+}
+
+fn to_csv<T: CsvRecord>(rows: &[T]) -> String {
+    let mut out = csv_line(T::HEADER);
+    for row in rows {
+        out.push_str(&csv_line(&row.fields()));
+    }
+    out
+}
+
+/// Export the token stats of every session of a project as CSV
+///
+/// Returns the written path, or None if the dialog was cancelled. Secrets in
+/// session summaries are masked (see `crate::redaction`) when `redact` is
+/// true; the other stats exports hold no free text.
+#[tauri::command]
+pub async fn export_session_token_stats_csv(
+    app: tauri::AppHandle,
+    project_path: String,
+    redact: Option<bool>,
+) -> Result<Option<String>, AppError> {
+    let _timer = OperationTimer::start("export_session_token_stats_csv");
+
+    let session_files: Vec<PathBuf> = collect_jsonl_files_async(Path::new(&project_path))
+        .await
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+    let mut stats: Vec<SessionTokenStats> = tauri::async_runtime::spawn_blocking(move || {
+        session_files
+            .par_iter()
+            .filter_map(extract_session_token_stats_sync)
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?;
+    stats.sort_by(|a, b| {
+        a.first_message_time
+            .cmp(&b.first_message_time)
+            .then_with(|| a.session_id.cmp(&b.session_id))
+    });
+    if redact.unwrap_or(false) {
+        for session in &mut stats {
+            session.summary = session.summary.as_deref().map(redact_text);
+        }
+    }
+
+    Ok(save_with_dialog(
+        app,
+        "Export session token stats",
+        "session-token-stats.csv".to_string(),
+        ("CSV", "csv"),
+        to_csv(&stats),
+    )
+    .await?)
+}
+
+/// Export daily stats as CSV
+///
+/// `scope` is "project" (`path` is a project directory) or "global" (`path`
+/// is the Claude folder). Returns the written path, or None if the dialog
+/// was cancelled.
+#[tauri::command]
+pub async fn export_daily_stats_csv(
+    app: tauri::AppHandle,
+    scope: String,
+    path: String,
+) -> Result<Option<String>, AppError> {
+    let _timer = OperationTimer::start("export_daily_stats_csv");
+
+    let daily_stats = match scope.as_str() {
+        "project" => get_project_stats_summary(path).await?.daily_stats,
+        "global" => {
+            get_global_stats_summary(path, None, None)
+                .await?
+                .daily_stats
+        }
+        other => {
+            return Err(AppError::invalid_input(format!(
+                "Unsupported stats scope: {other}"
+            )))
+        }
+    };
+
+    Ok(save_with_dialog(
+        app,
+        "Export daily stats",
+        "daily-stats.csv".to_string(),
+        ("CSV", "csv"),
+        to_csv(&daily_stats),
+    )
+    .await?)
+}
+
+/// Export per-model usage of all projects as CSV
+///
+/// Returns the written path, or None if the dialog was cancelled.
+#[tauri::command]
+pub async fn export_model_stats_csv(
+    app: tauri::AppHandle,
+    claude_path: String,
+) -> Result<Option<String>, AppError> {
+    let _timer = OperationTimer::start("export_model_stats_csv");
+
+    let model_stats = get_global_stats_summary(claude_path, None, None)
+        .await?
+        .model_distribution;
+
+    Ok(save_with_dialog(
+        app,
+        "Export model stats",
+        "model-stats.csv".to_string(),
+        ("CSV", "csv"),
+        to_csv(&model_stats),
+    )
+    .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\"\nbye"), "\"say \"\"hi\"\"\nbye\"");
+    }
+
+    #[test]
+    fn test_to_csv_writes_header_and_rows() {
+        let rows = vec![DailyStats {
+            date: "2025-01-01".to_string(),
+            total_tokens: 300,
+            input_tokens: 100,
+            output_tokens: 200,
+            message_count: 4,
+            session_count: 1,
+            active_hours: 2,
+        }];
+
+        assert_eq!(
+            to_csv(&rows),
+            "date,total_tokens,input_tokens,output_tokens,message_count,session_count,active_hours\r\n\
+             2025-01-01,300,100,200,4,1,2\r\n"
+        );
+    }
+}
@@ -0,0 +1,393 @@
+//! Shared building blocks of the document exporters (Markdown, HTML, PDF)
+//!
+//! Turns the main conversation of a session into a transcript of merged
+//! turns that each exporter renders in its own format, and writes finished
+//! documents to a path chosen in a save dialog:
+//! - Consecutive entries of the same sender are merged into one turn
+//! - Tool results are folded into the assistant turn that requested them
+//! - Thinking, sidechain, system and progress entries are dropped
+
+use super::ordering::{compare_messages_for_export, sort_messages_for_export};
+use super::selection::select_messages;
+use crate::commands::retry_loops::{tool_result_text, truncate_chars};
+use crate::commands::session::{
+    is_genuine_user_text, parse_session_messages, read_session_file_async, read_session_messages,
+};
+use crate::commands::stats::{parse_raw_log_entries, ResponseUsageTracker};
+use crate::errors::AppError;
+use crate::models::{ClaudeMessage, MessageSelection};
+use crate::pricing::estimate_cost_usd;
+use crate::redaction::redact_messages;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri_plugin_dialog::DialogExt;
+
+/// Maximum characters kept from a single tool result
+const MAX_TOOL_RESULT_CHARS: usize = 2000;
+
+/// Maximum characters of the first prompt used as the document title
+const MAX_TITLE_CHARS: usize = 80;
+
+/// One block of a turn
+pub(super) enum Part {
+    /// Message text (Markdown as written by the user or model)
+    Text(String),
+    ToolUse {
+        name: String,
+        input: String, // Pretty-printed JSON
+    },
+    ToolResult {
+        is_error: bool,
+        tool_name: Option<String>,
+        text: String, // Truncated
+    },
+}
+
+/// Consecutive blocks of the same sender
+pub(super) struct Turn {
+    pub sender: &'static str, // "User" or "Assistant"
+    pub timestamp: String,    // Of the first message of the turn
+    pub model: Option<String>,
+    pub parts: Vec<Part>,
+}
+
+/// The main conversation of a session, ready to be rendered
+pub(super) struct Transcript {
+    pub title: String,
+    pub started: Option<String>,
+    pub ended: Option<String>,
+    pub turns: Vec<Turn>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_write_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cost_usd: f64,
+}
+
+/// Append `part` to the last turn if it has the same sender, else start a new turn
+fn push_part(turns: &mut Vec<Turn>, sender: &'static str, message: &ClaudeMessage, part: Part) {
+    match turns.last_mut() {
+        Some(turn) if turn.sender == sender => {
+            if turn.model.is_none() {
+                turn.model.clone_from(&message.model);
+            }
+            turn.parts.push(part);
+        }
+        _ => turns.push(Turn {
+            sender,
+            timestamp: message.timestamp.clone(),
+            model: message.model.clone(),
+            parts: vec![part],
+        }),
+    }
+}
+
+/// Add the blocks of one message to the conversation turns
+fn add_message(message: &ClaudeMessage, tool_names: &HashMap<&str, &str>, turns: &mut Vec<Turn>) {
+    let sender = match message.message_type.as_str() {
+        "user" => "User",
+        "assistant" => "Assistant",
+        _ => return,
+    };
+    let keep_text = |text: &str| {
+        !text.trim().is_empty() && (sender == "Assistant" || is_genuine_user_text(text))
+    };
+    let items = match message.content.as_ref() {
+        Some(serde_json::Value::String(text)) => {
+            if keep_text(text) {
+                push_part(turns, sender, message, Part::Text(text.trim().to_string()));
+            }
+            return;
+        }
+        Some(serde_json::Value::Array(items)) => items,
+        _ => return,
+    };
+
+    for item in items {
+        match item.get("type").and_then(|v| v.as_str()) {
+            Some("text") => {
+                if let Some(text) = item.get("text").and_then(|v| v.as_str()) {
+                    if keep_text(text) {
+                        push_part(turns, sender, message, Part::Text(text.trim().to_string()));
+                    }
+                }
+            }
+            Some("tool_use") => push_part(
+                turns,
+                "Assistant",
+                message,
+                Part::ToolUse {
+                    name: item
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("unknown")
+                        .to_string(),
+                    input: item
+                        .get("input")
+                        .map(|input| serde_json::to_string_pretty(input).unwrap_or_default())
+                        .unwrap_or_default(),
+                },
+            ),
+            Some("tool_result") => {
+                let text = tool_result_text(item).unwrap_or_default();
+                push_part(
+                    turns,
+                    "Assistant",
+                    message,
+                    Part::ToolResult {
+                        is_error: item.get("is_error").and_then(serde_json::Value::as_bool)
+                            == Some(true),
+                        tool_name: item
+                            .get("tool_use_id")
+                            .and_then(|v| v.as_str())
+                            .and_then(|id| tool_names.get(id))
+                            .map(|name| (*name).to_string()),
+                        text: truncate_chars(text.trim(), MAX_TOOL_RESULT_CHARS)
+                            .trim()
+                            .to_string(),
+                    },
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Build the transcript of the main conversation of a session
+///
+/// `cwd` is the session's working directory, used to match pricing overrides.
+pub(super) fn build_transcript(
+    session_id: &str,
+    messages: &[ClaudeMessage],
+    cwd: Option<&str>,
+) -> Transcript {
+    let mut ordered: Vec<&ClaudeMessage> = messages
+        .iter()
+        .filter(|m| !m.is_sidechain.unwrap_or(false))
+        .collect();
+    ordered.sort_by(|a, b| compare_messages_for_export(a, b));
+
+    let tool_names: HashMap<&str, &str> = ordered
+        .iter()
+        .filter_map(|m| m.content.as_ref()?.as_array())
+        .flatten()
+        .filter(|item| item.get("type").and_then(|v| v.as_str()) == Some("tool_use"))
+        .filter_map(|item| Some((item.get("id")?.as_str()?, item.get("name")?.as_str()?)))
+        .collect();
+
+    let mut transcript = Transcript {
+        title: String::new(),
+        started: ordered.first().map(|m| m.timestamp.clone()),
+        ended: ordered.last().map(|m| m.timestamp.clone()),
+        turns: Vec::new(),
+        input_tokens: 0,
+        output_tokens: 0,
+        cache_write_tokens: 0,
+        cache_read_tokens: 0,
+        cost_usd: 0.0,
+    };
+    let mut responses = ResponseUsageTracker::default();
+    for message in &ordered {
+        add_message(message, &tool_names, &mut transcript.turns);
+        if message.message_type != "assistant" {
+            continue;
+        }
+        if let Some(usage) = responses.usage_of(message) {
+            transcript.input_tokens += u64::from(usage.input_tokens.unwrap_or(0));
+            transcript.output_tokens += u64::from(usage.output_tokens.unwrap_or(0));
+            transcript.cache_write_tokens +=
+                u64::from(usage.cache_creation_input_tokens.unwrap_or(0));
+            transcript.cache_read_tokens += u64::from(usage.cache_read_input_tokens.unwrap_or(0));
+            transcript.cost_usd += estimate_cost_usd(message.model.as_deref(), cwd, &usage);
+        }
+    }
+
+    transcript.title = transcript
+        .turns
+        .iter()
+        .filter(|turn| turn.sender == "User")
+        .flat_map(|turn| &turn.parts)
+        .find_map(|part| match part {
+            Part::Text(text) => text.lines().find(|line| !line.trim().is_empty()),
+            _ => None,
+        })
+        .map(|line| truncate_chars(line.trim(), MAX_TITLE_CHARS))
+        .unwrap_or_else(|| format!("Claude Code session {session_id}"));
+    transcript
+}
+
+/// Messages of a session file, with secrets masked when `redact` is set,
+/// in conversation order, limited to `selection` if given
+pub(super) fn read_export_messages(
+    session_path: &Path,
+    redact: bool,
+    selection: Option<&MessageSelection>,
+) -> Result<Vec<ClaudeMessage>, String> {
+    prepare_export_messages(read_session_messages(session_path)?, redact, selection)
+}
+
+/// Same as `read_export_messages`, for a session file already read into memory
+pub(super) fn parse_export_messages(
+    session_path: &Path,
+    data: &[u8],
+    redact: bool,
+    selection: Option<&MessageSelection>,
+) -> Result<Vec<ClaudeMessage>, String> {
+    prepare_export_messages(
+        parse_session_messages(session_path, data),
+        redact,
+        selection,
+    )
+}
+
+fn prepare_export_messages(
+    mut messages: Vec<ClaudeMessage>,
+    redact: bool,
+    selection: Option<&MessageSelection>,
+) -> Result<Vec<ClaudeMessage>, String> {
+    sort_messages_for_export(&mut messages);
+    let mut messages = select_messages(messages, selection, |m| Some(m.uuid.as_str()))?;
+    if redact {
+        redact_messages(&mut messages);
+    }
+    Ok(messages)
+}
+
+/// Working directory recorded in a session file's content (for pricing overrides)
+pub(super) fn session_cwd(data: &[u8]) -> Option<String> {
+    parse_raw_log_entries(data)
+        .into_iter()
+        .find_map(|entry| entry.cwd)
+}
+
+/// Read a session file with async IO, then build an export from its content
+/// on the blocking pool
+pub(super) async fn with_session_data<T: Send + 'static>(
+    session_path: &Path,
+    build: impl FnOnce(&Path, &[u8]) -> Result<T, String> + Send + 'static,
+) -> Result<T, AppError> {
+    let data = read_session_file_async(session_path).await?;
+    let session_path = session_path.to_path_buf();
+    Ok(
+        tauri::async_runtime::spawn_blocking(move || build(&session_path, &data))
+            .await
+            .map_err(|e| format!("Task join error: {e}"))??,
+    )
+}
+
+/// Ask for a target path in a save dialog and write `content` there
+///
+/// Returns the written path, or None if the dialog was cancelled.
+pub(super) async fn save_with_dialog(
+    app: tauri::AppHandle,
+    title: &'static str,
+    file_name: String,
+    filter: (&'static str, &'static str), // (name, extension)
+    content: impl AsRef<[u8]>,
+) -> Result<Option<String>, String> {
+    let target = tauri::async_runtime::spawn_blocking(move || {
+        app.dialog()
+            .file()
+            .set_title(title)
+            .set_file_name(file_name)
+            .add_filter(filter.0, &[filter.1])
+            .blocking_save_file()
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?;
+    let Some(target) = target else {
+        return Ok(None);
+    };
+    let target = target
+        .into_path()
+        .map_err(|e| format!("Invalid export path: {e}"))?;
+
+    tokio::fs::write(&target, content)
+        .await
+        .map_err(|e| format!("Failed to write export: {e}"))?;
+    Ok(Some(target.to_string_lossy().to_string()))
+}
+
+/// Ask for a target directory in a folder dialog
+///
+/// Returns None if the dialog was cancelled.
+pub(super) async fn pick_folder_with_dialog(
+    app: tauri::AppHandle,
+    title: &'static str,
+) -> Result<Option<PathBuf>, String> {
+    let folder = tauri::async_runtime::spawn_blocking(move || {
+        app.dialog().file().set_title(title).blocking_pick_folder()
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?;
+    folder
+        .map(|folder| {
+            folder
+                .into_path()
+                .map_err(|e| format!("Invalid export directory: {e}"))
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use crate::test_utils::MessageBuilder;
+    use serde_json::json;
+
+    /// A short session with a failing tool call, shared by the exporter tests
+    pub(in crate::commands::export) fn sample_session() -> Vec<ClaudeMessage> {
+        vec![
+            MessageBuilder::user()
+                .with_uuid("u1")
+                .with_timestamp("2025-01-01T00:00:00Z")
+                .with_text_content("Fix the build\nIt fails on CI")
+                .build(),
+            MessageBuilder::assistant()
+                .with_uuid("a1")
+                .with_timestamp("2025-01-01T00:00:01Z")
+                .with_message_id("msg_1")
+                .with_usage(100, 20)
+                .with_content(json!([
+                    {"type": "thinking", "thinking": "hmm"},
+                    {"type": "text", "text": "Let me look."},
+                    {"type": "tool_use", "id": "t1", "name": "Bash", "input": {"command": "cargo build"}}
+                ]))
+                .build(),
+            MessageBuilder::user()
+                .with_uuid("u2")
+                .with_timestamp("2025-01-01T00:00:02Z")
+                .with_content(json!([
+                    {"type": "tool_result", "tool_use_id": "t1", "content": "error[E0308]", "is_error": true}
+                ]))
+                .build(),
+            MessageBuilder::assistant()
+                .with_uuid("a2")
+                .with_timestamp("2025-01-01T00:00:03Z")
+                .with_message_id("msg_2")
+                .with_usage(150, 30)
+                .with_text_content("Fixed.")
+                .build(),
+        ]
+    }
+
+    #[test]
+    fn test_build_transcript_merges_turns() {
+        let transcript = build_transcript("s1", &sample_session(), None);
+
+        assert_eq!(transcript.title, "Fix the build");
+        assert_eq!(transcript.turns.len(), 2);
+        assert_eq!(transcript.turns[1].timestamp, "2025-01-01T00:00:01Z");
+        assert_eq!(
+            transcript.turns[1].model.as_deref(),
+            Some("claude-opus-4-20250514")
+        );
+        assert_eq!(transcript.turns[1].parts.len(), 4);
+        assert!(matches!(
+            &transcript.turns[1].parts[2],
+            Part::ToolResult { is_error: true, tool_name: Some(name), .. } if name == "Bash"
+        ));
+        assert_eq!(transcript.input_tokens, 250);
+        assert_eq!(transcript.output_tokens, 50);
+    }
+}
@@ -0,0 +1,513 @@
+//! Standalone HTML session export
+//!
+//! Renders the main conversation of a session as a single self-contained
+//! HTML file (inline CSS, no scripts or external resources) that opens in
+//! any browser:
+//! - Fenced code blocks in message text are syntax highlighted
+//! - Tool calls and tool results are collapsible `<details>` sections
+//! - A footer sums the session's tokens and estimated cost
+
+use super::document::{
+    build_transcript, parse_export_messages, save_with_dialog, session_cwd, with_session_data, Part,
+};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ClaudeMessage, MessageSelection};
+use crate::utils::resolve_session_file;
+use std::fmt::Write;
+
+pub(super) const STYLESHEET: &str = r"
+:root { --bg: #ffffff; --fg: #1f2328; --muted: #656d76; --border: #d0d7de;
+  --user: #ddf4ff; --code-bg: #f6f8fa; --kw: #cf222e; --str: #0a3069;
+  --num: #0550ae; --comment: #6e7781; --error: #cf222e; }
+@media (prefers-color-scheme: dark) {
+  :root { --bg: #0d1117; --fg: #e6edf3; --muted: #8d96a0; --border: #30363d;
+    --user: #12263b; --code-bg: #161b22; --kw: #ff7b72; --str: #a5d6ff;
+    --num: #79c0ff; --comment: #8b949e; --error: #f85149; }
+}
+body { margin: 0 auto; max-width: 60rem; padding: 2rem 1rem; background: var(--bg);
+  color: var(--fg); font: 15px/1.6 -apple-system, BlinkMacSystemFont, 'Segoe UI', sans-serif; }
+header .meta, footer { color: var(--muted); font-size: 0.85rem; }
+footer { border-top: 1px solid var(--border); margin-top: 2rem; padding-top: 1rem; }
+.turn { border: 1px solid var(--border); border-radius: 8px; margin: 1rem 0; padding: 0 1rem; }
+.turn.user { background: var(--user); }
+.turn h2 { font-size: 0.9rem; text-transform: uppercase; color: var(--muted); margin: 0.75rem 0; }
+pre { background: var(--code-bg); border-radius: 6px; padding: 0.75rem; overflow-x: auto;
+  font: 13px/1.45 ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; }
+code { font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; font-size: 0.9em; }
+details { margin: 0.5rem 0; }
+summary { cursor: pointer; color: var(--muted); }
+details.error summary { color: var(--error); }
+.tok-kw { color: var(--kw); } .tok-str { color: var(--str); }
+.tok-num { color: var(--num); } .tok-comment { color: var(--comment); font-style: italic; }
+";
+
+/// Escape text for use in HTML content and attribute values
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Comment and keyword syntax of a highlighted language
+struct Syntax {
+    line_comment: Option<&'static str>,
+    block_comments: bool,
+    backtick_strings: bool,
+    lifetimes: bool, // `'a` is not a string (Rust)
+    keywords: &'static [&'static str],
+}
+
+fn syntax_for(language: &str) -> Syntax {
+    const C_LIKE: Syntax = Syntax {
+        line_comment: Some("//"),
+        block_comments: true,
+        backtick_strings: false,
+        lifetimes: false,
+        keywords: &[],
+    };
+    match language.to_ascii_lowercase().as_str() {
+        "rust" | "rs" => Syntax {
+            lifetimes: true,
+            keywords: &[
+                "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else",
+                "enum", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+                "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super",
+                "trait", "true", "type", "unsafe", "use", "where", "while",
+            ],
+            ..C_LIKE
+        },
+        "js" | "jsx" | "javascript" | "ts" | "tsx" | "typescript" => Syntax {
+            backtick_strings: true,
+            keywords: &[
+                "async",
+                "await",
+                "break",
+                "case",
+                "catch",
+                "class",
+                "const",
+                "continue",
+                "default",
+                "else",
+                "export",
+                "extends",
+                "false",
+                "for",
+                "from",
+                "function",
+                "if",
+                "import",
+                "in",
+                "instanceof",
+                "interface",
+                "let",
+                "new",
+                "null",
+                "of",
+                "return",
+                "switch",
+                "this",
+                "throw",
+                "true",
+                "try",
+                "type",
+                "typeof",
+                "undefined",
+                "var",
+                "while",
+            ],
+            ..C_LIKE
+        },
+        "go" => Syntax {
+            keywords: &[
+                "break",
+                "case",
+                "chan",
+                "const",
+                "continue",
+                "default",
+                "defer",
+                "else",
+                "false",
+                "for",
+                "func",
+                "go",
+                "if",
+                "import",
+                "interface",
+                "map",
+                "nil",
+                "package",
+                "range",
+                "return",
+                "select",
+                "struct",
+                "switch",
+                "true",
+                "type",
+                "var",
+            ],
+            ..C_LIKE
+        },
+        "python" | "py" => Syntax {
+            line_comment: Some("#"),
+            block_comments: false,
+            backtick_strings: false,
+            lifetimes: false,
+            keywords: &[
+                "and", "as", "async", "await", "class", "def", "elif", "else", "except", "False",
+                "finally", "for", "from", "if", "import", "in", "is", "lambda", "None", "not",
+                "or", "pass", "raise", "return", "True", "try", "while", "with", "yield",
+            ],
+        },
+        "sh" | "bash" | "shell" | "zsh" | "console" => Syntax {
+            line_comment: Some("#"),
+            block_comments: false,
+            backtick_strings: false,
+            lifetimes: false,
+            keywords: &[
+                "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function",
+                "if", "in", "local", "then", "while",
+            ],
+        },
+        "json" => Syntax {
+            line_comment: None,
+            block_comments: false,
+            backtick_strings: false,
+            lifetimes: false,
+            keywords: &["true", "false", "null"],
+        },
+        _ => C_LIKE,
+    }
+}
+
+fn push_span(out: &mut String, class: &str, text: &[char]) {
+    let text: String = text.iter().collect();
+    let _ = write!(out, "<span class=\"{class}\">{}</span>", escape_html(&text));
+}
+
+/// End (exclusive) of the string literal opened at `start`, if it is closed
+fn string_end(chars: &[char], start: usize, multiline: bool) -> Option<usize> {
+    let quote = chars[start];
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '\n' if !multiline => return None,
+            c if c == quote => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Highlight source code as HTML spans (comments, strings, numbers, keywords)
+fn highlight(code: &str, language: &str) -> String {
+    let syntax = syntax_for(language);
+    let chars: Vec<char> = code.chars().collect();
+    let starts_with = |at: usize, prefix: &str| {
+        prefix
+            .chars()
+            .enumerate()
+            .all(|(k, c)| chars.get(at + k) == Some(&c))
+    };
+
+    let mut out = String::with_capacity(code.len() * 2);
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let is_lifetime = syntax.lifetimes
+            && c == '\''
+            && chars.get(i + 1) != Some(&'\\')
+            && chars.get(i + 2) != Some(&'\'');
+        if syntax
+            .line_comment
+            .is_some_and(|prefix| starts_with(i, prefix))
+        {
+            let end = chars[i..]
+                .iter()
+                .position(|&c| c == '\n')
+                .map_or(chars.len(), |p| i + p);
+            push_span(&mut out, "tok-comment", &chars[i..end]);
+            i = end;
+        } else if syntax.block_comments && starts_with(i, "/*") {
+            let end = (i + 2..chars.len())
+                .find(|&j| starts_with(j, "*/"))
+                .map_or(chars.len(), |j| j + 2);
+            push_span(&mut out, "tok-comment", &chars[i..end]);
+            i = end;
+        } else if let Some(end) =
+            (c == '"' || (c == '\'' && !is_lifetime) || (c == '`' && syntax.backtick_strings))
+                .then(|| string_end(&chars, i, c == '`'))
+                .flatten()
+        {
+            push_span(&mut out, "tok-str", &chars[i..end.min(chars.len())]);
+            i = end;
+        } else if c.is_ascii_digit() {
+            let len = chars[i..]
+                .iter()
+                .take_while(|c| c.is_ascii_alphanumeric() || **c == '.' || **c == '_')
+                .count();
+            push_span(&mut out, "tok-num", &chars[i..i + len]);
+            i += len;
+        } else if c.is_alphabetic() || c == '_' {
+            let len = chars[i..]
+                .iter()
+                .take_while(|c| c.is_alphanumeric() || **c == '_')
+                .count();
+            let word: String = chars[i..i + len].iter().collect();
+            if syntax.keywords.contains(&word.as_str()) {
+                let _ = write!(out, "<span class=\"tok-kw\">{word}</span>");
+            } else {
+                out.push_str(&escape_html(&word));
+            }
+            i += len;
+        } else {
+            out.push_str(&escape_html(&c.to_string()));
+            i += 1;
+        }
+    }
+    out
+}
+
+fn code_block(code: &str, language: &str) -> String {
+    format!(
+        "<pre><code class=\"language-{}\">{}</code></pre>\n",
+        escape_html(language),
+        highlight(code, language)
+    )
+}
+
+/// A line of prose with `inline code` spans
+fn render_inline(line: &str) -> String {
+    let segments: Vec<&str> = line.split('`').collect();
+    if segments.len() % 2 == 0 {
+        // Unbalanced backticks: show as written
+        return escape_html(line);
+    }
+    segments
+        .iter()
+        .enumerate()
+        .map(|(idx, segment)| {
+            if idx % 2 == 1 {
+                format!("<code>{}</code>", escape_html(segment))
+            } else {
+                escape_html(segment)
+            }
+        })
+        .collect()
+}
+
+fn flush_prose(out: &mut String, prose: &mut Vec<&str>) {
+    for paragraph in prose.split(|line| line.trim().is_empty()) {
+        if paragraph.is_empty() {
+            continue;
+        }
+        let lines: Vec<String> = paragraph.iter().map(|line| render_inline(line)).collect();
+        let _ = writeln!(out, "<p>{}</p>", lines.join("<br>\n"));
+    }
+    prose.clear();
+}
+
+/// Message text as HTML: fenced code blocks are highlighted, the rest is
+/// shown as paragraphs
+fn render_text(text: &str) -> String {
+    let mut out = String::new();
+    let mut prose: Vec<&str> = Vec::new();
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let Some(info) = line.trim_start().strip_prefix("```") else {
+            prose.push(line);
+            continue;
+        };
+        flush_prose(&mut out, &mut prose);
+        let fence = format!(
+            "```{}",
+            "`".repeat(info.chars().take_while(|&c| c == '`').count())
+        );
+        let language = info
+            .trim_start_matches('`')
+            .split_whitespace()
+            .next()
+            .unwrap_or("");
+        let mut code: Vec<&str> = Vec::new();
+        for line in lines.by_ref() {
+            let trimmed = line.trim();
+            if trimmed.starts_with(&fence) && trimmed.chars().all(|c| c == '`') {
+                break;
+            }
+            code.push(line);
+        }
+        out.push_str(&code_block(&code.join("\n"), language));
+    }
+    flush_prose(&mut out, &mut prose);
+    out
+}
+
+fn render_part(part: &Part) -> String {
+    match part {
+        Part::Text(text) => render_text(text),
+        Part::ToolUse { name, input } => format!(
+            "<details class=\"tool-call\"><summary>Tool: <strong>{}</strong></summary>\n{}</details>\n",
+            escape_html(name),
+            code_block(input, "json")
+        ),
+        Part::ToolResult {
+            is_error,
+            tool_name,
+            text,
+        } => {
+            let (class, label) = if *is_error {
+                ("tool-result error", "Tool error")
+            } else {
+                ("tool-result", "Tool result")
+            };
+            let summary = match tool_name {
+                Some(name) => format!("{label}: {}", escape_html(name)),
+                None => label.to_string(),
+            };
+            format!(
+                "<details class=\"{class}\"><summary>{summary}</summary>\n<pre>{}</pre></details>\n",
+                escape_html(text)
+            )
+        }
+    }
+}
+
+/// Render the main conversation of a session as a standalone HTML page
+///
+/// `cwd` is the session's working directory, used to match pricing overrides.
+pub fn render_session_html(
+    session_id: &str,
+    messages: &[ClaudeMessage],
+    cwd: Option<&str>,
+) -> String {
+    let transcript = build_transcript(session_id, messages, cwd);
+    let title = escape_html(&transcript.title);
+
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n<style>{STYLESHEET}</style>\n</head>\n<body>\n\
+         <header>\n<h1>{title}</h1>\n<p class=\"meta\">Session <code>{}</code>",
+        escape_html(session_id)
+    );
+    if let (Some(started), Some(ended)) = (&transcript.started, &transcript.ended) {
+        let _ = write!(out, " · {} – {}", escape_html(started), escape_html(ended));
+    }
+    out.push_str("</p>\n</header>\n<main>\n");
+
+    for turn in &transcript.turns {
+        let _ = write!(
+            out,
+            "<section class=\"turn {}\">\n<h2>{}</h2>\n",
+            turn.sender.to_ascii_lowercase(),
+            turn.sender
+        );
+        for part in &turn.parts {
+            out.push_str(&render_part(part));
+        }
+        out.push_str("</section>\n");
+    }
+
+    let _ = write!(
+        out,
+        "</main>\n<footer>Tokens: {} input · {} output · {} cache write · {} cache read · \
+         Estimated cost: ${:.4}</footer>\n</body>\n</html>\n",
+        transcript.input_tokens,
+        transcript.output_tokens,
+        transcript.cache_write_tokens,
+        transcript.cache_read_tokens,
+        transcript.cost_usd
+    );
+    out
+}
+
+/// Export a session as a standalone HTML file chosen in a save dialog
+///
+/// Returns the written path, or None if the dialog was cancelled. Secrets
+/// are masked (see `crate::redaction`) when `redact` is true. `selection`
+/// limits the export to part of the session.
+#[tauri::command]
+pub async fn export_session_html(
+    app: tauri::AppHandle,
+    session_id: String,
+    project_path: String,
+    redact: Option<bool>,
+    selection: Option<MessageSelection>,
+) -> Result<Option<String>, AppError> {
+    let _timer = OperationTimer::start("export_session_html");
+
+    let redact = redact.unwrap_or(false);
+    let file_name = format!("{session_id}.html");
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+    let html = with_session_data(&session_path, move |session_path, data| {
+        let messages = parse_export_messages(session_path, data, redact, selection.as_ref())?;
+        let cwd = session_cwd(data);
+        Ok(render_session_html(&session_id, &messages, cwd.as_deref()))
+    })
+    .await?;
+
+    Ok(save_with_dialog(
+        app,
+        "Export session as HTML",
+        file_name,
+        ("HTML", "html"),
+        html,
+    )
+    .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::document::tests::sample_session;
+
+    #[test]
+    fn test_highlight_marks_tokens_and_escapes() {
+        let html = highlight("let s = \"<b>\"; // done\nlet n = 42;", "rust");
+        assert_eq!(
+            html,
+            "<span class=\"tok-kw\">let</span> s = <span class=\"tok-str\">&quot;&lt;b&gt;&quot;</span>; \
+             <span class=\"tok-comment\">// done</span>\n\
+             <span class=\"tok-kw\">let</span> n = <span class=\"tok-num\">42</span>;"
+        );
+        // Rust lifetimes are not strings
+        assert!(!highlight("fn f<'a>(x: &'a str) {}", "rust").contains("tok-str"));
+    }
+
+    #[test]
+    fn test_render_text_splits_code_blocks_from_prose() {
+        let html = render_text("Run `cargo test`:\n\n```sh\ncargo test # all\n```\nDone <now>");
+        assert_eq!(
+            html,
+            "<p>Run <code>cargo test</code>:</p>\n\
+             <pre><code class=\"language-sh\">cargo test <span class=\"tok-comment\"># all</span></code></pre>\n\
+             <p>Done &lt;now&gt;</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_render_session_html_is_self_contained() {
+        let html = render_session_html("s1", &sample_session(), None);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Fix the build</title>"));
+        assert!(html
+            .contains("<details class=\"tool-result error\"><summary>Tool error: Bash</summary>"));
+        assert!(html.contains("<section class=\"turn assistant\">"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("src=\"http") && !html.contains("href=\"http"));
+        assert!(html.contains("Tokens: 250 input · 50 output"));
+    }
+}
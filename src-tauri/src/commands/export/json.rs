@@ -0,0 +1,382 @@
+//! Normalized JSON session export
+//!
+//! Emits a session in a documented schema (`NormalizedSession`) so scripts
+//! don't have to replicate the viewer's parsing of raw JSONL lines:
+//! - Parents are resolved to the nearest exported ancestor, skipping meta,
+//!   progress and snapshot entries, and every message lists its children
+//! - Usage is merged per API response and reported once, on its first entry
+//! - Tool results are decoded to text and carry the name of their tool call
+
+use super::document::{parse_export_messages, with_session_data};
+use super::ordering::sort_messages_for_export;
+use crate::commands::retry_loops::tool_result_text;
+use crate::commands::stats::parse_raw_log_entries;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{
+    ClaudeMessage, MessageSelection, NormalizedBlock, NormalizedMessage, NormalizedSession,
+    NormalizedUsage, RawLogEntry, TokenUsage,
+};
+use crate::pricing::estimate_cost_usd;
+use crate::redaction::redact_entries;
+use crate::utils::resolve_session_file;
+use std::collections::{HashMap, HashSet};
+
+/// Version of the normalized schema, bumped on breaking changes
+pub const NORMALIZED_SCHEMA_VERSION: u32 = 1;
+
+/// Merge the usage reported by two entries of the same response
+///
+/// Each entry repeats the usage of the response so far, so the field-wise
+/// maximum is the usage of the whole response.
+fn merge_usage(a: &TokenUsage, b: &TokenUsage) -> TokenUsage {
+    TokenUsage {
+        input_tokens: a.input_tokens.max(b.input_tokens),
+        output_tokens: a.output_tokens.max(b.output_tokens),
+        cache_creation_input_tokens: a
+            .cache_creation_input_tokens
+            .max(b.cache_creation_input_tokens),
+        cache_read_input_tokens: a.cache_read_input_tokens.max(b.cache_read_input_tokens),
+        service_tier: a.service_tier.clone().or_else(|| b.service_tier.clone()),
+    }
+}
+
+fn normalized_usage(usage: &TokenUsage) -> NormalizedUsage {
+    NormalizedUsage {
+        input_tokens: u64::from(usage.input_tokens.unwrap_or(0)),
+        output_tokens: u64::from(usage.output_tokens.unwrap_or(0)),
+        cache_creation_input_tokens: u64::from(usage.cache_creation_input_tokens.unwrap_or(0)),
+        cache_read_input_tokens: u64::from(usage.cache_read_input_tokens.unwrap_or(0)),
+    }
+}
+
+fn normalize_blocks(
+    message: &ClaudeMessage,
+    tool_names: &HashMap<&str, &str>,
+) -> Vec<NormalizedBlock> {
+    let str_field = |item: &serde_json::Value, key: &str| {
+        item.get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let items = match message.content.as_ref() {
+        Some(serde_json::Value::String(text)) => {
+            return vec![NormalizedBlock::Text { text: text.clone() }];
+        }
+        Some(serde_json::Value::Array(items)) => items,
+        _ => return Vec::new(),
+    };
+
+    items
+        .iter()
+        .filter_map(|item| match item.get("type").and_then(|v| v.as_str())? {
+            "text" => Some(NormalizedBlock::Text {
+                text: str_field(item, "text"),
+            }),
+            "thinking" => Some(NormalizedBlock::Thinking {
+                text: str_field(item, "thinking"),
+            }),
+            "tool_use" => Some(NormalizedBlock::ToolUse {
+                id: str_field(item, "id"),
+                name: str_field(item, "name"),
+                input: item
+                    .get("input")
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Null),
+            }),
+            "tool_result" => {
+                let tool_use_id = str_field(item, "tool_use_id");
+                Some(NormalizedBlock::ToolResult {
+                    tool_name: tool_names
+                        .get(tool_use_id.as_str())
+                        .map(|name| (*name).to_string()),
+                    tool_use_id,
+                    is_error: item.get("is_error").and_then(serde_json::Value::as_bool)
+                        == Some(true),
+                    text: tool_result_text(item).unwrap_or_default(),
+                    details: message.tool_use_result.clone(),
+                })
+            }
+            "image" => Some(NormalizedBlock::Image {
+                media_type: item
+                    .get("source")
+                    .and_then(|source| source.get("media_type"))
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Nearest ancestor of `parent` that is exported, walking through the
+/// parents of skipped entries
+fn resolve_parent(
+    parent: Option<&str>,
+    exported: &HashSet<&str>,
+    raw_parents: &HashMap<&str, Option<&str>>,
+) -> Option<String> {
+    let mut current = parent;
+    let mut visited: HashSet<&str> = HashSet::new();
+    while let Some(uuid) = current {
+        if exported.contains(uuid) {
+            return Some(uuid.to_string());
+        }
+        if !visited.insert(uuid) {
+            return None;
+        }
+        current = raw_parents.get(uuid).copied().flatten();
+    }
+    None
+}
+
+/// Build the normalized form of a session
+///
+/// `raw_entries` are all entries of the session file, used to resolve
+/// parents through entries that are not exported. `cwd` is the session's
+/// working directory, used to match pricing overrides.
+pub fn build_normalized_session(
+    session_id: &str,
+    messages: &[ClaudeMessage],
+    raw_entries: &[RawLogEntry],
+    cwd: Option<&str>,
+) -> NormalizedSession {
+    let mut ordered: Vec<ClaudeMessage> = messages
+        .iter()
+        .filter(|m| matches!(m.message_type.as_str(), "user" | "assistant" | "system"))
+        .cloned()
+        .collect();
+    sort_messages_for_export(&mut ordered);
+
+    let exported: HashSet<&str> = ordered.iter().map(|m| m.uuid.as_str()).collect();
+    let raw_parents: HashMap<&str, Option<&str>> = raw_entries
+        .iter()
+        .filter_map(|entry| Some((entry.uuid.as_deref()?, entry.parent_uuid.as_deref())))
+        .collect();
+    let tool_names: HashMap<&str, &str> = ordered
+        .iter()
+        .filter_map(|m| m.content.as_ref()?.as_array())
+        .flatten()
+        .filter(|item| item.get("type").and_then(|v| v.as_str()) == Some("tool_use"))
+        .filter_map(|item| Some((item.get("id")?.as_str()?, item.get("name")?.as_str()?)))
+        .collect();
+
+    // Whole usage of each response, and the entry it is reported on
+    let mut response_usage: HashMap<&str, (usize, TokenUsage)> = HashMap::new();
+    for (idx, message) in ordered.iter().enumerate() {
+        let (Some(id), Some(usage)) = (&message.message_id, &message.usage) else {
+            continue;
+        };
+        if message.message_type != "assistant" {
+            continue;
+        }
+        response_usage
+            .entry(id.as_str())
+            .and_modify(|(_, merged)| *merged = merge_usage(merged, usage))
+            .or_insert_with(|| (idx, usage.clone()));
+    }
+
+    let mut session_usage = NormalizedUsage::default();
+    let mut cost_usd = 0.0;
+    let mut normalized: Vec<NormalizedMessage> = Vec::with_capacity(ordered.len());
+    for (idx, message) in ordered.iter().enumerate() {
+        let response_id = (message.message_type == "assistant")
+            .then(|| message.message_id.clone())
+            .flatten();
+        let usage = match response_id.as_deref() {
+            Some(id) => response_usage
+                .get(id)
+                .filter(|(first, _)| *first == idx)
+                .map(|(_, usage)| usage.clone()),
+            None if message.message_type == "assistant" => message.usage.clone(),
+            None => None,
+        };
+        let usage = usage.map(|usage| {
+            cost_usd += estimate_cost_usd(message.model.as_deref(), cwd, &usage);
+            let usage = normalized_usage(&usage);
+            session_usage.input_tokens += usage.input_tokens;
+            session_usage.output_tokens += usage.output_tokens;
+            session_usage.cache_creation_input_tokens += usage.cache_creation_input_tokens;
+            session_usage.cache_read_input_tokens += usage.cache_read_input_tokens;
+            usage
+        });
+
+        normalized.push(NormalizedMessage {
+            uuid: message.uuid.clone(),
+            parent_uuid: resolve_parent(message.parent_uuid.as_deref(), &exported, &raw_parents)
+                .filter(|parent| *parent != message.uuid),
+            children: Vec::new(),
+            depth: 0,
+            role: message.message_type.clone(),
+            timestamp: message.timestamp.clone(),
+            is_sidechain: message.is_sidechain.unwrap_or(false),
+            model: message.model.clone(),
+            response_id,
+            usage,
+            blocks: normalize_blocks(message, &tool_names),
+        });
+    }
+
+    // Link children, then assign depths from the roots down
+    let index: HashMap<String, usize> = normalized
+        .iter()
+        .enumerate()
+        .map(|(idx, m)| (m.uuid.clone(), idx))
+        .collect();
+    for idx in 0..normalized.len() {
+        if let Some(&parent) = normalized[idx]
+            .parent_uuid
+            .as_ref()
+            .and_then(|parent| index.get(parent))
+        {
+            let uuid = normalized[idx].uuid.clone();
+            normalized[parent].children.push(uuid);
+        }
+    }
+    let roots: Vec<String> = normalized
+        .iter()
+        .filter(|m| m.parent_uuid.is_none())
+        .map(|m| m.uuid.clone())
+        .collect();
+    let mut stack: Vec<(usize, usize)> = roots.iter().map(|uuid| (index[uuid], 0)).collect();
+    while let Some((idx, depth)) = stack.pop() {
+        normalized[idx].depth = depth;
+        stack.extend(
+            normalized[idx]
+                .children
+                .iter()
+                .map(|child| (index[child], depth + 1)),
+        );
+    }
+
+    NormalizedSession {
+        schema_version: NORMALIZED_SCHEMA_VERSION,
+        session_id: session_id.to_string(),
+        started_at: ordered.first().map(|m| m.timestamp.clone()),
+        ended_at: ordered.last().map(|m| m.timestamp.clone()),
+        roots,
+        usage: session_usage,
+        cost_usd,
+        messages: normalized,
+    }
+}
+
+/// Export a session in the normalized JSON schema
+///
+/// Secrets are masked (see `crate::redaction`) when `redact` is true.
+/// `selection` limits the export to part of the session.
+#[tauri::command]
+pub async fn export_session_json(
+    session_id: String,
+    project_path: String,
+    redact: Option<bool>,
+    selection: Option<MessageSelection>,
+) -> Result<NormalizedSession, AppError> {
+    let _timer = OperationTimer::start("export_session_json");
+
+    let redact = redact.unwrap_or(false);
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+    with_session_data(&session_path, move |session_path, data| {
+        let messages = parse_export_messages(session_path, data, redact, selection.as_ref())?;
+        let mut raw_entries = parse_raw_log_entries(data);
+        if redact {
+            redact_entries(&mut raw_entries);
+        }
+        let cwd = raw_entries.iter().find_map(|entry| entry.cwd.clone());
+
+        Ok(build_normalized_session(
+            &session_id,
+            &messages,
+            &raw_entries,
+            cwd.as_deref(),
+        ))
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MessageBuilder;
+    use serde_json::json;
+
+    fn raw_entry(uuid: &str, parent: Option<&str>) -> RawLogEntry {
+        serde_json::from_value(json!({
+            "type": "user", "uuid": uuid, "parentUuid": parent, "isMeta": true
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_build_normalized_session() {
+        let first_block = MessageBuilder::assistant()
+            .with_uuid("a1")
+            .with_parent_uuid("meta")
+            .with_timestamp("2025-01-01T00:00:01Z")
+            .with_message_id("msg_1")
+            .with_usage(100, 5)
+            .with_content(json!([{"type": "text", "text": "Running it."}]))
+            .build();
+        let mut result = MessageBuilder::user()
+            .with_uuid("u2")
+            .with_parent_uuid("a2")
+            .with_timestamp("2025-01-01T00:00:03Z")
+            .with_content(json!([
+                {"type": "tool_result", "tool_use_id": "t1",
+                 "content": [{"type": "text", "text": "ok"}]}
+            ]))
+            .build();
+        result.tool_use_result = Some(json!({"stdout": "ok", "exitCode": 0}));
+        let messages = vec![
+            MessageBuilder::user()
+                .with_uuid("u1")
+                .with_timestamp("2025-01-01T00:00:00Z")
+                .with_text_content("Run the tests")
+                .build(),
+            first_block,
+            MessageBuilder::assistant()
+                .with_uuid("a2")
+                .with_parent_uuid("a1")
+                .with_timestamp("2025-01-01T00:00:02Z")
+                .with_message_id("msg_1")
+                .with_usage(100, 20)
+                .with_content(json!([
+                    {"type": "tool_use", "id": "t1", "name": "Bash", "input": {"command": "cargo test"}}
+                ]))
+                .build(),
+            result,
+        ];
+        // a1's parent is a meta entry that is not exported
+        let raw_entries = vec![raw_entry("u1", None), raw_entry("meta", Some("u1"))];
+
+        let session = build_normalized_session("s1", &messages, &raw_entries, None);
+
+        assert_eq!(session.schema_version, NORMALIZED_SCHEMA_VERSION);
+        assert_eq!(session.roots, vec!["u1"]);
+        let a1 = &session.messages[1];
+        assert_eq!(a1.parent_uuid.as_deref(), Some("u1"));
+        assert_eq!(a1.children, vec!["a2"]);
+        assert_eq!(a1.depth, 1);
+        assert_eq!(session.messages[3].depth, 3);
+
+        // Usage of msg_1 is merged onto its first entry
+        assert_eq!(a1.usage.as_ref().unwrap().output_tokens, 20);
+        assert!(session.messages[2].usage.is_none());
+        assert_eq!(session.usage.input_tokens, 100);
+        assert_eq!(session.usage.output_tokens, 20);
+
+        assert_eq!(
+            session.messages[3].blocks,
+            vec![NormalizedBlock::ToolResult {
+                tool_use_id: "t1".to_string(),
+                tool_name: Some("Bash".to_string()),
+                is_error: false,
+                text: "ok".to_string(),
+                details: Some(json!({"stdout": "ok", "exitCode": 0})),
+            }]
+        );
+        let json = serde_json::to_value(&session.messages[2].blocks[0]).unwrap();
+        assert_eq!(json["type"], "tool_use");
+    }
+}
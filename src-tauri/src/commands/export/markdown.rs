@@ -0,0 +1,147 @@
+//! Markdown session export
+//!
+//! Renders the main conversation of a session as a document that can be
+//! pasted into PR descriptions or wikis:
+//! - Tool calls show their JSON input; tool results are collapsed
+//!   `<details>` blocks in the assistant turn
+//! - A footer sums the session's tokens and estimated cost
+
+use super::document::{
+    build_transcript, parse_export_messages, save_with_dialog, session_cwd, with_session_data, Part,
+};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ClaudeMessage, MessageSelection};
+use crate::utils::resolve_session_file;
+use std::fmt::Write;
+
+/// Fenced code block whose fence is longer than any backtick run in `text`
+fn fenced(text: &str, language: &str) -> String {
+    let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{fence}{language}\n{text}\n{fence}")
+}
+
+fn render_part(part: &Part) -> String {
+    match part {
+        Part::Text(text) => text.clone(),
+        Part::ToolUse { name, input } => format!("**Tool: {name}**\n\n{}", fenced(input, "json")),
+        Part::ToolResult {
+            is_error,
+            tool_name,
+            text,
+        } => {
+            let label = if *is_error {
+                "Tool error"
+            } else {
+                "Tool result"
+            };
+            let summary = match tool_name {
+                Some(name) => format!("{label}: {name}"),
+                None => label.to_string(),
+            };
+            format!(
+                "<details>\n<summary>{summary}</summary>\n\n{}\n\n</details>",
+                fenced(text, "")
+            )
+        }
+    }
+}
+
+/// Render the main conversation of a session as a Markdown document
+///
+/// `cwd` is the session's working directory, used to match pricing overrides.
+pub fn render_session_markdown(
+    session_id: &str,
+    messages: &[ClaudeMessage],
+    cwd: Option<&str>,
+) -> String {
+    let transcript = build_transcript(session_id, messages, cwd);
+
+    let mut out = format!("# {}\n\nSession `{session_id}`", transcript.title);
+    if let (Some(started), Some(ended)) = (&transcript.started, &transcript.ended) {
+        let _ = write!(out, " · {started} – {ended}");
+    }
+    out.push('\n');
+
+    for turn in &transcript.turns {
+        let parts: Vec<String> = turn.parts.iter().map(render_part).collect();
+        let _ = write!(out, "\n## {}\n\n{}\n", turn.sender, parts.join("\n\n"));
+    }
+
+    let _ = write!(
+        out,
+        "\n---\n\n*Tokens: {} input · {} output · {} cache write · {} cache read · \
+         Estimated cost: ${:.4}*\n",
+        transcript.input_tokens,
+        transcript.output_tokens,
+        transcript.cache_write_tokens,
+        transcript.cache_read_tokens,
+        transcript.cost_usd
+    );
+    out
+}
+
+/// Export a session as Markdown to a file chosen in a save dialog
+///
+/// Returns the written path, or None if the dialog was cancelled. Secrets
+/// are masked (see `crate::redaction`) when `redact` is true. `selection`
+/// limits the export to part of the session.
+#[tauri::command]
+pub async fn export_session_markdown(
+    app: tauri::AppHandle,
+    session_id: String,
+    project_path: String,
+    redact: Option<bool>,
+    selection: Option<MessageSelection>,
+) -> Result<Option<String>, AppError> {
+    let _timer = OperationTimer::start("export_session_markdown");
+
+    let redact = redact.unwrap_or(false);
+    let file_name = format!("{session_id}.md");
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+    let markdown = with_session_data(&session_path, move |session_path, data| {
+        let messages = parse_export_messages(session_path, data, redact, selection.as_ref())?;
+        let cwd = session_cwd(data);
+        Ok(render_session_markdown(
+            &session_id,
+            &messages,
+            cwd.as_deref(),
+        ))
+    })
+    .await?;
+
+    Ok(save_with_dialog(
+        app,
+        "Export session as Markdown",
+        file_name,
+        ("Markdown", "md"),
+        markdown,
+    )
+    .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::document::tests::sample_session;
+
+    #[test]
+    fn test_fenced_outgrows_backticks_in_text() {
+        assert_eq!(fenced("let x = 1;", "rust"), "```rust\nlet x = 1;\n```");
+        assert_eq!(fenced("```\ncode\n```", ""), "````\n```\ncode\n```\n````");
+    }
+
+    #[test]
+    fn test_render_session_markdown_folds_tool_results() {
+        let markdown = render_session_markdown("s1", &sample_session(), None);
+
+        assert!(markdown.starts_with("# Fix the build\n\nSession `s1`"));
+        assert_eq!(markdown.matches("\n## Assistant\n").count(), 1);
+        assert!(markdown.contains("**Tool: Bash**\n\n```json\n{"));
+        assert!(markdown
+            .contains("<details>\n<summary>Tool error: Bash</summary>\n\n```\nerror[E0308]\n```"));
+        assert!(!markdown.contains("hmm"));
+        assert!(markdown.contains("*Tokens: 250 input · 50 output"));
+    }
+}
@@ -0,0 +1,46 @@
+//! Session export commands module
+//!
+//! This module contains the session exporters organized into submodules:
+//! - `ordering`: Canonical message/session ordering shared by all exporters
+//! - `claude_ai`: claude.ai conversation import format
+//! - `csv`: Statistics as CSV files for spreadsheets
+//! - `document`: Transcript and save dialog shared by the document exporters
+//! - `markdown`: Readable Markdown documents for sharing
+//! - `html`: Standalone HTML pages for sharing
+//! - `json`: Normalized JSON schema for scripts
+//! - `pdf`: Fixed-layout PDF documents for archiving
+//! - `prompt`: Prompts and replies only, to re-feed into a new session
+//! - `selection`: Partial exports of a message range or list
+//! - `share`: Encrypted single-file bundles to share a session with a teammate
+//! - `project`: Bulk export of every session of a project
+//! - `sidechain`: Standalone transcripts of a single sub-agent run
+//! - `warehouse`: Date-partitioned Parquet/NDJSON tables for data warehouses
+
+mod claude_ai;
+mod csv;
+mod document;
+mod html;
+mod json;
+mod markdown;
+mod ordering;
+mod pdf;
+mod project;
+mod prompt;
+mod selection;
+mod share;
+mod sidechain;
+mod warehouse;
+
+// Re-export all commands
+pub use claude_ai::*;
+pub use csv::*;
+pub use html::*;
+pub use json::*;
+pub use markdown::*;
+pub use ordering::*;
+pub use pdf::*;
+pub use project::*;
+pub use prompt::*;
+pub use share::*;
+pub use sidechain::*;
+pub use warehouse::*;
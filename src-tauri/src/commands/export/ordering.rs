@@ -0,0 +1,142 @@
+//! Canonical export ordering
+//!
+//! Every exporter goes through these helpers so that two exports of the same
+//! data are byte-for-byte comparable:
+//! - Messages are ordered by timestamp, then by `uuid` (never by scan order)
+//! - Sessions are ordered by first message time, then by session ID
+//! - Derived IDs (anchors, keys) are computed from message content identity only
+
+use crate::models::{ClaudeMessage, ClaudeSession};
+use chrono::{DateTime, Utc};
+use std::cmp::Ordering;
+
+/// Parse an RFC 3339 timestamp for ordering purposes
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Compare two timestamps chronologically, falling back to string order
+/// when either side is not valid RFC 3339
+fn compare_timestamps(a: &str, b: &str) -> Ordering {
+    match (parse_timestamp(a), parse_timestamp(b)) {
+        (Some(a_time), Some(b_time)) => a_time.cmp(&b_time),
+        _ => a.cmp(b),
+    }
+}
+
+/// Canonical export ordering for messages: timestamp, then uuid
+pub fn compare_messages_for_export(a: &ClaudeMessage, b: &ClaudeMessage) -> Ordering {
+    compare_timestamps(&a.timestamp, &b.timestamp).then_with(|| a.uuid.cmp(&b.uuid))
+}
+
+/// Sort messages into the canonical export order
+pub fn sort_messages_for_export(messages: &mut [ClaudeMessage]) {
+    messages.sort_by(compare_messages_for_export);
+}
+
+/// Sort sessions into the canonical export order: first message time, then session ID
+pub fn sort_sessions_for_export(sessions: &mut [ClaudeSession]) {
+    sessions.sort_by(|a, b| {
+        compare_timestamps(&a.first_message_time, &b.first_message_time)
+            .then_with(|| a.session_id.cmp(&b.session_id))
+    });
+}
+
+/// Stable anchor/key for a message in exported documents
+///
+/// Derived only from the message `uuid`, so it does not shift when other
+/// messages are added or removed. Characters outside `[A-Za-z0-9_-]` are
+/// replaced so the ID is safe to use in HTML anchors and file names.
+pub fn export_message_id(message: &ClaudeMessage) -> String {
+    let sanitized: String = message
+        .uuid
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("msg-{sanitized}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MessageBuilder;
+
+    #[test]
+    fn test_sort_messages_by_timestamp_then_uuid() {
+        let mut messages = vec![
+            MessageBuilder::user()
+                .with_uuid("b")
+                .with_timestamp("2025-01-01T00:00:01Z")
+                .build(),
+            MessageBuilder::user()
+                .with_uuid("c")
+                .with_timestamp("2025-01-01T00:00:00Z")
+                .build(),
+            MessageBuilder::user()
+                .with_uuid("a")
+                .with_timestamp("2025-01-01T00:00:01Z")
+                .build(),
+        ];
+
+        sort_messages_for_export(&mut messages);
+
+        let uuids: Vec<&str> = messages.iter().map(|m| m.uuid.as_str()).collect();
+        assert_eq!(uuids, vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_sort_messages_compares_offsets_chronologically() {
+        // 09:00+09:00 is 00:00Z, which is earlier than 01:00Z
+        let mut messages = vec![
+            MessageBuilder::user()
+                .with_uuid("later")
+                .with_timestamp("2025-01-01T01:00:00Z")
+                .build(),
+            MessageBuilder::user()
+                .with_uuid("earlier")
+                .with_timestamp("2025-01-01T09:00:00+09:00")
+                .build(),
+        ];
+
+        sort_messages_for_export(&mut messages);
+
+        assert_eq!(messages[0].uuid, "earlier");
+    }
+
+    #[test]
+    fn test_sort_is_independent_of_input_order() {
+        let build = |uuid: &str, ts: &str| {
+            MessageBuilder::user()
+                .with_uuid(uuid)
+                .with_timestamp(ts)
+                .build()
+        };
+        let mut forward = vec![
+            build("1", "2025-01-01T00:00:00Z"),
+            build("2", "2025-01-01T00:00:00Z"),
+            build("3", "2025-01-02T00:00:00Z"),
+        ];
+        let mut reversed: Vec<ClaudeMessage> = forward.iter().rev().cloned().collect();
+
+        sort_messages_for_export(&mut forward);
+        sort_messages_for_export(&mut reversed);
+
+        let a: Vec<&str> = forward.iter().map(|m| m.uuid.as_str()).collect();
+        let b: Vec<&str> = reversed.iter().map(|m| m.uuid.as_str()).collect();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_export_message_id_is_sanitized() {
+        let message = MessageBuilder::user().with_uuid("abc/def:1").build();
+        assert_eq!(export_message_id(&message), "msg-abc-def-1");
+    }
+}
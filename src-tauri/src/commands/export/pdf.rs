@@ -0,0 +1,472 @@
+//! PDF session export
+//!
+//! Lays out the transcript of a session on A4 pages with the PDF base fonts
+//! (Helvetica for prose, Courier for tool calls and results) so it can be
+//! archived in a fixed format. Every turn keeps its timestamp and model, and
+//! the first page lists the session's time span, models and token usage.
+
+use super::document::{
+    build_transcript, parse_export_messages, save_with_dialog, session_cwd, with_session_data,
+    Part, Transcript,
+};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ClaudeMessage, MessageSelection};
+use crate::utils::resolve_session_file;
+use chrono::{DateTime, Utc};
+use printpdf::{BuiltinFont, Color, Greyscale, Mm, OffsetDateTime, PdfDocument};
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 18.0;
+const TEXT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+
+/// Baseline of the page footer, below the bottom margin
+const FOOTER_Y: f32 = 10.0;
+
+/// Indentation of tool inputs and results
+const CODE_INDENT: f32 = 4.0;
+
+const PT_TO_MM: f32 = 0.3528;
+
+/// Characters outside Latin-1 that the base fonts' `WinAnsiEncoding` covers
+const WIN_ANSI_EXTRAS: &str = "€‚ƒ„…†‡ˆ‰Š‹ŒŽ‘’“”•–—˜™š›œžŸ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Title,
+    Heading,
+    Meta,
+    Body,
+    Code,
+}
+
+impl Style {
+    fn font_size(self) -> f32 {
+        match self {
+            Self::Title => 16.0,
+            Self::Heading => 12.0,
+            Self::Meta => 8.5,
+            Self::Body => 10.0,
+            Self::Code => 8.0,
+        }
+    }
+
+    fn line_height(self) -> f32 {
+        self.font_size() * PT_TO_MM * 1.35
+    }
+
+    fn font(self) -> BuiltinFont {
+        match self {
+            Self::Title | Self::Heading => BuiltinFont::HelveticaBold,
+            Self::Meta | Self::Body => BuiltinFont::Helvetica,
+            Self::Code => BuiltinFont::Courier,
+        }
+    }
+
+    /// Approximate advance of `ch` in millimeters
+    fn char_width(self, ch: char) -> f32 {
+        let em = match self {
+            Self::Code => 0.6,
+            Self::Title | Self::Heading => helvetica_width(ch) * 1.06,
+            Self::Meta | Self::Body => helvetica_width(ch),
+        };
+        em * self.font_size() * PT_TO_MM
+    }
+
+    fn color(self) -> Color {
+        let level = match self {
+            Self::Meta => 0.4,
+            Self::Code => 0.2,
+            _ => 0.0,
+        };
+        Color::Greyscale(Greyscale::new(level, None))
+    }
+}
+
+/// Helvetica glyph width in em, rounded up by character class
+fn helvetica_width(ch: char) -> f32 {
+    match ch {
+        'i' | 'j' | 'l' | '\'' | '|' => 0.23,
+        ' ' | '.' | ',' | ':' | ';' | '!' | 'f' | 't' | 'I' | '[' | ']' | '(' | ')' | '/' => 0.28,
+        'r' | '-' | '"' => 0.34,
+        'm' | 'M' | 'W' | '@' | '%' => 0.9,
+        'w' | 'A'..='Z' => 0.72,
+        _ => 0.56,
+    }
+}
+
+/// Text the base fonts can show: tabs expanded, other characters replaced
+fn pdf_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\t' => out.push_str("    "),
+            ' '..='~' | '\u{A0}'..='\u{FF}' => out.push(ch),
+            _ if WIN_ANSI_EXTRAS.contains(ch) => out.push(ch),
+            '\r' => {}
+            _ => out.push('?'),
+        }
+    }
+    out
+}
+
+/// Split `text` into lines fitting `width` millimeters
+///
+/// Prose breaks after spaces; code keeps its spacing and breaks anywhere.
+fn wrap(text: &str, style: Style, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for source in text.split('\n') {
+        let mut line = String::new();
+        let mut line_width = 0.0;
+        let mut break_at: Option<usize> = None; // Byte index after the last space
+        for ch in pdf_text(source).chars() {
+            let char_width = style.char_width(ch);
+            if line_width + char_width > width && !line.is_empty() {
+                if ch == ' ' && style != Style::Code {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0.0;
+                    break_at = None;
+                    continue;
+                }
+                if let Some(index) = break_at.filter(|_| style != Style::Code) {
+                    let rest = line.split_off(index);
+                    lines.push(line.trim_end().to_string());
+                    line_width = rest.chars().map(|c| style.char_width(c)).sum();
+                    line = rest;
+                } else {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0.0;
+                }
+                break_at = None;
+            }
+            line.push(ch);
+            line_width += char_width;
+            if ch == ' ' {
+                break_at = Some(line.len());
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// A line of text placed on a page (coordinates in mm from the bottom left)
+#[derive(Debug, Clone, PartialEq)]
+struct PlacedLine {
+    style: Style,
+    x: f32,
+    y: f32,
+    text: String,
+}
+
+/// Lines placed top to bottom, starting a new page when one is full
+struct Layout {
+    pages: Vec<Vec<PlacedLine>>,
+    y: f32,
+}
+
+impl Layout {
+    fn new() -> Self {
+        Self {
+            pages: vec![Vec::new()],
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn gap(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn paragraph(&mut self, style: Style, indent: f32, text: &str) {
+        for text in wrap(text, style, TEXT_WIDTH - indent) {
+            let height = style.line_height();
+            if self.y - height < MARGIN {
+                self.pages.push(Vec::new());
+                self.y = PAGE_HEIGHT - MARGIN;
+            }
+            self.y -= height;
+            if let Some(page) = self.pages.last_mut() {
+                page.push(PlacedLine {
+                    style,
+                    x: MARGIN + indent,
+                    y: self.y,
+                    text,
+                });
+            }
+        }
+    }
+}
+
+/// `2025-01-01 00:00:00 UTC` for RFC 3339 timestamps, others unchanged
+fn format_time(timestamp: &str) -> String {
+    DateTime::parse_from_rfc3339(timestamp).map_or_else(
+        |_| timestamp.to_string(),
+        |time| {
+            time.with_timezone(&Utc)
+                .format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string()
+        },
+    )
+}
+
+fn layout_transcript(
+    transcript: &Transcript,
+    session_id: &str,
+    exported_at: DateTime<Utc>,
+) -> Layout {
+    let mut layout = Layout::new();
+    layout.paragraph(Style::Title, 0.0, &transcript.title);
+    layout.gap(2.0);
+    layout.paragraph(Style::Meta, 0.0, &format!("Session {session_id}"));
+    if let (Some(started), Some(ended)) = (&transcript.started, &transcript.ended) {
+        let span = format!("{} – {}", format_time(started), format_time(ended));
+        layout.paragraph(Style::Meta, 0.0, &span);
+    }
+    let mut models: Vec<&str> = Vec::new();
+    for model in transcript
+        .turns
+        .iter()
+        .filter_map(|turn| turn.model.as_deref())
+    {
+        if !models.contains(&model) {
+            models.push(model);
+        }
+    }
+    if !models.is_empty() {
+        layout.paragraph(Style::Meta, 0.0, &format!("Models: {}", models.join(", ")));
+    }
+    let usage = format!(
+        "Tokens: {} input · {} output · {} cache write · {} cache read · Estimated cost: ${:.4}",
+        transcript.input_tokens,
+        transcript.output_tokens,
+        transcript.cache_write_tokens,
+        transcript.cache_read_tokens,
+        transcript.cost_usd
+    );
+    layout.paragraph(Style::Meta, 0.0, &usage);
+    let exported = exported_at
+        .format("Exported %Y-%m-%d %H:%M:%S UTC")
+        .to_string();
+    layout.paragraph(Style::Meta, 0.0, &exported);
+
+    for turn in &transcript.turns {
+        layout.gap(4.0);
+        layout.paragraph(Style::Heading, 0.0, turn.sender);
+        let mut meta = format_time(&turn.timestamp);
+        if let Some(model) = &turn.model {
+            meta = format!("{meta} · {model}");
+        }
+        layout.paragraph(Style::Meta, 0.0, &meta);
+
+        for part in &turn.parts {
+            layout.gap(1.5);
+            match part {
+                Part::Text(text) => layout.paragraph(Style::Body, 0.0, text),
+                Part::ToolUse { name, input } => {
+                    layout.paragraph(Style::Meta, 0.0, &format!("Tool call: {name}"));
+                    layout.paragraph(Style::Code, CODE_INDENT, input);
+                }
+                Part::ToolResult {
+                    is_error,
+                    tool_name,
+                    text,
+                } => {
+                    let label = if *is_error {
+                        "Tool error"
+                    } else {
+                        "Tool result"
+                    };
+                    let label = match tool_name {
+                        Some(name) => format!("{label}: {name}"),
+                        None => label.to_string(),
+                    };
+                    layout.paragraph(Style::Meta, 0.0, &label);
+                    if !text.is_empty() {
+                        layout.paragraph(Style::Code, CODE_INDENT, text);
+                    }
+                }
+            }
+        }
+    }
+    layout
+}
+
+/// Render a session as a PDF document
+pub fn render_session_pdf(
+    session_id: &str,
+    messages: &[ClaudeMessage],
+    cwd: Option<&str>,
+    exported_at: DateTime<Utc>,
+) -> Result<Vec<u8>, String> {
+    let pdf_err = |e: printpdf::Error| format!("Failed to render PDF: {e}");
+    let transcript = build_transcript(session_id, messages, cwd);
+    let layout = layout_transcript(&transcript, session_id, exported_at);
+
+    let created = OffsetDateTime::from_unix_timestamp(exported_at.timestamp())
+        .map_err(|e| format!("Invalid export time: {e}"))?;
+    let (doc, first_page, first_layer) = PdfDocument::new(
+        pdf_text(&transcript.title),
+        Mm(PAGE_WIDTH),
+        Mm(PAGE_HEIGHT),
+        "Transcript",
+    );
+    let doc = doc
+        .with_subject(format!("Claude Code session {session_id}"))
+        .with_creator("Claude Code History Viewer")
+        .with_creation_date(created)
+        .with_mod_date(created);
+    let regular = doc
+        .add_builtin_font(BuiltinFont::Helvetica)
+        .map_err(pdf_err)?;
+    let bold = doc
+        .add_builtin_font(BuiltinFont::HelveticaBold)
+        .map_err(pdf_err)?;
+    let mono = doc
+        .add_builtin_font(BuiltinFont::Courier)
+        .map_err(pdf_err)?;
+
+    let page_count = layout.pages.len();
+    for (index, lines) in layout.pages.into_iter().enumerate() {
+        let (page, layer) = if index == 0 {
+            (first_page, first_layer)
+        } else {
+            doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Transcript")
+        };
+        let layer = doc.get_page(page).get_layer(layer);
+        for line in lines {
+            let font = match line.style.font() {
+                BuiltinFont::HelveticaBold => &bold,
+                BuiltinFont::Courier => &mono,
+                _ => &regular,
+            };
+            layer.set_fill_color(line.style.color());
+            layer.use_text(
+                line.text,
+                line.style.font_size(),
+                Mm(line.x),
+                Mm(line.y),
+                font,
+            );
+        }
+        layer.set_fill_color(Style::Meta.color());
+        layer.use_text(
+            format!("{session_id} · Page {} of {page_count}", index + 1),
+            Style::Meta.font_size(),
+            Mm(MARGIN),
+            Mm(FOOTER_Y),
+            &regular,
+        );
+    }
+
+    doc.save_to_bytes().map_err(pdf_err)
+}
+
+/// Export a session as a PDF file chosen in a save dialog
+///
+/// Returns the written path, or None if the dialog was cancelled. Secrets
+/// are masked (see `crate::redaction`) when `redact` is true. `selection`
+/// limits the export to part of the session.
+#[tauri::command]
+pub async fn export_session_pdf(
+    app: tauri::AppHandle,
+    session_id: String,
+    project_path: String,
+    redact: Option<bool>,
+    selection: Option<MessageSelection>,
+) -> Result<Option<String>, AppError> {
+    let _timer = OperationTimer::start("export_session_pdf");
+
+    let redact = redact.unwrap_or(false);
+    let file_name = format!("{session_id}.pdf");
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+    let pdf = with_session_data(&session_path, move |session_path, data| {
+        let messages = parse_export_messages(session_path, data, redact, selection.as_ref())?;
+        let cwd = session_cwd(data);
+        render_session_pdf(&session_id, &messages, cwd.as_deref(), Utc::now())
+    })
+    .await?;
+
+    Ok(save_with_dialog(app, "Export session as PDF", file_name, ("PDF", "pdf"), pdf).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::document::tests::sample_session;
+
+    fn exported_at() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2025-02-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_wrap_breaks_prose_at_spaces_and_code_anywhere() {
+        let width = Style::Code.char_width('x') * 10.0 + 0.01;
+        assert_eq!(
+            wrap("let value = compute(input);", Style::Code, width),
+            vec!["let value ", "= compute(", "input);"]
+        );
+        assert_eq!(
+            wrap("  indented\n\nnext", Style::Code, width),
+            vec!["  indented", "", "next"]
+        );
+
+        let width = "the quick brown"
+            .chars()
+            .map(|c| Style::Body.char_width(c))
+            .sum::<f32>()
+            + 0.01;
+        assert_eq!(
+            wrap("the quick brown fox jumps", Style::Body, width),
+            vec!["the quick brown", "fox jumps"]
+        );
+    }
+
+    #[test]
+    fn test_pdf_text_replaces_unsupported_characters() {
+        assert_eq!(pdf_text("café – “ok”\t✓ 日本"), "café – “ok”    ? ??");
+    }
+
+    #[test]
+    fn test_layout_keeps_times_and_models() {
+        let transcript = build_transcript("s1", &sample_session(), None);
+        let layout = layout_transcript(&transcript, "s1", exported_at());
+
+        let texts: Vec<&str> = layout.pages[0].iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts[0], "Fix the build");
+        assert!(texts.contains(&"2025-01-01 00:00:00 UTC – 2025-01-01 00:00:03 UTC"));
+        assert!(texts.contains(&"Models: claude-opus-4-20250514"));
+        assert!(texts.contains(&"2025-01-01 00:00:01 UTC · claude-opus-4-20250514"));
+        assert!(texts.contains(&"Tool error: Bash"));
+        assert!(texts.contains(&"Exported 2025-02-01 12:00:00 UTC"));
+    }
+
+    #[test]
+    fn test_layout_starts_new_pages() {
+        let mut layout = Layout::new();
+        for n in 0..200 {
+            layout.paragraph(Style::Body, 0.0, &format!("line {n}"));
+        }
+
+        assert!(layout.pages.len() > 1);
+        assert!(layout
+            .pages
+            .iter()
+            .flatten()
+            .all(|line| line.y >= MARGIN && line.y < PAGE_HEIGHT - MARGIN));
+        assert_eq!(
+            layout.pages[1][0].text,
+            format!("line {}", layout.pages[0].len())
+        );
+    }
+
+    #[test]
+    fn test_render_session_pdf_writes_document() {
+        let pdf = render_session_pdf("s1", &sample_session(), None, exported_at()).unwrap();
+
+        assert!(pdf.starts_with(b"%PDF-"));
+        assert!(String::from_utf8_lossy(&pdf).trim_end().ends_with("%%EOF"));
+    }
+}
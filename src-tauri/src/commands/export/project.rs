@@ -0,0 +1,299 @@
+//! Bulk export of a project
+//!
+//! Writes every session of a project into one directory, one file per
+//! session in the chosen format (Markdown, HTML or normalized JSON), plus an
+//! index file in the same format listing each session's summary and dates.
+
+use super::document::{pick_folder_with_dialog, read_export_messages};
+use super::html::{escape_html, render_session_html, STYLESHEET};
+use super::json::build_normalized_session;
+use super::markdown::render_session_markdown;
+use super::ordering::sort_sessions_for_export;
+use crate::commands::session::load_project_sessions;
+use crate::commands::stats::read_raw_log_entries;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ClaudeSession, ProjectExportIndexEntry, ProjectExportResult};
+use crate::redaction::{redact_entries, redact_text};
+use rayon::prelude::*;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Format of the files written by a project export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Markdown,
+    Html,
+    Json,
+}
+
+impl ExportFormat {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "markdown" | "md" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            "json" => Ok(Self::Json),
+            other => Err(format!("Unsupported export format: {other}")),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Markdown => "markdown",
+            Self::Html => "html",
+            Self::Json => "json",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Render one session file in `format`
+fn render_session(
+    session_id: &str,
+    session_path: &Path,
+    format: ExportFormat,
+    redact: bool,
+) -> Result<String, String> {
+    let messages = read_export_messages(session_path, redact, None)?;
+    let mut raw_entries = read_raw_log_entries(session_path);
+    if redact {
+        redact_entries(&mut raw_entries);
+    }
+    let cwd = raw_entries.iter().find_map(|entry| entry.cwd.clone());
+
+    Ok(match format {
+        ExportFormat::Markdown => render_session_markdown(session_id, &messages, cwd.as_deref()),
+        ExportFormat::Html => render_session_html(session_id, &messages, cwd.as_deref()),
+        ExportFormat::Json => serde_json::to_string_pretty(&build_normalized_session(
+            session_id,
+            &messages,
+            &raw_entries,
+            cwd.as_deref(),
+        ))
+        .map_err(|e| format!("Failed to serialize session: {e}"))?,
+    })
+}
+
+/// Markdown table cell: pipes escaped, on a single line
+fn table_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+fn render_index(
+    project_name: &str,
+    entries: &[ProjectExportIndexEntry],
+    format: ExportFormat,
+) -> Result<String, String> {
+    match format {
+        ExportFormat::Markdown => {
+            let mut out = format!(
+                "# {project_name}\n\n{} sessions\n\n\
+                 | Session | Summary | Started | Last message | Messages |\n\
+                 |---|---|---|---|---|\n",
+                entries.len()
+            );
+            for entry in entries {
+                let _ = writeln!(
+                    out,
+                    "| [{}]({}) | {} | {} | {} | {} |",
+                    entry.session_id,
+                    entry.file_name,
+                    table_cell(entry.summary.as_deref().unwrap_or("")),
+                    entry.first_message_time,
+                    entry.last_message_time,
+                    entry.message_count
+                );
+            }
+            Ok(out)
+        }
+        ExportFormat::Html => {
+            let title = escape_html(project_name);
+            let mut out = format!(
+                "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+                 <title>{title}</title>\n<style>{STYLESHEET}\
+                 table {{ border-collapse: collapse; width: 100%; }}\n\
+                 th, td {{ border-bottom: 1px solid var(--border); padding: 0.4rem; text-align: left; }}\n\
+                 </style>\n</head>\n<body>\n<h1>{title}</h1>\n<p>{} sessions</p>\n<table>\n\
+                 <tr><th>Session</th><th>Summary</th><th>Started</th><th>Last message</th><th>Messages</th></tr>\n",
+                entries.len()
+            );
+            for entry in entries {
+                let _ = writeln!(
+                    out,
+                    "<tr><td><a href=\"{}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    escape_html(&entry.file_name),
+                    escape_html(&entry.session_id),
+                    escape_html(entry.summary.as_deref().unwrap_or("")),
+                    escape_html(&entry.first_message_time),
+                    escape_html(&entry.last_message_time),
+                    entry.message_count
+                );
+            }
+            out.push_str("</table>\n</body>\n</html>\n");
+            Ok(out)
+        }
+        ExportFormat::Json => serde_json::to_string_pretty(entries)
+            .map_err(|e| format!("Failed to serialize index: {e}")),
+    }
+}
+
+/// Export `sessions` into `directory`, one file each plus an index
+fn export_sessions_to(
+    project_name: &str,
+    mut sessions: Vec<ClaudeSession>,
+    format: ExportFormat,
+    redact: bool,
+    directory: &Path,
+) -> Result<ProjectExportResult, String> {
+    fs::create_dir_all(directory).map_err(|e| format!("Failed to create directory: {e}"))?;
+    sort_sessions_for_export(&mut sessions);
+
+    let results: Vec<Result<ProjectExportIndexEntry, String>> = sessions
+        .par_iter()
+        .map(|session| {
+            let session_path = Path::new(&session.file_path);
+            let session_id = session_path.file_stem().map_or_else(
+                || session.session_id.clone(),
+                |stem| stem.to_string_lossy().to_string(),
+            );
+            let file_name = format!("{session_id}.{}", format.extension());
+            render_session(&session_id, session_path, format, redact)
+                .and_then(|content| {
+                    fs::write(directory.join(&file_name), content)
+                        .map_err(|e| format!("Failed to write export: {e}"))
+                })
+                .map_err(|e| format!("{session_id}: {e}"))?;
+
+            Ok(ProjectExportIndexEntry {
+                session_id,
+                file_name,
+                summary: session
+                    .summary
+                    .as_ref()
+                    .or(session.first_user_message.as_ref())
+                    .map(|summary| {
+                        if redact {
+                            redact_text(summary)
+                        } else {
+                            summary.clone()
+                        }
+                    }),
+                first_message_time: session.first_message_time.clone(),
+                last_message_time: session.last_message_time.clone(),
+                message_count: session.message_count,
+            })
+        })
+        .collect();
+
+    let mut entries = Vec::new();
+    let mut failed = Vec::new();
+    for result in results {
+        match result {
+            Ok(entry) => entries.push(entry),
+            Err(e) => failed.push(e),
+        }
+    }
+
+    let index_path = directory.join(format!("index.{}", format.extension()));
+    fs::write(&index_path, render_index(project_name, &entries, format)?)
+        .map_err(|e| format!("Failed to write index: {e}"))?;
+
+    Ok(ProjectExportResult {
+        directory: directory.to_string_lossy().to_string(),
+        format: format.as_str().to_string(),
+        index_file: index_path.to_string_lossy().to_string(),
+        exported: entries.len(),
+        failed,
+    })
+}
+
+/// Export every session of a project into a directory chosen in a dialog
+///
+/// `format` is "markdown", "html" or "json". Returns None if the dialog was
+/// cancelled; sessions that fail to export are listed in the result. Secrets
+/// are masked (see `crate::redaction`) when `redact` is true.
+#[tauri::command]
+pub async fn export_project(
+    app: tauri::AppHandle,
+    project_path: String,
+    format: String,
+    redact: Option<bool>,
+) -> Result<Option<ProjectExportResult>, AppError> {
+    let _timer = OperationTimer::start("export_project");
+
+    let format = ExportFormat::parse(&format)?;
+    let Some(directory) = pick_folder_with_dialog(app, "Export project to folder").await? else {
+        return Ok(None);
+    };
+
+    let project_name = Path::new(&project_path).file_name().map_or_else(
+        || project_path.clone(),
+        |name| name.to_string_lossy().to_string(),
+    );
+    let sessions = load_project_sessions(project_path, None, Some(true)).await?;
+
+    let redact = redact.unwrap_or(false);
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        export_sessions_to(&project_name, sessions, format, redact, &directory)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+    Ok(Some(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{create_jsonl_content, MessageBuilder};
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_export_sessions_to_writes_files_and_index() {
+        let project = TempDir::new().unwrap();
+        for (session_id, prompt) in [("s1", "Fix the | build"), ("s2", "Add tests")] {
+            let content = create_jsonl_content(&[
+                MessageBuilder::user()
+                    .with_session_id(session_id)
+                    .with_text_content(prompt),
+                MessageBuilder::assistant()
+                    .with_session_id(session_id)
+                    .with_text_content("Done."),
+            ]);
+            fs::write(project.path().join(format!("{session_id}.jsonl")), content).unwrap();
+        }
+        let sessions =
+            load_project_sessions(project.path().to_string_lossy().to_string(), None, None)
+                .await
+                .unwrap();
+
+        let target = TempDir::new().unwrap();
+        let directory = target.path().join("export");
+        let result =
+            export_sessions_to("demo", sessions, ExportFormat::Markdown, false, &directory)
+                .unwrap();
+
+        assert_eq!(result.exported, 2);
+        assert!(result.failed.is_empty());
+        let session = fs::read_to_string(directory.join("s1.md")).unwrap();
+        assert!(session.starts_with("# Fix the | build"));
+        let index = fs::read_to_string(&result.index_file).unwrap();
+        assert!(index.starts_with("# demo\n\n2 sessions"));
+        assert!(index.contains("| [s1](s1.md) | Fix the \\| build |"));
+        assert!(index.contains("| [s2](s2.md) | Add tests |"));
+    }
+
+    #[test]
+    fn test_export_format_parse() {
+        assert_eq!(ExportFormat::parse("md"), Ok(ExportFormat::Markdown));
+        assert_eq!(ExportFormat::parse("json").unwrap().extension(), "json");
+        assert!(ExportFormat::parse("pdf").is_err());
+    }
+}
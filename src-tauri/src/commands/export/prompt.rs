@@ -0,0 +1,237 @@
+//! Copy-as-prompt export
+//!
+//! Reduces a session to the text a new session needs to pick up where it
+//! left off: the user's prompts and the assistant's text replies, in the
+//! order of the active branch. The branch is found by walking `parentUuid`
+//! links back from the latest entry, so turns abandoned by rewinding or
+//! editing a prompt are left out. Tool calls and results, thinking, progress,
+//! system, meta and sidechain entries are dropped.
+
+use super::selection::select_messages;
+use crate::commands::prompt_quality::prompt_text;
+use crate::commands::session::is_genuine_user_text;
+use crate::commands::stats::read_raw_log_entries;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{MessageSelection, RawLogEntry};
+use crate::redaction::redact_entries;
+use crate::utils::resolve_session_file;
+use std::collections::{HashMap, HashSet};
+
+/// Main-conversation entries on the branch ending at the latest entry, in
+/// conversation order
+///
+/// Compaction restarts the chain at a `compact_boundary` entry without a
+/// parent; the walk then continues from the latest entry written before it.
+fn active_branch(entries: &[RawLogEntry]) -> Vec<&RawLogEntry> {
+    let is_main = |entry: &RawLogEntry| entry.uuid.is_some() && entry.is_sidechain != Some(true);
+    let positions: HashMap<&str, usize> = entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| is_main(entry))
+        .filter_map(|(index, entry)| Some((entry.uuid.as_deref()?, index)))
+        .collect();
+
+    let mut branch = Vec::new();
+    let mut visited = HashSet::new();
+    let mut current = entries.iter().rposition(is_main);
+    while let Some(index) = current {
+        if !visited.insert(index) {
+            break; // Cyclic links
+        }
+        let entry = &entries[index];
+        branch.push(entry);
+        current = match entry.parent_uuid.as_deref() {
+            Some(parent) => positions.get(parent).copied(),
+            None if entry.subtype.as_deref() == Some("compact_boundary") => {
+                entries[..index].iter().rposition(is_main)
+            }
+            None => None,
+        };
+    }
+    branch.reverse();
+    branch
+}
+
+/// Speaker and text of an entry worth keeping in the prompt
+fn entry_text(entry: &RawLogEntry) -> Option<(&'static str, String)> {
+    let content = &entry.message.as_ref()?.content;
+    match entry.message_type.as_str() {
+        "user" if entry.is_meta != Some(true) => {
+            let text = prompt_text(content)?;
+            is_genuine_user_text(&text).then(|| ("User", text.trim().to_string()))
+        }
+        "assistant" => {
+            let texts: Vec<&str> = match content {
+                serde_json::Value::String(text) => vec![text.as_str()],
+                serde_json::Value::Array(items) => items
+                    .iter()
+                    .filter(|item| item.get("type").and_then(|v| v.as_str()) == Some("text"))
+                    .filter_map(|item| item.get("text").and_then(|v| v.as_str()))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            let text = texts
+                .iter()
+                .map(|text| text.trim())
+                .filter(|text| !text.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            (!text.is_empty()).then_some(("Assistant", text))
+        }
+        _ => None,
+    }
+}
+
+/// Render the active branch of a session (or the `selection` of it) as plain
+/// `User:`/`Assistant:` turns
+pub fn render_session_prompt(
+    entries: &[RawLogEntry],
+    selection: Option<&MessageSelection>,
+) -> Result<String, String> {
+    let branch = select_messages(active_branch(entries), selection, |entry| {
+        entry.uuid.as_deref()
+    })?;
+    let mut turns: Vec<(&str, String)> = Vec::new();
+    for (speaker, text) in branch.into_iter().filter_map(entry_text) {
+        match turns.last_mut() {
+            Some((last_speaker, last_text)) if *last_speaker == speaker => {
+                last_text.push_str("\n\n");
+                last_text.push_str(&text);
+            }
+            _ => turns.push((speaker, text)),
+        }
+    }
+
+    Ok(turns
+        .iter()
+        .map(|(speaker, text)| format!("{speaker}:\n{text}\n"))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Export a session as prompt text, to copy into a new session
+///
+/// Secrets are masked (see `crate::redaction`) when `redact` is true.
+/// `selection` limits the export to part of the branch.
+#[tauri::command]
+pub async fn export_session_prompt(
+    session_id: String,
+    project_path: String,
+    redact: Option<bool>,
+    selection: Option<MessageSelection>,
+) -> Result<String, AppError> {
+    let _timer = OperationTimer::start("export_session_prompt");
+
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let mut entries = read_raw_log_entries(&session_path);
+        if redact.unwrap_or(false) {
+            redact_entries(&mut entries);
+        }
+        render_session_prompt(&entries, selection.as_ref())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::raw_entry;
+    use serde_json::json;
+
+    fn user(uuid: &str, parent: Option<&str>, content: serde_json::Value) -> RawLogEntry {
+        raw_entry(json!({
+            "type": "user", "uuid": uuid, "parentUuid": parent,
+            "message": {"role": "user", "content": content}
+        }))
+    }
+
+    fn assistant(uuid: &str, parent: &str, content: serde_json::Value) -> RawLogEntry {
+        raw_entry(json!({
+            "type": "assistant", "uuid": uuid, "parentUuid": parent,
+            "message": {"role": "assistant", "content": content}
+        }))
+    }
+
+    #[test]
+    fn test_render_session_prompt_drops_tool_noise() {
+        let entries = vec![
+            user("u1", None, json!("Fix the parser")),
+            assistant(
+                "a1",
+                "u1",
+                json!([
+                    {"type": "thinking", "thinking": "hmm"},
+                    {"type": "text", "text": "Looking at it."},
+                    {"type": "tool_use", "id": "t1", "name": "Read", "input": {}}
+                ]),
+            ),
+            user(
+                "u2",
+                Some("a1"),
+                json!([{"type": "tool_result", "tool_use_id": "t1", "content": "fn main"}]),
+            ),
+            raw_entry(json!({"type": "progress", "uuid": "p1", "parentUuid": "u2"})),
+            assistant("a2", "p1", json!([{"type": "text", "text": "Fixed."}])),
+            raw_entry(json!({
+                "type": "assistant", "uuid": "s1", "parentUuid": "a2", "isSidechain": true,
+                "message": {"role": "assistant", "content": "sub-agent chatter"}
+            })),
+        ];
+
+        assert_eq!(
+            render_session_prompt(&entries, None).unwrap(),
+            "User:\nFix the parser\n\nAssistant:\nLooking at it.\n\nFixed.\n"
+        );
+    }
+
+    #[test]
+    fn test_active_branch_skips_rewound_turns() {
+        let entries = vec![
+            user("u1", None, json!("First")),
+            assistant("a1", "u1", json!("Reply")),
+            user("u2", Some("a1"), json!("Abandoned prompt")),
+            assistant("a2", "u2", json!("Abandoned reply")),
+            user("u3", Some("a1"), json!("Edited prompt")),
+            assistant("a3", "u3", json!("Final reply")),
+        ];
+
+        assert_eq!(
+            render_session_prompt(&entries, None).unwrap(),
+            "User:\nFirst\n\nAssistant:\nReply\n\n\
+             User:\nEdited prompt\n\nAssistant:\nFinal reply\n"
+        );
+
+        let selection = MessageSelection {
+            from_uuid: Some("u3".to_string()),
+            ..MessageSelection::default()
+        };
+        assert_eq!(
+            render_session_prompt(&entries, Some(&selection)).unwrap(),
+            "User:\nEdited prompt\n\nAssistant:\nFinal reply\n"
+        );
+        let abandoned = MessageSelection {
+            from_uuid: Some("u2".to_string()),
+            ..MessageSelection::default()
+        };
+        assert!(render_session_prompt(&entries, Some(&abandoned)).is_err());
+    }
+
+    #[test]
+    fn test_active_branch_crosses_compaction() {
+        let entries = vec![
+            user("u1", None, json!("Before compaction")),
+            assistant("a1", "u1", json!("Old reply")),
+            raw_entry(json!({"type": "system", "subtype": "compact_boundary", "uuid": "c1"})),
+            user("u2", Some("c1"), json!("After compaction")),
+        ];
+
+        let branch: Vec<&str> = active_branch(&entries)
+            .iter()
+            .filter_map(|entry| entry.uuid.as_deref())
+            .collect();
+        assert_eq!(branch, ["u1", "a1", "c1", "u2"]);
+    }
+}
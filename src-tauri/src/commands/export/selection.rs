@@ -0,0 +1,119 @@
+//! Partial exports
+//!
+//! Narrows the messages of a session down to a `MessageSelection` before an
+//! exporter renders them, so a single exchange can be exported out of a long
+//! session. Items must already be in conversation order.
+
+use crate::models::MessageSelection;
+use std::collections::HashSet;
+
+/// Items of `selection`, or all items when there is no selection
+pub(super) fn select_messages<T>(
+    items: Vec<T>,
+    selection: Option<&MessageSelection>,
+    uuid: impl Fn(&T) -> Option<&str>,
+) -> Result<Vec<T>, String> {
+    let Some(selection) = selection else {
+        return Ok(items);
+    };
+
+    let position = |target: &str| {
+        items
+            .iter()
+            .position(|item| uuid(item) == Some(target))
+            .ok_or_else(|| format!("Message not found: {target}"))
+    };
+    let start = selection
+        .from_uuid
+        .as_deref()
+        .map(position)
+        .transpose()?
+        .unwrap_or(0);
+    let end = selection
+        .to_uuid
+        .as_deref()
+        .map(position)
+        .transpose()?
+        .map_or(items.len(), |index| index + 1);
+    if start >= end {
+        return Err("The selection ends before it starts".to_string());
+    }
+    let listed: Option<HashSet<&str>> = selection
+        .uuids
+        .as_ref()
+        .map(|uuids| uuids.iter().map(String::as_str).collect());
+
+    let selected: Vec<T> = items
+        .into_iter()
+        .enumerate()
+        .filter(|(index, item)| {
+            (start..end).contains(index)
+                && listed.as_ref().map_or(true, |listed| {
+                    uuid(item).is_some_and(|u| listed.contains(u))
+                })
+        })
+        .map(|(_, item)| item)
+        .collect();
+    if selected.is_empty() {
+        return Err("The selection contains no messages".to_string());
+    }
+    Ok(selected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn select(selection: &MessageSelection) -> Result<Vec<&'static str>, String> {
+        select_messages(vec!["a", "b", "c", "d"], Some(selection), |id| Some(*id))
+    }
+
+    #[test]
+    fn test_select_messages_by_range_and_list() {
+        let range = MessageSelection {
+            from_uuid: Some("b".to_string()),
+            to_uuid: Some("c".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(select(&range), Ok(vec!["b", "c"]));
+
+        let open_end = MessageSelection {
+            from_uuid: Some("c".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(select(&open_end), Ok(vec!["c", "d"]));
+
+        let listed = MessageSelection {
+            uuids: Some(vec!["d".to_string(), "a".to_string()]),
+            to_uuid: Some("c".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(select(&listed), Ok(vec!["a"]));
+    }
+
+    #[test]
+    fn test_select_messages_rejects_bad_selections() {
+        let unknown = MessageSelection {
+            from_uuid: Some("x".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(select(&unknown), Err("Message not found: x".to_string()));
+
+        let reversed = MessageSelection {
+            from_uuid: Some("c".to_string()),
+            to_uuid: Some("a".to_string()),
+            ..Default::default()
+        };
+        assert!(select(&reversed).is_err());
+
+        let empty = MessageSelection {
+            uuids: Some(Vec::new()),
+            ..Default::default()
+        };
+        assert!(select(&empty).is_err());
+        assert_eq!(
+            select_messages(vec!["a"], None, |id| Some(*id)),
+            Ok(vec!["a"])
+        );
+    }
+}
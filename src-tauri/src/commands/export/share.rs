@@ -0,0 +1,339 @@
+//! Encrypted session share bundles
+//!
+//! `share_session` writes one session into a single file a teammate can
+//! import with the passphrase: zstd-compressed JSON sealed with AES-256-GCM
+//! under a PBKDF2-HMAC-SHA256 key. Layout:
+//!
+//! `CHVSHARE` | version (1 byte) | PBKDF2 iterations (u32 BE) | salt (16) |
+//! nonce (12) | ciphertext + tag
+//!
+//! The header is authenticated as associated data, so a tampered iteration
+//! count or salt fails like a wrong passphrase.
+
+use super::document::read_export_messages;
+use super::warehouse::session_project_name;
+use crate::commands::journal::record_operation;
+use crate::commands::session::{shared_folder, shared_session};
+use crate::commands::usage_metrics::{write_json_atomic, OperationTimer};
+use crate::errors::AppError;
+use crate::models::{ClaudeSession, ShareOptions, ShareResult, SharedSessionBundle};
+use crate::utils::{display_path, resolve_session_file};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::fs;
+use std::io::Read;
+use std::num::NonZeroU32;
+use std::path::Path;
+
+const MAGIC: &[u8] = b"CHVSHARE";
+const FORMAT_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const HEADER_LEN: usize = MAGIC.len() + 1 + 4 + SALT_LEN + NONCE_LEN;
+
+/// PBKDF2 iterations of new bundles
+const PBKDF2_ITERATIONS: u32 = 600_000;
+
+/// Highest iteration count accepted when opening a bundle
+const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+
+const MIN_PASSPHRASE_CHARS: usize = 8;
+const ZSTD_LEVEL: i32 = 3;
+
+/// Largest accepted decompressed bundle
+const MAX_BUNDLE_BYTES: u64 = 512 * 1024 * 1024;
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: NonZeroU32) -> LessSafeKey {
+    let mut key = [0u8; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &key).expect("AES-256 key length"))
+}
+
+/// Compress and encrypt a bundle
+fn seal_bundle(
+    bundle: &SharedSessionBundle,
+    passphrase: &str,
+    iterations: u32,
+) -> Result<Vec<u8>, String> {
+    let iterations = NonZeroU32::new(iterations).ok_or("PBKDF2 iterations must be positive")?;
+    let json =
+        serde_json::to_vec(bundle).map_err(|e| format!("Failed to serialize bundle: {e}"))?;
+    let mut payload = zstd::encode_all(json.as_slice(), ZSTD_LEVEL)
+        .map_err(|e| format!("Failed to compress bundle: {e}"))?;
+
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut salt)
+        .and_then(|()| rng.fill(&mut nonce))
+        .map_err(|_| "Failed to generate random bytes".to_string())?;
+
+    let mut data = Vec::with_capacity(HEADER_LEN + payload.len() + AES_256_GCM.tag_len());
+    data.extend_from_slice(MAGIC);
+    data.push(FORMAT_VERSION);
+    data.extend_from_slice(&iterations.get().to_be_bytes());
+    data.extend_from_slice(&salt);
+    data.extend_from_slice(&nonce);
+
+    derive_key(passphrase, &salt, iterations)
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(&data[..HEADER_LEN]),
+            &mut payload,
+        )
+        .map_err(|_| "Failed to encrypt bundle".to_string())?;
+    data.extend_from_slice(&payload);
+    Ok(data)
+}
+
+/// Decrypt and decompress a bundle written by `seal_bundle`
+fn open_bundle(data: &[u8], passphrase: &str) -> Result<SharedSessionBundle, AppError> {
+    if data.len() < HEADER_LEN || !data.starts_with(MAGIC) {
+        return Err(AppError::invalid_input("Not a shared session bundle"));
+    }
+    let (header, ciphertext) = data.split_at(HEADER_LEN);
+    let version = header[MAGIC.len()];
+    if version != FORMAT_VERSION {
+        return Err(AppError::invalid_input(format!(
+            "Unsupported bundle version {version}; update the viewer to import it"
+        )));
+    }
+    let mut iterations = [0u8; 4];
+    iterations.copy_from_slice(&header[MAGIC.len() + 1..MAGIC.len() + 5]);
+    let iterations = u32::from_be_bytes(iterations);
+    let iterations = NonZeroU32::new(iterations)
+        .filter(|n| n.get() <= MAX_PBKDF2_ITERATIONS)
+        .ok_or_else(|| AppError::invalid_input("Invalid bundle header"))?;
+    let salt = &header[MAGIC.len() + 5..MAGIC.len() + 5 + SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce.copy_from_slice(&header[HEADER_LEN - NONCE_LEN..]);
+
+    let mut payload = ciphertext.to_vec();
+    let compressed = derive_key(passphrase, salt, iterations)
+        .open_in_place(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(header),
+            &mut payload,
+        )
+        .map_err(|_| AppError::invalid_input("Wrong passphrase or corrupted bundle"))?;
+
+    let mut json = Vec::new();
+    zstd::Decoder::new(&compressed[..])
+        .and_then(|decoder| decoder.take(MAX_BUNDLE_BYTES + 1).read_to_end(&mut json))
+        .map_err(|e| AppError::invalid_input(format!("Failed to decompress bundle: {e}")))?;
+    if json.len() as u64 > MAX_BUNDLE_BYTES {
+        return Err(AppError::invalid_input(
+            "Shared session bundle is too large",
+        ));
+    }
+    serde_json::from_slice(&json)
+        .map_err(|e| AppError::invalid_input(format!("Invalid bundle content: {e}")))
+}
+
+/// Store a decrypted bundle in `folder` and list its session
+fn store_bundle(folder: &Path, bundle: &SharedSessionBundle) -> Result<ClaudeSession, AppError> {
+    // Used as the file name; the sharer controls it
+    if uuid::Uuid::parse_str(&bundle.bundle_id).is_err() {
+        return Err(AppError::invalid_input(format!(
+            "Invalid bundle ID: {}",
+            bundle.bundle_id
+        )));
+    }
+    let path = folder.join(format!("{}.json", bundle.bundle_id));
+    let session = shared_session(&path, bundle)
+        .ok_or_else(|| AppError::invalid_input("Shared session has no messages"))?;
+
+    fs::create_dir_all(folder).map_err(|e| AppError::io("creating shared sessions folder", &e))?;
+    write_json_atomic(&path, bundle)?;
+    Ok(session)
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<(), AppError> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return Err(AppError::invalid_input(format!(
+            "Passphrase must be at least {MIN_PASSPHRASE_CHARS} characters"
+        )));
+    }
+    Ok(())
+}
+
+/// Share a session as a single encrypted file
+///
+/// Secrets are masked (see `crate::redaction`) unless `options.redact` is
+/// false. The recipient imports the file with `import_shared_session` and
+/// the same passphrase.
+#[tauri::command]
+pub async fn share_session(
+    session_id: String,
+    project_path: String,
+    options: ShareOptions,
+) -> Result<ShareResult, AppError> {
+    let _timer = OperationTimer::start("share_session");
+
+    validate_passphrase(&options.passphrase)?;
+    if !Path::new(&options.output_path).is_absolute() {
+        return Err(AppError::invalid_input(
+            "Invalid output path: must be an absolute path",
+        ));
+    }
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let redacted = options.redact.unwrap_or(true);
+        let messages = read_export_messages(&session_path, redacted, options.selection.as_ref())?;
+        if messages.is_empty() {
+            return Err(AppError::invalid_input("No messages to share"));
+        }
+        let bundle = SharedSessionBundle {
+            bundle_id: uuid::Uuid::new_v4().to_string(),
+            shared_at: chrono::Utc::now().to_rfc3339(),
+            shared_by: options.shared_by,
+            title: options.title,
+            note: options.note,
+            session_id,
+            project_name: session_project_name(&session_path).unwrap_or_default(),
+            redacted,
+            messages,
+        };
+
+        let data = seal_bundle(&bundle, &options.passphrase, PBKDF2_ITERATIONS)?;
+        let output_path = Path::new(&options.output_path);
+        fs::write(output_path, &data).map_err(|e| AppError::io("writing share bundle", &e))?;
+        Ok(ShareResult {
+            bundle_id: bundle.bundle_id,
+            path: display_path(output_path),
+            bytes: data.len() as u64,
+            message_count: bundle.messages.len(),
+            redacted,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Decrypt a bundle written by `share_session` into the "Shared with me"
+/// pseudo-project and return its session
+///
+/// Importing the same bundle again replaces the stored copy.
+#[tauri::command]
+pub async fn import_shared_session(
+    bundle_path: String,
+    passphrase: String,
+) -> Result<ClaudeSession, AppError> {
+    let _timer = OperationTimer::start("import_shared_session");
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let data = fs::read(&bundle_path).map_err(|e| AppError::io("reading share bundle", &e))?;
+        let bundle = open_bundle(&data, &passphrase)?;
+        let session = store_bundle(&shared_folder()?, &bundle)?;
+        record_operation(
+            "import_shared_session",
+            format!("Imported shared session {}", bundle.bundle_id),
+            None,
+        );
+        Ok(session)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::session::read_shared_bundle;
+    use crate::models::ClaudeMessage;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn bundle() -> SharedSessionBundle {
+        let message: ClaudeMessage = serde_json::from_value(json!({
+            "uuid": "u1",
+            "sessionId": "s1",
+            "timestamp": "2025-03-01T09:00:00Z",
+            "type": "user",
+            "content": "Why does the build fail?"
+        }))
+        .unwrap();
+        SharedSessionBundle {
+            bundle_id: uuid::Uuid::new_v4().to_string(),
+            shared_at: "2025-03-02T10:00:00Z".to_string(),
+            shared_by: Some("alex".to_string()),
+            title: Some("Build failure".to_string()),
+            note: None,
+            session_id: "s1".to_string(),
+            project_name: "demo".to_string(),
+            redacted: true,
+            messages: vec![message],
+        }
+    }
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let bundle = bundle();
+        let data = seal_bundle(&bundle, "correct horse", 1_000).unwrap();
+        assert!(data.starts_with(MAGIC));
+
+        let opened = open_bundle(&data, "correct horse").unwrap();
+        assert_eq!(opened.bundle_id, bundle.bundle_id);
+        assert_eq!(opened.messages.len(), 1);
+        assert_eq!(opened.messages[0].uuid, "u1");
+    }
+
+    #[test]
+    fn test_open_bundle_rejects_wrong_passphrase_and_tampering() {
+        let mut data = seal_bundle(&bundle(), "correct horse", 1_000).unwrap();
+        assert!(matches!(
+            open_bundle(&data, "wrong horse"),
+            Err(AppError::InvalidInput { .. })
+        ));
+
+        data[MAGIC.len() + 4] ^= 1; // Iteration count
+        assert!(matches!(
+            open_bundle(&data, "correct horse"),
+            Err(AppError::InvalidInput { .. })
+        ));
+        assert!(open_bundle(b"not a bundle", "correct horse").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_share_session_requires_absolute_output_path() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("s1.jsonl"), "{}\n").unwrap();
+        let options = ShareOptions {
+            passphrase: "correct horse".to_string(),
+            output_path: "bundle.chvshare".to_string(),
+            ..ShareOptions::default()
+        };
+
+        let result = share_session(
+            "s1".to_string(),
+            temp.path().to_string_lossy().to_string(),
+            options,
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::InvalidInput { .. })));
+        assert!(!Path::new("bundle.chvshare").exists());
+    }
+
+    #[test]
+    fn test_store_bundle_lists_under_shared_with_me() {
+        let temp = TempDir::new().unwrap();
+        let bundle = bundle();
+        let session = store_bundle(temp.path(), &bundle).unwrap();
+        assert_eq!(session.project_name, "Shared with me");
+        assert_eq!(session.actual_session_id, bundle.bundle_id);
+
+        let stored =
+            read_shared_bundle(&temp.path().join(format!("{}.json", bundle.bundle_id))).unwrap();
+        assert_eq!(stored.title.as_deref(), Some("Build failure"));
+
+        let mut escaping = bundle;
+        escaping.bundle_id = "../evil".to_string();
+        assert!(store_bundle(temp.path(), &escaping).is_err());
+    }
+}
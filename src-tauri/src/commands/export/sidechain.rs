@@ -0,0 +1,290 @@
+//! Sidechain-only transcript export
+//!
+//! Extracts one sub-agent run (a sidechain rooted at the prompt of a Task
+//! call) into a standalone transcript:
+//! - `markdown`: readable turns with tool calls and truncated tool results
+//! - `jsonl`: the original log lines of the sidechain, unchanged
+
+use crate::commands::retry_loops::{tool_result_text, truncate_chars};
+use crate::commands::session::{find_sidechains, read_session_with_subagents, subagent_files};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ClaudeMessage, SidechainTranscript};
+use crate::redaction::{redact_messages, redact_value};
+use crate::utils::resolve_session_file;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Maximum characters kept from a single tool result in Markdown
+const MAX_TOOL_RESULT_CHARS: usize = 2000;
+
+/// Markdown of one message's content blocks (thinking is dropped)
+fn render_content(content: &serde_json::Value) -> Vec<String> {
+    match content {
+        serde_json::Value::String(text) => vec![text.trim().to_string()],
+        serde_json::Value::Array(items) => items
+            .iter()
+            .filter_map(|item| match item.get("type").and_then(|v| v.as_str()) {
+                Some("text") => item
+                    .get("text")
+                    .and_then(|v| v.as_str())
+                    .filter(|text| !text.trim().is_empty())
+                    .map(|text| text.trim().to_string()),
+                Some("tool_use") => {
+                    let name = item
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("unknown");
+                    let input = item
+                        .get("input")
+                        .map(|input| serde_json::to_string_pretty(input).unwrap_or_default())
+                        .unwrap_or_default();
+                    Some(format!("**[Tool: {name}]**\n\n```json\n{input}\n```"))
+                }
+                Some("tool_result") => {
+                    let text = tool_result_text(item).unwrap_or_default();
+                    let label = if item.get("is_error").and_then(serde_json::Value::as_bool)
+                        == Some(true)
+                    {
+                        "Tool error"
+                    } else {
+                        "Tool result"
+                    };
+                    Some(format!(
+                        "**[{label}]**\n\n```\n{}\n```",
+                        truncate_chars(text.trim(), MAX_TOOL_RESULT_CHARS)
+                    ))
+                }
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Render a sidechain as a Markdown document
+fn render_markdown(
+    session_id: &str,
+    persona: &str,
+    description: Option<&str>,
+    messages: &[&ClaudeMessage],
+) -> String {
+    let mut out = format!("# Sub-agent transcript: {persona}\n\n");
+    if let Some(description) = description {
+        let _ = writeln!(out, "> {description}\n");
+    }
+    let _ = writeln!(out, "Session `{session_id}`");
+
+    for message in messages {
+        let heading = match message.message_type.as_str() {
+            "user" => "User",
+            "assistant" => "Assistant",
+            _ => continue,
+        };
+        let parts = message
+            .content
+            .as_ref()
+            .map(render_content)
+            .unwrap_or_default();
+        if parts.is_empty() {
+            continue;
+        }
+        let _ = write!(
+            out,
+            "\n## {heading} ({})\n\n{}\n",
+            message.timestamp,
+            parts.join("\n\n")
+        );
+    }
+    out
+}
+
+/// Original log lines of the given messages, from the session and sub-agent files
+fn raw_lines(session_path: &Path, uuids: &HashSet<&str>) -> Vec<String> {
+    let mut written: HashSet<String> = HashSet::new();
+    let mut lines = Vec::new();
+    for path in std::iter::once(session_path.to_path_buf()).chain(subagent_files(session_path)) {
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        for line in content.lines() {
+            let Ok(value) = serde_json::from_str::<serde_json::Value>(line) else {
+                continue;
+            };
+            let Some(uuid) = value.get("uuid").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            if uuids.contains(uuid) && written.insert(uuid.to_string()) {
+                lines.push(line.to_string());
+            }
+        }
+    }
+    lines
+}
+
+/// Build the transcript of the sidechain starting at `root_uuid`
+fn build_sidechain_transcript(
+    session_id: String,
+    session_path: &Path,
+    root_uuid: String,
+    format: String,
+    redact: bool,
+) -> Result<SidechainTranscript, String> {
+    let mut messages = read_session_with_subagents(session_path)?;
+    if redact {
+        redact_messages(&mut messages);
+    }
+    let sidechain = find_sidechains(&messages)
+        .into_iter()
+        .find(|sidechain| sidechain.root.uuid == root_uuid)
+        .ok_or_else(|| format!("Sidechain not found: {root_uuid}"))?;
+
+    let content = if format == "jsonl" {
+        let uuids: HashSet<&str> = sidechain.messages.iter().map(|m| m.uuid.as_str()).collect();
+        let mut lines = raw_lines(session_path, &uuids);
+        if redact {
+            lines = lines
+                .iter()
+                .filter_map(|line| {
+                    let mut value = serde_json::from_str(line).ok()?;
+                    redact_value(&mut value);
+                    serde_json::to_string(&value).ok()
+                })
+                .collect();
+        }
+        let mut content = lines.join("\n");
+        content.push('\n');
+        content
+    } else {
+        render_markdown(
+            &session_id,
+            sidechain.persona,
+            sidechain.description,
+            &sidechain.messages,
+        )
+    };
+
+    Ok(SidechainTranscript {
+        session_id,
+        root_uuid,
+        persona: sidechain.persona.to_string(),
+        description: sidechain.description.map(str::to_string),
+        format,
+        message_count: sidechain.messages.len(),
+        content,
+    })
+}
+
+/// Export a single sub-agent run of a session as a standalone transcript
+///
+/// `root_uuid` is the first message of the sidechain (see
+/// `SessionCast::sidechains`); `format` is "markdown" or "jsonl". Secrets are
+/// masked (see `crate::redaction`) when `redact` is true.
+#[tauri::command]
+pub async fn export_sidechain_transcript(
+    session_id: String,
+    project_path: String,
+    root_uuid: String,
+    format: String,
+    redact: Option<bool>,
+) -> Result<SidechainTranscript, AppError> {
+    let _timer = OperationTimer::start("export_sidechain_transcript");
+
+    if format != "markdown" && format != "jsonl" {
+        return Err(AppError::invalid_input(format!(
+            "Unsupported transcript format: {format}"
+        )));
+    }
+
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+    let redact = redact.unwrap_or(false);
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        build_sidechain_transcript(session_id, &session_path, root_uuid, format, redact)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::write_jsonl;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn write_session(project_dir: &Path) {
+        let lines = [
+            json!({"uuid": "u1", "sessionId": "s1", "timestamp": "2025-01-01T00:00:00Z", "type": "user",
+                "message": {"role": "user", "content": "Add tests"}}),
+            json!({"uuid": "a1", "parentUuid": "u1", "sessionId": "s1", "timestamp": "2025-01-01T00:00:01Z", "type": "assistant",
+            "message": {"role": "assistant", "content": [
+                {"type": "tool_use", "id": "t1", "name": "Task", "input": {
+                    "subagent_type": "test-writer", "description": "Write parser tests", "prompt": "Write tests for parse.rs"}}
+            ]}}),
+            json!({"uuid": "sc1", "sessionId": "s1", "timestamp": "2025-01-01T00:00:02Z", "type": "user", "isSidechain": true,
+                "message": {"role": "user", "content": "Write tests for parse.rs"}}),
+            json!({"uuid": "sc2", "parentUuid": "sc1", "sessionId": "s1", "timestamp": "2025-01-01T00:00:03Z", "type": "assistant", "isSidechain": true,
+            "message": {"role": "assistant", "content": [
+                {"type": "thinking", "thinking": "plan"},
+                {"type": "tool_use", "id": "t2", "name": "Read", "input": {"file_path": "parse.rs"}}
+            ]}}),
+            json!({"uuid": "sc3", "parentUuid": "sc2", "sessionId": "s1", "timestamp": "2025-01-01T00:00:04Z", "type": "user", "isSidechain": true,
+            "message": {"role": "user", "content": [
+                {"type": "tool_result", "tool_use_id": "t2", "content": "fn parse() {}"}
+            ]}}),
+            json!({"uuid": "a2", "parentUuid": "a1", "sessionId": "s1", "timestamp": "2025-01-01T00:00:05Z", "type": "assistant",
+                "message": {"role": "assistant", "content": "Done"}}),
+        ];
+        write_jsonl(&project_dir.join("s1.jsonl"), &lines);
+    }
+
+    #[tokio::test]
+    async fn test_export_sidechain_transcript_formats() {
+        let temp = TempDir::new().unwrap();
+        write_session(temp.path());
+        let project_path = temp.path().to_string_lossy().to_string();
+        let export = |format: &str| {
+            export_sidechain_transcript(
+                "s1".to_string(),
+                project_path.clone(),
+                "sc1".to_string(),
+                format.to_string(),
+                None,
+            )
+        };
+
+        let markdown = export("markdown").await.unwrap();
+        assert_eq!(markdown.persona, "test-writer");
+        assert_eq!(markdown.message_count, 3);
+        assert!(markdown
+            .content
+            .starts_with("# Sub-agent transcript: test-writer\n\n> Write parser tests"));
+        assert!(markdown.content.contains("**[Tool: Read]**"));
+        assert!(markdown.content.contains("fn parse() {}"));
+        assert!(!markdown.content.contains("plan"));
+        assert!(!markdown.content.contains("Done"));
+
+        let jsonl = export("jsonl").await.unwrap();
+        let uuids: Vec<String> = jsonl
+            .content
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<serde_json::Value>(line).unwrap()["uuid"].to_string()
+            })
+            .collect();
+        assert_eq!(uuids, vec!["\"sc1\"", "\"sc2\"", "\"sc3\""]);
+
+        assert!(export("pdf").await.is_err());
+        assert!(export_sidechain_transcript(
+            "s1".to_string(),
+            project_path.clone(),
+            "a1".to_string(),
+            "markdown".to_string(),
+            None,
+        )
+        .await
+        .is_err());
+    }
+}
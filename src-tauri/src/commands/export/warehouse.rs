@@ -0,0 +1,537 @@
+//! Date-partitioned export for data warehouses
+//!
+//! Writes two tables as Hive-style partitions that `BigQuery`, `ClickHouse` and
+//! most query engines load as-is:
+//!
+//! ```text
+//! <dest>/messages/date=2025-01-20/part-0.parquet
+//! <dest>/usage/date=2025-01-20/part-0.parquet
+//! ```
+//!
+//! `messages` has one row per log entry, `usage` one row per counted
+//! response with its tokens and cost. The partition key lives in the path
+//! only, and partitions are in UTC.
+
+use crate::commands::retry_loops::total_tokens;
+use crate::commands::stats::{
+    read_raw_log_entries, resolve_scope_session_files, ResponseUsageTracker,
+};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ClaudeMessage, WarehouseExportResult};
+use crate::utils::{extract_project_name, file_name_string};
+use chrono::{DateTime, Utc};
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use rayon::prelude::*;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WarehouseFormat {
+    Parquet,
+    Ndjson,
+}
+
+impl WarehouseFormat {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "parquet" => Ok(Self::Parquet),
+            "ndjson" | "jsonl" => Ok(Self::Ndjson),
+            other => Err(format!("Unsupported warehouse format: {other}")),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Parquet => "parquet",
+            Self::Ndjson => "ndjson",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Partitioning {
+    Date,
+    Month,
+}
+
+impl Partitioning {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "date" | "day" => Ok(Self::Date),
+            "month" => Ok(Self::Month),
+            other => Err(format!("Unsupported partitioning: {other}")),
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::Date => "date",
+            Self::Month => "month",
+        }
+    }
+
+    /// Partition directory (e.g. `date=2025-01-20`) of a UTC timestamp
+    fn directory(self, timestamp: DateTime<Utc>) -> String {
+        let value = match self {
+            Self::Date => timestamp.format("%Y-%m-%d"),
+            Self::Month => timestamp.format("%Y-%m"),
+        };
+        format!("{}={value}", self.key())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Text,
+    Int,
+    Float,
+    Bool,
+    Timestamp, // RFC 3339 in the rows, microseconds in Parquet
+}
+
+/// Columns of an exported table; every column is nullable
+struct Table {
+    name: &'static str,
+    fields: &'static [(&'static str, FieldKind)],
+}
+
+const MESSAGES: Table = Table {
+    name: "messages",
+    fields: &[
+        ("timestamp", FieldKind::Timestamp),
+        ("session_id", FieldKind::Text),
+        ("project_name", FieldKind::Text),
+        ("uuid", FieldKind::Text),
+        ("parent_uuid", FieldKind::Text),
+        ("type", FieldKind::Text),
+        ("role", FieldKind::Text),
+        ("model", FieldKind::Text),
+        ("is_sidechain", FieldKind::Bool),
+        ("content", FieldKind::Text), // Message content as JSON
+    ],
+};
+
+const USAGE: Table = Table {
+    name: "usage",
+    fields: &[
+        ("timestamp", FieldKind::Timestamp),
+        ("session_id", FieldKind::Text),
+        ("project_name", FieldKind::Text),
+        ("message_uuid", FieldKind::Text),
+        ("model", FieldKind::Text),
+        ("input_tokens", FieldKind::Int),
+        ("output_tokens", FieldKind::Int),
+        ("cache_creation_tokens", FieldKind::Int),
+        ("cache_read_tokens", FieldKind::Int),
+        ("total_tokens", FieldKind::Int),
+        ("cost_usd", FieldKind::Float),
+    ],
+};
+
+/// Rows of both tables in one partition
+#[derive(Default)]
+struct Partition {
+    messages: Vec<Value>,
+    usage: Vec<Value>,
+}
+
+/// Project name of a session file, from its project folder
+pub(crate) fn session_project_name(session_path: &Path) -> Option<String> {
+    session_path
+        .parent()
+        .and_then(file_name_string)
+        .map(|name| extract_project_name(&name))
+}
+
+/// `usage` row (counted responses only) and `messages` row of one message
+pub(crate) fn message_rows(
+    message: &ClaudeMessage,
+    cwd: Option<&str>,
+    project_name: Option<&str>,
+    tracker: &mut ResponseUsageTracker,
+) -> (Option<Value>, Value) {
+    let (usage, cost_usd) = tracker.usage_and_cost_of(message, cwd);
+    let tokens = total_tokens(&usage);
+    let usage_row =
+        (message.message_type == "assistant" && (tokens > 0 || cost_usd > 0.0)).then(|| {
+            json!({
+                "timestamp": message.timestamp,
+                "session_id": message.session_id,
+                "project_name": project_name,
+                "message_uuid": message.uuid,
+                "model": message.model,
+                "input_tokens": usage.input_tokens,
+                "output_tokens": usage.output_tokens,
+                "cache_creation_tokens": usage.cache_creation_input_tokens,
+                "cache_read_tokens": usage.cache_read_input_tokens,
+                "total_tokens": tokens,
+                "cost_usd": cost_usd,
+            })
+        });
+
+    let message_row = json!({
+        "timestamp": message.timestamp,
+        "session_id": message.session_id,
+        "project_name": project_name,
+        "uuid": message.uuid,
+        "parent_uuid": message.parent_uuid,
+        "type": message.message_type,
+        "role": message.role,
+        "model": message.model,
+        "is_sidechain": message.is_sidechain.unwrap_or(false),
+        "content": message.content.as_ref().map(Value::to_string),
+    });
+    (usage_row, message_row)
+}
+
+/// Rows of one session file, by partition directory
+fn session_rows(session_path: &Path, partitioning: Partitioning) -> BTreeMap<String, Partition> {
+    let project_name = session_project_name(session_path);
+
+    let mut partitions: BTreeMap<String, Partition> = BTreeMap::new();
+    let mut tracker = ResponseUsageTracker::default();
+    for mut entry in read_raw_log_entries(session_path) {
+        let cwd = entry.cwd.take();
+        let Ok(message) = ClaudeMessage::try_from(entry) else {
+            continue;
+        };
+        let Ok(timestamp) = DateTime::parse_from_rfc3339(&message.timestamp) else {
+            continue;
+        };
+        let partition = partitions
+            .entry(partitioning.directory(timestamp.with_timezone(&Utc)))
+            .or_default();
+
+        let (usage_row, message_row) = message_rows(
+            &message,
+            cwd.as_deref(),
+            project_name.as_deref(),
+            &mut tracker,
+        );
+        partition.usage.extend(usage_row);
+        partition.messages.push(message_row);
+    }
+    partitions
+}
+
+fn parquet_schema(table: &Table) -> String {
+    let fields: String = table
+        .fields
+        .iter()
+        .map(|(name, kind)| match kind {
+            FieldKind::Text => format!("OPTIONAL BYTE_ARRAY {name} (UTF8);"),
+            FieldKind::Int => format!("OPTIONAL INT64 {name};"),
+            FieldKind::Float => format!("OPTIONAL DOUBLE {name};"),
+            FieldKind::Bool => format!("OPTIONAL BOOLEAN {name};"),
+            FieldKind::Timestamp => format!("OPTIONAL INT64 {name} (TIMESTAMP(MICROS,true));"),
+        })
+        .collect();
+    format!("message {} {{ {fields} }}", table.name)
+}
+
+/// Non-null values of one column and the definition level of every row
+fn column_values<T>(
+    rows: &[Value],
+    name: &str,
+    convert: impl Fn(&Value) -> Option<T>,
+) -> (Vec<T>, Vec<i16>) {
+    let mut values = Vec::with_capacity(rows.len());
+    let mut levels = Vec::with_capacity(rows.len());
+    for value in rows.iter().map(|row| row.get(name).and_then(&convert)) {
+        levels.push(i16::from(value.is_some()));
+        values.extend(value);
+    }
+    (values, levels)
+}
+
+fn write_parquet(path: &Path, table: &Table, rows: &[Value]) -> Result<(), String> {
+    let to_error = |e: parquet::errors::ParquetError| format!("Failed to write Parquet file: {e}");
+    let schema = Arc::new(parse_message_type(&parquet_schema(table)).map_err(to_error)?);
+    let properties = Arc::new(
+        WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .build(),
+    );
+    let file = fs::File::create(path).map_err(|e| format!("Failed to create file: {e}"))?;
+    let mut writer = SerializedFileWriter::new(file, schema, properties).map_err(to_error)?;
+
+    let mut row_group = writer.next_row_group().map_err(to_error)?;
+    for (name, kind) in table.fields {
+        let Some(mut column) = row_group.next_column().map_err(to_error)? else {
+            break;
+        };
+        match kind {
+            FieldKind::Text => {
+                let (values, levels) = column_values(rows, name, |v| {
+                    v.as_str()
+                        .map(|text| ByteArray::from(text.as_bytes().to_vec()))
+                });
+                column
+                    .typed::<ByteArrayType>()
+                    .write_batch(&values, Some(&levels), None)
+            }
+            FieldKind::Int => {
+                let (values, levels) = column_values(rows, name, |v| {
+                    v.as_i64()
+                        .or_else(|| v.as_u64().map(|n| i64::try_from(n).unwrap_or(i64::MAX)))
+                });
+                column
+                    .typed::<Int64Type>()
+                    .write_batch(&values, Some(&levels), None)
+            }
+            FieldKind::Float => {
+                let (values, levels) = column_values(rows, name, Value::as_f64);
+                column
+                    .typed::<DoubleType>()
+                    .write_batch(&values, Some(&levels), None)
+            }
+            FieldKind::Bool => {
+                let (values, levels) = column_values(rows, name, Value::as_bool);
+                column
+                    .typed::<BoolType>()
+                    .write_batch(&values, Some(&levels), None)
+            }
+            FieldKind::Timestamp => {
+                let (values, levels) = column_values(rows, name, |v| {
+                    DateTime::parse_from_rfc3339(v.as_str()?)
+                        .ok()
+                        .map(|t| t.timestamp_micros())
+                });
+                column
+                    .typed::<Int64Type>()
+                    .write_batch(&values, Some(&levels), None)
+            }
+        }
+        .map_err(to_error)?;
+        column.close().map_err(to_error)?;
+    }
+    row_group.close().map_err(to_error)?;
+    writer.close().map_err(to_error)?;
+    Ok(())
+}
+
+fn write_ndjson(path: &Path, rows: &[Value]) -> Result<(), String> {
+    let file = fs::File::create(path).map_err(|e| format!("Failed to create file: {e}"))?;
+    let mut writer = BufWriter::new(file);
+    for row in rows {
+        serde_json::to_writer(&mut writer, row)
+            .map_err(|e| format!("Failed to serialize row: {e}"))?;
+        writer
+            .write_all(b"\n")
+            .map_err(|e| format!("Failed to write file: {e}"))?;
+    }
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to write file: {e}"))
+}
+
+/// Write the partitions of `session_files` below `dest`
+fn export_partitions(
+    session_files: &[PathBuf],
+    dest: &Path,
+    format: WarehouseFormat,
+    partitioning: Partitioning,
+) -> Result<WarehouseExportResult, String> {
+    let mut partitions: BTreeMap<String, Partition> = BTreeMap::new();
+    let per_session: Vec<_> = session_files
+        .par_iter()
+        .map(|path| session_rows(path, partitioning))
+        .collect();
+    for rows in per_session {
+        for (directory, partition) in rows {
+            let merged = partitions.entry(directory).or_default();
+            merged.messages.extend(partition.messages);
+            merged.usage.extend(partition.usage);
+        }
+    }
+
+    let extension = format.as_str();
+    let mut files = Vec::new();
+    let (mut message_rows, mut usage_rows) = (0, 0);
+    for (directory, mut partition) in partitions {
+        message_rows += partition.messages.len();
+        usage_rows += partition.usage.len();
+        for (table, rows) in [
+            (&MESSAGES, &mut partition.messages),
+            (&USAGE, &mut partition.usage),
+        ] {
+            if rows.is_empty() {
+                continue;
+            }
+            // RFC 3339 strings of one offset sort chronologically
+            rows.sort_by(|a, b| a["timestamp"].as_str().cmp(&b["timestamp"].as_str()));
+
+            let relative = format!("{}/{directory}/part-0.{extension}", table.name);
+            let path = dest.join(&relative);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create partition directory: {e}"))?;
+            }
+            match format {
+                WarehouseFormat::Parquet => write_parquet(&path, table, rows)?,
+                WarehouseFormat::Ndjson => write_ndjson(&path, rows)?,
+            }
+            files.push(relative);
+        }
+    }
+
+    Ok(WarehouseExportResult {
+        directory: dest.to_string_lossy().to_string(),
+        format: format.as_str().to_string(),
+        partition_by: partitioning.key().to_string(),
+        partition_count: files
+            .iter()
+            .filter_map(|file| file.split('/').nth(1))
+            .collect::<BTreeSet<_>>()
+            .len(),
+        message_rows,
+        usage_rows,
+        files,
+    })
+}
+
+/// Export messages and usage facts as partitioned files for a data warehouse
+///
+/// `scope` is "session", "project" or "global" with `path` pointing at the
+/// session file, project folder or Claude folder. `format` is "parquet" or
+/// "ndjson"; `partition_by` is "date" (default) or "month". Existing
+/// partitions in `dest` are overwritten.
+#[tauri::command]
+pub async fn export_warehouse(
+    scope: String,
+    path: String,
+    dest: String,
+    format: String,
+    partition_by: Option<String>,
+) -> Result<WarehouseExportResult, AppError> {
+    let _timer = OperationTimer::start("export_warehouse");
+
+    let format = WarehouseFormat::parse(&format).map_err(AppError::invalid_input)?;
+    let partitioning = Partitioning::parse(partition_by.as_deref().unwrap_or("date"))
+        .map_err(AppError::invalid_input)?;
+    let dest = PathBuf::from(dest);
+    if !dest.is_absolute() {
+        return Err(AppError::invalid_input(format!(
+            "Destination must be an absolute path: {}",
+            dest.display()
+        )));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let session_files = resolve_scope_session_files(&scope, &path)?;
+        Ok(export_partitions(
+            &session_files,
+            &dest,
+            format,
+            partitioning,
+        )?)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{prompt_line, response_line, write_jsonl};
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use tempfile::TempDir;
+
+    fn write_session(dir: &Path) -> PathBuf {
+        let project_dir = dir.join("-Users-me-demo");
+        fs::create_dir_all(&project_dir).unwrap();
+        let mut response = response_line(
+            "msg_1",
+            "2025-01-21T00:00:05Z",
+            json!([{"type": "text", "text": "hi"}]),
+            10,
+            5,
+        );
+        response["parentUuid"] = json!("u1");
+        let path = project_dir.join("s1.jsonl");
+        write_jsonl(
+            &path,
+            &[prompt_line("u1", "2025-01-20T23:59:00Z", "hello"), response],
+        );
+        path
+    }
+
+    #[test]
+    fn test_ndjson_export_is_partitioned_by_date() {
+        let temp = TempDir::new().unwrap();
+        let session = write_session(temp.path());
+        let dest = temp.path().join("out");
+
+        let result = export_partitions(
+            &[session],
+            &dest,
+            WarehouseFormat::Ndjson,
+            Partitioning::Date,
+        )
+        .unwrap();
+        assert_eq!(
+            result.files,
+            [
+                "messages/date=2025-01-20/part-0.ndjson",
+                "messages/date=2025-01-21/part-0.ndjson",
+                "usage/date=2025-01-21/part-0.ndjson",
+            ]
+        );
+        assert_eq!(result.partition_count, 2);
+        assert_eq!((result.message_rows, result.usage_rows), (2, 1));
+
+        let usage: Value = serde_json::from_str(
+            fs::read_to_string(dest.join(&result.files[2]))
+                .unwrap()
+                .trim(),
+        )
+        .unwrap();
+        assert_eq!(usage["project_name"], "demo");
+        assert_eq!(usage["total_tokens"], 15);
+        assert_eq!(usage["message_uuid"], "msg_1-2025-01-21T00:00:05Z");
+    }
+
+    #[test]
+    fn test_parquet_export_is_readable() {
+        let temp = TempDir::new().unwrap();
+        let session = write_session(temp.path());
+        let dest = temp.path().join("out");
+
+        let result = export_partitions(
+            &[session],
+            &dest,
+            WarehouseFormat::Parquet,
+            Partitioning::Month,
+        )
+        .unwrap();
+        assert_eq!(
+            result.files,
+            [
+                "messages/month=2025-01/part-0.parquet",
+                "usage/month=2025-01/part-0.parquet",
+            ]
+        );
+
+        let reader =
+            SerializedFileReader::new(fs::File::open(dest.join(&result.files[0])).unwrap())
+                .unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        let first = reader.get_row_iter(None).unwrap().next().unwrap().unwrap();
+        assert!(first.to_string().contains("hello"));
+    }
+
+    #[test]
+    fn test_rejects_unknown_format() {
+        assert!(WarehouseFormat::parse("csv").is_err());
+        assert!(Partitioning::parse("hour").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::errors::AppError;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,7 +18,7 @@ pub struct FeedbackData {
 }
 
 #[tauri::command]
-pub async fn send_feedback(feedback: FeedbackData) -> Result<(), String> {
+pub async fn send_feedback(feedback: FeedbackData) -> Result<(), AppError> {
     let mut email_body = feedback.body.clone();
 
     // Include system information
@@ -60,7 +61,7 @@ pub async fn send_feedback(feedback: FeedbackData) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn get_system_info() -> Result<SystemInfo, String> {
+pub async fn get_system_info() -> Result<SystemInfo, AppError> {
     Ok(SystemInfo {
         app_version: env!("CARGO_PKG_VERSION").to_string(),
         os_type: std::env::consts::OS.to_string(),
@@ -70,7 +71,7 @@ pub async fn get_system_info() -> Result<SystemInfo, String> {
 }
 
 #[tauri::command]
-pub async fn open_github_issues() -> Result<(), String> {
+pub async fn open_github_issues() -> Result<(), AppError> {
     let github_url = "https://github.com/jhlee0409/claude-code-history-viewer/issues/new";
 
     tauri_plugin_opener::open_url(github_url, None::<String>)
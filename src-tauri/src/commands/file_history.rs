@@ -0,0 +1,444 @@
+//! File-history storage analysis
+//!
+//! Before editing a file, Claude Code backs it up to
+//! `~/.claude/file-history/<session-id>/<path-hash>@v<version>`. Files are
+//! backed up again at every checkpoint even when unchanged, so the same
+//! content is stored many times. This module measures that storage, groups
+//! backups by content hash, and can replace duplicates by hard links to a
+//! single copy. Every backup path stays readable (rewinds keep working) while
+//! each content is stored once; backups are never modified in place, so
+//! sharing their data is safe.
+
+use crate::commands::journal::record_operation;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::io_limit::acquire_file_permit;
+use crate::models::{
+    DuplicateBackupGroup, FileHistoryCompaction, FileHistoryUsage, SessionFileHistoryUsage,
+};
+use crate::utils::{collect_session_files, display_path, file_name_string, long_path};
+use rayon::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::hash::Hasher;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Maximum number of duplicate groups returned
+const MAX_DUPLICATE_GROUPS: usize = 20;
+
+/// One backup file
+#[derive(Debug)]
+struct Backup {
+    session_id: String,
+    path: PathBuf,
+    size: u64,
+    hash: u64,
+    physical_id: Option<(u64, u64)>, // (device, inode); hard links share it
+}
+
+/// Backups with identical content, one per physical copy; the first is kept
+struct ContentGroup<'a> {
+    size: u64,
+    copies: Vec<&'a Backup>,
+}
+
+#[cfg(unix)]
+#[allow(clippy::unnecessary_wraps)] // None on platforms without inode numbers
+fn physical_id(metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn physical_id(_metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Read buffer for hashing and comparing backups
+const CHUNK_SIZE: usize = 64 * 1024;
+
+fn open_backup(path: &Path) -> io::Result<BufReader<fs::File>> {
+    fs::File::open(long_path(path)).map(|file| BufReader::with_capacity(CHUNK_SIZE, file))
+}
+
+/// Hash a backup without loading it into memory
+fn content_hash(path: &Path) -> io::Result<u64> {
+    let mut reader = open_backup(path)?;
+    let mut hasher = DefaultHasher::new();
+    loop {
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            return Ok(hasher.finish());
+        }
+        hasher.write(chunk);
+        let len = chunk.len();
+        reader.consume(len);
+    }
+}
+
+/// Compare two backups chunk by chunk
+fn same_content(a: &Path, b: &Path) -> io::Result<bool> {
+    let (mut a, mut b) = (open_backup(a)?, open_backup(b)?);
+    let mut buffer = vec![0; CHUNK_SIZE];
+    loop {
+        let chunk = a.fill_buf()?;
+        if chunk.is_empty() {
+            return Ok(b.fill_buf()?.is_empty());
+        }
+        let len = chunk.len();
+        let other = &mut buffer[..len];
+        if b.read_exact(other).is_err() || chunk != &*other {
+            return Ok(false);
+        }
+        a.consume(len);
+    }
+}
+
+/// Backup files under `directory`, sorted by path
+fn scan_backups(directory: &Path) -> Vec<Backup> {
+    let files: Vec<(String, PathBuf)> = WalkDir::new(directory)
+        .min_depth(2)
+        .max_depth(2)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let session_id = file_name_string(entry.path().parent()?)?;
+            Some((session_id, entry.into_path()))
+        })
+        .collect();
+
+    let mut backups: Vec<Backup> = files
+        .into_par_iter()
+        .filter_map(|(session_id, path)| {
+            let _permit = acquire_file_permit();
+            let metadata = fs::metadata(long_path(&path)).ok()?;
+            Some(Backup {
+                session_id,
+                size: metadata.len(),
+                hash: content_hash(&path).ok()?,
+                physical_id: physical_id(&metadata),
+                path,
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| a.path.cmp(&b.path));
+    backups
+}
+
+/// Group physical copies by content (hard links to one file count once)
+fn content_groups(backups: &[Backup]) -> Vec<ContentGroup<'_>> {
+    let mut seen_physical = HashSet::new();
+    let mut groups: HashMap<(u64, u64), Vec<&Backup>> = HashMap::new();
+    for backup in backups {
+        if backup
+            .physical_id
+            .is_some_and(|id| !seen_physical.insert(id))
+        {
+            continue;
+        }
+        groups
+            .entry((backup.size, backup.hash))
+            .or_default()
+            .push(backup);
+    }
+
+    let mut groups: Vec<ContentGroup> = groups
+        .into_values()
+        .map(|copies| ContentGroup {
+            size: copies[0].size,
+            copies,
+        })
+        .collect();
+    groups.sort_by(|a, b| a.copies[0].path.cmp(&b.copies[0].path));
+    groups
+}
+
+/// `live_sessions` holds the IDs of existing session logs (None if unknown)
+fn build_usage(
+    directory: &Path,
+    backups: &[Backup],
+    live_sessions: Option<&HashSet<String>>,
+) -> FileHistoryUsage {
+    let mut sessions: HashMap<&str, SessionFileHistoryUsage> = HashMap::new();
+    for backup in backups {
+        sessions
+            .entry(&backup.session_id)
+            .or_insert_with(|| SessionFileHistoryUsage {
+                session_id: backup.session_id.clone(),
+                file_count: 0,
+                total_bytes: 0,
+                reclaimable_bytes: 0,
+                orphaned: live_sessions.is_some_and(|live| !live.contains(&backup.session_id)),
+            })
+            .file_count += 1;
+    }
+
+    let groups = content_groups(backups);
+    let mut largest_duplicates = Vec::new();
+    for group in &groups {
+        for (index, copy) in group.copies.iter().enumerate() {
+            if let Some(session) = sessions.get_mut(copy.session_id.as_str()) {
+                session.total_bytes += group.size;
+                if index > 0 {
+                    session.reclaimable_bytes += group.size;
+                }
+            }
+        }
+        if group.copies.len() > 1 {
+            largest_duplicates.push(DuplicateBackupGroup {
+                size: group.size,
+                copies: group.copies.len(),
+                reclaimable_bytes: group.size * (group.copies.len() as u64 - 1),
+                sample_path: display_path(&group.copies[0].path),
+            });
+        }
+    }
+    largest_duplicates.sort_by(|a, b| {
+        b.reclaimable_bytes
+            .cmp(&a.reclaimable_bytes)
+            .then_with(|| a.sample_path.cmp(&b.sample_path))
+    });
+    largest_duplicates.truncate(MAX_DUPLICATE_GROUPS);
+
+    let mut sessions: Vec<SessionFileHistoryUsage> = sessions.into_values().collect();
+    sessions.sort_by(|a, b| {
+        b.total_bytes
+            .cmp(&a.total_bytes)
+            .then_with(|| a.session_id.cmp(&b.session_id))
+    });
+    let orphaned: Vec<&SessionFileHistoryUsage> = sessions.iter().filter(|s| s.orphaned).collect();
+
+    FileHistoryUsage {
+        directory: display_path(directory),
+        session_count: sessions.len(),
+        file_count: backups.len(),
+        total_bytes: sessions.iter().map(|s| s.total_bytes).sum(),
+        unique_contents: groups.len(),
+        unique_bytes: groups.iter().map(|g| g.size).sum(),
+        reclaimable_bytes: sessions.iter().map(|s| s.reclaimable_bytes).sum(),
+        orphaned_sessions: orphaned.len(),
+        orphaned_bytes: orphaned.iter().map(|s| s.total_bytes).sum(),
+        sessions,
+        largest_duplicates,
+    }
+}
+
+/// Replace `duplicate` by a hard link to `original`
+fn link_duplicate(original: &Path, duplicate: &Path) -> Result<(), String> {
+    // Content hashes can collide: only link byte-identical files
+    if !same_content(original, duplicate).map_err(|e| format!("Failed to read: {e}"))? {
+        return Err("Content differs from the kept copy".to_string());
+    }
+
+    let file_name = file_name_string(duplicate).ok_or("Invalid backup path")?;
+    let temp = duplicate.with_file_name(format!("{file_name}.dedupe-tmp"));
+    let _ = fs::remove_file(long_path(&temp));
+    fs::hard_link(long_path(original), long_path(&temp))
+        .map_err(|e| format!("Failed to create hard link: {e}"))?;
+    fs::rename(long_path(&temp), long_path(duplicate)).map_err(|e| {
+        let _ = fs::remove_file(long_path(&temp));
+        format!("Failed to replace file: {e}")
+    })
+}
+
+fn compact_backups(backups: &[Backup], dry_run: bool) -> FileHistoryCompaction {
+    let mut compaction = FileHistoryCompaction {
+        dry_run,
+        linked_files: 0,
+        reclaimed_bytes: 0,
+        failed: Vec::new(),
+    };
+    for group in content_groups(backups) {
+        let Some((kept, duplicates)) = group.copies.split_first() else {
+            continue;
+        };
+        for duplicate in duplicates {
+            let result = if dry_run {
+                Ok(())
+            } else {
+                link_duplicate(&kept.path, &duplicate.path)
+            };
+            match result {
+                Ok(()) => {
+                    compaction.linked_files += 1;
+                    compaction.reclaimed_bytes += group.size;
+                }
+                Err(e) => compaction
+                    .failed
+                    .push(format!("{}: {e}", display_path(&duplicate.path))),
+            }
+        }
+    }
+    compaction
+}
+
+fn file_history_dir(claude_path: &str) -> Result<PathBuf, String> {
+    let directory = PathBuf::from(claude_path).join("file-history");
+    if directory.is_dir() {
+        Ok(directory)
+    } else {
+        Err("File history directory not found".to_string())
+    }
+}
+
+/// Disk usage of the file-history backups and the space deduplication
+/// would reclaim
+#[tauri::command]
+pub async fn get_file_history_usage(claude_path: String) -> Result<FileHistoryUsage, AppError> {
+    let _timer = OperationTimer::start("get_file_history_usage");
+    let directory = file_history_dir(&claude_path)?;
+    let projects_path = PathBuf::from(&claude_path).join("projects");
+
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let live_sessions: Option<HashSet<String>> =
+            collect_session_files(&projects_path).ok().map(|files| {
+                files
+                    .iter()
+                    .filter_map(|(_, path)| Some(path.file_stem()?.to_string_lossy().to_string()))
+                    .collect()
+            });
+        build_usage(
+            &directory,
+            &scan_backups(&directory),
+            live_sessions.as_ref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?)
+}
+
+/// Replace duplicate file-history backups by hard links to one copy
+///
+/// With `dry_run`, only reports what would be linked. Unix only: elsewhere
+/// linked backups cannot be told apart from copies, so they would be
+/// reported as reclaimable again on every run.
+#[tauri::command]
+pub async fn compact_file_history(
+    claude_path: String,
+    dry_run: bool,
+) -> Result<FileHistoryCompaction, AppError> {
+    let _timer = OperationTimer::start("compact_file_history");
+    if cfg!(not(unix)) {
+        return Err(AppError::invalid_input(
+            "File-history compaction is not supported on this platform",
+        ));
+    }
+    let directory = file_history_dir(&claude_path)?;
+
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let compaction = compact_backups(&scan_backups(&directory), dry_run);
+        if !dry_run && compaction.linked_files > 0 {
+            record_operation(
+                "compact_file_history",
+                format!(
+                    "Linked {} duplicate file-history backups ({} bytes reclaimed)",
+                    compaction.linked_files, compaction.reclaimed_bytes
+                ),
+                None,
+            );
+        }
+        compaction
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// s1 stores "v1" twice, s2 a third copy plus unique content
+    fn write_history(root: &Path) -> PathBuf {
+        let directory = root.join("file-history");
+        for (session, name, content) in [
+            ("s1", "aaaa@v1", "fn main() {}\n"),
+            ("s1", "aaaa@v2", "fn main() {}\n"),
+            ("s2", "bbbb@v1", "fn main() {}\n"),
+            ("s2", "cccc@v1", "unique\n"),
+        ] {
+            fs::create_dir_all(directory.join(session)).unwrap();
+            fs::write(directory.join(session).join(name), content).unwrap();
+        }
+        directory
+    }
+
+    #[test]
+    fn test_build_usage_counts_duplicates_and_orphans() {
+        let temp = TempDir::new().unwrap();
+        let directory = write_history(temp.path());
+        let live = HashSet::from(["s1".to_string()]);
+
+        let usage = build_usage(&directory, &scan_backups(&directory), Some(&live));
+
+        assert_eq!(usage.file_count, 4);
+        assert_eq!(usage.session_count, 2);
+        assert_eq!(usage.total_bytes, 13 * 3 + 7);
+        assert_eq!(usage.unique_contents, 2);
+        assert_eq!(usage.unique_bytes, 13 + 7);
+        assert_eq!(usage.reclaimable_bytes, 26);
+        assert_eq!(usage.orphaned_sessions, 1);
+        assert_eq!(usage.orphaned_bytes, 13 + 7);
+        assert_eq!(usage.largest_duplicates.len(), 1);
+        assert_eq!(usage.largest_duplicates[0].copies, 3);
+
+        let s1 = usage
+            .sessions
+            .iter()
+            .find(|s| s.session_id == "s1")
+            .unwrap();
+        assert_eq!((s1.file_count, s1.reclaimable_bytes), (2, 13));
+        assert!(!s1.orphaned);
+    }
+
+    #[test]
+    fn test_same_content_compares_across_chunks() {
+        let temp = TempDir::new().unwrap();
+        let content = vec![7u8; CHUNK_SIZE * 2 + 5];
+        let mut changed = content.clone();
+        changed[CHUNK_SIZE + 1] = 8;
+        let paths = ["a", "b", "c", "d"].map(|name| temp.path().join(name));
+        fs::write(&paths[0], &content).unwrap();
+        fs::write(&paths[1], &content).unwrap();
+        fs::write(&paths[2], &changed).unwrap();
+        fs::write(&paths[3], &content[..CHUNK_SIZE]).unwrap();
+
+        assert!(same_content(&paths[0], &paths[1]).unwrap());
+        assert!(!same_content(&paths[0], &paths[2]).unwrap());
+        assert!(!same_content(&paths[0], &paths[3]).unwrap());
+        assert!(!same_content(&paths[3], &paths[0]).unwrap());
+        assert_eq!(
+            content_hash(&paths[0]).unwrap(),
+            content_hash(&paths[1]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_compact_backups_links_identical_files() {
+        let temp = TempDir::new().unwrap();
+        let directory = write_history(temp.path());
+
+        let dry_run = compact_backups(&scan_backups(&directory), true);
+        assert_eq!((dry_run.linked_files, dry_run.reclaimed_bytes), (2, 26));
+
+        let compaction = compact_backups(&scan_backups(&directory), false);
+        assert_eq!(compaction.linked_files, 2);
+        assert!(compaction.failed.is_empty(), "{:?}", compaction.failed);
+        assert_eq!(
+            fs::read_to_string(directory.join("s2").join("bbbb@v1")).unwrap(),
+            "fn main() {}\n"
+        );
+        assert_eq!(fs::read_dir(directory.join("s1")).unwrap().count(), 2);
+
+        #[cfg(unix)]
+        {
+            let usage = build_usage(&directory, &scan_backups(&directory), None);
+            assert_eq!(usage.total_bytes, 13 + 7);
+            assert_eq!(usage.reclaimable_bytes, 0);
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Focus-time estimate
+//!
+//! A session's first-to-last message span counts the lunch break and the
+//! night it was left open. Turns of all sessions are merged instead and cut
+//! into blocks wherever the pause between two turns exceeds the idle gap,
+//! the way OS idle detection would; each block is credited a short trailing
+//! allowance for reading the last answer.
+
+use crate::commands::export::session_project_name;
+use crate::commands::session::TimeRange;
+use crate::commands::stats::{read_raw_log_entries, resolve_scope_session_files};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{FocusBlock, FocusDay, FocusReport};
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::Path;
+
+/// Default pause between turns that ends a focus block
+pub(crate) const DEFAULT_IDLE_GAP_MINUTES: u32 = 15;
+
+/// Default time credited after the last turn of a block
+const DEFAULT_TRAILING_MINUTES: u32 = 5;
+
+/// Longest accepted idle gap (a day)
+pub(crate) const MAX_IDLE_GAP_MINUTES: u32 = 24 * 60;
+
+/// A user prompt or assistant response of the main conversation
+struct Turn {
+    time: DateTime<Utc>,
+    session_id: String,
+    project_name: Option<String>,
+}
+
+fn minutes(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to - from).num_milliseconds() as f64 / 60_000.0
+}
+
+/// Turns of a session in `range`, with the session's first-to-last span
+fn session_turns(session_path: &Path, range: TimeRange) -> (Vec<Turn>, f64) {
+    if !range.overlaps_file(session_path) {
+        return (Vec::new(), 0.0);
+    }
+    let project_name = session_project_name(session_path);
+    let turns: Vec<Turn> = read_raw_log_entries(session_path)
+        .into_iter()
+        .filter(|entry| matches!(entry.message_type.as_str(), "user" | "assistant"))
+        .filter(|entry| entry.is_sidechain != Some(true))
+        .filter_map(|entry| {
+            let timestamp = entry.timestamp.as_deref()?;
+            if !range.contains(timestamp) {
+                return None;
+            }
+            Some(Turn {
+                time: DateTime::parse_from_rfc3339(timestamp)
+                    .ok()?
+                    .with_timezone(&Utc),
+                session_id: entry.session_id.unwrap_or_default(),
+                project_name: project_name.clone(),
+            })
+        })
+        .collect();
+
+    let first = turns.iter().map(|turn| turn.time).min();
+    let last = turns.iter().map(|turn| turn.time).max();
+    let span = first
+        .zip(last)
+        .map_or(0.0, |(first, last)| minutes(first, last));
+    (turns, span)
+}
+
+fn focus_block(turns: &[&Turn], trailing_minutes: u32) -> FocusBlock {
+    let start = turns[0].time;
+    let end = turns[turns.len() - 1].time;
+    let sessions: HashSet<&str> = turns.iter().map(|turn| turn.session_id.as_str()).collect();
+    let projects: BTreeSet<&str> = turns
+        .iter()
+        .filter_map(|turn| turn.project_name.as_deref())
+        .collect();
+    FocusBlock {
+        start_time: start.to_rfc3339_opts(SecondsFormat::Secs, true),
+        end_time: end.to_rfc3339_opts(SecondsFormat::Secs, true),
+        focus_minutes: minutes(start, end) + f64::from(trailing_minutes),
+        turn_count: turns.len(),
+        session_count: sessions.len(),
+        project_names: projects.into_iter().map(str::to_string).collect(),
+    }
+}
+
+/// Cut the turns of one day (sorted by time) into focus blocks
+fn focus_day(
+    date: NaiveDate,
+    turns: &[&Turn],
+    idle_gap_minutes: u32,
+    trailing_minutes: u32,
+) -> FocusDay {
+    let idle_gap = f64::from(idle_gap_minutes);
+    let blocks: Vec<FocusBlock> = turns
+        .chunk_by(|a, b| minutes(a.time, b.time) <= idle_gap)
+        .map(|block| focus_block(block, trailing_minutes))
+        .collect();
+    FocusDay {
+        date: date.format("%Y-%m-%d").to_string(),
+        focus_minutes: blocks.iter().map(|block| block.focus_minutes).sum(),
+        span_minutes: minutes(turns[0].time, turns[turns.len() - 1].time),
+        blocks,
+    }
+}
+
+fn focus_report(
+    scope: String,
+    sessions: Vec<(Vec<Turn>, f64)>,
+    idle_gap_minutes: u32,
+    trailing_minutes: u32,
+) -> FocusReport {
+    let session_span_minutes = sessions.iter().map(|(_, span)| span).sum();
+    let mut turns: Vec<Turn> = sessions.into_iter().flat_map(|(turns, _)| turns).collect();
+    turns.sort_by_key(|turn| turn.time);
+
+    let mut by_day: BTreeMap<NaiveDate, Vec<&Turn>> = BTreeMap::new();
+    for turn in &turns {
+        by_day.entry(turn.time.date_naive()).or_default().push(turn);
+    }
+    let days: Vec<FocusDay> = by_day
+        .into_iter()
+        .map(|(date, turns)| focus_day(date, &turns, idle_gap_minutes, trailing_minutes))
+        .collect();
+
+    FocusReport {
+        scope,
+        idle_gap_minutes,
+        trailing_minutes,
+        total_focus_minutes: days.iter().map(|day| day.focus_minutes).sum(),
+        session_span_minutes,
+        days,
+    }
+}
+
+/// Estimated focused time per day, from turn timestamps merged across
+/// sessions into work blocks
+///
+/// `scope` is "session", "project" or "global" with `path` pointing at the
+/// session file, project folder or Claude folder. `from`/`to` are RFC 3339
+/// timestamps or `YYYY-MM-DD` dates. A pause longer than
+/// `idle_gap_minutes` (default 15) ends a block; `trailing_minutes`
+/// (default 5) are credited after each block's last turn.
+#[tauri::command]
+pub async fn get_focus_report(
+    scope: String,
+    path: String,
+    from: Option<String>,
+    to: Option<String>,
+    idle_gap_minutes: Option<u32>,
+    trailing_minutes: Option<u32>,
+) -> Result<FocusReport, AppError> {
+    let _timer = OperationTimer::start("get_focus_report");
+
+    let range =
+        TimeRange::parse(from.as_deref(), to.as_deref()).map_err(AppError::invalid_input)?;
+    let idle_gap_minutes = idle_gap_minutes.unwrap_or(DEFAULT_IDLE_GAP_MINUTES);
+    if !(1..=MAX_IDLE_GAP_MINUTES).contains(&idle_gap_minutes) {
+        return Err(AppError::invalid_input(format!(
+            "Idle gap must be between 1 and {MAX_IDLE_GAP_MINUTES} minutes"
+        )));
+    }
+    let trailing_minutes = trailing_minutes.unwrap_or(DEFAULT_TRAILING_MINUTES);
+    if trailing_minutes > idle_gap_minutes {
+        return Err(AppError::invalid_input(
+            "Trailing allowance cannot exceed the idle gap",
+        ));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let session_files = resolve_scope_session_files(&scope, &path)?;
+        let sessions: Vec<(Vec<Turn>, f64)> = session_files
+            .par_iter()
+            .map(|path| session_turns(path, range))
+            .collect();
+        Ok(focus_report(
+            scope,
+            sessions,
+            idle_gap_minutes,
+            trailing_minutes,
+        ))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn turn(session_id: &str, kind: &str, time: &str) -> String {
+        json!({
+            "uuid": format!("{session_id}-{time}"),
+            "sessionId": session_id,
+            "timestamp": time,
+            "type": kind,
+            "message": {"role": kind, "content": "..."}
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_focus_report_merges_sessions_into_blocks() {
+        let temp = TempDir::new().unwrap();
+        let project_dir = temp.path().join("-Users-me-demo");
+        fs::create_dir_all(&project_dir).unwrap();
+        // Session s1 is left open over lunch; s2 overlaps its first block
+        let s1 = [
+            turn("s1", "user", "2025-03-03T09:00:00Z"),
+            turn("s1", "assistant", "2025-03-03T09:10:00Z"),
+            turn("s1", "user", "2025-03-03T13:00:00Z"),
+            turn("s1", "assistant", "2025-03-03T13:12:00Z"),
+        ];
+        let s2 = [
+            turn("s2", "user", "2025-03-03T09:20:00Z"),
+            turn("s2", "assistant", "2025-03-03T09:30:00Z"),
+            turn("s2", "user", "2025-03-04T10:00:00Z"),
+        ];
+        fs::write(project_dir.join("s1.jsonl"), s1.join("\n")).unwrap();
+        fs::write(project_dir.join("s2.jsonl"), s2.join("\n")).unwrap();
+
+        let report = get_focus_report(
+            "project".to_string(),
+            project_dir.to_string_lossy().to_string(),
+            None,
+            Some("2025-03-03".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.days.len(), 1);
+        let day = &report.days[0];
+        assert_eq!(day.date, "2025-03-03");
+        let blocks: Vec<(&str, f64, usize)> = day
+            .blocks
+            .iter()
+            .map(|b| (b.start_time.as_str(), b.focus_minutes, b.session_count))
+            .collect();
+        assert_eq!(
+            blocks,
+            [
+                ("2025-03-03T09:00:00Z", 35.0, 2),
+                ("2025-03-03T13:00:00Z", 17.0, 1),
+            ]
+        );
+        assert_eq!(day.blocks[0].project_names, ["demo"]);
+        assert!((day.span_minutes - 252.0).abs() < 1e-9);
+        assert!((report.total_focus_minutes - 52.0).abs() < 1e-9);
+        assert!((report.session_span_minutes - 262.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_focus_report_validates_thresholds() {
+        let result = get_focus_report(
+            "global".to_string(),
+            "/nonexistent".to_string(),
+            None,
+            None,
+            Some(10),
+            Some(20),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::InvalidInput { .. })));
+    }
+}
@@ -0,0 +1,683 @@
+//! Hook latency impact analysis
+//!
+//! Claude Code logs a `hook_progress` progress entry when `PreToolUse`/`PostToolUse`
+//! hooks start, and a `stop_hook_summary` system entry once Stop hooks finish.
+//! Hook latency is estimated from the gap between those entries and their
+//! neighbouring log entries.
+//!
+//! `get_hook_stats` counts runs per hook command from the same entries, with
+//! the continuations Stop hooks prevented and the errors they reported.
+
+use crate::commands::stats::{read_raw_log_entries, resolve_scope_session_files};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{
+    HookCommandLatency, HookEventLatency, HookLatencyStats, HookStats, HookUsage, ProjectHookStats,
+    RawLogEntry, SessionHookLatency,
+};
+use crate::utils::{extract_project_name, file_name_string};
+use chrono::{DateTime, Utc};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Gaps longer than this are treated as idle time, not hook latency
+const MAX_HOOK_LATENCY_MS: i64 = 10 * 60 * 1000;
+
+/// Maximum number of commands and sessions returned
+const MAX_RESULTS: usize = 20;
+
+/// One estimated hook execution
+#[derive(Debug, Clone, PartialEq)]
+struct HookTiming {
+    event: String,
+    command: String,
+    latency_ms: u64,
+}
+
+/// Hook timings of a session; `group_ms` counts hooks that ran together once
+#[derive(Debug, Default)]
+struct SessionHookTimings {
+    session_id: String,
+    project_name: String,
+    commands: Vec<HookTiming>,
+    groups: Vec<(String, u64)>, // (event, latency)
+}
+
+fn parse_time(entry: &RawLogEntry) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(entry.timestamp.as_deref()?)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+fn gap_ms(start: DateTime<Utc>, end: DateTime<Utc>) -> Option<u64> {
+    let ms = (end - start).num_milliseconds();
+    (0..=MAX_HOOK_LATENCY_MS).contains(&ms).then_some(ms as u64)
+}
+
+/// (event, command, tool use ID) of a `hook_progress` entry
+fn hook_progress_info(entry: &RawLogEntry) -> Option<(String, String, Option<&str>)> {
+    if entry.message_type != "progress" {
+        return None;
+    }
+    let data = entry.data.as_ref()?;
+    if data.get("type").and_then(|v| v.as_str()) != Some("hook_progress") {
+        return None;
+    }
+
+    let hook_name = data.get("hookName").and_then(|v| v.as_str());
+    let event = data
+        .get("hookEvent")
+        .and_then(|v| v.as_str())
+        .or_else(|| hook_name.and_then(|name| name.split(':').next()))
+        .unwrap_or("unknown")
+        .to_string();
+    let command = data
+        .get("command")
+        .and_then(|v| v.as_str())
+        .or(hook_name)
+        .unwrap_or("unknown")
+        .to_string();
+
+    Some((event, command, entry.tool_use_id.as_deref()))
+}
+
+fn collect_hook_timings(entries: &[RawLogEntry]) -> (Vec<HookTiming>, Vec<(String, u64)>) {
+    let times: Vec<Option<DateTime<Utc>>> = entries.iter().map(parse_time).collect();
+    let mut commands = Vec::new();
+    let mut groups = Vec::new();
+
+    let mut idx = 0;
+    while idx < entries.len() {
+        let entry = &entries[idx];
+
+        // Stop hooks: the summary is written once all of them have finished
+        if entry.message_type == "system" && entry.subtype.as_deref() == Some("stop_hook_summary") {
+            let previous = times[..idx].iter().rev().find_map(|t| *t);
+            if let Some(latency) = previous.zip(times[idx]).and_then(|(s, e)| gap_ms(s, e)) {
+                groups.push(("Stop".to_string(), latency));
+                let hook_commands: Vec<&str> = entry
+                    .hook_infos
+                    .as_ref()
+                    .and_then(|infos| infos.as_array())
+                    .map(|infos| {
+                        infos
+                            .iter()
+                            .filter_map(|info| info.get("command").and_then(|v| v.as_str()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                // Stop hooks run in parallel, so each one may have taken the whole gap
+                for command in hook_commands {
+                    commands.push(HookTiming {
+                        event: "Stop".to_string(),
+                        command: command.to_string(),
+                        latency_ms: latency,
+                    });
+                }
+            }
+            idx += 1;
+            continue;
+        }
+
+        // Tool hooks: consecutive hook_progress entries of the same event and
+        // tool call form a group that ends at the next unrelated entry
+        let Some((event, _, tool_use_id)) = hook_progress_info(entry) else {
+            idx += 1;
+            continue;
+        };
+        let mut group_end = idx + 1;
+        while let Some((next_event, _, next_tool_use_id)) =
+            entries.get(group_end).and_then(hook_progress_info)
+        {
+            if next_event != event || next_tool_use_id != tool_use_id {
+                break;
+            }
+            group_end += 1;
+        }
+
+        let end_time = times[group_end..].iter().find_map(|t| *t);
+        if let Some(end_time) = end_time {
+            if let Some(latency) = times[idx].and_then(|start| gap_ms(start, end_time)) {
+                groups.push((event.clone(), latency));
+            }
+            for member in idx..group_end {
+                let Some((member_event, command, _)) = hook_progress_info(&entries[member]) else {
+                    continue;
+                };
+                if let Some(latency) = times[member].and_then(|start| gap_ms(start, end_time)) {
+                    commands.push(HookTiming {
+                        event: member_event,
+                        command,
+                        latency_ms: latency,
+                    });
+                }
+            }
+        }
+        idx = group_end;
+    }
+
+    (commands, groups)
+}
+
+fn session_project_name(session_path: &Path) -> String {
+    session_path
+        .parent()
+        .and_then(file_name_string)
+        .map(|name| extract_project_name(&name))
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+fn analyze_session(session_path: &Path) -> SessionHookTimings {
+    let entries = read_raw_log_entries(session_path);
+    let (commands, groups) = collect_hook_timings(&entries);
+
+    SessionHookTimings {
+        session_id: entries
+            .iter()
+            .find_map(|e| e.session_id.clone())
+            .or_else(|| {
+                session_path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+            })
+            .unwrap_or_else(|| "unknown-session".to_string()),
+        project_name: session_project_name(session_path),
+        commands,
+        groups,
+    }
+}
+
+/// One hook run, from a `hook_progress` entry or a Stop hook summary
+#[derive(Debug, Clone, PartialEq)]
+struct HookRun {
+    event: String,
+    command: String,
+    prevented: bool,
+    failed: bool,
+}
+
+/// Hook runs of a session
+#[derive(Debug, Default)]
+struct SessionHookRuns {
+    project_name: String,
+    runs: Vec<HookRun>,
+    stop_summaries: usize,
+    prevented_continuations: usize,
+    failed_summaries: usize,
+}
+
+/// Commands of a Stop hook summary, "unknown" for hooks `hookInfos` leaves out
+fn stop_hook_commands(entry: &RawLogEntry) -> Vec<String> {
+    let mut commands: Vec<String> = entry
+        .hook_infos
+        .as_ref()
+        .and_then(|infos| infos.as_array())
+        .map(|infos| {
+            infos
+                .iter()
+                .map(|info| {
+                    info.get("command")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("unknown")
+                        .to_string()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let hook_count = entry.hook_count.unwrap_or(0) as usize;
+    if commands.len() < hook_count {
+        commands.resize(hook_count, "unknown".to_string());
+    }
+    commands
+}
+
+/// Collect the hook runs of a session
+///
+/// The log does not say which Stop hook blocked, so a prevented continuation
+/// counts against every hook of the summary. Errors count against the hooks
+/// whose command they mention, or against all of them when none is named.
+fn collect_hook_runs(entries: &[RawLogEntry]) -> SessionHookRuns {
+    let mut session = SessionHookRuns::default();
+    for entry in entries {
+        if let Some((event, command, _)) = hook_progress_info(entry) {
+            session.runs.push(HookRun {
+                event,
+                command,
+                prevented: false,
+                failed: false,
+            });
+            continue;
+        }
+        if entry.message_type != "system" || entry.subtype.as_deref() != Some("stop_hook_summary") {
+            continue;
+        }
+
+        let prevented = entry.prevented_continuation == Some(true);
+        let errors: Vec<String> = entry
+            .hook_errors
+            .as_ref()
+            .and_then(|e| e.as_array())
+            .into_iter()
+            .flatten()
+            .map(|error| {
+                error
+                    .as_str()
+                    .map_or_else(|| error.to_string(), str::to_string)
+            })
+            .collect();
+        let commands = stop_hook_commands(entry);
+        let named: HashSet<&str> = commands
+            .iter()
+            .map(String::as_str)
+            .filter(|command| errors.iter().any(|error| error.contains(command)))
+            .collect();
+
+        session.stop_summaries += 1;
+        session.prevented_continuations += usize::from(prevented);
+        session.failed_summaries += usize::from(!errors.is_empty());
+        for command in &commands {
+            session.runs.push(HookRun {
+                event: "Stop".to_string(),
+                command: command.clone(),
+                prevented,
+                failed: !errors.is_empty()
+                    && (named.is_empty() || named.contains(command.as_str())),
+            });
+        }
+    }
+    session
+}
+
+/// Hook usage by (command, event)
+type HookUsageMap = HashMap<(String, String), HookUsage>;
+
+fn build_hook_stats(sessions: Vec<SessionHookRuns>) -> HookStats {
+    let session_count = sessions.len();
+    let mut projects: HashMap<String, (ProjectHookStats, HookUsageMap)> = HashMap::new();
+
+    for session in sessions {
+        if session.runs.is_empty() && session.stop_summaries == 0 {
+            continue;
+        }
+        let (project, hooks) = projects
+            .entry(session.project_name.clone())
+            .or_insert_with(|| {
+                (
+                    ProjectHookStats {
+                        project_name: session.project_name.clone(),
+                        sessions_with_hooks: 0,
+                        total_runs: 0,
+                        stop_summaries: 0,
+                        prevented_continuations: 0,
+                        failed_summaries: 0,
+                        hooks: Vec::new(),
+                    },
+                    HashMap::new(),
+                )
+            });
+        project.sessions_with_hooks += 1;
+        project.total_runs += session.runs.len();
+        project.stop_summaries += session.stop_summaries;
+        project.prevented_continuations += session.prevented_continuations;
+        project.failed_summaries += session.failed_summaries;
+
+        let mut seen: HashSet<(String, String)> = HashSet::new();
+        for run in session.runs {
+            let key = (run.command.clone(), run.event.clone());
+            let usage = hooks.entry(key.clone()).or_insert_with(|| HookUsage {
+                command: run.command,
+                event: run.event,
+                ..Default::default()
+            });
+            usage.runs += 1;
+            usage.prevented_continuations += usize::from(run.prevented);
+            usage.failures += usize::from(run.failed);
+            if seen.insert(key) {
+                usage.sessions += 1;
+            }
+        }
+    }
+
+    let mut projects: Vec<ProjectHookStats> = projects
+        .into_values()
+        .map(|(mut project, hooks)| {
+            project.hooks = hooks
+                .into_values()
+                .map(|mut usage| {
+                    usage.prevent_rate = usage.prevented_continuations as f64 / usage.runs as f64;
+                    usage.failure_rate = usage.failures as f64 / usage.runs as f64;
+                    usage
+                })
+                .collect();
+            project.hooks.sort_by(|a, b| {
+                b.runs
+                    .cmp(&a.runs)
+                    .then_with(|| a.command.cmp(&b.command))
+                    .then_with(|| a.event.cmp(&b.event))
+            });
+            project
+        })
+        .collect();
+    projects.sort_by(|a, b| {
+        b.total_runs
+            .cmp(&a.total_runs)
+            .then_with(|| a.project_name.cmp(&b.project_name))
+    });
+
+    HookStats {
+        session_count,
+        sessions_with_hooks: projects.iter().map(|p| p.sessions_with_hooks).sum(),
+        total_runs: projects.iter().map(|p| p.total_runs).sum(),
+        projects,
+    }
+}
+
+fn build_hook_latency_stats(sessions: Vec<SessionHookTimings>) -> HookLatencyStats {
+    let session_count = sessions.len();
+    let mut by_event: HashMap<String, HookEventLatency> = HashMap::new();
+    let mut by_command: HashMap<(String, String), HookCommandLatency> = HashMap::new();
+    let mut session_latencies = Vec::new();
+
+    for session in sessions {
+        if session.groups.is_empty() && session.commands.is_empty() {
+            continue;
+        }
+
+        for (event, latency) in &session.groups {
+            let stats = by_event
+                .entry(event.clone())
+                .or_insert_with(|| HookEventLatency {
+                    event: event.clone(),
+                    ..Default::default()
+                });
+            stats.invocations += 1;
+            stats.total_ms += latency;
+            stats.max_ms = stats.max_ms.max(*latency);
+        }
+
+        for timing in &session.commands {
+            let stats = by_command
+                .entry((timing.command.clone(), timing.event.clone()))
+                .or_insert_with(|| HookCommandLatency {
+                    command: timing.command.clone(),
+                    event: timing.event.clone(),
+                    ..Default::default()
+                });
+            stats.invocations += 1;
+            stats.total_ms += timing.latency_ms;
+            stats.max_ms = stats.max_ms.max(timing.latency_ms);
+        }
+
+        session_latencies.push(SessionHookLatency {
+            session_id: session.session_id,
+            project_name: session.project_name,
+            hook_invocations: session.commands.len(),
+            total_hook_ms: session.groups.iter().map(|(_, latency)| latency).sum(),
+        });
+    }
+
+    let mut by_event: Vec<HookEventLatency> = by_event
+        .into_values()
+        .map(|mut stats| {
+            stats.avg_ms = stats.total_ms as f64 / stats.invocations as f64;
+            stats
+        })
+        .collect();
+    by_event.sort_by(|a, b| {
+        b.total_ms
+            .cmp(&a.total_ms)
+            .then_with(|| a.event.cmp(&b.event))
+    });
+
+    let mut slowest_commands: Vec<HookCommandLatency> = by_command
+        .into_values()
+        .map(|mut stats| {
+            stats.avg_ms = stats.total_ms as f64 / stats.invocations as f64;
+            stats
+        })
+        .collect();
+    slowest_commands.sort_by(|a, b| {
+        b.avg_ms
+            .total_cmp(&a.avg_ms)
+            .then_with(|| a.command.cmp(&b.command))
+    });
+    slowest_commands.truncate(MAX_RESULTS);
+
+    let sessions_with_hooks = session_latencies.len();
+    let total_hook_ms: u64 = session_latencies.iter().map(|s| s.total_hook_ms).sum();
+    session_latencies.sort_by(|a, b| {
+        b.total_hook_ms
+            .cmp(&a.total_hook_ms)
+            .then_with(|| a.session_id.cmp(&b.session_id))
+    });
+    session_latencies.truncate(MAX_RESULTS);
+
+    HookLatencyStats {
+        session_count,
+        sessions_with_hooks,
+        total_hook_ms,
+        avg_hook_ms_per_session: if sessions_with_hooks > 0 {
+            total_hook_ms as f64 / sessions_with_hooks as f64
+        } else {
+            0.0
+        },
+        by_event,
+        slowest_commands,
+        sessions: session_latencies,
+    }
+}
+
+/// Estimate how much wall-clock time hooks add, per event, command and session
+///
+/// `scope` is "session", "project" or "global", as for `get_token_histograms`.
+#[tauri::command]
+pub async fn get_hook_latency_stats(
+    scope: String,
+    path: String,
+) -> Result<HookLatencyStats, AppError> {
+    let _timer = OperationTimer::start("get_hook_latency_stats");
+    let session_files = resolve_scope_session_files(&scope, &path)?;
+
+    let sessions: Vec<SessionHookTimings> = session_files
+        .par_iter()
+        .map(|path| analyze_session(path))
+        .collect();
+
+    Ok(build_hook_latency_stats(sessions))
+}
+
+/// Count hook runs, prevented continuations and failures per project and command
+///
+/// `scope` is "session", "project" or "global", as for `get_token_histograms`.
+#[tauri::command]
+pub async fn get_hook_stats(scope: String, path: String) -> Result<HookStats, AppError> {
+    let _timer = OperationTimer::start("get_hook_stats");
+    let session_files = resolve_scope_session_files(&scope, &path)?;
+
+    let sessions: Vec<SessionHookRuns> = session_files
+        .par_iter()
+        .map(|path| SessionHookRuns {
+            project_name: session_project_name(path),
+            ..collect_hook_runs(&read_raw_log_entries(path))
+        })
+        .collect();
+
+    Ok(build_hook_stats(sessions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::raw_entry;
+    use serde_json::json;
+
+    fn hook_progress(ts: &str, event: &str, command: &str, tool_use_id: &str) -> RawLogEntry {
+        raw_entry(json!({
+            "type": "progress",
+            "timestamp": ts,
+            "toolUseID": tool_use_id,
+            "data": {"type": "hook_progress", "hookEvent": event, "hookName": format!("{event}:Bash"), "command": command}
+        }))
+    }
+
+    fn plain(ts: &str, message_type: &str) -> RawLogEntry {
+        raw_entry(json!({"type": message_type, "timestamp": ts}))
+    }
+
+    #[test]
+    fn test_tool_hooks_grouped_until_next_entry() {
+        let entries = vec![
+            plain("2025-01-01T00:00:00.000Z", "assistant"),
+            hook_progress("2025-01-01T00:00:00.100Z", "PreToolUse", "lint.sh", "t1"),
+            hook_progress("2025-01-01T00:00:00.200Z", "PreToolUse", "guard.sh", "t1"),
+            plain("2025-01-01T00:00:01.100Z", "user"),
+            hook_progress("2025-01-01T00:00:02.000Z", "PostToolUse", "fmt.sh", "t1"),
+            plain("2025-01-01T00:00:05.000Z", "assistant"),
+        ];
+
+        let (commands, groups) = collect_hook_timings(&entries);
+
+        assert_eq!(
+            groups,
+            vec![
+                ("PreToolUse".to_string(), 1000),
+                ("PostToolUse".to_string(), 3000)
+            ]
+        );
+        assert_eq!(commands.len(), 3);
+        assert_eq!(commands[0].command, "lint.sh");
+        assert_eq!(commands[0].latency_ms, 1000);
+        assert_eq!(commands[1].latency_ms, 900);
+        assert_eq!(commands[2].latency_ms, 3000);
+    }
+
+    #[test]
+    fn test_stop_hook_summary_latency() {
+        let entries = vec![
+            plain("2025-01-01T00:00:00Z", "assistant"),
+            raw_entry(json!({
+                "type": "system",
+                "subtype": "stop_hook_summary",
+                "timestamp": "2025-01-01T00:00:04Z",
+                "hookInfos": [{"command": "notify.sh"}, {"command": "test.sh"}]
+            })),
+        ];
+
+        let (commands, groups) = collect_hook_timings(&entries);
+
+        assert_eq!(groups, vec![("Stop".to_string(), 4000)]);
+        assert_eq!(commands.len(), 2);
+        assert!(commands.iter().all(|c| c.latency_ms == 4000));
+    }
+
+    #[test]
+    fn test_idle_gaps_are_ignored() {
+        let entries = vec![
+            hook_progress("2025-01-01T00:00:00Z", "PostToolUse", "fmt.sh", "t1"),
+            plain("2025-01-01T05:00:00Z", "user"),
+        ];
+
+        let (commands, groups) = collect_hook_timings(&entries);
+        assert!(commands.is_empty());
+        assert!(groups.is_empty());
+    }
+
+    #[test]
+    fn test_build_hook_latency_stats() {
+        let sessions = vec![
+            SessionHookTimings {
+                session_id: "a".to_string(),
+                project_name: "p".to_string(),
+                commands: vec![
+                    HookTiming {
+                        event: "PostToolUse".to_string(),
+                        command: "fmt.sh".to_string(),
+                        latency_ms: 3000,
+                    },
+                    HookTiming {
+                        event: "PostToolUse".to_string(),
+                        command: "fmt.sh".to_string(),
+                        latency_ms: 1000,
+                    },
+                ],
+                groups: vec![
+                    ("PostToolUse".to_string(), 3000),
+                    ("PostToolUse".to_string(), 1000),
+                ],
+            },
+            SessionHookTimings::default(),
+        ];
+
+        let stats = build_hook_latency_stats(sessions);
+
+        assert_eq!(stats.session_count, 2);
+        assert_eq!(stats.sessions_with_hooks, 1);
+        assert_eq!(stats.total_hook_ms, 4000);
+        assert_eq!(stats.by_event[0].invocations, 2);
+        assert!((stats.slowest_commands[0].avg_ms - 2000.0).abs() < f64::EPSILON);
+        assert_eq!(stats.slowest_commands[0].max_ms, 3000);
+    }
+
+    #[test]
+    fn test_hook_stats_per_project_and_command() {
+        let stop = |prevented: bool, errors: serde_json::Value| {
+            raw_entry(json!({
+                "type": "system",
+                "subtype": "stop_hook_summary",
+                "timestamp": "2025-01-01T00:00:04Z",
+                "hookCount": 2,
+                "hookInfos": [{"command": "bash check.sh"}, {"command": "notify.sh"}],
+                "preventedContinuation": prevented,
+                "hookErrors": errors
+            }))
+        };
+        let entries = vec![
+            hook_progress("2025-01-01T00:00:00Z", "PostToolUse", "fmt.sh", "t1"),
+            stop(true, json!(["bash check.sh failed: exit 1"])),
+            stop(false, json!([])),
+            stop(false, json!(["Hook timed out"])),
+        ];
+
+        let session = SessionHookRuns {
+            project_name: "web".to_string(),
+            ..collect_hook_runs(&entries)
+        };
+        assert_eq!(session.stop_summaries, 3);
+        assert_eq!(session.prevented_continuations, 1);
+        assert_eq!(session.failed_summaries, 2);
+
+        let stats = build_hook_stats(vec![session, SessionHookRuns::default()]);
+        assert_eq!(stats.session_count, 2);
+        assert_eq!(stats.sessions_with_hooks, 1);
+        assert_eq!(stats.total_runs, 7);
+
+        let project = &stats.projects[0];
+        assert_eq!(project.project_name, "web");
+        let check = project
+            .hooks
+            .iter()
+            .find(|h| h.command == "bash check.sh")
+            .unwrap();
+        assert_eq!(check.runs, 3);
+        assert_eq!(check.sessions, 1);
+        assert_eq!(check.prevented_continuations, 1);
+        assert_eq!(check.failures, 2);
+        let notify = project
+            .hooks
+            .iter()
+            .find(|h| h.command == "notify.sh")
+            .unwrap();
+        assert_eq!(notify.failures, 1); // Only the unattributed timeout
+        assert!((notify.prevent_rate - 1.0 / 3.0).abs() < 1e-9);
+        let fmt = project
+            .hooks
+            .iter()
+            .find(|h| h.command == "fmt.sh")
+            .unwrap();
+        assert_eq!(
+            (fmt.event.as_str(), fmt.runs, fmt.failures),
+            ("PostToolUse", 1, 0)
+        );
+    }
+}
@@ -0,0 +1,617 @@
+//! Operation journal
+//!
+//! Every mutating command appends an entry to
+//! `~/.claude-history-viewer/journal.jsonl`, the audit trail shown by
+//! `get_operation_journal`. Entries of metadata changes and file restores
+//! carry the state before and after the operation, so `undo_operation` can
+//! put it back; undoing an undo entry redoes the operation. Archive and
+//! compaction runs and deletions are recorded but cannot be undone (deleted
+//! sessions are restored from the OS trash). Credentials in settings are
+//! never written to the journal.
+
+use crate::commands::budget::set_budgets;
+use crate::commands::metadata::{
+    apply_settings, get_metadata_folder, save_metadata_to_disk, MetadataState,
+};
+use crate::commands::session::{set_archived_sessions, write_file_atomic};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{JournalEntry, SessionMetadata, UndoAction, UserMetadata};
+use crate::utils::long_path;
+use chrono::{SecondsFormat, Utc};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::State;
+
+/// Serializes appends and trims of the journal file
+static JOURNAL_LOCK: Mutex<()> = Mutex::new(());
+
+/// Journal size that triggers dropping the oldest entries
+const MAX_JOURNAL_BYTES: usize = 32 * 1024 * 1024;
+
+/// Largest file content kept to undo a file restore
+const MAX_UNDO_FILE_BYTES: usize = 1024 * 1024;
+
+const DEFAULT_JOURNAL_LIMIT: usize = 200;
+
+/// Journal file (a per-process temporary file in unit tests, which must not
+/// write to the user's journal)
+fn journal_path() -> Result<PathBuf, String> {
+    if cfg!(test) {
+        return Ok(std::env::temp_dir()
+            .join(format!("claude-history-viewer-test-{}", std::process::id()))
+            .join("journal.jsonl"));
+    }
+    Ok(get_metadata_folder()?.join("journal.jsonl"))
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Keep the newest entries that fit in half the size limit
+fn trim_journal(path: &Path) -> Result<(), String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read operation journal: {e}"))?;
+    let mut kept = Vec::new();
+    let mut bytes = 0;
+    for line in content.lines().rev() {
+        bytes += line.len() + 1;
+        if bytes > MAX_JOURNAL_BYTES / 2 {
+            break;
+        }
+        kept.push(line);
+    }
+    kept.reverse();
+
+    let temp_path = path.with_extension("jsonl.tmp");
+    fs::write(&temp_path, kept.join("\n") + "\n")
+        .map_err(|e| format!("Failed to write temp file: {e}"))?;
+    fs::rename(&temp_path, path).map_err(|e| format!("Failed to rename temp file: {e}"))
+}
+
+fn append_entry(path: &Path, entry: &JournalEntry) -> Result<(), String> {
+    let line =
+        serde_json::to_string(entry).map_err(|e| format!("Failed to serialize entry: {e}"))?;
+    let _guard = JOURNAL_LOCK
+        .lock()
+        .map_err(|e| format!("Failed to lock operation journal: {e}"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create metadata folder: {e}"))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open operation journal: {e}"))?;
+    writeln!(file, "{line}").map_err(|e| format!("Failed to write operation journal: {e}"))?;
+
+    let size = file.metadata().map_or(0, |metadata| metadata.len());
+    if size > MAX_JOURNAL_BYTES as u64 {
+        trim_journal(path)?;
+    }
+    Ok(())
+}
+
+/// Journal entries, oldest first, with `undone_by` filled in
+fn read_journal(path: &Path) -> Result<Vec<JournalEntry>, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read operation journal: {e}")),
+    };
+    let mut entries: Vec<JournalEntry> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+
+    let undone_by: HashMap<String, String> = entries
+        .iter()
+        .filter_map(|entry| Some((entry.undoes.clone()?, entry.op_id.clone())))
+        .collect();
+    for entry in &mut entries {
+        entry.undone_by = undone_by.get(&entry.op_id).cloned();
+    }
+    Ok(entries)
+}
+
+fn is_noop(undo: &UndoAction) -> bool {
+    match undo {
+        UndoAction::SessionMetadata { before, after, .. } => before == after,
+        UndoAction::ProjectMetadata { before, after, .. } => before == after,
+        UndoAction::Settings { before, after } => before == after,
+        UndoAction::Metadata { before, after } => before == after,
+        UndoAction::FileContent { before, after, .. } => before == after,
+    }
+}
+
+fn journal_entry(operation: &str, summary: String, undo: Option<UndoAction>) -> JournalEntry {
+    JournalEntry {
+        op_id: uuid::Uuid::new_v4().to_string(),
+        timestamp: now(),
+        operation: operation.to_string(),
+        summary,
+        undoes: None,
+        undo,
+        undone_by: None,
+    }
+}
+
+/// Append an operation to the journal; failures are logged, not returned,
+/// since the operation itself already succeeded
+pub(crate) fn record_operation(operation: &str, summary: String, undo: Option<UndoAction>) {
+    match journal_path() {
+        Ok(path) => record_operation_at(&path, operation, summary, undo),
+        Err(e) => eprintln!("Failed to record {operation} in the operation journal: {e}"),
+    }
+}
+
+/// `record_operation` into the journal at `path`, without the credentials
+/// of journaled settings
+fn record_operation_at(path: &Path, operation: &str, summary: String, undo: Option<UndoAction>) {
+    let undo = undo.map(UndoAction::without_secrets);
+    if undo.as_ref().is_some_and(is_noop) {
+        return;
+    }
+    let entry = journal_entry(operation, summary, undo);
+    if let Err(e) = append_entry(path, &entry) {
+        eprintln!("Failed to record {operation} in the operation journal: {e}");
+    }
+}
+
+/// Undo state of a file about to be overwritten with `after` (None when
+/// either side is too large or not UTF-8)
+pub(crate) fn file_content_undo(path: &Path, after: &str) -> Option<UndoAction> {
+    if after.len() > MAX_UNDO_FILE_BYTES {
+        return None;
+    }
+    let before = match fs::read(long_path(path)) {
+        Ok(bytes) if bytes.len() <= MAX_UNDO_FILE_BYTES => Some(String::from_utf8(bytes).ok()?),
+        Err(e) if e.kind() == ErrorKind::NotFound => None,
+        _ => return None,
+    };
+    Some(UndoAction::FileContent {
+        path: path.to_string_lossy().to_string(),
+        before,
+        after: Some(after.to_string()),
+    })
+}
+
+/// "Session s1: tags +bug -wip, starred" style summary of a session
+/// metadata change
+pub(crate) fn session_change_summary(
+    session_id: &str,
+    before: Option<&SessionMetadata>,
+    after: Option<&SessionMetadata>,
+) -> String {
+    let empty = SessionMetadata::default();
+    let before = before.unwrap_or(&empty);
+    let after = after.unwrap_or(&empty);
+
+    let mut changes = Vec::new();
+    let mut tags: Vec<String> = after
+        .tags
+        .iter()
+        .filter(|tag| !before.tags.contains(tag))
+        .map(|tag| format!("+{tag}"))
+        .collect();
+    tags.extend(
+        before
+            .tags
+            .iter()
+            .filter(|tag| !after.tags.contains(tag))
+            .map(|tag| format!("-{tag}")),
+    );
+    if !tags.is_empty() {
+        changes.push(format!("tags {}", tags.join(" ")));
+    }
+    if before.custom_name != after.custom_name {
+        changes.push("name".to_string());
+    }
+    if before.starred != after.starred {
+        changes.push(if after.starred == Some(true) {
+            "starred".to_string()
+        } else {
+            "unstarred".to_string()
+        });
+    }
+    if before.notes != after.notes {
+        changes.push("notes".to_string());
+    }
+    if before.archived_at.is_some() != after.archived_at.is_some() {
+        changes.push(if after.archived_at.is_some() {
+            "archived".to_string()
+        } else {
+            "unarchived".to_string()
+        });
+    }
+    if changes.is_empty() {
+        format!("Session {session_id}: no changes")
+    } else {
+        format!("Session {session_id}: {}", changes.join(", "))
+    }
+}
+
+/// The entry with `op_id` and its undo action, if it can still be undone
+fn find_undoable(entries: Vec<JournalEntry>, op_id: &str) -> Result<JournalEntry, AppError> {
+    let entry = entries
+        .into_iter()
+        .find(|entry| entry.op_id == op_id)
+        .ok_or_else(|| AppError::not_found(format!("Operation not found: {op_id}")))?;
+    if entry.undo.is_none() {
+        return Err(AppError::invalid_input(format!(
+            "{} cannot be undone",
+            entry.operation
+        )));
+    }
+    if let Some(undone_by) = &entry.undone_by {
+        return Err(AppError::invalid_input(format!(
+            "Operation already undone by {undone_by}"
+        )));
+    }
+    Ok(entry)
+}
+
+fn changed_since() -> AppError {
+    AppError::invalid_input("Changed since the operation; undo the later operations first")
+}
+
+/// Put back the metadata `undo` changed, unless it changed again since
+///
+/// Journaled settings carry no credentials: they are compared without the
+/// current ones, which are kept.
+fn revert_metadata(metadata: &mut UserMetadata, undo: &UndoAction) -> Result<(), AppError> {
+    match undo {
+        UndoAction::SessionMetadata {
+            session_id,
+            before,
+            after,
+        } => {
+            if metadata.sessions.get(session_id) != after.as_ref() {
+                return Err(changed_since());
+            }
+            match before {
+                Some(before) => metadata.sessions.insert(session_id.clone(), before.clone()),
+                None => metadata.sessions.remove(session_id),
+            };
+        }
+        UndoAction::ProjectMetadata {
+            project_path,
+            before,
+            after,
+        } => {
+            if metadata.projects.get(project_path) != after.as_ref() {
+                return Err(changed_since());
+            }
+            match before {
+                Some(before) => metadata
+                    .projects
+                    .insert(project_path.clone(), before.clone()),
+                None => metadata.projects.remove(project_path),
+            };
+        }
+        UndoAction::Settings { before, after } => {
+            if metadata.settings.without_secrets() != **after {
+                return Err(changed_since());
+            }
+            let mut settings = (**before).clone();
+            settings.keep_secrets_of(&metadata.settings);
+            metadata.settings = settings;
+        }
+        UndoAction::Metadata { before, after } => {
+            if metadata.without_secrets() != **after {
+                return Err(changed_since());
+            }
+            let mut restored = (**before).clone();
+            restored.settings.keep_secrets_of(&metadata.settings);
+            *metadata = restored;
+        }
+        UndoAction::FileContent { .. } => {
+            return Err(AppError::invalid_input("Not a metadata change"));
+        }
+    }
+    Ok(())
+}
+
+/// Put back the file content `undo` changed, unless it changed again since
+fn revert_file(undo: &UndoAction) -> Result<(), AppError> {
+    let UndoAction::FileContent {
+        path,
+        before,
+        after,
+    } = undo
+    else {
+        return Err(AppError::invalid_input("Not a file change"));
+    };
+    let path = long_path(Path::new(path));
+    let current = match fs::read(&path) {
+        Ok(bytes) => String::from_utf8(bytes).ok(),
+        Err(e) if e.kind() == ErrorKind::NotFound => None,
+        Err(e) => return Err(AppError::io("Failed to read file", &e)),
+    };
+    if current != *after {
+        return Err(changed_since());
+    }
+    match before {
+        Some(content) => write_file_atomic(&path, content)?,
+        None => fs::remove_file(&path).map_err(|e| AppError::io("Failed to remove file", &e))?,
+    }
+    Ok(())
+}
+
+/// The entry recording the undo (or redo) of `entry`
+fn undo_entry(entry: JournalEntry, undo: UndoAction) -> JournalEntry {
+    let (operation, summary) = if entry.operation == "undo" {
+        let summary = entry
+            .summary
+            .strip_prefix("Undo: ")
+            .unwrap_or(&entry.summary);
+        ("redo", format!("Redo: {summary}"))
+    } else {
+        ("undo", format!("Undo: {}", entry.summary))
+    };
+    JournalEntry {
+        undoes: Some(entry.op_id),
+        ..journal_entry(operation, summary, Some(undo.inverse()))
+    }
+}
+
+/// Recorded operations, newest first
+///
+/// `operation` keeps only entries of one command (e.g.
+/// "`update_session_metadata`", or "undo"); `limit` defaults to 200.
+#[tauri::command]
+pub async fn get_operation_journal(
+    operation: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<JournalEntry>, AppError> {
+    let _timer = OperationTimer::start("get_operation_journal");
+
+    let limit = limit.unwrap_or(DEFAULT_JOURNAL_LIMIT);
+    tauri::async_runtime::spawn_blocking(move || {
+        let entries = read_journal(&journal_path()?)?;
+        Ok(entries
+            .into_iter()
+            .rev()
+            .filter(|entry| operation.as_ref().map_or(true, |op| entry.operation == *op))
+            .take(limit)
+            .collect())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Undo a recorded operation and return the journal entry of the undo
+///
+/// Fails when the changed metadata or file changed again since; undo the
+/// later operations first. Undoing an undo entry redoes the operation.
+#[tauri::command]
+pub async fn undo_operation(
+    op_id: String,
+    state: State<'_, MetadataState>,
+) -> Result<JournalEntry, AppError> {
+    let _timer = OperationTimer::start("undo_operation");
+
+    let mut entry = tauri::async_runtime::spawn_blocking(move || -> Result<_, AppError> {
+        find_undoable(read_journal(&journal_path()?)?, &op_id)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+    let undo = entry.undo.take().ok_or("Operation cannot be undone")?;
+
+    // Perform quick in-memory mutation while holding lock, then release
+    let metadata_to_save = if matches!(undo, UndoAction::FileContent { .. }) {
+        None
+    } else {
+        let mut cached = state
+            .metadata
+            .lock()
+            .map_err(|e| format!("Failed to lock metadata: {e}"))?;
+
+        let metadata = cached.get_or_insert_with(UserMetadata::new);
+        revert_metadata(metadata, &undo)?;
+        apply_settings(&metadata.settings);
+        set_budgets(&metadata.projects);
+        set_archived_sessions(&metadata.sessions);
+
+        Some(metadata.clone())
+    }; // Lock released here
+
+    tauri::async_runtime::spawn_blocking(move || {
+        match &metadata_to_save {
+            Some(metadata) => save_metadata_to_disk(metadata)?,
+            None => revert_file(&undo)?,
+        }
+        let undo_entry = undo_entry(entry, undo);
+        append_entry(&journal_path()?, &undo_entry)?;
+        Ok(undo_entry)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ProjectMetadata, SummarizerSettings, UserSettings};
+    use tempfile::TempDir;
+
+    fn tagged(tags: &[&str]) -> SessionMetadata {
+        SessionMetadata {
+            tags: tags.iter().map(|tag| (*tag).to_string()).collect(),
+            ..SessionMetadata::default()
+        }
+    }
+
+    #[test]
+    fn test_journal_marks_undone_entries() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("journal.jsonl");
+        let undo = UndoAction::SessionMetadata {
+            session_id: "s1".to_string(),
+            before: None,
+            after: Some(tagged(&["bug"])),
+        };
+        let first = journal_entry(
+            "update_session_metadata",
+            "Session s1".to_string(),
+            Some(undo),
+        );
+        append_entry(&path, &first).unwrap();
+        append_entry(
+            &path,
+            &journal_entry("run_archive", "Archived".to_string(), None),
+        )
+        .unwrap();
+
+        let entries = read_journal(&path).unwrap();
+        assert_eq!(entries.len(), 2);
+        let entry = find_undoable(entries, &first.op_id).unwrap();
+        let undo = undo_entry(entry, first.undo.clone().unwrap());
+        assert_eq!(undo.operation, "undo");
+        assert_eq!(undo.summary, "Undo: Session s1");
+        append_entry(&path, &undo).unwrap();
+
+        let entries = read_journal(&path).unwrap();
+        assert_eq!(entries[0].undone_by.as_ref(), Some(&undo.op_id));
+        assert!(matches!(
+            find_undoable(entries.clone(), &first.op_id),
+            Err(AppError::InvalidInput { .. })
+        ));
+        let archive_id = entries[1].op_id.clone();
+        assert!(find_undoable(entries.clone(), &archive_id).is_err());
+        // Undoing the undo redoes the change
+        let redo = undo_entry(
+            find_undoable(entries, &undo.op_id).unwrap(),
+            undo.undo.unwrap(),
+        );
+        assert_eq!(redo.operation, "redo");
+        assert_eq!(redo.summary, "Redo: Session s1");
+        assert_eq!(redo.undo, first.undo);
+    }
+
+    fn with_api_key(model: &str, api_key: &str) -> UserSettings {
+        UserSettings {
+            summarizer: Some(SummarizerSettings {
+                backend: "anthropic".to_string(),
+                model: model.to_string(),
+                api_key: Some(api_key.to_string()),
+                ..SummarizerSettings::default()
+            }),
+            ..UserSettings::default()
+        }
+    }
+
+    #[test]
+    fn test_record_operation_appends_entry_without_secrets() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("journal.jsonl");
+        let undo = UndoAction::Settings {
+            before: Box::new(with_api_key("haiku", "sk-old-secret")),
+            after: Box::new(with_api_key("sonnet", "sk-new-secret")),
+        };
+
+        record_operation_at(&path, "run_archive", "Archived".to_string(), None);
+        record_operation_at(
+            &path,
+            "update_user_settings",
+            "Settings".to_string(),
+            Some(undo),
+        );
+        // Changing only a credential leaves nothing to undo
+        let key_only = UndoAction::Settings {
+            before: Box::new(with_api_key("sonnet", "sk-new-secret")),
+            after: Box::new(with_api_key("sonnet", "sk-newer-secret")),
+        };
+        record_operation_at(
+            &path,
+            "update_user_settings",
+            "Key".to_string(),
+            Some(key_only),
+        );
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("secret"), "{content}");
+        let entries = read_journal(&path).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].operation, "run_archive");
+        let Some(UndoAction::Settings { before, .. }) = &entries[1].undo else {
+            panic!("missing settings undo");
+        };
+        assert_eq!(**before, with_api_key("haiku", "x").without_secrets());
+    }
+
+    #[test]
+    fn test_revert_settings_keeps_current_secrets() {
+        let mut metadata = UserMetadata::new();
+        metadata.settings = with_api_key("sonnet", "sk-current");
+        let undo = UndoAction::Settings {
+            before: Box::new(with_api_key("haiku", "sk-old")),
+            after: Box::new(with_api_key("sonnet", "sk-current")),
+        }
+        .without_secrets();
+
+        revert_metadata(&mut metadata, &undo).unwrap();
+        assert_eq!(metadata.settings, with_api_key("haiku", "sk-current"));
+    }
+
+    #[test]
+    fn test_revert_metadata_refuses_later_changes() {
+        let mut metadata = UserMetadata::new();
+        metadata.sessions.insert("s1".to_string(), tagged(&["bug"]));
+        metadata
+            .projects
+            .insert("/p".to_string(), ProjectMetadata::default());
+        let undo = UndoAction::SessionMetadata {
+            session_id: "s1".to_string(),
+            before: Some(tagged(&["wip"])),
+            after: Some(tagged(&["bug"])),
+        };
+
+        revert_metadata(&mut metadata, &undo).unwrap();
+        assert_eq!(metadata.sessions["s1"], tagged(&["wip"]));
+        // Already reverted: the state no longer matches `after`
+        assert!(revert_metadata(&mut metadata, &undo).is_err());
+        revert_metadata(&mut metadata, &undo.inverse()).unwrap();
+        assert_eq!(metadata.sessions["s1"], tagged(&["bug"]));
+    }
+
+    #[test]
+    fn test_revert_file() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("main.rs");
+        fs::write(&path, "old").unwrap();
+        let undo = file_content_undo(&path, "new").unwrap();
+        fs::write(&path, "new").unwrap();
+
+        revert_file(&undo).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+        assert!(revert_file(&undo).is_err());
+
+        let created = temp.path().join("created.rs");
+        let undo = file_content_undo(&created, "new").unwrap();
+        fs::write(&created, "new").unwrap();
+        revert_file(&undo).unwrap();
+        assert!(!created.exists());
+    }
+
+    #[test]
+    fn test_session_change_summary() {
+        let before = SessionMetadata {
+            starred: Some(true),
+            ..tagged(&["wip", "auth"])
+        };
+        let after = tagged(&["auth", "bug"]);
+        assert_eq!(
+            session_change_summary("s1", Some(&before), Some(&after)),
+            "Session s1: tags +bug -wip, unstarred"
+        );
+        assert_eq!(
+            session_change_summary("s1", None, Some(&tagged(&["bug"]))),
+            "Session s1: tags +bug"
+        );
+    }
+}
@@ -0,0 +1,303 @@
+//! Session file linter
+//!
+//! Checks the structural invariants of a Claude Code JSONL session file, for
+//! diagnosing corrupted files and attaching to Claude Code bug reports.
+
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{LintReport, LintViolation};
+use crate::utils::long_path;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Maximum number of violations returned (counts are always complete)
+const MAX_VIOLATIONS: usize = 1000;
+
+/// How far a timestamp may go back before it is reported; Claude Code writes
+/// some entries (e.g. hook output) slightly out of order
+const TIMESTAMP_TOLERANCE_SECS: i64 = 60;
+
+/// Entry types written by Claude Code
+const KNOWN_TYPES: [&str; 7] = [
+    "user",
+    "assistant",
+    "system",
+    "summary",
+    "file-history-snapshot",
+    "progress",
+    "queue-operation",
+];
+
+/// Fields an entry of `message_type` must carry
+fn required_fields(message_type: &str) -> &'static [&'static str] {
+    match message_type {
+        "user" | "assistant" => &["uuid", "sessionId", "timestamp", "message"],
+        "system" => &["uuid", "sessionId", "timestamp"],
+        "summary" => &["summary", "leafUuid"],
+        "file-history-snapshot" => &["messageId", "snapshot"],
+        _ => &[],
+    }
+}
+
+#[derive(Default)]
+struct Linter {
+    violations: Vec<LintViolation>,
+    error_count: usize,
+    warning_count: usize,
+}
+
+impl Linter {
+    fn report(
+        &mut self,
+        line_number: usize,
+        severity: &str,
+        rule: &str,
+        message: String,
+        uuid: Option<&str>,
+    ) {
+        if severity == "error" {
+            self.error_count += 1;
+        } else {
+            self.warning_count += 1;
+        }
+        if self.violations.len() < MAX_VIOLATIONS {
+            self.violations.push(LintViolation {
+                line_number,
+                severity: severity.to_string(),
+                rule: rule.to_string(),
+                message,
+                uuid: uuid.map(String::from),
+            });
+        }
+    }
+}
+
+fn str_field<'a>(entry: &'a serde_json::Value, field: &str) -> Option<&'a str> {
+    entry.get(field).and_then(serde_json::Value::as_str)
+}
+
+/// Lint the content of a session file
+pub fn lint_session_content(path: &str, data: &[u8]) -> LintReport {
+    let mut linter = Linter::default();
+    let mut line_count = 0;
+    let mut first_line_of_uuid: HashMap<String, usize> = HashMap::new();
+    let mut parent_refs: Vec<(usize, String, String)> = Vec::new(); // (line, uuid, parent)
+    let mut last_timestamp: Option<DateTime<Utc>> = None;
+
+    for (idx, line) in data.split(|&b| b == b'\n').enumerate() {
+        let line_number = idx + 1;
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        line_count += 1;
+
+        let entry: serde_json::Value = match serde_json::from_slice(line) {
+            Ok(entry) => entry,
+            Err(e) => {
+                linter.report(line_number, "error", "invalid_json", e.to_string(), None);
+                continue;
+            }
+        };
+        if !entry.is_object() {
+            linter.report(
+                line_number,
+                "error",
+                "invalid_json",
+                "Entry is not a JSON object".to_string(),
+                None,
+            );
+            continue;
+        }
+
+        let uuid = str_field(&entry, "uuid");
+        let Some(message_type) = str_field(&entry, "type") else {
+            linter.report(
+                line_number,
+                "error",
+                "missing_field",
+                "Missing required field `type`".to_string(),
+                uuid,
+            );
+            continue;
+        };
+
+        if !KNOWN_TYPES.contains(&message_type) {
+            linter.report(
+                line_number,
+                "warning",
+                "unknown_type",
+                format!("Unknown entry type `{message_type}`"),
+                uuid,
+            );
+        }
+        for field in required_fields(message_type) {
+            if matches!(entry.get(*field), None | Some(serde_json::Value::Null)) {
+                linter.report(
+                    line_number,
+                    "error",
+                    "missing_field",
+                    format!("`{message_type}` entry is missing required field `{field}`"),
+                    uuid,
+                );
+            }
+        }
+
+        if let Some(uuid) = uuid {
+            match first_line_of_uuid.get(uuid) {
+                Some(first_line) => linter.report(
+                    line_number,
+                    "error",
+                    "duplicate_uuid",
+                    format!("uuid already used on line {first_line}"),
+                    Some(uuid),
+                ),
+                None => {
+                    first_line_of_uuid.insert(uuid.to_string(), line_number);
+                }
+            }
+            if let Some(parent) = str_field(&entry, "parentUuid") {
+                parent_refs.push((line_number, uuid.to_string(), parent.to_string()));
+            }
+        }
+
+        if let Some(timestamp) = str_field(&entry, "timestamp") {
+            match DateTime::parse_from_rfc3339(timestamp) {
+                Ok(parsed) => {
+                    let parsed = parsed.with_timezone(&Utc);
+                    if let Some(last) = last_timestamp {
+                        if (last - parsed).num_seconds() > TIMESTAMP_TOLERANCE_SECS {
+                            linter.report(
+                                line_number,
+                                "warning",
+                                "timestamp_regression",
+                                format!(
+                                    "Timestamp {timestamp} is earlier than a previous entry ({})",
+                                    last.to_rfc3339()
+                                ),
+                                uuid,
+                            );
+                        }
+                    }
+                    last_timestamp = Some(last_timestamp.map_or(parsed, |last| last.max(parsed)));
+                }
+                Err(e) => linter.report(
+                    line_number,
+                    "error",
+                    "invalid_timestamp",
+                    format!("Invalid timestamp `{timestamp}`: {e}"),
+                    uuid,
+                ),
+            }
+        }
+    }
+
+    // Parents may legitimately live in another file after a resume, so this is a warning
+    for (line_number, uuid, parent) in &parent_refs {
+        if !first_line_of_uuid.contains_key(parent) {
+            linter.report(
+                *line_number,
+                "warning",
+                "unresolved_parent",
+                format!("parentUuid {parent} does not match any entry in this file"),
+                Some(uuid),
+            );
+        }
+    }
+    linter.violations.sort_by_key(|v| v.line_number);
+
+    LintReport {
+        path: path.to_string(),
+        line_count,
+        error_count: linter.error_count,
+        warning_count: linter.warning_count,
+        truncated: linter.error_count + linter.warning_count > linter.violations.len(),
+        violations: linter.violations,
+    }
+}
+
+/// Check a session JSONL file for structural problems
+#[tauri::command]
+pub async fn lint_session_file(path: String) -> Result<LintReport, AppError> {
+    let _timer = OperationTimer::start("lint_session_file");
+
+    let file_path = Path::new(&path);
+    if !file_path.is_absolute() {
+        return Err(AppError::invalid_input(
+            "Invalid session path: must be an absolute path",
+        ));
+    }
+    if file_path.extension().and_then(|ext| ext.to_str()) != Some("jsonl") {
+        return Err(AppError::invalid_input(
+            "Invalid session path: must be a .jsonl file",
+        ));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let data = fs::read(long_path(Path::new(&path)))
+            .map_err(|e| format!("Failed to read session file: {e}"))?;
+        Ok(lint_session_content(&path, &data))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(report: &LintReport) -> Vec<(usize, &str)> {
+        report
+            .violations
+            .iter()
+            .map(|v| (v.line_number, v.rule.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_file_has_no_violations() {
+        let log = concat!(
+            r#"{"type":"user","uuid":"a","parentUuid":null,"sessionId":"s","timestamp":"2025-01-01T00:00:00Z","message":{}}"#,
+            "\n",
+            r#"{"type":"assistant","uuid":"b","parentUuid":"a","sessionId":"s","timestamp":"2025-01-01T00:00:01Z","message":{}}"#,
+            "\n",
+        );
+
+        let report = lint_session_content("s.jsonl", log.as_bytes());
+
+        assert_eq!(report.line_count, 2);
+        assert!(report.violations.is_empty());
+    }
+
+    #[test]
+    fn test_structural_violations_are_reported() {
+        let log = concat!(
+            r#"{"type":"user","uuid":"a","parentUuid":"gone","sessionId":"s","timestamp":"2025-01-01T00:10:00Z","message":{}}"#,
+            "\n",
+            "{broken\n",
+            "\n",
+            r#"{"type":"assistant","uuid":"a","sessionId":"s","timestamp":"2025-01-01T00:00:00Z"}"#,
+            "\n",
+            r#"{"type":"mystery","timestamp":"yesterday"}"#,
+        );
+
+        let report = lint_session_content("s.jsonl", log.as_bytes());
+
+        assert_eq!(
+            rules(&report),
+            vec![
+                (1, "unresolved_parent"),
+                (2, "invalid_json"),
+                (4, "missing_field"),
+                (4, "duplicate_uuid"),
+                (4, "timestamp_regression"),
+                (5, "unknown_type"),
+                (5, "invalid_timestamp"),
+            ]
+        );
+        assert_eq!(report.error_count, 4);
+        assert_eq!(report.warning_count, 3);
+        assert!(!report.truncated);
+    }
+}
@@ -0,0 +1,222 @@
+//! Read-only preview of local files referenced in sessions
+//!
+//! Lets the frontend show the current content of a file next to the version
+//! recorded in a session. Disabled unless the `localFilePreview` setting is on.
+
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::LocalFileContent;
+use crate::utils::{display_path, long_path};
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Files larger than this are not previewed
+const MAX_PREVIEW_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Leading bytes checked for NUL to detect binary files
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+/// Whether the user enabled local file previews
+static LOCAL_FILE_PREVIEW: AtomicBool = AtomicBool::new(false);
+
+/// Apply the user's `localFilePreview` setting (off when unset)
+pub fn set_local_file_preview(enabled: Option<bool>) {
+    LOCAL_FILE_PREVIEW.store(enabled.unwrap_or(false), Ordering::SeqCst);
+}
+
+/// Syntax highlighting language of a file, matching the frontend's names
+fn language_for_path(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "rs" => "rust",
+        "ts" => "typescript",
+        "tsx" => "tsx",
+        "js" => "javascript",
+        "jsx" => "jsx",
+        "py" => "python",
+        "json" => "json",
+        "md" | "markdown" => "markdown",
+        "css" => "css",
+        "scss" | "sass" => "scss",
+        "html" | "htm" => "html",
+        "yaml" | "yml" => "yaml",
+        "sh" | "zsh" | "bash" => "bash",
+        "go" => "go",
+        "java" => "java",
+        "swift" => "swift",
+        "kt" | "kotlin" => "kotlin",
+        "rb" => "ruby",
+        "toml" => "toml",
+        _ => {
+            let file_name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            if file_name.contains("dockerfile") {
+                "dockerfile"
+            } else if file_name.contains("makefile") {
+                "makefile"
+            } else {
+                "text"
+            }
+        }
+    }
+}
+
+/// Read `path`, keeping lines `start_line..=end_line` (1-based) when given
+fn read_file_preview(
+    path: &Path,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+) -> Result<LocalFileContent, String> {
+    if start_line == Some(0) || matches!((start_line, end_line), (Some(s), Some(e)) if s > e) {
+        return Err("Invalid line range".to_string());
+    }
+
+    let mut preview = LocalFileContent {
+        path: display_path(path),
+        exists: false,
+        is_binary: false,
+        language: language_for_path(path).to_string(),
+        size_bytes: 0,
+        modified: None,
+        total_lines: 0,
+        start_line: 0,
+        end_line: 0,
+        content: String::new(),
+    };
+
+    let metadata = match fs::metadata(long_path(path)) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(preview),
+        Err(e) => return Err(format!("Failed to read file metadata: {e}")),
+    };
+    if !metadata.is_file() {
+        return Err(format!("Not a file: {}", preview.path));
+    }
+    if metadata.len() > MAX_PREVIEW_FILE_BYTES {
+        return Err(format!(
+            "File too large to preview: {} bytes",
+            metadata.len()
+        ));
+    }
+    preview.exists = true;
+    preview.size_bytes = metadata.len();
+    preview.modified = metadata
+        .modified()
+        .ok()
+        .map(|time| DateTime::<Utc>::from(time).to_rfc3339());
+
+    let bytes = fs::read(long_path(path)).map_err(|e| format!("Failed to read file: {e}"))?;
+    if bytes.iter().take(BINARY_SNIFF_BYTES).any(|&b| b == 0) {
+        preview.is_binary = true;
+        return Ok(preview);
+    }
+
+    let text = String::from_utf8_lossy(&bytes);
+    let lines: Vec<&str> = text.lines().collect();
+    preview.total_lines = lines.len();
+    let start = start_line.unwrap_or(1);
+    let end = end_line.unwrap_or(lines.len()).min(lines.len());
+    if start <= end {
+        preview.start_line = start;
+        preview.end_line = end;
+        preview.content = lines[start - 1..end].join("\n");
+    }
+    Ok(preview)
+}
+
+/// Read the current content of a local file, optionally a 1-based inclusive
+/// line range
+///
+/// Requires the `localFilePreview` setting. Missing files are reported with
+/// `exists: false` rather than an error; binary files return no content.
+#[tauri::command]
+pub async fn read_local_file(
+    path: String,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+) -> Result<LocalFileContent, AppError> {
+    let _timer = OperationTimer::start("read_local_file");
+
+    if !LOCAL_FILE_PREVIEW.load(Ordering::SeqCst) {
+        return Err("Local file preview is disabled in settings".into());
+    }
+    if path.contains('\0') {
+        return Err(AppError::invalid_input("Invalid path: contains null bytes"));
+    }
+    let path = PathBuf::from(path);
+    if !path.is_absolute() {
+        return Err(AppError::invalid_input("File path must be absolute"));
+    }
+
+    Ok(
+        tauri::async_runtime::spawn_blocking(move || {
+            read_file_preview(&path, start_line, end_line)
+        })
+        .await
+        .map_err(|e| format!("Task join error: {e}"))??,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_file_preview_ranges() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("main.rs");
+        fs::write(&path, "fn main() {\n    run();\n}\n").unwrap();
+
+        let whole = read_file_preview(&path, None, None).unwrap();
+        assert!(whole.exists);
+        assert_eq!(whole.language, "rust");
+        assert_eq!(whole.total_lines, 3);
+        assert_eq!((whole.start_line, whole.end_line), (1, 3));
+        assert!(whole.modified.is_some());
+
+        let range = read_file_preview(&path, Some(2), Some(10)).unwrap();
+        assert_eq!(range.content, "    run();\n}");
+        assert_eq!(range.end_line, 3);
+
+        let past_end = read_file_preview(&path, Some(5), None).unwrap();
+        assert!(past_end.content.is_empty());
+        assert!(read_file_preview(&path, Some(3), Some(2)).is_err());
+        assert!(read_file_preview(&path, Some(0), None).is_err());
+
+        let missing = read_file_preview(&temp_dir.path().join("gone.rs"), None, None).unwrap();
+        assert!(!missing.exists);
+
+        let binary = temp_dir.path().join("image.png");
+        fs::write(&binary, [0x89, b'P', b'N', b'G', 0, 1]).unwrap();
+        let binary = read_file_preview(&binary, None, None).unwrap();
+        assert!(binary.is_binary);
+        assert!(binary.content.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_read_local_file_requires_setting() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("Dockerfile");
+        fs::write(&path, "FROM rust\n").unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        set_local_file_preview(None);
+        assert!(read_local_file(path.clone(), None, None).await.is_err());
+
+        set_local_file_preview(Some(true));
+        let preview = read_local_file(path, None, None).await.unwrap();
+        assert_eq!(preview.language, "dockerfile");
+        assert!(read_local_file("relative.rs".to_string(), None, None)
+            .await
+            .is_err());
+        set_local_file_preview(None);
+    }
+}
@@ -0,0 +1,250 @@
+//! Distribution of log entries by message type
+//!
+//! Session files hold much more than the conversation: turn durations,
+//! hook and tool progress, file history snapshots... Entries are counted
+//! per type and subtype along with the bytes of their lines, so the share
+//! of the log that is actual conversation can be measured.
+
+use crate::commands::session::subagent_session_file;
+use crate::commands::stats::{parse_raw_log_entry_simd, resolve_scope_session_files};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::io_limit::acquire_file_permit;
+use crate::models::{MessageTypeCount, MessageTypeGroup, MessageTypeStats, RawLogEntry};
+use crate::utils::{
+    display_path, extract_project_name, file_name_string, find_line_ranges, long_path,
+};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Type of lines that are not valid log entries
+const UNPARSED_TYPE: &str = "unparsed";
+
+/// Entry count and bytes per message type
+type TypeCounts = HashMap<String, (usize, u64)>;
+
+/// `type` of an entry, refined with its subtype: `system` entries by
+/// `subtype`, `progress` entries by the type of their data
+fn type_key(entry: &RawLogEntry) -> String {
+    let subtype = match entry.message_type.as_str() {
+        "system" => entry.subtype.as_deref(),
+        "progress" => entry
+            .data
+            .as_ref()
+            .and_then(|data| data.get("type"))
+            .and_then(|t| t.as_str()),
+        _ => None,
+    };
+    match subtype.filter(|s| !s.is_empty()) {
+        Some(subtype) => format!("{}:{subtype}", entry.message_type),
+        None => entry.message_type.clone(),
+    }
+}
+
+fn file_type_counts(path: &Path) -> TypeCounts {
+    let data = {
+        let _permit = acquire_file_permit();
+        fs::read(long_path(path)).unwrap_or_default()
+    };
+
+    let mut counts = TypeCounts::new();
+    let mut line = Vec::new();
+    for (start, end) in find_line_ranges(&data) {
+        line.clear();
+        line.extend_from_slice(&data[start..end]);
+        let key = parse_raw_log_entry_simd(&mut line)
+            .map_or_else(|| UNPARSED_TYPE.to_string(), |entry| type_key(&entry));
+        let count = counts.entry(key).or_default();
+        count.0 += 1;
+        count.1 += (end - start) as u64;
+    }
+    counts
+}
+
+fn merge_counts(into: &mut TypeCounts, counts: &TypeCounts) {
+    for (key, &(entries, bytes)) in counts {
+        let count = into.entry(key.clone()).or_default();
+        count.0 += entries;
+        count.1 += bytes;
+    }
+}
+
+fn build_group(name: String, path: String, counts: TypeCounts) -> MessageTypeGroup {
+    let entry_count = counts.values().map(|&(entries, _)| entries).sum();
+    let bytes: u64 = counts.values().map(|&(_, bytes)| bytes).sum();
+    let share = |part: u64| {
+        if bytes > 0 {
+            part as f64 / bytes as f64
+        } else {
+            0.0
+        }
+    };
+    let conversation_bytes = ["user", "assistant"]
+        .iter()
+        .filter_map(|key| counts.get(*key))
+        .map(|&(_, bytes)| bytes)
+        .sum();
+
+    let mut types: Vec<MessageTypeCount> = counts
+        .into_iter()
+        .map(
+            |(message_type, (entry_count, type_bytes))| MessageTypeCount {
+                message_type,
+                entry_count,
+                bytes: type_bytes,
+                byte_share: share(type_bytes),
+            },
+        )
+        .collect();
+    types.sort_by(|a, b| {
+        b.bytes
+            .cmp(&a.bytes)
+            .then_with(|| a.message_type.cmp(&b.message_type))
+    });
+
+    MessageTypeGroup {
+        name,
+        path,
+        entry_count,
+        bytes,
+        conversation_share: share(conversation_bytes),
+        types,
+    }
+}
+
+fn sorted_groups(
+    groups: HashMap<PathBuf, TypeCounts>,
+    name: impl Fn(&Path) -> String,
+) -> Vec<MessageTypeGroup> {
+    let mut groups: Vec<MessageTypeGroup> = groups
+        .into_iter()
+        .map(|(path, counts)| build_group(name(&path), display_path(&path), counts))
+        .collect();
+    groups.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    groups
+}
+
+fn message_type_stats(scope: String, session_files: &[PathBuf]) -> MessageTypeStats {
+    let file_counts: Vec<(PathBuf, TypeCounts)> = session_files
+        .par_iter()
+        .map(|path| {
+            let session = subagent_session_file(path).unwrap_or_else(|| path.clone());
+            (session, file_type_counts(path))
+        })
+        .collect();
+
+    let mut totals = TypeCounts::new();
+    let mut sessions: HashMap<PathBuf, TypeCounts> = HashMap::new();
+    let mut projects: HashMap<PathBuf, TypeCounts> = HashMap::new();
+    for (session, counts) in &file_counts {
+        merge_counts(&mut totals, counts);
+        if let Some(project) = session.parent() {
+            merge_counts(projects.entry(project.to_path_buf()).or_default(), counts);
+        }
+        merge_counts(sessions.entry(session.clone()).or_default(), counts);
+    }
+
+    MessageTypeStats {
+        totals: build_group(scope.clone(), String::new(), totals),
+        scope,
+        projects: sorted_groups(projects, |path| {
+            extract_project_name(&file_name_string(path).unwrap_or_default())
+        }),
+        sessions: sorted_groups(sessions, |path| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        }),
+    }
+}
+
+/// Breakdown of log entries by message type and subtype (user, assistant,
+/// `system:turn_duration`, `progress:…`, `file-history-snapshot`...) per
+/// session and project, with entry counts and bytes
+///
+/// `scope` is "session", "project" or "global" with `path` pointing at the
+/// session file, project folder or Claude folder.
+#[tauri::command]
+pub async fn get_message_type_stats(
+    scope: String,
+    path: String,
+) -> Result<MessageTypeStats, AppError> {
+    let _timer = OperationTimer::start("get_message_type_stats");
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let session_files = resolve_scope_session_files(&scope, &path)?;
+        Ok(message_type_stats(scope, &session_files))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_message_type_stats_by_type_and_subtype() {
+        let temp = TempDir::new().unwrap();
+        let project_dir = temp.path().join("-Users-me-demo");
+        let subagents_dir = project_dir.join("s1").join("subagents");
+        fs::create_dir_all(&subagents_dir).unwrap();
+
+        let lines = [
+            json!({"type": "user", "uuid": "u1", "message": {"role": "user", "content": "hi"}})
+                .to_string(),
+            json!({"type": "system", "subtype": "turn_duration", "uuid": "s1"}).to_string(),
+            json!({"type": "progress", "uuid": "p1", "data": {"type": "hook_progress"}})
+                .to_string(),
+            json!({"type": "file-history-snapshot", "messageId": "m1"}).to_string(),
+            "not json".to_string(),
+        ];
+        fs::write(project_dir.join("s1.jsonl"), lines.join("\n")).unwrap();
+        fs::write(
+            subagents_dir.join("agent-1.jsonl"),
+            json!({"type": "assistant", "uuid": "a1"}).to_string(),
+        )
+        .unwrap();
+
+        let stats = get_message_type_stats(
+            "project".to_string(),
+            project_dir.to_string_lossy().to_string(),
+        )
+        .await
+        .unwrap();
+
+        let mut types: Vec<(&str, usize)> = stats
+            .totals
+            .types
+            .iter()
+            .map(|t| (t.message_type.as_str(), t.entry_count))
+            .collect();
+        types.sort_unstable();
+        assert_eq!(
+            types,
+            [
+                ("assistant", 1),
+                ("file-history-snapshot", 1),
+                ("progress:hook_progress", 1),
+                ("system:turn_duration", 1),
+                ("unparsed", 1),
+                ("user", 1),
+            ]
+        );
+        assert_eq!(stats.totals.entry_count, 6);
+        assert!(stats.totals.conversation_share > 0.0 && stats.totals.conversation_share < 1.0);
+        let shares: f64 = stats.totals.types.iter().map(|t| t.byte_share).sum();
+        assert!((shares - 1.0).abs() < 1e-9);
+
+        // The sub-agent file counts towards its session
+        assert_eq!(stats.sessions.len(), 1);
+        assert_eq!(stats.sessions[0].name, "s1");
+        assert_eq!(stats.sessions[0].entry_count, 6);
+        assert_eq!(stats.projects.len(), 1);
+        assert_eq!(stats.projects[0].name, "demo");
+    }
+}
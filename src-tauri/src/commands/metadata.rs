@@ -3,7 +3,27 @@
 //! This module provides commands for loading, saving, and updating
 //! user metadata stored in ~/.claude-history-viewer/user-data.json
 
-use crate::models::{ProjectMetadata, SessionMetadata, UserMetadata, UserSettings};
+use crate::commands::archive::{set_archive_settings, validate_archive_settings};
+use crate::commands::budget::{set_budgets, validate_budget};
+use crate::commands::digest::{set_digest_settings, validate_digest_settings};
+use crate::commands::journal::{record_operation, session_change_summary};
+use crate::commands::local_file::set_local_file_preview;
+use crate::commands::session::set_archived_sessions;
+use crate::commands::stats::{set_scan_roots, validate_scan_roots};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::commands::usage_sink::{set_usage_sink_settings, validate_usage_sink_settings};
+use crate::counting::set_count_exclusions;
+use crate::derived::{evaluate, read_session_entries, set_derived_fields, validate_derived_fields};
+use crate::errors::AppError;
+use crate::io_limit::{set_low_memory, set_max_open_files};
+use crate::models::{
+    ProjectBudget, ProjectMetadata, SessionMetadata, UndoAction, UserMetadata, UserSettings,
+    METADATA_SCHEMA_VERSION,
+};
+use crate::pricing::set_pricing_overrides;
+use crate::redaction::{set_redaction_patterns, validate_redaction_patterns};
+use crate::summarizer::{set_summarizer_settings, validate_summarizer_settings};
+use crate::utils::resolve_session_file;
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -36,7 +56,7 @@ impl Default for MetadataState {
 }
 
 /// Get the metadata folder path (~/.claude-history-viewer)
-fn get_metadata_folder() -> Result<PathBuf, String> {
+pub(crate) fn get_metadata_folder() -> Result<PathBuf, String> {
     let home = dirs::home_dir().ok_or("Could not find home directory")?;
     Ok(home.join(".claude-history-viewer"))
 }
@@ -47,7 +67,7 @@ fn get_user_data_path() -> Result<PathBuf, String> {
 }
 
 /// Ensure the metadata folder exists
-fn ensure_metadata_folder() -> Result<PathBuf, String> {
+pub(crate) fn ensure_metadata_folder() -> Result<PathBuf, String> {
     let folder = get_metadata_folder()?;
     if !folder.exists() {
         fs::create_dir_all(&folder)
@@ -58,7 +78,7 @@ fn ensure_metadata_folder() -> Result<PathBuf, String> {
 
 /// Get the metadata folder path
 #[tauri::command]
-pub async fn get_metadata_folder_path() -> Result<String, String> {
+pub async fn get_metadata_folder_path() -> Result<String, AppError> {
     tauri::async_runtime::spawn_blocking(|| {
         let path = get_metadata_folder()?;
         Ok(path.to_string_lossy().to_string())
@@ -70,15 +90,13 @@ pub async fn get_metadata_folder_path() -> Result<String, String> {
 /// Load user metadata from disk
 /// Creates default metadata if file doesn't exist
 #[tauri::command]
-pub async fn load_user_metadata(state: State<'_, MetadataState>) -> Result<UserMetadata, String> {
+pub async fn load_user_metadata(state: State<'_, MetadataState>) -> Result<UserMetadata, AppError> {
     let path = get_user_data_path()?;
 
     // Perform blocking file I/O off the async runtime
-    let metadata = tauri::async_runtime::spawn_blocking(move || {
+    let metadata = tauri::async_runtime::spawn_blocking(move || -> Result<_, AppError> {
         if path.exists() {
-            let content = fs::read_to_string(&path)
-                .map_err(|e| format!("Failed to read metadata file: {e}"))?;
-            serde_json::from_str(&content).map_err(|e| format!("Failed to parse metadata: {e}"))
+            read_metadata_file(&path)
         } else {
             Ok(UserMetadata::new())
         }
@@ -86,6 +104,10 @@ pub async fn load_user_metadata(state: State<'_, MetadataState>) -> Result<UserM
     .await
     .map_err(|e| format!("Task join error: {e}"))??;
 
+    apply_settings(&metadata.settings);
+    set_budgets(&metadata.projects);
+    set_archived_sessions(&metadata.sessions);
+
     // Cache the metadata (lock is quick, no need to spawn_blocking)
     let mut cached = state
         .metadata
@@ -96,8 +118,56 @@ pub async fn load_user_metadata(state: State<'_, MetadataState>) -> Result<UserM
     Ok(metadata)
 }
 
+fn read_metadata_file(path: &Path) -> Result<UserMetadata, AppError> {
+    let content =
+        fs::read_to_string(path).map_err(|e| AppError::io("Failed to read metadata file", &e))?;
+    serde_json::from_str(&content).map_err(|e| AppError::parse(path, &e))
+}
+
+/// Reject settings the setters below cannot apply
+fn validate_settings(settings: &UserSettings) -> Result<(), AppError> {
+    validate_derived_fields(&settings.derived_fields)?;
+    validate_redaction_patterns(&settings.redaction_patterns)?;
+    validate_archive_settings(settings.archive.as_ref())?;
+    validate_digest_settings(settings.weekly_digest.as_ref())?;
+    validate_usage_sink_settings(settings.usage_sink.as_ref())?;
+    validate_summarizer_settings(settings.summarizer.as_ref())?;
+    validate_scan_roots(&settings.scan_roots)?;
+    Ok(())
+}
+
+/// Hand the settings over to the modules that use them
+pub(crate) fn apply_settings(settings: &UserSettings) {
+    set_max_open_files(settings.max_open_files);
+    set_low_memory(settings.low_memory == Some(true));
+    set_pricing_overrides(settings.pricing_overrides.clone());
+    set_local_file_preview(settings.local_file_preview);
+    set_count_exclusions(settings.count_exclusions.as_deref());
+    set_derived_fields(settings.derived_fields.clone());
+    set_redaction_patterns(&settings.redaction_patterns);
+    set_archive_settings(settings.archive.clone());
+    set_digest_settings(settings.weekly_digest.clone());
+    set_usage_sink_settings(settings.usage_sink.clone());
+    set_summarizer_settings(settings.summarizer.clone());
+    set_scan_roots(settings.scan_roots.clone());
+}
+
+/// Apply the saved settings outside the app (headless runs)
+pub(crate) fn apply_saved_settings() {
+    let Ok(path) = get_user_data_path() else {
+        return;
+    };
+    if !path.exists() {
+        return;
+    }
+    match read_metadata_file(&path) {
+        Ok(metadata) => apply_settings(&metadata.settings),
+        Err(e) => eprintln!("Failed to read settings: {e}"),
+    }
+}
+
 /// Internal helper to save metadata to disk (blocking)
-fn save_metadata_to_disk(metadata: &UserMetadata) -> Result<(), String> {
+pub(crate) fn save_metadata_to_disk(metadata: &UserMetadata) -> Result<(), String> {
     ensure_metadata_folder()?;
     let path = get_user_data_path()?;
 
@@ -124,13 +194,32 @@ fn save_metadata_to_disk(metadata: &UserMetadata) -> Result<(), String> {
 pub async fn save_user_metadata(
     metadata: UserMetadata,
     state: State<'_, MetadataState>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let metadata_clone = metadata.clone();
+    let before = state
+        .metadata
+        .lock()
+        .map_err(|e| format!("Failed to lock metadata: {e}"))?
+        .clone();
 
     // Perform blocking file I/O off the async runtime
-    tauri::async_runtime::spawn_blocking(move || save_metadata_to_disk(&metadata_clone))
-        .await
-        .map_err(|e| format!("Task join error: {e}"))??;
+    tauri::async_runtime::spawn_blocking(move || {
+        save_metadata_to_disk(&metadata_clone)?;
+        let summary = format!(
+            "Saved metadata of {} sessions and {} projects",
+            metadata_clone.sessions.len(),
+            metadata_clone.projects.len()
+        );
+        // Unknown previous state when nothing was loaded yet
+        let undo = before.map(|before| UndoAction::Metadata {
+            before: Box::new(before),
+            after: Box::new(metadata_clone),
+        });
+        record_operation("save_user_metadata", summary, undo);
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
 
     // Update cache
     let mut cached = state
@@ -148,31 +237,97 @@ pub async fn update_session_metadata(
     session_id: String,
     update: SessionMetadata,
     state: State<'_, MetadataState>,
-) -> Result<UserMetadata, String> {
+) -> Result<UserMetadata, AppError> {
     // Perform quick in-memory mutation while holding lock, then release
-    let metadata_to_save = {
+    let (metadata_to_save, before, after) = {
         let mut cached = state
             .metadata
             .lock()
             .map_err(|e| format!("Failed to lock metadata: {e}"))?;
 
         let metadata = cached.get_or_insert_with(UserMetadata::new);
+        let before = metadata.sessions.get(&session_id).cloned();
 
         // Update or insert session metadata
         if update.is_empty() {
             metadata.sessions.remove(&session_id);
         } else {
-            metadata.sessions.insert(session_id, update);
+            metadata.sessions.insert(session_id.clone(), update);
         }
+        set_archived_sessions(&metadata.sessions);
 
-        metadata.clone()
+        let after = metadata.sessions.get(&session_id).cloned();
+        (metadata.clone(), before, after)
     }; // Lock released here
 
     // Perform blocking file I/O off the async runtime
     let metadata_clone = metadata_to_save.clone();
-    tauri::async_runtime::spawn_blocking(move || save_metadata_to_disk(&metadata_clone))
-        .await
-        .map_err(|e| format!("Task join error: {e}"))??;
+    tauri::async_runtime::spawn_blocking(move || {
+        save_metadata_to_disk(&metadata_clone)?;
+        let summary = session_change_summary(&session_id, before.as_ref(), after.as_ref());
+        let undo = UndoAction::SessionMetadata {
+            session_id,
+            before,
+            after,
+        };
+        record_operation("update_session_metadata", summary, Some(undo));
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+
+    Ok(metadata_to_save)
+}
+
+/// Archive a session, hiding it from session lists, or bring it back
+///
+/// Only the viewer's metadata changes: the session file stays where Claude
+/// Code can resume it.
+#[tauri::command]
+pub async fn archive_session(
+    session_id: String,
+    archived: bool,
+    state: State<'_, MetadataState>,
+) -> Result<UserMetadata, AppError> {
+    let (metadata_to_save, before, after) = {
+        let mut cached = state
+            .metadata
+            .lock()
+            .map_err(|e| format!("Failed to lock metadata: {e}"))?;
+
+        let metadata = cached.get_or_insert_with(UserMetadata::new);
+        let before = metadata.sessions.get(&session_id).cloned();
+
+        let session = metadata.get_session_mut(&session_id);
+        if !archived {
+            session.archived_at = None;
+        } else if session.archived_at.is_none() {
+            session.archived_at =
+                Some(chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+        }
+        if session.is_empty() {
+            metadata.sessions.remove(&session_id);
+        }
+        set_archived_sessions(&metadata.sessions);
+
+        let after = metadata.sessions.get(&session_id).cloned();
+        (metadata.clone(), before, after)
+    }; // Lock released here
+
+    let metadata_clone = metadata_to_save.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        save_metadata_to_disk(&metadata_clone)?;
+        let summary = session_change_summary(&session_id, before.as_ref(), after.as_ref());
+        let undo = UndoAction::SessionMetadata {
+            session_id,
+            before,
+            after,
+        };
+        record_operation("archive_session", summary, Some(undo));
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
 
     Ok(metadata_to_save)
 }
@@ -183,34 +338,107 @@ pub async fn update_project_metadata(
     project_path: String,
     update: ProjectMetadata,
     state: State<'_, MetadataState>,
-) -> Result<UserMetadata, String> {
+) -> Result<UserMetadata, AppError> {
     // Validate that project path is absolute
     validate_absolute_path(&project_path)?;
+    if let Some(budget) = &update.budget {
+        validate_budget(budget).map_err(AppError::invalid_input)?;
+    }
 
     // Perform quick in-memory mutation while holding lock, then release
-    let metadata_to_save = {
+    let (metadata_to_save, before, after) = {
         let mut cached = state
             .metadata
             .lock()
             .map_err(|e| format!("Failed to lock metadata: {e}"))?;
 
         let metadata = cached.get_or_insert_with(UserMetadata::new);
+        let before = metadata.projects.get(&project_path).cloned();
 
         // Update or insert project metadata
         if update.is_empty() {
             metadata.projects.remove(&project_path);
         } else {
-            metadata.projects.insert(project_path, update);
+            metadata.projects.insert(project_path.clone(), update);
         }
+        set_budgets(&metadata.projects);
 
-        metadata.clone()
+        let after = metadata.projects.get(&project_path).cloned();
+        (metadata.clone(), before, after)
     }; // Lock released here
 
     // Perform blocking file I/O off the async runtime
     let metadata_clone = metadata_to_save.clone();
-    tauri::async_runtime::spawn_blocking(move || save_metadata_to_disk(&metadata_clone))
-        .await
-        .map_err(|e| format!("Task join error: {e}"))??;
+    tauri::async_runtime::spawn_blocking(move || {
+        save_metadata_to_disk(&metadata_clone)?;
+        let summary = format!("Project {project_path}: metadata updated");
+        let undo = UndoAction::ProjectMetadata {
+            project_path,
+            before,
+            after,
+        };
+        record_operation("update_project_metadata", summary, Some(undo));
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+
+    Ok(metadata_to_save)
+}
+
+/// Set or clear (`None`) the monthly budget of a project
+///
+/// `project_path` is the project folder under `~/.claude/projects`.
+#[tauri::command]
+pub async fn set_project_budget(
+    project_path: String,
+    budget: Option<ProjectBudget>,
+    state: State<'_, MetadataState>,
+) -> Result<UserMetadata, AppError> {
+    validate_absolute_path(&project_path)?;
+    if let Some(budget) = &budget {
+        validate_budget(budget).map_err(AppError::invalid_input)?;
+    }
+
+    // Perform quick in-memory mutation while holding lock, then release
+    let (metadata_to_save, before, after) = {
+        let mut cached = state
+            .metadata
+            .lock()
+            .map_err(|e| format!("Failed to lock metadata: {e}"))?;
+
+        let metadata = cached.get_or_insert_with(UserMetadata::new);
+        let before = metadata.projects.get(&project_path).cloned();
+        let project = metadata.get_project_mut(&project_path);
+        project.budget = budget;
+        if project.is_empty() {
+            metadata.projects.remove(&project_path);
+        }
+        set_budgets(&metadata.projects);
+
+        let after = metadata.projects.get(&project_path).cloned();
+        (metadata.clone(), before, after)
+    }; // Lock released here
+
+    // Perform blocking file I/O off the async runtime
+    let metadata_clone = metadata_to_save.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        save_metadata_to_disk(&metadata_clone)?;
+        let summary = if after.as_ref().and_then(|p| p.budget.as_ref()).is_some() {
+            format!("Project {project_path}: budget set")
+        } else {
+            format!("Project {project_path}: budget cleared")
+        };
+        let undo = UndoAction::ProjectMetadata {
+            project_path,
+            before,
+            after,
+        };
+        record_operation("set_project_budget", summary, Some(undo));
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
 
     Ok(metadata_to_save)
 }
@@ -220,25 +448,188 @@ pub async fn update_project_metadata(
 pub async fn update_user_settings(
     settings: UserSettings,
     state: State<'_, MetadataState>,
-) -> Result<UserMetadata, String> {
+) -> Result<UserMetadata, AppError> {
+    validate_settings(&settings)?;
+
     // Perform quick in-memory mutation while holding lock, then release
-    let metadata_to_save = {
+    let (metadata_to_save, before) = {
         let mut cached = state
             .metadata
             .lock()
             .map_err(|e| format!("Failed to lock metadata: {e}"))?;
 
         let metadata = cached.get_or_insert_with(UserMetadata::new);
-        metadata.settings = settings;
+        apply_settings(&settings);
+        let before = std::mem::replace(&mut metadata.settings, settings);
+
+        (metadata.clone(), before)
+    }; // Lock released here
+
+    // Perform blocking file I/O off the async runtime
+    let metadata_clone = metadata_to_save.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        save_metadata_to_disk(&metadata_clone)?;
+        let undo = UndoAction::Settings {
+            before: Box::new(before),
+            after: Box::new(metadata_clone.settings),
+        };
+        record_operation(
+            "update_user_settings",
+            "Settings updated".to_string(),
+            Some(undo),
+        );
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+
+    Ok(metadata_to_save)
+}
+
+/// Read an exported metadata file, rejecting newer schemas and invalid
+/// settings or budgets
+fn read_metadata_export(path: &Path) -> Result<UserMetadata, AppError> {
+    let metadata = read_metadata_file(path)?;
+    if metadata.version > METADATA_SCHEMA_VERSION {
+        return Err(AppError::invalid_input(format!(
+            "Metadata schema version {} is newer than the supported version {METADATA_SCHEMA_VERSION}",
+            metadata.version
+        )));
+    }
+    validate_settings(&metadata.settings)?;
+    for (project_path, project) in &metadata.projects {
+        validate_absolute_path(project_path).map_err(AppError::invalid_input)?;
+        if let Some(budget) = &project.budget {
+            validate_budget(budget).map_err(AppError::invalid_input)?;
+        }
+    }
+    Ok(metadata)
+}
+
+/// Add imported session and project metadata to `metadata` (imported
+/// entries win) and take over the imported settings
+fn merge_metadata(metadata: &mut UserMetadata, imported: UserMetadata) {
+    metadata.sessions.extend(imported.sessions);
+    metadata.projects.extend(imported.projects);
+    metadata.settings = imported.settings;
+}
+
+/// Make `imported` current, replacing `metadata` or with `merge` merged
+/// into it; exports carry no credentials, so the current ones are kept
+fn import_into(metadata: &mut UserMetadata, mut imported: UserMetadata, merge: bool) {
+    imported.settings.keep_secrets_of(&metadata.settings);
+    if merge {
+        merge_metadata(metadata, imported);
+    } else {
+        *metadata = imported;
+    }
+}
+
+/// Content of a metadata export: credentials are left out, as the file is
+/// meant to be shared or put under version control
+fn metadata_export_content(metadata: &UserMetadata) -> Result<String, String> {
+    serde_json::to_string_pretty(&metadata.without_secrets())
+        .map_err(|e| format!("Failed to serialize metadata: {e}"))
+}
+
+/// Write the viewer's own data (session names, tags, notes and stars,
+/// project aliases, hidden flags and budgets, settings) to `path`, without
+/// any session log or stored credential
+#[tauri::command]
+pub async fn export_metadata(
+    path: String,
+    state: State<'_, MetadataState>,
+) -> Result<(), AppError> {
+    let _timer = OperationTimer::start("export_metadata");
+    if !Path::new(&path).is_absolute() {
+        return Err(AppError::invalid_input(
+            "Invalid export path: must be an absolute path",
+        ));
+    }
+
+    let cached = state
+        .metadata
+        .lock()
+        .map_err(|e| format!("Failed to lock metadata: {e}"))?
+        .clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let metadata = if let Some(metadata) = cached {
+            metadata
+        } else {
+            let data_path = get_user_data_path()?;
+            if data_path.exists() {
+                read_metadata_file(&data_path)?
+            } else {
+                UserMetadata::new()
+            }
+        };
+        let content = metadata_export_content(&metadata)?;
+        fs::write(&path, content).map_err(|e| AppError::io("Failed to write metadata export", &e))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Load metadata written by `export_metadata` and make it current
+///
+/// The imported file replaces the current metadata, or with `merge` adds
+/// its sessions and projects to it (imported entries win). Settings are
+/// always taken from the file, except for the current credentials.
+#[tauri::command]
+pub async fn import_metadata(
+    path: String,
+    merge: Option<bool>,
+    state: State<'_, MetadataState>,
+) -> Result<UserMetadata, AppError> {
+    let _timer = OperationTimer::start("import_metadata");
+    if !Path::new(&path).is_absolute() {
+        return Err(AppError::invalid_input(
+            "Invalid import path: must be an absolute path",
+        ));
+    }
+    let merge = merge.unwrap_or(false);
+    let summary = if merge {
+        format!("Merged metadata from {path}")
+    } else {
+        format!("Replaced metadata with {path}")
+    };
+    let imported =
+        tauri::async_runtime::spawn_blocking(move || read_metadata_export(Path::new(&path)))
+            .await
+            .map_err(|e| format!("Task join error: {e}"))??;
 
-        metadata.clone()
+    // Perform quick in-memory mutation while holding lock, then release
+    let (metadata_to_save, before) = {
+        let mut cached = state
+            .metadata
+            .lock()
+            .map_err(|e| format!("Failed to lock metadata: {e}"))?;
+
+        let metadata = cached.get_or_insert_with(UserMetadata::new);
+        let before = metadata.clone();
+        import_into(metadata, imported, merge);
+        metadata.version = METADATA_SCHEMA_VERSION;
+        apply_settings(&metadata.settings);
+        set_budgets(&metadata.projects);
+        set_archived_sessions(&metadata.sessions);
+
+        (metadata.clone(), before)
     }; // Lock released here
 
     // Perform blocking file I/O off the async runtime
     let metadata_clone = metadata_to_save.clone();
-    tauri::async_runtime::spawn_blocking(move || save_metadata_to_disk(&metadata_clone))
-        .await
-        .map_err(|e| format!("Task join error: {e}"))??;
+    tauri::async_runtime::spawn_blocking(move || {
+        save_metadata_to_disk(&metadata_clone)?;
+        let undo = UndoAction::Metadata {
+            before: Box::new(before),
+            after: Box::new(metadata_clone),
+        };
+        record_operation("import_metadata", summary, Some(undo));
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
 
     Ok(metadata_to_save)
 }
@@ -248,7 +639,7 @@ pub async fn update_user_settings(
 pub async fn is_project_hidden(
     project_path: String,
     state: State<'_, MetadataState>,
-) -> Result<bool, String> {
+) -> Result<bool, AppError> {
     // Validate that project path is absolute
     validate_absolute_path(&project_path)?;
 
@@ -271,7 +662,7 @@ pub async fn get_session_display_name(
     session_id: String,
     fallback_summary: Option<String>,
     state: State<'_, MetadataState>,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, AppError> {
     let cached = state
         .metadata
         .lock()
@@ -286,9 +677,26 @@ pub async fn get_session_display_name(
     Ok(display_name)
 }
 
+/// Evaluate a derived field expression on one session, to preview it
+/// before saving it in the settings
+#[tauri::command]
+pub async fn preview_derived_field(
+    expression: String,
+    session_id: String,
+    project_path: String,
+) -> Result<Option<serde_json::Value>, AppError> {
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        evaluate(&expression, read_session_entries(&session_path)?)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::SummarizerSettings;
     use std::env;
     use std::sync::{LazyLock, Mutex, MutexGuard};
     use tempfile::TempDir;
@@ -351,4 +759,66 @@ mod tests {
 
         drop(temp);
     }
+
+    #[test]
+    fn test_metadata_export_round_trip_and_merge() {
+        let temp = TempDir::new().unwrap();
+        let mut exported = UserMetadata::new();
+        exported.get_session_mut("s1").tags = vec!["release".to_string()];
+        exported.get_session_mut("s2").notes = Some("imported".to_string());
+        exported.get_project_mut("/work/api").alias = Some("API".to_string());
+        exported.settings.hidden_patterns = vec!["tmp-*".to_string()];
+        let path = temp.path().join("metadata.json");
+        fs::write(&path, serde_json::to_string_pretty(&exported).unwrap()).unwrap();
+
+        let imported = read_metadata_export(&path).unwrap();
+        assert_eq!(imported, exported);
+
+        let mut current = UserMetadata::new();
+        current.get_session_mut("s2").notes = Some("local".to_string());
+        current.get_session_mut("s3").starred = Some(true);
+        merge_metadata(&mut current, imported);
+        assert_eq!(current.sessions.len(), 3);
+        assert_eq!(current.sessions["s2"].notes.as_deref(), Some("imported"));
+        assert_eq!(current.sessions["s3"].starred, Some(true));
+        assert_eq!(current.settings.hidden_patterns, ["tmp-*"]);
+    }
+
+    #[test]
+    fn test_metadata_export_and_import_keep_credentials_local() {
+        let mut current = UserMetadata::new();
+        current.settings.summarizer = Some(SummarizerSettings {
+            backend: "anthropic".to_string(),
+            model: "haiku".to_string(),
+            api_key: Some("sk-local-secret".to_string()),
+            ..SummarizerSettings::default()
+        });
+
+        let content = metadata_export_content(&current).unwrap();
+        assert!(!content.contains("sk-local-secret"), "{content}");
+
+        let mut imported: UserMetadata = serde_json::from_str(&content).unwrap();
+        imported.settings.summarizer.as_mut().unwrap().model = "sonnet".to_string();
+        for merge in [false, true] {
+            let mut metadata = current.clone();
+            import_into(&mut metadata, imported.clone(), merge);
+            let summarizer = metadata.settings.summarizer.unwrap();
+            assert_eq!(summarizer.model, "sonnet");
+            assert_eq!(summarizer.api_key.as_deref(), Some("sk-local-secret"));
+        }
+    }
+
+    #[test]
+    fn test_metadata_import_rejects_newer_schema() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("metadata.json");
+        fs::write(&path, r#"{"version": 99, "sessions": {}}"#).unwrap();
+        assert!(matches!(
+            read_metadata_export(&path),
+            Err(AppError::InvalidInput { .. })
+        ));
+
+        fs::write(&path, r#"{"projects": {"relative/path": {"alias": "x"}}}"#).unwrap();
+        assert!(read_metadata_export(&path).is_err());
+    }
 }
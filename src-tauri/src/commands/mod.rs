@@ -1,8 +1,44 @@
+pub mod anomalies;
+pub mod archive;
+pub mod attention;
+pub mod branch_costs;
+pub mod budget;
+pub mod changelog;
+pub mod churn;
+pub mod compare;
+pub mod context_budget;
+pub mod cost_report;
+pub mod digest;
+pub mod entities;
+pub mod error_report;
+pub mod expensive_messages;
+pub mod export;
 pub mod feedback;
+pub mod file_history;
+pub mod focus;
+pub mod hooks;
+pub mod journal;
+pub mod lint;
+pub mod local_file;
+pub mod message_types;
 pub mod metadata;
+pub mod pricing_catalog;
+pub mod pricing_file;
+pub mod profile;
 pub mod project;
+pub mod prompt_quality;
+pub mod retry_loops;
+pub mod reveal;
 pub mod session;
+pub mod sidechains;
+pub mod slash_commands;
 pub mod stats;
+pub mod timeline;
+pub mod trash;
+pub mod usage_blocks;
+pub mod usage_metrics;
+pub mod usage_sink;
+pub mod waste;
 
 #[cfg(test)]
 mod proptest_examples;
@@ -0,0 +1,172 @@
+//! Pricing catalog sync
+//!
+//! Fetches per-model prices from the public `LiteLLM` catalog and caches them
+//! in the metadata folder, so models released after this build are still
+//! priced correctly. Without a cached catalog the built-in table is used.
+
+use crate::commands::metadata::{ensure_metadata_folder, get_metadata_folder};
+use crate::commands::usage_metrics::{write_json_atomic, OperationTimer};
+use crate::errors::AppError;
+use crate::models::PricingCatalogStatus;
+use crate::pricing::{set_catalog_pricing, ModelPricing};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// `LiteLLM` model price list (USD per token)
+pub const LITELLM_CATALOG_URL: &str =
+    "https://raw.githubusercontent.com/BerriAI/litellm/main/model_prices_and_context_window.json";
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Catalog as cached on disk
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CachedPricingCatalog {
+    source_url: String,
+    fetched_at: String,
+    models: HashMap<String, ModelPricing>,
+}
+
+/// Pricing fields of a `LiteLLM` catalog entry
+#[derive(serde::Deserialize)]
+struct LiteLlmEntry {
+    input_cost_per_token: Option<f64>,
+    output_cost_per_token: Option<f64>,
+    cache_creation_input_token_cost: Option<f64>,
+    cache_read_input_token_cost: Option<f64>,
+}
+
+/// Get the catalog cache path (~/.claude-history-viewer/pricing-catalog.json)
+fn get_catalog_path() -> Result<PathBuf, String> {
+    Ok(get_metadata_folder()?.join("pricing-catalog.json"))
+}
+
+/// Extract Claude model prices (USD per million tokens) from the `LiteLLM` JSON
+///
+/// Entries without input and output prices are skipped. Missing cache prices
+/// follow Anthropic's usual ratios to the input price (1.25x write, 0.1x read).
+fn parse_litellm_catalog(json: &str) -> Result<HashMap<String, ModelPricing>, String> {
+    let entries: HashMap<String, serde_json::Value> =
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse pricing catalog: {e}"))?;
+
+    let per_mtok = |per_token: f64| per_token * 1_000_000.0;
+    let models: HashMap<String, ModelPricing> = entries
+        .into_iter()
+        .filter(|(model, _)| model.to_lowercase().contains("claude"))
+        .filter_map(|(model, value)| {
+            let entry: LiteLlmEntry = serde_json::from_value(value).ok()?;
+            let input = per_mtok(entry.input_cost_per_token?);
+            Some((
+                model.to_lowercase(),
+                ModelPricing {
+                    input,
+                    output: per_mtok(entry.output_cost_per_token?),
+                    cache_write: entry
+                        .cache_creation_input_token_cost
+                        .map_or(input * 1.25, per_mtok),
+                    cache_read: entry
+                        .cache_read_input_token_cost
+                        .map_or(input * 0.1, per_mtok),
+                },
+            ))
+        })
+        .collect();
+
+    if models.is_empty() {
+        return Err("Pricing catalog contains no Claude models".to_string());
+    }
+    Ok(models)
+}
+
+/// Load the cached catalog into the pricing engine, if one was synced before
+pub fn load_cached_pricing_catalog() {
+    let Ok(path) = get_catalog_path() else {
+        return;
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return;
+    };
+    match serde_json::from_str::<CachedPricingCatalog>(&content) {
+        Ok(catalog) => set_catalog_pricing(catalog.models),
+        Err(e) => eprintln!("Ignoring invalid pricing catalog cache: {e}"),
+    }
+}
+
+/// Fetch the latest model prices and cache them for future launches
+#[tauri::command]
+pub async fn sync_pricing_catalog() -> Result<PricingCatalogStatus, AppError> {
+    let _timer = OperationTimer::start("sync_pricing_catalog");
+
+    let client = tauri_plugin_http::reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+    let response = client
+        .get(LITELLM_CATALOG_URL)
+        .send()
+        .await
+        .and_then(tauri_plugin_http::reqwest::Response::error_for_status)
+        .map_err(|e| format!("Failed to fetch pricing catalog: {e}"))?;
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read pricing catalog: {e}"))?;
+
+    let catalog = CachedPricingCatalog {
+        source_url: LITELLM_CATALOG_URL.to_string(),
+        fetched_at: Utc::now().to_rfc3339(),
+        models: parse_litellm_catalog(&body)?,
+    };
+    let status = PricingCatalogStatus {
+        source_url: catalog.source_url.clone(),
+        fetched_at: catalog.fetched_at.clone(),
+        model_count: catalog.models.len(),
+    };
+
+    let catalog = tauri::async_runtime::spawn_blocking(move || {
+        ensure_metadata_folder()?;
+        write_json_atomic(&get_catalog_path()?, &catalog)?;
+        Ok::<_, String>(catalog)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+    set_catalog_pricing(catalog.models);
+
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_litellm_catalog() {
+        let json = r#"{
+            "sample_spec": {"input_cost_per_token": 0.0},
+            "claude-sonnet-5": {
+                "input_cost_per_token": 3e-06,
+                "output_cost_per_token": 1.5e-05,
+                "cache_read_input_token_cost": 3e-07
+            },
+            "anthropic.claude-haiku-9": {"input_cost_per_token": 1e-06},
+            "gpt-4o": {"input_cost_per_token": 2.5e-06, "output_cost_per_token": 1e-05}
+        }"#;
+
+        let models = parse_litellm_catalog(json).unwrap();
+
+        assert_eq!(models.len(), 1);
+        let sonnet = models["claude-sonnet-5"];
+        assert!((sonnet.input - 3.0).abs() < 1e-9);
+        assert!((sonnet.output - 15.0).abs() < 1e-9);
+        assert!((sonnet.cache_write - 3.75).abs() < 1e-9);
+        assert!((sonnet.cache_read - 0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_litellm_catalog_rejects_empty() {
+        assert!(parse_litellm_catalog("{}").is_err());
+        assert!(parse_litellm_catalog("not json").is_err());
+    }
+}
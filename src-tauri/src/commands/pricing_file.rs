@@ -0,0 +1,271 @@
+//! User pricing file
+//!
+//! `pricing.toml` (or `pricing.json`) in the metadata folder adds custom
+//! models, enterprise rates and a currency multiplier to the pricing engine
+//! (see `crate::pricing`), e.g. for Bedrock or Vertex contracts. The file is
+//! loaded at startup and polled in the background, so edits apply without a
+//! restart; every reload is reported through `PRICING_FILE_RELOADED_EVENT`.
+//! A file that fails to parse leaves the previous prices in effect.
+
+use crate::commands::metadata::get_metadata_folder;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::freshness::FileStamp;
+use crate::models::{PricingFile, PricingFileStatus};
+use crate::pricing::set_pricing_file;
+use crate::utils::{display_path, long_path};
+use chrono::Utc;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Event emitted after the pricing file was reloaded
+pub const PRICING_FILE_RELOADED_EVENT: &str = "pricing-file-reloaded";
+
+/// Interval between two checks of the pricing file
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// File names looked up in the metadata folder, in priority order
+const PRICING_FILE_NAMES: &[&str] = &["pricing.toml", "pricing.json"];
+
+/// Currency of all prices before the multiplier is applied
+const BASE_CURRENCY: &str = "USD";
+
+#[derive(Default)]
+struct PricingFileState {
+    checked: Option<(PathBuf, FileStamp)>, // File found at the last check
+    status: Option<PricingFileStatus>,     // None until the first load
+}
+
+type ReloadListener = Box<dyn Fn(&PricingFileStatus) + Send + Sync>;
+
+/// Forwards reloads to the frontend (unset in tests)
+static RELOAD_LISTENER: OnceLock<ReloadListener> = OnceLock::new();
+
+static STATE: OnceLock<Mutex<PricingFileState>> = OnceLock::new();
+
+fn state() -> &'static Mutex<PricingFileState> {
+    STATE.get_or_init(|| Mutex::new(PricingFileState::default()))
+}
+
+/// Register the callback that emits reload events to the frontend
+pub fn set_pricing_file_listener(listener: impl Fn(&PricingFileStatus) + Send + Sync + 'static) {
+    let _ = RELOAD_LISTENER.set(Box::new(listener));
+}
+
+/// The pricing file in `folder`, with its current stamp
+fn find_pricing_file(folder: &Path) -> Option<(PathBuf, FileStamp)> {
+    PRICING_FILE_NAMES.iter().find_map(|name| {
+        let path = folder.join(name);
+        FileStamp::of(&path).map(|stamp| (path, stamp))
+    })
+}
+
+/// Parse a pricing file as TOML or JSON (by extension) and check its values
+fn parse_pricing_file(path: &Path, content: &str) -> Result<PricingFile, String> {
+    let file: PricingFile = if path.extension().and_then(|s| s.to_str()) == Some("toml") {
+        toml::from_str(content).map_err(|e| format!("Invalid pricing file: {e}"))?
+    } else {
+        serde_json::from_str(content).map_err(|e| format!("Invalid pricing file: {e}"))?
+    };
+
+    let valid_price = |price: f64| price.is_finite() && price >= 0.0;
+    if let Some(multiplier) = file.currency_multiplier {
+        if !valid_price(multiplier) {
+            return Err(format!("Invalid currency multiplier: {multiplier}"));
+        }
+    }
+    for (model, pricing) in &file.models {
+        let prices = [
+            pricing.input,
+            pricing.output,
+            pricing.cache_write,
+            pricing.cache_read,
+        ];
+        if !prices.into_iter().all(valid_price) {
+            return Err(format!("Invalid prices for model {model}"));
+        }
+    }
+    for o in &file.overrides {
+        let prices = [o.input, o.output, o.cache_write, o.cache_read];
+        if !prices.into_iter().flatten().all(valid_price) {
+            return Err("Invalid prices in pricing override".to_string());
+        }
+    }
+    Ok(file)
+}
+
+fn file_status(path: &Path, file: Option<&PricingFile>) -> PricingFileStatus {
+    PricingFileStatus {
+        path: display_path(path),
+        exists: file.is_some(),
+        loaded_at: file.map(|_| Utc::now().to_rfc3339()),
+        currency: file
+            .and_then(|file| file.currency.clone())
+            .unwrap_or_else(|| BASE_CURRENCY.to_string()),
+        currency_multiplier: file
+            .and_then(|file| file.currency_multiplier)
+            .unwrap_or(1.0),
+        custom_model_count: file.map_or(0, |file| file.models.len()),
+        override_count: file.map_or(0, |file| file.overrides.len()),
+        error: None,
+    }
+}
+
+/// Load the pricing file in `folder` if it changed since the last check
+///
+/// Returns the new status when the file was (re)loaded or removed.
+fn reload_if_changed(folder: &Path) -> Option<PricingFileStatus> {
+    let current = find_pricing_file(folder);
+    let mut state = state().lock().ok()?;
+    if state.status.is_some() && state.checked == current {
+        return None;
+    }
+    state.checked.clone_from(&current);
+
+    let status = match current {
+        None => {
+            set_pricing_file(PricingFile::default());
+            file_status(&folder.join(PRICING_FILE_NAMES[0]), None)
+        }
+        Some((path, _)) => {
+            let loaded = fs::read_to_string(long_path(&path))
+                .map_err(|e| format!("Failed to read pricing file: {e}"))
+                .and_then(|content| parse_pricing_file(&path, &content));
+            match loaded {
+                Ok(file) => {
+                    let status = file_status(&path, Some(&file));
+                    set_pricing_file(file);
+                    status
+                }
+                Err(e) => {
+                    // Keep the prices and status of the last good load
+                    let mut status = state
+                        .status
+                        .clone()
+                        .filter(|status| status.exists)
+                        .unwrap_or_else(|| file_status(&path, None));
+                    status.path = display_path(&path);
+                    status.exists = true;
+                    status.error = Some(e);
+                    status
+                }
+            }
+        }
+    };
+    state.status = Some(status.clone());
+    Some(status)
+}
+
+/// Load the pricing file at startup
+pub fn load_pricing_file() {
+    if let Ok(folder) = get_metadata_folder() {
+        if let Some(PricingFileStatus {
+            error: Some(e),
+            path,
+            ..
+        }) = reload_if_changed(&folder)
+        {
+            eprintln!("Ignoring pricing file {path}: {e}");
+        }
+    }
+}
+
+/// Start polling the pricing file in the background
+pub fn start_pricing_file_watcher() {
+    tauri::async_runtime::spawn(async {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let Ok(Some(status)) = tauri::async_runtime::spawn_blocking(|| {
+                get_metadata_folder()
+                    .ok()
+                    .and_then(|folder| reload_if_changed(&folder))
+            })
+            .await
+            else {
+                continue;
+            };
+            if let Some(listener) = RELOAD_LISTENER.get() {
+                listener(&status);
+            }
+        }
+    });
+}
+
+/// Where the pricing file is read from and what it currently contributes
+#[tauri::command]
+pub async fn get_pricing_file_status() -> Result<PricingFileStatus, AppError> {
+    let _timer = OperationTimer::start("get_pricing_file_status");
+
+    tauri::async_runtime::spawn_blocking(|| {
+        let folder = get_metadata_folder()?;
+        reload_if_changed(&folder);
+        Ok(state()
+            .lock()
+            .ok()
+            .and_then(|state| state.status.clone())
+            .unwrap_or_else(|| file_status(&folder.join(PRICING_FILE_NAMES[0]), None)))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pricing_file_toml() {
+        let toml = r#"
+            currency = "EUR"
+            currencyMultiplier = 0.92
+
+            [models."acme-claude-internal"]
+            input = 2.0
+            output = 10.0
+            cacheWrite = 2.5
+            cacheRead = 0.2
+
+            [[overrides]]
+            modelPattern = "*opus*"
+            input = 12.0
+        "#;
+
+        let file = parse_pricing_file(Path::new("pricing.toml"), toml).unwrap();
+
+        assert_eq!(file.currency.as_deref(), Some("EUR"));
+        assert_eq!(file.currency_multiplier, Some(0.92));
+        let custom = file.models["acme-claude-internal"];
+        assert!((custom.cache_write - 2.5).abs() < f64::EPSILON);
+        assert_eq!(file.overrides.len(), 1);
+        assert_eq!(file.overrides[0].model_pattern.as_deref(), Some("*opus*"));
+    }
+
+    #[test]
+    fn test_parse_pricing_file_json_and_validation() {
+        let json =
+            r#"{"models": {"m": {"input": 1, "output": 2, "cache_write": 0, "cache_read": 0}}}"#;
+        let file = parse_pricing_file(Path::new("pricing.json"), json).unwrap();
+        assert_eq!(file.models.len(), 1);
+        assert_eq!(file.currency_multiplier, None);
+
+        let negative = r#"{"currencyMultiplier": -1}"#;
+        assert!(parse_pricing_file(Path::new("pricing.json"), negative).is_err());
+        let bad_override = r#"{"overrides": [{"input": -3}]}"#;
+        assert!(parse_pricing_file(Path::new("pricing.json"), bad_override).is_err());
+        assert!(parse_pricing_file(Path::new("pricing.toml"), "currency = ").is_err());
+    }
+
+    #[test]
+    fn test_find_pricing_file_prefers_toml() {
+        let temp = tempfile::TempDir::new().unwrap();
+        assert!(find_pricing_file(temp.path()).is_none());
+
+        fs::write(temp.path().join("pricing.json"), "{}").unwrap();
+        fs::write(temp.path().join("pricing.toml"), "").unwrap();
+
+        let (path, _) = find_pricing_file(temp.path()).unwrap();
+        assert!(path.ends_with("pricing.toml"));
+    }
+}
@@ -0,0 +1,290 @@
+//! Timing breakdowns for performance reports
+//!
+//! `profile_operation` runs one of the heavy commands on the user's own data
+//! and splits its cost into phases. The session files are first read
+//! (`io`, cold) and parsed (`parse`) with the same SIMD parser the commands
+//! use, chunk by chunk to bound memory. The command then runs on the cached
+//! files; what it spends beyond parsing is reported as `aggregate`, and
+//! serializing its result for the frontend as `serialize`.
+
+use crate::commands::session::{load_project_sessions, load_session_messages};
+use crate::commands::stats::{
+    get_global_stats_summary, get_project_stats_summary, get_session_token_stats,
+    parse_raw_log_entries, resolve_scope_session_files,
+};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::io_limit::acquire_file_permit;
+use crate::models::{ClaudeMessage, OperationProfile, PhaseTiming};
+use crate::utils::long_path;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Files read and parsed together, bounding the memory held by `io`
+const CHUNK_FILES: usize = 256;
+
+/// Parameters of the profiled command (only the one it takes is required)
+#[derive(Debug, Default, Deserialize)]
+struct ProfileParams {
+    #[serde(rename = "sessionPath")]
+    session: Option<String>,
+    #[serde(rename = "projectPath")]
+    project: Option<String>,
+    #[serde(rename = "claudePath")]
+    claude: Option<String>,
+}
+
+/// Operations that can be profiled, with the path they run on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProfiledOperation {
+    LoadSessionMessages,
+    SessionTokenStats,
+    ProjectSessions,
+    ProjectStatsSummary,
+    GlobalStatsSummary,
+}
+
+impl ProfiledOperation {
+    fn parse(name: &str) -> Result<Self, AppError> {
+        match name {
+            "load_session_messages" => Ok(Self::LoadSessionMessages),
+            "get_session_token_stats" => Ok(Self::SessionTokenStats),
+            "load_project_sessions" => Ok(Self::ProjectSessions),
+            "get_project_stats_summary" => Ok(Self::ProjectStatsSummary),
+            "get_global_stats_summary" => Ok(Self::GlobalStatsSummary),
+            other => Err(AppError::invalid_input(format!(
+                "Operation cannot be profiled: {other}"
+            ))),
+        }
+    }
+
+    /// Scope and path of the session files the operation reads
+    fn target(self, params: ProfileParams) -> Result<(&'static str, String), AppError> {
+        let (scope, path, name) = match self {
+            Self::LoadSessionMessages | Self::SessionTokenStats => {
+                ("session", params.session, "sessionPath")
+            }
+            Self::ProjectSessions | Self::ProjectStatsSummary => {
+                ("project", params.project, "projectPath")
+            }
+            Self::GlobalStatsSummary => ("global", params.claude, "claudePath"),
+        };
+        let path = path.ok_or_else(|| AppError::invalid_input(format!("Missing {name}")))?;
+        Ok((scope, path))
+    }
+}
+
+/// `io` and `parse` measurements over all session files
+#[derive(Default)]
+struct ReadTimings {
+    io: Duration,
+    parse: Duration,
+    bytes_read: u64,
+    entry_count: usize,
+}
+
+fn read_and_parse(files: &[PathBuf]) -> ReadTimings {
+    let mut timings = ReadTimings::default();
+    for chunk in files.chunks(CHUNK_FILES) {
+        let started = Instant::now();
+        let contents: Vec<Vec<u8>> = chunk
+            .par_iter()
+            .filter_map(|path| {
+                let _permit = acquire_file_permit();
+                fs::read(long_path(path)).ok()
+            })
+            .collect();
+        timings.io += started.elapsed();
+        timings.bytes_read += contents.iter().map(|data| data.len() as u64).sum::<u64>();
+
+        let started = Instant::now();
+        timings.entry_count += contents
+            .par_iter()
+            .map(|data| {
+                parse_raw_log_entries(data)
+                    .into_iter()
+                    .filter_map(|entry| ClaudeMessage::try_from(entry).ok())
+                    .count()
+            })
+            .sum::<usize>();
+        timings.parse += started.elapsed();
+    }
+    timings
+}
+
+/// Run time of `command` and of serializing its result, with the result size
+async fn time_command<T, F>(command: F) -> Result<(Duration, Duration, usize), AppError>
+where
+    T: Serialize,
+    F: Future<Output = Result<T, AppError>>,
+{
+    let started = Instant::now();
+    let result = command.await?;
+    let command_time = started.elapsed();
+
+    let started = Instant::now();
+    let serialized =
+        serde_json::to_vec(&result).map_err(|e| format!("Failed to serialize result: {e}"))?;
+    Ok((command_time, started.elapsed(), serialized.len()))
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Phase list with each phase's share of the total
+fn phase_timings(phases: &[(&str, Duration)]) -> (f64, Vec<PhaseTiming>) {
+    let total: f64 = phases.iter().map(|(_, duration)| millis(*duration)).sum();
+    let timings = phases
+        .iter()
+        .map(|(phase, duration)| PhaseTiming {
+            phase: (*phase).to_string(),
+            duration_ms: millis(*duration),
+            share: if total > 0.0 {
+                millis(*duration) / total
+            } else {
+                0.0
+            },
+        })
+        .collect();
+    (total, timings)
+}
+
+/// Run `operation` with a timing breakdown (scan, io, parse, aggregate,
+/// serialize) to attach to performance reports
+///
+/// `operation` is the command name (`load_session_messages`,
+/// `get_session_token_stats`, `load_project_sessions`,
+/// `get_project_stats_summary` or `get_global_stats_summary`) and `params`
+/// holds its path argument (`sessionPath`, `projectPath` or `claudePath`).
+#[tauri::command]
+pub async fn profile_operation(
+    operation: String,
+    params: serde_json::Value,
+) -> Result<OperationProfile, AppError> {
+    let _timer = OperationTimer::start("profile_operation");
+
+    let kind = ProfiledOperation::parse(&operation)?;
+    let params: ProfileParams = serde_json::from_value(params)
+        .map_err(|e| AppError::invalid_input(format!("Invalid parameters: {e}")))?;
+    let (scope, path) = kind.target(params)?;
+
+    let (scan, files, reads) = {
+        let path = path.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let started = Instant::now();
+            let files = resolve_scope_session_files(scope, &path)?;
+            let scan = started.elapsed();
+            let reads = read_and_parse(&files);
+            Ok::<_, String>((scan, files, reads))
+        })
+        .await
+        .map_err(|e| format!("Task join error: {e}"))??
+    };
+
+    let (command, serialize, result_bytes) = match kind {
+        ProfiledOperation::LoadSessionMessages => {
+            time_command(load_session_messages(path, None)).await?
+        }
+        ProfiledOperation::SessionTokenStats => time_command(get_session_token_stats(path)).await?,
+        ProfiledOperation::ProjectSessions => {
+            time_command(load_project_sessions(path, None, None)).await?
+        }
+        ProfiledOperation::ProjectStatsSummary => {
+            time_command(get_project_stats_summary(path)).await?
+        }
+        ProfiledOperation::GlobalStatsSummary => {
+            time_command(get_global_stats_summary(path, None, None)).await?
+        }
+    };
+
+    let (total_ms, phases) = phase_timings(&[
+        ("scan", scan),
+        ("io", reads.io),
+        ("parse", reads.parse),
+        ("aggregate", command.saturating_sub(reads.parse)),
+        ("serialize", serialize),
+    ]);
+    Ok(OperationProfile {
+        operation,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os_type: std::env::consts::OS.to_string(),
+        worker_threads: rayon::current_num_threads(),
+        file_count: files.len(),
+        bytes_read: reads.bytes_read,
+        entry_count: reads.entry_count,
+        result_bytes,
+        command_ms: millis(command),
+        total_ms,
+        phases,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_profile_project_stats() {
+        let temp = TempDir::new().unwrap();
+        let project_dir = temp.path().join("-Users-me-demo");
+        fs::create_dir_all(&project_dir).unwrap();
+        let lines = ["msg_1", "msg_2"].map(|id| {
+            json!({
+                "uuid": id,
+                "sessionId": "s1",
+                "timestamp": "2025-03-01T09:40:00Z",
+                "type": "assistant",
+                "message": {
+                    "id": id,
+                    "role": "assistant",
+                    "model": "claude-sonnet-4-20250514",
+                    "content": [{"type": "text", "text": "ok"}],
+                    "usage": {"input_tokens": 0, "output_tokens": 10}
+                }
+            })
+            .to_string()
+        });
+        fs::write(project_dir.join("s1.jsonl"), lines.join("\n")).unwrap();
+
+        let profile = profile_operation(
+            "get_project_stats_summary".to_string(),
+            json!({"projectPath": project_dir.to_string_lossy()}),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(profile.file_count, 1);
+        assert_eq!(profile.entry_count, 2);
+        assert!(profile.bytes_read > 0);
+        assert!(profile.result_bytes > 0);
+        assert_eq!(
+            profile
+                .phases
+                .iter()
+                .map(|p| p.phase.as_str())
+                .collect::<Vec<_>>(),
+            ["scan", "io", "parse", "aggregate", "serialize"]
+        );
+        let shares: f64 = profile.phases.iter().map(|p| p.share).sum();
+        assert!((shares - 1.0).abs() < 1e-9 || profile.total_ms == 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_profile_rejects_unknown_operation_and_missing_path() {
+        assert!(matches!(
+            profile_operation("delete_everything".to_string(), json!({})).await,
+            Err(AppError::InvalidInput { .. })
+        ));
+        assert!(matches!(
+            profile_operation("get_project_stats_summary".to_string(), json!({})).await,
+            Err(AppError::InvalidInput { .. })
+        ));
+    }
+}
@@ -1,34 +1,149 @@
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
 use crate::models::ClaudeProject;
-use crate::utils::{estimate_message_count_from_size, extract_project_name};
+use crate::utils::{
+    collect_jsonl_files_async, display_path, estimate_message_count_from_size,
+    extract_project_name, file_name_string, long_path,
+};
 use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::ffi::OsString;
 use std::fs;
-use std::path::PathBuf;
-use walkdir::WalkDir;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Event emitted with the `ClaudeProject` of a project folder that appeared
+/// after the last scan
+pub const PROJECT_ADDED_EVENT: &str = "project-added";
+
+/// How often the projects folder is checked for new projects
+const PROJECT_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+type ProjectAddedListener = Box<dyn Fn(&ClaudeProject) + Send + Sync>;
+
+/// Forwards new projects to the frontend (unset in tests)
+static PROJECT_ADDED_LISTENER: OnceLock<ProjectAddedListener> = OnceLock::new();
+
+/// Projects folder of the last scan, watched for new subdirectories
+static PROJECTS_WATCH: Mutex<Option<ProjectsWatch>> = Mutex::new(None);
+
+/// Subdirectories of a projects folder already known to the frontend
+struct ProjectsWatch {
+    projects_path: PathBuf,
+    known: HashSet<OsString>,
+}
+
+impl ProjectsWatch {
+    fn new(projects_path: PathBuf, known: HashSet<OsString>) -> Self {
+        Self {
+            projects_path,
+            known,
+        }
+    }
+
+    /// Project folders created since the previous call
+    fn poll(&mut self) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(long_path(&self.projects_path)) else {
+            return Vec::new();
+        };
+        let mut added: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+            .filter(|entry| self.known.insert(entry.file_name()))
+            .map(|entry| self.projects_path.join(entry.file_name()))
+            .collect();
+        added.sort();
+        added
+    }
+}
+
+/// Register the callback that emits new projects to the frontend
+pub fn set_project_added_listener(listener: impl Fn(&ClaudeProject) + Send + Sync + 'static) {
+    let _ = PROJECT_ADDED_LISTENER.set(Box::new(listener));
+}
+
+/// Start polling the projects folder of the last scan for new projects
+pub fn start_project_watcher() {
+    tauri::async_runtime::spawn(async {
+        loop {
+            tokio::time::sleep(PROJECT_POLL_INTERVAL).await;
+            let added = PROJECTS_WATCH
+                .lock()
+                .ok()
+                .and_then(|mut watch| watch.as_mut().map(ProjectsWatch::poll))
+                .unwrap_or_default();
+            for project_dir in added {
+                let project = read_project(&project_dir).await;
+                if let Some(listener) = PROJECT_ADDED_LISTENER.get() {
+                    listener(&project);
+                }
+            }
+        }
+    });
+}
+
+/// Project summary of one project folder, from file metadata only
+async fn read_project(project_dir: &Path) -> ClaudeProject {
+    let raw_project_name = file_name_string(project_dir).unwrap_or_default();
+    let mut session_count = 0;
+    let mut message_count = 0;
+    let mut last_modified = None;
+
+    for (_, metadata) in collect_jsonl_files_async(project_dir).await {
+        session_count += 1;
+
+        if let Ok(modified) = metadata.modified() {
+            if last_modified.is_none() || modified > last_modified.unwrap() {
+                last_modified = Some(modified);
+            }
+        }
+
+        // Estimate message count from file size - much faster
+        let estimated_messages = estimate_message_count_from_size(metadata.len());
+        message_count += estimated_messages;
+    }
+
+    let last_modified_str = last_modified
+        .map(|lm| {
+            let dt: DateTime<Utc> = lm.into();
+            dt.to_rfc3339()
+        })
+        .unwrap_or_else(|| Utc::now().to_rfc3339());
+
+    ClaudeProject {
+        name: extract_project_name(&raw_project_name),
+        path: display_path(project_dir),
+        session_count,
+        message_count,
+        last_modified: last_modified_str,
+    }
+}
 
 #[tauri::command]
-pub async fn get_claude_folder_path() -> Result<String, String> {
-    let home_dir =
-        dirs::home_dir().ok_or("HOME_DIRECTORY_NOT_FOUND:Could not determine home directory")?;
+pub async fn get_claude_folder_path() -> Result<String, AppError> {
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| AppError::not_found("Could not determine home directory"))?;
     let claude_path = home_dir.join(".claude");
 
     if !claude_path.exists() {
-        return Err(format!(
-            "CLAUDE_FOLDER_NOT_FOUND:Claude folder not found at {}",
+        return Err(AppError::not_found(format!(
+            "Claude folder not found at {}",
             claude_path.display()
-        ));
+        )));
     }
 
     if fs::read_dir(&claude_path).is_err() {
-        return Err(
-            "PERMISSION_DENIED:Cannot access Claude folder. Please check permissions.".to_string(),
-        );
+        return Err(AppError::permission_denied(
+            "Cannot access Claude folder. Please check permissions.",
+        ));
     }
 
-    Ok(claude_path.to_string_lossy().to_string())
+    Ok(display_path(&claude_path))
 }
 
 #[tauri::command]
-pub async fn validate_claude_folder(path: String) -> Result<bool, String> {
+pub async fn validate_claude_folder(path: String) -> Result<bool, AppError> {
     let path_buf = PathBuf::from(&path);
 
     if !path_buf.exists() {
@@ -50,66 +165,32 @@ pub async fn validate_claude_folder(path: String) -> Result<bool, String> {
 }
 
 #[tauri::command]
-pub async fn scan_projects(claude_path: String) -> Result<Vec<ClaudeProject>, String> {
+pub async fn scan_projects(claude_path: String) -> Result<Vec<ClaudeProject>, AppError> {
+    let _timer = OperationTimer::start("scan_projects");
     #[cfg(debug_assertions)]
     let start_time = std::time::Instant::now();
     let projects_path = PathBuf::from(&claude_path).join("projects");
 
-    if !projects_path.exists() {
+    // Only file metadata is read, with async IO so a slow (e.g. network)
+    // folder does not hold up other commands
+    let Ok(mut project_entries) = tokio::fs::read_dir(&projects_path).await else {
         return Ok(vec![]);
-    }
+    };
 
     let mut projects = Vec::new();
+    let mut known = HashSet::new();
 
-    for entry in WalkDir::new(&projects_path)
-        .min_depth(1)
-        .max_depth(1)
-        .into_iter()
-        .filter_map(std::result::Result::ok)
-        .filter(|e| e.file_type().is_dir())
-    {
-        let raw_project_name = entry.file_name().to_string_lossy().to_string();
-        let project_path = entry.path().to_string_lossy().to_string();
-        let project_name = extract_project_name(&raw_project_name);
-
-        let mut session_count = 0;
-        let mut message_count = 0;
-        let mut last_modified = None;
-
-        for jsonl_entry in WalkDir::new(entry.path())
-            .into_iter()
-            .filter_map(std::result::Result::ok)
-            .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
-        {
-            session_count += 1;
-
-            if let Ok(metadata) = jsonl_entry.metadata() {
-                if let Ok(modified) = metadata.modified() {
-                    if last_modified.is_none() || modified > last_modified.unwrap() {
-                        last_modified = Some(modified);
-                    }
-                }
-
-                // Estimate message count from file size - much faster
-                let estimated_messages = estimate_message_count_from_size(metadata.len());
-                message_count += estimated_messages;
-            }
+    while let Ok(Some(entry)) = project_entries.next_entry().await {
+        if !entry.file_type().await.is_ok_and(|t| t.is_dir()) {
+            continue;
         }
+        known.insert(entry.file_name());
+        projects.push(read_project(&entry.path()).await);
+    }
 
-        let last_modified_str = last_modified
-            .map(|lm| {
-                let dt: DateTime<Utc> = lm.into();
-                dt.to_rfc3339()
-            })
-            .unwrap_or_else(|| Utc::now().to_rfc3339());
-
-        projects.push(ClaudeProject {
-            name: project_name,
-            path: project_path,
-            session_count,
-            message_count,
-            last_modified: last_modified_str,
-        });
+    // Folders created from now on are reported through PROJECT_ADDED_EVENT
+    if let Ok(mut watch) = PROJECTS_WATCH.lock() {
+        *watch = Some(ProjectsWatch::new(projects_path, known));
     }
 
     projects.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
@@ -140,6 +221,35 @@ mod tests {
         file.write_all(content.as_bytes()).unwrap();
     }
 
+    #[test]
+    fn test_projects_watch_reports_new_folders_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let projects_path = temp_dir.path().join("projects");
+        fs::create_dir_all(projects_path.join("-Users-me-old")).unwrap();
+        let known = HashSet::from([OsString::from("-Users-me-old")]);
+        let mut watch = ProjectsWatch::new(projects_path.clone(), known);
+
+        assert!(watch.poll().is_empty());
+
+        fs::create_dir_all(projects_path.join("-Users-me-new")).unwrap();
+        fs::write(projects_path.join("notes.txt"), "").unwrap();
+        assert_eq!(watch.poll(), [projects_path.join("-Users-me-new")]);
+        assert!(watch.poll().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_read_project_of_new_folder() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("-Users-me-new");
+        fs::create_dir_all(&project_dir).unwrap();
+        create_test_jsonl_file(&project_dir, "s1.jsonl", "{}\n");
+
+        let project = read_project(&project_dir).await;
+
+        assert_eq!(project.name, "new");
+        assert_eq!(project.session_count, 1);
+    }
+
     // Test validate_claude_folder
     #[tokio::test]
     async fn test_validate_claude_folder_nonexistent() {
@@ -353,7 +463,7 @@ mod tests {
 
         let projects = result.unwrap();
         assert_eq!(projects.len(), 1);
-        // WalkDir should find sessions in subdirectories too
+        // Sessions in subdirectories are found too
         assert_eq!(projects[0].session_count, 2);
     }
 }
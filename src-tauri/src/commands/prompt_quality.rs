@@ -0,0 +1,563 @@
+//! Prompt length vs. response quality heuristics
+//!
+//! Splits sessions into turns (a user prompt plus everything until the next
+//! prompt) and correlates prompt length/structure with the friction that
+//! followed: failed tool calls, corrections and interrupts. The length stats
+//! set the same buckets against what the prompts cost: output tokens, time,
+//! tool calls and estimated cost.
+
+use crate::commands::session::is_genuine_user_text;
+use crate::commands::stats::{read_raw_log_entries, resolve_scope_session_files};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{
+    PromptLengthBucket, PromptLengthReport, PromptQualityGroup, PromptQualityReport, RawLogEntry,
+};
+use crate::pricing::estimate_cost_usd;
+use chrono::{DateTime, Utc};
+use rayon::prelude::*;
+use std::collections::HashSet;
+
+/// Word-count buckets: (label, inclusive upper bound; None = open-ended)
+const LENGTH_BUCKETS: [(&str, Option<usize>); 4] = [
+    ("1-10", Some(10)),
+    ("11-40", Some(40)),
+    ("41-150", Some(150)),
+    ("151+", None),
+];
+
+/// Prefix Claude Code writes when the user interrupts a response
+pub(crate) const INTERRUPT_PREFIX: &str = "[Request interrupted by user";
+
+/// Phrases at the start of a prompt that mark it as a correction of the previous turn
+const CORRECTION_PREFIXES: &[&str] = &[
+    "no,",
+    "no ",
+    "nope",
+    "wrong",
+    "undo",
+    "revert",
+    "that's not",
+    "that is not",
+];
+
+/// Phrases anywhere in a prompt that mark it as a correction of the previous turn
+const CORRECTION_PHRASES: &[&str] = &[
+    "try again",
+    "still not",
+    "still doesn't",
+    "still does not",
+    "still fails",
+    "still failing",
+    "doesn't work",
+    "does not work",
+    "didn't work",
+    "did not work",
+    "not what i",
+    "you broke",
+];
+
+/// Number of leading characters inspected for correction phrases
+const CORRECTION_SCAN_CHARS: usize = 120;
+
+/// One user prompt and the activity it triggered
+#[derive(Debug, Default, Clone)]
+struct PromptTurn {
+    words: usize,
+    has_code_block: bool,
+    has_list: bool,
+    tool_errors: usize,
+    tool_calls: usize,
+    assistant_messages: usize,
+    output_tokens: u64,
+    cost_usd: f64,
+    followed_by_retry: bool,
+    started: Option<DateTime<Utc>>, // Prompt time
+    ended: Option<DateTime<Utc>>,   // Last response or tool result of the turn
+}
+
+impl PromptTurn {
+    fn from_prompt(text: &str) -> Self {
+        Self {
+            words: text.split_whitespace().count(),
+            has_code_block: text.contains("```"),
+            has_list: text.lines().any(|line| {
+                let line = line.trim_start();
+                line.starts_with("- ")
+                    || line.starts_with("* ")
+                    || line.split_once(". ").is_some_and(|(n, _)| {
+                        !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())
+                    })
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn friction(&self) -> f64 {
+        self.tool_errors as f64 + if self.followed_by_retry { 1.0 } else { 0.0 }
+    }
+
+    fn duration_seconds(&self) -> f64 {
+        self.started.zip(self.ended).map_or(0.0, |(start, end)| {
+            (end - start).num_milliseconds().max(0) as f64 / 1000.0
+        })
+    }
+}
+
+/// Whether a prompt reads like a correction of the previous answer
+fn is_correction(text: &str) -> bool {
+    let head: String = text
+        .trim()
+        .chars()
+        .take(CORRECTION_SCAN_CHARS)
+        .collect::<String>()
+        .to_lowercase();
+    CORRECTION_PREFIXES.iter().any(|p| head.starts_with(p))
+        || CORRECTION_PHRASES.iter().any(|p| head.contains(p))
+}
+
+/// Prompt text of a user entry, if it is a genuine prompt (not a tool result)
+pub(crate) fn prompt_text(content: &serde_json::Value) -> Option<String> {
+    match content {
+        serde_json::Value::String(text) => Some(text.clone()),
+        serde_json::Value::Array(items) => {
+            let mut texts = Vec::new();
+            for item in items {
+                match item.get("type").and_then(|v| v.as_str()) {
+                    Some("tool_result") => return None,
+                    Some("text") => {
+                        if let Some(text) = item.get("text").and_then(|v| v.as_str()) {
+                            texts.push(text);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            (!texts.is_empty()).then(|| texts.join("\n"))
+        }
+        _ => None,
+    }
+}
+
+/// Split a session's entries (in file order) into prompt turns
+///
+/// Usage of a response streamed over several entries counts once.
+fn collect_turns(entries: impl IntoIterator<Item = RawLogEntry>) -> Vec<PromptTurn> {
+    let mut turns: Vec<PromptTurn> = Vec::new();
+    let mut counted_responses: HashSet<String> = HashSet::new();
+
+    for entry in entries {
+        if entry.is_sidechain == Some(true) || entry.is_meta == Some(true) {
+            continue;
+        }
+        let Some(message) = entry.message else {
+            continue;
+        };
+        let timestamp = entry
+            .timestamp
+            .as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc));
+
+        match entry.message_type.as_str() {
+            "user" => {
+                if let serde_json::Value::Array(items) = &message.content {
+                    let errors = items
+                        .iter()
+                        .filter(|item| {
+                            is_tool_result(item)
+                                && item.get("is_error").and_then(serde_json::Value::as_bool)
+                                    == Some(true)
+                        })
+                        .count();
+                    if let Some(turn) = turns.last_mut() {
+                        turn.tool_errors += errors;
+                        if items.iter().any(is_tool_result) {
+                            turn.ended = timestamp.or(turn.ended);
+                        }
+                    }
+                }
+
+                let Some(text) = prompt_text(&message.content) else {
+                    continue;
+                };
+                if text.trim_start().starts_with(INTERRUPT_PREFIX) {
+                    if let Some(turn) = turns.last_mut() {
+                        turn.followed_by_retry = true;
+                    }
+                    continue;
+                }
+                if !is_genuine_user_text(&text) {
+                    continue;
+                }
+
+                if is_correction(&text) {
+                    if let Some(turn) = turns.last_mut() {
+                        turn.followed_by_retry = true;
+                    }
+                }
+                turns.push(PromptTurn {
+                    started: timestamp,
+                    ..PromptTurn::from_prompt(&text)
+                });
+            }
+            "assistant" => {
+                if let Some(turn) = turns.last_mut() {
+                    turn.assistant_messages += 1;
+                    turn.ended = timestamp.or(turn.ended);
+                    if let serde_json::Value::Array(items) = &message.content {
+                        turn.tool_calls += items
+                            .iter()
+                            .filter(|item| {
+                                item.get("type").and_then(|v| v.as_str()) == Some("tool_use")
+                            })
+                            .count();
+                    }
+                    let first_entry = message
+                        .id
+                        .as_ref()
+                        .map_or(true, |id| counted_responses.insert(id.clone()));
+                    if let Some(usage) = message.usage.as_ref().filter(|_| first_entry) {
+                        turn.output_tokens += usage.output_tokens.map_or(0, u64::from);
+                        turn.cost_usd += estimate_cost_usd(
+                            message.model.as_deref(),
+                            entry.cwd.as_deref(),
+                            usage,
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    turns
+}
+
+fn is_tool_result(item: &serde_json::Value) -> bool {
+    item.get("type").and_then(|v| v.as_str()) == Some("tool_result")
+}
+
+fn summarize_group(label: &str, turns: &[&PromptTurn]) -> PromptQualityGroup {
+    let count = turns.len();
+    let avg = |value: f64| if count > 0 { value / count as f64 } else { 0.0 };
+
+    PromptQualityGroup {
+        label: label.to_string(),
+        prompt_count: count,
+        avg_words: avg(turns.iter().map(|t| t.words as f64).sum()),
+        avg_tool_errors: avg(turns.iter().map(|t| t.tool_errors as f64).sum()),
+        error_turn_rate: avg(turns.iter().filter(|t| t.tool_errors > 0).count() as f64),
+        retry_rate: avg(turns.iter().filter(|t| t.followed_by_retry).count() as f64),
+        avg_assistant_messages: avg(turns.iter().map(|t| t.assistant_messages as f64).sum()),
+        avg_output_tokens: avg(turns.iter().map(|t| t.output_tokens as f64).sum()),
+    }
+}
+
+/// Pearson correlation coefficient, None if either side has no variance
+fn pearson(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    let n = xs.len();
+    if n < 2 || n != ys.len() {
+        return None;
+    }
+    let mean_x = xs.iter().sum::<f64>() / n as f64;
+    let mean_y = ys.iter().sum::<f64>() / n as f64;
+
+    let mut covariance = 0.0;
+    let mut variance_x = 0.0;
+    let mut variance_y = 0.0;
+    for (x, y) in xs.iter().zip(ys) {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance_x += (x - mean_x).powi(2);
+        variance_y += (y - mean_y).powi(2);
+    }
+
+    if variance_x <= f64::EPSILON || variance_y <= f64::EPSILON {
+        return None;
+    }
+    Some(covariance / (variance_x.sqrt() * variance_y.sqrt()))
+}
+
+/// Turns of each `LENGTH_BUCKETS` bucket
+fn length_buckets(turns: &[PromptTurn]) -> Vec<(&'static str, Vec<&PromptTurn>)> {
+    let mut lower_bound = 0;
+    LENGTH_BUCKETS
+        .iter()
+        .map(|&(label, upper_bound)| {
+            let in_bucket: Vec<&PromptTurn> = turns
+                .iter()
+                .filter(|t| t.words > lower_bound && !upper_bound.is_some_and(|max| t.words > max))
+                .collect();
+            lower_bound = upper_bound.unwrap_or(usize::MAX);
+            (label, in_bucket)
+        })
+        .collect()
+}
+
+fn build_report(scope: String, session_count: usize, turns: &[PromptTurn]) -> PromptQualityReport {
+    let length_buckets = length_buckets(turns)
+        .into_iter()
+        .map(|(label, in_bucket)| summarize_group(label, &in_bucket))
+        .collect();
+
+    let partition = |label: &str, predicate: fn(&PromptTurn) -> bool| {
+        let (with, without): (Vec<&PromptTurn>, Vec<&PromptTurn>) =
+            turns.iter().partition(|t| predicate(t));
+        [
+            summarize_group(&format!("with_{label}"), &with),
+            summarize_group(&format!("without_{label}"), &without),
+        ]
+    };
+    let structure = partition("code_block", |t| t.has_code_block)
+        .into_iter()
+        .chain(partition("list", |t| t.has_list))
+        .collect();
+
+    let words: Vec<f64> = turns.iter().map(|t| t.words as f64).collect();
+    let friction: Vec<f64> = turns.iter().map(PromptTurn::friction).collect();
+
+    PromptQualityReport {
+        scope,
+        session_count,
+        prompt_count: turns.len(),
+        length_buckets,
+        structure,
+        length_friction_correlation: pearson(&words, &friction),
+    }
+}
+
+fn length_bucket(label: &str, turns: &[&PromptTurn]) -> PromptLengthBucket {
+    let count = turns.len();
+    let avg = |value: f64| if count > 0 { value / count as f64 } else { 0.0 };
+
+    PromptLengthBucket {
+        label: label.to_string(),
+        prompt_count: count,
+        avg_words: avg(turns.iter().map(|t| t.words as f64).sum()),
+        avg_output_tokens: avg(turns.iter().map(|t| t.output_tokens as f64).sum()),
+        avg_duration_seconds: avg(turns.iter().map(|t| t.duration_seconds()).sum()),
+        avg_tool_calls: avg(turns.iter().map(|t| t.tool_calls as f64).sum()),
+        avg_cost_usd: avg(turns.iter().map(|t| t.cost_usd).sum()),
+        retry_rate: avg(turns.iter().filter(|t| t.followed_by_retry).count() as f64),
+    }
+}
+
+fn build_length_report(
+    scope: String,
+    session_count: usize,
+    turns: &[PromptTurn],
+) -> PromptLengthReport {
+    let words: Vec<f64> = turns.iter().map(|t| t.words as f64).collect();
+    let correlation = |value: fn(&PromptTurn) -> f64| {
+        pearson(&words, &turns.iter().map(value).collect::<Vec<_>>())
+    };
+
+    PromptLengthReport {
+        scope,
+        session_count,
+        prompt_count: turns.len(),
+        buckets: length_buckets(turns)
+            .into_iter()
+            .map(|(label, in_bucket)| length_bucket(label, &in_bucket))
+            .collect(),
+        output_tokens_correlation: correlation(|t| t.output_tokens as f64),
+        duration_correlation: correlation(PromptTurn::duration_seconds),
+        tool_calls_correlation: correlation(|t| t.tool_calls as f64),
+        cost_correlation: correlation(|t| t.cost_usd),
+    }
+}
+
+/// Correlate prompt length/structure with downstream tool errors and retries
+///
+/// `scope` is "session", "project" or "global", as for `get_token_histograms`.
+#[tauri::command]
+pub async fn get_prompt_quality_report(
+    scope: String,
+    path: String,
+) -> Result<PromptQualityReport, AppError> {
+    let _timer = OperationTimer::start("get_prompt_quality_report");
+    let session_files = resolve_scope_session_files(&scope, &path)?;
+
+    let turns: Vec<PromptTurn> = session_files
+        .par_iter()
+        .flat_map_iter(|path| collect_turns(read_raw_log_entries(path)))
+        .collect();
+
+    Ok(build_report(scope, session_files.len(), &turns))
+}
+
+/// Average output tokens, duration, tool calls and cost of prompts by
+/// length, with the correlation of each with the prompt's word count
+///
+/// `scope` is "session", "project" or "global", as for `get_token_histograms`.
+#[tauri::command]
+pub async fn get_prompt_length_stats(
+    scope: String,
+    path: String,
+) -> Result<PromptLengthReport, AppError> {
+    let _timer = OperationTimer::start("get_prompt_length_stats");
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let session_files = resolve_scope_session_files(&scope, &path)?;
+        let turns: Vec<PromptTurn> = session_files
+            .par_iter()
+            .flat_map_iter(|path| collect_turns(read_raw_log_entries(path)))
+            .collect();
+        Ok(build_length_report(scope, session_files.len(), &turns))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::raw_entry;
+    use serde_json::json;
+
+    fn user(content: serde_json::Value) -> RawLogEntry {
+        raw_entry(json!({"type": "user", "message": {"role": "user", "content": content}}))
+    }
+
+    fn assistant(output_tokens: u32) -> RawLogEntry {
+        raw_entry(json!({
+            "type": "assistant",
+            "message": {
+                "role": "assistant",
+                "content": [{"type": "text", "text": "ok"}],
+                "usage": {"output_tokens": output_tokens}
+            }
+        }))
+    }
+
+    fn tool_result(is_error: bool) -> RawLogEntry {
+        user(
+            json!([{"type": "tool_result", "tool_use_id": "t1", "content": "x", "is_error": is_error}]),
+        )
+    }
+
+    #[test]
+    fn test_collect_turns_counts_errors_and_retries() {
+        let turns = collect_turns(vec![
+            user(json!("fix it")),
+            assistant(10),
+            tool_result(true),
+            assistant(20),
+            user(json!("That still doesn't work, try again")),
+            assistant(5),
+            tool_result(false),
+            user(json!([{"type": "text", "text": "[Request interrupted by user]"}])),
+        ]);
+
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].words, 2);
+        assert_eq!(turns[0].tool_errors, 1);
+        assert_eq!(turns[0].assistant_messages, 2);
+        assert_eq!(turns[0].output_tokens, 30);
+        assert!(turns[0].followed_by_retry);
+        assert_eq!(turns[1].tool_errors, 0);
+        assert!(turns[1].followed_by_retry); // interrupted
+    }
+
+    #[test]
+    fn test_prompt_structure_detection() {
+        let turn =
+            PromptTurn::from_prompt("Do this:\n1. parse\n2. render\n```rust\nfn main() {}\n```");
+        assert!(turn.has_list);
+        assert!(turn.has_code_block);
+
+        let turn = PromptTurn::from_prompt("Version 1.2 please");
+        assert!(!turn.has_list);
+    }
+
+    #[test]
+    fn test_is_correction() {
+        assert!(is_correction("No, use the other file"));
+        assert!(is_correction("It still fails with the same error"));
+        assert!(!is_correction("Now add tests for the parser"));
+    }
+
+    #[test]
+    fn test_build_report_buckets_and_correlation() {
+        let terse = PromptTurn {
+            words: 3,
+            tool_errors: 2,
+            followed_by_retry: true,
+            ..Default::default()
+        };
+        let long = PromptTurn {
+            words: 200,
+            ..Default::default()
+        };
+
+        let report = build_report("project".to_string(), 1, &[terse.clone(), terse, long]);
+
+        assert_eq!(report.prompt_count, 3);
+        assert_eq!(report.length_buckets[0].prompt_count, 2);
+        assert!((report.length_buckets[0].retry_rate - 1.0).abs() < f64::EPSILON);
+        assert_eq!(report.length_buckets[3].prompt_count, 1);
+        assert!(report.length_friction_correlation.unwrap() < 0.0);
+        assert_eq!(report.structure.len(), 4);
+    }
+
+    #[test]
+    fn test_length_stats_count_time_tools_and_cost() {
+        let at = |mut entry: RawLogEntry, time: &str| {
+            entry.timestamp = Some(time.to_string());
+            entry
+        };
+        let response = |id: &str, tools: usize, time: &str| {
+            let mut content = vec![json!({"type": "text", "text": "ok"})];
+            content.extend(
+                (0..tools)
+                    .map(|_| json!({"type": "tool_use", "id": "t", "name": "Read", "input": {}})),
+            );
+            at(
+                raw_entry(json!({
+                    "type": "assistant",
+                    "message": {
+                        "id": id,
+                        "role": "assistant",
+                        "model": "claude-sonnet-4-20250514",
+                        "content": content,
+                        "usage": {"input_tokens": 0, "output_tokens": 100}
+                    }
+                })),
+                time,
+            )
+        };
+        let long_prompt = "word ".repeat(200);
+        let turns = collect_turns(vec![
+            at(user(json!("fix it")), "2025-03-01T09:00:00Z"),
+            response("r1", 0, "2025-03-01T09:00:10Z"),
+            at(user(json!(long_prompt)), "2025-03-01T09:01:00Z"),
+            // One response streamed over two entries
+            response("r2", 1, "2025-03-01T09:01:30Z"),
+            response("r2", 1, "2025-03-01T09:01:31Z"),
+            at(tool_result(false), "2025-03-01T09:02:00Z"),
+            response("r3", 0, "2025-03-01T09:03:00Z"),
+        ]);
+
+        assert!((turns[0].duration_seconds() - 10.0).abs() < 1e-9);
+        assert_eq!(turns[1].output_tokens, 200);
+        assert_eq!(turns[1].tool_calls, 2);
+        assert!((turns[1].duration_seconds() - 120.0).abs() < 1e-9);
+        assert!(turns[1].cost_usd > turns[0].cost_usd);
+
+        let report = build_length_report("session".to_string(), 1, &turns);
+        assert_eq!(report.prompt_count, 2);
+        assert_eq!(report.buckets[0].prompt_count, 1);
+        assert!((report.buckets[0].avg_output_tokens - 100.0).abs() < 1e-9);
+        assert!((report.buckets[3].avg_tool_calls - 2.0).abs() < 1e-9);
+        assert!((report.buckets[3].avg_duration_seconds - 120.0).abs() < 1e-9);
+        assert!(report.output_tokens_correlation.unwrap() > 0.99);
+        assert!(report.cost_correlation.unwrap() > 0.99);
+    }
+
+    #[test]
+    fn test_pearson_requires_variance() {
+        assert_eq!(pearson(&[1.0, 1.0], &[2.0, 3.0]), None);
+        let r = pearson(&[1.0, 2.0, 3.0], &[2.0, 4.0, 6.0]).unwrap();
+        assert!((r - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,378 @@
+//! Retry loop detection
+//!
+//! Finds runs of consecutive calls to the same tool with near-identical input
+//! that keep failing, and estimates the tokens burned on the repeats.
+
+use crate::commands::stats::read_raw_log_entries;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{RawLogEntry, RetryLoop, SessionRetryLoops, TokenUsage};
+use crate::pricing::estimate_cost_usd;
+use crate::utils::resolve_session_file;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+/// Minimum number of consecutive similar calls that form a loop
+const MIN_LOOP_ATTEMPTS: usize = 3;
+
+/// Minimum number of failed calls within a loop
+const MIN_LOOP_FAILURES: usize = 2;
+
+/// Jaccard similarity of input tokens above which two calls count as "the same"
+const INPUT_SIMILARITY_THRESHOLD: f64 = 0.7;
+
+/// Maximum characters kept for sample inputs and error messages
+const MAX_SAMPLE_CHARS: usize = 200;
+
+/// A single tool invocation and its outcome
+#[derive(Debug, Clone)]
+pub(crate) struct ToolCall {
+    pub name: String,
+    pub input: serde_json::Value,
+    pub message_uuid: String,
+    pub timestamp: String,
+    pub tokens: u64,   // Share of the issuing response's tokens
+    pub cost_usd: f64, // Share of the issuing response's estimated cost
+    pub failed: bool,
+    pub error: Option<String>,
+}
+
+/// All tokens of a response (input, output and cache)
+pub(crate) fn total_tokens(usage: &TokenUsage) -> u64 {
+    u64::from(usage.input_tokens.unwrap_or(0))
+        + u64::from(usage.output_tokens.unwrap_or(0))
+        + u64::from(usage.cache_creation_input_tokens.unwrap_or(0))
+        + u64::from(usage.cache_read_input_tokens.unwrap_or(0))
+}
+
+pub(crate) fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() > max_chars {
+        let truncated: String = text.chars().take(max_chars).collect();
+        format!("{truncated}...")
+    } else {
+        text.to_string()
+    }
+}
+
+/// Text of a `tool_result` block (string or list of text blocks)
+pub(crate) fn tool_result_text(block: &serde_json::Value) -> Option<String> {
+    match block.get("content")? {
+        serde_json::Value::String(text) => Some(text.clone()),
+        serde_json::Value::Array(items) => {
+            let texts: Vec<&str> = items
+                .iter()
+                .filter_map(|item| item.get("text").and_then(|v| v.as_str()))
+                .collect();
+            (!texts.is_empty()).then(|| texts.join("\n"))
+        }
+        _ => None,
+    }
+}
+
+/// Extract tool calls of the main conversation, in order, with their results
+///
+/// Claude Code writes one line per content block of a response, each carrying
+/// the full response usage, so tokens are counted once per response `id`.
+pub(crate) fn extract_tool_calls(entries: &[RawLogEntry]) -> Vec<ToolCall> {
+    let mut calls: Vec<ToolCall> = Vec::new();
+    let mut call_index: HashMap<String, usize> = HashMap::new();
+    let mut counted_responses: HashSet<String> = HashSet::new();
+
+    for (line_num, entry) in entries.iter().enumerate() {
+        if entry.is_sidechain == Some(true) {
+            continue;
+        }
+        let Some(message) = &entry.message else {
+            continue;
+        };
+        let Some(items) = message.content.as_array() else {
+            continue;
+        };
+
+        match entry.message_type.as_str() {
+            "assistant" => {
+                let tool_uses: Vec<&serde_json::Value> = items
+                    .iter()
+                    .filter(|item| item.get("type").and_then(|v| v.as_str()) == Some("tool_use"))
+                    .collect();
+                if tool_uses.is_empty() {
+                    continue;
+                }
+
+                let first_seen = match &message.id {
+                    Some(id) => counted_responses.insert(id.clone()),
+                    None => true,
+                };
+                let (response_tokens, response_cost) = match (&message.usage, first_seen) {
+                    (Some(usage), true) => (
+                        total_tokens(usage),
+                        estimate_cost_usd(message.model.as_deref(), entry.cwd.as_deref(), usage),
+                    ),
+                    _ => (0, 0.0),
+                };
+                let tokens_per_call = response_tokens / tool_uses.len() as u64;
+                let cost_per_call = response_cost / tool_uses.len() as f64;
+
+                for tool_use in tool_uses {
+                    if let Some(id) = tool_use.get("id").and_then(|v| v.as_str()) {
+                        call_index.insert(id.to_string(), calls.len());
+                    }
+                    calls.push(ToolCall {
+                        name: tool_use
+                            .get("name")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("unknown")
+                            .to_string(),
+                        input: tool_use
+                            .get("input")
+                            .cloned()
+                            .unwrap_or(serde_json::Value::Null),
+                        message_uuid: entry
+                            .uuid
+                            .clone()
+                            .unwrap_or_else(|| format!("line-{}", line_num + 1)),
+                        timestamp: entry.timestamp.clone().unwrap_or_default(),
+                        tokens: tokens_per_call,
+                        cost_usd: cost_per_call,
+                        failed: false,
+                        error: None,
+                    });
+                }
+            }
+            "user" => {
+                for block in items {
+                    if block.get("type").and_then(|v| v.as_str()) != Some("tool_result") {
+                        continue;
+                    }
+                    let is_error = block
+                        .get("is_error")
+                        .and_then(serde_json::Value::as_bool)
+                        .unwrap_or(false);
+                    let call = block
+                        .get("tool_use_id")
+                        .and_then(|v| v.as_str())
+                        .and_then(|id| call_index.get(id));
+                    if let (true, Some(&idx)) = (is_error, call) {
+                        calls[idx].failed = true;
+                        calls[idx].error =
+                            tool_result_text(block).map(|e| truncate_chars(&e, MAX_SAMPLE_CHARS));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    calls
+}
+
+/// Word tokens of a tool input, used for similarity comparison
+fn input_tokens(input: &serde_json::Value) -> HashSet<String> {
+    input
+        .to_string()
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn is_similar_input(a: &HashSet<String>, b: &HashSet<String>) -> bool {
+    if a.is_empty() && b.is_empty() {
+        return true;
+    }
+    let intersection = a.intersection(b).count();
+    let union = a.union(b).count();
+    union > 0 && intersection as f64 / union as f64 >= INPUT_SIMILARITY_THRESHOLD
+}
+
+pub(crate) fn build_loop(run: &[ToolCall]) -> RetryLoop {
+    let first = &run[0];
+    let last = &run[run.len() - 1];
+
+    RetryLoop {
+        tool_name: first.name.clone(),
+        attempts: run.len(),
+        failed_attempts: run.iter().filter(|c| c.failed).count(),
+        first_message_uuid: first.message_uuid.clone(),
+        last_message_uuid: last.message_uuid.clone(),
+        start_time: first.timestamp.clone(),
+        end_time: last.timestamp.clone(),
+        wasted_tokens: run[1..].iter().map(|c| c.tokens).sum(),
+        wasted_cost_usd: run[1..].iter().map(|c| c.cost_usd).sum(),
+        sample_input: truncate_chars(&first.input.to_string(), MAX_SAMPLE_CHARS),
+        last_error: run.iter().rev().find_map(|c| c.error.clone()),
+    }
+}
+
+/// Index ranges of consecutive similar calls that look like failing loops
+pub(crate) fn find_loop_runs(calls: &[ToolCall]) -> Vec<Range<usize>> {
+    let tokens: Vec<HashSet<String>> = calls.iter().map(|c| input_tokens(&c.input)).collect();
+    let mut runs = Vec::new();
+    let mut run_start = 0;
+
+    for idx in 1..=calls.len() {
+        let continues_run = idx < calls.len()
+            && calls[idx].name == calls[idx - 1].name
+            && is_similar_input(&tokens[idx], &tokens[idx - 1]);
+        if continues_run {
+            continue;
+        }
+
+        let run = &calls[run_start..idx];
+        if run.len() >= MIN_LOOP_ATTEMPTS
+            && run.iter().filter(|c| c.failed).count() >= MIN_LOOP_FAILURES
+        {
+            runs.push(run_start..idx);
+        }
+        run_start = idx;
+    }
+
+    runs
+}
+
+/// Group consecutive similar calls and keep the runs that look like failing loops
+pub(crate) fn detect_retry_loops(calls: &[ToolCall]) -> Vec<RetryLoop> {
+    find_loop_runs(calls)
+        .into_iter()
+        .map(|run| build_loop(&calls[run]))
+        .collect()
+}
+
+/// Detect repetitive failing tool-call loops in a session
+#[tauri::command]
+pub async fn get_retry_loops(
+    session_id: String,
+    project_path: String,
+) -> Result<SessionRetryLoops, AppError> {
+    let _timer = OperationTimer::start("get_retry_loops");
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+
+    let entries = tauri::async_runtime::spawn_blocking(move || read_raw_log_entries(&session_path))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?;
+    let loops = detect_retry_loops(&extract_tool_calls(&entries));
+
+    Ok(SessionRetryLoops {
+        session_id,
+        total_loop_attempts: loops.iter().map(|l| l.attempts).sum(),
+        total_wasted_tokens: loops.iter().map(|l| l.wasted_tokens).sum(),
+        total_wasted_cost_usd: loops.iter().map(|l| l.wasted_cost_usd).sum(),
+        loops,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::raw_entry;
+    use serde_json::json;
+
+    fn tool_call(n: usize, name: &str, input: serde_json::Value, tokens: u32) -> RawLogEntry {
+        raw_entry(json!({
+            "uuid": format!("a{n}"),
+            "timestamp": format!("2025-01-01T00:00:0{n}Z"),
+            "type": "assistant",
+            "message": {
+                "id": format!("msg_{n}"),
+                "role": "assistant",
+                "content": [{"type": "tool_use", "id": format!("toolu_{n}"), "name": name, "input": input}],
+                "usage": {"input_tokens": tokens, "output_tokens": 0}
+            }
+        }))
+    }
+
+    fn tool_result(n: usize, is_error: bool) -> RawLogEntry {
+        raw_entry(json!({
+            "uuid": format!("u{n}"),
+            "type": "user",
+            "message": {
+                "role": "user",
+                "content": [{
+                    "type": "tool_result",
+                    "tool_use_id": format!("toolu_{n}"),
+                    "content": format!("error {n}"),
+                    "is_error": is_error
+                }]
+            }
+        }))
+    }
+
+    #[test]
+    fn test_detects_failing_loop() {
+        let entries = vec![
+            tool_call(1, "Bash", json!({"command": "cargo test --lib"}), 100),
+            tool_result(1, true),
+            tool_call(2, "Bash", json!({"command": "cargo test --lib"}), 200),
+            tool_result(2, true),
+            tool_call(3, "Bash", json!({"command": "cargo test --lib"}), 300),
+            tool_result(3, false),
+            tool_call(4, "Read", json!({"file_path": "/src/lib.rs"}), 50),
+            tool_result(4, false),
+        ];
+
+        let loops = detect_retry_loops(&extract_tool_calls(&entries));
+
+        assert_eq!(loops.len(), 1);
+        let retry_loop = &loops[0];
+        assert_eq!(retry_loop.tool_name, "Bash");
+        assert_eq!(retry_loop.attempts, 3);
+        assert_eq!(retry_loop.failed_attempts, 2);
+        assert_eq!(retry_loop.first_message_uuid, "a1");
+        assert_eq!(retry_loop.last_message_uuid, "a3");
+        assert_eq!(retry_loop.wasted_tokens, 500);
+        assert_eq!(retry_loop.last_error.as_deref(), Some("error 2"));
+    }
+
+    #[test]
+    fn test_successful_repeats_are_not_loops() {
+        let entries = vec![
+            tool_call(1, "Read", json!({"file_path": "/a.rs"}), 10),
+            tool_result(1, false),
+            tool_call(2, "Read", json!({"file_path": "/a.rs"}), 10),
+            tool_result(2, false),
+            tool_call(3, "Read", json!({"file_path": "/a.rs"}), 10),
+            tool_result(3, true),
+        ];
+
+        assert!(detect_retry_loops(&extract_tool_calls(&entries)).is_empty());
+    }
+
+    #[test]
+    fn test_dissimilar_inputs_break_loop() {
+        let entries = vec![
+            tool_call(1, "Bash", json!({"command": "npm run build"}), 10),
+            tool_result(1, true),
+            tool_call(2, "Bash", json!({"command": "ls -la /tmp/output/dir"}), 10),
+            tool_result(2, true),
+            tool_call(3, "Bash", json!({"command": "npm run build"}), 10),
+            tool_result(3, true),
+        ];
+
+        assert!(detect_retry_loops(&extract_tool_calls(&entries)).is_empty());
+    }
+
+    #[test]
+    fn test_response_tokens_counted_once_per_id() {
+        let mut duplicate = tool_call(2, "Bash", json!({"command": "x"}), 999);
+        duplicate.message.as_mut().unwrap().id = Some("msg_1".to_string());
+        let entries = vec![
+            tool_call(1, "Bash", json!({"command": "x"}), 100),
+            duplicate,
+        ];
+
+        let calls = extract_tool_calls(&entries);
+        assert_eq!(calls[0].tokens, 100);
+        assert_eq!(calls[1].tokens, 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_retry_loops_unknown_session() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let result = get_retry_loops(
+            "missing".to_string(),
+            temp.path().to_string_lossy().to_string(),
+        )
+        .await;
+        assert!(result.is_err());
+    }
+}
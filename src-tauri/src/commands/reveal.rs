@@ -0,0 +1,127 @@
+//! Reveal files and folders in the platform file manager
+//!
+//! Sessions may have been recorded under WSL or on another machine, so paths
+//! are translated between `/mnt/<drive>/…` and `<drive>:\…` forms before
+//! giving up.
+
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::utils::{display_path, long_path};
+use std::path::PathBuf;
+
+/// Whether the app runs inside Windows Subsystem for Linux
+fn running_in_wsl() -> bool {
+    std::env::var_os("WSL_DISTRO_NAME").is_some()
+}
+
+/// Drive letter and remainder of a Windows path (`C:\Users` → `c`, `Users`)
+fn split_windows_drive(path: &str) -> Option<(char, &str)> {
+    let mut chars = path.chars();
+    let drive = chars.next().filter(char::is_ascii_alphabetic)?;
+    let rest = chars.as_str().strip_prefix(':')?;
+    if !rest.is_empty() && !rest.starts_with(['\\', '/']) {
+        return None;
+    }
+    Some((
+        drive.to_ascii_lowercase(),
+        rest.trim_start_matches(['\\', '/']),
+    ))
+}
+
+/// Drive letter and remainder of a WSL mount path (`/mnt/c/Users` → `c`, `Users`)
+fn split_wsl_mount(path: &str) -> Option<(char, &str)> {
+    let rest = path.strip_prefix("/mnt/")?;
+    let mut chars = rest.chars();
+    let drive = chars.next().filter(char::is_ascii_alphabetic)?;
+    let rest = chars.as_str();
+    if !rest.is_empty() && !rest.starts_with('/') {
+        return None;
+    }
+    Some((drive, rest.trim_start_matches('/')))
+}
+
+/// Paths a recorded path may correspond to on this machine, most likely first
+fn local_path_candidates(path: &str, windows: bool, wsl: bool) -> Vec<String> {
+    let mut candidates = vec![path.to_string()];
+    if windows {
+        if let Some((drive, rest)) = split_wsl_mount(path) {
+            candidates.push(format!(
+                "{}:\\{}",
+                drive.to_ascii_uppercase(),
+                rest.replace('/', "\\")
+            ));
+        }
+    }
+    if wsl {
+        if let Some((drive, rest)) = split_windows_drive(path) {
+            candidates.push(format!("/mnt/{drive}/{}", rest.replace('\\', "/")));
+        }
+    }
+    candidates
+}
+
+/// Reveal a session file, project folder or edited file in the file manager
+///
+/// Files are selected in their parent folder. Paths recorded under WSL or
+/// Windows are translated for the current platform. Returns the path that
+/// was revealed.
+#[tauri::command]
+pub async fn reveal_path(path: String) -> Result<String, AppError> {
+    let _timer = OperationTimer::start("reveal_path");
+
+    let path = path.trim();
+    if path.is_empty() || path.contains('\0') {
+        return Err(AppError::invalid_input(format!("Invalid path: {path}")));
+    }
+
+    let local_path = local_path_candidates(path, cfg!(target_os = "windows"), running_in_wsl())
+        .into_iter()
+        .map(PathBuf::from)
+        .find(|candidate| candidate.is_absolute() && long_path(candidate).exists())
+        .ok_or_else(|| {
+            format!("Path not found on this machine (it may have been recorded elsewhere): {path}")
+        })?;
+
+    tauri_plugin_opener::reveal_item_in_dir(&local_path)
+        .map_err(|e| format!("Failed to reveal {}: {e}", display_path(&local_path)))?;
+    Ok(display_path(&local_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_local_path_candidates_translate_drives() {
+        assert_eq!(
+            local_path_candidates("/mnt/c/Users/me/app", true, false),
+            vec!["/mnt/c/Users/me/app", r"C:\Users\me\app"]
+        );
+        assert_eq!(
+            local_path_candidates(r"D:\work\app", false, true),
+            vec![r"D:\work\app", "/mnt/d/work/app"]
+        );
+        assert_eq!(
+            local_path_candidates("/mnt/data/app", true, true),
+            vec!["/mnt/data/app"]
+        );
+        assert_eq!(
+            local_path_candidates("/home/me/app", false, false),
+            vec!["/home/me/app"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reveal_path_rejects_missing_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = temp_dir.path().join("gone.jsonl");
+
+        let err = reveal_path(missing.to_string_lossy().to_string())
+            .await
+            .unwrap_err();
+        assert!(err.message().contains("recorded elsewhere"));
+        assert!(reveal_path("relative/path".to_string()).await.is_err());
+        assert!(reveal_path("  ".to_string()).await.is_err());
+    }
+}
@@ -0,0 +1,278 @@
+//! Aider chat histories
+//!
+//! Aider appends every chat to `.aider.chat.history.md` in the project it
+//! runs in. Each run starts with a `# aider chat started at <time>` heading
+//! and becomes one session. Within it, `#### ` lines are the user's prompts,
+//! `> ` lines are aider's own output (kept as system messages) and all other
+//! lines are the model's replies. The file has no per-message times, so every
+//! message carries the start time of its run.
+
+use super::sources::{chat_conversation, chat_message, HistorySource, SourceConversation};
+use crate::models::ClaudeMessage;
+use crate::utils::{file_name_string, long_path};
+use chrono::{Local, NaiveDateTime, TimeZone, Utc};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::{DirEntry, WalkDir};
+
+/// `ClaudeSession::source` of Aider chats
+pub const AIDER_SOURCE: &str = "aider";
+
+/// File Aider writes its chat history to
+const HISTORY_FILE: &str = ".aider.chat.history.md";
+
+/// Heading that starts each run
+const RUN_HEADING: &str = "# aider chat started at ";
+
+/// Folder depth searched for history files
+const MAX_SCAN_DEPTH: usize = 6;
+
+/// Folders never searched for history files
+const SKIPPED_FOLDERS: [&str; 3] = ["node_modules", "target", "venv"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Block {
+    User,
+    Assistant,
+    Output,
+}
+
+/// A run of a history file: its start time and lines
+struct Run<'a> {
+    started_at: &'a str,
+    lines: Vec<&'a str>,
+}
+
+fn is_searched(entry: &DirEntry) -> bool {
+    let name = entry.file_name().to_string_lossy();
+    entry.depth() == 0
+        || !entry.file_type().is_dir()
+        || !(name.starts_with('.') || SKIPPED_FOLDERS.contains(&name.as_ref()))
+}
+
+/// History files under `root`
+fn history_files(root: &Path) -> Vec<PathBuf> {
+    WalkDir::new(root)
+        .max_depth(MAX_SCAN_DEPTH)
+        .into_iter()
+        .filter_entry(is_searched)
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.file_type().is_file() && e.file_name() == HISTORY_FILE)
+        .map(|e| e.path().to_path_buf())
+        .collect()
+}
+
+/// Runs of a history file; lines before the first heading are ignored
+fn split_runs(content: &str) -> Vec<Run<'_>> {
+    let mut runs: Vec<Run> = Vec::new();
+    for line in content.lines() {
+        if let Some(started_at) = line.strip_prefix(RUN_HEADING) {
+            runs.push(Run {
+                started_at: started_at.trim(),
+                lines: Vec::new(),
+            });
+        } else if let Some(run) = runs.last_mut() {
+            run.lines.push(line);
+        }
+    }
+    runs
+}
+
+/// Start time of a run (local time in the file) as RFC 3339
+fn run_timestamp(started_at: &str) -> Option<String> {
+    let naive = NaiveDateTime::parse_from_str(started_at, "%Y-%m-%d %H:%M:%S").ok()?;
+    let local = Local.from_local_datetime(&naive).earliest()?;
+    Some(local.with_timezone(&Utc).to_rfc3339())
+}
+
+fn classify(line: &str) -> (Block, &str) {
+    if let Some(text) = line.strip_prefix("####") {
+        (Block::User, text.strip_prefix(' ').unwrap_or(text))
+    } else if let Some(text) = line.strip_prefix('>') {
+        (Block::Output, text.strip_prefix(' ').unwrap_or(text))
+    } else {
+        (Block::Assistant, line)
+    }
+}
+
+/// Consecutive lines of the same kind, as (kind, text)
+fn blocks<'a>(lines: &[&'a str]) -> Vec<(Block, String)> {
+    let mut blocks: Vec<(Block, Vec<&'a str>)> = Vec::new();
+    for line in lines {
+        let (kind, text) = classify(line);
+        if line.trim().is_empty() {
+            // Blank lines only belong to replies; elsewhere they separate blocks
+            if let Some((Block::Assistant, current)) = blocks.last_mut() {
+                current.push("");
+            }
+            continue;
+        }
+        match blocks.last_mut() {
+            Some((current_kind, current)) if *current_kind == kind => current.push(text),
+            _ => blocks.push((kind, vec![text])),
+        }
+    }
+    blocks
+        .into_iter()
+        .map(|(kind, lines)| (kind, lines.join("\n").trim_end().to_string()))
+        .collect()
+}
+
+/// Messages of a run in the common model
+fn run_messages(run: &Run, session_id: &str, fallback_time: &str) -> Vec<ClaudeMessage> {
+    let timestamp = run_timestamp(run.started_at).unwrap_or_else(|| fallback_time.to_string());
+    let mut previous: Option<String> = None;
+    blocks(&run.lines)
+        .into_iter()
+        .enumerate()
+        .map(|(index, (kind, text))| {
+            let uuid = format!("aider-{session_id}-{index}");
+            let (message_type, content) = match kind {
+                Block::User => ("user", Value::String(text)),
+                Block::Assistant => (
+                    "assistant",
+                    serde_json::json!([{"type": "text", "text": text}]),
+                ),
+                Block::Output => ("system", Value::String(text)),
+            };
+            let parent_uuid = previous.replace(uuid.clone());
+            chat_message(
+                session_id,
+                uuid,
+                parent_uuid,
+                timestamp.clone(),
+                message_type,
+                content,
+            )
+        })
+        .collect()
+}
+
+fn read_history(path: &Path) -> Result<String, String> {
+    fs::read_to_string(long_path(path)).map_err(|e| format!("Failed to read Aider history: {e}"))
+}
+
+fn file_time(path: &Path) -> String {
+    fs::metadata(long_path(path))
+        .and_then(|metadata| metadata.modified())
+        .map(|modified| chrono::DateTime::<Utc>::from(modified).to_rfc3339())
+        .unwrap_or_default()
+}
+
+/// Aider `.aider.chat.history.md` files, one session per run
+pub(super) struct AiderSource;
+
+impl HistorySource for AiderSource {
+    fn read_conversations(root: &Path) -> Result<Vec<SourceConversation>, String> {
+        let mut conversations = Vec::new();
+        for file in history_files(root) {
+            let content = read_history(&file)?;
+            let project_name = file
+                .parent()
+                .and_then(file_name_string)
+                .unwrap_or_else(|| "Unknown".to_string());
+            let modified = file_time(&file);
+            for (index, run) in split_runs(&content).iter().enumerate() {
+                let session_id = index.to_string();
+                conversations.extend(chat_conversation(
+                    AIDER_SOURCE,
+                    &file,
+                    &session_id,
+                    project_name.clone(),
+                    None,
+                    run_messages(run, &session_id, &modified),
+                ));
+            }
+        }
+        Ok(conversations)
+    }
+
+    fn read_messages(file_path: &Path, session_id: &str) -> Result<Vec<ClaudeMessage>, String> {
+        let content = read_history(file_path)?;
+        let run = session_id
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| split_runs(&content).into_iter().nth(index))
+            .ok_or_else(|| format!("Aider chat not found: {session_id}"))?;
+        Ok(run_messages(&run, session_id, &file_time(file_path)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const HISTORY: &str = "\
+# aider chat started at 2024-05-01 10:00:00
+
+> Aider v0.50.0
+> Added src/lib.rs to the chat.
+
+#### Add a docstring
+#### to parse()
+
+Here is the change:
+
+src/lib.rs
+```rust
+/// Parse input
+```
+
+> Applied edit to src/lib.rs
+
+# aider chat started at 2024-05-02 09:30:00
+
+#### /help
+";
+
+    #[test]
+    fn test_run_messages_split_blocks() {
+        let runs = split_runs(HISTORY);
+        assert_eq!(runs.len(), 2);
+
+        let messages = run_messages(&runs[0], "0", "");
+        let kinds: Vec<&str> = messages.iter().map(|m| m.message_type.as_str()).collect();
+        assert_eq!(kinds, ["system", "user", "assistant", "system"]);
+        assert_eq!(
+            messages[1].content,
+            Some(Value::String("Add a docstring\nto parse()".to_string()))
+        );
+        assert_eq!(
+            messages[2].content,
+            Some(serde_json::json!([{
+                "type": "text",
+                "text": "Here is the change:\n\nsrc/lib.rs\n```rust\n/// Parse input\n```"
+            }]))
+        );
+        assert_eq!(messages[2].parent_uuid.as_deref(), Some("aider-0-1"));
+        assert_eq!(
+            messages[0].timestamp,
+            run_timestamp("2024-05-01 10:00:00").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_read_sessions_finds_history_files() {
+        let dir = TempDir::new().unwrap();
+        let project = dir.path().join("api");
+        fs::create_dir_all(project.join("node_modules")).unwrap();
+        fs::write(project.join(HISTORY_FILE), HISTORY).unwrap();
+        fs::write(project.join("node_modules").join(HISTORY_FILE), HISTORY).unwrap();
+
+        let sessions = AiderSource::read_sessions(dir.path()).unwrap();
+
+        assert_eq!(sessions.len(), 2);
+        assert!(sessions.iter().all(|s| s.project_name == "api"));
+        assert_eq!(
+            sessions[0].summary.as_deref(),
+            Some("Add a docstring\nto parse()")
+        );
+        assert_eq!(sessions[1].message_count, 1);
+
+        let messages = AiderSource::read_messages(&project.join(HISTORY_FILE), "1").unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(AiderSource::read_messages(&project.join(HISTORY_FILE), "2").is_err());
+    }
+}
@@ -0,0 +1,384 @@
+//! Cursor chat histories
+//!
+//! Cursor keeps its chats in the VS Code state databases under its `User`
+//! folder (`workspaceStorage/<id>/state.vscdb` per workspace and
+//! `globalStorage/state.vscdb`). Three layouts are read:
+//! - Chat panel tabs: the `workbench.panel.aichat.view.aichat.chatdata` item,
+//!   with `bubbles` of type "user" or "ai"
+//! - Workspace composers: the `composer.composerData` item
+//! - Global composers: `composerData:<id>` rows of `cursorDiskKV`, whose
+//!   messages are either inline or stored as `bubbleId:<id>:<bubble>` rows
+//!
+//! Composer messages have type 1 (user) or 2 (assistant). Only chat text is
+//! kept; the databases are opened read-only.
+
+use super::sources::{chat_conversation, chat_message, HistorySource, SourceConversation};
+use crate::models::ClaudeMessage;
+use crate::utils::{display_path, file_name_string};
+use chrono::DateTime;
+use rusqlite::{Connection, OpenFlags, OptionalExtension, Row};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// `ClaudeSession::source` of Cursor chats
+pub const CURSOR_SOURCE: &str = "cursor";
+
+/// Project name of chats not tied to a workspace
+const GLOBAL_PROJECT_NAME: &str = "Cursor";
+
+/// State database file name
+const STATE_DB: &str = "state.vscdb";
+
+const CHAT_PANEL_KEY: &str = "workbench.panel.aichat.view.aichat.chatdata";
+const COMPOSER_KEY: &str = "composer.composerData";
+
+/// A chat of a state database
+struct CursorChat {
+    id: String,
+    title: Option<String>,
+    updated_at: Option<i64>,               // Milliseconds since the epoch
+    messages: Vec<(&'static str, String)>, // (message type, text)
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+fn array_field<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .map_or(&[], Vec::as_slice)
+}
+
+/// State databases under Cursor's `User` folder
+fn state_databases(root: &Path) -> Vec<PathBuf> {
+    WalkDir::new(root)
+        .max_depth(3)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.file_type().is_file() && e.file_name() == STATE_DB)
+        .map(|e| e.path().to_path_buf())
+        .collect()
+}
+
+/// Name of the folder a workspace database belongs to, from the
+/// `workspace.json` next to it
+fn workspace_name(db_path: &Path) -> String {
+    db_path
+        .parent()
+        .map(|dir| dir.join("workspace.json"))
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .and_then(|workspace| {
+            let folder = str_field(&workspace, "folder")?;
+            let decoded = urlencoding::decode(folder).ok()?;
+            file_name_string(Path::new(decoded.trim_end_matches('/')))
+        })
+        .unwrap_or_else(|| GLOBAL_PROJECT_NAME.to_string())
+}
+
+fn open_database(path: &Path) -> Result<Connection, String> {
+    Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| format!("Failed to open {}: {e}", display_path(path)))
+}
+
+fn has_table(conn: &Connection, table: &str) -> bool {
+    conn.query_row(
+        "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |_| Ok(()),
+    )
+    .optional()
+    .ok()
+    .flatten()
+    .is_some()
+}
+
+/// Bytes of the `value` column, which Cursor stores as text or blob
+fn value_bytes(row: &Row) -> rusqlite::Result<Vec<u8>> {
+    Ok(row.get_ref(0)?.as_bytes()?.to_vec())
+}
+
+/// JSON value stored under `key` in a key-value table
+fn read_json(conn: &Connection, table: &str, key: &str) -> Option<Value> {
+    let sql = format!("SELECT value FROM {table} WHERE key = ?1");
+    let bytes: Vec<u8> = conn.query_row(&sql, [key], value_bytes).optional().ok()??;
+    serde_json::from_slice(&bytes).ok()
+}
+
+fn chat_panel_chats(data: &Value) -> Vec<CursorChat> {
+    array_field(data, "tabs")
+        .iter()
+        .filter_map(|tab| {
+            let messages = array_field(tab, "bubbles")
+                .iter()
+                .filter_map(|bubble| {
+                    let message_type = match str_field(bubble, "type")? {
+                        "user" => "user",
+                        "ai" => "assistant",
+                        _ => return None,
+                    };
+                    let text = str_field(bubble, "text")
+                        .filter(|text| !text.is_empty())
+                        .or_else(|| str_field(bubble, "rawText"))?;
+                    Some((message_type, text.to_string()))
+                })
+                .collect();
+            Some(CursorChat {
+                id: str_field(tab, "tabId")?.to_string(),
+                title: str_field(tab, "chatTitle").map(str::to_string),
+                updated_at: tab.get("lastSendTime").and_then(Value::as_i64),
+                messages,
+            })
+        })
+        .collect()
+}
+
+/// (message type, text) of a composer bubble
+fn composer_message(bubble: &Value) -> Option<(&'static str, String)> {
+    let message_type = match bubble.get("type").and_then(Value::as_i64)? {
+        1 => "user",
+        2 => "assistant",
+        _ => return None,
+    };
+    let text = str_field(bubble, "text").filter(|text| !text.trim().is_empty())?;
+    Some((message_type, text.to_string()))
+}
+
+/// A composer; `bubble` looks up bubbles stored outside the composer
+fn composer_chat(composer: &Value, bubble: impl Fn(&str) -> Option<Value>) -> Option<CursorChat> {
+    let id = str_field(composer, "composerId")?;
+    let inline = array_field(composer, "conversation");
+    let messages = if inline.is_empty() {
+        array_field(composer, "fullConversationHeadersOnly")
+            .iter()
+            .filter_map(|header| bubble(str_field(header, "bubbleId")?))
+            .filter_map(|bubble| composer_message(&bubble))
+            .collect()
+    } else {
+        inline.iter().filter_map(composer_message).collect()
+    };
+    Some(CursorChat {
+        id: id.to_string(),
+        title: str_field(composer, "name").map(str::to_string),
+        updated_at: composer
+            .get("lastUpdatedAt")
+            .or_else(|| composer.get("createdAt"))
+            .and_then(Value::as_i64),
+        messages,
+    })
+}
+
+/// Every chat of a state database (missing tables and items are skipped)
+fn read_chats(db_path: &Path) -> Result<Vec<CursorChat>, String> {
+    let conn = open_database(db_path)?;
+    let mut chats = Vec::new();
+
+    if has_table(&conn, "ItemTable") {
+        if let Some(data) = read_json(&conn, "ItemTable", CHAT_PANEL_KEY) {
+            chats.extend(chat_panel_chats(&data));
+        }
+        if let Some(data) = read_json(&conn, "ItemTable", COMPOSER_KEY) {
+            chats.extend(
+                array_field(&data, "allComposers")
+                    .iter()
+                    .filter_map(|composer| composer_chat(composer, |_| None)),
+            );
+        }
+    }
+
+    if has_table(&conn, "cursorDiskKV") {
+        let mut stmt = conn
+            .prepare("SELECT value FROM cursorDiskKV WHERE key LIKE 'composerData:%'")
+            .map_err(|e| format!("Failed to read Cursor chats: {e}"))?;
+        let composers: Vec<Value> = stmt
+            .query_map([], value_bytes)
+            .map_err(|e| format!("Failed to read Cursor chats: {e}"))?
+            .filter_map(std::result::Result::ok)
+            .filter_map(|bytes| serde_json::from_slice(&bytes).ok())
+            .collect();
+        for composer in &composers {
+            let composer_id = str_field(composer, "composerId").unwrap_or_default();
+            chats.extend(composer_chat(composer, |bubble_id| {
+                read_json(
+                    &conn,
+                    "cursorDiskKV",
+                    &format!("bubbleId:{composer_id}:{bubble_id}"),
+                )
+            }));
+        }
+    }
+
+    Ok(chats)
+}
+
+fn chat_timestamp(updated_at: Option<i64>) -> String {
+    updated_at
+        .and_then(DateTime::from_timestamp_millis)
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_default()
+}
+
+/// Messages of a chat in the common model; they all carry the chat's last
+/// update time, as Cursor does not store per-message times
+fn chat_messages(chat: &CursorChat) -> Vec<ClaudeMessage> {
+    let timestamp = chat_timestamp(chat.updated_at);
+    let mut previous: Option<String> = None;
+    chat.messages
+        .iter()
+        .enumerate()
+        .map(|(index, (message_type, text))| {
+            let uuid = format!("cursor-{}-{index}", chat.id);
+            let content = if *message_type == "user" {
+                Value::String(text.clone())
+            } else {
+                serde_json::json!([{"type": "text", "text": text}])
+            };
+            let parent_uuid = previous.replace(uuid.clone());
+            chat_message(
+                &chat.id,
+                uuid,
+                parent_uuid,
+                timestamp.clone(),
+                message_type,
+                content,
+            )
+        })
+        .collect()
+}
+
+/// Cursor chat panel and composer logs
+pub(super) struct CursorSource;
+
+impl HistorySource for CursorSource {
+    fn read_conversations(root: &Path) -> Result<Vec<SourceConversation>, String> {
+        let mut conversations = Vec::new();
+        for db_path in state_databases(root) {
+            let project_name = workspace_name(&db_path);
+            for chat in read_chats(&db_path)? {
+                conversations.extend(chat_conversation(
+                    CURSOR_SOURCE,
+                    &db_path,
+                    &chat.id,
+                    project_name.clone(),
+                    chat.title.clone(),
+                    chat_messages(&chat),
+                ));
+            }
+        }
+        Ok(conversations)
+    }
+
+    fn read_messages(file_path: &Path, session_id: &str) -> Result<Vec<ClaudeMessage>, String> {
+        read_chats(file_path)?
+            .iter()
+            .find(|chat| chat.id == session_id)
+            .map(chat_messages)
+            .ok_or_else(|| format!("Cursor chat not found: {session_id}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn create_database(path: &Path, rows: &[(&str, &str, Value)]) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let conn = Connection::open(path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE ItemTable (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB);
+             CREATE TABLE cursorDiskKV (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB);",
+        )
+        .unwrap();
+        for (table, key, value) in rows {
+            conn.execute(
+                &format!("INSERT INTO {table} (key, value) VALUES (?1, ?2)"),
+                (key, value.to_string()),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_read_sessions_from_workspace_and_global_storage() {
+        let dir = TempDir::new().unwrap();
+        let workspace = dir.path().join("workspaceStorage").join("abc123");
+        create_database(
+            &workspace.join(STATE_DB),
+            &[(
+                "ItemTable",
+                CHAT_PANEL_KEY,
+                json!({"tabs": [{
+                    "tabId": "tab-1",
+                    "chatTitle": "Flaky test",
+                    "lastSendTime": 1_714_557_600_000_i64,
+                    "bubbles": [
+                        {"type": "user", "text": "Why does this test flake?"},
+                        {"type": "ai", "text": "", "rawText": "It races the timer."}
+                    ]
+                }]}),
+            )],
+        );
+        fs::write(
+            workspace.join("workspace.json"),
+            r#"{"folder": "file:///home/me/my%20api"}"#,
+        )
+        .unwrap();
+        create_database(
+            &dir.path().join("globalStorage").join(STATE_DB),
+            &[
+                (
+                    "cursorDiskKV",
+                    "composerData:c1",
+                    json!({
+                        "composerId": "c1",
+                        "name": "Refactor",
+                        "lastUpdatedAt": 1_714_644_000_000_i64,
+                        "fullConversationHeadersOnly": [
+                            {"bubbleId": "b1", "type": 1},
+                            {"bubbleId": "b2", "type": 2}
+                        ]
+                    }),
+                ),
+                (
+                    "cursorDiskKV",
+                    "bubbleId:c1:b1",
+                    json!({"type": 1, "text": "Split this module"}),
+                ),
+                (
+                    "cursorDiskKV",
+                    "bubbleId:c1:b2",
+                    json!({"type": 2, "text": "Done."}),
+                ),
+            ],
+        );
+
+        let mut sessions = CursorSource::read_sessions(dir.path()).unwrap();
+        sessions.sort_by(|a, b| a.actual_session_id.cmp(&b.actual_session_id));
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].actual_session_id, "c1");
+        assert_eq!(sessions[0].project_name, GLOBAL_PROJECT_NAME);
+        assert_eq!(sessions[0].summary.as_deref(), Some("Refactor"));
+        assert_eq!(sessions[0].last_modified, "2024-05-02T10:00:00+00:00");
+        assert_eq!(sessions[1].actual_session_id, "tab-1");
+        assert_eq!(sessions[1].project_name, "my api");
+        assert_eq!(sessions[1].message_count, 2);
+
+        let messages =
+            CursorSource::read_messages(Path::new(&sessions[1].file_path), "tab-1").unwrap();
+        assert_eq!(messages[1].message_type, "assistant");
+        assert_eq!(
+            messages[1].content,
+            Some(json!([{"type": "text", "text": "It races the timer."}]))
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! Claude desktop app conversations
+//!
+//! The desktop app keeps chats on claude.ai and only caches them in Chromium
+//! storage, so the readable local copy is the app's data export: one or more
+//! `conversations.json` files in the claude.ai export format. They are looked
+//! up in the desktop app's data folder (or any folder given, such as an
+//! unpacked export) and listed under a "Claude Desktop" project.
+
+use super::sources::{chat_conversation, chat_message, HistorySource, SourceConversation};
+use crate::models::ClaudeMessage;
+use crate::utils::{display_path, long_path};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// `ClaudeSession::source` of desktop app conversations
+pub const DESKTOP_SOURCE: &str = "claude-desktop";
+
+/// Project name desktop app conversations are listed under
+pub const DESKTOP_PROJECT_NAME: &str = "Claude Desktop";
+
+/// File name of the claude.ai / desktop app conversation export
+const CONVERSATIONS_FILE: &str = "conversations.json";
+
+/// Folder depth searched for export files
+const MAX_SCAN_DEPTH: usize = 4;
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+/// Export files under `folder`
+fn conversation_files(folder: &Path) -> Vec<PathBuf> {
+    WalkDir::new(folder)
+        .max_depth(MAX_SCAN_DEPTH)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.file_type().is_file() && e.file_name() == CONVERSATIONS_FILE)
+        .map(|e| e.path().to_path_buf())
+        .collect()
+}
+
+/// Conversations of an export file
+fn read_conversations(path: &Path) -> Result<Vec<Value>, String> {
+    let content = fs::read_to_string(long_path(path))
+        .map_err(|e| format!("Failed to read {}: {e}", display_path(path)))?;
+    match serde_json::from_str(&content) {
+        Ok(Value::Array(conversations)) => Ok(conversations),
+        Ok(_) => Err(format!(
+            "{} is not a conversation export",
+            display_path(path)
+        )),
+        Err(e) => Err(format!("Failed to parse {}: {e}", display_path(path))),
+    }
+}
+
+/// Content blocks of a chat message; older exports only have `text`
+fn message_content(message: &Value) -> Value {
+    match message.get("content") {
+        Some(Value::Array(blocks)) if !blocks.is_empty() => Value::Array(blocks.clone()),
+        _ => json!([{"type": "text", "text": str_field(message, "text").unwrap_or_default()}]),
+    }
+}
+
+/// Chat messages of a conversation in the common model
+fn conversation_messages(conversation: &Value) -> Vec<ClaudeMessage> {
+    let session_id = str_field(conversation, "uuid").unwrap_or_default();
+    let created_at = str_field(conversation, "created_at").unwrap_or_default();
+    let mut previous: Option<String> = None;
+    conversation
+        .get("chat_messages")
+        .and_then(Value::as_array)
+        .map_or(&[][..], Vec::as_slice)
+        .iter()
+        .enumerate()
+        .map(|(index, message)| {
+            let uuid = str_field(message, "uuid")
+                .map_or_else(|| format!("{session_id}-{index}"), str::to_string);
+            let message_type = match str_field(message, "sender") {
+                Some("human") => "user",
+                _ => "assistant",
+            };
+            let parent_uuid = str_field(message, "parent_message_uuid")
+                .map(str::to_string)
+                .or(previous.take());
+            previous = Some(uuid.clone());
+            chat_message(
+                session_id,
+                uuid,
+                parent_uuid,
+                str_field(message, "created_at")
+                    .unwrap_or(created_at)
+                    .to_string(),
+                message_type,
+                message_content(message),
+            )
+        })
+        .collect()
+}
+
+/// A conversation of an export file as a session (None without messages)
+fn desktop_conversation(file_path: &Path, conversation: &Value) -> Option<SourceConversation> {
+    let uuid = str_field(conversation, "uuid")?;
+    let mut converted = chat_conversation(
+        DESKTOP_SOURCE,
+        file_path,
+        uuid,
+        DESKTOP_PROJECT_NAME.to_string(),
+        str_field(conversation, "name").map(str::to_string),
+        conversation_messages(conversation),
+    )?;
+    if let Some(updated_at) = str_field(conversation, "updated_at") {
+        converted.session.last_modified = updated_at.to_string();
+    }
+    Some(converted)
+}
+
+/// Messages of every conversation exported under `folder` (unreadable
+/// files are skipped)
+pub(super) fn read_all_desktop_messages(folder: &Path) -> Vec<ClaudeMessage> {
+    conversation_files(folder)
+        .iter()
+        .filter_map(|file| read_conversations(file).ok())
+        .flatten()
+        .flat_map(|conversation| conversation_messages(&conversation))
+        .collect()
+}
+
+/// Conversation exports of the Claude desktop app
+pub(super) struct DesktopSource;
+
+impl HistorySource for DesktopSource {
+    fn read_conversations(root: &Path) -> Result<Vec<SourceConversation>, String> {
+        let mut conversations = Vec::new();
+        for file in conversation_files(root) {
+            for conversation in read_conversations(&file)? {
+                conversations.extend(desktop_conversation(&file, &conversation));
+            }
+        }
+        Ok(conversations)
+    }
+
+    fn read_messages(file_path: &Path, session_id: &str) -> Result<Vec<ClaudeMessage>, String> {
+        read_conversations(file_path)?
+            .iter()
+            .find(|conversation| str_field(conversation, "uuid") == Some(session_id))
+            .map(conversation_messages)
+            .ok_or_else(|| format!("Conversation not found: {session_id}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::session::sources::read_sorted_sessions;
+    use tempfile::TempDir;
+
+    fn export() -> Value {
+        json!([
+            {
+                "uuid": "conv-1",
+                "name": "Trip planning",
+                "created_at": "2025-03-01T09:00:00Z",
+                "updated_at": "2025-03-01T09:05:00Z",
+                "chat_messages": [
+                    {
+                        "uuid": "m1", "sender": "human", "text": "Plan a trip to Lisbon",
+                        "content": [{"type": "text", "text": "Plan a trip to Lisbon"}],
+                        "created_at": "2025-03-01T09:00:00Z"
+                    },
+                    {
+                        "uuid": "m2", "sender": "assistant", "text": "Day 1: Alfama",
+                        "content": [
+                            {"type": "tool_use", "name": "web_search", "input": {"query": "Lisbon"}},
+                            {"type": "text", "text": "Day 1: Alfama"}
+                        ],
+                        "created_at": "2025-03-01T09:01:00Z"
+                    }
+                ]
+            },
+            {"uuid": "empty", "name": "", "chat_messages": []},
+            {
+                "uuid": "conv-2",
+                "name": "",
+                "created_at": "2025-04-01T09:00:00Z",
+                "updated_at": "2025-04-01T09:00:00Z",
+                "chat_messages": [
+                    {"uuid": "m3", "sender": "human", "text": "Hello", "created_at": "2025-04-01T09:00:00Z"}
+                ]
+            }
+        ])
+    }
+
+    fn write_export(dir: &Path) -> PathBuf {
+        let folder = dir.join("export-2025-04");
+        fs::create_dir_all(&folder).unwrap();
+        let path = folder.join(CONVERSATIONS_FILE);
+        fs::write(&path, export().to_string()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_read_desktop_sessions() {
+        let dir = TempDir::new().unwrap();
+        let path = write_export(dir.path());
+
+        let sessions = read_sorted_sessions::<DesktopSource>(dir.path()).unwrap();
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].actual_session_id, "conv-2");
+        assert_eq!(sessions[0].summary.as_deref(), Some("Hello"));
+        let trip = &sessions[1];
+        assert_eq!(
+            trip.session_id,
+            format!("claude-desktop:{}#conv-1", display_path(&path))
+        );
+        assert_eq!(trip.file_path, display_path(&path));
+        assert_eq!(trip.project_name, DESKTOP_PROJECT_NAME);
+        assert_eq!(trip.source.as_deref(), Some(DESKTOP_SOURCE));
+        assert_eq!(trip.message_count, 2);
+        assert_eq!(trip.last_message_time, "2025-03-01T09:01:00Z");
+        assert_eq!(trip.summary.as_deref(), Some("Trip planning"));
+        assert_eq!(
+            trip.first_user_message.as_deref(),
+            Some("Plan a trip to Lisbon")
+        );
+        assert!(trip.has_tool_use);
+        assert!(!trip.has_errors);
+    }
+
+    #[test]
+    fn test_conversation_messages() {
+        let export = export();
+        let messages = conversation_messages(&export[2]);
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].message_type, "user");
+        assert_eq!(messages[0].session_id, "conv-2");
+        assert_eq!(
+            messages[0].content,
+            Some(json!([{"type": "text", "text": "Hello"}]))
+        );
+
+        let messages = conversation_messages(&export[0]);
+        assert_eq!(messages[1].message_type, "assistant");
+        assert_eq!(messages[1].parent_uuid.as_deref(), Some("m1"));
+    }
+
+    #[test]
+    fn test_read_desktop_sessions_rejects_other_json() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(CONVERSATIONS_FILE), "{}").unwrap();
+
+        assert!(read_sorted_sessions::<DesktopSource>(dir.path()).is_err());
+        assert!(read_all_desktop_messages(dir.path()).is_empty());
+    }
+}
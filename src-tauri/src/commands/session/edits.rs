@@ -1,14 +1,33 @@
-//! File edit and restore functions
-
-use crate::models::{RawLogEntry, RecentFileEdit};
-use crate::utils::find_line_ranges;
+//! File edit tracking, restore and per-file session lookup
+
+use crate::commands::journal::{file_content_undo, record_operation};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::io_limit::acquire_file_permit;
+use crate::models::{
+    FileActivity, FileActivityStats, FileSessionMatch, FileTouch, RawLogEntry, RecentFileEdit,
+};
+use crate::utils::{
+    collect_session_files, display_path, extract_project_name, find_line_ranges, long_path,
+    stable_line_id,
+};
+use memchr::memmem;
 use memmap2::Mmap;
 use rayon::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+/// File tools and the operation they perform on their `file_path` input
+const FILE_TOOLS: &[(&str, &str)] = &[
+    ("Read", "read"),
+    ("Edit", "edit"),
+    ("MultiEdit", "edit"),
+    ("NotebookEdit", "edit"),
+    ("Write", "write"),
+];
+
 /// Intermediate result from processing a single session file (for parallel processing)
 struct SessionEditsResult {
     edits: Vec<RecentFileEdit>,
@@ -18,7 +37,8 @@ struct SessionEditsResult {
 /// Process a single session file and extract edit information
 #[allow(unsafe_code)] // Required for mmap performance optimization
 fn process_session_file_for_edits(file_path: &PathBuf) -> Option<SessionEditsResult> {
-    let file = fs::File::open(file_path).ok()?;
+    let _permit = acquire_file_permit();
+    let file = fs::File::open(long_path(file_path)).ok()?;
 
     // SAFETY: We're only reading the file, and the file handle is kept open
     // for the duration of the mmap's lifetime. Session files are append-only.
@@ -180,20 +200,11 @@ pub struct PaginatedRecentEdits {
     pub has_more: bool,
 }
 
-/// Scan all JSONL files in a project and extract recent file edits/writes
-/// Returns the LATEST content for each unique file path, sorted by timestamp descending
-/// Only includes files that belong to the project's working directory
-/// Supports pagination with offset and limit parameters
-#[tauri::command]
-pub async fn get_recent_edits(
-    project_path: String,
-    offset: Option<usize>,
-    limit: Option<usize>,
-) -> Result<PaginatedRecentEdits, String> {
-    let offset = offset.unwrap_or(0);
-    let limit = limit.unwrap_or(20);
+/// All Edit/Write operations of a project within its working directory,
+/// along with that directory (the most common cwd of its sessions)
+fn collect_project_edits(project_path: &str) -> (Vec<RecentFileEdit>, Option<String>) {
     // Phase 1: Collect all session files
-    let session_files: Vec<PathBuf> = WalkDir::new(&project_path)
+    let session_files: Vec<PathBuf> = WalkDir::new(project_path)
         .into_iter()
         .filter_map(std::result::Result::ok)
         .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
@@ -247,6 +258,24 @@ pub async fn get_recent_edits(
         all_edits
     };
 
+    (filtered_edits, project_cwd)
+}
+
+/// Scan all JSONL files in a project and extract recent file edits/writes
+/// Returns the LATEST content for each unique file path, sorted by timestamp descending
+/// Only includes files that belong to the project's working directory
+/// Supports pagination with offset and limit parameters
+#[tauri::command]
+pub async fn get_recent_edits(
+    project_path: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<PaginatedRecentEdits, AppError> {
+    let _timer = OperationTimer::start("get_recent_edits");
+    let offset = offset.unwrap_or(0);
+    let limit = limit.unwrap_or(20);
+    let (filtered_edits, project_cwd) = collect_project_edits(&project_path);
+
     let total_edits_count = filtered_edits.len();
 
     // Sort by timestamp descending (newest first)
@@ -281,35 +310,261 @@ pub async fn get_recent_edits(
     })
 }
 
-/// Restore a file by writing content to the specified path
-///
-/// Uses atomic write pattern: writes to a temporary file first, then renames.
-/// This prevents data loss if the write operation fails midway.
-///
-/// Security: Validates path to prevent path traversal attacks
+/// Aggregate edits per file, most edited first (ties broken by lines changed)
+fn build_file_activity(
+    edits: Vec<RecentFileEdit>,
+    project_cwd: Option<String>,
+    limit: usize,
+) -> FileActivityStats {
+    let total_edits = edits.len();
+    let mut by_file: HashMap<String, (FileActivity, HashSet<String>)> = HashMap::new();
+
+    for edit in edits {
+        let (activity, sessions) = by_file.entry(edit.file_path.clone()).or_insert_with(|| {
+            let relative_path = project_cwd
+                .as_deref()
+                .and_then(|cwd| edit.file_path.strip_prefix(cwd))
+                .map(|rest| rest.trim_start_matches(['/', '\\']))
+                .filter(|rest| !rest.is_empty())
+                .unwrap_or(&edit.file_path)
+                .to_string();
+            (
+                FileActivity {
+                    file_path: edit.file_path.clone(),
+                    relative_path,
+                    edit_count: 0,
+                    write_count: 0,
+                    session_count: 0,
+                    lines_added: 0,
+                    lines_removed: 0,
+                    first_touched: edit.timestamp.clone(),
+                    last_touched: edit.timestamp.clone(),
+                },
+                HashSet::new(),
+            )
+        });
+        if edit.operation_type == "write" {
+            activity.write_count += 1;
+        } else {
+            activity.edit_count += 1;
+        }
+        activity.lines_added += edit.lines_added;
+        activity.lines_removed += edit.lines_removed;
+        if edit.timestamp < activity.first_touched {
+            activity.first_touched.clone_from(&edit.timestamp);
+        }
+        if edit.timestamp > activity.last_touched {
+            activity.last_touched.clone_from(&edit.timestamp);
+        }
+        sessions.insert(edit.session_id);
+    }
+
+    let mut files: Vec<FileActivity> = by_file
+        .into_values()
+        .map(|(mut activity, sessions)| {
+            activity.session_count = sessions.len();
+            activity
+        })
+        .collect();
+    files.sort_by(|a, b| {
+        (b.edit_count + b.write_count)
+            .cmp(&(a.edit_count + a.write_count))
+            .then_with(|| (b.lines_added + b.lines_removed).cmp(&(a.lines_added + a.lines_removed)))
+            .then_with(|| b.last_touched.cmp(&a.last_touched))
+            .then_with(|| a.file_path.cmp(&b.file_path))
+    });
+    let unique_files = files.len();
+    files.truncate(limit);
+
+    FileActivityStats {
+        project_cwd,
+        total_edits,
+        unique_files,
+        files,
+    }
+}
+
+/// Rank the files of a project by Edit/Write count, lines changed and last edit
 #[tauri::command]
-pub async fn restore_file(file_path: String, content: String) -> Result<(), String> {
-    use std::fs;
-    use std::path::Path;
+pub async fn get_file_activity_stats(
+    project_path: String,
+    limit: Option<usize>,
+) -> Result<FileActivityStats, AppError> {
+    let _timer = OperationTimer::start("get_file_activity_stats");
+    let limit = limit.unwrap_or(50);
 
-    // Security validation: reject paths with null bytes
-    if file_path.contains('\0') {
-        return Err("Invalid file path: contains null bytes".to_string());
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let (edits, project_cwd) = collect_project_edits(&project_path);
+        build_file_activity(edits, project_cwd, limit)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?)
+}
+
+/// Normalize a path for comparison: forward slashes, no trailing slash
+/// (and case-insensitive on Windows)
+fn normalize_file_path(path: &str) -> String {
+    let normalized = path.trim().replace('\\', "/");
+    let normalized = normalized.trim_end_matches('/');
+    if cfg!(target_os = "windows") {
+        normalized.to_lowercase()
+    } else {
+        normalized.to_string()
     }
+}
 
-    // Security validation: reject relative paths (must be absolute)
-    let path = Path::new(&file_path);
-    if !path.is_absolute() {
-        return Err("Invalid file path: must be an absolute path".to_string());
+/// Whether a tool call path refers to the queried file; relative queries
+/// match any absolute path ending with them
+fn file_path_matches(tool_path: &str, query: &str) -> bool {
+    let tool_path = normalize_file_path(tool_path);
+    tool_path == query
+        || (!Path::new(query).is_absolute()
+            && tool_path
+                .strip_suffix(query)
+                .is_some_and(|prefix| prefix.ends_with('/')))
+}
+
+/// File tool calls of an entry as (tool name, operation, path)
+pub(crate) fn file_tool_calls(log_entry: &RawLogEntry) -> Vec<(&str, &'static str, &str)> {
+    let content_blocks = log_entry
+        .message
+        .as_ref()
+        .and_then(|m| m.content.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|block| block.get("type").and_then(|t| t.as_str()) == Some("tool_use"));
+
+    content_blocks
+        .chain(log_entry.tool_use.as_ref())
+        .filter_map(|tool_use| {
+            let name = tool_use.get("name")?.as_str()?;
+            let (_, operation) = FILE_TOOLS.iter().find(|(tool, _)| *tool == name)?;
+            let input = tool_use.get("input")?;
+            let path = input
+                .get("file_path")
+                .or_else(|| input.get("notebook_path"))?
+                .as_str()?;
+            Some((name, *operation, path))
+        })
+        .collect()
+}
+
+/// Tool calls of one session file that read or modified the queried file
+#[allow(unsafe_code)] // Required for mmap performance optimization
+fn process_session_file_for_file(
+    raw_project_name: &str,
+    session_path: &Path,
+    query: &str,
+) -> Option<FileSessionMatch> {
+    let _permit = acquire_file_permit();
+    let file = fs::File::open(long_path(session_path)).ok()?;
+
+    // SAFETY: We're only reading the file, and the file handle is kept open
+    // for the duration of the mmap's lifetime. Session files are append-only.
+    let mmap = unsafe { Mmap::map(&file) }.ok()?;
+
+    // Cheap pre-filter on the file name (not on Windows, where the query is
+    // lowercased and may differ in case from the log)
+    let file_name = query.rsplit('/').next().unwrap_or(query);
+    let finder = (!cfg!(target_os = "windows")).then(|| memmem::Finder::new(file_name.as_bytes()));
+    if let Some(finder) = &finder {
+        finder.find(&mmap)?;
     }
 
-    // Security validation: reject paths with parent traversal segments
-    for component in path.components() {
-        if let std::path::Component::ParentDir = component {
-            return Err("Invalid file path: path traversal not allowed".to_string());
+    let mut session_id: Option<String> = None;
+    let mut touched_path: Option<String> = None;
+    let mut touches: Vec<FileTouch> = Vec::new();
+
+    for (line_num, (start, end)) in find_line_ranges(&mmap).into_iter().enumerate() {
+        let line = &mmap[start..end];
+        if finder.as_ref().is_some_and(|f| f.find(line).is_none()) {
+            continue;
         }
+        let mut line_bytes = line.to_vec();
+        let Ok(log_entry) = simd_json::serde::from_slice::<RawLogEntry>(&mut line_bytes) else {
+            continue;
+        };
+        if session_id.is_none() {
+            session_id.clone_from(&log_entry.session_id);
+        }
+
+        for (tool_name, operation, path) in file_tool_calls(&log_entry) {
+            if !file_path_matches(path, query) {
+                continue;
+            }
+            touched_path.get_or_insert_with(|| path.to_string());
+            touches.push(FileTouch {
+                message_uuid: log_entry.uuid.clone().unwrap_or_else(|| {
+                    stable_line_id(log_entry.session_id.as_deref(), session_path, line_num)
+                }),
+                timestamp: log_entry.timestamp.clone().unwrap_or_default(),
+                tool_name: tool_name.to_string(),
+                operation: operation.to_string(),
+            });
+        }
+    }
+
+    let touched_path = touched_path?;
+    touches.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    let read_count = touches.iter().filter(|t| t.operation == "read").count();
+
+    Some(FileSessionMatch {
+        project_name: extract_project_name(raw_project_name),
+        session_id: session_id.unwrap_or_else(|| {
+            session_path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default()
+        }),
+        file_path: display_path(session_path),
+        touched_path,
+        first_touched: touches.first()?.timestamp.clone(),
+        last_touched: touches.last()?.timestamp.clone(),
+        read_count,
+        modify_count: touches.len() - read_count,
+        touches,
+    })
+}
+
+/// Find every session (across projects) that read or modified a file
+///
+/// Scans Read/Edit/`MultiEdit`/Write tool calls. `path` is matched exactly
+/// when absolute, or as a path suffix when relative (`src/main.rs`).
+/// Sessions are returned most recently touched first.
+#[tauri::command]
+pub async fn find_sessions_by_file(
+    claude_path: String,
+    path: String,
+) -> Result<Vec<FileSessionMatch>, AppError> {
+    let _timer = OperationTimer::start("find_sessions_by_file");
+    let query = normalize_file_path(&path);
+    if query.is_empty() {
+        return Err(AppError::invalid_input("File path must not be empty"));
     }
 
+    let projects_path = PathBuf::from(&claude_path).join("projects");
+    if !projects_path.exists() {
+        return Err(AppError::not_found("Projects directory not found"));
+    }
+
+    let session_files = collect_session_files(&projects_path)?;
+    let mut sessions: Vec<FileSessionMatch> = session_files
+        .par_iter()
+        .filter_map(|(project_name, path)| {
+            process_session_file_for_file(project_name, path, &query)
+        })
+        .collect();
+
+    sessions.sort_by(|a, b| b.last_touched.cmp(&a.last_touched));
+    Ok(sessions)
+}
+
+/// Write `content` to `path` through a temporary file, creating parent
+/// directories as needed
+///
+/// Uses atomic write pattern: writes to a temporary file first, then renames.
+/// This prevents data loss if the write operation fails midway.
+pub(crate) fn write_file_atomic(path: &Path, content: &str) -> Result<(), String> {
     // Create parent directories if they don't exist
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).map_err(|e| format!("Failed to create directories: {e}"))?;
@@ -320,14 +575,51 @@ pub async fn restore_file(file_path: String, content: String) -> Result<(), Stri
     let temp_path = path.with_extension("tmp.restore");
 
     // Write to temporary file
-    fs::write(&temp_path, &content).map_err(|e| format!("Failed to write temporary file: {e}"))?;
+    fs::write(&temp_path, content).map_err(|e| format!("Failed to write temporary file: {e}"))?;
 
     // Atomically rename temp file to target (this is atomic on most filesystems)
     fs::rename(&temp_path, path).map_err(|e| {
         // Clean up temp file if rename fails
         let _ = fs::remove_file(&temp_path);
         format!("Failed to rename temporary file: {e}")
-    })?;
+    })
+}
+
+/// Restore a file by writing content to the specified path
+///
+/// The previous content is kept in the operation journal, so the restore
+/// can be undone.
+///
+/// Security: Validates path to prevent path traversal attacks
+#[tauri::command]
+pub async fn restore_file(file_path: String, content: String) -> Result<(), AppError> {
+    // Security validation: reject paths with null bytes
+    if file_path.contains('\0') {
+        return Err(AppError::invalid_input(
+            "Invalid file path: contains null bytes",
+        ));
+    }
+
+    // Security validation: reject relative paths (must be absolute)
+    let path = Path::new(&file_path);
+    if !path.is_absolute() {
+        return Err(AppError::invalid_input(
+            "Invalid file path: must be an absolute path",
+        ));
+    }
+
+    // Security validation: reject paths with parent traversal segments
+    for component in path.components() {
+        if let std::path::Component::ParentDir = component {
+            return Err(AppError::invalid_input(
+                "Invalid file path: path traversal not allowed",
+            ));
+        }
+    }
+
+    let undo = file_content_undo(path, &content);
+    write_file_atomic(&long_path(path), &content)?;
+    record_operation("restore_file", format!("Restored {file_path}"), undo);
 
     Ok(())
 }
@@ -351,7 +643,7 @@ mod tests {
     async fn test_restore_file_rejects_null_bytes() {
         let result = restore_file("/tmp/test\0file.txt".to_string(), "content".to_string()).await;
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("null bytes"));
+        assert!(result.unwrap_err().message().contains("null bytes"));
     }
 
     #[tokio::test]
@@ -359,14 +651,14 @@ mod tests {
         let result =
             restore_file("relative/path/file.txt".to_string(), "content".to_string()).await;
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("absolute path"));
+        assert!(result.unwrap_err().message().contains("absolute path"));
     }
 
     #[tokio::test]
     async fn test_restore_file_rejects_path_traversal() {
         let result = restore_file("/tmp/../etc/passwd".to_string(), "content".to_string()).await;
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("path traversal"));
+        assert!(result.unwrap_err().message().contains("path traversal"));
     }
 
     #[tokio::test]
@@ -573,4 +865,96 @@ mod tests {
         assert_eq!(edits_result.unique_files_count, 2);
         assert_eq!(edits_result.project_cwd, Some("/test/project".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_get_file_activity_stats_ranks_by_edit_count() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let content = r#"{"uuid":"uuid-1","sessionId":"session-1","timestamp":"2025-06-26T10:00:00Z","type":"user","cwd":"/test/project","toolUseResult":{"filePath":"/test/project/src/lib.rs","oldString":"a","newString":"b\nc","originalFile":"a"}}
+{"uuid":"uuid-2","sessionId":"session-1","timestamp":"2025-06-26T10:01:00Z","type":"assistant","cwd":"/test/project","toolUse":{"name":"Write","input":{"file_path":"/test/project/README.md","content":"one\ntwo\nthree"}}}
+{"uuid":"uuid-3","sessionId":"session-1","timestamp":"2025-06-26T10:02:00Z","type":"user","cwd":"/test/project","toolUseResult":{"filePath":"/test/project/src/lib.rs","oldString":"b","newString":"d","originalFile":"b\nc"}}"#;
+        create_test_jsonl_file(&temp_dir, "session-1.jsonl", content);
+        let content = r#"{"uuid":"uuid-4","sessionId":"session-2","timestamp":"2025-06-27T09:00:00Z","type":"user","cwd":"/test/project","toolUseResult":{"filePath":"/test/project/src/lib.rs","oldString":"d","newString":"e","originalFile":"d\nc"}}"#;
+        create_test_jsonl_file(&temp_dir, "session-2.jsonl", content);
+
+        let stats = get_file_activity_stats(temp_dir.path().to_string_lossy().to_string(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(stats.project_cwd, Some("/test/project".to_string()));
+        assert_eq!(stats.total_edits, 4);
+        assert_eq!(stats.unique_files, 2);
+
+        let lib = &stats.files[0];
+        assert_eq!(lib.relative_path, "src/lib.rs");
+        assert_eq!((lib.edit_count, lib.write_count), (3, 0));
+        assert_eq!(lib.session_count, 2);
+        assert_eq!(lib.first_touched, "2025-06-26T10:00:00Z");
+        assert_eq!(lib.last_touched, "2025-06-27T09:00:00Z");
+        assert_eq!(stats.files[1].relative_path, "README.md");
+        assert_eq!(stats.files[1].write_count, 1);
+
+        let limited =
+            get_file_activity_stats(temp_dir.path().to_string_lossy().to_string(), Some(1))
+                .await
+                .unwrap();
+        assert_eq!(limited.files.len(), 1);
+        assert_eq!(limited.unique_files, 2);
+    }
+
+    #[tokio::test]
+    async fn test_find_sessions_by_file_reads_and_edits() {
+        let temp_dir = TempDir::new().unwrap();
+        let projects_dir = temp_dir.path().join("projects");
+        let project_a = projects_dir.join("-Users-me-app");
+        let project_b = projects_dir.join("-Users-me-other");
+        fs::create_dir_all(&project_a).unwrap();
+        fs::create_dir_all(&project_b).unwrap();
+
+        let read = r#"{"uuid":"a1","sessionId":"s1","timestamp":"2025-06-26T10:00:00Z","type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Read","input":{"file_path":"/Users/me/app/src/main.rs"}}]}}"#;
+        let edit = r#"{"uuid":"a2","sessionId":"s1","timestamp":"2025-06-26T10:05:00Z","type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Fixing"},{"type":"tool_use","id":"t2","name":"Edit","input":{"file_path":"/Users/me/app/src/main.rs","old_string":"a","new_string":"b"}}]}}"#;
+        let other_file = r#"{"uuid":"a3","sessionId":"s1","timestamp":"2025-06-26T10:06:00Z","type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t3","name":"Edit","input":{"file_path":"/Users/me/app/src/not_main.rs","old_string":"a","new_string":"b"}}]}}"#;
+        fs::write(
+            project_a.join("s1.jsonl"),
+            [read, edit, other_file].join("\n"),
+        )
+        .unwrap();
+        let write = r#"{"uuid":"b1","sessionId":"s2","timestamp":"2025-06-27T09:00:00Z","type":"assistant","toolUse":{"name":"Write","input":{"file_path":"/Users/me/app/src/main.rs","content":"fn main() {}"}}}"#;
+        fs::write(project_b.join("s2.jsonl"), write).unwrap();
+        let mention = r#"{"uuid":"c1","sessionId":"s3","timestamp":"2025-06-28T09:00:00Z","type":"user","message":{"role":"user","content":"look at src/main.rs"}}"#;
+        fs::write(project_b.join("s3.jsonl"), mention).unwrap();
+
+        let claude_path = temp_dir.path().to_string_lossy().to_string();
+        let sessions = find_sessions_by_file(claude_path.clone(), "src/main.rs".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].session_id, "s2");
+        assert_eq!(sessions[0].modify_count, 1);
+        assert_eq!(sessions[1].project_name, "app");
+        assert_eq!(sessions[1].touched_path, "/Users/me/app/src/main.rs");
+        assert_eq!(sessions[1].read_count, 1);
+        assert_eq!(sessions[1].modify_count, 1);
+        assert_eq!(sessions[1].first_touched, "2025-06-26T10:00:00Z");
+        assert_eq!(sessions[1].last_touched, "2025-06-26T10:05:00Z");
+        let uuids: Vec<&str> = sessions[1]
+            .touches
+            .iter()
+            .map(|t| t.message_uuid.as_str())
+            .collect();
+        assert_eq!(uuids, vec!["a1", "a2"]);
+
+        let exact =
+            find_sessions_by_file(claude_path.clone(), "/Users/me/app/src/main.rs".to_string())
+                .await
+                .unwrap();
+        assert_eq!(exact.len(), 2);
+        // A bare file name matches it in any directory, but not as a suffix of another name
+        let by_name = find_sessions_by_file(claude_path, "main.rs".to_string())
+            .await
+            .unwrap();
+        assert_eq!(by_name.len(), 2);
+        assert_eq!(by_name[1].touches.len(), 2);
+    }
 }
@@ -0,0 +1,200 @@
+//! Find within one open session
+//!
+//! Returns the position of every match so the frontend can step through
+//! them (find next / previous) and page to the right message, without
+//! holding the whole transcript itself.
+
+use super::read_session_messages;
+use super::search::build_matcher;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ClaudeMessage, SessionSearchHit, SessionSearchHits};
+use crate::utils::resolve_session_file;
+use regex::Regex;
+
+/// Maximum number of hits returned by `search_in_session`
+const MAX_SESSION_HITS: usize = 10_000;
+
+/// Text of each block the transcript view renders, with its type and index
+fn searchable_blocks(content: &serde_json::Value) -> Vec<(&'static str, Option<usize>, &str)> {
+    let serde_json::Value::Array(blocks) = content else {
+        return content
+            .as_str()
+            .map(|text| vec![("text", None, text)])
+            .unwrap_or_default();
+    };
+
+    blocks
+        .iter()
+        .enumerate()
+        .filter_map(|(index, block)| {
+            let (block_type, text) = match block.get("type").and_then(|t| t.as_str())? {
+                "text" => ("text", block.get("text")),
+                "thinking" => ("thinking", block.get("thinking")),
+                "tool_result" => ("tool_result", block.get("content")),
+                _ => return None,
+            };
+            Some((block_type, Some(index), text?.as_str()?))
+        })
+        .collect()
+}
+
+/// Match ranges of `matcher` in `text` as UTF-16 offsets (JS string indices)
+fn utf16_ranges(text: &str, matcher: &Regex) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let (mut byte_pos, mut utf16_pos) = (0, 0);
+    for found in matcher.find_iter(text).filter(|m| !m.is_empty()) {
+        utf16_pos += text[byte_pos..found.start()].encode_utf16().count();
+        let start = utf16_pos;
+        utf16_pos += found.as_str().encode_utf16().count();
+        byte_pos = found.end();
+        ranges.push((start, utf16_pos));
+    }
+    ranges
+}
+
+fn session_hits(messages: &[ClaudeMessage], matcher: &Regex) -> Vec<SessionSearchHit> {
+    let mut hits = Vec::new();
+    for message in messages {
+        let Some(content) = message.content.as_ref() else {
+            continue;
+        };
+        for (block_type, block_index, text) in searchable_blocks(content) {
+            hits.extend(utf16_ranges(text, matcher).into_iter().map(|(start, end)| {
+                SessionSearchHit {
+                    message_uuid: message.uuid.clone(),
+                    block_type: block_type.to_string(),
+                    block_index,
+                    start,
+                    end,
+                }
+            }));
+        }
+    }
+    hits
+}
+
+/// Find every match of `query` in one session, in transcript order
+///
+/// Searches the rendered text of messages: text and thinking blocks and
+/// plain-text tool results. `mode` is "plain" (default), "regex" or "glob";
+/// matching ignores case. Offsets are UTF-16 so they index JS strings.
+#[tauri::command]
+pub async fn search_in_session(
+    session_id: String,
+    project_path: String,
+    query: String,
+    mode: Option<String>,
+) -> Result<SessionSearchHits, AppError> {
+    let _timer = OperationTimer::start("search_in_session");
+
+    if query.is_empty() {
+        return Err(AppError::invalid_input("Search query is empty"));
+    }
+    let matcher = build_matcher(&query, mode.as_deref()).map_err(AppError::invalid_input)?;
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+
+    let mut hits = tauri::async_runtime::spawn_blocking(move || {
+        let messages = read_session_messages(&session_path)?;
+        Ok::<_, String>(session_hits(&messages, &matcher))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+
+    let total_hits = hits.len();
+    hits.truncate(MAX_SESSION_HITS);
+    Ok(SessionSearchHits {
+        session_id,
+        total_hits,
+        truncated: total_hits > hits.len(),
+        hits,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_utf16_ranges_count_surrogate_pairs() {
+        let matcher = build_matcher("cat", None).unwrap();
+        assert_eq!(utf16_ranges("🐱 cat é CAT", &matcher), [(3, 6), (9, 12)]);
+    }
+
+    #[tokio::test]
+    async fn test_search_in_session_returns_ordered_hits() {
+        let temp = TempDir::new().unwrap();
+        let lines = [
+            json!({
+                "uuid": "u1",
+                "sessionId": "s1",
+                "timestamp": "2025-03-01T09:40:00Z",
+                "type": "user",
+                "message": {"role": "user", "content": "Fix the parser; the parser panics"}
+            }),
+            json!({
+                "uuid": "a1",
+                "sessionId": "s1",
+                "timestamp": "2025-03-01T09:40:05Z",
+                "type": "assistant",
+                "message": {
+                    "role": "assistant",
+                    "content": [
+                        {"type": "thinking", "thinking": "Look at the Parser"},
+                        {"type": "tool_use", "id": "t1", "name": "Read", "input": {"file_path": "parser.rs"}},
+                        {"type": "text", "text": "Fixed."}
+                    ]
+                }
+            }),
+        ]
+        .map(|line| line.to_string());
+        fs::write(temp.path().join("s1.jsonl"), lines.join("\n")).unwrap();
+        let project_path = temp.path().to_string_lossy().to_string();
+
+        let result = search_in_session(
+            "s1".to_string(),
+            project_path.clone(),
+            "parser".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let hits: Vec<(&str, &str, Option<usize>, usize)> = result
+            .hits
+            .iter()
+            .map(|h| {
+                (
+                    h.message_uuid.as_str(),
+                    h.block_type.as_str(),
+                    h.block_index,
+                    h.start,
+                )
+            })
+            .collect();
+        assert_eq!(
+            hits,
+            [
+                ("u1", "text", None, 8),
+                ("u1", "text", None, 20),
+                ("a1", "thinking", Some(0), 12),
+            ]
+        );
+        assert_eq!(result.total_hits, 3);
+        assert!(!result.truncated);
+
+        assert!(matches!(
+            search_in_session(
+                "s1".to_string(),
+                project_path,
+                "(".to_string(),
+                Some("regex".to_string())
+            )
+            .await,
+            Err(AppError::InvalidInput { .. })
+        ));
+    }
+}
@@ -0,0 +1,214 @@
+//! Fuzzy "quick open" matching of sessions
+//!
+//! Matches the query as a subsequence of each session's summary and first
+//! prompt, using only the cached session metadata (no full-text index).
+
+use super::load::load_project_sessions;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ClaudeSession, FuzzySessionMatch};
+use std::fs;
+use std::path::PathBuf;
+
+/// Default number of sessions returned by `fuzzy_find_sessions`
+const DEFAULT_FUZZY_LIMIT: usize = 50;
+
+const SCORE_MATCH: i64 = 16;
+const BONUS_CONSECUTIVE: i64 = 12;
+const BONUS_WORD_START: i64 = 10;
+const PENALTY_GAP: i64 = 1;
+/// Gaps longer than this cost no more than this many characters
+const MAX_PENALIZED_GAP: i64 = 10;
+
+/// Score `candidate` against the (lowercased, whitespace-free) query
+///
+/// Returns the best score over every start position and the character
+/// indices of the matched letters, or None if the query is not a
+/// subsequence of the candidate.
+fn fuzzy_score(candidate: &str, query: &[char]) -> Option<(i64, Vec<usize>)> {
+    let chars: Vec<char> = candidate
+        .chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect();
+    let first = *query.first()?;
+
+    let mut best: Option<(i64, Vec<usize>)> = None;
+    for start in (0..chars.len()).filter(|&i| chars[i] == first) {
+        let mut indices: Vec<usize> = Vec::with_capacity(query.len());
+        let mut score = 0;
+        let mut pos = start;
+        for &q in query {
+            let Some(offset) = chars[pos..].iter().position(|&c| c == q) else {
+                break;
+            };
+            let idx = pos + offset;
+            score += SCORE_MATCH;
+            match indices.last() {
+                Some(&prev) if idx == prev + 1 => score += BONUS_CONSECUTIVE,
+                Some(&prev) => {
+                    let gap = i64::try_from(idx - prev - 1).unwrap_or(MAX_PENALIZED_GAP);
+                    score -= PENALTY_GAP * gap.min(MAX_PENALIZED_GAP);
+                }
+                None => {}
+            }
+            if idx == 0 || !chars[idx - 1].is_alphanumeric() {
+                score += BONUS_WORD_START;
+            }
+            indices.push(idx);
+            pos = idx + 1;
+        }
+        if indices.len() < query.len() {
+            // Later starts only have fewer characters left to match
+            break;
+        }
+        if best.as_ref().map_or(true, |(s, _)| score > *s) {
+            best = Some((score, indices));
+        }
+    }
+    best
+}
+
+/// Best match of a session over its summary and first prompt
+fn match_session(session: ClaudeSession, query: &[char]) -> Option<FuzzySessionMatch> {
+    let fields = [
+        ("summary", session.summary.as_deref()),
+        ("first_user_message", session.first_user_message.as_deref()),
+    ];
+    let (field, (score, matched_indices)) = fields
+        .into_iter()
+        .filter_map(|(field, text)| Some((field, fuzzy_score(text?, query)?)))
+        .max_by_key(|(_, (score, _))| *score)?;
+
+    Some(FuzzySessionMatch {
+        matched_field: field.to_string(),
+        matched_indices,
+        score,
+        session,
+    })
+}
+
+/// Rank sessions of every project whose summary or first prompt fuzzily
+/// matches `query`, best first
+///
+/// Uses the cached session metadata, so it stays fast enough for a quick
+/// open palette. Returns at most `limit` sessions (default 50).
+#[tauri::command]
+pub async fn fuzzy_find_sessions(
+    claude_path: String,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<FuzzySessionMatch>, AppError> {
+    let _timer = OperationTimer::start("fuzzy_find_sessions");
+
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let projects_path = PathBuf::from(&claude_path).join("projects");
+    let Ok(project_dirs) = fs::read_dir(&projects_path) else {
+        return Ok(Vec::new());
+    };
+
+    let mut matches = Vec::new();
+    for project_dir in project_dirs.filter_map(std::result::Result::ok) {
+        let project_path = project_dir.path();
+        if !project_path.is_dir() {
+            continue;
+        }
+        let sessions =
+            load_project_sessions(project_path.to_string_lossy().to_string(), None, None).await?;
+        matches.extend(
+            sessions
+                .into_iter()
+                .filter_map(|session| match_session(session, &query)),
+        );
+    }
+
+    matches.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| b.session.last_modified.cmp(&a.session.last_modified))
+    });
+    matches.truncate(limit.unwrap_or(DEFAULT_FUZZY_LIMIT));
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn chars(query: &str) -> Vec<char> {
+        query.chars().collect()
+    }
+
+    #[test]
+    fn test_fuzzy_score_prefers_word_starts_and_runs() {
+        let (_, indices) = fuzzy_score("Fix Login Bug", &chars("flb")).unwrap();
+        assert_eq!(indices, vec![0, 4, 10]);
+
+        let (run, _) = fuzzy_score("the login page", &chars("login")).unwrap();
+        let (scattered, _) = fuzzy_score("list of git inputs", &chars("login")).unwrap();
+        assert!(run > scattered);
+
+        assert!(fuzzy_score("login", &chars("logout")).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fuzzy_find_sessions_matches_summary_and_first_prompt() {
+        let temp = TempDir::new().unwrap();
+        let project_dir = temp.path().join("projects").join("-Users-me-demo");
+        fs::create_dir_all(&project_dir).unwrap();
+        let lines = |session: &str, summary: Option<&str>, prompt: &str| {
+            let mut lines = Vec::new();
+            if let Some(summary) = summary {
+                lines.push(format!(
+                    r#"{{"type":"summary","summary":"{summary}","leafUuid":"x"}}"#
+                ));
+            }
+            lines.push(format!(
+                r#"{{"uuid":"{session}-u1","sessionId":"{session}","timestamp":"2025-01-01T00:00:00Z","type":"user","message":{{"role":"user","content":"{prompt}"}}}}"#
+            ));
+            lines.join("\n")
+        };
+        fs::write(
+            project_dir.join("s1.jsonl"),
+            lines(
+                "s1",
+                Some("Refactor the parser"),
+                "Please clean up parse.rs",
+            ),
+        )
+        .unwrap();
+        fs::write(
+            project_dir.join("s2.jsonl"),
+            lines("s2", None, "Fix the login redirect"),
+        )
+        .unwrap();
+        let claude_path = temp.path().to_string_lossy().to_string();
+
+        let found = fuzzy_find_sessions(claude_path.clone(), "login".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].session.actual_session_id, "s2");
+
+        // The first prompt is searched even when a summary exists
+        let found = fuzzy_find_sessions(claude_path.clone(), "clean up".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].matched_field, "first_user_message");
+
+        let found = fuzzy_find_sessions(claude_path, "rfctr".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(found[0].matched_field, "summary");
+        assert_eq!(found[0].matched_indices.len(), 5);
+    }
+}
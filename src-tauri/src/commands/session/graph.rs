@@ -0,0 +1,485 @@
+//! Session graph functions
+//!
+//! Builds a DAG of a session's messages (parent links, sidechain branches and
+//! tool call -> tool result links) for the conversation graph view.
+
+use super::load::{is_system_message_type, read_session_messages};
+use super::repair::{apply_link_repairs, find_link_repairs};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ClaudeMessage, SessionGraph, SessionGraphEdge, SessionGraphNode};
+use crate::utils::resolve_session_file;
+use std::collections::{HashMap, HashSet};
+
+/// Maximum characters of message text shown in a node label
+const MAX_LABEL_CHARS: usize = 80;
+
+/// Build a single-line, truncated label for a message node
+pub(super) fn build_label(message: &ClaudeMessage) -> String {
+    let text = match &message.content {
+        Some(serde_json::Value::String(text)) => Some(text.clone()),
+        Some(serde_json::Value::Array(items)) => {
+            items
+                .iter()
+                .find_map(|item| match item.get("type").and_then(|v| v.as_str()) {
+                    Some("text") => item.get("text").and_then(|v| v.as_str()).map(String::from),
+                    Some("tool_use") => Some(format!(
+                        "[tool_use: {}]",
+                        item.get("name")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("unknown")
+                    )),
+                    Some("tool_result") => Some("[tool_result]".to_string()),
+                    Some("thinking") => Some("[thinking]".to_string()),
+                    _ => None,
+                })
+        }
+        _ => None,
+    };
+
+    let text = text.unwrap_or_else(|| message.message_type.clone());
+    let single_line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if single_line.chars().count() > MAX_LABEL_CHARS {
+        let truncated: String = single_line.chars().take(MAX_LABEL_CHARS).collect();
+        format!("{truncated}...")
+    } else {
+        single_line
+    }
+}
+
+/// Iterate over the content blocks of a message with the given type
+pub(super) fn content_blocks<'a>(
+    message: &'a ClaudeMessage,
+    block_type: &'a str,
+) -> impl Iterator<Item = &'a serde_json::Value> + 'a {
+    message
+        .content
+        .as_ref()
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+        .filter(move |item| item.get("type").and_then(|v| v.as_str()) == Some(block_type))
+}
+
+/// Build the session graph from messages in file order
+///
+/// System messages (progress, snapshots, ...) are not shown as nodes; parent
+/// links that pass through them are collapsed onto the nearest visible ancestor.
+pub fn build_session_graph(session_id: &str, messages: &[ClaudeMessage]) -> SessionGraph {
+    build_graph(session_id, messages, &HashSet::new())
+}
+
+/// Build the session graph with orphaned messages reattached
+///
+/// Links rebuilt by `find_link_repairs` get the "repaired" edge kind.
+pub fn build_repaired_session_graph(
+    session_id: &str,
+    mut messages: Vec<ClaudeMessage>,
+) -> SessionGraph {
+    let links = find_link_repairs(&messages);
+    apply_link_repairs(&mut messages, &links);
+    let repaired: HashSet<&str> = links.iter().map(|link| link.uuid.as_str()).collect();
+    build_graph(session_id, &messages, &repaired)
+}
+
+/// Index of a visible message's parent and whether the link was repaired
+pub(super) type ResolvedParent = Option<(usize, bool)>;
+
+/// Visible messages (system entries hidden, first occurrence of each uuid)
+/// with the parent of each one
+///
+/// Parent links that pass through hidden messages are collapsed onto the
+/// nearest visible ancestor.
+pub(super) fn visible_parents<'a>(
+    messages: &'a [ClaudeMessage],
+    repaired: &HashSet<&str>,
+) -> (Vec<&'a ClaudeMessage>, Vec<ResolvedParent>) {
+    // Parent links of hidden messages, used to bridge over them
+    let hidden_parents: HashMap<&str, Option<&str>> = messages
+        .iter()
+        .filter(|m| is_system_message_type(&m.message_type))
+        .map(|m| (m.uuid.as_str(), m.parent_uuid.as_deref()))
+        .collect();
+
+    // Visible messages, first occurrence of each uuid wins
+    let mut seen: HashSet<&str> = HashSet::new();
+    let visible: Vec<&ClaudeMessage> = messages
+        .iter()
+        .filter(|m| !is_system_message_type(&m.message_type))
+        .filter(|m| seen.insert(m.uuid.as_str()))
+        .collect();
+    let index: HashMap<&str, usize> = visible
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (m.uuid.as_str(), i))
+        .collect();
+
+    // Returns the parent index and whether the link crosses a repaired one
+    let resolve_parent = |message: &ClaudeMessage| -> Option<(usize, bool)> {
+        let mut current = message.parent_uuid.as_deref();
+        let mut is_repaired = repaired.contains(message.uuid.as_str());
+        // Bounded walk in case of malformed (cyclic) parent chains
+        for _ in 0..=hidden_parents.len() {
+            let uuid = current?;
+            if let Some(&idx) = index.get(uuid) {
+                return Some((idx, is_repaired));
+            }
+            is_repaired |= repaired.contains(uuid);
+            current = *hidden_parents.get(uuid)?;
+        }
+        None
+    };
+
+    let parents = visible
+        .iter()
+        .enumerate()
+        .map(|(idx, message)| resolve_parent(message).filter(|&(p, _)| p != idx))
+        .collect();
+    (visible, parents)
+}
+
+fn build_graph(
+    session_id: &str,
+    messages: &[ClaudeMessage],
+    repaired: &HashSet<&str>,
+) -> SessionGraph {
+    let (visible, resolved) = visible_parents(messages, repaired);
+
+    let mut edges = Vec::new();
+    let mut parents: Vec<Option<usize>> = Vec::with_capacity(visible.len());
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); visible.len()];
+
+    for (idx, (message, parent)) in visible.iter().zip(resolved).enumerate() {
+        if let Some((parent_idx, is_repaired)) = parent {
+            children[parent_idx].push(idx);
+            let kind = if is_repaired {
+                "repaired"
+            } else if message.is_sidechain == Some(true) {
+                "sidechain"
+            } else {
+                "parent"
+            };
+            edges.push(SessionGraphEdge {
+                source: visible[parent_idx].uuid.clone(),
+                target: message.uuid.clone(),
+                kind: kind.to_string(),
+            });
+        }
+        parents.push(parent.map(|(p, _)| p));
+    }
+
+    // Link tool calls to the messages carrying their results
+    let mut tool_use_owners: HashMap<&str, usize> = HashMap::new();
+    for (idx, message) in visible.iter().enumerate() {
+        for block in content_blocks(message, "tool_use") {
+            if let Some(id) = block.get("id").and_then(|v| v.as_str()) {
+                tool_use_owners.insert(id, idx);
+            }
+        }
+    }
+    for (idx, message) in visible.iter().enumerate() {
+        for block in content_blocks(message, "tool_result") {
+            let owner = block
+                .get("tool_use_id")
+                .and_then(|v| v.as_str())
+                .and_then(|id| tool_use_owners.get(id));
+            if let Some(&owner_idx) = owner {
+                if owner_idx != idx {
+                    edges.push(SessionGraphEdge {
+                        source: visible[owner_idx].uuid.clone(),
+                        target: message.uuid.clone(),
+                        kind: "tool_result".to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    // Assign depth and lane with a depth-first walk: the first child continues
+    // its parent's lane, every other branch opens a new lane
+    let root_indices: Vec<usize> = (0..visible.len())
+        .filter(|&i| parents[i].is_none())
+        .collect();
+    let mut depths = vec![0usize; visible.len()];
+    let mut lanes = vec![0usize; visible.len()];
+    let mut visited = vec![false; visible.len()];
+    let mut next_lane = 0usize;
+
+    let mut start_points = root_indices.clone();
+    // Anything unreachable from a root is part of a cycle; treat it as a root
+    start_points.extend((0..visible.len()).filter(|&i| parents[i].is_some()));
+
+    for start in start_points {
+        if visited[start] {
+            continue;
+        }
+        let mut stack: Vec<(usize, usize, Option<usize>)> = vec![(start, 0, None)];
+        while let Some((idx, depth, lane)) = stack.pop() {
+            if visited[idx] {
+                continue;
+            }
+            visited[idx] = true;
+            let lane = lane.unwrap_or_else(|| {
+                next_lane += 1;
+                next_lane - 1
+            });
+            depths[idx] = depth;
+            lanes[idx] = lane;
+
+            for (position, &child) in children[idx].iter().enumerate().rev() {
+                let child_lane = (position == 0).then_some(lane);
+                stack.push((child, depth + 1, child_lane));
+            }
+        }
+    }
+
+    let mut nodes: Vec<SessionGraphNode> = visible
+        .iter()
+        .enumerate()
+        .map(|(idx, message)| SessionGraphNode {
+            id: message.uuid.clone(),
+            message_type: message.message_type.clone(),
+            label: build_label(message),
+            timestamp: message.timestamp.clone(),
+            is_sidechain: message.is_sidechain == Some(true),
+            tool_names: content_blocks(message, "tool_use")
+                .filter_map(|b| b.get("name").and_then(|v| v.as_str()))
+                .map(String::from)
+                .collect(),
+            depth: depths[idx],
+            lane: lanes[idx],
+        })
+        .collect();
+    nodes.sort_by_key(|n| (n.depth, n.lane));
+
+    SessionGraph {
+        session_id: session_id.to_string(),
+        max_depth: depths.iter().copied().max().unwrap_or(0),
+        lane_count: next_lane,
+        root_ids: root_indices
+            .iter()
+            .map(|&i| visible[i].uuid.clone())
+            .collect(),
+        nodes,
+        edges,
+    }
+}
+
+#[tauri::command]
+pub async fn get_session_graph(
+    session_id: String,
+    project_path: String,
+    repair: Option<bool>,
+) -> Result<SessionGraph, AppError> {
+    let _timer = OperationTimer::start("get_session_graph");
+
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+    let messages = read_session_messages(&session_path)?;
+
+    if repair.unwrap_or(false) {
+        Ok(build_repaired_session_graph(&session_id, messages))
+    } else {
+        Ok(build_session_graph(&session_id, &messages))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MessageBuilder;
+    use serde_json::json;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn edge_kinds(graph: &SessionGraph, kind: &str) -> Vec<(String, String)> {
+        graph
+            .edges
+            .iter()
+            .filter(|e| e.kind == kind)
+            .map(|e| (e.source.clone(), e.target.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_branching_assigns_lanes() {
+        let messages = vec![
+            MessageBuilder::user().with_uuid("root").build(),
+            MessageBuilder::assistant()
+                .with_uuid("a")
+                .with_parent_uuid("root")
+                .build(),
+            MessageBuilder::assistant()
+                .with_uuid("b")
+                .with_parent_uuid("root")
+                .build(),
+            MessageBuilder::user()
+                .with_uuid("a2")
+                .with_parent_uuid("a")
+                .build(),
+        ];
+
+        let graph = build_session_graph("s1", &messages);
+        let node = |id: &str| graph.nodes.iter().find(|n| n.id == id).unwrap();
+
+        assert_eq!(graph.root_ids, vec!["root"]);
+        assert_eq!(graph.max_depth, 2);
+        assert_eq!(graph.lane_count, 2);
+        assert_eq!((node("root").depth, node("root").lane), (0, 0));
+        assert_eq!((node("a").depth, node("a").lane), (1, 0));
+        assert_eq!((node("a2").depth, node("a2").lane), (2, 0));
+        assert_eq!((node("b").depth, node("b").lane), (1, 1));
+        assert_eq!(edge_kinds(&graph, "parent").len(), 3);
+    }
+
+    #[test]
+    fn test_tool_result_and_sidechain_edges() {
+        let mut sidechain = MessageBuilder::user()
+            .with_uuid("side")
+            .with_parent_uuid("call")
+            .build();
+        sidechain.is_sidechain = Some(true);
+
+        let messages = vec![
+            MessageBuilder::assistant()
+                .with_uuid("call")
+                .with_content(json!([
+                    {"type": "tool_use", "id": "toolu_1", "name": "Read", "input": {}}
+                ]))
+                .build(),
+            sidechain,
+            MessageBuilder::user()
+                .with_uuid("result")
+                .with_parent_uuid("call")
+                .with_content(json!([
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": "ok"}
+                ]))
+                .build(),
+        ];
+
+        let graph = build_session_graph("s1", &messages);
+
+        assert_eq!(
+            edge_kinds(&graph, "tool_result"),
+            vec![("call".to_string(), "result".to_string())]
+        );
+        assert_eq!(
+            edge_kinds(&graph, "sidechain"),
+            vec![("call".to_string(), "side".to_string())]
+        );
+        let call = graph.nodes.iter().find(|n| n.id == "call").unwrap();
+        assert_eq!(call.tool_names, vec!["Read"]);
+        assert_eq!(call.label, "[tool_use: Read]");
+    }
+
+    #[test]
+    fn test_system_messages_are_bridged() {
+        let messages = vec![
+            MessageBuilder::user().with_uuid("u1").build(),
+            MessageBuilder::new()
+                .with_type("system")
+                .with_uuid("sys")
+                .with_parent_uuid("u1")
+                .build(),
+            MessageBuilder::assistant()
+                .with_uuid("a1")
+                .with_parent_uuid("sys")
+                .build(),
+        ];
+
+        let graph = build_session_graph("s1", &messages);
+
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(
+            edge_kinds(&graph, "parent"),
+            vec![("u1".to_string(), "a1".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_repaired_graph_marks_rebuilt_links() {
+        let messages = vec![
+            MessageBuilder::user()
+                .with_uuid("u1")
+                .with_timestamp("2025-01-01T00:00:00Z")
+                .build(),
+            MessageBuilder::new()
+                .with_type("system")
+                .with_uuid("sys")
+                .with_parent_uuid("lost")
+                .with_timestamp("2025-01-01T00:00:01Z")
+                .build(),
+            MessageBuilder::assistant()
+                .with_uuid("a1")
+                .with_parent_uuid("sys")
+                .with_timestamp("2025-01-01T00:00:02Z")
+                .build(),
+        ];
+
+        let plain = build_session_graph("s1", &messages);
+        assert_eq!(plain.root_ids, vec!["u1", "a1"]);
+
+        let graph = build_repaired_session_graph("s1", messages);
+        assert_eq!(graph.root_ids, vec!["u1"]);
+        assert_eq!(
+            edge_kinds(&graph, "repaired"),
+            vec![("u1".to_string(), "a1".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_label_is_single_line_and_truncated() {
+        let long_text = format!("line one\nline two {}", "x".repeat(200));
+        let message = MessageBuilder::user().with_text_content(&long_text).build();
+
+        let label = build_label(&message);
+        assert!(label.starts_with("line one line two"));
+        assert!(label.ends_with("..."));
+        assert_eq!(label.chars().count(), MAX_LABEL_CHARS + 3);
+    }
+
+    #[tokio::test]
+    async fn test_get_session_graph_rejects_path_traversal() {
+        let temp = TempDir::new().unwrap();
+        let project_path = temp.path().to_string_lossy().to_string();
+
+        let result = get_session_graph("../secret".to_string(), project_path, None).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_session_graph_from_file() {
+        let temp = TempDir::new().unwrap();
+        let mut file = std::fs::File::create(temp.path().join("s1.jsonl")).unwrap();
+        writeln!(
+            file,
+            "{}",
+            MessageBuilder::user()
+                .with_uuid("u1")
+                .with_session_id("s1")
+                .to_jsonl()
+        )
+        .unwrap();
+        writeln!(
+            file,
+            "{}",
+            MessageBuilder::assistant()
+                .with_uuid("a1")
+                .with_parent_uuid("u1")
+                .with_session_id("s1")
+                .to_jsonl()
+        )
+        .unwrap();
+
+        let graph = get_session_graph(
+            "s1".to_string(),
+            temp.path().to_string_lossy().to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(graph.session_id, "s1");
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.root_ids, vec!["u1"]);
+    }
+}
@@ -1,17 +1,58 @@
 //! Session loading functions
 
-use crate::models::{ClaudeMessage, ClaudeSession, MessagePage, RawLogEntry};
-use crate::utils::{extract_project_name, find_line_ranges, find_line_starts};
+use super::responses::merge_response_parts;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::counting::{count_rules, CountRules};
+use crate::derived::apply_derived_fields;
+use crate::errors::AppError;
+use crate::freshness::{self, FileChange};
+use crate::io_limit::{acquire_file_permit, low_memory};
+use crate::models::{
+    ClaudeMessage, ClaudeSession, MessagePage, RawLogEntry, SessionMetadata, SessionRefreshedEvent,
+};
+use crate::utils::{
+    collect_jsonl_files_async, display_path, extract_project_name, file_name_string,
+    find_line_ranges, find_line_starts, long_path, stable_line_id,
+};
 use chrono::{DateTime, Utc};
 use memmap2::Mmap;
 use rayon::prelude::*;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
 use std::time::SystemTime;
-use uuid::Uuid;
-use walkdir::WalkDir;
+
+/// IDs of the sessions archived in the session metadata
+static ARCHIVED_SESSIONS: RwLock<BTreeSet<String>> = RwLock::new(BTreeSet::new());
+
+/// Apply the archived flags of the session metadata
+pub fn set_archived_sessions<'a>(
+    sessions: impl IntoIterator<Item = (&'a String, &'a SessionMetadata)>,
+) {
+    let archived = sessions
+        .into_iter()
+        .filter(|(_, session)| session.archived_at.is_some())
+        .map(|(session_id, _)| session_id.clone())
+        .collect();
+    if let Ok(mut current) = ARCHIVED_SESSIONS.write() {
+        *current = archived;
+    }
+}
+
+/// Drop archived sessions, matched by listing ID or actual session ID
+fn remove_archived_sessions(sessions: &mut Vec<ClaudeSession>) {
+    let Ok(archived) = ARCHIVED_SESSIONS.read() else {
+        return;
+    };
+    if !archived.is_empty() {
+        sessions.retain(|session| {
+            !archived.contains(&session.session_id)
+                && !archived.contains(&session.actual_session_id)
+        });
+    }
+}
 
 /// Cache entry for a single session file (supports incremental parsing)
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
@@ -30,6 +71,8 @@ struct CachedSessionMetadata {
     has_tool_use: bool,
     /// Whether errors were detected (for incremental updates)
     has_errors: bool,
+    /// Counting rules the message count was computed with
+    count_rules: u8,
 }
 
 /// Session metadata cache file structure
@@ -41,7 +84,7 @@ struct SessionMetadataCache {
     entries: HashMap<String, CachedSessionMetadata>,
 }
 
-const CACHE_VERSION: u32 = 4;
+const CACHE_VERSION: u32 = 6;
 
 /// Get the cache file path for a project
 fn get_cache_path(project_path: &str) -> PathBuf {
@@ -71,9 +114,14 @@ fn save_cache(project_path: &str, cache: &SessionMetadataCache) {
 
 /// Get file modification time as Unix timestamp
 fn get_modified_time(path: &PathBuf) -> Option<u64> {
-    path.metadata()
+    path.metadata().ok().as_ref().and_then(modified_secs)
+}
+
+/// Modification time of file metadata as Unix timestamp
+fn modified_secs(metadata: &fs::Metadata) -> Option<u64> {
+    metadata
+        .modified()
         .ok()
-        .and_then(|m| m.modified().ok())
         .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
         .map(|d| d.as_secs())
 }
@@ -90,6 +138,8 @@ struct IncrementalParseState {
     start_offset: u64,
     /// Previous message count
     message_count: usize,
+    /// Previous raw entry count
+    raw_message_count: usize,
     /// Previous sidechain count
     sidechain_count: usize,
     /// Previous last timestamp
@@ -162,6 +212,8 @@ struct QuickLineClassifier {
 struct SessionExtractionResult {
     session: ClaudeSession,
     sidechain_count: usize,
+    /// Counting rules applied to `session.message_count`
+    count_rules: CountRules,
     /// Final byte offset after parsing (for incremental updates)
     final_byte_offset: u64,
     /// Whether `tool_use` was detected
@@ -173,7 +225,8 @@ struct SessionExtractionResult {
 /// Fast session metadata extraction with two-phase parsing:
 /// Phase 1: Extract essential metadata from first ~50 lines
 /// Phase 2: Count remaining messages with minimal parsing
-/// Always extracts total count (without sidechain filtering) for caching purposes
+/// Always extracts total count (without sidechain filtering) for caching purposes,
+/// unless the counting rules exclude sidechains altogether
 fn extract_session_metadata_from_file(file_path: &PathBuf) -> Option<SessionExtractionResult> {
     extract_session_metadata_internal(file_path, None)
 }
@@ -202,13 +255,16 @@ fn extract_session_metadata_internal(
         })
         .unwrap_or_else(|| Utc::now().to_rfc3339());
 
-    let mut file = fs::File::open(file_path).ok()?;
-    let file_path_str = file_path.to_string_lossy().to_string();
+    let _permit = acquire_file_permit();
+    let mut file = fs::File::open(long_path(file_path)).ok()?;
+    let file_path_str = display_path(file_path);
+    let rules = count_rules();
 
     // Initialize from incremental state or start fresh
     let (
         start_offset,
         mut message_count,
+        mut raw_message_count,
         mut sidechain_count,
         mut first_timestamp,
         mut last_timestamp,
@@ -221,6 +277,7 @@ fn extract_session_metadata_internal(
         (
             state.start_offset,
             state.message_count,
+            state.raw_message_count,
             state.sidechain_count,
             state.first_timestamp.clone(),
             state.last_timestamp.clone(),
@@ -232,7 +289,7 @@ fn extract_session_metadata_internal(
         )
     } else {
         (
-            0u64, 0usize, 0usize, None, None, None, None, false, false, None,
+            0u64, 0usize, 0usize, 0usize, None, None, None, None, false, false, None,
         )
     };
 
@@ -274,23 +331,23 @@ fn extract_session_metadata_internal(
                     continue;
                 }
 
-                // Skip system message types
-                if is_system_message_type(&entry.message_type) {
-                    continue;
-                }
-
                 // Need timestamp or session_id to be valid
                 if entry.session_id.is_none() && entry.timestamp.is_none() {
                     continue;
                 }
+                raw_message_count += 1;
 
-                // Skip meta messages (internal/command-related messages)
-                if entry.is_meta.unwrap_or(false) {
+                // Skip system, progress and meta entries (per counting rules)
+                let is_sidechain = entry.is_sidechain.unwrap_or(false);
+                if !rules.counts(
+                    &entry.message_type,
+                    entry.is_meta.unwrap_or(false),
+                    is_sidechain,
+                ) {
                     continue;
                 }
 
                 // Track sidechain messages separately
-                let is_sidechain = entry.is_sidechain.unwrap_or(false);
                 if is_sidechain {
                     sidechain_count += 1;
                 }
@@ -353,10 +410,11 @@ fn extract_session_metadata_internal(
                     }
                 }
 
-                // Check if we have all essential metadata
+                // Check if we have all essential metadata (the first prompt is
+                // kept even when a summary was found)
                 if actual_session_id.is_some()
                     && first_timestamp.is_some()
-                    && (first_user_content.is_some() || session_summary.is_some())
+                    && first_user_content.is_some()
                 {
                     metadata_complete = true;
                 }
@@ -377,23 +435,23 @@ fn extract_session_metadata_internal(
                 continue;
             }
 
-            // Skip system message types
-            if is_system_message_type(&classifier.message_type) {
-                continue;
-            }
-
             // Need timestamp or session_id to be valid
             if classifier.session_id.is_none() && classifier.timestamp.is_none() {
                 continue;
             }
+            raw_message_count += 1;
 
-            // Skip meta messages (internal/command-related messages)
-            if classifier.is_meta.unwrap_or(false) {
+            // Skip system, progress and meta entries (per counting rules)
+            let is_sidechain = classifier.is_sidechain.unwrap_or(false);
+            if !rules.counts(
+                &classifier.message_type,
+                classifier.is_meta.unwrap_or(false),
+                is_sidechain,
+            ) {
                 continue;
             }
 
             // Track sidechain messages separately
-            let is_sidechain = classifier.is_sidechain.unwrap_or(false);
             if is_sidechain {
                 sidechain_count += 1;
             }
@@ -426,13 +484,11 @@ fn extract_session_metadata_internal(
 
     let raw_project_name = file_path
         .parent()
-        .and_then(|p| p.file_name())
-        .and_then(|n| n.to_str())
-        .unwrap_or("Unknown")
-        .to_string();
+        .and_then(file_name_string)
+        .unwrap_or_else(|| "Unknown".to_string());
 
     let project_name = extract_project_name(&raw_project_name);
-    let final_summary = session_summary.or(first_user_content);
+    let final_summary = session_summary.or_else(|| first_user_content.clone());
 
     Some(SessionExtractionResult {
         session: ClaudeSession {
@@ -441,6 +497,7 @@ fn extract_session_metadata_internal(
             file_path: file_path_str,
             project_name,
             message_count,
+            raw_message_count,
             first_message_time: first_timestamp.unwrap_or_else(|| Utc::now().to_rfc3339()),
             last_message_time: last_timestamp
                 .clone()
@@ -449,8 +506,12 @@ fn extract_session_metadata_internal(
             has_tool_use,
             has_errors,
             summary: final_summary,
+            first_user_message: first_user_content,
+            derived_fields: BTreeMap::new(),
+            source: None,
         },
         sidechain_count,
+        count_rules: rules,
         final_byte_offset: file_size,
         has_tool_use,
         has_errors,
@@ -468,7 +529,7 @@ const SYSTEM_MESSAGE_TYPES: [&str; 4] = [
 
 /// Check if a message type is a system type (should be excluded)
 #[inline]
-fn is_system_message_type(message_type: &str) -> bool {
+pub(crate) fn is_system_message_type(message_type: &str) -> bool {
     SYSTEM_MESSAGE_TYPES.contains(&message_type)
 }
 
@@ -504,7 +565,7 @@ fn classify_line(line: &str, exclude_sidechain: bool) -> bool {
 }
 
 // Helper to check if text is a genuine user message (not system-generated)
-fn is_genuine_user_text(text: &str) -> bool {
+pub(crate) fn is_genuine_user_text(text: &str) -> bool {
     let trimmed = text.trim();
     if trimmed.is_empty() {
         return false;
@@ -538,7 +599,7 @@ fn truncate_text(text: &str, max_chars: usize) -> String {
 }
 
 // Extract text from message content, filtering out system messages
-fn extract_user_text(content: &serde_json::Value) -> Option<String> {
+pub(super) fn extract_user_text(content: &serde_json::Value) -> Option<String> {
     match content {
         serde_json::Value::String(text) => {
             if is_genuine_user_text(text) {
@@ -565,6 +626,58 @@ fn extract_user_text(content: &serde_json::Value) -> Option<String> {
     }
 }
 
+/// Build the cache entry of a freshly parsed file
+fn build_cache_entry(
+    path: &PathBuf,
+    result: Option<&SessionExtractionResult>,
+) -> CachedSessionMetadata {
+    CachedSessionMetadata {
+        modified_time: get_modified_time(path).unwrap_or(0),
+        file_size: get_file_size(path).unwrap_or(0),
+        last_byte_offset: result.map_or(0, |r| r.final_byte_offset),
+        session: result.map(|r| r.session.clone()),
+        sidechain_count: result.map_or(0, |r| r.sidechain_count),
+        has_tool_use: result.is_some_and(|r| r.has_tool_use),
+        has_errors: result.is_some_and(|r| r.has_errors),
+        count_rules: result.map_or_else(count_rules, |r| r.count_rules).bits(),
+    }
+}
+
+/// Detect a session file modified outside the app since its data was last
+/// served; if so, reindex its cache entry and notify the frontend
+fn refresh_if_stale(session_path: &str) {
+    let path = PathBuf::from(session_path);
+    let Some(change) = freshness::check_and_record(&path) else {
+        return;
+    };
+
+    let mut session = None;
+    if let Some(project_path) = path.parent().map(|p| p.to_string_lossy().to_string()) {
+        let mut cache = load_cache(&project_path);
+        // Only projects that were already scanned have a cache to fix
+        if cache.version == CACHE_VERSION {
+            if change == FileChange::Deleted {
+                cache.entries.remove(session_path);
+            } else {
+                let entry =
+                    build_cache_entry(&path, extract_session_metadata_from_file(&path).as_ref());
+                session.clone_from(&entry.session);
+                if let Some(session) = session.as_mut() {
+                    apply_derived_fields(std::slice::from_mut(session));
+                }
+                cache.entries.insert(session_path.to_string(), entry);
+            }
+            save_cache(&project_path, &cache);
+        }
+    }
+
+    freshness::notify_refreshed(&SessionRefreshedEvent {
+        file_path: session_path.to_string(),
+        change: change.as_str().to_string(),
+        session,
+    });
+}
+
 /// Categorization of how to handle a file
 enum FileParseStrategy {
     /// Use cached data as-is (file unchanged)
@@ -575,36 +688,51 @@ enum FileParseStrategy {
     FullParse(PathBuf),
 }
 
+/// Sessions of a project; archived sessions are left out unless `include_archived`
 #[tauri::command]
 pub async fn load_project_sessions(
     project_path: String,
     exclude_sidechain: Option<bool>,
-) -> Result<Vec<ClaudeSession>, String> {
+    include_archived: Option<bool>,
+) -> Result<Vec<ClaudeSession>, AppError> {
+    let _timer = OperationTimer::start("load_project_sessions");
+
+    // Listing is IO-bound and runs on the async runtime; cache lookups and
+    // parsing are CPU-bound and run on the blocking pool
+    let files = collect_jsonl_files_async(Path::new(&project_path)).await;
+    let exclude = exclude_sidechain.unwrap_or(false);
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let mut sessions = build_project_sessions(&project_path, exclude, files);
+        if include_archived != Some(true) {
+            remove_archived_sessions(&mut sessions);
+        }
+        sessions
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?)
+}
+
+/// Sessions of a project's session files (with their metadata), reusing and
+/// updating the project's metadata cache
+fn build_project_sessions(
+    project_path: &str,
+    exclude: bool,
+    files: Vec<(PathBuf, fs::Metadata)>,
+) -> Vec<ClaudeSession> {
     #[cfg(debug_assertions)]
     let start_time = std::time::Instant::now();
 
-    let exclude = exclude_sidechain.unwrap_or(false);
+    let rules = count_rules().bits();
 
     // 1. Load existing cache
-    let mut cache = load_cache(&project_path);
+    let mut cache = load_cache(project_path);
     let mut cache_updated = false;
 
-    // 2. Collect all JSONL file paths
-    let file_paths: Vec<PathBuf> = WalkDir::new(&project_path)
-        .into_iter()
-        .filter_map(std::result::Result::ok)
-        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
-        .map(|e| e.path().to_path_buf())
-        .collect();
-
     #[cfg(debug_assertions)]
-    eprintln!(
-        "🔍 load_project_sessions: processing {} files",
-        file_paths.len()
-    );
+    eprintln!("🔍 load_project_sessions: processing {} files", files.len());
 
-    // 3. Categorize files into: cached, incremental, full parse
-    let mut strategies: Vec<FileParseStrategy> = Vec::with_capacity(file_paths.len());
+    // 2. Categorize files into: cached, incremental, full parse
+    let mut strategies: Vec<FileParseStrategy> = Vec::with_capacity(files.len());
     #[cfg(debug_assertions)]
     let mut cache_hit_count = 0usize;
     #[cfg(debug_assertions)]
@@ -612,12 +740,17 @@ pub async fn load_project_sessions(
     #[cfg(debug_assertions)]
     let mut full_parse_count = 0usize;
 
-    for path in &file_paths {
-        let path_str = path.to_string_lossy().to_string();
-        let current_size = get_file_size(path).unwrap_or(0);
-        let current_mtime = get_modified_time(path);
+    for (path, metadata) in &files {
+        let path_str = display_path(path);
+        let current_size = metadata.len();
+        let current_mtime = modified_secs(metadata);
 
-        if let Some(cached) = cache.entries.get(&path_str) {
+        // Counts cached under other counting rules need a full reparse
+        if let Some(cached) = cache
+            .entries
+            .get(&path_str)
+            .filter(|cached| cached.count_rules == rules)
+        {
             // Check if file hasn't changed at all
             if Some(cached.modified_time) == current_mtime && cached.file_size == current_size {
                 if let Some(ref session) = cached.session {
@@ -645,6 +778,7 @@ pub async fn load_project_sessions(
                         IncrementalParseState {
                             start_offset: cached.last_byte_offset,
                             message_count: session.message_count,
+                            raw_message_count: session.raw_message_count,
                             sidechain_count: cached.sidechain_count,
                             last_timestamp: Some(session.last_message_time.clone()),
                             has_tool_use: cached.has_tool_use,
@@ -652,7 +786,7 @@ pub async fn load_project_sessions(
                             session_id: Some(session.actual_session_id.clone()),
                             first_timestamp: Some(session.first_message_time.clone()),
                             summary: session.summary.clone(),
-                            first_user_content: session.summary.clone(),
+                            first_user_content: session.first_user_message.clone(),
                         },
                     ));
                     continue;
@@ -673,7 +807,7 @@ pub async fn load_project_sessions(
         "📦 Cache hits: {cache_hit_count}, incremental parsing: {incremental_count}, full parsing: {full_parse_count}"
     );
 
-    // 4. Process strategies in parallel
+    // 3. Process strategies in parallel
     let results: Vec<(FileParseStrategy, Option<SessionExtractionResult>)> = strategies
         .into_par_iter()
         .map(|strategy| match &strategy {
@@ -689,7 +823,7 @@ pub async fn load_project_sessions(
         })
         .collect();
 
-    // 5. Process results and update cache
+    // 4. Process results and update cache
     let mut sessions: Vec<ClaudeSession> = Vec::with_capacity(results.len());
 
     for (strategy, result_opt) in results {
@@ -706,33 +840,9 @@ pub async fn load_project_sessions(
                 sessions.push(session_clone);
             }
             FileParseStrategy::Incremental(path, _) | FileParseStrategy::FullParse(path) => {
-                let path_str = path.to_string_lossy().to_string();
-                let mtime = get_modified_time(&path).unwrap_or(0);
-                let file_size = get_file_size(&path).unwrap_or(0);
-
-                let (session_for_cache, sidechain_count, byte_offset, has_tool_use, has_errors) =
-                    match &result_opt {
-                        Some(result) => (
-                            Some(result.session.clone()),
-                            result.sidechain_count,
-                            result.final_byte_offset,
-                            result.has_tool_use,
-                            result.has_errors,
-                        ),
-                        None => (None, 0, 0, false, false),
-                    };
-
                 cache.entries.insert(
-                    path_str,
-                    CachedSessionMetadata {
-                        modified_time: mtime,
-                        file_size,
-                        last_byte_offset: byte_offset,
-                        session: session_for_cache,
-                        sidechain_count,
-                        has_tool_use,
-                        has_errors,
-                    },
+                    display_path(&path),
+                    build_cache_entry(&path, result_opt.as_ref()),
                 );
                 cache_updated = true;
 
@@ -751,10 +861,13 @@ pub async fn load_project_sessions(
         }
     }
 
-    // 6. Sort
+    // Custom columns from the `derivedFields` setting
+    apply_derived_fields(&mut sessions);
+
+    // 5. Sort
     sessions.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
 
-    // 8. Summary propagation
+    // 6. Summary propagation
     let mut summary_map: HashMap<String, String> = HashMap::new();
 
     for session in &sessions {
@@ -778,10 +891,15 @@ pub async fn load_project_sessions(
         }
     }
 
-    // 9. Save updated cache
+    // 7. Drop entries of files deleted since the last scan
+    let entry_count = cache.entries.len();
+    cache.entries.retain(|path, _| Path::new(path).is_file());
+    cache_updated |= cache.entries.len() != entry_count;
+
+    // 8. Save updated cache
     if cache_updated {
         cache.version = CACHE_VERSION;
-        save_cache(&project_path, &cache);
+        save_cache(project_path, &cache);
     }
 
     #[cfg(debug_assertions)]
@@ -794,12 +912,17 @@ pub async fn load_project_sessions(
         );
     }
 
-    Ok(sessions)
+    for (path, _) in &files {
+        freshness::check_and_record(path);
+    }
+
+    sessions
 }
 
 /// Parse a single line into `ClaudeMessage` (with line number)
 #[allow(dead_code)] // Keep for fallback and tests
 fn parse_line_to_message(
+    session_path: &Path,
     line_num: usize,
     line: &str,
     include_summary: bool,
@@ -820,7 +943,9 @@ fn parse_line_to_message(
             return None;
         }
         let summary_text = log_entry.summary?;
-        let uuid = log_entry.uuid.unwrap_or_else(|| Uuid::new_v4().to_string());
+        let uuid = log_entry.uuid.unwrap_or_else(|| {
+            stable_line_id(log_entry.session_id.as_deref(), session_path, line_num)
+        });
 
         return Some(ClaudeMessage {
             uuid,
@@ -857,6 +982,7 @@ fn parse_line_to_message(
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            git_branch: None,
         });
     }
 
@@ -867,7 +993,7 @@ fn parse_line_to_message(
 
     let uuid = log_entry
         .uuid
-        .unwrap_or_else(|| format!("{}-line-{}", Uuid::new_v4(), line_num + 1));
+        .unwrap_or_else(|| stable_line_id(log_entry.session_id.as_deref(), session_path, line_num));
 
     let (role, message_id, model, stop_reason, usage) = if let Some(ref msg) = log_entry.message {
         (
@@ -916,12 +1042,41 @@ fn parse_line_to_message(
         prevented_continuation: log_entry.prevented_continuation,
         compact_metadata: log_entry.compact_metadata,
         microcompact_metadata: log_entry.microcompact_metadata,
+        git_branch: log_entry.git_branch,
     })
 }
 
+/// Read all messages of a session file in file order (summaries excluded)
+#[allow(unsafe_code)] // Required for mmap performance optimization
+pub(crate) fn read_session_messages(session_path: &Path) -> Result<Vec<ClaudeMessage>, String> {
+    let _permit = acquire_file_permit();
+    let file = fs::File::open(long_path(session_path))
+        .map_err(|e| format!("Failed to open session file: {e}"))?;
+
+    // SAFETY: We're only reading the file, and the file handle is kept open
+    // for the duration of the mmap's lifetime. Session files are append-only.
+    let mmap = unsafe { Mmap::map(&file) }
+        .map_err(|e| format!("Failed to memory-map session file: {e}"))?;
+
+    Ok(parse_session_messages(session_path, &mmap))
+}
+
+/// All messages of a session file's content in file order (summaries excluded)
+pub(crate) fn parse_session_messages(session_path: &Path, data: &[u8]) -> Vec<ClaudeMessage> {
+    find_line_ranges(data)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(line_num, (start, end))| {
+            let mut line_bytes = data[start..end].to_vec();
+            parse_line_simd(session_path, line_num, &mut line_bytes, false)
+        })
+        .collect()
+}
+
 /// Parse a single line using simd-json for faster parsing
 /// Returns None if the line is empty or fails to parse
-fn parse_line_simd(
+pub(super) fn parse_line_simd(
+    session_path: &Path,
     line_num: usize,
     line: &mut [u8],
     include_summary: bool,
@@ -946,7 +1101,9 @@ fn parse_line_simd(
             return None;
         }
         let summary_text = log_entry.summary?;
-        let uuid = log_entry.uuid.unwrap_or_else(|| Uuid::new_v4().to_string());
+        let uuid = log_entry.uuid.unwrap_or_else(|| {
+            stable_line_id(log_entry.session_id.as_deref(), session_path, line_num)
+        });
 
         return Some(ClaudeMessage {
             uuid,
@@ -983,6 +1140,7 @@ fn parse_line_simd(
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            git_branch: None,
         });
     }
 
@@ -993,7 +1151,7 @@ fn parse_line_simd(
 
     let uuid = log_entry
         .uuid
-        .unwrap_or_else(|| format!("{}-line-{}", Uuid::new_v4(), line_num + 1));
+        .unwrap_or_else(|| stable_line_id(log_entry.session_id.as_deref(), session_path, line_num));
 
     let (role, message_id, model, stop_reason, usage) = if let Some(ref msg) = log_entry.message {
         (
@@ -1042,42 +1200,85 @@ fn parse_line_simd(
         prevented_continuation: log_entry.prevented_continuation,
         compact_metadata: log_entry.compact_metadata,
         microcompact_metadata: log_entry.microcompact_metadata,
+        git_branch: log_entry.git_branch,
     })
 }
 
+/// Load all messages of a session
+///
+/// With `merge_parts`, assistant entries of the same API response (streamed
+/// blocks and retries) are merged into one message.
 #[tauri::command]
-#[allow(unsafe_code)] // Required for mmap performance optimization
-pub async fn load_session_messages(session_path: String) -> Result<Vec<ClaudeMessage>, String> {
+pub async fn load_session_messages(
+    session_path: String,
+    merge_parts: Option<bool>,
+) -> Result<Vec<ClaudeMessage>, AppError> {
     #[cfg(debug_assertions)]
     let start_time = std::time::Instant::now();
 
-    // Use memory-mapped file for faster I/O
-    let file =
-        fs::File::open(&session_path).map_err(|e| format!("Failed to open session file: {e}"))?;
+    refresh_if_stale_async(&session_path).await?;
+    if low_memory() {
+        return Ok(tauri::async_runtime::spawn_blocking(move || {
+            stream_session_messages(Path::new(&session_path), merge_parts == Some(true))
+        })
+        .await
+        .map_err(|e| format!("Task join error: {e}"))??);
+    }
+    let data = read_session_file_async(Path::new(&session_path)).await?;
+    let messages = tauri::async_runtime::spawn_blocking(move || {
+        parse_session_data(Path::new(&session_path), &data, merge_parts == Some(true))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?;
 
-    // SAFETY: We're only reading the file, and the file handle is kept open
-    // for the duration of the mmap's lifetime. No concurrent modifications expected
-    // as session files are append-only by Claude.
-    let mmap = unsafe { Mmap::map(&file) }
-        .map_err(|e| format!("Failed to memory-map session file: {e}"))?;
+    #[cfg(debug_assertions)]
+    {
+        let elapsed = start_time.elapsed();
+        eprintln!(
+            "📤 [load_session_messages] {} messages, {}ms elapsed (simd-json)",
+            messages.len(),
+            elapsed.as_millis()
+        );
+    }
+
+    Ok(messages)
+}
+
+/// Run `refresh_if_stale` on the blocking pool, as it may reparse the file
+pub(crate) async fn refresh_if_stale_async(session_path: &str) -> Result<(), String> {
+    let session_path = session_path.to_string();
+    tauri::async_runtime::spawn_blocking(move || refresh_if_stale(&session_path))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))
+}
+
+/// Read a whole session file with async IO, so a file on a slow or network
+/// filesystem only holds up the command reading it
+pub(crate) async fn read_session_file_async(session_path: &Path) -> Result<Vec<u8>, AppError> {
+    tokio::fs::read(long_path(session_path))
+        .await
+        .map_err(|e| AppError::io("Failed to open session file", &e))
+}
 
+/// Parse the messages of a session file's content, in file order
+fn parse_session_data(session_path: &Path, data: &[u8], merge_parts: bool) -> Vec<ClaudeMessage> {
     // Find line boundaries efficiently using SIMD-accelerated memchr
-    let line_starts = find_line_starts(&mmap);
+    let line_starts = find_line_starts(data);
 
     // Parse lines in parallel using simd-json
     let mut messages: Vec<(usize, ClaudeMessage)> = line_starts
         .par_iter()
         .enumerate()
         .filter_map(|(line_num, &start)| {
-            let end = line_starts.get(line_num + 1).map_or(mmap.len(), |&e| e - 1);
+            let end = line_starts.get(line_num + 1).map_or(data.len(), |&e| e - 1);
             if start >= end {
                 return None;
             }
 
             // Create a mutable copy for simd-json (it requires mutable slice)
-            let mut line_bytes = mmap[start..end].to_vec();
+            let mut line_bytes = data[start..end].to_vec();
 
-            parse_line_simd(line_num, &mut line_bytes, false)
+            parse_line_simd(session_path, line_num, &mut line_bytes, false)
                 .filter(|msg| !is_system_message_type(&msg.message_type))
                 .map(|msg| (line_num, msg))
         })
@@ -1086,18 +1287,100 @@ pub async fn load_session_messages(session_path: String) -> Result<Vec<ClaudeMes
     // Sort by line number to maintain original order
     messages.sort_by_key(|(line_num, _)| *line_num);
     let messages: Vec<ClaudeMessage> = messages.into_iter().map(|(_, msg)| msg).collect();
+    if merge_parts {
+        merge_response_parts(messages)
+    } else {
+        messages
+    }
+}
 
-    #[cfg(debug_assertions)]
-    {
-        let elapsed = start_time.elapsed();
-        eprintln!(
-            "📤 [load_session_messages] {} messages, {}ms elapsed (simd-json + mmap optimized)",
-            messages.len(),
-            elapsed.as_millis()
-        );
+fn open_session_reader(session_path: &Path) -> Result<BufReader<fs::File>, String> {
+    fs::File::open(long_path(session_path))
+        .map(BufReader::new)
+        .map_err(|e| format!("Failed to open session file: {e}"))
+}
+
+fn read_line_into(reader: &mut impl BufRead, line: &mut Vec<u8>) -> Result<usize, String> {
+    line.clear();
+    reader
+        .read_until(b'\n', line)
+        .map_err(|e| format!("Failed to read session file: {e}"))
+}
+
+/// Same as `parse_session_data`, reading the file a line at a time instead
+/// of whole (low-memory mode)
+fn stream_session_messages(
+    session_path: &Path,
+    merge_parts: bool,
+) -> Result<Vec<ClaudeMessage>, String> {
+    let mut reader = open_session_reader(session_path)?;
+    let mut line = Vec::new();
+    let mut messages = Vec::new();
+    let mut line_num = 0;
+    while read_line_into(&mut reader, &mut line)? > 0 {
+        if let Some(message) = parse_line_simd(session_path, line_num, &mut line, false)
+            .filter(|msg| !is_system_message_type(&msg.message_type))
+        {
+            messages.push(message);
+        }
+        line_num += 1;
     }
+    Ok(if merge_parts {
+        merge_response_parts(messages)
+    } else {
+        messages
+    })
+}
 
-    Ok(messages)
+/// Same as `parse_message_page`, keeping only the byte offsets of the
+/// message lines in memory and reading back the lines of the page
+/// (low-memory mode)
+fn stream_message_page(
+    session_path: &Path,
+    offset: usize,
+    limit: usize,
+    exclude: bool,
+) -> Result<MessagePage, String> {
+    let mut reader = open_session_reader(session_path)?;
+    let mut line = Vec::new();
+    // (line index among non-empty lines, byte offset)
+    let mut message_lines: Vec<(usize, u64)> = Vec::new();
+    let mut line_index = 0;
+    let mut position = 0u64;
+    loop {
+        let read = read_line_into(&mut reader, &mut line)?;
+        if read == 0 {
+            break;
+        }
+        let content = line.strip_suffix(b"\n").unwrap_or(&line);
+        if !content.is_empty() {
+            if classify_line_fast(content, exclude) {
+                message_lines.push((line_index, position));
+            }
+            line_index += 1;
+        }
+        position += read as u64;
+    }
+
+    let total_count = message_lines.len();
+    let (start_idx, end_idx) = page_bounds(total_count, offset, limit);
+    let mut messages = Vec::with_capacity(end_idx - start_idx);
+    for &(line_index, position) in &message_lines[start_idx..end_idx] {
+        reader
+            .seek(SeekFrom::Start(position))
+            .map_err(|e| format!("Failed to read session file: {e}"))?;
+        read_line_into(&mut reader, &mut line)?;
+        if let Some(message) = parse_line_simd(session_path, line_index, &mut line, false) {
+            messages.push(message);
+        }
+    }
+
+    Ok(MessagePage {
+        next_offset: offset + messages.len(),
+        has_more: start_idx > 0,
+        messages,
+        total_count,
+    })
 }
 
 /// Fast line classifier for simd-json (mutable slice)
@@ -1130,37 +1413,78 @@ fn classify_line_fast(line: &[u8], exclude_sidechain: bool) -> bool {
 }
 
 #[tauri::command]
-#[allow(unsafe_code)] // Required for mmap performance optimization
 pub async fn load_session_messages_paginated(
     session_path: String,
     offset: usize,
     limit: usize,
     exclude_sidechain: Option<bool>,
-) -> Result<MessagePage, String> {
+) -> Result<MessagePage, AppError> {
+    let _timer = OperationTimer::start("load_session_messages_paginated");
     #[cfg(debug_assertions)]
     let start_time = std::time::Instant::now();
 
-    // Use memory-mapped file for faster I/O
-    let file =
-        fs::File::open(&session_path).map_err(|e| format!("Failed to open session file: {e}"))?;
+    refresh_if_stale_async(&session_path).await?;
+    let exclude = exclude_sidechain.unwrap_or(false);
+    if low_memory() {
+        return Ok(tauri::async_runtime::spawn_blocking(move || {
+            stream_message_page(Path::new(&session_path), offset, limit, exclude)
+        })
+        .await
+        .map_err(|e| format!("Task join error: {e}"))??);
+    }
+    let data = read_session_file_async(Path::new(&session_path)).await?;
+    let page = tauri::async_runtime::spawn_blocking(move || {
+        parse_message_page(Path::new(&session_path), &data, offset, limit, exclude)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?;
 
-    // SAFETY: We're only reading the file, and the file handle is kept open
-    // for the duration of the mmap's lifetime. No concurrent modifications expected
-    // as session files are append-only by Claude.
-    let mmap = unsafe { Mmap::map(&file) }
-        .map_err(|e| format!("Failed to memory-map session file: {e}"))?;
+    #[cfg(debug_assertions)]
+    {
+        let elapsed = start_time.elapsed();
+        eprintln!(
+            "📊 load_session_messages_paginated performance: {}/{} messages, {}ms elapsed (simd-json)",
+            page.messages.len(),
+            page.total_count,
+            elapsed.as_millis()
+        );
+    }
 
-    let exclude = exclude_sidechain.unwrap_or(false);
+    Ok(page)
+}
 
+/// Index range of the messages on a page, counting from the newest message
+fn page_bounds(total_count: usize, offset: usize, limit: usize) -> (usize, usize) {
+    let already_loaded = offset;
+    let remaining_messages = total_count.saturating_sub(already_loaded);
+    let messages_to_load = std::cmp::min(limit, remaining_messages);
+
+    if remaining_messages == 0 {
+        (0, 0)
+    } else {
+        let start = total_count - already_loaded - messages_to_load;
+        let end = total_count - already_loaded;
+        (start, end)
+    }
+}
+
+/// Parse one page of a session file's content, counting from the newest message
+fn parse_message_page(
+    session_path: &Path,
+    data: &[u8],
+    offset: usize,
+    limit: usize,
+    exclude: bool,
+) -> MessagePage {
     // Find line boundaries efficiently using SIMD-accelerated memchr
-    let line_ranges = find_line_ranges(&mmap);
+    let line_ranges = find_line_ranges(data);
 
     // Phase 1: Build valid line indices (fast classification)
     let valid_indices: Vec<usize> = line_ranges
         .iter()
         .enumerate()
         .filter(|(_, &(start, end))| {
-            let line = &mmap[start..end];
+            let line = &data[start..end];
             classify_line_fast(line, exclude)
         })
         .map(|(idx, _)| idx)
@@ -1170,25 +1494,15 @@ pub async fn load_session_messages_paginated(
 
     // Chat-style pagination: offset=0 means newest messages (at the end)
     if total_count == 0 {
-        return Ok(MessagePage {
+        return MessagePage {
             messages: vec![],
             total_count: 0,
             has_more: false,
             next_offset: 0,
-        });
+        };
     }
 
-    let already_loaded = offset;
-    let remaining_messages = total_count.saturating_sub(already_loaded);
-    let messages_to_load = std::cmp::min(limit, remaining_messages);
-
-    let (start_idx, end_idx) = if remaining_messages == 0 {
-        (0, 0)
-    } else {
-        let start = total_count - already_loaded - messages_to_load;
-        let end = total_count - already_loaded;
-        (start, end)
-    };
+    let (start_idx, end_idx) = page_bounds(total_count, offset, limit);
 
     // Phase 2: Parse only the target lines (parallel with simd-json)
     let target_indices = &valid_indices[start_idx..end_idx];
@@ -1196,8 +1510,8 @@ pub async fn load_session_messages_paginated(
         .par_iter()
         .filter_map(|&range_idx| {
             let (start, end) = line_ranges[range_idx];
-            let mut line_bytes = mmap[start..end].to_vec();
-            let msg = parse_line_simd(range_idx, &mut line_bytes, false)?;
+            let mut line_bytes = data[start..end].to_vec();
+            let msg = parse_line_simd(session_path, range_idx, &mut line_bytes, false)?;
             Some((range_idx, msg))
         })
         .collect();
@@ -1209,52 +1523,34 @@ pub async fn load_session_messages_paginated(
     let has_more = start_idx > 0;
     let next_offset = offset + messages.len();
 
-    #[cfg(debug_assertions)]
-    {
-        let elapsed = start_time.elapsed();
-        eprintln!("📊 load_session_messages_paginated performance: {}/{} messages, {}ms elapsed (simd-json + mmap)",
-                 messages.len(), total_count, elapsed.as_millis());
-    }
-
-    Ok(MessagePage {
+    MessagePage {
         messages,
         total_count,
         has_more,
         next_offset,
-    })
+    }
 }
 
 #[tauri::command]
-#[allow(unsafe_code)] // Required for mmap performance optimization
 pub async fn get_session_message_count(
     session_path: String,
     exclude_sidechain: Option<bool>,
-) -> Result<usize, String> {
-    // Use memory-mapped file for faster I/O
-    let file =
-        fs::File::open(&session_path).map_err(|e| format!("Failed to open session file: {e}"))?;
-
-    // SAFETY: We're only reading the file, and the file handle is kept open
-    // for the duration of the mmap's lifetime. No concurrent modifications expected
-    // as session files are append-only by Claude.
-    let mmap = unsafe { Mmap::map(&file) }
-        .map_err(|e| format!("Failed to memory-map session file: {e}"))?;
-
+) -> Result<usize, AppError> {
+    let data = read_session_file_async(Path::new(&session_path)).await?;
     let exclude = exclude_sidechain.unwrap_or(false);
 
-    // Find line boundaries and count valid lines using SIMD-accelerated memchr
-    let line_ranges = find_line_ranges(&mmap);
-
-    // Parallel counting with fast classification
-    let count: usize = line_ranges
-        .par_iter()
-        .filter(|&&(start, end)| {
-            let line = &mmap[start..end];
-            classify_line_fast(line, exclude)
-        })
-        .count();
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        // Find line boundaries and count valid lines using SIMD-accelerated memchr
+        let line_ranges = find_line_ranges(&data);
 
-    Ok(count)
+        // Parallel counting with fast classification
+        line_ranges
+            .par_iter()
+            .filter(|&&(start, end)| classify_line_fast(&data[start..end], exclude))
+            .count()
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?)
 }
 
 #[cfg(test)]
@@ -1300,7 +1596,7 @@ mod tests {
 
         let file_path = create_test_jsonl_file(&temp_dir, "test.jsonl", &content);
 
-        let result = load_session_messages(file_path.to_string_lossy().to_string()).await;
+        let result = load_session_messages(file_path.to_string_lossy().to_string(), None).await;
 
         assert!(result.is_ok());
         let messages = result.unwrap();
@@ -1322,7 +1618,7 @@ mod tests {
 
         let file_path = create_test_jsonl_file(&temp_dir, "test.jsonl", &content);
 
-        let result = load_session_messages(file_path.to_string_lossy().to_string()).await;
+        let result = load_session_messages(file_path.to_string_lossy().to_string(), None).await;
 
         assert!(result.is_ok());
         let messages = result.unwrap();
@@ -1339,7 +1635,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let file_path = create_test_jsonl_file(&temp_dir, "empty.jsonl", "");
 
-        let result = load_session_messages(file_path.to_string_lossy().to_string()).await;
+        let result = load_session_messages(file_path.to_string_lossy().to_string(), None).await;
 
         assert!(result.is_ok());
         assert!(result.unwrap().is_empty());
@@ -1357,7 +1653,7 @@ mod tests {
 
         let file_path = create_test_jsonl_file(&temp_dir, "test.jsonl", &content);
 
-        let result = load_session_messages(file_path.to_string_lossy().to_string()).await;
+        let result = load_session_messages(file_path.to_string_lossy().to_string(), None).await;
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap().len(), 2);
@@ -1365,10 +1661,13 @@ mod tests {
 
     #[tokio::test]
     async fn test_load_session_messages_file_not_found() {
-        let result = load_session_messages("/nonexistent/path/file.jsonl".to_string()).await;
+        let result = load_session_messages("/nonexistent/path/file.jsonl".to_string(), None).await;
 
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Failed to open session file"));
+        assert!(result
+            .unwrap_err()
+            .message()
+            .contains("Failed to open session file"));
     }
 
     #[tokio::test]
@@ -1384,7 +1683,7 @@ mod tests {
 
         let file_path = create_test_jsonl_file(&temp_dir, "test.jsonl", &content);
 
-        let result = load_session_messages(file_path.to_string_lossy().to_string()).await;
+        let result = load_session_messages(file_path.to_string_lossy().to_string(), None).await;
 
         // Should still succeed with valid messages
         assert!(result.is_ok());
@@ -1452,6 +1751,36 @@ mod tests {
         assert!(!page.has_more);
     }
 
+    #[test]
+    fn test_streaming_matches_in_memory_parsing() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = format!(
+            "{}\n\n{}\n{}\n\n{}\n{}",
+            create_sample_user_message("uuid-1", "session-1", "Hello"),
+            create_sample_summary_message("Summary"),
+            create_sample_assistant_message("uuid-2", "session-1", "Hi"),
+            create_sample_user_message("uuid-3", "session-1", "Again"),
+            create_sample_assistant_message("uuid-4", "session-1", "Sure"),
+        );
+        let file_path = create_test_jsonl_file(&temp_dir, "test.jsonl", &content);
+        let to_json = |messages: &[ClaudeMessage]| serde_json::to_value(messages).unwrap();
+
+        for merge_parts in [false, true] {
+            let expected = parse_session_data(&file_path, content.as_bytes(), merge_parts);
+            let streamed = stream_session_messages(&file_path, merge_parts).unwrap();
+            assert_eq!(to_json(&streamed), to_json(&expected));
+        }
+
+        for (offset, limit) in [(0, 2), (2, 2), (3, 10), (10, 10)] {
+            let expected = parse_message_page(&file_path, content.as_bytes(), offset, limit, false);
+            let streamed = stream_message_page(&file_path, offset, limit, false).unwrap();
+            assert_eq!(to_json(&streamed.messages), to_json(&expected.messages));
+            assert_eq!(streamed.total_count, expected.total_count);
+            assert_eq!(streamed.has_more, expected.has_more);
+            assert_eq!(streamed.next_offset, expected.next_offset);
+        }
+    }
+
     #[tokio::test]
     async fn test_load_session_messages_paginated_exclude_sidechain() {
         let temp_dir = TempDir::new().unwrap();
@@ -1543,7 +1872,7 @@ mod tests {
         file.write_all(content.as_bytes()).unwrap();
 
         let result =
-            load_project_sessions(temp_dir.path().to_string_lossy().to_string(), None).await;
+            load_project_sessions(temp_dir.path().to_string_lossy().to_string(), None, None).await;
 
         assert!(result.is_ok());
         let sessions = result.unwrap();
@@ -1551,6 +1880,71 @@ mod tests {
         assert_eq!(sessions[0].message_count, 2);
     }
 
+    #[tokio::test]
+    async fn test_load_project_sessions_reports_raw_and_adjusted_counts() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let content = [
+            create_sample_user_message("uuid-1", "session-1", "Hello"),
+            r#"{"type":"progress","sessionId":"session-1","timestamp":"2025-06-26T10:00:01Z"}"#
+                .to_string(),
+            r#"{"uuid":"uuid-m","type":"user","isMeta":true,"sessionId":"session-1","timestamp":"2025-06-26T10:00:02Z","message":{"role":"user","content":"<command-name>/clear</command-name>"}}"#
+                .to_string(),
+            create_sample_assistant_message("uuid-2", "session-1", "Hi!"),
+            create_sample_summary_message("Greeting"),
+        ]
+        .join("\n");
+        create_test_jsonl_file(&temp_dir, "test.jsonl", &content);
+
+        let sessions =
+            load_project_sessions(temp_dir.path().to_string_lossy().to_string(), None, None)
+                .await
+                .unwrap();
+        assert_eq!(sessions[0].message_count, 2);
+        assert_eq!(sessions[0].raw_message_count, 4);
+    }
+
+    #[tokio::test]
+    async fn test_externally_modified_session_is_reindexed() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_path = temp_dir.path().to_string_lossy().to_string();
+        let file_path = temp_dir.path().join("test.jsonl");
+        let session_path = file_path.to_string_lossy().to_string();
+
+        let mut file = File::create(&file_path).unwrap();
+        writeln!(
+            file,
+            "{}",
+            create_sample_user_message("uuid-1", "session-1", "Hello")
+        )
+        .unwrap();
+        let sessions = load_project_sessions(project_path.clone(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(sessions[0].message_count, 1);
+
+        writeln!(
+            file,
+            "{}",
+            create_sample_assistant_message("uuid-2", "session-1", "Hi!")
+        )
+        .unwrap();
+        load_session_messages(session_path.clone(), None)
+            .await
+            .unwrap();
+
+        let cache = load_cache(&project_path);
+        let cached = cache.entries[&session_path].session.as_ref().unwrap();
+        assert_eq!(cached.message_count, 2);
+
+        // Deleted files are dropped from the cache on the next scan
+        fs::remove_file(&file_path).unwrap();
+        load_project_sessions(project_path.clone(), None, None)
+            .await
+            .unwrap();
+        assert!(load_cache(&project_path).entries.is_empty());
+    }
+
     #[tokio::test]
     async fn test_load_project_sessions_with_summary() {
         let temp_dir = TempDir::new().unwrap();
@@ -1567,7 +1961,7 @@ mod tests {
         file.write_all(content.as_bytes()).unwrap();
 
         let result =
-            load_project_sessions(temp_dir.path().to_string_lossy().to_string(), None).await;
+            load_project_sessions(temp_dir.path().to_string_lossy().to_string(), None, None).await;
 
         assert!(result.is_ok());
         let sessions = result.unwrap();
@@ -1602,7 +1996,7 @@ mod tests {
         file2.write_all(content2.as_bytes()).unwrap();
 
         let result =
-            load_project_sessions(temp_dir.path().to_string_lossy().to_string(), None).await;
+            load_project_sessions(temp_dir.path().to_string_lossy().to_string(), None, None).await;
 
         assert!(result.is_ok());
         let sessions = result.unwrap();
@@ -1622,16 +2016,20 @@ mod tests {
         file.write_all(content.as_bytes()).unwrap();
 
         // Without exclude
-        let result_all = load_project_sessions(temp_dir.path().to_string_lossy().to_string(), None)
-            .await
-            .unwrap();
+        let result_all =
+            load_project_sessions(temp_dir.path().to_string_lossy().to_string(), None, None)
+                .await
+                .unwrap();
         assert_eq!(result_all[0].message_count, 2);
 
         // With exclude
-        let result_filtered =
-            load_project_sessions(temp_dir.path().to_string_lossy().to_string(), Some(true))
-                .await
-                .unwrap();
+        let result_filtered = load_project_sessions(
+            temp_dir.path().to_string_lossy().to_string(),
+            Some(true),
+            None,
+        )
+        .await
+        .unwrap();
         assert_eq!(result_filtered[0].message_count, 1);
     }
 
@@ -1648,7 +2046,7 @@ mod tests {
         file.write_all(content.as_bytes()).unwrap();
 
         let result =
-            load_project_sessions(temp_dir.path().to_string_lossy().to_string(), None).await;
+            load_project_sessions(temp_dir.path().to_string_lossy().to_string(), None, None).await;
 
         assert!(result.is_ok());
         let sessions = result.unwrap();
@@ -1660,12 +2058,39 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
 
         let result =
-            load_project_sessions(temp_dir.path().to_string_lossy().to_string(), None).await;
+            load_project_sessions(temp_dir.path().to_string_lossy().to_string(), None, None).await;
 
         assert!(result.is_ok());
         assert!(result.unwrap().is_empty());
     }
 
+    #[tokio::test]
+    async fn test_load_project_sessions_hides_archived() {
+        let temp_dir = TempDir::new().unwrap();
+        for session_id in ["kept-session", "archived-listing-session"] {
+            let content = format!(
+                r#"{{"uuid":"uuid-1","sessionId":"{session_id}","timestamp":"2025-06-26T10:00:00Z","type":"user","message":{{"role":"user","content":"Hello"}}}}"#
+            );
+            fs::write(temp_dir.path().join(format!("{session_id}.jsonl")), content).unwrap();
+        }
+        let archived = SessionMetadata {
+            archived_at: Some("2025-07-01T00:00:00Z".to_string()),
+            ..SessionMetadata::default()
+        };
+        set_archived_sessions([(&"archived-listing-session".to_string(), &archived)]);
+
+        let path = temp_dir.path().to_string_lossy().to_string();
+        let listed = load_project_sessions(path.clone(), None, None)
+            .await
+            .unwrap();
+        let all = load_project_sessions(path, None, Some(true)).await.unwrap();
+        set_archived_sessions([]);
+
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].actual_session_id, "kept-session");
+        assert_eq!(all.len(), 2);
+    }
+
     #[tokio::test]
     async fn test_incremental_parsing_on_file_append() {
         use std::io::Write;
@@ -1681,9 +2106,10 @@ mod tests {
         std::fs::write(&file_path, initial_content).unwrap();
 
         // First load - creates cache
-        let result1 = load_project_sessions(temp_dir.path().to_string_lossy().to_string(), None)
-            .await
-            .unwrap();
+        let result1 =
+            load_project_sessions(temp_dir.path().to_string_lossy().to_string(), None, None)
+                .await
+                .unwrap();
         assert_eq!(result1.len(), 1);
         assert_eq!(result1[0].message_count, 2);
 
@@ -1697,9 +2123,10 @@ mod tests {
         drop(file);
 
         // Second load - should use incremental parsing
-        let result2 = load_project_sessions(temp_dir.path().to_string_lossy().to_string(), None)
-            .await
-            .unwrap();
+        let result2 =
+            load_project_sessions(temp_dir.path().to_string_lossy().to_string(), None, None)
+                .await
+                .unwrap();
         assert_eq!(result2.len(), 1);
         assert_eq!(result2[0].message_count, 4); // 2 original + 2 appended
         assert_eq!(result2[0].last_message_time, "2025-06-26T10:03:00Z");
@@ -1717,7 +2144,7 @@ mod tests {
         let mut file = File::create(&file_path).unwrap();
         file.write_all(content.as_bytes()).unwrap();
 
-        let result = load_session_messages(file_path.to_string_lossy().to_string()).await;
+        let result = load_session_messages(file_path.to_string_lossy().to_string(), None).await;
 
         assert!(result.is_ok());
         let messages = result.unwrap();
@@ -1739,7 +2166,7 @@ mod tests {
         let mut file = File::create(&file_path).unwrap();
         file.write_all(content.as_bytes()).unwrap();
 
-        let result = load_session_messages(file_path.to_string_lossy().to_string()).await;
+        let result = load_session_messages(file_path.to_string_lossy().to_string(), None).await;
 
         assert!(result.is_ok());
         let messages = result.unwrap();
@@ -1758,7 +2185,7 @@ mod tests {
         let mut file = File::create(&file_path).unwrap();
         file.write_all(content.as_bytes()).unwrap();
 
-        let result = load_session_messages(file_path.to_string_lossy().to_string()).await;
+        let result = load_session_messages(file_path.to_string_lossy().to_string(), None).await;
 
         assert!(result.is_ok());
         let messages = result.unwrap();
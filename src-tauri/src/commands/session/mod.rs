@@ -3,13 +3,64 @@
 //! This module contains all session-related Tauri commands organized into submodules:
 //! - `load`: Session and message loading functions
 //! - `search`: Message search functions
-//! - `edits`: File edit tracking and restore functions
+//! - `edits`: File edit tracking, restore and per-file session lookup
+//! - `find`: Find-next/previous hit positions within one session
+//! - `fuzzy`: Fuzzy quick-open matching of sessions
+//! - `graph`: Conversation graph (DAG) functions
+//! - `personas`: Sub-agent cast list of a session
+//! - `raw`: Original JSONL line lookup
+//! - `repair`: Display-only repair of broken parent chains
+//! - `responses`: Merging of assistant entries split across one API response
+//! - `summaries`: Summary entry indexing and leaf resolution
+//! - `summarize`: Session summaries generated by a configurable model backend
+//! - `tail`: Live streaming of raw lines appended to a session file
+//! - `tree`: Nested conversation tree with branches and sidechains
+//! - `tool_search`: Structured search over tool calls by name and input
+//! - `sources`: Chat histories of other tools (`HistorySource` adapters)
+//! - `aider`: Aider chat history files
+//! - `cursor`: Cursor chat and composer logs
+//! - `desktop`: Claude desktop app conversation exports
+//! - `shared`: Sessions imported from teammates' share bundles
 
+mod aider;
+mod cursor;
+mod desktop;
 mod edits;
+mod find;
+mod fuzzy;
+mod graph;
 mod load;
+mod personas;
+mod raw;
+mod repair;
+mod responses;
 mod search;
+mod shared;
+mod sources;
+mod summaries;
+mod summarize;
+mod tail;
+mod tool_search;
+mod tree;
 
 // Re-export all commands
+pub use aider::*;
+pub use cursor::*;
+pub use desktop::*;
 pub use edits::*;
+pub use find::*;
+pub use fuzzy::*;
+pub use graph::*;
 pub use load::*;
+pub use personas::*;
+pub use raw::*;
+pub use repair::*;
+pub use responses::*;
 pub use search::*;
+pub use shared::*;
+pub use sources::*;
+pub use summaries::*;
+pub use summarize::*;
+pub use tail::*;
+pub use tool_search::*;
+pub use tree::*;
@@ -0,0 +1,362 @@
+//! Sub-agent cast list of a session
+//!
+//! Sub-agents are launched through the Task tool, whose input names the agent
+//! type and carries the prompt. Each sidechain starts with that prompt, which
+//! is how its messages are attributed to a persona.
+
+use super::load::{is_system_message_type, read_session_messages};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ClaudeMessage, SessionCast, SessionPersona, SidechainSummary};
+use crate::utils::resolve_session_file;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Persona of the main conversation
+const MAIN_PERSONA: &str = "main";
+
+/// Persona of sidechains whose launching Task call could not be found
+const UNKNOWN_PERSONA: &str = "sidechain";
+
+/// Agent type used by the Task tool when none is given
+const DEFAULT_AGENT_TYPE: &str = "general-purpose";
+
+/// A Task tool call launching a sub-agent
+struct TaskInvocation<'a> {
+    agent_type: &'a str,
+    description: Option<&'a str>,
+    prompt: &'a str,
+}
+
+fn task_invocations(messages: &[ClaudeMessage]) -> Vec<TaskInvocation<'_>> {
+    messages
+        .iter()
+        .filter(|m| m.message_type == "assistant" && m.is_sidechain != Some(true))
+        .filter_map(|m| m.content.as_ref()?.as_array())
+        .flatten()
+        .filter(|block| block.get("type").and_then(|v| v.as_str()) == Some("tool_use"))
+        .filter(|block| {
+            matches!(
+                block.get("name").and_then(|v| v.as_str()),
+                Some("Task" | "Agent")
+            )
+        })
+        .filter_map(|block| {
+            let input = block.get("input")?;
+            let field = |name: &str| input.get(name).and_then(|v| v.as_str()).map(str::trim);
+            Some(TaskInvocation {
+                agent_type: field("subagent_type")
+                    .filter(|t| !t.is_empty())
+                    .unwrap_or(DEFAULT_AGENT_TYPE),
+                description: field("description"),
+                prompt: field("prompt").unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Text of a message: its string content or its first text block
+fn message_text(message: &ClaudeMessage) -> Option<&str> {
+    match message.content.as_ref()? {
+        serde_json::Value::String(text) => Some(text.trim()),
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .find(|b| b.get("type").and_then(|v| v.as_str()) == Some("text"))
+            .and_then(|b| b.get("text")?.as_str())
+            .map(str::trim),
+        _ => None,
+    }
+}
+
+/// First message of the sidechain `message` belongs to
+fn sidechain_root<'a>(
+    message: &'a ClaudeMessage,
+    by_uuid: &HashMap<&str, &'a ClaudeMessage>,
+) -> &'a ClaudeMessage {
+    let mut current = message;
+    // Bounded walk in case of malformed (cyclic) parent chains
+    for _ in 0..by_uuid.len() {
+        match current
+            .parent_uuid
+            .as_deref()
+            .and_then(|uuid| by_uuid.get(uuid))
+        {
+            Some(parent) if parent.is_sidechain == Some(true) => current = parent,
+            _ => break,
+        }
+    }
+    current
+}
+
+/// A sidechain of a session, attributed to the Task call that launched it
+pub(crate) struct Sidechain<'a> {
+    pub root: &'a ClaudeMessage,
+    pub persona: &'a str,
+    pub description: Option<&'a str>,
+    pub messages: Vec<&'a ClaudeMessage>, // File order, system messages excluded
+}
+
+/// Group the sidechain messages of a session by sidechain, in order of appearance
+///
+/// Sidechain roots are matched to the Task call with the same prompt, falling
+/// back to the unclaimed calls in order.
+pub(crate) fn find_sidechains(messages: &[ClaudeMessage]) -> Vec<Sidechain<'_>> {
+    let invocations = task_invocations(messages);
+    let by_uuid: HashMap<&str, &ClaudeMessage> =
+        messages.iter().map(|m| (m.uuid.as_str(), m)).collect();
+
+    let mut sidechains: Vec<Sidechain<'_>> = Vec::new();
+    let mut by_root: HashMap<&str, usize> = HashMap::new();
+    let mut claimed = vec![false; invocations.len()];
+
+    for message in messages {
+        if message.is_sidechain != Some(true) || is_system_message_type(&message.message_type) {
+            continue;
+        }
+        let root = sidechain_root(message, &by_uuid);
+        let idx = *by_root.entry(root.uuid.as_str()).or_insert_with(|| {
+            let text = message_text(root).unwrap_or_default();
+            let matched = invocations
+                .iter()
+                .enumerate()
+                .position(|(i, inv)| !claimed[i] && !inv.prompt.is_empty() && inv.prompt == text)
+                .or_else(|| claimed.iter().position(|c| !c));
+            let invocation = matched.map(|i| {
+                claimed[i] = true;
+                &invocations[i]
+            });
+            sidechains.push(Sidechain {
+                root,
+                persona: invocation.map_or(UNKNOWN_PERSONA, |inv| inv.agent_type),
+                description: invocation.and_then(|inv| inv.description),
+                messages: Vec::new(),
+            });
+            sidechains.len() - 1
+        });
+        sidechains[idx].messages.push(message);
+    }
+
+    sidechains
+}
+
+fn add_usage(persona: &mut SessionPersona, message: &ClaudeMessage, counted: &mut HashSet<String>) {
+    persona.message_count += 1;
+    let first_part = match &message.message_id {
+        Some(id) => counted.insert(id.clone()),
+        None => true,
+    };
+    if let (Some(usage), true) = (&message.usage, first_part) {
+        persona.input_tokens += u64::from(usage.input_tokens.unwrap_or(0));
+        persona.output_tokens += u64::from(usage.output_tokens.unwrap_or(0));
+        persona.cache_creation_tokens += u64::from(usage.cache_creation_input_tokens.unwrap_or(0));
+        persona.cache_read_tokens += u64::from(usage.cache_read_input_tokens.unwrap_or(0));
+    }
+}
+
+/// Build the cast list of a session from its messages (sub-agent files included)
+pub fn build_session_cast(session_id: &str, messages: &[ClaudeMessage]) -> SessionCast {
+    let new_persona = |name: &str| SessionPersona {
+        name: name.to_string(),
+        ..SessionPersona::default()
+    };
+    let mut personas: HashMap<String, SessionPersona> = HashMap::new();
+    personas.insert(MAIN_PERSONA.to_string(), new_persona(MAIN_PERSONA));
+
+    for invocation in task_invocations(messages) {
+        let persona = personas
+            .entry(invocation.agent_type.to_string())
+            .or_insert_with(|| new_persona(invocation.agent_type));
+        persona.invocation_count += 1;
+        if let Some(description) = invocation.description {
+            if !persona.descriptions.iter().any(|d| d == description) {
+                persona.descriptions.push(description.to_string());
+            }
+        }
+    }
+
+    let mut counted_responses: HashSet<String> = HashSet::new();
+    if let Some(main) = personas.get_mut(MAIN_PERSONA) {
+        for message in messages
+            .iter()
+            .filter(|m| m.is_sidechain != Some(true) && !is_system_message_type(&m.message_type))
+        {
+            add_usage(main, message, &mut counted_responses);
+        }
+    }
+
+    let sidechains = find_sidechains(messages);
+    for sidechain in &sidechains {
+        let persona = personas
+            .entry(sidechain.persona.to_string())
+            .or_insert_with(|| new_persona(sidechain.persona));
+        for message in &sidechain.messages {
+            add_usage(persona, message, &mut counted_responses);
+        }
+    }
+
+    let mut personas: Vec<SessionPersona> = personas
+        .into_values()
+        .map(|mut p| {
+            p.total_tokens =
+                p.input_tokens + p.output_tokens + p.cache_creation_tokens + p.cache_read_tokens;
+            p
+        })
+        .collect();
+    personas.sort_by(|a, b| {
+        (b.name == MAIN_PERSONA)
+            .cmp(&(a.name == MAIN_PERSONA))
+            .then_with(|| b.total_tokens.cmp(&a.total_tokens))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    SessionCast {
+        session_id: session_id.to_string(),
+        personas,
+        sidechains: sidechains
+            .iter()
+            .map(|sidechain| SidechainSummary {
+                root_uuid: sidechain.root.uuid.clone(),
+                persona: sidechain.persona.to_string(),
+                description: sidechain.description.map(str::to_string),
+                message_count: sidechain.messages.len(),
+                start_time: sidechain.root.timestamp.clone(),
+            })
+            .collect(),
+    }
+}
+
+/// Sub-agent files stored next to a session (`<project>/<session_id>/subagents/*.jsonl`)
+pub(crate) fn subagent_files(session_path: &Path) -> Vec<PathBuf> {
+    let subagents_dir = session_path.with_extension("").join("subagents");
+    let Ok(entries) = fs::read_dir(&subagents_dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(std::result::Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Session file a sub-agent file belongs to, or None for other files
+pub(crate) fn subagent_session_file(path: &Path) -> Option<PathBuf> {
+    let subagents_dir = path.parent()?;
+    if subagents_dir.file_name()? != "subagents" {
+        return None;
+    }
+    Some(subagents_dir.parent()?.with_extension("jsonl"))
+}
+
+/// Messages of a session followed by those of its sub-agent files
+pub(crate) fn read_session_with_subagents(
+    session_path: &Path,
+) -> Result<Vec<ClaudeMessage>, String> {
+    let mut messages = read_session_messages(session_path)?;
+    for path in subagent_files(session_path) {
+        if let Ok(subagent_messages) = read_session_messages(&path) {
+            messages.extend(subagent_messages);
+        }
+    }
+    Ok(messages)
+}
+
+/// Per-session cast list: the main agent and each sub-agent persona with
+/// their message counts and token consumption
+#[tauri::command]
+pub async fn get_session_personas(
+    session_id: String,
+    project_path: String,
+) -> Result<SessionCast, AppError> {
+    let _timer = OperationTimer::start("get_session_personas");
+
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+    let messages = read_session_with_subagents(&session_path)?;
+    Ok(build_session_cast(&session_id, &messages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MessageBuilder;
+    use serde_json::json;
+
+    fn task_call(uuid: &str, agent_type: &str, prompt: &str) -> ClaudeMessage {
+        MessageBuilder::assistant()
+            .with_uuid(uuid)
+            .with_content(json!([{
+                "type": "tool_use",
+                "id": format!("toolu_{uuid}"),
+                "name": "Task",
+                "input": {"subagent_type": agent_type, "description": format!("{agent_type} run"), "prompt": prompt}
+            }]))
+            .with_usage(100, 10)
+            .build()
+    }
+
+    fn sidechain(uuid: &str, parent: Option<&str>, text: &str, usage: (u32, u32)) -> ClaudeMessage {
+        let mut builder = MessageBuilder::assistant()
+            .with_uuid(uuid)
+            .with_text_content(text)
+            .with_usage(usage.0, usage.1);
+        if let Some(parent) = parent {
+            builder = builder.with_parent_uuid(parent);
+        }
+        let mut message = builder.build();
+        message.is_sidechain = Some(true);
+        message
+    }
+
+    #[test]
+    fn test_build_session_cast_attributes_sidechains() {
+        let messages = vec![
+            MessageBuilder::user().with_uuid("u1").build(),
+            task_call("a1", "code-reviewer", "Review the diff"),
+            task_call("a2", "test-writer", "Write tests for load.rs"),
+            // Sidechains appear in a different order than their Task calls
+            sidechain("t1", None, "Write tests for load.rs", (0, 0)),
+            sidechain("t2", Some("t1"), "Added three tests", (1_000, 500)),
+            sidechain("r1", None, "Review the diff", (0, 0)),
+            sidechain("r2", Some("r1"), "Looks good", (200, 50)),
+        ];
+
+        let cast = build_session_cast("s1", &messages);
+        let names: Vec<&str> = cast.personas.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["main", "test-writer", "code-reviewer"]);
+
+        let main = &cast.personas[0];
+        assert_eq!(main.message_count, 3);
+        assert_eq!(main.total_tokens, 220);
+        assert_eq!(main.invocation_count, 0);
+
+        let writer = &cast.personas[1];
+        assert_eq!(writer.message_count, 2);
+        assert_eq!(writer.total_tokens, 1_500);
+        assert_eq!(writer.invocation_count, 1);
+        assert_eq!(writer.descriptions, vec!["test-writer run"]);
+        assert_eq!(cast.personas[2].output_tokens, 50);
+
+        assert_eq!(cast.sidechains.len(), 2);
+        assert_eq!(cast.sidechains[0].root_uuid, "t1");
+        assert_eq!(cast.sidechains[0].persona, "test-writer");
+        assert_eq!(
+            cast.sidechains[1].description.as_deref(),
+            Some("code-reviewer run")
+        );
+    }
+
+    #[test]
+    fn test_build_session_cast_unmatched_sidechain() {
+        let messages = vec![
+            MessageBuilder::user().with_uuid("u1").build(),
+            sidechain("x1", None, "Orphan work", (10, 10)),
+        ];
+
+        let cast = build_session_cast("s1", &messages);
+        assert_eq!(cast.personas.len(), 2);
+        assert_eq!(cast.personas[1].name, "sidechain");
+        assert_eq!(cast.personas[1].total_tokens, 20);
+    }
+}
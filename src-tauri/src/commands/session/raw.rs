@@ -0,0 +1,214 @@
+//! Raw JSONL line lookup
+//!
+//! Returns the original log line behind a parsed message, for inspecting the
+//! underlying data when the parsed view looks wrong.
+
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::RawEntry;
+use crate::utils::{find_line_ranges, long_path, resolve_session_file, stable_line_id};
+use memchr::memmem;
+use memmap2::Mmap;
+use std::fs;
+use std::path::Path;
+
+/// Minimal view of a line for matching it against a message ID
+#[derive(serde::Deserialize)]
+struct LineIds<'a> {
+    #[serde(borrow)]
+    uuid: Option<&'a str>,
+    #[serde(rename = "sessionId", borrow)]
+    session_id: Option<&'a str>,
+}
+
+fn line_ids(line: &[u8]) -> Option<LineIds<'_>> {
+    serde_json::from_slice(line).ok()
+}
+
+/// Find the line of the message `uuid`, returning `(line_index, start, end)`
+///
+/// Entries without a `uuid` of their own are matched through the
+/// `<session>-line-<n>` ID assigned while loading (see `stable_line_id`).
+fn find_raw_line(session_path: &Path, data: &[u8], uuid: &str) -> Option<(usize, usize, usize)> {
+    let ranges = find_line_ranges(data);
+    let finder = memmem::Finder::new(uuid.as_bytes());
+
+    let by_uuid = ranges.iter().enumerate().find(|(_, &(start, end))| {
+        let line = &data[start..end];
+        finder.find(line).is_some() && line_ids(line).is_some_and(|ids| ids.uuid == Some(uuid))
+    });
+    if let Some((idx, &(start, end))) = by_uuid {
+        return Some((idx, start, end));
+    }
+
+    let line_number: usize = uuid.rsplit_once("-line-")?.1.parse().ok()?;
+    let idx = line_number.checked_sub(1)?;
+    let &(start, end) = ranges.get(idx)?;
+    let ids = line_ids(&data[start..end])?;
+    (ids.uuid.is_none() && stable_line_id(ids.session_id, session_path, idx) == uuid)
+        .then_some((idx, start, end))
+}
+
+/// Re-indent a JSON document without reordering its keys
+///
+/// Returns None if `json` is not valid JSON.
+fn pretty_print_json(json: &str) -> Option<String> {
+    serde_json::from_str::<serde::de::IgnoredAny>(json).ok()?;
+
+    let mut out = String::with_capacity(json.len() * 2);
+    let mut indent = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut chars = json.trim().chars().peekable();
+
+    let newline = |out: &mut String, indent: usize| {
+        out.push('\n');
+        out.push_str(&"  ".repeat(indent));
+    };
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c);
+            }
+            '{' | '[' => {
+                out.push(c);
+                let closing = if c == '{' { '}' } else { ']' };
+                while chars.peek().is_some_and(|next| next.is_whitespace()) {
+                    chars.next();
+                }
+                if chars.peek() == Some(&closing) {
+                    out.push(closing);
+                    chars.next();
+                } else {
+                    indent += 1;
+                    newline(&mut out, indent);
+                }
+            }
+            '}' | ']' => {
+                indent = indent.saturating_sub(1);
+                newline(&mut out, indent);
+                out.push(c);
+            }
+            ',' => {
+                out.push(c);
+                newline(&mut out, indent);
+            }
+            ':' => out.push_str(": "),
+            c if c.is_whitespace() => {}
+            c => out.push(c),
+        }
+    }
+
+    Some(out)
+}
+
+/// Get the original JSONL line of a message
+///
+/// With `pretty`, the line is re-indented (key order is preserved).
+#[tauri::command]
+#[allow(unsafe_code)] // Required for mmap performance optimization
+pub async fn get_raw_entry(
+    session_id: String,
+    project_path: String,
+    uuid: String,
+    pretty: Option<bool>,
+) -> Result<RawEntry, AppError> {
+    let _timer = OperationTimer::start("get_raw_entry");
+
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+    let file = fs::File::open(long_path(&session_path))
+        .map_err(|e| format!("Failed to open session file: {e}"))?;
+
+    // SAFETY: We're only reading the file, and the file handle is kept open
+    // for the duration of the mmap's lifetime. Session files are append-only.
+    let mmap = unsafe { Mmap::map(&file) }
+        .map_err(|e| format!("Failed to memory-map session file: {e}"))?;
+
+    let (line_index, start, end) = find_raw_line(&session_path, &mmap, &uuid)
+        .ok_or_else(|| format!("Message not found: {uuid}"))?;
+
+    let raw = String::from_utf8_lossy(&mmap[start..end]).into_owned();
+    let is_pretty = pretty.unwrap_or(false);
+    let raw = if is_pretty {
+        pretty_print_json(&raw).unwrap_or(raw)
+    } else {
+        raw
+    };
+
+    Ok(RawEntry {
+        session_id,
+        uuid,
+        line_number: line_index + 1,
+        byte_offset: start as u64,
+        raw,
+        pretty: is_pretty,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = concat!(
+        r#"{"type":"user","uuid":"u1","sessionId":"s1","message":{"content":"mentions u2"}}"#,
+        "\n\n",
+        r#"{"type":"summary","sessionId":"s1","summary":"no uuid"}"#,
+        "\n",
+        r#"{"type":"assistant","uuid":"u2","sessionId":"s1"}"#,
+        "\n",
+    );
+
+    #[test]
+    fn test_find_raw_line_matches_uuid_field_only() {
+        let path = Path::new("s1.jsonl");
+        let (idx, start, end) = find_raw_line(path, LOG.as_bytes(), "u2").unwrap();
+        assert_eq!(idx, 2);
+        assert!(LOG[start..end].starts_with(r#"{"type":"assistant""#));
+    }
+
+    #[test]
+    fn test_find_raw_line_by_stable_line_id() {
+        let path = Path::new("s1.jsonl");
+        let (idx, start, end) = find_raw_line(path, LOG.as_bytes(), "s1-line-2").unwrap();
+        assert_eq!(idx, 1);
+        assert!(LOG[start..end].contains("no uuid"));
+
+        // Line 1 has its own uuid, so its fallback ID never matches
+        assert!(find_raw_line(path, LOG.as_bytes(), "s1-line-1").is_none());
+        assert!(find_raw_line(path, LOG.as_bytes(), "missing").is_none());
+    }
+
+    #[test]
+    fn test_find_raw_line_without_session_id_uses_file_stem() {
+        let log = concat!(r#"{"type":"summary","summary":"no session"}"#, "\n",);
+        let path = Path::new("/projects/app/abc.jsonl");
+        let (idx, _, _) = find_raw_line(path, log.as_bytes(), "abc-line-1").unwrap();
+        assert_eq!(idx, 0);
+        assert!(find_raw_line(path, log.as_bytes(), "unknown-session-line-1").is_none());
+    }
+
+    #[test]
+    fn test_pretty_print_preserves_key_order() {
+        let pretty =
+            pretty_print_json(r#"{"z":1,"a":{"s":"x, {y}: \"q\""},"e":[],"l":[1,2]}"#).unwrap();
+        assert_eq!(
+            pretty,
+            "{\n  \"z\": 1,\n  \"a\": {\n    \"s\": \"x, {y}: \\\"q\\\"\"\n  },\n  \"e\": [],\n  \"l\": [\n    1,\n    2\n  ]\n}"
+        );
+        assert!(pretty_print_json("{not json").is_none());
+    }
+}
@@ -0,0 +1,172 @@
+//! Broken parent chain repair
+//!
+//! After a crash, entries can reference a `parentUuid` that never made it to
+//! disk. Such orphans are unreachable from any root, so tree views drop them.
+//! Repair reattaches each orphan to the entry written just before it (by
+//! timestamp) for display purposes only; the session file is never modified.
+
+use super::load::read_session_messages;
+use crate::commands::export::compare_messages_for_export;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ClaudeMessage, RepairedLink, SessionLinkRepair};
+use crate::utils::resolve_session_file;
+use std::collections::{HashMap, HashSet};
+
+/// Whether `candidate` descends from `orphan` under the current parent links
+fn is_descendant(parents: &HashMap<&str, Option<&str>>, candidate: &str, orphan: &str) -> bool {
+    let mut current = Some(candidate);
+    // Bounded walk in case of malformed (cyclic) parent chains
+    for _ in 0..=parents.len() {
+        match current {
+            Some(uuid) if uuid == orphan => return true,
+            Some(uuid) => current = parents.get(uuid).copied().flatten(),
+            None => return false,
+        }
+    }
+    false
+}
+
+/// Find entries whose `parentUuid` is missing and pick a replacement parent
+///
+/// The replacement is the closest earlier entry on the same side of the
+/// sidechain split, skipping entries that descend from the orphan itself.
+pub fn find_link_repairs(messages: &[ClaudeMessage]) -> Vec<RepairedLink> {
+    let known: HashSet<&str> = messages.iter().map(|m| m.uuid.as_str()).collect();
+
+    let mut order: Vec<&ClaudeMessage> = messages.iter().collect();
+    order.sort_by(|a, b| compare_messages_for_export(a, b));
+
+    let mut parents: HashMap<&str, Option<&str>> = HashMap::new();
+    for message in &order {
+        parents
+            .entry(message.uuid.as_str())
+            .or_insert(message.parent_uuid.as_deref());
+    }
+
+    let mut links = Vec::new();
+    let mut repaired: HashSet<&str> = HashSet::new();
+    for (position, message) in order.iter().enumerate() {
+        let Some(missing) = message.parent_uuid.as_deref() else {
+            continue;
+        };
+        if known.contains(missing) || !repaired.insert(message.uuid.as_str()) {
+            continue;
+        }
+
+        let is_sidechain = message.is_sidechain == Some(true);
+        let replacement = order[..position]
+            .iter()
+            .rev()
+            .filter(|candidate| candidate.uuid != message.uuid)
+            .filter(|candidate| (candidate.is_sidechain == Some(true)) == is_sidechain)
+            .find(|candidate| !is_descendant(&parents, &candidate.uuid, &message.uuid))
+            .map(|candidate| candidate.uuid.as_str());
+
+        parents.insert(message.uuid.as_str(), replacement);
+        links.push(RepairedLink {
+            uuid: message.uuid.clone(),
+            missing_parent_uuid: missing.to_string(),
+            repaired_parent_uuid: replacement.map(String::from),
+        });
+    }
+
+    links
+}
+
+/// Rewrite `parent_uuid` of repaired messages in place
+///
+/// Orphans without a replacement become roots.
+pub fn apply_link_repairs(messages: &mut [ClaudeMessage], links: &[RepairedLink]) {
+    let by_uuid: HashMap<&str, &RepairedLink> = links
+        .iter()
+        .map(|link| (link.uuid.as_str(), link))
+        .collect();
+
+    for message in messages.iter_mut() {
+        if let Some(link) = by_uuid.get(message.uuid.as_str()) {
+            if message.parent_uuid.as_deref() == Some(link.missing_parent_uuid.as_str()) {
+                message.parent_uuid.clone_from(&link.repaired_parent_uuid);
+            }
+        }
+    }
+}
+
+/// Find broken parent links of a session and how they would be repaired
+#[tauri::command]
+pub async fn repair_session_links(
+    session_id: String,
+    project_path: String,
+) -> Result<SessionLinkRepair, AppError> {
+    let _timer = OperationTimer::start("repair_session_links");
+
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+    let messages = read_session_messages(&session_path)?;
+    let links = find_link_repairs(&messages);
+
+    Ok(SessionLinkRepair {
+        session_id,
+        orphan_count: links.len(),
+        links,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MessageBuilder;
+
+    fn message(uuid: &str, parent: Option<&str>, ts: &str) -> ClaudeMessage {
+        let builder = MessageBuilder::user().with_uuid(uuid).with_timestamp(ts);
+        match parent {
+            Some(parent) => builder.with_parent_uuid(parent).build(),
+            None => builder.build(),
+        }
+    }
+
+    #[test]
+    fn test_orphan_reattached_to_previous_entry() {
+        let messages = vec![
+            message("a", None, "2025-01-01T00:00:00Z"),
+            message("b", Some("a"), "2025-01-01T00:00:01Z"),
+            message("c", Some("lost"), "2025-01-01T00:00:03Z"),
+            message("d", Some("c"), "2025-01-01T00:00:04Z"),
+        ];
+
+        let links = find_link_repairs(&messages);
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].uuid, "c");
+        assert_eq!(links[0].missing_parent_uuid, "lost");
+        assert_eq!(links[0].repaired_parent_uuid.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_repair_skips_own_descendants() {
+        // "d" was written before its orphaned parent "c" (clock skew)
+        let messages = vec![
+            message("a", None, "2025-01-01T00:00:00Z"),
+            message("d", Some("c"), "2025-01-01T00:00:02Z"),
+            message("c", Some("lost"), "2025-01-01T00:00:03Z"),
+        ];
+
+        let links = find_link_repairs(&messages);
+
+        assert_eq!(links[0].repaired_parent_uuid.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn test_first_orphan_becomes_root() {
+        let mut messages = vec![
+            message("a", Some("lost"), "2025-01-01T00:00:00Z"),
+            message("b", Some("a"), "2025-01-01T00:00:01Z"),
+        ];
+
+        let links = find_link_repairs(&messages);
+        apply_link_repairs(&mut messages, &links);
+
+        assert_eq!(links[0].repaired_parent_uuid, None);
+        assert_eq!(messages[0].parent_uuid, None);
+        assert_eq!(messages[1].parent_uuid.as_deref(), Some("a"));
+    }
+}
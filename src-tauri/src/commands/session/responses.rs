@@ -0,0 +1,203 @@
+//! Grouping of assistant entries by API response
+//!
+//! A streamed response is written as one entry per content block, and a
+//! retried response is written again, all sharing the API message `id`. For
+//! display these entries are merged into one logical message.
+
+use crate::models::ClaudeMessage;
+use std::collections::HashMap;
+
+/// Content blocks of a message, wrapping plain string content as a text block
+fn content_blocks(content: Option<serde_json::Value>) -> Vec<serde_json::Value> {
+    match content {
+        Some(serde_json::Value::Array(blocks)) => blocks,
+        Some(serde_json::Value::String(text)) => {
+            vec![serde_json::json!({"type": "text", "text": text})]
+        }
+        Some(serde_json::Value::Null) | None => Vec::new(),
+        Some(other) => vec![other],
+    }
+}
+
+/// API response an assistant entry belongs to
+///
+/// The streamed and retried entries of a response share its message `id`.
+/// Only the first entry's usage is counted for the response, both by the
+/// stats (`ResponseUsageTracker`) and in merged messages, so the two report
+/// the same tokens.
+pub fn response_id(message: &ClaudeMessage) -> Option<&str> {
+    match (message.message_type.as_str(), &message.message_id) {
+        ("assistant", Some(id)) => Some(id),
+        _ => None,
+    }
+}
+
+/// Append `part` to the merged message `target`
+///
+/// The merged message keeps the first part's position, timestamp, parent and
+/// usage, and takes the last part's uuid so entries chained after it stay
+/// attached.
+fn absorb_part(target: &mut ClaudeMessage, part: ClaudeMessage) {
+    let mut blocks = content_blocks(target.content.take());
+    for block in content_blocks(part.content) {
+        // Retries repeat blocks that were already written
+        if !blocks.contains(&block) {
+            blocks.push(block);
+        }
+    }
+    target.content = Some(serde_json::Value::Array(blocks));
+    target.uuid = part.uuid;
+    if part.stop_reason.is_some() {
+        target.stop_reason = part.stop_reason;
+    }
+}
+
+/// Follow uuid renames until reaching a uuid that survived merging
+fn resolve_uuid<'a>(renamed: &'a HashMap<String, String>, uuid: &'a str) -> &'a str {
+    let mut current = uuid;
+    // Bounded in case of malformed (cyclic) renames
+    for _ in 0..=renamed.len() {
+        match renamed.get(current) {
+            Some(next) => current = next,
+            None => break,
+        }
+    }
+    current
+}
+
+/// Merge assistant entries sharing an API message ID into one message each
+pub fn merge_response_parts(messages: Vec<ClaudeMessage>) -> Vec<ClaudeMessage> {
+    let mut merged: Vec<ClaudeMessage> = Vec::with_capacity(messages.len());
+    let mut by_response: HashMap<String, usize> = HashMap::new();
+    // uuid of an absorbed entry -> uuid that replaced it
+    let mut renamed: HashMap<String, String> = HashMap::new();
+
+    for message in messages {
+        let response_id = response_id(&message).map(str::to_string);
+        if let Some(&index) = response_id.as_ref().and_then(|id| by_response.get(id)) {
+            let target = &mut merged[index];
+            if target.uuid != message.uuid {
+                renamed.insert(target.uuid.clone(), message.uuid.clone());
+            }
+            absorb_part(target, message);
+        } else {
+            if let Some(id) = response_id {
+                by_response.insert(id, merged.len());
+            }
+            merged.push(message);
+        }
+    }
+
+    if !renamed.is_empty() {
+        for message in &mut merged {
+            let Some(parent) = message.parent_uuid.as_deref() else {
+                continue;
+            };
+            let resolved = resolve_uuid(&renamed, parent);
+            if resolved != parent && resolved != message.uuid {
+                message.parent_uuid = Some(resolved.to_string());
+            }
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::stats::ResponseUsageTracker;
+    use crate::test_utils::MessageBuilder;
+    use serde_json::json;
+
+    fn part(uuid: &str, parent: &str, id: &str, block: serde_json::Value) -> ClaudeMessage {
+        let mut message = MessageBuilder::assistant()
+            .with_uuid(uuid)
+            .with_parent_uuid(parent)
+            .with_content(json!([block]))
+            .build();
+        message.message_id = Some(id.to_string());
+        message
+    }
+
+    #[test]
+    fn test_streamed_parts_are_merged() {
+        let user = MessageBuilder::user().with_uuid("u1").build();
+        let thinking = part(
+            "a1",
+            "u1",
+            "msg_1",
+            json!({"type": "thinking", "thinking": "hmm"}),
+        );
+        let tool_use = part("a2", "a1", "msg_1", json!({"type": "tool_use", "id": "t1"}));
+        let result = MessageBuilder::user()
+            .with_uuid("u2")
+            .with_parent_uuid("a1")
+            .build();
+
+        let merged = merge_response_parts(vec![user, thinking, tool_use, result]);
+
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[1].uuid, "a2");
+        assert_eq!(merged[1].parent_uuid.as_deref(), Some("u1"));
+        assert_eq!(
+            merged[1]
+                .content
+                .as_ref()
+                .unwrap()
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(merged[2].parent_uuid.as_deref(), Some("a2"));
+    }
+
+    #[test]
+    fn test_retried_blocks_are_not_duplicated() {
+        let block = json!({"type": "text", "text": "Done"});
+        let first = part("a1", "u1", "msg_1", block.clone());
+        let retry = part("a2", "u1", "msg_1", block);
+        let other = part("a3", "a2", "msg_2", json!({"type": "text", "text": "Next"}));
+
+        let merged = merge_response_parts(vec![first, retry, other]);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(
+            merged[0]
+                .content
+                .as_ref()
+                .unwrap()
+                .as_array()
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(merged[1].parent_uuid.as_deref(), Some("a2"));
+    }
+
+    #[test]
+    fn test_merged_usage_matches_stats() {
+        let usage_part = |uuid: &str, output: u32| {
+            let mut message = MessageBuilder::assistant()
+                .with_uuid(uuid)
+                .with_content(json!([{"type": "text", "text": uuid}]))
+                .with_usage(100, output)
+                .build();
+            message.message_id = Some("msg_1".to_string());
+            message
+        };
+        // Each streamed part carries the usage as of when it was written
+        let parts = vec![usage_part("a1", 10), usage_part("a2", 50)];
+
+        let mut tracker = ResponseUsageTracker::default();
+        let counted: Vec<_> = parts.iter().filter_map(|p| tracker.usage_of(p)).collect();
+        let merged = merge_response_parts(parts);
+
+        assert_eq!(counted.len(), 1);
+        assert_eq!(merged.len(), 1);
+        let usage = merged[0].usage.as_ref().unwrap();
+        assert_eq!(usage.input_tokens, counted[0].input_tokens);
+        assert_eq!(usage.output_tokens, counted[0].output_tokens);
+    }
+}
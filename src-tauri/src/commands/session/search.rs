@@ -1,13 +1,32 @@
 //! Session search functions
 
-use crate::models::{ClaudeMessage, RawLogEntry};
-use crate::utils::find_line_ranges;
-use chrono::Utc;
+use super::desktop::read_all_desktop_messages;
+use crate::commands::metadata::ensure_metadata_folder;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::index::{
+    IndexedError, IndexedErrorGroup, IndexedMessage, SearchIndex, SEARCH_INDEX_FILE_NAME,
+};
+use crate::io_limit::{acquire_file_permit, acquire_search_permit};
+use crate::models::{
+    ClaudeMessage, ErrorGroup, ErrorSearchMatch, GlobalSearchSummary, ProjectSearchMatch,
+    ProjectSearchResultsEvent, RawLogEntry, SearchIndexStatus, SearchSnippet,
+};
+use crate::utils::{
+    collect_session_files, display_path, extract_project_name, find_line_ranges, long_path,
+    stable_line_id,
+};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
 use memmap2::Mmap;
 use rayon::prelude::*;
+use regex::{Regex, RegexBuilder};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
-use uuid::Uuid;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use tauri::State;
 use walkdir::WalkDir;
 
 /// Initial buffer capacity for JSON parsing (4KB covers most messages)
@@ -16,16 +35,283 @@ const PARSE_BUFFER_INITIAL_CAPACITY: usize = 4096;
 /// Initial capacity for search results (most searches find few matches)
 const SEARCH_RESULTS_INITIAL_CAPACITY: usize = 8;
 
-/// Recursively search for a query within a `serde_json::Value`
-/// Returns true if the query is found in any string value.
-/// This avoids the expensive JSON serialization that was previously used.
-#[inline]
-fn search_in_value(value: &serde_json::Value, query: &str) -> bool {
-    match value {
-        serde_json::Value::String(s) => s.to_lowercase().contains(query),
-        serde_json::Value::Array(arr) => arr.iter().any(|item| search_in_value(item, query)),
-        serde_json::Value::Object(obj) => obj.values().any(|val| search_in_value(val, query)),
-        _ => false, // Numbers, booleans, null don't contain searchable text
+/// Characters of context kept on each side of a match in snippets
+const SNIPPET_CONTEXT_CHARS: usize = 60;
+
+/// Default maximum number of matches returned by `search_project_messages`
+const DEFAULT_PROJECT_SEARCH_LIMIT: usize = 200;
+
+/// Event carrying the matches of one project during `search_all_projects`
+pub const PROJECT_SEARCH_RESULTS_EVENT: &str = "project-search-results";
+
+type ProjectSearchListener = Box<dyn Fn(&ProjectSearchResultsEvent) + Send + Sync>;
+
+/// Forwards per-project search results to the frontend (unset in tests)
+static PROJECT_SEARCH_LISTENER: OnceLock<ProjectSearchListener> = OnceLock::new();
+
+/// Register the callback that emits per-project search results to the frontend
+pub fn set_project_search_listener(
+    listener: impl Fn(&ProjectSearchResultsEvent) + Send + Sync + 'static,
+) {
+    let _ = PROJECT_SEARCH_LISTENER.set(Box::new(listener));
+}
+
+/// Bytes read from each end of a session file to find its time bounds
+const TIME_BOUNDS_PROBE_BYTES: u64 = 64 * 1024;
+
+/// Error returned by a search stopped through `cancel_search`
+const SEARCH_CANCELLED: &str = "Search cancelled";
+
+/// Cancellation flags of running searches, keyed by request ID
+#[derive(Default)]
+pub struct SearchCancellations {
+    flags: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+/// A running search; removes its flag from `SearchCancellations` when dropped
+struct SearchRequest<'a> {
+    cancellations: &'a SearchCancellations,
+    request_id: Option<String>,
+    flag: Arc<AtomicBool>,
+}
+
+impl SearchCancellations {
+    /// Track a search so `cancel_search` can stop it (untracked without an ID)
+    fn register(&self, request_id: Option<String>) -> SearchRequest<'_> {
+        let flag = Arc::new(AtomicBool::new(false));
+        if let Some(id) = &request_id {
+            self.flags
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(id.clone(), Arc::clone(&flag));
+        }
+        SearchRequest {
+            cancellations: self,
+            request_id,
+            flag,
+        }
+    }
+
+    /// Flag a running search as cancelled; false if no such search is running
+    fn cancel(&self, request_id: &str) -> bool {
+        let flags = self.flags.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(flag) = flags.get(request_id) else {
+            return false;
+        };
+        flag.store(true, Ordering::SeqCst);
+        true
+    }
+}
+
+impl Drop for SearchRequest<'_> {
+    fn drop(&mut self) {
+        let Some(id) = &self.request_id else {
+            return;
+        };
+        let mut flags = self
+            .cancellations
+            .flags
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        // The ID may have been reused by a newer search
+        if flags
+            .get(id)
+            .is_some_and(|flag| Arc::ptr_eq(flag, &self.flag))
+        {
+            flags.remove(id);
+        }
+    }
+}
+
+/// Fail with `SEARCH_CANCELLED` once the search has been cancelled
+fn check_cancelled(cancel: &AtomicBool) -> Result<(), AppError> {
+    if cancel.load(Ordering::SeqCst) {
+        Err(AppError::cancelled(SEARCH_CANCELLED))
+    } else {
+        Ok(())
+    }
+}
+
+/// Skip `offset` items and keep at most `limit`
+fn paginate<T>(items: &mut Vec<T>, offset: Option<usize>, limit: usize) {
+    items.drain(..offset.unwrap_or(0).min(items.len()));
+    items.truncate(limit);
+}
+
+/// Stop the search started with `request_id`
+///
+/// Returns false if no search with this ID is running. The cancelled
+/// command fails with "Search cancelled".
+#[tauri::command]
+pub async fn cancel_search(
+    state: State<'_, SearchCancellations>,
+    request_id: String,
+) -> Result<bool, AppError> {
+    let _timer = OperationTimer::start("cancel_search");
+    Ok(state.cancel(&request_id))
+}
+
+/// Optional `from`/`to` bounds of a search (inclusive)
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct TimeRange {
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+}
+
+#[derive(serde::Deserialize)]
+struct TimestampProbe {
+    timestamp: Option<String>,
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+fn line_timestamp(line: &str) -> Option<DateTime<Utc>> {
+    let probe: TimestampProbe = serde_json::from_str(line).ok()?;
+    parse_timestamp(probe.timestamp.as_deref()?)
+}
+
+/// First and last message time of a session file, read from its head and tail
+fn session_time_bounds(path: &Path) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let _permit = acquire_file_permit();
+    let mut file = fs::File::open(long_path(path)).ok()?;
+    let len = file.metadata().ok()?.len();
+
+    let mut head = Vec::new();
+    (&mut file)
+        .take(TIME_BOUNDS_PROBE_BYTES)
+        .read_to_end(&mut head)
+        .ok()?;
+    let first = String::from_utf8_lossy(&head)
+        .lines()
+        .find_map(line_timestamp)?;
+
+    file.seek(SeekFrom::Start(len.saturating_sub(TIME_BOUNDS_PROBE_BYTES)))
+        .ok()?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail).ok()?;
+    let last = String::from_utf8_lossy(&tail)
+        .lines()
+        .rev()
+        .find_map(line_timestamp)?;
+
+    Some((first, last))
+}
+
+impl TimeRange {
+    /// Parse RFC 3339 timestamps or `YYYY-MM-DD` dates (whole days)
+    pub(crate) fn parse(from: Option<&str>, to: Option<&str>) -> Result<Self, String> {
+        let bound = |value: Option<&str>, end_of_day: bool| -> Result<_, String> {
+            let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+                return Ok(None);
+            };
+            if let Some(time) = parse_timestamp(value) {
+                return Ok(Some(time));
+            }
+            let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map_err(|_| format!("Invalid date: {value}"))?;
+            let time = if end_of_day {
+                date.and_hms_milli_opt(23, 59, 59, 999)
+            } else {
+                date.and_hms_opt(0, 0, 0)
+            };
+            Ok(time.map(|time| Utc.from_utc_datetime(&time)))
+        };
+        let range = Self {
+            from: bound(from, false)?,
+            to: bound(to, true)?,
+        };
+        if let (Some(from), Some(to)) = (range.from, range.to) {
+            if from > to {
+                return Err("Invalid date range: from is after to".to_string());
+            }
+        }
+        Ok(range)
+    }
+
+    fn is_unbounded(self) -> bool {
+        self.from.is_none() && self.to.is_none()
+    }
+
+    /// Whether a message time is in range (unparseable times are kept)
+    pub(crate) fn contains(self, timestamp: &str) -> bool {
+        let Some(time) = parse_timestamp(timestamp) else {
+            return true;
+        };
+        self.from.map_or(true, |from| time >= from) && self.to.map_or(true, |to| time <= to)
+    }
+
+    /// Whether a session file may hold messages in range, judged by its first
+    /// and last message times (files of unknown span are kept)
+    pub(crate) fn overlaps_file(self, path: &Path) -> bool {
+        if self.is_unbounded() {
+            return true;
+        }
+        let Some((first, last)) = session_time_bounds(path) else {
+            return true;
+        };
+        self.from.map_or(true, |from| last >= from) && self.to.map_or(true, |to| first <= to)
+    }
+}
+
+/// Part of a message a search can look at
+#[derive(Debug, Clone, Copy)]
+enum SearchField {
+    Text = 1,
+    Thinking = 2,
+    ToolInput = 4,
+    ToolResult = 8,
+}
+
+/// Set of `SearchField`s
+#[derive(Debug, Clone, Copy)]
+struct SearchFields(u8);
+
+impl SearchFields {
+    const ALL: Self = Self(0b1111);
+
+    /// Parse field names ("text", "thinking", "`tool_input`", "`tool_result`");
+    /// None or an empty list selects every field
+    fn parse(fields: Option<&[String]>) -> Result<Self, String> {
+        let Some(fields) = fields.filter(|f| !f.is_empty()) else {
+            return Ok(Self::ALL);
+        };
+        let mut scope = Self(0);
+        for field in fields {
+            let field = match field.as_str() {
+                "text" => SearchField::Text,
+                "thinking" => SearchField::Thinking,
+                "tool_input" => SearchField::ToolInput,
+                "tool_result" => SearchField::ToolResult,
+                other => return Err(format!("Unknown search field: {other}")),
+            };
+            scope.0 |= field as u8;
+        }
+        Ok(scope)
+    }
+
+    fn contains(self, field: SearchField) -> bool {
+        self.0 & field as u8 != 0
+    }
+
+    /// Whether blocks of `block_type` are searched, and which part of them
+    fn searchable_part<'a>(
+        self,
+        block_type: &str,
+        block: &'a serde_json::Value,
+    ) -> Option<&'a serde_json::Value> {
+        // Only the user-visible part of each block, not ids or signatures
+        let (field, part) = match block_type {
+            "text" => (SearchField::Text, block.get("text")),
+            "thinking" => (SearchField::Thinking, block.get("thinking")),
+            "tool_use" | "server_tool_use" => (SearchField::ToolInput, block.get("input")),
+            t if t.ends_with("tool_result") => (SearchField::ToolResult, block.get("content")),
+            _ => (SearchField::Text, Some(block)),
+        };
+        part.filter(|_| self.contains(field))
     }
 }
 
@@ -33,10 +319,14 @@ fn search_in_value(value: &serde_json::Value, query: &str) -> bool {
 ///
 /// Uses a reusable buffer to avoid repeated heap allocations during JSON parsing.
 #[allow(unsafe_code)] // Required for mmap performance optimization
-fn search_in_file(file_path: &PathBuf, query: &str) -> Vec<ClaudeMessage> {
-    let query_lower = query.to_lowercase();
-
-    let file = match fs::File::open(file_path) {
+fn search_in_file(
+    file_path: &PathBuf,
+    matcher: &Regex,
+    fields: SearchFields,
+    cancel: &AtomicBool,
+) -> Vec<ClaudeMessage> {
+    let _permit = acquire_file_permit();
+    let file = match fs::File::open(long_path(file_path)) {
         Ok(f) => f,
         Err(_) => return Vec::new(),
     };
@@ -58,6 +348,9 @@ fn search_in_file(file_path: &PathBuf, query: &str) -> Vec<ClaudeMessage> {
     let mut parse_buffer = Vec::with_capacity(PARSE_BUFFER_INITIAL_CAPACITY);
 
     for (line_num, (start, end)) in line_ranges.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
         // Reuse buffer instead of allocating new Vec each iteration
         parse_buffer.clear();
         parse_buffer.extend_from_slice(&mmap[*start..*end]);
@@ -76,23 +369,14 @@ fn search_in_file(file_path: &PathBuf, query: &str) -> Vec<ClaudeMessage> {
             None => continue,
         };
 
-        // Use recursive search to avoid JSON serialization overhead
-        let matches = match &message_content.content {
-            serde_json::Value::String(s) => s.to_lowercase().contains(&query_lower),
-            serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
-                search_in_value(&message_content.content, &query_lower)
-            }
-            _ => false,
-        };
-
-        if !matches {
+        if find_entry_match(&log_entry, matcher, fields).is_none() {
             continue;
         }
 
         let claude_message = ClaudeMessage {
-            uuid: log_entry
-                .uuid
-                .unwrap_or_else(|| format!("{}-line-{}", Uuid::new_v4(), line_num + 1)),
+            uuid: log_entry.uuid.unwrap_or_else(|| {
+                stable_line_id(log_entry.session_id.as_deref(), file_path, line_num)
+            }),
             parent_uuid: log_entry.parent_uuid,
             session_id: log_entry
                 .session_id
@@ -126,6 +410,7 @@ fn search_in_file(file_path: &PathBuf, query: &str) -> Vec<ClaudeMessage> {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            git_branch: None,
         };
         results.push(claude_message);
     }
@@ -133,20 +418,657 @@ fn search_in_file(file_path: &PathBuf, query: &str) -> Vec<ClaudeMessage> {
     results
 }
 
+/// Find `query_lower` in `text` ignoring case, returning the byte range of
+/// the match in the original text
+fn find_ignore_case(text: &str, query_lower: &str) -> Option<(usize, usize)> {
+    // Lowercasing can change byte lengths, so remember which original
+    // character produced each byte of the lowered text
+    let mut lowered = String::with_capacity(text.len());
+    let mut origin: Vec<usize> = Vec::with_capacity(text.len());
+    for (idx, ch) in text.char_indices() {
+        lowered.extend(ch.to_lowercase());
+        origin.resize(lowered.len(), idx);
+    }
+
+    let start = lowered.find(query_lower)?;
+    let last = origin[start + query_lower.len() - 1];
+    let end = last + text[last..].chars().next().map_or(0, char::len_utf8);
+    Some((origin[start], end))
+}
+
+/// Snippet text on a single line
+fn single_line(chars: &[char]) -> String {
+    chars
+        .iter()
+        .map(|&c| if c.is_whitespace() { ' ' } else { c })
+        .collect()
+}
+
+/// Build a snippet with up to `SNIPPET_CONTEXT_CHARS` characters on each side
+fn build_snippet(text: &str, (start, end): (usize, usize)) -> SearchSnippet {
+    let before: Vec<char> = text[..start].chars().collect();
+    let after: Vec<char> = text[end..].chars().collect();
+
+    let mut before_text =
+        single_line(&before[before.len().saturating_sub(SNIPPET_CONTEXT_CHARS)..]);
+    if before.len() > SNIPPET_CONTEXT_CHARS {
+        before_text.insert(0, '…');
+    }
+    let mut after_text = single_line(&after[..after.len().min(SNIPPET_CONTEXT_CHARS)]);
+    if after.len() > SNIPPET_CONTEXT_CHARS {
+        after_text.push('…');
+    }
+
+    SearchSnippet {
+        before: before_text,
+        matched: text[start..end].to_string(),
+        after: after_text,
+    }
+}
+
+/// Upper bound on the compiled size of user-supplied search patterns
+const SEARCH_PATTERN_SIZE_LIMIT: usize = 1 << 20;
+
+/// Translate a glob pattern (`*`, `?`) into an unanchored regex
+pub(super) fn glob_to_regex(glob: &str) -> String {
+    let mut pattern = String::with_capacity(glob.len() * 2);
+    for ch in glob.chars() {
+        match ch {
+            '*' => pattern.push_str(".*?"),
+            '?' => pattern.push('.'),
+            _ => pattern.push_str(&regex::escape(ch.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    pattern
+}
+
+/// Compile a case-insensitive matcher for `query` in the given search mode
+/// ("plain" by default, "regex" or "glob")
+pub(super) fn build_matcher(query: &str, mode: Option<&str>) -> Result<Regex, String> {
+    let pattern = match mode.unwrap_or("plain") {
+        "plain" => regex::escape(query),
+        "regex" => query.to_string(),
+        "glob" => glob_to_regex(query),
+        other => return Err(format!("Unknown search mode: {other}")),
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(true)
+        .size_limit(SEARCH_PATTERN_SIZE_LIMIT)
+        .build()
+        .map_err(|e| format!("Invalid search pattern: {e}"))
+}
+
+/// Snippet of the first non-empty match in any string of `value`
+fn find_snippet(value: &serde_json::Value, matcher: &Regex) -> Option<SearchSnippet> {
+    match value {
+        serde_json::Value::String(s) => matcher
+            .find_iter(s)
+            .find(|m| !m.is_empty())
+            .map(|m| build_snippet(s, (m.start(), m.end()))),
+        serde_json::Value::Array(arr) => arr.iter().find_map(|v| find_snippet(v, matcher)),
+        serde_json::Value::Object(obj) => obj.values().find_map(|v| find_snippet(v, matcher)),
+        _ => None,
+    }
+}
+
+/// First match in message content, with the type and index of its block
+fn find_block_match(
+    content: &serde_json::Value,
+    matcher: &Regex,
+    fields: SearchFields,
+) -> Option<(String, Option<usize>, SearchSnippet)> {
+    let serde_json::Value::Array(blocks) = content else {
+        if !fields.contains(SearchField::Text) {
+            return None;
+        }
+        return find_snippet(content, matcher).map(|snippet| ("text".to_string(), None, snippet));
+    };
+
+    blocks.iter().enumerate().find_map(|(index, block)| {
+        let block_type = block.get("type").and_then(|t| t.as_str()).unwrap_or("text");
+        let searchable = fields.searchable_part(block_type, block)?;
+        find_snippet(searchable, matcher)
+            .map(|snippet| (block_type.to_string(), Some(index), snippet))
+    })
+}
+
+/// First match in a log entry: its content blocks, then the tool payloads
+/// stored next to the message (`toolUse` / `toolUseResult`)
+fn find_entry_match(
+    log_entry: &RawLogEntry,
+    matcher: &Regex,
+    fields: SearchFields,
+) -> Option<(String, Option<usize>, SearchSnippet)> {
+    let tool_payload = |value: &Option<serde_json::Value>, field, block_type: &str| {
+        value
+            .as_ref()
+            .filter(|_| fields.contains(field))
+            .and_then(|v| find_snippet(v, matcher))
+            .map(|snippet| (block_type.to_string(), None, snippet))
+    };
+
+    log_entry
+        .message
+        .as_ref()
+        .and_then(|m| find_block_match(&m.content, matcher, fields))
+        .or_else(|| tool_payload(&log_entry.tool_use, SearchField::ToolInput, "tool_use"))
+        .or_else(|| {
+            tool_payload(
+                &log_entry.tool_use_result,
+                SearchField::ToolResult,
+                "tool_result",
+            )
+        })
+}
+
+/// Search the user and assistant messages of one session file
+#[allow(unsafe_code)] // Required for mmap performance optimization
+fn search_project_file(
+    file_path: &Path,
+    matcher: &Regex,
+    fields: SearchFields,
+    cancel: &AtomicBool,
+) -> Vec<ProjectSearchMatch> {
+    let _permit = acquire_file_permit();
+    let Ok(file) = fs::File::open(long_path(file_path)) else {
+        return Vec::new();
+    };
+
+    // SAFETY: We're only reading the file, and the file handle is kept open
+    // for the duration of the mmap's lifetime. Session files are append-only.
+    let Ok(mmap) = (unsafe { Mmap::map(&file) }) else {
+        return Vec::new();
+    };
+
+    let file_path_str = display_path(file_path);
+    let mut matches = Vec::with_capacity(SEARCH_RESULTS_INITIAL_CAPACITY);
+    let mut parse_buffer = Vec::with_capacity(PARSE_BUFFER_INITIAL_CAPACITY);
+
+    for (line_num, (start, end)) in find_line_ranges(&mmap).into_iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        parse_buffer.clear();
+        parse_buffer.extend_from_slice(&mmap[start..end]);
+
+        let Ok(log_entry) = simd_json::serde::from_slice::<RawLogEntry>(&mut parse_buffer) else {
+            continue;
+        };
+        if log_entry.message_type != "user" && log_entry.message_type != "assistant" {
+            continue;
+        }
+        let Some((block_type, block_index, snippet)) =
+            find_entry_match(&log_entry, matcher, fields)
+        else {
+            continue;
+        };
+
+        matches.push(ProjectSearchMatch {
+            message_uuid: log_entry.uuid.unwrap_or_else(|| {
+                stable_line_id(log_entry.session_id.as_deref(), file_path, line_num)
+            }),
+            session_id: log_entry
+                .session_id
+                .unwrap_or_else(|| "unknown-session".to_string()),
+            file_path: file_path_str.clone(),
+            timestamp: log_entry.timestamp.unwrap_or_default(),
+            message_type: log_entry.message_type,
+            is_sidechain: log_entry.is_sidechain == Some(true),
+            snippet,
+            block_type: Some(block_type),
+            block_index,
+        });
+    }
+
+    matches
+}
+
+/// Search every session file of a project, newest matches first
+#[allow(clippy::too_many_arguments)]
+fn run_project_search(
+    project_path: &str,
+    query: &str,
+    mode: Option<&str>,
+    fields: Option<&[String]>,
+    range: TimeRange,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    cancel: &AtomicBool,
+) -> Result<Vec<ProjectSearchMatch>, AppError> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(AppError::invalid_input("Search query must not be empty"));
+    }
+    let matcher = build_matcher(query, mode)?;
+    let fields = SearchFields::parse(fields)?;
+    if !Path::new(project_path).is_dir() {
+        return Err(AppError::not_found(format!(
+            "Project not found: {project_path}"
+        )));
+    }
+
+    let file_paths: Vec<PathBuf> = WalkDir::new(project_path)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .map(|e| e.path().to_path_buf())
+        .collect();
+
+    let mut matches: Vec<ProjectSearchMatch> = file_paths
+        .par_iter()
+        .filter(|path| range.overlaps_file(path))
+        .flat_map(|path| search_project_file(path, &matcher, fields, cancel))
+        .filter(|m| range.contains(&m.timestamp))
+        .collect();
+    check_cancelled(cancel)?;
+
+    keep_newest(&mut matches, offset, limit);
+    Ok(matches)
+}
+
+/// Full-text search across every session file of a project
+///
+/// `mode` is "plain" (default), "regex" or "glob"; matching ignores case.
+/// `fields` limits the search to "text", "thinking", "`tool_input`" and/or
+/// "`tool_result`" (default: all). `from`/`to` (RFC 3339 or `YYYY-MM-DD`)
+/// restrict matches to a time range, skipping files outside it unread.
+/// Returns the newest-first page of `limit` matches (default 200) after
+/// `offset`. A `request_id` lets `cancel_search` stop the search.
 #[tauri::command]
-pub async fn search_messages(
+#[allow(clippy::too_many_arguments)]
+pub async fn search_project_messages(
+    state: State<'_, SearchCancellations>,
+    project_path: String,
+    query: String,
+    limit: Option<usize>,
+    mode: Option<String>,
+    fields: Option<Vec<String>>,
+    offset: Option<usize>,
+    request_id: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<ProjectSearchMatch>, AppError> {
+    let _timer = OperationTimer::start("search_project_messages");
+
+    let range = TimeRange::parse(from.as_deref(), to.as_deref())?;
+    let request = state.register(request_id);
+    let cancel = Arc::clone(&request.flag);
+    tauri::async_runtime::spawn_blocking(move || {
+        let _search = acquire_search_permit();
+        run_project_search(
+            &project_path,
+            &query,
+            mode.as_deref(),
+            fields.as_deref(),
+            range,
+            offset,
+            limit,
+            &cancel,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Sort matches newest first and keep the page after `offset` of at most
+/// `limit` matches (default 200)
+fn keep_newest(matches: &mut Vec<ProjectSearchMatch>, offset: Option<usize>, limit: Option<usize>) {
+    matches.sort_by(|a, b| {
+        b.timestamp
+            .cmp(&a.timestamp)
+            .then_with(|| a.message_uuid.cmp(&b.message_uuid))
+    });
+    paginate(
+        matches,
+        offset,
+        limit.unwrap_or(DEFAULT_PROJECT_SEARCH_LIMIT),
+    );
+}
+
+/// Search every project in parallel, calling `on_project` as each completes
+#[allow(clippy::too_many_arguments)]
+fn search_projects(
+    projects_path: &Path,
+    search_id: &str,
+    matcher: &Regex,
+    fields: SearchFields,
+    range: TimeRange,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    cancel: &AtomicBool,
+    on_project: impl Fn(ProjectSearchResultsEvent) + Sync,
+) -> Result<GlobalSearchSummary, AppError> {
+    let mut projects: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for (project_name, session_path) in collect_session_files(projects_path)? {
+        projects.entry(project_name).or_default().push(session_path);
+    }
+
+    let total_projects = projects.len();
+    let completed = AtomicUsize::new(0);
+    let match_counts: Vec<usize> = projects
+        .par_iter()
+        .map(|(raw_name, session_files)| {
+            if cancel.load(Ordering::Relaxed) {
+                return 0;
+            }
+            let mut matches: Vec<ProjectSearchMatch> = session_files
+                .iter()
+                .filter(|path| range.overlaps_file(path))
+                .flat_map(|path| search_project_file(path, matcher, fields, cancel))
+                .filter(|m| range.contains(&m.timestamp))
+                .collect();
+            if cancel.load(Ordering::Relaxed) {
+                return 0;
+            }
+            keep_newest(&mut matches, offset, limit);
+            let match_count = matches.len();
+
+            on_project(ProjectSearchResultsEvent {
+                search_id: search_id.to_string(),
+                project_name: extract_project_name(raw_name),
+                project_path: display_path(&projects_path.join(raw_name)),
+                matches,
+                completed_projects: completed.fetch_add(1, Ordering::SeqCst) + 1,
+                total_projects,
+            });
+            match_count
+        })
+        .collect();
+    check_cancelled(cancel)?;
+
+    Ok(GlobalSearchSummary {
+        search_id: search_id.to_string(),
+        total_projects,
+        projects_with_matches: match_counts.iter().filter(|&&n| n > 0).count(),
+        total_matches: match_counts.iter().sum(),
+    })
+}
+
+/// Search all projects, streaming each project's matches to the frontend as
+/// `project-search-results` events tagged with `search_id`
+///
+/// Takes the same `mode`, `fields`, `from` and `to` as
+/// `search_project_messages`; `offset` and `limit` apply per project. Resolves once every project has been
+/// searched; `cancel_search` with the `search_id` stops it.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn search_all_projects(
+    state: State<'_, SearchCancellations>,
     claude_path: String,
     query: String,
-    _filters: serde_json::Value,
-) -> Result<Vec<ClaudeMessage>, String> {
-    #[cfg(debug_assertions)]
-    let start_time = std::time::Instant::now();
+    search_id: String,
+    mode: Option<String>,
+    fields: Option<Vec<String>>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<GlobalSearchSummary, AppError> {
+    let _timer = OperationTimer::start("search_all_projects");
 
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(AppError::invalid_input("Search query must not be empty"));
+    }
+    let matcher = build_matcher(query, mode.as_deref())?;
+    let fields = SearchFields::parse(fields.as_deref())?;
+    let range = TimeRange::parse(from.as_deref(), to.as_deref())?;
     let projects_path = PathBuf::from(&claude_path).join("projects");
+    if !projects_path.is_dir() {
+        return Err(AppError::not_found("Projects directory not found"));
+    }
+
+    let request = state.register(Some(search_id.clone()));
+    let cancel = Arc::clone(&request.flag);
+    tauri::async_runtime::spawn_blocking(move || {
+        let _search = acquire_search_permit();
+        search_projects(
+            &projects_path,
+            &search_id,
+            &matcher,
+            fields,
+            range,
+            offset,
+            limit,
+            &cancel,
+            |event| {
+                if let Some(listener) = PROJECT_SEARCH_LISTENER.get() {
+                    listener(&event);
+                }
+            },
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+fn open_search_index() -> Result<(SearchIndex, PathBuf), String> {
+    let index_path = ensure_metadata_folder()?.join(SEARCH_INDEX_FILE_NAME);
+    Ok((SearchIndex::open(&index_path)?, index_path))
+}
+
+/// Open the search index for a query; fails with `IndexStale` until
+/// `refresh_search_index` has indexed at least one session file
+fn open_search_index_for_query() -> Result<SearchIndex, AppError> {
+    let (index, _) = open_search_index()?;
+    if index.indexed_file_count()? == 0 {
+        return Err(AppError::index_stale(
+            "The search index is empty; refresh it first",
+        ));
+    }
+    Ok(index)
+}
+
+/// Update the persistent search index, re-reading only changed session files
+#[tauri::command]
+pub async fn refresh_search_index(claude_path: String) -> Result<SearchIndexStatus, AppError> {
+    let _timer = OperationTimer::start("refresh_search_index");
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let projects_path = PathBuf::from(&claude_path).join("projects");
+        let session_files: Vec<PathBuf> = if projects_path.is_dir() {
+            collect_session_files(&projects_path)?
+                .into_iter()
+                .map(|(_, path)| path)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let (mut index, index_path) = open_search_index()?;
+        let stats = index.refresh(&session_files)?;
+        Ok(SearchIndexStatus {
+            index_path: display_path(&index_path),
+            indexed_files: stats.indexed_files,
+            updated_files: stats.updated_files,
+            removed_files: stats.removed_files,
+            message_count: index.message_count()?,
+            error_count: index.error_count()?,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+fn indexed_match(message: IndexedMessage, query_lower: &str) -> ProjectSearchMatch {
+    // FTS tokenization can match where a plain substring search does not
+    let range = find_ignore_case(&message.text, query_lower).unwrap_or((0, 0));
+    ProjectSearchMatch {
+        message_uuid: message.uuid,
+        session_id: message.session_id,
+        file_path: message.file_path,
+        timestamp: message.timestamp,
+        message_type: message.message_type,
+        is_sidechain: message.is_sidechain,
+        snippet: build_snippet(&message.text, range),
+        block_type: None,
+        block_index: None,
+    }
+}
 
-    if !projects_path.exists() {
-        return Ok(vec![]);
+/// Search messages through the persistent index (see `refresh_search_index`)
+///
+/// `project_path` restricts results to one project. Returns the newest-first
+/// page of `limit` matches (default 200) after `offset`.
+#[tauri::command]
+pub async fn search_indexed_messages(
+    query: String,
+    project_path: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<Vec<ProjectSearchMatch>, AppError> {
+    let _timer = OperationTimer::start("search_indexed_messages");
+
+    let query = query.trim().to_string();
+    if query.is_empty() {
+        return Err(AppError::invalid_input("Search query must not be empty"));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let _search = acquire_search_permit();
+        let index = open_search_index_for_query()?;
+        let limit = limit.unwrap_or(DEFAULT_PROJECT_SEARCH_LIMIT);
+        let mut messages = index.search(
+            &query,
+            project_path.as_deref(),
+            offset.unwrap_or(0).saturating_add(limit),
+        )?;
+        paginate(&mut messages, offset, limit);
+        let query_lower = query.to_lowercase();
+        Ok(messages
+            .into_iter()
+            .map(|message| indexed_match(message, &query_lower))
+            .collect())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+fn error_match(error: IndexedError) -> ErrorSearchMatch {
+    ErrorSearchMatch {
+        message_uuid: error.uuid,
+        session_id: error.session_id,
+        file_path: error.file_path,
+        timestamp: error.timestamp,
+        tool_name: error.tool_name,
+        kind: error.kind,
+        message: error.message,
+        context: error.context,
+        signature: error.signature,
+        fingerprint: error.fingerprint,
+    }
+}
+
+/// Search the errors extracted from tool results by `refresh_search_index`
+///
+/// An empty `query` lists every error. `kind` keeps only "panic",
+/// "`compile_error`", "exception" or "error", and `fingerprint` the
+/// occurrences of one error group; `project_path` restricts results to one
+/// project. Returns the newest-first page of `limit` errors (default 200)
+/// after `offset`.
+#[tauri::command]
+pub async fn search_errors(
+    query: String,
+    kind: Option<String>,
+    fingerprint: Option<String>,
+    project_path: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<Vec<ErrorSearchMatch>, AppError> {
+    let _timer = OperationTimer::start("search_errors");
+
+    let query = query.trim().to_string();
+    tauri::async_runtime::spawn_blocking(move || {
+        let _search = acquire_search_permit();
+        let index = open_search_index_for_query()?;
+        let limit = limit.unwrap_or(DEFAULT_PROJECT_SEARCH_LIMIT);
+        let mut errors = index.search_errors(
+            &query,
+            kind.as_deref(),
+            fingerprint.as_deref(),
+            project_path.as_deref(),
+            offset.unwrap_or(0).saturating_add(limit),
+        )?;
+        paginate(&mut errors, offset, limit);
+        Ok(errors.into_iter().map(error_match).collect())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+fn error_group(group: IndexedErrorGroup) -> ErrorGroup {
+    ErrorGroup {
+        fingerprint: group.fingerprint,
+        kind: group.kind,
+        message: group.message,
+        context: group.context,
+        tool_name: group.tool_name,
+        occurrences: group.occurrences,
+        session_ids: group.session_ids,
+        first_seen: group.first_seen,
+        last_seen: group.last_seen,
     }
+}
+
+/// Indexed errors grouped by fingerprint, most recently seen first
+///
+/// Each group counts the occurrences of one error with its first/last seen
+/// times and affected sessions; `search_errors` with the fingerprint lists
+/// them. Takes the same `kind` and `project_path` filters as
+/// `search_errors` and returns at most `limit` groups (default 200).
+#[tauri::command]
+pub async fn get_error_groups(
+    kind: Option<String>,
+    project_path: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<ErrorGroup>, AppError> {
+    let _timer = OperationTimer::start("get_error_groups");
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let index = open_search_index_for_query()?;
+        let groups = index.error_groups(
+            kind.as_deref(),
+            project_path.as_deref(),
+            limit.unwrap_or(DEFAULT_PROJECT_SEARCH_LIMIT),
+        )?;
+        Ok(groups.into_iter().map(error_group).collect())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Search the messages of every session file under `claude_path`
+///
+/// Body of `search_messages`; stops with "Search cancelled" once `cancel` is set.
+pub fn run_message_search(
+    claude_path: &str,
+    query: &str,
+    filters: &serde_json::Value,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    cancel: &AtomicBool,
+) -> Result<Vec<ClaudeMessage>, AppError> {
+    #[cfg(debug_assertions)]
+    let start_time = std::time::Instant::now();
+
+    let fields: Option<Vec<String>> = match filters.get("fields") {
+        Some(value) => Some(
+            serde_json::from_value(value.clone()).map_err(|e| format!("Invalid fields: {e}"))?,
+        ),
+        None => None,
+    };
+    let fields = SearchFields::parse(fields.as_deref())?;
+    let range = match filters.get("dateRange") {
+        Some(value) => {
+            let bounds: [Option<String>; 2] = serde_json::from_value(value.clone())
+                .map_err(|e| format!("Invalid dateRange: {e}"))?;
+            TimeRange::parse(bounds[0].as_deref(), bounds[1].as_deref())?
+        }
+        None => TimeRange::default(),
+    };
+    let matcher = build_matcher(query, None)?;
+
+    let projects_path = PathBuf::from(claude_path).join("projects");
 
     // 1. Collect all JSONL file paths
     let file_paths: Vec<PathBuf> = WalkDir::new(&projects_path)
@@ -160,10 +1082,27 @@ pub async fn search_messages(
     eprintln!("🔍 search_messages: searching {} files", file_paths.len());
 
     // 2. Parallel search using rayon
-    let all_messages: Vec<ClaudeMessage> = file_paths
+    let mut all_messages: Vec<ClaudeMessage> = file_paths
         .par_iter()
-        .flat_map(|path| search_in_file(path, &query))
+        .filter(|path| range.overlaps_file(path))
+        .flat_map(|path| search_in_file(path, &matcher, fields, cancel))
+        .filter(|m| range.contains(&m.timestamp))
         .collect();
+    check_cancelled(cancel)?;
+
+    // 3. Claude desktop app conversations, when their folder is given
+    if let Some(desktop_path) = filters.get("desktopPath").and_then(|v| v.as_str()) {
+        all_messages.extend(
+            read_all_desktop_messages(Path::new(desktop_path))
+                .into_iter()
+                .filter(|m| range.contains(&m.timestamp))
+                .filter(|m| {
+                    m.content.as_ref().is_some_and(|content| {
+                        find_block_match(content, &matcher, fields).is_some()
+                    })
+                }),
+        );
+    }
 
     #[cfg(debug_assertions)]
     {
@@ -175,14 +1114,46 @@ pub async fn search_messages(
         );
     }
 
+    paginate(&mut all_messages, offset, limit.unwrap_or(usize::MAX));
     Ok(all_messages)
 }
 
+/// Search messages of all projects
+///
+/// `filters.fields` scopes the search like in `search_project_messages` and
+/// `filters.dateRange` (`[from, to]`) restricts it to a time range.
+/// `filters.desktopPath` also searches the Claude desktop app conversations
+/// exported under that folder.
+/// Returns every match unless `offset`/`limit` select a page; a
+/// `request_id` lets `cancel_search` stop the search.
+#[tauri::command]
+pub async fn search_messages(
+    state: State<'_, SearchCancellations>,
+    claude_path: String,
+    query: String,
+    filters: serde_json::Value,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    request_id: Option<String>,
+) -> Result<Vec<ClaudeMessage>, AppError> {
+    let _timer = OperationTimer::start("search_messages");
+
+    let request = state.register(request_id);
+    let cancel = Arc::clone(&request.flag);
+    tauri::async_runtime::spawn_blocking(move || {
+        let _search = acquire_search_permit();
+        run_message_search(&claude_path, &query, &filters, offset, limit, &cancel)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs::File;
     use std::io::Write;
+    use std::sync::Mutex;
     use tempfile::TempDir;
 
     fn create_sample_user_message(uuid: &str, session_id: &str, content: &str) -> String {
@@ -197,8 +1168,8 @@ mod tests {
         )
     }
 
-    #[tokio::test]
-    async fn test_search_messages_basic() {
+    #[test]
+    fn test_search_messages_basic() {
         let temp_dir = TempDir::new().unwrap();
         let projects_dir = temp_dir.path().join("projects");
         let project_dir = projects_dir.join("test-project");
@@ -215,20 +1186,22 @@ mod tests {
         let mut file = File::create(&file_path).unwrap();
         file.write_all(content.as_bytes()).unwrap();
 
-        let result = search_messages(
-            temp_dir.path().to_string_lossy().to_string(),
-            "Rust".to_string(),
-            serde_json::json!({}),
-        )
-        .await;
+        let result = run_message_search(
+            &temp_dir.path().to_string_lossy(),
+            "Rust",
+            &serde_json::json!({}),
+            None,
+            None,
+            &AtomicBool::new(false),
+        );
 
         assert!(result.is_ok());
         let messages = result.unwrap();
         assert_eq!(messages.len(), 2); // Both messages contain "Rust"
     }
 
-    #[tokio::test]
-    async fn test_search_messages_case_insensitive() {
+    #[test]
+    fn test_search_messages_case_insensitive() {
         let temp_dir = TempDir::new().unwrap();
         let projects_dir = temp_dir.path().join("projects");
         let project_dir = projects_dir.join("test-project");
@@ -243,20 +1216,22 @@ mod tests {
         let mut file = File::create(&file_path).unwrap();
         file.write_all(content.as_bytes()).unwrap();
 
-        let result = search_messages(
-            temp_dir.path().to_string_lossy().to_string(),
-            "hello".to_string(), // lowercase
-            serde_json::json!({}),
-        )
-        .await;
+        let result = run_message_search(
+            &temp_dir.path().to_string_lossy(),
+            "hello", // lowercase
+            &serde_json::json!({}),
+            None,
+            None,
+            &AtomicBool::new(false),
+        );
 
         assert!(result.is_ok());
         let messages = result.unwrap();
         assert_eq!(messages.len(), 1);
     }
 
-    #[tokio::test]
-    async fn test_search_messages_no_results() {
+    #[test]
+    fn test_search_messages_no_results() {
         let temp_dir = TempDir::new().unwrap();
         let projects_dir = temp_dir.path().join("projects");
         let project_dir = projects_dir.join("test-project");
@@ -271,30 +1246,373 @@ mod tests {
         let mut file = File::create(&file_path).unwrap();
         file.write_all(content.as_bytes()).unwrap();
 
-        let result = search_messages(
-            temp_dir.path().to_string_lossy().to_string(),
-            "nonexistent".to_string(),
-            serde_json::json!({}),
-        )
-        .await;
+        let result = run_message_search(
+            &temp_dir.path().to_string_lossy(),
+            "nonexistent",
+            &serde_json::json!({}),
+            None,
+            None,
+            &AtomicBool::new(false),
+        );
 
         assert!(result.is_ok());
         assert!(result.unwrap().is_empty());
     }
 
-    #[tokio::test]
-    async fn test_search_messages_empty_projects_dir() {
+    #[test]
+    fn test_search_messages_includes_desktop_conversations() {
         let temp_dir = TempDir::new().unwrap();
-        // Don't create projects directory
+        let desktop_dir = temp_dir.path().join("desktop");
+        std::fs::create_dir_all(&desktop_dir).unwrap();
+        let export = serde_json::json!([{
+            "uuid": "conv-1",
+            "name": "Lifetimes",
+            "chat_messages": [
+                {"uuid": "d1", "sender": "human", "text": "Explain Rust lifetimes", "created_at": "2025-06-26T10:00:00Z"},
+                {"uuid": "d2", "sender": "assistant", "text": "They scope borrows", "created_at": "2025-06-26T10:01:00Z"}
+            ]
+        }]);
+        std::fs::write(desktop_dir.join("conversations.json"), export.to_string()).unwrap();
 
-        let result = search_messages(
-            temp_dir.path().to_string_lossy().to_string(),
-            "test".to_string(),
-            serde_json::json!({}),
+        let messages = run_message_search(
+            &temp_dir.path().to_string_lossy(),
+            "rust",
+            &serde_json::json!({"desktopPath": desktop_dir.to_string_lossy()}),
+            None,
+            None,
+            &AtomicBool::new(false),
         )
-        .await;
+        .unwrap();
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].uuid, "d1");
+        assert_eq!(messages[0].session_id, "conv-1");
+    }
+
+    #[test]
+    fn test_search_messages_empty_projects_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        // Don't create projects directory
+
+        let result = run_message_search(
+            &temp_dir.path().to_string_lossy(),
+            "test",
+            &serde_json::json!({}),
+            None,
+            None,
+            &AtomicBool::new(false),
+        );
 
         assert!(result.is_ok());
         assert!(result.unwrap().is_empty());
     }
+
+    #[test]
+    fn test_find_ignore_case_maps_to_original_bytes() {
+        assert_eq!(
+            find_ignore_case("Fix the LOGIN bug", "login"),
+            Some((8, 13))
+        );
+        // 'İ' lowercases to two characters
+        let text = "İstanbul Login";
+        let (start, end) = find_ignore_case(text, "login").unwrap();
+        assert_eq!(&text[start..end], "Login");
+        assert_eq!(find_ignore_case("nothing here", "login"), None);
+    }
+
+    #[test]
+    fn test_search_project_messages_snippets() {
+        let temp_dir = TempDir::new().unwrap();
+        let long_prefix = "word ".repeat(20);
+        let content = format!(
+            "{}\n{}\n",
+            create_sample_user_message(
+                "uuid-1",
+                "session-1",
+                &format!("{long_prefix}the Login\\nbug")
+            ),
+            create_sample_assistant_message("uuid-2", "session-2", "No match here")
+        );
+        fs::write(temp_dir.path().join("s1.jsonl"), content).unwrap();
+
+        let matches = run_project_search(
+            &temp_dir.path().to_string_lossy(),
+            "login",
+            None,
+            None,
+            TimeRange::default(),
+            None,
+            None,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].message_uuid, "uuid-1");
+        assert_eq!(matches[0].session_id, "session-1");
+        assert_eq!(matches[0].snippet.matched, "Login");
+        assert!(matches[0].snippet.before.starts_with('…'));
+        assert!(matches[0].snippet.before.ends_with("word the "));
+        assert_eq!(matches[0].snippet.after, " bug");
+
+        let empty = run_project_search(
+            &temp_dir.path().to_string_lossy(),
+            "  ",
+            None,
+            None,
+            TimeRange::default(),
+            None,
+            None,
+            &AtomicBool::new(false),
+        );
+        assert!(empty.is_err());
+    }
+
+    #[test]
+    fn test_search_project_messages_modes_report_block() {
+        let temp_dir = TempDir::new().unwrap();
+        let line = r#"{"uuid":"uuid-1","sessionId":"session-1","timestamp":"2025-06-26T10:01:00Z","type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Reading the loader"},{"type":"tool_use","id":"toolu_1","name":"Grep","input":{"pattern":"fn  load_session(path)"}}]}}"#;
+        fs::write(temp_dir.path().join("s1.jsonl"), format!("{line}\n")).unwrap();
+        let project = temp_dir.path().to_string_lossy().to_string();
+        let search = |query: &str, mode: &str| {
+            run_project_search(
+                &project,
+                query,
+                Some(mode),
+                None,
+                TimeRange::default(),
+                None,
+                None,
+                &AtomicBool::new(false),
+            )
+        };
+
+        let regex_matches = search(r"fn\s+load_\w+", "regex").unwrap();
+        assert_eq!(regex_matches.len(), 1);
+        assert_eq!(regex_matches[0].snippet.matched, "fn  load_session");
+        assert_eq!(regex_matches[0].block_type.as_deref(), Some("tool_use"));
+        assert_eq!(regex_matches[0].block_index, Some(1));
+
+        let glob_matches = search("READ*loader", "glob").unwrap();
+        assert_eq!(glob_matches[0].snippet.matched, "Reading the loader");
+        assert_eq!(glob_matches[0].block_type.as_deref(), Some("text"));
+        assert_eq!(glob_matches[0].block_index, Some(0));
+
+        // Regex metacharacters are literal in plain mode; tool ids are not searched
+        assert!(search("load_session(", "plain").unwrap().len() == 1);
+        assert!(search("toolu_1", "plain").unwrap().is_empty());
+        assert!(search("(unclosed", "regex").is_err());
+        assert!(search("x", "fuzzy").is_err());
+    }
+
+    #[test]
+    fn test_search_fields_scope_tool_payloads() {
+        let temp_dir = TempDir::new().unwrap();
+        let lines = [
+            r#"{"uuid":"uuid-1","sessionId":"session-1","timestamp":"2025-06-26T10:01:00Z","type":"assistant","message":{"role":"assistant","content":[{"type":"thinking","thinking":"Clean the build dir"},{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"rm -rf target"}}]}}"#,
+            r#"{"uuid":"uuid-2","sessionId":"session-1","timestamp":"2025-06-26T10:02:00Z","type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","content":"done"}]},"toolUseResult":{"stdout":"removed target/debug","stderr":""}}"#,
+            r#"{"uuid":"uuid-3","sessionId":"session-1","timestamp":"2025-06-26T10:03:00Z","type":"user","message":{"role":"user","content":"Why did you rm -rf target?"}}"#,
+        ];
+        fs::write(temp_dir.path().join("s1.jsonl"), lines.join("\n")).unwrap();
+        let project = temp_dir.path().to_string_lossy().to_string();
+        let search = |query: &str, fields: &[&str]| {
+            run_project_search(
+                &project,
+                query,
+                None,
+                Some(&fields.iter().map(ToString::to_string).collect::<Vec<_>>()),
+                TimeRange::default(),
+                None,
+                None,
+                &AtomicBool::new(false),
+            )
+        };
+
+        let inputs = search("rm -rf", &["tool_input"]).unwrap();
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].message_uuid, "uuid-1");
+        assert_eq!(inputs[0].block_type.as_deref(), Some("tool_use"));
+
+        let results = search("target/debug", &["tool_result"]).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].block_type.as_deref(), Some("tool_result"));
+        assert_eq!(results[0].block_index, None);
+
+        assert!(search("build dir", &["text"]).unwrap().is_empty());
+        assert_eq!(search("build dir", &["thinking"]).unwrap().len(), 1);
+        assert_eq!(search("rm -rf", &[]).unwrap().len(), 2);
+        assert!(search("rm", &["attachments"]).is_err());
+
+        // The global search takes the same scope through its filters
+        let projects_dir = temp_dir.path().join("claude").join("projects").join("p");
+        std::fs::create_dir_all(&projects_dir).unwrap();
+        fs::write(projects_dir.join("s1.jsonl"), lines.join("\n")).unwrap();
+        let messages = run_message_search(
+            &temp_dir.path().join("claude").to_string_lossy(),
+            "target/debug",
+            &serde_json::json!({ "fields": ["tool_result"] }),
+            None,
+            None,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].uuid, "uuid-2");
+    }
+
+    #[test]
+    fn test_search_projects_reports_each_project() {
+        let temp_dir = TempDir::new().unwrap();
+        let projects_path = temp_dir.path().join("projects");
+        for (project, text) in [
+            ("-Users-me-alpha", "Deploy the login service"),
+            ("-Users-me-beta", "Nothing relevant"),
+            ("-Users-me-gamma", "Login page styles"),
+        ] {
+            let dir = projects_path.join(project);
+            std::fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("s.jsonl"),
+                create_sample_user_message("uuid-1", "session-1", text),
+            )
+            .unwrap();
+        }
+        let events = Mutex::new(Vec::new());
+
+        let summary = search_projects(
+            &projects_path,
+            "search-1",
+            &build_matcher("login", None).unwrap(),
+            SearchFields::ALL,
+            TimeRange::default(),
+            None,
+            None,
+            &AtomicBool::new(false),
+            |event| events.lock().unwrap().push(event),
+        )
+        .unwrap();
+
+        assert_eq!(summary.search_id, "search-1");
+        assert_eq!(summary.total_projects, 3);
+        assert_eq!(summary.projects_with_matches, 2);
+        assert_eq!(summary.total_matches, 2);
+
+        let mut events = events.into_inner().unwrap();
+        assert_eq!(events.len(), 3);
+        let mut completed: Vec<usize> = events.iter().map(|e| e.completed_projects).collect();
+        completed.sort_unstable();
+        assert_eq!(completed, vec![1, 2, 3]);
+        events.sort_by(|a, b| a.project_name.cmp(&b.project_name));
+        assert_eq!(events[0].project_name, "alpha");
+        assert_eq!(events[0].matches[0].snippet.matched, "login");
+        assert!(events[1].matches.is_empty());
+    }
+
+    #[test]
+    fn test_project_search_pagination_and_cancellation() {
+        let temp_dir = TempDir::new().unwrap();
+        let lines: Vec<String> = (1..=5)
+            .map(|n| {
+                format!(
+                    r#"{{"uuid":"uuid-{n}","sessionId":"session-1","timestamp":"2025-06-26T10:0{n}:00Z","type":"user","message":{{"role":"user","content":"deploy step {n}"}}}}"#
+                )
+            })
+            .collect();
+        fs::write(temp_dir.path().join("s1.jsonl"), lines.join("\n")).unwrap();
+        let project = temp_dir.path().to_string_lossy();
+        let search = |offset, limit, cancel: &AtomicBool| {
+            run_project_search(
+                &project,
+                "deploy",
+                None,
+                None,
+                TimeRange::default(),
+                offset,
+                limit,
+                cancel,
+            )
+        };
+
+        let page = search(Some(1), Some(2), &AtomicBool::new(false)).unwrap();
+        let uuids: Vec<&str> = page.iter().map(|m| m.message_uuid.as_str()).collect();
+        assert_eq!(uuids, vec!["uuid-4", "uuid-3"]);
+        assert!(search(Some(10), None, &AtomicBool::new(false))
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(
+            search(None, None, &AtomicBool::new(true)).unwrap_err(),
+            AppError::cancelled(SEARCH_CANCELLED)
+        );
+    }
+
+    #[test]
+    fn test_search_cancellations_track_running_requests() {
+        let cancellations = SearchCancellations::default();
+        assert!(!cancellations.cancel("search-1"));
+
+        let request = cancellations.register(Some("search-1".to_string()));
+        assert!(cancellations.cancel("search-1"));
+        assert!(request.flag.load(Ordering::SeqCst));
+
+        // A newer search reusing the ID keeps its flag when the old one ends
+        let newer = cancellations.register(Some("search-1".to_string()));
+        drop(request);
+        assert!(cancellations.cancel("search-1"));
+        assert!(newer.flag.load(Ordering::SeqCst));
+
+        drop(newer);
+        assert!(!cancellations.cancel("search-1"));
+    }
+
+    #[test]
+    fn test_date_range_prunes_files_and_messages() {
+        let temp_dir = TempDir::new().unwrap();
+        let message = |uuid: &str, day: u32| {
+            format!(
+                r#"{{"uuid":"{uuid}","sessionId":"s","timestamp":"2025-06-{day:02}T10:00:00Z","type":"user","message":{{"role":"user","content":"deploy it"}}}}"#
+            )
+        };
+        let old = [message("old-1", 1), message("old-2", 2)].join("\n");
+        let recent = [message("recent-1", 9), message("recent-2", 12)].join("\n");
+        let project_dir = temp_dir.path().join("projects").join("p");
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::write(project_dir.join("old.jsonl"), &old).unwrap();
+        fs::write(project_dir.join("recent.jsonl"), &recent).unwrap();
+
+        let range = TimeRange::parse(Some("2025-06-08"), Some("2025-06-10")).unwrap();
+        assert!(!range.overlaps_file(&project_dir.join("old.jsonl")));
+        assert!(range.overlaps_file(&project_dir.join("recent.jsonl")));
+
+        let matches = run_project_search(
+            &project_dir.to_string_lossy(),
+            "deploy",
+            None,
+            None,
+            range,
+            None,
+            None,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+        let uuids: Vec<&str> = matches.iter().map(|m| m.message_uuid.as_str()).collect();
+        assert_eq!(uuids, vec!["recent-1"]);
+
+        let messages = run_message_search(
+            &temp_dir.path().to_string_lossy(),
+            "deploy",
+            &serde_json::json!({ "dateRange": ["2025-06-02T00:00:00Z", null] }),
+            None,
+            None,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+        let mut uuids: Vec<&str> = messages.iter().map(|m| m.uuid.as_str()).collect();
+        uuids.sort_unstable();
+        assert_eq!(uuids, vec!["old-2", "recent-1", "recent-2"]);
+
+        assert!(TimeRange::parse(Some("last week"), None).is_err());
+        assert!(TimeRange::parse(Some("2025-06-10"), Some("2025-06-01")).is_err());
+        assert!(TimeRange::default().contains("2025-06-01T00:00:00Z"));
+    }
 }
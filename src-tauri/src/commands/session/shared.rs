@@ -0,0 +1,78 @@
+//! Sessions shared by teammates
+//!
+//! `import_shared_session` decrypts a bundle written by `share_session` into
+//! `~/.claude-history-viewer/shared/<bundle_id>.json`. Every imported bundle
+//! is one session of the "Shared with me" pseudo-project.
+
+use super::sources::{chat_conversation, chat_session, HistorySource, SourceConversation};
+use crate::commands::metadata::get_metadata_folder;
+use crate::models::{ClaudeMessage, ClaudeSession, SharedSessionBundle};
+use crate::utils::long_path;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// `ClaudeSession::source` of imported shared sessions
+pub const SHARED_SOURCE: &str = "shared";
+
+/// Project name imported sessions are listed under
+pub const SHARED_PROJECT_NAME: &str = "Shared with me";
+
+/// Folder imported bundles are stored in
+pub(crate) fn shared_folder() -> Result<PathBuf, String> {
+    Ok(get_metadata_folder()?.join("shared"))
+}
+
+pub(crate) fn read_shared_bundle(path: &Path) -> Result<SharedSessionBundle, String> {
+    let content = fs::read_to_string(long_path(path))
+        .map_err(|e| format!("Failed to read shared session: {e}"))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse shared session: {e}"))
+}
+
+/// Listing of an imported bundle (None without messages)
+pub(crate) fn shared_session(path: &Path, bundle: &SharedSessionBundle) -> Option<ClaudeSession> {
+    chat_session(
+        SHARED_SOURCE,
+        path,
+        &bundle.bundle_id,
+        SHARED_PROJECT_NAME.to_string(),
+        bundle.title.clone(),
+        &bundle.messages,
+    )
+}
+
+/// Imported bundles, one session each
+pub(super) struct SharedSource;
+
+impl HistorySource for SharedSource {
+    fn read_conversations(root: &Path) -> Result<Vec<SourceConversation>, String> {
+        let Ok(entries) = fs::read_dir(long_path(root)) else {
+            return Ok(Vec::new());
+        };
+        let mut conversations = Vec::new();
+        for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let bundle = match read_shared_bundle(&path) {
+                Ok(bundle) => bundle,
+                Err(e) => {
+                    eprintln!("Skipping shared session {}: {e}", path.display());
+                    continue;
+                }
+            };
+            conversations.extend(chat_conversation(
+                SHARED_SOURCE,
+                &path,
+                &bundle.bundle_id,
+                SHARED_PROJECT_NAME.to_string(),
+                bundle.title,
+                bundle.messages,
+            ));
+        }
+        Ok(conversations)
+    }
+
+    fn read_messages(file_path: &Path, _session_id: &str) -> Result<Vec<ClaudeMessage>, String> {
+        Ok(read_shared_bundle(file_path)?.messages)
+    }
+}
@@ -0,0 +1,342 @@
+//! Chat histories of other tools
+//!
+//! A `HistorySource` reads the history format of one tool into the common
+//! session and message model. Sessions carry the source id in
+//! `ClaudeSession::source` so the UI can label them, and are opened with
+//! `load_source_session_messages` instead of the JSONL loaders:
+//! - `claude-desktop`: Claude desktop app conversation exports
+//! - `cursor`: Cursor chat and composer logs
+//! - `aider`: Aider `.aider.chat.history.md` files
+//! - `shared`: Sessions shared by teammates, imported from encrypted bundles
+
+use super::aider::{AiderSource, AIDER_SOURCE};
+use super::cursor::{CursorSource, CURSOR_SOURCE};
+use super::desktop::{DesktopSource, DESKTOP_SOURCE};
+use super::load::extract_user_text;
+use super::shared::{shared_folder, SharedSource, SHARED_SOURCE};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ClaudeMessage, ClaudeSession};
+use crate::utils::display_path;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// `ClaudeSession::source` id used for Claude Code's own sessions, whose
+/// `source` is None
+pub const CLAUDE_CODE_SOURCE: &str = "claude-code";
+
+/// A session of another tool with its messages
+pub struct SourceConversation {
+    pub session: ClaudeSession,
+    pub messages: Vec<ClaudeMessage>,
+}
+
+/// Reader of another tool's chat history
+pub(super) trait HistorySource {
+    /// Sessions stored under `root`, with their messages
+    fn read_conversations(root: &Path) -> Result<Vec<SourceConversation>, String>;
+
+    /// Sessions stored under `root`
+    fn read_sessions(root: &Path) -> Result<Vec<ClaudeSession>, String> {
+        Ok(Self::read_conversations(root)?
+            .into_iter()
+            .map(|conversation| conversation.session)
+            .collect())
+    }
+
+    /// Messages of a session, by its `file_path` and `actual_session_id`
+    fn read_messages(file_path: &Path, session_id: &str) -> Result<Vec<ClaudeMessage>, String>;
+}
+
+/// Sessions of a source, most recently modified first
+pub(super) fn read_sorted_sessions<S: HistorySource>(
+    root: &Path,
+) -> Result<Vec<ClaudeSession>, String> {
+    let mut sessions = S::read_sessions(root)?;
+    sessions.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
+    Ok(sessions)
+}
+
+/// A message of another tool in the common model
+pub(super) fn chat_message(
+    session_id: &str,
+    uuid: String,
+    parent_uuid: Option<String>,
+    timestamp: String,
+    message_type: &str,
+    content: Value,
+) -> ClaudeMessage {
+    let role = matches!(message_type, "user" | "assistant").then(|| message_type.to_string());
+    ClaudeMessage {
+        uuid,
+        parent_uuid,
+        session_id: session_id.to_string(),
+        timestamp,
+        message_type: message_type.to_string(),
+        content: Some(content),
+        tool_use: None,
+        tool_use_result: None,
+        is_sidechain: Some(false),
+        usage: None,
+        role,
+        model: None,
+        stop_reason: None,
+        cost_usd: None,
+        duration_ms: None,
+        message_id: None,
+        snapshot: None,
+        is_snapshot_update: None,
+        data: None,
+        tool_use_id: None,
+        parent_tool_use_id: None,
+        operation: None,
+        subtype: None,
+        level: None,
+        hook_count: None,
+        hook_infos: None,
+        stop_reason_system: None,
+        prevented_continuation: None,
+        compact_metadata: None,
+        microcompact_metadata: None,
+        git_branch: None,
+    }
+}
+
+fn has_block(message: &ClaudeMessage, matches: impl Fn(&Value) -> bool) -> bool {
+    message
+        .content
+        .as_ref()
+        .and_then(Value::as_array)
+        .is_some_and(|blocks| blocks.iter().any(&matches))
+}
+
+fn block_type(block: &Value) -> Option<&str> {
+    block.get("type").and_then(Value::as_str)
+}
+
+/// Conversation of another tool from its messages (None without messages)
+pub(super) fn chat_conversation(
+    source: &str,
+    file_path: &Path,
+    session_id: &str,
+    project_name: String,
+    title: Option<String>,
+    messages: Vec<ClaudeMessage>,
+) -> Option<SourceConversation> {
+    let session = chat_session(
+        source,
+        file_path,
+        session_id,
+        project_name,
+        title,
+        &messages,
+    )?;
+    Some(SourceConversation { session, messages })
+}
+
+/// Session listing of another tool's conversation (None without messages)
+///
+/// `session_id` only needs to be unique within `file_path`.
+pub(super) fn chat_session(
+    source: &str,
+    file_path: &Path,
+    session_id: &str,
+    project_name: String,
+    title: Option<String>,
+    messages: &[ClaudeMessage],
+) -> Option<ClaudeSession> {
+    let first = messages.first()?;
+    let last = messages.last()?;
+    let first_user_message = messages
+        .iter()
+        .filter(|message| message.message_type == "user")
+        .find_map(|message| message.content.as_ref().and_then(extract_user_text));
+    let file_path = display_path(file_path);
+
+    Some(ClaudeSession {
+        session_id: format!("{source}:{file_path}#{session_id}"),
+        actual_session_id: session_id.to_string(),
+        file_path,
+        project_name,
+        message_count: messages.len(),
+        raw_message_count: messages.len(),
+        first_message_time: first.timestamp.clone(),
+        last_message_time: last.timestamp.clone(),
+        last_modified: last.timestamp.clone(),
+        has_tool_use: messages
+            .iter()
+            .any(|message| has_block(message, |block| block_type(block) == Some("tool_use"))),
+        has_errors: messages.iter().any(|message| {
+            has_block(message, |block| {
+                block_type(block) == Some("tool_result")
+                    && block.get("is_error").and_then(Value::as_bool) == Some(true)
+            })
+        }),
+        summary: title
+            .filter(|title| !title.trim().is_empty())
+            .or_else(|| first_user_message.clone()),
+        first_user_message,
+        derived_fields: BTreeMap::new(),
+        source: Some(source.to_string()),
+    })
+}
+
+/// Every conversation of a source under `root`, with its messages
+pub(crate) fn read_source_conversations(
+    source: &str,
+    root: &Path,
+) -> Result<Vec<SourceConversation>, String> {
+    match source {
+        DESKTOP_SOURCE => DesktopSource::read_conversations(root),
+        CURSOR_SOURCE => CursorSource::read_conversations(root),
+        AIDER_SOURCE => AiderSource::read_conversations(root),
+        SHARED_SOURCE => SharedSource::read_conversations(root),
+        other => Err(format!("Unknown history source: {other}")),
+    }
+}
+
+fn read_source_sessions(source: &str, root: &Path) -> Result<Vec<ClaudeSession>, String> {
+    match source {
+        DESKTOP_SOURCE => read_sorted_sessions::<DesktopSource>(root),
+        CURSOR_SOURCE => read_sorted_sessions::<CursorSource>(root),
+        AIDER_SOURCE => read_sorted_sessions::<AiderSource>(root),
+        SHARED_SOURCE => read_sorted_sessions::<SharedSource>(root),
+        other => Err(format!("Unknown history source: {other}")),
+    }
+}
+
+fn read_source_messages(
+    source: &str,
+    file_path: &Path,
+    session_id: &str,
+) -> Result<Vec<ClaudeMessage>, String> {
+    match source {
+        DESKTOP_SOURCE => DesktopSource::read_messages(file_path, session_id),
+        CURSOR_SOURCE => CursorSource::read_messages(file_path, session_id),
+        AIDER_SOURCE => AiderSource::read_messages(file_path, session_id),
+        SHARED_SOURCE => SharedSource::read_messages(file_path, session_id),
+        other => Err(format!("Unknown history source: {other}")),
+    }
+}
+
+/// Default folder of a source's history, if it has one
+pub(crate) fn default_source_folder(source: &str) -> Result<PathBuf, String> {
+    let config_dir = dirs::config_dir()
+        .ok_or("HOME_DIRECTORY_NOT_FOUND:Could not determine config directory")?;
+    match source {
+        DESKTOP_SOURCE => Ok(config_dir.join("Claude")),
+        CURSOR_SOURCE => Ok(config_dir.join("Cursor").join("User")),
+        AIDER_SOURCE => Err(
+            "NO_DEFAULT_FOLDER:Aider keeps its history in each project; choose a folder"
+                .to_string(),
+        ),
+        SHARED_SOURCE => shared_folder(),
+        other => Err(format!("Unknown history source: {other}")),
+    }
+}
+
+/// Get the folder a history source stores its chats in by default
+#[tauri::command]
+pub async fn get_history_source_folder_path(source: String) -> Result<String, AppError> {
+    let folder = default_source_folder(&source)?;
+
+    if !folder.is_dir() {
+        return Err(AppError::not_found(format!(
+            "{source} folder not found at {}",
+            folder.display()
+        )));
+    }
+
+    Ok(display_path(&folder))
+}
+
+/// List the sessions of a history source (`claude-desktop`, `cursor`,
+/// `aider` or `shared`) found under `root_path`, most recently modified first
+#[tauri::command]
+pub async fn load_source_sessions(
+    source: String,
+    root_path: String,
+) -> Result<Vec<ClaudeSession>, AppError> {
+    let _timer = OperationTimer::start("load_source_sessions");
+
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        read_source_sessions(&source, Path::new(&root_path))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??)
+}
+
+/// Load the messages of a session returned by `load_source_sessions`
+///
+/// `file_path` and `session_id` are the `file_path` and `actual_session_id`
+/// of the session.
+#[tauri::command]
+pub async fn load_source_session_messages(
+    source: String,
+    file_path: String,
+    session_id: String,
+) -> Result<Vec<ClaudeMessage>, AppError> {
+    let _timer = OperationTimer::start("load_source_session_messages");
+
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        read_source_messages(&source, Path::new(&file_path), &session_id)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_chat_session_summarizes_messages() {
+        let messages = vec![
+            chat_message(
+                "s1",
+                "m1".to_string(),
+                None,
+                "2025-01-01T10:00:00+00:00".to_string(),
+                "user",
+                json!("Add a retry to the client"),
+            ),
+            chat_message(
+                "s1",
+                "m2".to_string(),
+                Some("m1".to_string()),
+                "2025-01-01T10:02:00+00:00".to_string(),
+                "assistant",
+                json!([{"type": "tool_use", "id": "t1", "name": "edit", "input": {}}]),
+            ),
+        ];
+
+        let session = chat_session(
+            "cursor",
+            Path::new("/tmp/state.vscdb"),
+            "s1",
+            "api".to_string(),
+            Some(" ".to_string()),
+            &messages,
+        )
+        .unwrap();
+
+        assert_eq!(session.session_id, "cursor:/tmp/state.vscdb#s1");
+        assert_eq!(session.source.as_deref(), Some("cursor"));
+        assert_eq!(session.message_count, 2);
+        assert_eq!(session.last_modified, "2025-01-01T10:02:00+00:00");
+        assert_eq!(
+            session.summary.as_deref(),
+            Some("Add a retry to the client")
+        );
+        assert!(session.has_tool_use);
+        assert!(chat_session("cursor", Path::new("x"), "s2", String::new(), None, &[]).is_none());
+    }
+
+    #[test]
+    fn test_unknown_source() {
+        assert!(read_source_sessions("copilot", Path::new(".")).is_err());
+        assert!(default_source_folder("aider").is_err());
+    }
+}
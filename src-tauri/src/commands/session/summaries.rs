@@ -0,0 +1,217 @@
+//! Summary entry indexing
+//!
+//! `summary` entries point at the last message (`leafUuid`) of the branch they
+//! describe, which usually lives in another session file of the project. This
+//! module indexes the leaves of every file to attach each summary to the
+//! session and branch it actually belongs to.
+
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::io_limit::acquire_file_permit;
+use crate::models::ProjectSummary;
+use crate::utils::long_path;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Minimal view of a log line for summary resolution
+#[derive(serde::Deserialize)]
+struct SummaryIndexEntry {
+    #[serde(rename = "type")]
+    message_type: String,
+    uuid: Option<String>,
+    #[serde(rename = "sessionId")]
+    session_id: Option<String>,
+    timestamp: Option<String>,
+    summary: Option<String>,
+    #[serde(rename = "leafUuid")]
+    leaf_uuid: Option<String>,
+}
+
+/// Where a message uuid lives
+#[derive(Debug, Clone)]
+struct LeafLocation {
+    session_id: Option<String>,
+    file_path: String,
+    timestamp: Option<String>,
+}
+
+/// Summaries and message locations of one session file
+#[derive(Debug, Default)]
+struct FileSummaryIndex {
+    summaries: Vec<(String, String)>, // (summary, leaf uuid)
+    locations: Vec<(String, LeafLocation)>,
+}
+
+fn index_session_content(file_path: &str, content: &str) -> FileSummaryIndex {
+    let mut index = FileSummaryIndex::default();
+
+    for line in content.lines() {
+        let Ok(entry) = serde_json::from_str::<SummaryIndexEntry>(line) else {
+            continue;
+        };
+        if entry.message_type == "summary" {
+            if let (Some(summary), Some(leaf_uuid)) = (entry.summary, entry.leaf_uuid) {
+                index.summaries.push((summary, leaf_uuid));
+            }
+        } else if let Some(uuid) = entry.uuid {
+            index.locations.push((
+                uuid,
+                LeafLocation {
+                    session_id: entry.session_id,
+                    file_path: file_path.to_string(),
+                    timestamp: entry.timestamp,
+                },
+            ));
+        }
+    }
+
+    index
+}
+
+fn index_session_file(path: &Path) -> FileSummaryIndex {
+    let file_path = path.to_string_lossy().to_string();
+    let _permit = acquire_file_permit();
+    fs::read_to_string(long_path(path))
+        .map(|content| index_session_content(&file_path, &content))
+        .unwrap_or_default()
+}
+
+/// Resolve every summary of the project against the leaves of all its files
+///
+/// Identical (summary, leaf) pairs written to several files are merged.
+/// Resolved summaries come first, newest leaf first.
+fn resolve_summaries(indexes: Vec<FileSummaryIndex>, file_paths: &[String]) -> Vec<ProjectSummary> {
+    let mut locations: HashMap<String, LeafLocation> = HashMap::new();
+    // BTreeMap keeps the output independent of scan order
+    let mut summaries: BTreeMap<(String, String), Vec<String>> = BTreeMap::new();
+
+    for (index, file_path) in indexes.into_iter().zip(file_paths) {
+        for (uuid, location) in index.locations {
+            locations.entry(uuid).or_insert(location);
+        }
+        for key in index.summaries {
+            let sources = summaries.entry(key).or_default();
+            if !sources.contains(file_path) {
+                sources.push(file_path.clone());
+            }
+        }
+    }
+
+    let mut resolved: Vec<ProjectSummary> = summaries
+        .into_iter()
+        .map(|((summary, leaf_uuid), mut source_file_paths)| {
+            source_file_paths.sort();
+            let leaf = locations.get(&leaf_uuid);
+            ProjectSummary {
+                summary,
+                session_id: leaf.and_then(|l| l.session_id.clone()),
+                file_path: leaf.map(|l| l.file_path.clone()),
+                leaf_timestamp: leaf.and_then(|l| l.timestamp.clone()),
+                leaf_uuid,
+                source_file_paths,
+            }
+        })
+        .collect();
+
+    resolved.sort_by(|a, b| {
+        b.file_path
+            .is_some()
+            .cmp(&a.file_path.is_some())
+            .then_with(|| b.leaf_timestamp.cmp(&a.leaf_timestamp))
+            .then_with(|| a.leaf_uuid.cmp(&b.leaf_uuid))
+    });
+    resolved
+}
+
+/// List all summaries of a project, attached to the session each one describes
+#[tauri::command]
+pub async fn get_project_summaries(project_path: String) -> Result<Vec<ProjectSummary>, AppError> {
+    let _timer = OperationTimer::start("get_project_summaries");
+
+    let project_dir = PathBuf::from(&project_path);
+    if !project_dir.is_dir() {
+        return Err(AppError::not_found(format!(
+            "Project not found: {project_path}"
+        )));
+    }
+
+    let mut file_paths: Vec<PathBuf> = WalkDir::new(&project_dir)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .map(|e| e.path().to_path_buf())
+        .collect();
+    file_paths.sort();
+
+    let indexes: Vec<FileSummaryIndex> = file_paths
+        .par_iter()
+        .map(|path| index_session_file(path))
+        .collect();
+    let file_paths: Vec<String> = file_paths
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+
+    Ok(resolve_summaries(indexes, &file_paths))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_summary_resolves_to_leaf_in_other_file() {
+        let old = index_session_content(
+            "old.jsonl",
+            r#"{"type":"user","uuid":"leaf-1","sessionId":"old","timestamp":"2025-01-01T00:00:00Z"}"#,
+        );
+        let new = index_session_content(
+            "new.jsonl",
+            concat!(
+                r#"{"type":"summary","summary":"Fix login bug","leafUuid":"leaf-1"}"#,
+                "\n",
+                r#"{"type":"summary","summary":"Lost work","leafUuid":"gone"}"#,
+                "\n",
+                r#"{"type":"user","uuid":"n1","sessionId":"new","timestamp":"2025-01-02T00:00:00Z"}"#,
+            ),
+        );
+
+        let summaries = resolve_summaries(
+            vec![new, old],
+            &["new.jsonl".to_string(), "old.jsonl".to_string()],
+        );
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].summary, "Fix login bug");
+        assert_eq!(summaries[0].session_id.as_deref(), Some("old"));
+        assert_eq!(summaries[0].file_path.as_deref(), Some("old.jsonl"));
+        assert_eq!(summaries[0].source_file_paths, vec!["new.jsonl"]);
+        assert_eq!(summaries[1].summary, "Lost work");
+        assert!(summaries[1].session_id.is_none());
+    }
+
+    #[test]
+    fn test_duplicate_summaries_are_merged() {
+        let line = r#"{"type":"summary","summary":"Refactor","leafUuid":"l1"}"#;
+        let a = index_session_content("a.jsonl", line);
+        let b = index_session_content("b.jsonl", line);
+
+        let summaries =
+            resolve_summaries(vec![a, b], &["a.jsonl".to_string(), "b.jsonl".to_string()]);
+
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].source_file_paths, vec!["a.jsonl", "b.jsonl"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_project_summaries_missing_project() {
+        let temp = TempDir::new().unwrap();
+        let missing = temp.path().join("missing").to_string_lossy().to_string();
+
+        assert!(get_project_summaries(missing).await.is_err());
+    }
+}
@@ -0,0 +1,52 @@
+//! Session summaries generated by the configured summarizer backend
+
+use crate::commands::session::read_session_messages;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::GeneratedSessionSummary;
+use crate::summarizer::{
+    session_transcript, summarize, summarizer_settings, DEFAULT_MAX_INPUT_CHARS,
+};
+use crate::utils::resolve_session_file;
+use chrono::Utc;
+
+/// Summarize a session with the model of the `summarizer` setting
+///
+/// Only the conversation text and tool names are sent; long transcripts
+/// keep their start and end. With a local backend (Ollama, llama.cpp)
+/// nothing leaves the machine.
+#[tauri::command]
+pub async fn summarize_session(
+    session_id: String,
+    project_path: String,
+) -> Result<GeneratedSessionSummary, AppError> {
+    let _timer = OperationTimer::start("summarize_session");
+
+    let settings = summarizer_settings()
+        .ok_or_else(|| AppError::invalid_input("No summarizer is configured"))?;
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+    let max_chars = settings.max_input_chars.unwrap_or(DEFAULT_MAX_INPUT_CHARS);
+
+    let (transcript, truncated) = tauri::async_runtime::spawn_blocking(move || {
+        let messages = read_session_messages(&session_path)?;
+        Ok::<_, String>(session_transcript(&messages, max_chars))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+    if transcript.is_empty() {
+        return Err(AppError::invalid_input(
+            "The session has no conversation to summarize",
+        ));
+    }
+
+    let summary = summarize(&settings, &transcript).await?;
+    Ok(GeneratedSessionSummary {
+        session_id,
+        backend: settings.backend,
+        model: settings.model,
+        summary,
+        generated_at: Utc::now().to_rfc3339(),
+        transcript_chars: transcript.chars().count(),
+        truncated,
+    })
+}
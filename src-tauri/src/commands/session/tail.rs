@@ -0,0 +1,222 @@
+//! Live streaming of raw session lines
+//!
+//! `tail_raw` returns the last lines of a session file and then follows it
+//! like `tail -f`, emitting every complete line appended afterwards through
+//! `RAW_TAIL_EVENT` until `stop_tail_raw` is called.
+
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::RawTailEvent;
+use crate::utils::{long_path, resolve_session_file};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::Duration;
+
+/// Event carrying the lines appended to a tailed session file
+pub const RAW_TAIL_EVENT: &str = "session-raw-lines";
+
+/// Default number of existing lines returned when a tail starts
+const DEFAULT_TAIL_BACKLOG: usize = 100;
+
+/// Interval between two reads of a tailed file
+const TAIL_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+type RawTailListener = Box<dyn Fn(&RawTailEvent) + Send + Sync>;
+
+/// Forwards appended lines to the frontend (unset in tests)
+static RAW_TAIL_LISTENER: OnceLock<RawTailListener> = OnceLock::new();
+
+/// Stop flags of the running tails, keyed by session file
+static RUNNING_TAILS: OnceLock<Mutex<HashMap<PathBuf, Arc<AtomicBool>>>> = OnceLock::new();
+
+fn running_tails() -> &'static Mutex<HashMap<PathBuf, Arc<AtomicBool>>> {
+    RUNNING_TAILS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Register the callback that emits appended raw lines to the frontend
+pub fn set_raw_tail_listener(listener: impl Fn(&RawTailEvent) + Send + Sync + 'static) {
+    let _ = RAW_TAIL_LISTENER.set(Box::new(listener));
+}
+
+/// Complete lines read since the previous call
+pub(crate) struct NewLines {
+    pub(crate) first_line: usize, // 1-based
+    pub(crate) lines: Vec<String>,
+    pub(crate) reset: bool,
+}
+
+/// Read position in a followed file
+#[derive(Default)]
+pub(crate) struct TailReader {
+    offset: u64,
+    lines_read: usize,
+    partial: Vec<u8>, // Bytes of a line still being written
+}
+
+impl TailReader {
+    /// Reader skipping the current content of `path`; line numbers count
+    /// from there
+    pub(crate) fn at_end(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            offset: fs::metadata(long_path(path))?.len(),
+            ..Self::default()
+        })
+    }
+
+    /// Read the complete lines appended since the previous call
+    ///
+    /// Starts over from the beginning when the file shrank (truncated or
+    /// replaced).
+    pub(crate) fn read_new_lines(&mut self, path: &Path) -> io::Result<NewLines> {
+        let mut file = fs::File::open(long_path(path))?;
+        let reset = file.metadata()?.len() < self.offset;
+        if reset {
+            *self = Self::default();
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut appended = Vec::new();
+        file.read_to_end(&mut appended)?;
+        self.offset += appended.len() as u64;
+        self.partial.extend_from_slice(&appended);
+
+        let first_line = self.lines_read + 1;
+        let mut lines = Vec::new();
+        while let Some(newline) = self.partial.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.partial.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            lines.push(line.trim_end_matches(['\n', '\r']).to_string());
+        }
+        self.lines_read += lines.len();
+
+        Ok(NewLines {
+            first_line,
+            lines,
+            reset,
+        })
+    }
+}
+
+/// Follow `path` until `stop` is set, reporting appended lines to the listener
+fn follow(session_id: &str, path: &Path, mut reader: TailReader, stop: &AtomicBool) {
+    while !stop.load(Ordering::SeqCst) {
+        std::thread::sleep(TAIL_POLL_INTERVAL);
+        let Ok(new) = reader.read_new_lines(path) else {
+            continue;
+        };
+        if new.lines.is_empty() && !new.reset {
+            continue;
+        }
+        if let Some(listener) = RAW_TAIL_LISTENER.get() {
+            listener(&RawTailEvent {
+                session_id: session_id.to_string(),
+                file_path: path.to_string_lossy().to_string(),
+                first_line: new.first_line,
+                lines: new.lines,
+                reset: new.reset,
+            });
+        }
+    }
+}
+
+/// Start following the raw lines of a session file
+///
+/// Returns the last `backlog` lines (100 by default); lines appended later
+/// are emitted as `session-raw-lines` events. Starting a tail on a file that
+/// is already followed replaces the previous tail.
+#[tauri::command]
+pub async fn tail_raw(
+    session_id: String,
+    project_path: String,
+    backlog: Option<usize>,
+) -> Result<RawTailEvent, AppError> {
+    let _timer = OperationTimer::start("tail_raw");
+
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+    let mut reader = TailReader::default();
+    let mut initial = reader
+        .read_new_lines(&session_path)
+        .map_err(|e| format!("Failed to read session file: {e}"))?;
+    let skipped = initial
+        .lines
+        .len()
+        .saturating_sub(backlog.unwrap_or(DEFAULT_TAIL_BACKLOG));
+    initial.lines.drain(..skipped);
+    initial.first_line = reader.lines_read + 1 - initial.lines.len();
+
+    let stop = Arc::new(AtomicBool::new(false));
+    if let Some(previous) = running_tails()
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(session_path.clone(), Arc::clone(&stop))
+    {
+        previous.store(true, Ordering::SeqCst);
+    }
+
+    let event = RawTailEvent {
+        session_id: session_id.clone(),
+        file_path: session_path.to_string_lossy().to_string(),
+        first_line: initial.first_line,
+        lines: initial.lines,
+        reset: false,
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        follow(&session_id, &session_path, reader, &stop);
+    });
+    Ok(event)
+}
+
+/// Stop following a session file; false if it was not tailed
+#[tauri::command]
+pub async fn stop_tail_raw(session_id: String, project_path: String) -> Result<bool, AppError> {
+    let _timer = OperationTimer::start("stop_tail_raw");
+
+    let session_path = Path::new(&project_path).join(format!("{session_id}.jsonl"));
+    let stop = running_tails()
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(&session_path);
+    if let Some(stop) = &stop {
+        stop.store(true, Ordering::SeqCst);
+    }
+    Ok(stop.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_tail_reader_returns_complete_appended_lines() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("s1.jsonl");
+        fs::write(&path, "{\"a\":1}\n\n{\"b\":").unwrap();
+
+        let mut reader = TailReader::default();
+        let first = reader.read_new_lines(&path).unwrap();
+        assert_eq!(first.first_line, 1);
+        assert_eq!(first.lines, vec!["{\"a\":1}", ""]);
+
+        // The partial line is completed by the next write
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "2}}\r\n{{\"c\":3}}\n").unwrap();
+        let second = reader.read_new_lines(&path).unwrap();
+        assert_eq!(second.first_line, 3);
+        assert_eq!(second.lines, vec!["{\"b\":2}", "{\"c\":3}"]);
+        assert!(!second.reset);
+
+        assert!(reader.read_new_lines(&path).unwrap().lines.is_empty());
+
+        fs::write(&path, "{\"d\":4}\n").unwrap();
+        let replaced = reader.read_new_lines(&path).unwrap();
+        assert!(replaced.reset);
+        assert_eq!(replaced.first_line, 1);
+        assert_eq!(replaced.lines, vec!["{\"d\":4}"]);
+    }
+}
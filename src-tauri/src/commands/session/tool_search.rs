@@ -0,0 +1,358 @@
+//! Structured search over tool calls
+//!
+//! Matches `tool_use` blocks by tool name and by conditions on individual
+//! input parameters (e.g. `Bash` calls whose `command` contains
+//! `cargo test`, or `WebFetch` calls to a domain), then attaches the
+//! outcome of each call from its `tool_result`.
+
+use super::search::{build_matcher, glob_to_regex};
+use crate::commands::retry_loops::{tool_result_text, truncate_chars};
+use crate::commands::stats::{read_raw_log_entries, resolve_scope_session_files};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ToolInputFilter, ToolInvocationMatch, ToolQuery};
+use crate::utils::{display_path, extract_project_name, file_name_string, stable_line_id};
+use rayon::prelude::*;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Default number of calls returned by `search_tool_invocations`
+const DEFAULT_TOOL_SEARCH_LIMIT: usize = 200;
+
+/// Maximum characters kept in a tool result preview
+const RESULT_PREVIEW_MAX_CHARS: usize = 200;
+
+/// A `ToolInputFilter` ready to be evaluated
+enum InputCondition {
+    Matches(Regex), // "contains" and "regex"
+    Equals(String),
+    Domain(String),
+    Exists,
+}
+
+struct CompiledQuery {
+    tool_name: Option<Regex>,
+    input: Vec<(String, InputCondition)>, // JSON pointer into the input
+}
+
+fn compile_filter(filter: &ToolInputFilter) -> Result<(String, InputCondition), String> {
+    let field = filter.field.trim();
+    if field.is_empty() {
+        return Err("Tool input filter needs a field".to_string());
+    }
+    let value = || {
+        filter
+            .value
+            .as_deref()
+            .ok_or_else(|| format!("Tool input filter on {field} needs a value"))
+    };
+    let condition = match filter.op.as_deref().unwrap_or("contains") {
+        "contains" => InputCondition::Matches(build_matcher(value()?, None)?),
+        "regex" => InputCondition::Matches(build_matcher(value()?, Some("regex"))?),
+        "equals" => InputCondition::Equals(value()?.to_string()),
+        "domain" => InputCondition::Domain(value()?.trim_start_matches('.').to_lowercase()),
+        "exists" => InputCondition::Exists,
+        other => return Err(format!("Unknown tool input operator: {other}")),
+    };
+    Ok((format!("/{}", field.replace('.', "/")), condition))
+}
+
+fn compile_query(query: &ToolQuery) -> Result<CompiledQuery, String> {
+    let tool_name = query
+        .tool_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| build_matcher(&format!("^(?:{})$", glob_to_regex(name)), Some("regex")))
+        .transpose()?;
+    if tool_name.is_none() && query.input.is_empty() {
+        return Err("Tool query needs a tool name or an input filter".to_string());
+    }
+    Ok(CompiledQuery {
+        tool_name,
+        input: query
+            .input
+            .iter()
+            .map(compile_filter)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Host of a URL, lowercased and without credentials or port
+fn url_host(url: &str) -> Option<String> {
+    let (_, rest) = url.trim().split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    };
+    (!host.is_empty()).then(|| host.to_lowercase())
+}
+
+/// Text an input value is compared as (nested values as compact JSON)
+fn value_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn input_matches(input: &serde_json::Value, pointer: &str, condition: &InputCondition) -> bool {
+    let Some(value) = input.pointer(pointer).filter(|v| !v.is_null()) else {
+        return false;
+    };
+    match condition {
+        InputCondition::Exists => true,
+        InputCondition::Matches(matcher) => matcher.is_match(&value_text(value)),
+        InputCondition::Equals(expected) => value_text(value) == *expected,
+        InputCondition::Domain(domain) => url_host(&value_text(value)).is_some_and(|host| {
+            host == *domain
+                || host
+                    .strip_suffix(domain.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        }),
+    }
+}
+
+fn call_matches(query: &CompiledQuery, name: &str, input: &serde_json::Value) -> bool {
+    query
+        .tool_name
+        .as_ref()
+        .map_or(true, |matcher| matcher.is_match(name))
+        && query
+            .input
+            .iter()
+            .all(|(pointer, condition)| input_matches(input, pointer, condition))
+}
+
+/// Matching tool calls of one session file, with their results attached
+fn session_tool_invocations(
+    session_path: &Path,
+    query: &CompiledQuery,
+) -> Vec<ToolInvocationMatch> {
+    let entries = read_raw_log_entries(session_path);
+    let file_path = display_path(session_path);
+    let project_name = session_path
+        .parent()
+        .and_then(file_name_string)
+        .map(|name| extract_project_name(&name))
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    let mut matches: Vec<ToolInvocationMatch> = Vec::new();
+    let mut by_tool_use_id: HashMap<String, usize> = HashMap::new();
+
+    for (line_num, entry) in entries.iter().enumerate() {
+        let Some(blocks) = entry.message.as_ref().and_then(|m| m.content.as_array()) else {
+            continue;
+        };
+        for block in blocks {
+            match block.get("type").and_then(|t| t.as_str()) {
+                Some("tool_use") => {
+                    let name = block
+                        .get("name")
+                        .and_then(|n| n.as_str())
+                        .unwrap_or_default();
+                    let input = block.get("input").cloned().unwrap_or_default();
+                    if !call_matches(query, name, &input) {
+                        continue;
+                    }
+                    let tool_use_id = block.get("id").and_then(|id| id.as_str());
+                    if let Some(id) = tool_use_id {
+                        by_tool_use_id.insert(id.to_string(), matches.len());
+                    }
+                    matches.push(ToolInvocationMatch {
+                        project_name: project_name.clone(),
+                        session_id: entry
+                            .session_id
+                            .clone()
+                            .unwrap_or_else(|| "unknown-session".to_string()),
+                        file_path: file_path.clone(),
+                        message_uuid: entry.uuid.clone().unwrap_or_else(|| {
+                            stable_line_id(entry.session_id.as_deref(), session_path, line_num)
+                        }),
+                        timestamp: entry.timestamp.clone().unwrap_or_default(),
+                        is_sidechain: entry.is_sidechain == Some(true),
+                        tool_use_id: tool_use_id.map(str::to_string),
+                        tool_name: name.to_string(),
+                        input,
+                        is_error: None,
+                        result_preview: None,
+                    });
+                }
+                Some("tool_result") => {
+                    let Some(&idx) = block
+                        .get("tool_use_id")
+                        .and_then(|id| id.as_str())
+                        .and_then(|id| by_tool_use_id.get(id))
+                    else {
+                        continue;
+                    };
+                    matches[idx].is_error = Some(
+                        block.get("is_error").and_then(serde_json::Value::as_bool) == Some(true),
+                    );
+                    matches[idx].result_preview = tool_result_text(block)
+                        .map(|text| truncate_chars(text.trim(), RESULT_PREVIEW_MAX_CHARS));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    matches
+}
+
+/// Find tool calls by tool name and input parameters, newest first
+///
+/// `scope` is "session", "project" or "global" with `path` pointing at the
+/// session file, project folder or Claude folder. `query.tool_name` is an
+/// exact name or glob (`mcp__github__*`); every `query.input` filter must
+/// hold. Returns the page of `limit` calls (default 200) after `offset`.
+#[tauri::command]
+pub async fn search_tool_invocations(
+    scope: String,
+    path: String,
+    query: ToolQuery,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<Vec<ToolInvocationMatch>, AppError> {
+    let _timer = OperationTimer::start("search_tool_invocations");
+
+    let query = compile_query(&query)?;
+    let session_files = resolve_scope_session_files(&scope, &path)?;
+    let mut matches: Vec<ToolInvocationMatch> = session_files
+        .par_iter()
+        .flat_map(|path| session_tool_invocations(path, &query))
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.timestamp
+            .cmp(&a.timestamp)
+            .then_with(|| a.message_uuid.cmp(&b.message_uuid))
+    });
+    Ok(matches
+        .into_iter()
+        .skip(offset.unwrap_or(0))
+        .take(limit.unwrap_or(DEFAULT_TOOL_SEARCH_LIMIT))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn filter(field: &str, op: &str, value: &str) -> ToolInputFilter {
+        ToolInputFilter {
+            field: field.to_string(),
+            op: Some(op.to_string()),
+            value: Some(value.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_url_host() {
+        assert_eq!(
+            url_host("https://user@Docs.RS:443/serde?x=1").as_deref(),
+            Some("docs.rs")
+        );
+        assert_eq!(
+            url_host("http://localhost:8080").as_deref(),
+            Some("localhost")
+        );
+        assert_eq!(url_host("not a url"), None);
+    }
+
+    #[tokio::test]
+    async fn test_search_tool_invocations_by_name_and_input() {
+        let temp = TempDir::new().unwrap();
+        let project_dir = temp.path().join("-Users-me-demo");
+        fs::create_dir_all(&project_dir).unwrap();
+        let call = |n: u32, name: &str, input: serde_json::Value| {
+            json!({
+                "uuid": format!("a{n}"), "sessionId": "s1", "type": "assistant",
+                "timestamp": format!("2025-01-01T00:00:0{n}Z"),
+                "message": {"role": "assistant", "content": [
+                    {"type": "tool_use", "id": format!("t{n}"), "name": name, "input": input}
+                ]}
+            })
+            .to_string()
+        };
+        let result = json!({
+            "uuid": "u1", "sessionId": "s1", "type": "user", "timestamp": "2025-01-01T00:00:02Z",
+            "message": {"role": "user", "content": [
+                {"type": "tool_result", "tool_use_id": "t1", "content": "test result: FAILED", "is_error": true}
+            ]}
+        })
+        .to_string();
+        let lines = [
+            call(1, "Bash", json!({"command": "cargo test --workspace"})),
+            result,
+            call(3, "Bash", json!({"command": "cargo build"})),
+            call(
+                4,
+                "WebFetch",
+                json!({"url": "https://api.github.com/repos", "prompt": "x"}),
+            ),
+            call(5, "WebFetch", json!({"url": "https://notgithub.com/"})),
+        ];
+        fs::write(project_dir.join("s1.jsonl"), lines.join("\n")).unwrap();
+        let search = |query: ToolQuery| {
+            search_tool_invocations(
+                "project".to_string(),
+                project_dir.to_string_lossy().to_string(),
+                query,
+                None,
+                None,
+            )
+        };
+
+        let tests = search(ToolQuery {
+            tool_name: Some("bash".to_string()),
+            input: vec![filter("command", "contains", "cargo test")],
+        })
+        .await
+        .unwrap();
+        assert_eq!(tests.len(), 1);
+        assert_eq!(tests[0].message_uuid, "a1");
+        assert_eq!(tests[0].is_error, Some(true));
+        assert_eq!(
+            tests[0].result_preview.as_deref(),
+            Some("test result: FAILED")
+        );
+
+        let fetches = search(ToolQuery {
+            tool_name: Some("Web*".to_string()),
+            input: vec![filter("url", "domain", "github.com")],
+        })
+        .await
+        .unwrap();
+        assert_eq!(fetches.len(), 1);
+        assert_eq!(fetches[0].tool_use_id.as_deref(), Some("t4"));
+        assert_eq!(fetches[0].is_error, None);
+
+        let all_bash = search(ToolQuery {
+            tool_name: Some("Bash".to_string()),
+            input: Vec::new(),
+        })
+        .await
+        .unwrap();
+        assert_eq!(all_bash[0].message_uuid, "a3");
+
+        assert!(search(ToolQuery {
+            tool_name: None,
+            input: Vec::new(),
+        })
+        .await
+        .is_err());
+        assert!(search(ToolQuery {
+            tool_name: None,
+            input: vec![filter("command", "fuzzy", "x")],
+        })
+        .await
+        .is_err());
+    }
+}
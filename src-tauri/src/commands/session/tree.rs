@@ -0,0 +1,289 @@
+//! Session tree functions
+//!
+//! Nests a session's messages into linear runs and the branches forking off
+//! them: edited or retried prompts and sub-agent sidechains.
+
+use super::graph::{build_label, content_blocks, visible_parents};
+use super::load::read_session_messages;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ClaudeMessage, SessionTree, SessionTreeBranch, SessionTreeMessage};
+use crate::utils::resolve_session_file;
+use std::collections::HashSet;
+
+struct TreeBuilder<'a> {
+    visible: Vec<&'a ClaudeMessage>,
+    children: Vec<Vec<usize>>, // In file order
+    latest: Vec<usize>,        // Most recent message of each subtree
+    branch_count: usize,
+    sidechain_count: usize,
+}
+
+impl TreeBuilder<'_> {
+    fn is_sidechain(&self, idx: usize) -> bool {
+        self.visible[idx].is_sidechain == Some(true)
+    }
+
+    /// A reply that starts a sub-agent thread off the conversation
+    fn starts_sidechain(&self, parent: usize, child: usize) -> bool {
+        self.is_sidechain(child) && !self.is_sidechain(parent)
+    }
+
+    /// Reply continuing the run: the one leading to the latest message,
+    /// never a sub-agent thread
+    fn active_child(&self, idx: usize) -> Option<usize> {
+        self.children[idx]
+            .iter()
+            .copied()
+            .filter(|&child| !self.starts_sidechain(idx, child))
+            .max_by_key(|&child| self.latest[child])
+    }
+
+    fn tree_message(&self, idx: usize) -> SessionTreeMessage {
+        let message = self.visible[idx];
+        SessionTreeMessage {
+            id: message.uuid.clone(),
+            message_type: message.message_type.clone(),
+            label: build_label(message),
+            timestamp: message.timestamp.clone(),
+            tool_names: content_blocks(message, "tool_use")
+                .filter_map(|b| b.get("name").and_then(|v| v.as_str()))
+                .map(String::from)
+                .collect(),
+            child_count: self.children[idx].len(),
+            is_branch_point: self.children[idx].len() > 1,
+        }
+    }
+
+    fn build_branch(
+        &mut self,
+        start: usize,
+        kind: &str,
+        fork_id: Option<String>,
+    ) -> SessionTreeBranch {
+        match kind {
+            "branch" => self.branch_count += 1,
+            "sidechain" => self.sidechain_count += 1,
+            _ => {}
+        }
+
+        let mut messages = Vec::new();
+        let mut branches = Vec::new();
+        let mut current = Some(start);
+        while let Some(idx) = current {
+            messages.push(self.tree_message(idx));
+            current = self.active_child(idx);
+            for child in self.children[idx].clone() {
+                if Some(child) == current {
+                    continue;
+                }
+                let kind = if self.starts_sidechain(idx, child) {
+                    "sidechain"
+                } else {
+                    "branch"
+                };
+                let fork_id = Some(self.visible[idx].uuid.clone());
+                branches.push(self.build_branch(child, kind, fork_id));
+            }
+        }
+
+        SessionTreeBranch {
+            kind: kind.to_string(),
+            fork_id,
+            messages,
+            branches,
+        }
+    }
+}
+
+/// Build the nested conversation tree from messages in file order
+///
+/// System messages are left out as in the graph view. Messages whose parent
+/// chain loops are treated as roots.
+pub fn build_session_tree(session_id: &str, messages: &[ClaudeMessage]) -> SessionTree {
+    let (visible, resolved) = visible_parents(messages, &HashSet::new());
+    let count = visible.len();
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); count];
+    for (idx, parent) in resolved.iter().enumerate() {
+        if let Some((parent_idx, _)) = parent {
+            children[*parent_idx].push(idx);
+        }
+    }
+
+    // Depth-first walk from the roots, then from anything left in a cycle
+    let mut roots = Vec::new();
+    let mut order = Vec::with_capacity(count);
+    let mut tree_children: Vec<Vec<usize>> = vec![Vec::new(); count];
+    let mut visited = vec![false; count];
+    let start_points = (0..count)
+        .filter(|&i| resolved[i].is_none())
+        .chain((0..count).filter(|&i| resolved[i].is_some()));
+    for start in start_points {
+        if visited[start] {
+            continue;
+        }
+        roots.push(start);
+        let mut stack: Vec<(usize, Option<usize>)> = vec![(start, None)];
+        while let Some((idx, parent)) = stack.pop() {
+            if visited[idx] {
+                continue;
+            }
+            visited[idx] = true;
+            order.push(idx);
+            if let Some(parent) = parent {
+                tree_children[parent].push(idx);
+            }
+            stack.extend(children[idx].iter().rev().map(|&child| (child, Some(idx))));
+        }
+    }
+    for list in &mut tree_children {
+        list.sort_unstable();
+    }
+
+    let mut latest: Vec<usize> = (0..count).collect();
+    for &idx in order.iter().rev() {
+        for &child in &tree_children[idx] {
+            latest[idx] = latest[idx].max(latest[child]);
+        }
+    }
+
+    let mut builder = TreeBuilder {
+        visible,
+        children: tree_children,
+        latest,
+        branch_count: 0,
+        sidechain_count: 0,
+    };
+    let roots = roots
+        .into_iter()
+        .map(|root| {
+            let kind = if builder.is_sidechain(root) {
+                "sidechain"
+            } else {
+                "main"
+            };
+            builder.build_branch(root, kind, None)
+        })
+        .collect();
+
+    SessionTree {
+        session_id: session_id.to_string(),
+        roots,
+        message_count: count,
+        branch_point_count: builder
+            .children
+            .iter()
+            .filter(|children| children.len() > 1)
+            .count(),
+        branch_count: builder.branch_count,
+        sidechain_count: builder.sidechain_count,
+    }
+}
+
+/// Conversation tree of a session: the main thread, edited or retried
+/// branches and sub-agent sidechains, with branch points marked
+#[tauri::command]
+pub async fn get_session_tree(
+    session_id: String,
+    project_path: String,
+) -> Result<SessionTree, AppError> {
+    let _timer = OperationTimer::start("get_session_tree");
+
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let messages = read_session_messages(&session_path)?;
+        Ok(build_session_tree(&session_id, &messages))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MessageBuilder;
+
+    fn ids(branch: &SessionTreeBranch) -> Vec<&str> {
+        branch.messages.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_tree_follows_latest_branch_and_nests_others() {
+        let mut side = MessageBuilder::user()
+            .with_uuid("side")
+            .with_parent_uuid("a1")
+            .build();
+        side.is_sidechain = Some(true);
+        let messages = vec![
+            MessageBuilder::user().with_uuid("u1").build(),
+            MessageBuilder::assistant()
+                .with_uuid("a1")
+                .with_parent_uuid("u1")
+                .build(),
+            side,
+            // Prompt edited: the first attempt is abandoned
+            MessageBuilder::user()
+                .with_uuid("old")
+                .with_parent_uuid("a1")
+                .build(),
+            MessageBuilder::user()
+                .with_uuid("new")
+                .with_parent_uuid("a1")
+                .build(),
+            MessageBuilder::assistant()
+                .with_uuid("a2")
+                .with_parent_uuid("new")
+                .build(),
+        ];
+
+        let tree = build_session_tree("s1", &messages);
+
+        assert_eq!(tree.roots.len(), 1);
+        let main = &tree.roots[0];
+        assert_eq!(main.kind, "main");
+        assert_eq!(ids(main), ["u1", "a1", "new", "a2"]);
+        assert!(main.messages[1].is_branch_point);
+        assert_eq!(main.messages[1].child_count, 3);
+
+        let forks: Vec<(&str, Option<&str>, Vec<&str>)> = main
+            .branches
+            .iter()
+            .map(|b| (b.kind.as_str(), b.fork_id.as_deref(), ids(b)))
+            .collect();
+        assert_eq!(
+            forks,
+            [
+                ("sidechain", Some("a1"), vec!["side"]),
+                ("branch", Some("a1"), vec!["old"]),
+            ]
+        );
+        assert_eq!(tree.message_count, 6);
+        assert_eq!(
+            (
+                tree.branch_point_count,
+                tree.branch_count,
+                tree.sidechain_count
+            ),
+            (1, 1, 1)
+        );
+    }
+
+    #[test]
+    fn test_tree_breaks_parent_cycles() {
+        let messages = vec![
+            MessageBuilder::user()
+                .with_uuid("x")
+                .with_parent_uuid("y")
+                .build(),
+            MessageBuilder::assistant()
+                .with_uuid("y")
+                .with_parent_uuid("x")
+                .build(),
+        ];
+
+        let tree = build_session_tree("s1", &messages);
+
+        assert_eq!(tree.roots.len(), 1);
+        assert_eq!(ids(&tree.roots[0]), ["x", "y"]);
+    }
+}
@@ -0,0 +1,225 @@
+//! Main-thread vs sidechain (sub-agent) usage
+//!
+//! Sub-agent messages count towards session totals like any other. This
+//! report separates them out per agent type, using the cast list of each
+//! session (its file plus the sub-agent files stored next to it).
+
+use crate::commands::export::session_project_name;
+use crate::commands::session::{
+    build_session_cast, read_session_with_subagents, subagent_session_file,
+};
+use crate::commands::stats::resolve_scope_session_files;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{AgentUsage, SessionCast, SessionSidechainUsage, SidechainUsageReport};
+use crate::utils::display_path;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Persona of the main conversation in a `SessionCast`
+const MAIN_PERSONA: &str = "main";
+
+/// Runs of each agent type in a session: its Task calls, or its sidechains
+/// when those outnumber the calls found
+fn agent_runs(cast: &SessionCast) -> HashMap<&str, usize> {
+    let mut runs: HashMap<&str, usize> = HashMap::new();
+    for sidechain in &cast.sidechains {
+        *runs.entry(sidechain.persona.as_str()).or_default() += 1;
+    }
+    for persona in cast.personas.iter().filter(|p| p.name != MAIN_PERSONA) {
+        let count = runs.entry(persona.name.as_str()).or_default();
+        *count = (*count).max(persona.invocation_count);
+    }
+    runs
+}
+
+fn build_report(scope: String, sessions: Vec<(String, SessionCast)>) -> SidechainUsageReport {
+    let mut report = SidechainUsageReport {
+        scope,
+        session_count: sessions.len(),
+        sessions_with_sidechains: 0,
+        run_count: 0,
+        main_message_count: 0,
+        sidechain_message_count: 0,
+        main_tokens: 0,
+        sidechain_tokens: 0,
+        sidechain_share: 0.0,
+        agents: Vec::new(),
+        sessions: Vec::new(),
+    };
+    let mut agents: HashMap<String, AgentUsage> = HashMap::new();
+
+    for (file_path, cast) in sessions {
+        let runs = agent_runs(&cast);
+        let mut usage = SessionSidechainUsage {
+            session_id: cast.session_id.clone(),
+            project_name: session_project_name(Path::new(&file_path)),
+            file_path,
+            run_count: runs.values().sum(),
+            main_tokens: 0,
+            sidechain_tokens: 0,
+            agents: Vec::new(),
+        };
+
+        for persona in &cast.personas {
+            if persona.name == MAIN_PERSONA {
+                usage.main_tokens = persona.total_tokens;
+                report.main_message_count += persona.message_count;
+                continue;
+            }
+            usage.sidechain_tokens += persona.total_tokens;
+            usage.agents.push(persona.name.clone());
+            report.sidechain_message_count += persona.message_count;
+
+            let agent = agents
+                .entry(persona.name.clone())
+                .or_insert_with(|| AgentUsage {
+                    agent_type: persona.name.clone(),
+                    ..AgentUsage::default()
+                });
+            agent.run_count += runs.get(persona.name.as_str()).copied().unwrap_or(0);
+            agent.session_count += 1;
+            agent.message_count += persona.message_count;
+            agent.input_tokens += persona.input_tokens;
+            agent.output_tokens += persona.output_tokens;
+            agent.cache_creation_tokens += persona.cache_creation_tokens;
+            agent.cache_read_tokens += persona.cache_read_tokens;
+            agent.total_tokens += persona.total_tokens;
+        }
+
+        report.main_tokens += usage.main_tokens;
+        report.sidechain_tokens += usage.sidechain_tokens;
+        report.run_count += usage.run_count;
+        if usage.run_count > 0 || usage.sidechain_tokens > 0 {
+            report.sessions_with_sidechains += 1;
+            report.sessions.push(usage);
+        }
+    }
+
+    let total_tokens = report.main_tokens + report.sidechain_tokens;
+    if total_tokens > 0 {
+        report.sidechain_share = report.sidechain_tokens as f64 / total_tokens as f64;
+    }
+    report.agents = agents.into_values().collect();
+    report.agents.sort_by(|a, b| {
+        b.total_tokens
+            .cmp(&a.total_tokens)
+            .then_with(|| a.agent_type.cmp(&b.agent_type))
+    });
+    report.sessions.sort_by(|a, b| {
+        b.sidechain_tokens
+            .cmp(&a.sidechain_tokens)
+            .then_with(|| a.file_path.cmp(&b.file_path))
+    });
+    report
+}
+
+/// Token usage of the main thread vs sidechains (Task sub-agents), with the
+/// agents invoked and the sessions that ran them
+///
+/// `scope` is "session", "project" or "global" with `path` pointing at the
+/// session file, project folder or Claude folder.
+#[tauri::command]
+pub async fn get_sidechain_stats(
+    scope: String,
+    path: String,
+) -> Result<SidechainUsageReport, AppError> {
+    let _timer = OperationTimer::start("get_sidechain_stats");
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let session_files = resolve_scope_session_files(&scope, &path)?;
+        let sessions: Vec<(String, SessionCast)> = session_files
+            .par_iter()
+            .filter(|path| subagent_session_file(path).is_none())
+            .filter_map(|path| {
+                let messages = read_session_with_subagents(path).ok()?;
+                let session_id = path.file_stem()?.to_string_lossy().to_string();
+                Some((
+                    display_path(path),
+                    build_session_cast(&session_id, &messages),
+                ))
+            })
+            .collect();
+        Ok(build_report(scope, sessions))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn assistant(uuid: &str, content: Value, output_tokens: u32) -> String {
+        json!({
+            "uuid": uuid,
+            "sessionId": "s1",
+            "timestamp": "2025-03-01T09:40:00Z",
+            "type": "assistant",
+            "message": {
+                "id": format!("msg_{uuid}"),
+                "role": "assistant",
+                "model": "claude-sonnet-4-20250514",
+                "content": content,
+                "usage": {"input_tokens": 0, "output_tokens": output_tokens}
+            }
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_sidechain_stats_separate_sub_agent_usage() {
+        let temp = TempDir::new().unwrap();
+        let project_dir = temp.path().join("-Users-me-demo");
+        let subagents_dir = project_dir.join("s1").join("subagents");
+        fs::create_dir_all(&subagents_dir).unwrap();
+
+        let task = json!([{
+            "type": "tool_use",
+            "id": "toolu_1",
+            "name": "Task",
+            "input": {"subagent_type": "code-reviewer", "description": "Review", "prompt": "Review the diff"}
+        }]);
+        fs::write(project_dir.join("s1.jsonl"), assistant("a1", task, 100)).unwrap();
+        let mut sidechain: Value = serde_json::from_str(&assistant(
+            "side1",
+            json!([{"type": "text", "text": "Review the diff"}]),
+            400,
+        ))
+        .unwrap();
+        sidechain["isSidechain"] = json!(true);
+        fs::write(subagents_dir.join("agent-1.jsonl"), sidechain.to_string()).unwrap();
+        fs::write(
+            project_dir.join("s2.jsonl"),
+            assistant("b1", json!([{"type": "text", "text": "hi"}]), 50),
+        )
+        .unwrap();
+
+        let report = get_sidechain_stats(
+            "project".to_string(),
+            project_dir.to_string_lossy().to_string(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.session_count, 2);
+        assert_eq!(report.sessions_with_sidechains, 1);
+        assert_eq!(report.run_count, 1);
+        assert_eq!((report.main_tokens, report.sidechain_tokens), (150, 400));
+        assert!((report.sidechain_share - 400.0 / 550.0).abs() < 1e-9);
+
+        assert_eq!(report.agents.len(), 1);
+        let agent = &report.agents[0];
+        assert_eq!(agent.agent_type, "code-reviewer");
+        assert_eq!((agent.run_count, agent.session_count), (1, 1));
+        assert_eq!(agent.output_tokens, 400);
+
+        assert_eq!(report.sessions[0].session_id, "s1");
+        assert_eq!(report.sessions[0].agents, ["code-reviewer"]);
+        assert_eq!(report.sessions[0].project_name.as_deref(), Some("demo"));
+    }
+}
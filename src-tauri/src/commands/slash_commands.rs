@@ -0,0 +1,284 @@
+//! Slash command usage
+//!
+//! Claude Code logs a slash command as a message whose text holds
+//! `<command-name>/name</command-name>` and `<command-args>...</command-args>`:
+//! a `local_command` system entry for built-in commands, a user message for
+//! custom and plugin commands.
+
+use crate::commands::prompt_quality::prompt_text;
+use crate::commands::stats::{read_raw_log_entries, resolve_scope_session_files};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{
+    RawLogEntry, SlashCommandCount, SlashCommandProjectCount, SlashCommandStats, SlashCommandUse,
+};
+use crate::utils::{extract_project_name, file_name_string};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Maximum number of recent runs returned
+const MAX_RECENT_USES: usize = 50;
+
+/// Trimmed content of the first `<tag>...</tag>` in `text`
+fn tag_content<'a>(text: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{tag}>");
+    let start = text.find(&open)? + open.len();
+    let end = start + text[start..].find(&format!("</{tag}>"))?;
+    Some(text[start..end].trim())
+}
+
+/// (name, args) of a slash command message
+fn parse_slash_command(text: &str) -> Option<(String, Option<String>)> {
+    let name = tag_content(text, "command-name").filter(|name| !name.is_empty())?;
+    let name = if name.starts_with('/') {
+        name.to_string()
+    } else {
+        format!("/{name}")
+    };
+    let args = tag_content(text, "command-args")
+        .filter(|args| !args.is_empty())
+        .map(str::to_string);
+    Some((name, args))
+}
+
+fn entry_command_text(entry: &RawLogEntry) -> Option<String> {
+    match entry.message_type.as_str() {
+        "system" if entry.subtype.as_deref() == Some("local_command") => entry
+            .content
+            .as_ref()
+            .and_then(|c| c.as_str())
+            .map(str::to_string),
+        "user" => prompt_text(&entry.message.as_ref()?.content),
+        _ => None,
+    }
+}
+
+fn collect_slash_commands(
+    entries: &[RawLogEntry],
+    session_id: &str,
+    project_name: &str,
+) -> Vec<SlashCommandUse> {
+    entries
+        .iter()
+        .filter(|entry| entry.is_sidechain != Some(true))
+        .filter_map(|entry| {
+            let (name, args) = parse_slash_command(&entry_command_text(entry)?)?;
+            Some(SlashCommandUse {
+                name,
+                args,
+                session_id: entry
+                    .session_id
+                    .clone()
+                    .unwrap_or_else(|| session_id.to_string()),
+                project_name: project_name.to_string(),
+                timestamp: entry.timestamp.clone(),
+                uuid: entry.uuid.clone(),
+            })
+        })
+        .collect()
+}
+
+fn analyze_session(session_path: &Path) -> Vec<SlashCommandUse> {
+    let session_id = session_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "unknown-session".to_string());
+    let project_name = session_path
+        .parent()
+        .and_then(file_name_string)
+        .map(|name| extract_project_name(&name))
+        .unwrap_or_else(|| "Unknown".to_string());
+    collect_slash_commands(
+        &read_raw_log_entries(session_path),
+        &session_id,
+        &project_name,
+    )
+}
+
+fn sorted_project_counts(counts: HashMap<String, usize>) -> Vec<SlashCommandProjectCount> {
+    let mut projects: Vec<SlashCommandProjectCount> = counts
+        .into_iter()
+        .map(|(project_name, uses)| SlashCommandProjectCount { project_name, uses })
+        .collect();
+    projects.sort_by(|a, b| {
+        b.uses
+            .cmp(&a.uses)
+            .then_with(|| a.project_name.cmp(&b.project_name))
+    });
+    projects
+}
+
+fn build_slash_command_stats(sessions: Vec<Vec<SlashCommandUse>>) -> SlashCommandStats {
+    let session_count = sessions.len();
+    let sessions: Vec<Vec<SlashCommandUse>> = sessions
+        .into_iter()
+        .filter(|uses| !uses.is_empty())
+        .collect();
+    let sessions_with_commands = sessions.len();
+
+    let mut commands: HashMap<String, (SlashCommandCount, HashMap<String, usize>)> = HashMap::new();
+    let mut projects: HashMap<String, usize> = HashMap::new();
+    for uses in &sessions {
+        let mut seen: HashSet<&str> = HashSet::new();
+        for use_ in uses {
+            let (count, by_project) = commands.entry(use_.name.clone()).or_insert_with(|| {
+                (
+                    SlashCommandCount {
+                        name: use_.name.clone(),
+                        uses: 0,
+                        sessions: 0,
+                        last_used: None,
+                        projects: Vec::new(),
+                    },
+                    HashMap::new(),
+                )
+            });
+            count.uses += 1;
+            if seen.insert(&use_.name) {
+                count.sessions += 1;
+            }
+            if use_.timestamp > count.last_used {
+                count.last_used.clone_from(&use_.timestamp);
+            }
+            *by_project.entry(use_.project_name.clone()).or_default() += 1;
+            *projects.entry(use_.project_name.clone()).or_default() += 1;
+        }
+    }
+
+    let mut commands: Vec<SlashCommandCount> = commands
+        .into_values()
+        .map(|(mut count, by_project)| {
+            count.projects = sorted_project_counts(by_project);
+            count
+        })
+        .collect();
+    commands.sort_by(|a, b| b.uses.cmp(&a.uses).then_with(|| a.name.cmp(&b.name)));
+
+    let mut recent: Vec<SlashCommandUse> = sessions.into_iter().flatten().collect();
+    recent.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    recent.truncate(MAX_RECENT_USES);
+
+    SlashCommandStats {
+        session_count,
+        sessions_with_commands,
+        total_uses: commands.iter().map(|c| c.uses).sum(),
+        commands,
+        projects: sorted_project_counts(projects),
+        recent,
+    }
+}
+
+/// Count slash command runs per command and project
+///
+/// `scope` is "session", "project" or "global", as for `get_token_histograms`.
+#[tauri::command]
+pub async fn get_slash_command_stats(
+    scope: String,
+    path: String,
+) -> Result<SlashCommandStats, AppError> {
+    let _timer = OperationTimer::start("get_slash_command_stats");
+    let session_files = resolve_scope_session_files(&scope, &path)?;
+
+    let sessions: Vec<Vec<SlashCommandUse>> = session_files
+        .par_iter()
+        .map(|path| analyze_session(path))
+        .collect();
+
+    Ok(build_slash_command_stats(sessions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::raw_entry;
+    use serde_json::json;
+
+    fn local_command(ts: &str, text: &str) -> RawLogEntry {
+        raw_entry(json!({
+            "type": "system",
+            "subtype": "local_command",
+            "timestamp": ts,
+            "content": text
+        }))
+    }
+
+    #[test]
+    fn test_parse_slash_command() {
+        assert_eq!(
+            parse_slash_command(
+                "<command-name>/model</command-name>\n<command-message>model</command-message>\n<command-args>opus</command-args>"
+            ),
+            Some(("/model".to_string(), Some("opus".to_string())))
+        );
+        assert_eq!(
+            parse_slash_command(
+                "<command-name>review</command-name><command-args> </command-args>"
+            ),
+            Some(("/review".to_string(), None))
+        );
+        assert_eq!(parse_slash_command("Please run <command-name>"), None);
+        assert_eq!(parse_slash_command("plain prompt"), None);
+    }
+
+    #[test]
+    fn test_slash_command_stats() {
+        let web = collect_slash_commands(
+            &[
+                local_command(
+                    "2025-01-01T00:00:00Z",
+                    "<command-name>/compact</command-name>",
+                ),
+                local_command(
+                    "2025-01-01T00:00:01Z",
+                    "<local-command-stdout>Compacted</local-command-stdout>",
+                ),
+                raw_entry(json!({
+                    "type": "user",
+                    "timestamp": "2025-01-01T00:00:02Z",
+                    "message": {"role": "user", "content": [{"type": "text", "text": "<command-name>/review</command-name><command-args>#12</command-args>"}]}
+                })),
+                raw_entry(json!({
+                    "type": "user",
+                    "isSidechain": true,
+                    "message": {"role": "user", "content": "<command-name>/review</command-name>"}
+                })),
+                local_command(
+                    "2025-01-01T00:00:03Z",
+                    "<command-name>/compact</command-name>",
+                ),
+            ],
+            "s1",
+            "web",
+        );
+        let api = collect_slash_commands(
+            &[local_command(
+                "2025-01-02T00:00:00Z",
+                "<command-name>/compact</command-name>",
+            )],
+            "s2",
+            "api",
+        );
+        assert_eq!(web.len(), 3);
+        assert_eq!(web[1].args.as_deref(), Some("#12"));
+        assert_eq!(web[1].session_id, "s1");
+
+        let stats = build_slash_command_stats(vec![web, api, Vec::new()]);
+        assert_eq!(stats.session_count, 3);
+        assert_eq!(stats.sessions_with_commands, 2);
+        assert_eq!(stats.total_uses, 4);
+
+        let compact = &stats.commands[0];
+        assert_eq!(
+            (compact.name.as_str(), compact.uses, compact.sessions),
+            ("/compact", 3, 2)
+        );
+        assert_eq!(compact.last_used.as_deref(), Some("2025-01-02T00:00:00Z"));
+        assert_eq!(compact.projects[0].project_name, "web");
+        assert_eq!(compact.projects[0].uses, 2);
+
+        assert_eq!(stats.projects[0].project_name, "web");
+        assert_eq!(stats.projects[0].uses, 3);
+        assert_eq!(stats.recent[0].project_name, "api");
+    }
+}
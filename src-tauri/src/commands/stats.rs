@@ -1,47 +1,151 @@
-use crate::commands::session::load_session_messages;
+use crate::commands::session::{
+    default_source_folder, is_system_message_type, read_source_conversations,
+    refresh_if_stale_async, response_id, SourceConversation, CLAUDE_CODE_SOURCE,
+};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::counting::{count_rules, CountRules};
+use crate::errors::AppError;
+use crate::io_limit::{acquire_file_permit, low_memory};
 #[cfg(test)]
 use crate::models::MessageContent;
 use crate::models::{
-    ActivityHeatmap, ClaudeMessage, DailyStats, GlobalStatsSummary, ModelStats, ProjectRanking,
-    ProjectStatsSummary, RawLogEntry, SessionComparison, SessionTokenStats, TokenDistribution,
-    TokenUsage, ToolUsageStats,
+    ActivityHeatmap, ClaudeMessage, DailyStats, GlobalStatsSummary, LongestSession,
+    ModelDailyStats, ModelStats, ModelThinkingStats, ModelVariantStats, ProjectRanking,
+    ProjectStatsSummary, RawLogEntry, RootStats, ScanRoot, ServiceTierStats, SessionComparison,
+    SessionTokenStats, SourceStats, TimeOfDaySessionLength, TokenDistribution, TokenHistogram,
+    TokenHistogramBucket, TokenHistograms, TokenUsage, ToolUsageStats, WorkPatternStats,
+};
+use crate::pricing::message_cost_usd;
+use crate::utils::{
+    collect_session_files, display_path, file_name_string, find_line_ranges, long_path,
+    normalize_model_name,
 };
-use crate::utils::find_line_ranges;
-use chrono::{DateTime, Datelike, Timelike, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc};
 use memmap2::Mmap;
 use rayon::prelude::*;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
 use walkdir::WalkDir;
 
 /// Parse a line using simd-json (requires mutable slice)
 /// Returns None if parsing fails
 #[inline]
-fn parse_raw_log_entry_simd(line: &mut [u8]) -> Option<RawLogEntry> {
+pub(crate) fn parse_raw_log_entry_simd(line: &mut [u8]) -> Option<RawLogEntry> {
     simd_json::serde::from_slice(line).ok()
 }
 
+/// Read every parseable entry of a session file, in file order
+#[allow(unsafe_code)] // Required for mmap performance optimization
+pub(crate) fn read_raw_log_entries(session_path: &Path) -> Vec<RawLogEntry> {
+    let _permit = acquire_file_permit();
+    let Ok(file) = fs::File::open(long_path(session_path)) else {
+        return Vec::new();
+    };
+
+    // SAFETY: We're only reading the file, and the file handle is kept open
+    // for the duration of the mmap's lifetime. Session files are append-only.
+    let Ok(mmap) = (unsafe { Mmap::map(&file) }) else {
+        return Vec::new();
+    };
+
+    parse_raw_log_entries(&mmap)
+}
+
+/// Every parseable entry of a session file's content, in file order
+pub(crate) fn parse_raw_log_entries(data: &[u8]) -> Vec<RawLogEntry> {
+    find_line_ranges(data)
+        .into_iter()
+        .filter_map(|(start, end)| {
+            let mut line_bytes = data[start..end].to_vec();
+            parse_raw_log_entry_simd(&mut line_bytes)
+        })
+        .collect()
+}
+
+/// Responses, tokens and cost per service tier
+#[derive(Default)]
+struct ServiceTierUsage(HashMap<String, (u32, u64, f64)>);
+
+impl ServiceTierUsage {
+    /// Count a response; `usage` and `cost_usd` are zero for repeated lines
+    fn add(&mut self, usage: &TokenUsage, tokens: u64, cost_usd: f64) {
+        if tokens == 0 && cost_usd <= 0.0 {
+            return;
+        }
+        let tier = usage
+            .service_tier
+            .as_deref()
+            .map_or_else(|| "unknown".to_string(), str::to_lowercase);
+        let entry = self.0.entry(tier).or_default();
+        entry.0 += 1;
+        entry.1 += tokens;
+        entry.2 += cost_usd;
+    }
+
+    fn merge(&mut self, other: Self) {
+        for (tier, (responses, tokens, cost)) in other.0 {
+            let entry = self.0.entry(tier).or_default();
+            entry.0 += responses;
+            entry.1 += tokens;
+            entry.2 += cost;
+        }
+    }
+
+    /// Breakdown with the most tokens first
+    fn into_stats(self) -> Vec<ServiceTierStats> {
+        let mut tiers: Vec<ServiceTierStats> = self
+            .0
+            .into_iter()
+            .map(
+                |(service_tier, (response_count, total_tokens, cost_usd))| ServiceTierStats {
+                    service_tier,
+                    response_count,
+                    total_tokens,
+                    cost_usd,
+                },
+            )
+            .collect();
+        tiers.sort_by(|a, b| {
+            b.total_tokens
+                .cmp(&a.total_tokens)
+                .then_with(|| a.service_tier.cmp(&b.service_tier))
+        });
+        tiers
+    }
+}
+
 /// Intermediate stats collected from a single session file (for parallel processing)
 #[derive(Default)]
 struct SessionFileStats {
     total_messages: u32,
+    raw_messages: u32,
     total_tokens: u64,
+    cost_usd: f64,
     token_distribution: TokenDistribution,
     tool_usage: HashMap<String, (u32, u32)>, // (usage_count, success_count)
     daily_stats: HashMap<String, DailyStats>,
     activity_data: HashMap<(u8, u8), (u32, u64)>, // (hour, day) -> (count, tokens)
     model_usage: HashMap<String, (u32, u64, u64, u64, u64, u64)>, // model -> (msg_count, total, input, output, cache_create, cache_read)
+    model_cost_usd: HashMap<String, f64>,
+    model_daily: ModelDailyUsage,
+    service_tiers: ServiceTierUsage,
     session_duration_minutes: u64,
     first_message: Option<DateTime<Utc>>,
     last_message: Option<DateTime<Utc>>,
     project_name: String,
+    file_path: String,
+    source: String,       // History source id, see `SourceStats`
+    root: Option<String>, // Scan root label of Claude Code sessions
 }
 
 /// Process a single session file and return aggregated stats
 #[allow(unsafe_code)] // Required for mmap performance optimization
 fn process_session_file_for_global_stats(session_path: &PathBuf) -> Option<SessionFileStats> {
-    let file = fs::File::open(session_path).ok()?;
+    let _permit = acquire_file_permit();
+    let file = fs::File::open(long_path(session_path)).ok()?;
 
     // SAFETY: We're only reading the file, and the file handle is kept open
     // for the duration of the mmap's lifetime. Session files are append-only.
@@ -49,13 +153,13 @@ fn process_session_file_for_global_stats(session_path: &PathBuf) -> Option<Sessi
 
     let project_name = session_path
         .parent()
-        .and_then(|p| p.file_name())
-        .and_then(|n| n.to_str())
-        .unwrap_or("Unknown")
-        .to_string();
+        .and_then(file_name_string)
+        .unwrap_or_else(|| "Unknown".to_string());
 
     let mut stats = SessionFileStats {
         project_name,
+        file_path: display_path(session_path),
+        source: CLAUDE_CODE_SOURCE.to_string(),
         ..Default::default()
     };
 
@@ -64,117 +168,176 @@ fn process_session_file_for_global_stats(session_path: &PathBuf) -> Option<Sessi
     // Use SIMD-accelerated line detection
     let line_ranges = find_line_ranges(&mmap);
 
+    let rules = count_rules();
+    let mut responses = ResponseUsageTracker::default();
     for (start, end) in line_ranges {
         // simd-json requires mutable slice
         let mut line_bytes = mmap[start..end].to_vec();
 
-        if let Some(log_entry) = parse_raw_log_entry_simd(&mut line_bytes) {
+        if let Some(mut log_entry) = parse_raw_log_entry_simd(&mut line_bytes) {
+            let counted = rules.counts_entry(&log_entry);
+            let cwd = log_entry.cwd.take();
             if let Ok(message) = ClaudeMessage::try_from(log_entry) {
-                stats.total_messages = stats.total_messages.saturating_add(1);
+                add_message_to_global_stats(
+                    &mut stats,
+                    &message,
+                    counted,
+                    cwd.as_deref(),
+                    &mut responses,
+                    &mut session_timestamps,
+                );
+            }
+        }
+    }
 
-                if let Ok(timestamp) = DateTime::parse_from_rfc3339(&message.timestamp) {
-                    let timestamp = timestamp.with_timezone(&Utc);
-                    session_timestamps.push(timestamp);
+    stats.session_duration_minutes = active_minutes(&mut session_timestamps);
+    Some(stats)
+}
 
-                    // Track first/last message
-                    if stats.first_message.is_none() || timestamp < stats.first_message.unwrap() {
-                        stats.first_message = Some(timestamp);
-                    }
-                    if stats.last_message.is_none() || timestamp > stats.last_message.unwrap() {
-                        stats.last_message = Some(timestamp);
-                    }
+/// Stats of another tool's conversation; its messages carry no token usage
+fn process_source_conversation(
+    source: &str,
+    conversation: &SourceConversation,
+) -> SessionFileStats {
+    let mut stats = SessionFileStats {
+        project_name: conversation.session.project_name.clone(),
+        file_path: conversation.session.file_path.clone(),
+        source: source.to_string(),
+        ..Default::default()
+    };
+    let mut timestamps = Vec::new();
+    let mut responses = ResponseUsageTracker::default();
+    for message in &conversation.messages {
+        add_message_to_global_stats(
+            &mut stats,
+            message,
+            true,
+            None,
+            &mut responses,
+            &mut timestamps,
+        );
+    }
+    stats.session_duration_minutes = active_minutes(&mut timestamps);
+    stats
+}
 
-                    let hour = timestamp.hour() as u8;
-                    let day = timestamp.weekday().num_days_from_sunday() as u8;
-                    let usage = extract_token_usage(&message);
-                    let tokens = u64::from(usage.input_tokens.unwrap_or(0))
-                        + u64::from(usage.output_tokens.unwrap_or(0))
-                        + u64::from(usage.cache_creation_input_tokens.unwrap_or(0))
-                        + u64::from(usage.cache_read_input_tokens.unwrap_or(0));
-                    let input_tokens = u64::from(usage.input_tokens.unwrap_or(0));
-                    let output_tokens = u64::from(usage.output_tokens.unwrap_or(0));
-                    let cache_creation_tokens =
-                        u64::from(usage.cache_creation_input_tokens.unwrap_or(0));
-                    let cache_read_tokens = u64::from(usage.cache_read_input_tokens.unwrap_or(0));
+/// Breakdown entry of a source without sessions; token and cost totals
+/// are only kept for sources that record usage
+fn empty_source_stats(source: &str) -> SourceStats {
+    let records_usage = source == CLAUDE_CODE_SOURCE;
+    SourceStats {
+        source: source.to_string(),
+        total_sessions: 0,
+        total_messages: 0,
+        total_tokens: records_usage.then_some(0),
+        total_cost_usd: records_usage.then_some(0.0),
+    }
+}
 
-                    stats.total_tokens += tokens;
+/// Add one message's activity, tokens and tool calls to a session's stats
+///
+/// `cwd` is the entry's working directory, matched against pricing overrides.
+fn add_message_to_global_stats(
+    stats: &mut SessionFileStats,
+    message: &ClaudeMessage,
+    counted: bool,
+    cwd: Option<&str>,
+    responses: &mut ResponseUsageTracker,
+    timestamps: &mut Vec<DateTime<Utc>>,
+) {
+    stats.raw_messages = stats.raw_messages.saturating_add(1);
+    if counted {
+        stats.total_messages = stats.total_messages.saturating_add(1);
+    }
 
-                    // Activity data
-                    let activity_entry = stats.activity_data.entry((hour, day)).or_insert((0, 0));
-                    activity_entry.0 += 1;
-                    activity_entry.1 += tokens;
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(&message.timestamp) {
+        let timestamp = timestamp.with_timezone(&Utc);
+        timestamps.push(timestamp);
 
-                    // Daily stats
-                    let date = timestamp.format("%Y-%m-%d").to_string();
-                    let daily_entry =
-                        stats
-                            .daily_stats
-                            .entry(date.clone())
-                            .or_insert_with(|| DailyStats {
-                                date,
-                                ..Default::default()
-                            });
-                    daily_entry.total_tokens += tokens;
-                    daily_entry.input_tokens += input_tokens;
-                    daily_entry.output_tokens += output_tokens;
-                    daily_entry.message_count += 1;
-
-                    // Token distribution
-                    stats.token_distribution.input += input_tokens;
-                    stats.token_distribution.output += output_tokens;
-                    stats.token_distribution.cache_creation += cache_creation_tokens;
-                    stats.token_distribution.cache_read += cache_read_tokens;
-
-                    // Model usage
-                    if let Some(model_name) = &message.model {
-                        let model_entry = stats
-                            .model_usage
-                            .entry(model_name.clone())
-                            .or_insert((0, 0, 0, 0, 0, 0));
-                        model_entry.0 += 1;
-                        model_entry.1 += tokens;
-                        model_entry.2 += input_tokens;
-                        model_entry.3 += output_tokens;
-                        model_entry.4 += cache_creation_tokens;
-                        model_entry.5 += cache_read_tokens;
-                    }
-                }
+        // Track first/last message
+        if stats.first_message.is_none() || timestamp < stats.first_message.unwrap() {
+            stats.first_message = Some(timestamp);
+        }
+        if stats.last_message.is_none() || timestamp > stats.last_message.unwrap() {
+            stats.last_message = Some(timestamp);
+        }
 
-                // Tool usage from assistant content
-                if message.message_type == "assistant" {
-                    if let Some(content) = &message.content {
-                        if let Some(content_array) = content.as_array() {
-                            for item in content_array {
-                                if let Some(item_type) = item.get("type").and_then(|v| v.as_str()) {
-                                    if item_type == "tool_use" {
-                                        if let Some(name) =
-                                            item.get("name").and_then(|v| v.as_str())
-                                        {
-                                            let tool_entry = stats
-                                                .tool_usage
-                                                .entry(name.to_string())
-                                                .or_insert((0, 0));
-                                            tool_entry.0 += 1;
-                                            tool_entry.1 += 1;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+        let hour = timestamp.hour() as u8;
+        let day = timestamp.weekday().num_days_from_sunday() as u8;
+        let (usage, cost) = responses.usage_and_cost_of(message, cwd);
+        let tokens = u64::from(usage.input_tokens.unwrap_or(0))
+            + u64::from(usage.output_tokens.unwrap_or(0))
+            + u64::from(usage.cache_creation_input_tokens.unwrap_or(0))
+            + u64::from(usage.cache_read_input_tokens.unwrap_or(0));
+        let input_tokens = u64::from(usage.input_tokens.unwrap_or(0));
+        let output_tokens = u64::from(usage.output_tokens.unwrap_or(0));
+        let cache_creation_tokens = u64::from(usage.cache_creation_input_tokens.unwrap_or(0));
+        let cache_read_tokens = u64::from(usage.cache_read_input_tokens.unwrap_or(0));
+
+        stats.total_tokens += tokens;
+        stats.cost_usd += cost;
+        stats.service_tiers.add(&usage, tokens, cost);
+
+        // Activity data
+        let activity_entry = stats.activity_data.entry((hour, day)).or_insert((0, 0));
+        activity_entry.0 += u32::from(counted);
+        activity_entry.1 += tokens;
+
+        // Daily stats
+        let date = timestamp.format("%Y-%m-%d").to_string();
+        let daily_entry = stats
+            .daily_stats
+            .entry(date.clone())
+            .or_insert_with(|| DailyStats {
+                date: date.clone(),
+                ..Default::default()
+            });
+        daily_entry.total_tokens += tokens;
+        daily_entry.input_tokens += input_tokens;
+        daily_entry.output_tokens += output_tokens;
+        daily_entry.message_count += usize::from(counted);
+
+        // Token distribution
+        stats.token_distribution.input += input_tokens;
+        stats.token_distribution.output += output_tokens;
+        stats.token_distribution.cache_creation += cache_creation_tokens;
+        stats.token_distribution.cache_read += cache_read_tokens;
+
+        // Model usage
+        if let Some(model_name) = &message.model {
+            let model_entry = stats
+                .model_usage
+                .entry(model_name.clone())
+                .or_insert((0, 0, 0, 0, 0, 0));
+            model_entry.0 += u32::from(counted);
+            model_entry.1 += tokens;
+            model_entry.2 += input_tokens;
+            model_entry.3 += output_tokens;
+            model_entry.4 += cache_creation_tokens;
+            model_entry.5 += cache_read_tokens;
+            *stats.model_cost_usd.entry(model_name.clone()).or_default() += cost;
+            add_model_daily(
+                &mut stats.model_daily,
+                date,
+                model_name,
+                counted,
+                tokens,
+                cost,
+            );
+        }
+    }
 
-                // Tool usage from explicit tool_use field
-                if let Some(tool_use) = &message.tool_use {
-                    if let Some(name) = tool_use.get("name").and_then(|v| v.as_str()) {
-                        let tool_entry = stats.tool_usage.entry(name.to_string()).or_insert((0, 0));
-                        tool_entry.0 += 1;
-                        if let Some(result) = &message.tool_use_result {
-                            let is_error = result
-                                .get("is_error")
-                                .and_then(serde_json::Value::as_bool)
-                                .unwrap_or(false);
-                            if !is_error {
+    // Tool usage from assistant content
+    if message.message_type == "assistant" {
+        if let Some(content) = &message.content {
+            if let Some(content_array) = content.as_array() {
+                for item in content_array {
+                    if let Some(item_type) = item.get("type").and_then(|v| v.as_str()) {
+                        if item_type == "tool_use" {
+                            if let Some(name) = item.get("name").and_then(|v| v.as_str()) {
+                                let tool_entry =
+                                    stats.tool_usage.entry(name.to_string()).or_insert((0, 0));
+                                tool_entry.0 += 1;
                                 tool_entry.1 += 1;
                             }
                         }
@@ -184,7 +347,26 @@ fn process_session_file_for_global_stats(session_path: &PathBuf) -> Option<Sessi
         }
     }
 
-    // Calculate session duration
+    // Tool usage from explicit tool_use field
+    if let Some(tool_use) = &message.tool_use {
+        if let Some(name) = tool_use.get("name").and_then(|v| v.as_str()) {
+            let tool_entry = stats.tool_usage.entry(name.to_string()).or_insert((0, 0));
+            tool_entry.0 += 1;
+            if let Some(result) = &message.tool_use_result {
+                let is_error = result
+                    .get("is_error")
+                    .and_then(serde_json::Value::as_bool)
+                    .unwrap_or(false);
+                if !is_error {
+                    tool_entry.1 += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Active minutes of a session, splitting it at breaks of over two hours
+fn active_minutes(session_timestamps: &mut [DateTime<Utc>]) -> u64 {
     const SESSION_BREAK_THRESHOLD_MINUTES: i64 = 120;
 
     if session_timestamps.len() >= 2 {
@@ -208,25 +390,28 @@ fn process_session_file_for_global_stats(session_path: &PathBuf) -> Option<Sessi
         let final_period = (last_timestamp - current_period_start).num_minutes();
         total_active_minutes += final_period.max(1) as u64;
 
-        stats.session_duration_minutes = total_active_minutes;
-    } else if session_timestamps.len() == 1 {
-        stats.session_duration_minutes = 1;
+        total_active_minutes
+    } else {
+        u64::from(session_timestamps.len() == 1)
     }
-
-    Some(stats)
 }
 
 /// Intermediate stats collected from a single session file (for project stats)
 #[derive(Default)]
 struct ProjectSessionFileStats {
     total_messages: u32,
+    raw_messages: u32,
     token_distribution: TokenDistribution,
+    cost_usd: f64,
+    service_tiers: ServiceTierUsage,
     tool_usage: HashMap<String, (u32, u32)>,
     daily_stats: HashMap<String, DailyStats>,
     activity_data: HashMap<(u8, u8), (u32, u64)>,
     session_duration_minutes: u32,
     session_dates: HashSet<String>,
     timestamps: Vec<DateTime<Utc>>,
+    model_daily: ModelDailyUsage,
+    thinking: ModelThinkingUsage,
 }
 
 /// Process a single session file for project stats
@@ -234,7 +419,8 @@ struct ProjectSessionFileStats {
 fn process_session_file_for_project_stats(
     session_path: &PathBuf,
 ) -> Option<ProjectSessionFileStats> {
-    let file = fs::File::open(session_path).ok()?;
+    let _permit = acquire_file_permit();
+    let file = fs::File::open(long_path(session_path)).ok()?;
 
     // SAFETY: We're only reading the file, and the file handle is kept open
     // for the duration of the mmap's lifetime. Session files are append-only.
@@ -246,13 +432,21 @@ fn process_session_file_for_project_stats(
     // Use SIMD-accelerated line detection
     let line_ranges = find_line_ranges(&mmap);
 
+    let rules = count_rules();
+    let mut responses = ResponseUsageTracker::default();
+    let mut thinking_turns: HashMap<String, ThinkingTurn> = HashMap::new();
     for (start, end) in line_ranges {
         // simd-json requires mutable slice
         let mut line_bytes = mmap[start..end].to_vec();
 
-        if let Some(log_entry) = parse_raw_log_entry_simd(&mut line_bytes) {
+        if let Some(mut log_entry) = parse_raw_log_entry_simd(&mut line_bytes) {
+            let counted = rules.counts_entry(&log_entry);
+            let cwd = log_entry.cwd.take();
             if let Ok(message) = ClaudeMessage::try_from(log_entry) {
-                stats.total_messages += 1;
+                stats.raw_messages += 1;
+                if counted {
+                    stats.total_messages += 1;
+                }
 
                 if let Ok(timestamp) = DateTime::parse_from_rfc3339(&message.timestamp) {
                     let timestamp = timestamp.with_timezone(&Utc);
@@ -260,18 +454,35 @@ fn process_session_file_for_project_stats(
 
                     let hour = timestamp.hour() as u8;
                     let day = timestamp.weekday().num_days_from_sunday() as u8;
-                    let usage = extract_token_usage(&message);
+                    let (usage, cost) = responses.usage_and_cost_of(&message, cwd.as_deref());
+                    stats.cost_usd += cost;
+                    add_thinking_turn(
+                        &mut thinking_turns,
+                        &message,
+                        u64::from(usage.output_tokens.unwrap_or(0)),
+                    );
                     let tokens = usage.input_tokens.unwrap_or(0)
                         + usage.output_tokens.unwrap_or(0)
                         + usage.cache_creation_input_tokens.unwrap_or(0)
                         + usage.cache_read_input_tokens.unwrap_or(0);
+                    stats.service_tiers.add(&usage, u64::from(tokens), cost);
 
                     let activity_entry = stats.activity_data.entry((hour, day)).or_insert((0, 0));
-                    activity_entry.0 += 1;
+                    activity_entry.0 += u32::from(counted);
                     activity_entry.1 += u64::from(tokens);
 
                     let date = timestamp.format("%Y-%m-%d").to_string();
                     stats.session_dates.insert(date.clone());
+                    if let Some(model_name) = &message.model {
+                        add_model_daily(
+                            &mut stats.model_daily,
+                            date.clone(),
+                            model_name,
+                            counted,
+                            u64::from(tokens),
+                            cost,
+                        );
+                    }
 
                     let daily_entry =
                         stats
@@ -284,7 +495,7 @@ fn process_session_file_for_project_stats(
                     daily_entry.total_tokens += u64::from(tokens);
                     daily_entry.input_tokens += u64::from(usage.input_tokens.unwrap_or(0));
                     daily_entry.output_tokens += u64::from(usage.output_tokens.unwrap_or(0));
-                    daily_entry.message_count += 1;
+                    daily_entry.message_count += usize::from(counted);
 
                     stats.token_distribution.input += u64::from(usage.input_tokens.unwrap_or(0));
                     stats.token_distribution.output += u64::from(usage.output_tokens.unwrap_or(0));
@@ -368,9 +579,52 @@ fn process_session_file_for_project_stats(
     }
 
     stats.timestamps = session_timestamps;
+    stats.thinking = model_thinking_usage(thinking_turns);
     Some(stats)
 }
 
+/// Tracks the API responses whose usage was already counted
+///
+/// Claude Code writes one line per content block of a streamed response, and
+/// rewrites a response when it is retried, each line carrying the full usage.
+#[derive(Default)]
+pub(crate) struct ResponseUsageTracker {
+    counted: HashSet<String>,
+    duplicates: usize,
+}
+
+impl ResponseUsageTracker {
+    /// Usage to count for `message`, or None if its response was already counted
+    ///
+    /// The first entry of a response is counted (see `response_id`).
+    pub(crate) fn usage_of(&mut self, message: &ClaudeMessage) -> Option<TokenUsage> {
+        if let Some(id) = response_id(message) {
+            if !self.counted.insert(id.to_string()) {
+                self.duplicates += 1;
+                return None;
+            }
+        }
+        Some(extract_token_usage(message))
+    }
+
+    /// Usage and cost to count for `message`, both zero if its response was
+    /// already counted; `cwd` is matched against the pricing overrides
+    pub(crate) fn usage_and_cost_of(
+        &mut self,
+        message: &ClaudeMessage,
+        cwd: Option<&str>,
+    ) -> (TokenUsage, f64) {
+        self.usage_of(message).map_or_else(
+            || (TokenUsage::default(), 0.0),
+            |usage| {
+                let cost =
+                    message_cost_usd(message.cost_usd, message.model.as_deref(), cwd, &usage);
+                (usage, cost)
+            },
+        )
+    }
+}
+
 fn extract_token_usage(message: &ClaudeMessage) -> TokenUsage {
     if let Some(usage) = &message.usage {
         return usage.clone();
@@ -467,14 +721,88 @@ fn extract_token_usage(message: &ClaudeMessage) -> TokenUsage {
     usage
 }
 
+/// Messages of a session file the viewer shows, with the entry counts
+#[derive(Default)]
+struct SessionPass {
+    messages: Vec<ClaudeMessage>,
+    message_count: usize,
+    raw_message_count: usize,
+    cwd: Option<String>,
+}
+
+impl SessionPass {
+    fn add_line(&mut self, line: &mut [u8], rules: &CountRules) {
+        let Some(entry) = parse_raw_log_entry_simd(line) else {
+            return;
+        };
+        if self.cwd.is_none() {
+            self.cwd.clone_from(&entry.cwd);
+        }
+        let counted = rules.counts_entry(&entry);
+        let shown = entry.is_meta != Some(true) && !is_system_message_type(&entry.message_type);
+        let Ok(message) = ClaudeMessage::try_from(entry) else {
+            return;
+        };
+        self.raw_message_count += 1;
+        self.message_count += usize::from(counted);
+        if shown {
+            self.messages.push(message);
+        }
+    }
+}
+
+/// Count the entries of a session file in the pass that selects the
+/// messages the viewer shows; the file is streamed in low-memory mode
+#[allow(unsafe_code)] // Required for mmap performance optimization
+fn read_session_pass(session_path: &Path) -> Result<SessionPass, AppError> {
+    let _permit = acquire_file_permit();
+    let file = fs::File::open(long_path(session_path))
+        .map_err(|e| AppError::io("Failed to open session file", &e))?;
+    let rules = count_rules();
+    let mut pass = SessionPass::default();
+
+    if low_memory() {
+        let mut reader = BufReader::new(file);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            let read = reader
+                .read_until(b'\n', &mut line)
+                .map_err(|e| AppError::io("Failed to read session file", &e))?;
+            if read == 0 {
+                break;
+            }
+            pass.add_line(&mut line, &rules);
+        }
+    } else {
+        // SAFETY: We're only reading the file, and the file handle is kept open
+        // for the duration of the mmap's lifetime. Session files are append-only.
+        let mmap = unsafe { Mmap::map(&file) }
+            .map_err(|e| AppError::io("Failed to read session file", &e))?;
+        for (start, end) in find_line_ranges(&mmap) {
+            pass.add_line(&mut mmap[start..end].to_vec(), &rules);
+        }
+    }
+    Ok(pass)
+}
+
 #[tauri::command]
-pub async fn get_session_token_stats(session_path: String) -> Result<SessionTokenStats, String> {
+pub async fn get_session_token_stats(session_path: String) -> Result<SessionTokenStats, AppError> {
     let start = std::time::Instant::now();
-    let messages = load_session_messages(session_path.clone()).await?;
+    refresh_if_stale_async(&session_path).await?;
+    let path = PathBuf::from(&session_path);
+    let SessionPass {
+        messages,
+        message_count,
+        raw_message_count,
+        cwd,
+    } = tauri::async_runtime::spawn_blocking(move || read_session_pass(&path))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))??;
     let load_time = start.elapsed();
 
     if messages.is_empty() {
-        return Err("No valid messages found in session".to_string());
+        return Err("No valid messages found in session".to_string().into());
     }
 
     let session_id = messages[0].session_id.clone();
@@ -490,12 +818,15 @@ pub async fn get_session_token_stats(session_path: String) -> Result<SessionToke
     let mut total_output_tokens = 0u32;
     let mut total_cache_creation_tokens = 0u32;
     let mut total_cache_read_tokens = 0u32;
+    let mut total_cost_usd = 0.0;
 
     let mut first_time: Option<String> = None;
     let mut last_time: Option<String> = None;
 
+    let mut responses = ResponseUsageTracker::default();
     for message in &messages {
-        let usage = extract_token_usage(message);
+        let (usage, cost) = responses.usage_and_cost_of(message, cwd.as_deref());
+        total_cost_usd += cost;
 
         total_input_tokens += usage.input_tokens.unwrap_or(0);
         total_output_tokens += usage.output_tokens.unwrap_or(0);
@@ -531,10 +862,13 @@ pub async fn get_session_token_stats(session_path: String) -> Result<SessionToke
         total_cache_creation_tokens,
         total_cache_read_tokens,
         total_tokens,
-        message_count: messages.len(),
+        message_count,
+        raw_message_count,
         first_message_time: first_time.unwrap_or_else(|| "unknown".to_string()),
         last_message_time: last_time.unwrap_or_else(|| "unknown".to_string()),
         summary: None,
+        duplicate_usage_entries: responses.duplicates,
+        total_cost_usd,
     })
 }
 
@@ -550,8 +884,11 @@ pub struct PaginatedTokenStats {
 
 /// Synchronous version of session token stats extraction for parallel processing
 #[allow(unsafe_code)] // Required for mmap performance optimization
-fn extract_session_token_stats_sync(session_path: &PathBuf) -> Option<SessionTokenStats> {
-    let file = fs::File::open(session_path).ok()?;
+pub(crate) fn extract_session_token_stats_sync(
+    session_path: &PathBuf,
+) -> Option<SessionTokenStats> {
+    let _permit = acquire_file_permit();
+    let file = fs::File::open(long_path(session_path)).ok()?;
 
     // SAFETY: We're only reading the file, and the file handle is kept open
     // for the duration of the mmap's lifetime. Session files are append-only.
@@ -559,17 +896,17 @@ fn extract_session_token_stats_sync(session_path: &PathBuf) -> Option<SessionTok
 
     let project_name = session_path
         .parent()
-        .and_then(|p| p.file_name())
-        .and_then(|n| n.to_str())
-        .unwrap_or("Unknown")
-        .to_string();
+        .and_then(file_name_string)
+        .unwrap_or_else(|| "Unknown".to_string());
 
     let mut session_id: Option<String> = None;
     let mut total_input_tokens = 0u32;
     let mut total_output_tokens = 0u32;
     let mut total_cache_creation_tokens = 0u32;
     let mut total_cache_read_tokens = 0u32;
+    let mut total_cost_usd = 0.0;
     let mut message_count = 0usize;
+    let mut raw_message_count = 0usize;
     let mut first_time: Option<String> = None;
     let mut last_time: Option<String> = None;
     let mut summary: Option<String> = None;
@@ -577,11 +914,13 @@ fn extract_session_token_stats_sync(session_path: &PathBuf) -> Option<SessionTok
     // Use SIMD-accelerated line detection
     let line_ranges = find_line_ranges(&mmap);
 
+    let rules = count_rules();
+    let mut responses = ResponseUsageTracker::default();
     for (start, end) in line_ranges {
         // simd-json requires mutable slice
         let mut line_bytes = mmap[start..end].to_vec();
 
-        if let Some(log_entry) = parse_raw_log_entry_simd(&mut line_bytes) {
+        if let Some(mut log_entry) = parse_raw_log_entry_simd(&mut line_bytes) {
             // Check for summary message type before converting
             if log_entry.message_type == "summary" {
                 if let Some(s) = &log_entry.summary {
@@ -589,14 +928,20 @@ fn extract_session_token_stats_sync(session_path: &PathBuf) -> Option<SessionTok
                 }
             }
 
+            let counted = rules.counts_entry(&log_entry);
+            let cwd = log_entry.cwd.take();
             if let Ok(message) = ClaudeMessage::try_from(log_entry) {
                 if session_id.is_none() {
                     session_id = Some(message.session_id.clone());
                 }
 
-                message_count += 1;
+                raw_message_count += 1;
+                if counted {
+                    message_count += 1;
+                }
 
-                let usage = extract_token_usage(&message);
+                let (usage, cost) = responses.usage_and_cost_of(&message, cwd.as_deref());
+                total_cost_usd += cost;
                 total_input_tokens += usage.input_tokens.unwrap_or(0);
                 total_output_tokens += usage.output_tokens.unwrap_or(0);
                 total_cache_creation_tokens += usage.cache_creation_input_tokens.unwrap_or(0);
@@ -614,7 +959,7 @@ fn extract_session_token_stats_sync(session_path: &PathBuf) -> Option<SessionTok
     }
 
     let session_id = session_id?;
-    if message_count == 0 {
+    if raw_message_count == 0 {
         return None;
     }
 
@@ -632,9 +977,12 @@ fn extract_session_token_stats_sync(session_path: &PathBuf) -> Option<SessionTok
         total_cache_read_tokens,
         total_tokens,
         message_count,
+        raw_message_count,
         first_message_time: first_time.unwrap_or_else(|| "unknown".to_string()),
         last_message_time: last_time.unwrap_or_else(|| "unknown".to_string()),
         summary,
+        duplicate_usage_entries: responses.duplicates,
+        total_cost_usd,
     })
 }
 
@@ -643,7 +991,8 @@ pub async fn get_project_token_stats(
     project_path: String,
     offset: Option<usize>,
     limit: Option<usize>,
-) -> Result<PaginatedTokenStats, String> {
+) -> Result<PaginatedTokenStats, AppError> {
+    let _timer = OperationTimer::start("get_project_token_stats");
     let start = std::time::Instant::now();
     let offset = offset.unwrap_or(0);
     let limit = limit.unwrap_or(20);
@@ -697,13 +1046,11 @@ pub async fn get_project_token_stats(
 #[tauri::command]
 pub async fn get_project_stats_summary(
     project_path: String,
-) -> Result<ProjectStatsSummary, String> {
+) -> Result<ProjectStatsSummary, AppError> {
+    let _timer = OperationTimer::start("get_project_stats_summary");
     let start = std::time::Instant::now();
-    let project_name = PathBuf::from(&project_path)
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("Unknown")
-        .to_string();
+    let project_name =
+        file_name_string(Path::new(&project_path)).unwrap_or_else(|| "Unknown".to_string());
 
     // Phase 1: Collect all session files
     let session_files: Vec<PathBuf> = WalkDir::new(&project_path)
@@ -731,9 +1078,18 @@ pub async fn get_project_stats_summary(
     let mut daily_stats_map: HashMap<String, DailyStats> = HashMap::new();
     let mut activity_map: HashMap<(u8, u8), (u32, u64)> = HashMap::new();
     let mut session_dates: HashSet<String> = HashSet::new();
+    let mut model_daily = ModelDailyUsage::new();
+    let mut thinking = ModelThinkingUsage::new();
+
+    let mut service_tiers = ServiceTierUsage::default();
 
     for stats in file_stats {
         summary.total_messages += stats.total_messages as usize;
+        merge_model_daily(&mut model_daily, stats.model_daily);
+        merge_model_thinking(&mut thinking, stats.thinking);
+        summary.raw_total_messages += stats.raw_messages as usize;
+        summary.total_cost_usd += stats.cost_usd;
+        service_tiers.merge(stats.service_tiers);
 
         // Aggregate token distribution
         summary.token_distribution.input += stats.token_distribution.input;
@@ -824,6 +1180,10 @@ pub async fn get_project_stats_summary(
         })
         .collect();
 
+    summary.service_tier_breakdown = service_tiers.into_stats();
+    summary.model_daily_stats = model_daily_stats(model_daily);
+    summary.thinking_stats = model_thinking_stats(thinking);
+
     summary.total_tokens = summary.token_distribution.input
         + summary.token_distribution.output
         + summary.token_distribution.cache_creation
@@ -870,7 +1230,8 @@ struct SessionComparisonStats {
 /// Process a single session file for comparison stats (lightweight)
 #[allow(unsafe_code)] // Required for mmap performance optimization
 fn process_session_file_for_comparison(session_path: &PathBuf) -> Option<SessionComparisonStats> {
-    let file = fs::File::open(session_path).ok()?;
+    let _permit = acquire_file_permit();
+    let file = fs::File::open(long_path(session_path)).ok()?;
 
     // SAFETY: We're only reading the file, and the file handle is kept open
     // for the duration of the mmap's lifetime. Session files are append-only.
@@ -885,19 +1246,24 @@ fn process_session_file_for_comparison(session_path: &PathBuf) -> Option<Session
     // Use SIMD-accelerated line detection
     let line_ranges = find_line_ranges(&mmap);
 
+    let rules = count_rules();
+    let mut responses = ResponseUsageTracker::default();
     for (start, end) in line_ranges {
         // simd-json requires mutable slice
         let mut line_bytes = mmap[start..end].to_vec();
 
         if let Some(log_entry) = parse_raw_log_entry_simd(&mut line_bytes) {
+            let counted = rules.counts_entry(&log_entry);
             if let Ok(message) = ClaudeMessage::try_from(log_entry) {
                 if session_id.is_none() {
                     session_id = Some(message.session_id.clone());
                 }
 
-                message_count += 1;
+                if counted {
+                    message_count += 1;
+                }
 
-                let usage = extract_token_usage(&message);
+                let usage = responses.usage_of(&message).unwrap_or_default();
                 total_tokens += usage.input_tokens.unwrap_or(0)
                     + usage.output_tokens.unwrap_or(0)
                     + usage.cache_creation_input_tokens.unwrap_or(0)
@@ -933,7 +1299,8 @@ fn process_session_file_for_comparison(session_path: &PathBuf) -> Option<Session
 pub async fn get_session_comparison(
     session_id: String,
     project_path: String,
-) -> Result<SessionComparison, String> {
+) -> Result<SessionComparison, AppError> {
+    let _timer = OperationTimer::start("get_session_comparison");
     let start = std::time::Instant::now();
 
     // Phase 1: Collect all session files
@@ -1083,23 +1450,311 @@ impl TryFrom<RawLogEntry> for ClaudeMessage {
             prevented_continuation: log_entry.prevented_continuation,
             compact_metadata: log_entry.compact_metadata,
             microcompact_metadata: log_entry.microcompact_metadata,
+            git_branch: log_entry.git_branch,
         })
     }
 }
 
-#[tauri::command]
-pub async fn get_global_stats_summary(claude_path: String) -> Result<GlobalStatsSummary, String> {
-    let projects_path = PathBuf::from(&claude_path).join("projects");
+/// (messages, tokens, cost) per (date, model as recorded)
+type ModelDailyUsage = HashMap<(String, String), (u32, u64, f64)>;
+
+fn add_model_daily(
+    usage: &mut ModelDailyUsage,
+    date: String,
+    model_name: &str,
+    counted: bool,
+    tokens: u64,
+    cost: f64,
+) {
+    let entry = usage.entry((date, model_name.to_string())).or_default();
+    entry.0 += u32::from(counted);
+    entry.1 += tokens;
+    entry.2 += cost;
+}
 
-    if !projects_path.exists() {
-        return Err("Projects directory not found".to_string());
+fn merge_model_daily(into: &mut ModelDailyUsage, usage: ModelDailyUsage) {
+    for (key, (messages, tokens, cost)) in usage {
+        let entry = into.entry(key).or_default();
+        entry.0 += messages;
+        entry.1 += tokens;
+        entry.2 += cost;
     }
+}
 
-    // Phase 1: Collect all session files and their project names
-    let mut session_files: Vec<PathBuf> = Vec::new();
-    let mut project_names: HashSet<String> = HashSet::new();
+/// Daily usage per model, aliases grouped like `group_model_stats`
+fn model_daily_stats(usage: ModelDailyUsage) -> Vec<ModelDailyStats> {
+    let mut grouped: HashMap<(String, String), ModelDailyStats> = HashMap::new();
+    for ((date, raw_name), (messages, tokens, cost)) in usage {
+        let model_name = normalize_model_name(&raw_name);
+        let stats = grouped
+            .entry((date.clone(), model_name.clone()))
+            .or_insert_with(|| ModelDailyStats {
+                date,
+                model_name,
+                message_count: 0,
+                token_count: 0,
+                cost_usd: 0.0,
+            });
+        stats.message_count += messages;
+        stats.token_count += tokens;
+        stats.cost_usd += cost;
+    }
+
+    let mut daily: Vec<ModelDailyStats> = grouped.into_values().collect();
+    daily.sort_by(|a, b| {
+        a.date
+            .cmp(&b.date)
+            .then_with(|| b.token_count.cmp(&a.token_count))
+            .then_with(|| a.model_name.cmp(&b.model_name))
+    });
+    daily
+}
+
+/// Characters per token used to estimate thinking tokens from thinking text
+const THINKING_CHARS_PER_TOKEN: u64 = 4;
+
+/// Thinking blocks of one API response, whose lines share a message ID
+#[derive(Default)]
+struct ThinkingTurn {
+    model: String,
+    blocks: u32,
+    chars: u64,
+    output_tokens: u64,
+}
+
+/// (turns, thinking turns, thinking blocks, thinking chars, output tokens)
+type ThinkingCounts = (u32, u32, u32, u64, u64);
+
+/// Thinking counts per model as recorded
+type ModelThinkingUsage = HashMap<String, ThinkingCounts>;
+
+/// Count the thinking blocks of an assistant line; `output_tokens` is zero
+/// for lines whose response was already counted
+fn add_thinking_turn(
+    turns: &mut HashMap<String, ThinkingTurn>,
+    message: &ClaudeMessage,
+    output_tokens: u64,
+) {
+    if message.message_type != "assistant" {
+        return;
+    }
+    let Some(model) = message.model.as_deref().filter(|m| *m != "<synthetic>") else {
+        return;
+    };
+    let key = message.message_id.as_ref().unwrap_or(&message.uuid);
+    let turn = turns.entry(key.clone()).or_default();
+    if turn.model.is_empty() {
+        turn.model = model.to_string();
+    }
+    turn.output_tokens += output_tokens;
+
+    let blocks = message
+        .content
+        .as_ref()
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten();
+    for block in blocks {
+        match block.get("type").and_then(|t| t.as_str()) {
+            Some("thinking") => {
+                turn.blocks += 1;
+                turn.chars += block
+                    .get("thinking")
+                    .and_then(|t| t.as_str())
+                    .map_or(0, |t| t.chars().count() as u64);
+            }
+            Some("redacted_thinking") => turn.blocks += 1,
+            _ => {}
+        }
+    }
+}
+
+fn model_thinking_usage(turns: HashMap<String, ThinkingTurn>) -> ModelThinkingUsage {
+    let mut usage = ModelThinkingUsage::new();
+    for turn in turns.into_values() {
+        add_thinking_counts(
+            usage.entry(turn.model).or_default(),
+            (
+                1,
+                u32::from(turn.blocks > 0),
+                turn.blocks,
+                turn.chars,
+                turn.output_tokens,
+            ),
+        );
+    }
+    usage
+}
+
+fn add_thinking_counts(into: &mut ThinkingCounts, counts: ThinkingCounts) {
+    into.0 += counts.0;
+    into.1 += counts.1;
+    into.2 += counts.2;
+    into.3 += counts.3;
+    into.4 += counts.4;
+}
+
+fn merge_model_thinking(into: &mut ModelThinkingUsage, usage: ModelThinkingUsage) {
+    for (model, counts) in usage {
+        add_thinking_counts(into.entry(model).or_default(), counts);
+    }
+}
+
+/// Thinking usage per model, aliases grouped like `group_model_stats`
+fn model_thinking_stats(usage: ModelThinkingUsage) -> Vec<ModelThinkingStats> {
+    let mut grouped = ModelThinkingUsage::new();
+    for (raw_name, counts) in usage {
+        add_thinking_counts(
+            grouped.entry(normalize_model_name(&raw_name)).or_default(),
+            counts,
+        );
+    }
+
+    let mut stats: Vec<ModelThinkingStats> = grouped
+        .into_iter()
+        .map(
+            |(
+                model_name,
+                (turn_count, thinking_turn_count, thinking_block_count, chars, output_tokens),
+            )| {
+                let estimated_thinking_tokens = chars.div_ceil(THINKING_CHARS_PER_TOKEN);
+                ModelThinkingStats {
+                    model_name,
+                    turn_count,
+                    thinking_turn_count,
+                    thinking_block_count,
+                    estimated_thinking_tokens,
+                    output_tokens,
+                    thinking_token_share: if output_tokens > 0 {
+                        (estimated_thinking_tokens as f64 / output_tokens as f64 * 100.0).min(100.0)
+                    } else {
+                        0.0
+                    },
+                    avg_thinking_tokens: if thinking_turn_count > 0 {
+                        estimated_thinking_tokens as f64 / f64::from(thinking_turn_count)
+                    } else {
+                        0.0
+                    },
+                }
+            },
+        )
+        .collect();
+    stats.sort_by(|a, b| {
+        b.thinking_turn_count
+            .cmp(&a.thinking_turn_count)
+            .then_with(|| b.turn_count.cmp(&a.turn_count))
+            .then_with(|| a.model_name.cmp(&b.model_name))
+    });
+    stats
+}
+
+/// Build the model distribution, grouping raw model names by canonical name
+///
+/// `model_cost` holds the cost in USD per raw model name.
+fn group_model_stats(
+    model_usage: HashMap<String, (u32, u64, u64, u64, u64, u64)>,
+    model_cost: &HashMap<String, f64>,
+) -> Vec<ModelStats> {
+    let mut grouped: HashMap<String, ModelStats> = HashMap::new();
+    for (raw_name, (message_count, token_count, input, output, cache_create, cache_read)) in
+        model_usage
+    {
+        let stats = grouped
+            .entry(normalize_model_name(&raw_name))
+            .or_insert_with_key(|name| ModelStats {
+                model_name: name.clone(),
+                message_count: 0,
+                token_count: 0,
+                input_tokens: 0,
+                output_tokens: 0,
+                cache_creation_tokens: 0,
+                cache_read_tokens: 0,
+                cost_usd: 0.0,
+                variants: Vec::new(),
+            });
+        stats.message_count += message_count;
+        stats.token_count += token_count;
+        stats.input_tokens += input;
+        stats.output_tokens += output;
+        stats.cache_creation_tokens += cache_create;
+        stats.cache_read_tokens += cache_read;
+        stats.cost_usd += model_cost.get(&raw_name).copied().unwrap_or_default();
+        stats.variants.push(ModelVariantStats {
+            model_name: raw_name,
+            message_count,
+            token_count,
+        });
+    }
+
+    let mut distribution: Vec<ModelStats> = grouped.into_values().collect();
+    for stats in &mut distribution {
+        stats.variants.sort_by(|a, b| {
+            b.token_count
+                .cmp(&a.token_count)
+                .then_with(|| a.model_name.cmp(&b.model_name))
+        });
+    }
+    distribution.sort_by(|a, b| b.token_count.cmp(&a.token_count));
+    distribution
+}
+
+/// Label of the `claude_path` folder in the per-root breakdown
+const LOCAL_ROOT_LABEL: &str = "local";
+
+static SCAN_ROOTS: RwLock<Vec<ScanRoot>> = RwLock::new(Vec::new());
+
+/// Check the `scanRoots` setting
+pub fn validate_scan_roots(roots: &[ScanRoot]) -> Result<(), String> {
+    let mut labels = HashSet::new();
+    for root in roots {
+        let label = root.label.trim();
+        if label.is_empty() || root.path.trim().is_empty() {
+            return Err("Scan roots need a label and a path".to_string());
+        }
+        if label.eq_ignore_ascii_case(LOCAL_ROOT_LABEL) {
+            return Err(format!(
+                "The scan root label \"{LOCAL_ROOT_LABEL}\" is reserved"
+            ));
+        }
+        if !labels.insert(label) {
+            return Err(format!("Duplicate scan root label: {label}"));
+        }
+    }
+    Ok(())
+}
+
+/// Apply the `scanRoots` setting
+pub fn set_scan_roots(roots: Vec<ScanRoot>) {
+    if let Ok(mut current) = SCAN_ROOTS.write() {
+        *current = roots;
+    }
+}
+
+fn scan_roots() -> Vec<ScanRoot> {
+    SCAN_ROOTS
+        .read()
+        .map(|roots| roots.clone())
+        .unwrap_or_default()
+}
+
+/// `projects` folder of a scan root, which is either a Claude folder or a
+/// copied `projects` folder
+fn root_projects_path(path: &Path) -> PathBuf {
+    let nested = path.join("projects");
+    if nested.is_dir() {
+        nested
+    } else {
+        path.to_path_buf()
+    }
+}
 
-    for project_entry in fs::read_dir(&projects_path).map_err(|e| e.to_string())? {
+/// Claude Code session files under `projects_path`, adding their project names
+fn collect_global_session_files(
+    projects_path: &Path,
+    project_names: &mut HashSet<String>,
+) -> Result<Vec<PathBuf>, String> {
+    let mut session_files: Vec<PathBuf> = Vec::new();
+    for project_entry in fs::read_dir(projects_path).map_err(|e| e.to_string())? {
         let project_entry = project_entry.map_err(|e| e.to_string())?;
         let project_path = project_entry.path();
 
@@ -1107,11 +1762,7 @@ pub async fn get_global_stats_summary(claude_path: String) -> Result<GlobalStats
             continue;
         }
 
-        let project_name = project_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("Unknown")
-            .to_string();
+        let project_name = file_name_string(&project_path).unwrap_or_else(|| "Unknown".to_string());
         project_names.insert(project_name);
 
         for entry in WalkDir::new(&project_path)
@@ -1122,13 +1773,164 @@ pub async fn get_global_stats_summary(claude_path: String) -> Result<GlobalStats
             session_files.push(entry.path().to_path_buf());
         }
     }
+    Ok(session_files)
+}
 
-    // Phase 2: Process all session files in parallel
-    let file_stats: Vec<SessionFileStats> = session_files
+/// Session files of one scanned folder
+struct RootFiles {
+    label: String,
+    path: String,
+    project_count: usize,
+    files: Vec<PathBuf>,
+}
+
+/// Session files of `claude_path` and of the registered scan roots, adding
+/// their project names (prefixed by the root label when roots are registered)
+fn collect_root_session_files(
+    claude_path: &str,
+    roots: &[ScanRoot],
+    project_names: &mut HashSet<String>,
+) -> Result<Vec<RootFiles>, String> {
+    let local_projects = PathBuf::from(claude_path).join("projects");
+    if roots.is_empty() {
+        if !local_projects.exists() {
+            return Err("Projects directory not found".to_string());
+        }
+        let files = collect_global_session_files(&local_projects, project_names)?;
+        return Ok(vec![RootFiles {
+            label: LOCAL_ROOT_LABEL.to_string(),
+            path: claude_path.to_string(),
+            project_count: project_names.len(),
+            files,
+        }]);
+    }
+
+    let local = ScanRoot {
+        label: LOCAL_ROOT_LABEL.to_string(),
+        path: claude_path.to_string(),
+    };
+    let mut scanned: HashSet<PathBuf> = HashSet::new();
+    let mut root_files = Vec::new();
+    for root in std::iter::once(&local).chain(roots) {
+        let projects_path = if root.label == LOCAL_ROOT_LABEL {
+            local_projects.clone()
+        } else {
+            root_projects_path(Path::new(&root.path))
+        };
+        // A root registered twice, or pointing at the local folder, counts once
+        let key = fs::canonicalize(&projects_path).unwrap_or_else(|_| projects_path.clone());
+        let mut names = HashSet::new();
+        let files = if projects_path.is_dir() && scanned.insert(key) {
+            collect_global_session_files(&projects_path, &mut names)?
+        } else {
+            Vec::new()
+        };
+        project_names.extend(names.iter().map(|name| format!("{}/{name}", root.label)));
+        root_files.push(RootFiles {
+            label: root.label.clone(),
+            path: root.path.clone(),
+            project_count: names.len(),
+            files,
+        });
+    }
+    Ok(root_files)
+}
+
+fn empty_root_stats(root: &RootFiles) -> RootStats {
+    RootStats {
+        label: root.label.clone(),
+        path: root.path.clone(),
+        total_projects: root.project_count as u32,
+        total_sessions: 0,
+        total_messages: 0,
+        total_tokens: 0,
+        total_cost_usd: 0.0,
+        first_message: None,
+        last_message: None,
+    }
+}
+
+/// (source, scan root, project name) of a project ranking
+type ProjectKey = (String, Option<String>, String);
+
+/// Totals of one scan root while aggregating
+#[derive(Default)]
+struct RootTotals {
+    sessions: u32,
+    messages: u32,
+    tokens: u64,
+    cost_usd: f64,
+    first: Option<DateTime<Utc>>,
+    last: Option<DateTime<Utc>>,
+}
+
+/// Global stats across history sources
+///
+/// `sources` selects the history sources to include (default: only
+/// `claude-code`); `source_paths` maps a source to its root folder where it
+/// differs from the default one. Token and cost totals only cover sources
+/// that record usage, see `SourceStats`. The other stat commands take the
+/// path of a session or project, which belongs to a single source.
+///
+/// Claude Code sessions of the `scanRoots` setting are included as well,
+/// with a per-root breakdown and root-labelled project rankings.
+#[tauri::command]
+pub async fn get_global_stats_summary(
+    claude_path: String,
+    sources: Option<Vec<String>>,
+    source_paths: Option<BTreeMap<String, String>>,
+) -> Result<GlobalStatsSummary, AppError> {
+    let _timer = OperationTimer::start("get_global_stats_summary");
+    global_stats_summary(&claude_path, sources, source_paths, &scan_roots())
+}
+
+fn global_stats_summary(
+    claude_path: &str,
+    sources: Option<Vec<String>>,
+    source_paths: Option<BTreeMap<String, String>>,
+    roots: &[ScanRoot],
+) -> Result<GlobalStatsSummary, AppError> {
+    let mut sources = sources.unwrap_or_else(|| vec![CLAUDE_CODE_SOURCE.to_string()]);
+    let mut seen = HashSet::new();
+    sources.retain(|source| seen.insert(source.clone()));
+    let source_paths = source_paths.unwrap_or_default();
+
+    // Phase 1: Collect all session files and their project names
+    let mut project_names: HashSet<String> = HashSet::new();
+    let root_files = if sources.iter().any(|s| s == CLAUDE_CODE_SOURCE) {
+        collect_root_session_files(claude_path, roots, &mut project_names)?
+    } else {
+        Vec::new()
+    };
+
+    // Phase 2: Process all session files in parallel, then the other sources
+    let session_files: Vec<(&str, &PathBuf)> = root_files
+        .iter()
+        .flat_map(|root| root.files.iter().map(|file| (root.label.as_str(), file)))
+        .collect();
+    let mut file_stats: Vec<SessionFileStats> = session_files
         .par_iter()
-        .filter_map(process_session_file_for_global_stats)
+        .filter_map(|(label, path)| {
+            let stats = process_session_file_for_global_stats(path)?;
+            Some(SessionFileStats {
+                root: Some((*label).to_string()),
+                ..stats
+            })
+        })
         .collect();
 
+    for source in sources.iter().filter(|s| *s != CLAUDE_CODE_SOURCE) {
+        let root = match source_paths.get(source) {
+            Some(path) => PathBuf::from(path),
+            None => default_source_folder(source)?,
+        };
+        let conversations = read_source_conversations(source, &root)?;
+        for conversation in &conversations {
+            project_names.insert(format!("{source}:{}", conversation.session.project_name));
+            file_stats.push(process_source_conversation(source, conversation));
+        }
+    }
+
     // Phase 3: Aggregate results
     let mut summary = GlobalStatsSummary::default();
     summary.total_projects = project_names.len() as u32;
@@ -1138,14 +1940,31 @@ pub async fn get_global_stats_summary(claude_path: String) -> Result<GlobalStats
     let mut daily_stats_map: HashMap<String, DailyStats> = HashMap::new();
     let mut activity_map: HashMap<(u8, u8), (u32, u64)> = HashMap::new();
     let mut model_usage_map: HashMap<String, (u32, u64, u64, u64, u64, u64)> = HashMap::new();
-    let mut project_stats_map: HashMap<String, (u32, u32, u64)> = HashMap::new();
+    let mut model_cost_map: HashMap<String, f64> = HashMap::new();
+    let mut project_stats_map: HashMap<ProjectKey, (u32, u32, u64, f64)> = HashMap::new();
+    let mut source_map: HashMap<String, SourceStats> = HashMap::new();
+    let mut root_map: HashMap<String, RootTotals> = HashMap::new();
     let mut global_first_message: Option<DateTime<Utc>> = None;
     let mut global_last_message: Option<DateTime<Utc>> = None;
+    let mut session_lengths: Vec<SessionLength> = Vec::new();
+    let mut model_daily = ModelDailyUsage::new();
 
+    let mut service_tiers = ServiceTierUsage::default();
     for stats in file_stats {
         summary.total_messages += stats.total_messages;
+        summary.raw_total_messages += stats.raw_messages;
         summary.total_tokens += stats.total_tokens;
+        summary.total_cost_usd += stats.cost_usd;
+        service_tiers.merge(stats.service_tiers);
         summary.total_session_duration_minutes += stats.session_duration_minutes;
+        if let Some(start) = stats.first_message {
+            session_lengths.push(SessionLength {
+                start,
+                minutes: stats.session_duration_minutes,
+                project_name: stats.project_name.clone(),
+                file_path: stats.file_path.clone(),
+            });
+        }
 
         // Aggregate token distribution
         summary.token_distribution.input += stats.token_distribution.input;
@@ -1193,14 +2012,46 @@ pub async fn get_global_stats_summary(claude_path: String) -> Result<GlobalStats
             entry.4 += cache_create;
             entry.5 += cache_read;
         }
+        for (model, cost) in stats.model_cost_usd {
+            *model_cost_map.entry(model).or_default() += cost;
+        }
+        merge_model_daily(&mut model_daily, stats.model_daily);
+
+        // Aggregate source stats
+        let source_entry = source_map
+            .entry(stats.source.clone())
+            .or_insert_with_key(|source| empty_source_stats(source));
+        source_entry.total_sessions += 1;
+        source_entry.total_messages += stats.total_messages;
+        if let Some(tokens) = &mut source_entry.total_tokens {
+            *tokens += stats.total_tokens;
+        }
+        if let Some(cost) = &mut source_entry.total_cost_usd {
+            *cost += stats.cost_usd;
+        }
+
+        // Aggregate root stats
+        if let Some(root) = &stats.root {
+            let root_entry = root_map.entry(root.clone()).or_default();
+            root_entry.sessions += 1;
+            root_entry.messages += stats.total_messages;
+            root_entry.tokens += stats.total_tokens;
+            root_entry.cost_usd += stats.cost_usd;
+            if let Some(first) = stats.first_message {
+                root_entry.first = Some(root_entry.first.map_or(first, |f| f.min(first)));
+            }
+            root_entry.last = root_entry.last.max(stats.last_message);
+        }
 
         // Aggregate project stats
+        let root = stats.root.filter(|_| !roots.is_empty());
         let project_entry = project_stats_map
-            .entry(stats.project_name)
-            .or_insert((0, 0, 0));
+            .entry((stats.source, root, stats.project_name))
+            .or_insert((0, 0, 0, 0.0));
         project_entry.0 += 1; // sessions
         project_entry.1 += stats.total_messages; // messages
         project_entry.2 += stats.total_tokens; // tokens
+        project_entry.3 += stats.cost_usd; // cost
 
         // Track global first/last message
         if let Some(first) = stats.first_message {
@@ -1233,48 +2084,56 @@ pub async fn get_global_stats_summary(claude_path: String) -> Result<GlobalStats
         .most_used_tools
         .sort_by(|a, b| b.usage_count.cmp(&a.usage_count));
 
-    summary.model_distribution = model_usage_map
-        .into_iter()
-        .map(
-            |(
-                model_name,
-                (
-                    message_count,
-                    token_count,
-                    input_tokens,
-                    output_tokens,
-                    cache_creation_tokens,
-                    cache_read_tokens,
-                ),
-            )| ModelStats {
-                model_name,
-                message_count,
-                token_count,
-                input_tokens,
-                output_tokens,
-                cache_creation_tokens,
-                cache_read_tokens,
-            },
-        )
-        .collect();
-    summary
-        .model_distribution
-        .sort_by(|a, b| b.token_count.cmp(&a.token_count));
+    summary.model_distribution = group_model_stats(model_usage_map, &model_cost_map);
+    summary.model_daily_stats = model_daily_stats(model_daily);
+    summary.service_tier_breakdown = service_tiers.into_stats();
 
     summary.top_projects = project_stats_map
         .into_iter()
         .map(
-            |(project_name, (sessions, messages, tokens))| ProjectRanking {
-                project_name,
-                sessions,
-                messages,
-                tokens,
+            |((source, root, project_name), (sessions, messages, tokens, cost_usd))| {
+                ProjectRanking {
+                    project_name,
+                    source: (source != CLAUDE_CODE_SOURCE).then_some(source),
+                    root,
+                    sessions,
+                    messages,
+                    tokens,
+                    cost_usd,
+                }
             },
         )
         .collect();
     summary.top_projects.sort_by(|a, b| b.tokens.cmp(&a.tokens));
     summary.top_projects.truncate(10);
 
+    summary.source_breakdown = sources
+        .iter()
+        .map(|source| {
+            source_map
+                .remove(source)
+                .unwrap_or_else(|| empty_source_stats(source))
+        })
+        .collect();
+
+    if !roots.is_empty() {
+        summary.root_breakdown = root_files
+            .iter()
+            .map(|root| {
+                let mut stats = empty_root_stats(root);
+                if let Some(totals) = root_map.remove(&root.label) {
+                    stats.total_sessions = totals.sessions;
+                    stats.total_messages = totals.messages;
+                    stats.total_tokens = totals.tokens;
+                    stats.total_cost_usd = totals.cost_usd;
+                    stats.first_message = totals.first.map(|first| first.to_rfc3339());
+                    stats.last_message = totals.last.map(|last| last.to_rfc3339());
+                }
+                stats
+            })
+            .collect();
+    }
+
     summary.daily_stats = daily_stats_map.into_values().collect();
     summary.daily_stats.sort_by(|a, b| a.date.cmp(&b.date));
 
@@ -1293,10 +2152,287 @@ pub async fn get_global_stats_summary(claude_path: String) -> Result<GlobalStats
         summary.date_range.last_message = Some(last.to_rfc3339());
         summary.date_range.days_span = (last - first).num_days() as u32;
     }
+    summary.work_patterns = work_patterns(&summary, &session_lengths, Utc::now().date_naive());
 
     Ok(summary)
 }
 
+/// Start and active minutes of a session, for the work pattern stats
+struct SessionLength {
+    start: DateTime<Utc>,
+    minutes: u64,
+    project_name: String,
+    file_path: String,
+}
+
+/// Parts of the day sessions are grouped by, with their first hour (UTC)
+const TIME_OF_DAY_PERIODS: [(&str, u32); 4] = [
+    ("night", 0),
+    ("morning", 6),
+    ("afternoon", 12),
+    ("evening", 18),
+];
+
+/// Streaks, busiest weekday and session lengths of a global summary
+///
+/// Active days come from the daily stats, the busiest weekday from the
+/// heatmap; `today` (UTC) decides whether the last streak is still running.
+fn work_patterns(
+    summary: &GlobalStatsSummary,
+    sessions: &[SessionLength],
+    today: NaiveDate,
+) -> WorkPatternStats {
+    let mut patterns = WorkPatternStats::default();
+
+    let mut days: Vec<NaiveDate> = summary
+        .daily_stats
+        .iter()
+        .filter_map(|daily| NaiveDate::parse_from_str(&daily.date, "%Y-%m-%d").ok())
+        .collect();
+    days.sort_unstable();
+    days.dedup();
+    patterns.active_days = days.len() as u32;
+
+    let streaks: Vec<&[NaiveDate]> = days.chunk_by(|a, b| (*b - *a).num_days() == 1).collect();
+    if let Some(longest) = streaks.iter().max_by_key(|streak| streak.len()) {
+        patterns.longest_streak_days = longest.len() as u32;
+        patterns.longest_streak_start = Some(longest[0].format("%Y-%m-%d").to_string());
+        patterns.longest_streak_end =
+            Some(longest[longest.len() - 1].format("%Y-%m-%d").to_string());
+    }
+    if let Some(last) = streaks.last() {
+        if (today - last[last.len() - 1]).num_days() <= 1 {
+            patterns.current_streak_days = last.len() as u32;
+        }
+    }
+
+    let mut weekdays = [0u32; 7];
+    for cell in &summary.activity_heatmap {
+        weekdays[usize::from(cell.day % 7)] += cell.activity_count;
+    }
+    patterns.busiest_weekday = (0..7u8)
+        .rev()
+        .max_by_key(|&day| weekdays[usize::from(day)])
+        .filter(|&day| weekdays[usize::from(day)] > 0);
+
+    // (sessions, minutes) per part of the day
+    let mut periods = [(0u32, 0u64); TIME_OF_DAY_PERIODS.len()];
+    for session in sessions {
+        let hour = session.start.hour();
+        let period = TIME_OF_DAY_PERIODS
+            .iter()
+            .rposition(|&(_, first_hour)| hour >= first_hour)
+            .unwrap_or(0);
+        periods[period].0 += 1;
+        periods[period].1 += session.minutes;
+    }
+    patterns.session_length_by_time_of_day = TIME_OF_DAY_PERIODS
+        .iter()
+        .zip(periods)
+        .map(
+            |(&(period, _), (session_count, minutes))| TimeOfDaySessionLength {
+                period: period.to_string(),
+                session_count,
+                avg_duration_minutes: if session_count > 0 {
+                    minutes as f64 / f64::from(session_count)
+                } else {
+                    0.0
+                },
+            },
+        )
+        .collect();
+
+    patterns.longest_session =
+        sessions
+            .iter()
+            .max_by_key(|session| session.minutes)
+            .map(|session| LongestSession {
+                project_name: session.project_name.clone(),
+                file_path: session.file_path.clone(),
+                started_at: session.start.to_rfc3339(),
+                duration_minutes: session.minutes,
+            });
+
+    patterns
+}
+
+/// Inclusive upper bounds of the token histogram buckets (the last bucket is open-ended)
+const TOKEN_HISTOGRAM_BOUNDS: [u64; 8] = [100, 500, 1_000, 5_000, 10_000, 50_000, 100_000, 200_000];
+
+/// Value at the given percentile of an ascending-sorted slice (nearest-rank)
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+/// Bucket per-message token counts into a histogram
+fn build_token_histogram(mut sizes: Vec<u64>) -> TokenHistogram {
+    sizes.sort_unstable();
+    let total_tokens: u64 = sizes.iter().sum();
+    let share = |tokens: u64| {
+        if total_tokens > 0 {
+            tokens as f64 / total_tokens as f64
+        } else {
+            0.0
+        }
+    };
+
+    let mut buckets = Vec::with_capacity(TOKEN_HISTOGRAM_BOUNDS.len() + 1);
+    let mut min_tokens = 0u64;
+    for max_tokens in TOKEN_HISTOGRAM_BOUNDS
+        .iter()
+        .copied()
+        .map(Some)
+        .chain(std::iter::once(None))
+    {
+        let in_bucket = sizes
+            .iter()
+            .filter(|&&size| size >= min_tokens && !max_tokens.is_some_and(|max| size > max));
+        let (message_count, bucket_tokens) =
+            in_bucket.fold((0u64, 0u64), |(count, sum), &size| (count + 1, sum + size));
+
+        buckets.push(TokenHistogramBucket {
+            min_tokens,
+            max_tokens,
+            message_count,
+            total_tokens: bucket_tokens,
+            token_share: share(bucket_tokens),
+        });
+        min_tokens = max_tokens.map_or(min_tokens, |max| max + 1);
+    }
+
+    let top_decile_count = sizes.len().div_ceil(10);
+    let top_decile_tokens: u64 = sizes.iter().rev().take(top_decile_count).sum();
+
+    TokenHistogram {
+        buckets,
+        message_count: sizes.len() as u64,
+        total_tokens,
+        median_tokens: percentile(&sizes, 50),
+        p90_tokens: percentile(&sizes, 90),
+        max_tokens: sizes.last().copied().unwrap_or(0),
+        top_decile_token_share: share(top_decile_tokens),
+    }
+}
+
+/// Collect (input, output) token sizes of every message with usage data in a session file
+///
+/// Input size includes cache creation and cache read tokens, since that is the
+/// context actually loaded for the request.
+#[allow(unsafe_code)] // Required for mmap performance optimization
+fn collect_message_token_sizes(session_path: &PathBuf) -> Vec<(u64, u64)> {
+    let _permit = acquire_file_permit();
+    let Ok(file) = fs::File::open(long_path(session_path)) else {
+        return Vec::new();
+    };
+
+    // SAFETY: We're only reading the file, and the file handle is kept open
+    // for the duration of the mmap's lifetime. Session files are append-only.
+    let Ok(mmap) = (unsafe { Mmap::map(&file) }) else {
+        return Vec::new();
+    };
+
+    let mut sizes = Vec::new();
+    let mut responses = ResponseUsageTracker::default();
+    for (start, end) in find_line_ranges(&mmap) {
+        let mut line_bytes = mmap[start..end].to_vec();
+
+        let Some(log_entry) = parse_raw_log_entry_simd(&mut line_bytes) else {
+            continue;
+        };
+        let Ok(message) = ClaudeMessage::try_from(log_entry) else {
+            continue;
+        };
+
+        let Some(usage) = responses.usage_of(&message) else {
+            continue;
+        };
+        let input = u64::from(usage.input_tokens.unwrap_or(0))
+            + u64::from(usage.cache_creation_input_tokens.unwrap_or(0))
+            + u64::from(usage.cache_read_input_tokens.unwrap_or(0));
+        let output = u64::from(usage.output_tokens.unwrap_or(0));
+        if input > 0 || output > 0 {
+            sizes.push((input, output));
+        }
+    }
+    sizes
+}
+
+/// Resolve the session files covered by a stats scope
+///
+/// `scope` selects what `path` points to: "session" (a session file),
+/// "project" (a project folder) or "global" (the Claude folder).
+pub(crate) fn resolve_scope_session_files(scope: &str, path: &str) -> Result<Vec<PathBuf>, String> {
+    match scope {
+        "session" => {
+            let session_path = PathBuf::from(path);
+            if !session_path.is_file() {
+                return Err("Session file not found".to_string());
+            }
+            Ok(vec![session_path])
+        }
+        "project" => Ok(WalkDir::new(path)
+            .into_iter()
+            .filter_map(std::result::Result::ok)
+            .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+            .map(|e| e.path().to_path_buf())
+            .collect()),
+        "global" => {
+            let projects_path = PathBuf::from(path).join("projects");
+            if !projects_path.exists() {
+                return Err("Projects directory not found".to_string());
+            }
+            Ok(collect_session_files(&projects_path)?
+                .into_iter()
+                .map(|(_, session_path)| session_path)
+                .collect())
+        }
+        _ => Err(format!("Invalid scope: {scope}")),
+    }
+}
+
+/// Distribution of per-message input/output token sizes
+///
+/// `scope` selects what `path` points to: "session" (a session file),
+/// "project" (a project folder) or "global" (the Claude folder).
+#[tauri::command]
+pub async fn get_token_histograms(
+    scope: String,
+    path: String,
+) -> Result<TokenHistograms, AppError> {
+    let _timer = OperationTimer::start("get_token_histograms");
+    let start = std::time::Instant::now();
+
+    let session_files = resolve_scope_session_files(&scope, &path)?;
+
+    let (input_sizes, output_sizes): (Vec<u64>, Vec<u64>) = session_files
+        .par_iter()
+        .flat_map_iter(collect_message_token_sizes)
+        .unzip();
+
+    let histograms = TokenHistograms {
+        scope,
+        session_count: session_files.len(),
+        input: build_token_histogram(input_sizes.into_iter().filter(|&s| s > 0).collect()),
+        output: build_token_histogram(output_sizes.into_iter().filter(|&s| s > 0).collect()),
+    };
+
+    eprintln!(
+        "📊 get_token_histograms: {} sessions, {} messages, total={}ms",
+        histograms.session_count,
+        histograms
+            .input
+            .message_count
+            .max(histograms.output.message_count),
+        start.elapsed().as_millis()
+    );
+
+    Ok(histograms)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1341,8 +2477,12 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            hook_errors: None,
+            error: None,
+            git_branch: None,
             content: None,
             is_meta: None,
+            is_api_error_message: None,
         };
 
         let result = ClaudeMessage::try_from(raw);
@@ -1400,8 +2540,12 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            hook_errors: None,
+            error: None,
+            git_branch: None,
             content: None,
             is_meta: None,
+            is_api_error_message: None,
         };
 
         let result = ClaudeMessage::try_from(raw);
@@ -1451,8 +2595,12 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            hook_errors: None,
+            error: None,
+            git_branch: None,
             content: None,
             is_meta: None,
+            is_api_error_message: None,
         };
 
         let result = ClaudeMessage::try_from(raw);
@@ -1499,8 +2647,12 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            hook_errors: None,
+            error: None,
+            git_branch: None,
             content: None,
             is_meta: None,
+            is_api_error_message: None,
         };
 
         let result = ClaudeMessage::try_from(raw);
@@ -1547,8 +2699,12 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            hook_errors: None,
+            error: None,
+            git_branch: None,
             content: None,
             is_meta: None,
+            is_api_error_message: None,
         };
 
         // Should succeed with timestamp even without session_id
@@ -1598,6 +2754,7 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            git_branch: None,
         };
 
         let usage = extract_token_usage(&msg);
@@ -1646,6 +2803,7 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            git_branch: None,
         };
 
         let usage = extract_token_usage(&msg);
@@ -1692,6 +2850,7 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            git_branch: None,
         };
 
         let usage = extract_token_usage(&msg);
@@ -1734,6 +2893,7 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            git_branch: None,
         };
 
         let usage = extract_token_usage(&msg);
@@ -1774,10 +2934,602 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            git_branch: None,
         };
 
         let usage = extract_token_usage(&msg);
         assert!(usage.input_tokens.is_none());
         assert!(usage.output_tokens.is_none());
     }
+
+    #[test]
+    fn test_build_token_histogram_buckets() {
+        let histogram = build_token_histogram(vec![50, 100, 101, 600, 250_000]);
+
+        assert_eq!(histogram.message_count, 5);
+        assert_eq!(histogram.total_tokens, 250_851);
+        assert_eq!(histogram.buckets.len(), 9);
+        assert_eq!(histogram.buckets[0].message_count, 2); // 0-100
+        assert_eq!(histogram.buckets[1].message_count, 1); // 101-500
+        assert_eq!(histogram.buckets[2].message_count, 1); // 501-1000
+        assert_eq!(histogram.buckets[8].min_tokens, 200_001);
+        assert_eq!(histogram.buckets[8].max_tokens, None);
+        assert_eq!(histogram.buckets[8].message_count, 1);
+        assert_eq!(histogram.median_tokens, 101);
+        assert_eq!(histogram.max_tokens, 250_000);
+        assert!(histogram.top_decile_token_share > 0.99);
+    }
+
+    #[test]
+    fn test_build_token_histogram_empty() {
+        let histogram = build_token_histogram(Vec::new());
+
+        assert_eq!(histogram.message_count, 0);
+        assert_eq!(histogram.median_tokens, 0);
+        assert!(histogram.buckets.iter().all(|b| b.message_count == 0));
+        assert!(histogram.top_decile_token_share.abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn test_stats_service_tier_breakdown() {
+        use crate::test_utils::{create_jsonl_content, MessageBuilder};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let project = temp.path().join("projects").join("api");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(
+            project.join("s1.jsonl"),
+            create_jsonl_content(&[
+                MessageBuilder::user(),
+                MessageBuilder::assistant()
+                    .with_usage(1_000, 100)
+                    .with_service_tier("priority"),
+                MessageBuilder::assistant()
+                    .with_usage(200, 10)
+                    .with_service_tier("standard"),
+                MessageBuilder::assistant()
+                    .with_usage(300, 20)
+                    .with_service_tier("priority"),
+                MessageBuilder::assistant().with_usage(5, 5),
+            ]),
+        )
+        .unwrap();
+
+        let project_summary = get_project_stats_summary(project.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        let tiers: Vec<(&str, u32, u64)> = project_summary
+            .service_tier_breakdown
+            .iter()
+            .map(|t| (t.service_tier.as_str(), t.response_count, t.total_tokens))
+            .collect();
+        assert_eq!(
+            tiers,
+            [
+                ("priority", 2, 1_420),
+                ("standard", 1, 210),
+                ("unknown", 1, 10)
+            ]
+        );
+
+        let global =
+            get_global_stats_summary(temp.path().to_string_lossy().to_string(), None, None)
+                .await
+                .unwrap();
+        assert_eq!(
+            global.service_tier_breakdown,
+            project_summary.service_tier_breakdown
+        );
+    }
+
+    #[tokio::test]
+    async fn test_model_daily_stats() {
+        use crate::test_utils::{create_jsonl_content, MessageBuilder};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let project = temp.path().join("projects").join("api");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(
+            project.join("s1.jsonl"),
+            create_jsonl_content(&[
+                MessageBuilder::assistant()
+                    .with_model("claude-sonnet-4-20250514")
+                    .with_timestamp("2025-03-01T09:00:00Z")
+                    .with_usage(100, 10),
+                MessageBuilder::assistant()
+                    .with_model("claude-opus-4-20250514")
+                    .with_timestamp("2025-03-02T09:00:00Z")
+                    .with_usage(1_000, 100),
+                MessageBuilder::assistant()
+                    .with_model("claude-sonnet-4-20250514")
+                    .with_timestamp("2025-03-02T10:00:00Z")
+                    .with_usage(50, 5),
+            ]),
+        )
+        .unwrap();
+
+        let project_summary = get_project_stats_summary(project.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        let daily: Vec<(&str, &str, u32, u64)> = project_summary
+            .model_daily_stats
+            .iter()
+            .map(|d| {
+                (
+                    d.date.as_str(),
+                    d.model_name.as_str(),
+                    d.message_count,
+                    d.token_count,
+                )
+            })
+            .collect();
+        assert_eq!(
+            daily,
+            [
+                ("2025-03-01", "claude-sonnet-4", 1, 110),
+                ("2025-03-02", "claude-opus-4", 1, 1_100),
+                ("2025-03-02", "claude-sonnet-4", 1, 55),
+            ]
+        );
+        assert!(project_summary.model_daily_stats[1].cost_usd > 0.0);
+
+        let global =
+            get_global_stats_summary(temp.path().to_string_lossy().to_string(), None, None)
+                .await
+                .unwrap();
+        assert_eq!(global.model_daily_stats, project_summary.model_daily_stats);
+    }
+
+    #[tokio::test]
+    async fn test_thinking_stats() {
+        use crate::test_utils::{create_jsonl_content, MessageBuilder};
+        use serde_json::json;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let project = temp.path().join("api");
+        fs::create_dir_all(&project).unwrap();
+        // One response written as a thinking line and a text line, each
+        // carrying the full usage, then a response without thinking
+        fs::write(
+            project.join("s1.jsonl"),
+            create_jsonl_content(&[
+                MessageBuilder::assistant()
+                    .with_message_id("msg_1")
+                    .with_model("claude-sonnet-4-20250514")
+                    .with_content(json!([{"type": "thinking", "thinking": "a".repeat(400)}]))
+                    .with_usage(100, 400),
+                MessageBuilder::assistant()
+                    .with_message_id("msg_1")
+                    .with_model("claude-sonnet-4-20250514")
+                    .with_content(json!([{"type": "text", "text": "Done"}]))
+                    .with_usage(100, 400),
+                MessageBuilder::assistant()
+                    .with_message_id("msg_2")
+                    .with_model("claude-sonnet-4-20250514")
+                    .with_content(json!([
+                        {"type": "redacted_thinking", "data": "..."},
+                        {"type": "text", "text": "Ok"}
+                    ]))
+                    .with_usage(100, 100),
+                MessageBuilder::assistant()
+                    .with_message_id("msg_3")
+                    .with_model("claude-opus-4-20250514")
+                    .with_content(json!([{"type": "text", "text": "Hi"}]))
+                    .with_usage(100, 50),
+            ]),
+        )
+        .unwrap();
+
+        let summary = get_project_stats_summary(project.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(summary.thinking_stats.len(), 2);
+
+        let sonnet = &summary.thinking_stats[0];
+        assert_eq!(sonnet.model_name, "claude-sonnet-4");
+        assert_eq!((sonnet.turn_count, sonnet.thinking_turn_count), (2, 2));
+        assert_eq!(sonnet.thinking_block_count, 2);
+        assert_eq!(sonnet.estimated_thinking_tokens, 100);
+        assert_eq!(sonnet.output_tokens, 500);
+        assert!((sonnet.thinking_token_share - 20.0).abs() < 1e-9);
+        assert!((sonnet.avg_thinking_tokens - 50.0).abs() < 1e-9);
+
+        let opus = &summary.thinking_stats[1];
+        assert_eq!((opus.turn_count, opus.thinking_turn_count), (1, 0));
+        assert!(opus.thinking_token_share.abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_global_stats_source_breakdown() {
+        use crate::test_utils::{create_jsonl_content, MessageBuilder};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let project = temp.path().join("projects").join("api");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(
+            project.join("s1.jsonl"),
+            create_jsonl_content(&[
+                MessageBuilder::user(),
+                MessageBuilder::assistant().with_usage(1_000, 100),
+            ]),
+        )
+        .unwrap();
+        let aider_project = temp.path().join("aider").join("web");
+        fs::create_dir_all(&aider_project).unwrap();
+        fs::write(
+            aider_project.join(".aider.chat.history.md"),
+            "# aider chat started at 2024-05-01 10:00:00\n\n#### Fix the build\n\nDone.\n",
+        )
+        .unwrap();
+        let claude_path = temp.path().to_string_lossy().to_string();
+        let source_paths = BTreeMap::from([(
+            "aider".to_string(),
+            temp.path().join("aider").to_string_lossy().to_string(),
+        )]);
+
+        let summary = get_global_stats_summary(
+            claude_path.clone(),
+            Some(vec!["claude-code".to_string(), "aider".to_string()]),
+            Some(source_paths.clone()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(summary.total_sessions, 2);
+        assert_eq!(summary.total_projects, 2);
+        assert_eq!(summary.total_tokens, 1_100);
+        assert_eq!(summary.source_breakdown.len(), 2);
+        let claude = &summary.source_breakdown[0];
+        assert_eq!(claude.source, "claude-code");
+        assert_eq!(claude.total_sessions, 1);
+        assert_eq!(claude.total_tokens, Some(1_100));
+        assert!(claude.total_cost_usd.unwrap() > 0.0);
+        let aider = &summary.source_breakdown[1];
+        assert_eq!(aider.total_sessions, 1);
+        assert_eq!(aider.total_messages, 2);
+        assert_eq!(aider.total_tokens, None);
+        assert!(summary
+            .top_projects
+            .iter()
+            .any(|p| p.project_name == "web" && p.source.as_deref() == Some("aider")));
+
+        let aider_only = get_global_stats_summary(
+            String::new(),
+            Some(vec!["aider".to_string()]),
+            Some(source_paths),
+        )
+        .await
+        .unwrap();
+        assert_eq!(aider_only.total_sessions, 1);
+        assert_eq!(aider_only.total_tokens, 0);
+
+        let default = get_global_stats_summary(claude_path, None, None)
+            .await
+            .unwrap();
+        assert_eq!(default.total_sessions, 1);
+        assert_eq!(default.source_breakdown[0].source, "claude-code");
+    }
+
+    #[test]
+    fn test_global_stats_per_scan_root() {
+        use crate::test_utils::{create_jsonl_content, MessageBuilder};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let write_session = |projects: &Path, project: &str, output_tokens: u32| {
+            let project = projects.join(project);
+            fs::create_dir_all(&project).unwrap();
+            fs::write(
+                project.join("s1.jsonl"),
+                create_jsonl_content(&[
+                    MessageBuilder::user(),
+                    MessageBuilder::assistant().with_usage(0, output_tokens),
+                ]),
+            )
+            .unwrap();
+        };
+        let local = temp.path().join("me");
+        write_session(&local.join("projects"), "-Users-me-api", 100);
+        // Copied `projects` folders of two team members
+        let alice = temp.path().join("shared").join("alice");
+        write_session(&alice, "-Users-alice-api", 200);
+        write_session(&alice, "-Users-alice-web", 300);
+        let bob = temp.path().join("shared").join("bob");
+        let roots =
+            [("alice", &alice), ("bob", &bob), ("me-again", &local)].map(|(label, path)| {
+                ScanRoot {
+                    label: label.to_string(),
+                    path: path.to_string_lossy().to_string(),
+                }
+            });
+
+        let summary = global_stats_summary(&local.to_string_lossy(), None, None, &roots).unwrap();
+
+        assert_eq!(summary.total_sessions, 3);
+        assert_eq!(summary.total_projects, 3);
+        assert_eq!(summary.total_tokens, 600);
+        let breakdown: Vec<(&str, u32, u32, u64)> = summary
+            .root_breakdown
+            .iter()
+            .map(|r| {
+                (
+                    r.label.as_str(),
+                    r.total_projects,
+                    r.total_sessions,
+                    r.total_tokens,
+                )
+            })
+            .collect();
+        assert_eq!(
+            breakdown,
+            [
+                ("local", 1, 1, 100),
+                ("alice", 2, 2, 500),
+                ("bob", 0, 0, 0),
+                ("me-again", 0, 0, 0),
+            ]
+        );
+        assert!(summary.top_projects.iter().any(|p| {
+            p.root.as_deref() == Some("alice") && p.project_name == "-Users-alice-web"
+        }));
+
+        let without_roots =
+            global_stats_summary(&local.to_string_lossy(), None, None, &[]).unwrap();
+        assert!(without_roots.root_breakdown.is_empty());
+        assert!(without_roots.top_projects.iter().all(|p| p.root.is_none()));
+    }
+
+    #[test]
+    fn test_work_patterns() {
+        let mut summary = GlobalStatsSummary::default();
+        summary.daily_stats = [
+            "2025-03-01",
+            "2025-03-02",
+            "2025-03-03",
+            "2025-03-07",
+            "2025-03-08",
+        ]
+        .iter()
+        .map(|date| DailyStats {
+            date: (*date).to_string(),
+            ..Default::default()
+        })
+        .collect();
+        summary.activity_heatmap = [(9, 1, 4), (14, 1, 2), (10, 3, 5)]
+            .iter()
+            .map(|&(hour, day, activity_count)| ActivityHeatmap {
+                hour,
+                day,
+                activity_count,
+                tokens_used: 0,
+            })
+            .collect();
+        let session = |start: &str, minutes: u64| SessionLength {
+            start: DateTime::parse_from_rfc3339(start)
+                .unwrap()
+                .with_timezone(&Utc),
+            minutes,
+            project_name: "api".to_string(),
+            file_path: format!("/projects/api/{minutes}.jsonl"),
+        };
+        let sessions = [
+            session("2025-03-01T09:00:00Z", 30),
+            session("2025-03-02T10:00:00Z", 90),
+            session("2025-03-07T20:00:00Z", 40),
+        ];
+        let today = NaiveDate::from_ymd_opt(2025, 3, 9).unwrap();
+
+        let patterns = work_patterns(&summary, &sessions, today);
+
+        assert_eq!(patterns.active_days, 5);
+        assert_eq!(patterns.longest_streak_days, 3);
+        assert_eq!(patterns.longest_streak_start.as_deref(), Some("2025-03-01"));
+        assert_eq!(patterns.longest_streak_end.as_deref(), Some("2025-03-03"));
+        assert_eq!(patterns.current_streak_days, 2);
+        assert_eq!(patterns.busiest_weekday, Some(1));
+        let periods: Vec<(&str, u32, f64)> = patterns
+            .session_length_by_time_of_day
+            .iter()
+            .map(|p| (p.period.as_str(), p.session_count, p.avg_duration_minutes))
+            .collect();
+        assert_eq!(
+            periods,
+            [
+                ("night", 0, 0.0),
+                ("morning", 2, 60.0),
+                ("afternoon", 0, 0.0),
+                ("evening", 1, 40.0),
+            ]
+        );
+        let longest = patterns.longest_session.unwrap();
+        assert_eq!(longest.duration_minutes, 90);
+        assert_eq!(longest.file_path, "/projects/api/90.jsonl");
+
+        // The streak is over once a day goes by without activity
+        let later = work_patterns(
+            &summary,
+            &sessions,
+            NaiveDate::from_ymd_opt(2025, 3, 10).unwrap(),
+        );
+        assert_eq!(later.current_streak_days, 0);
+    }
+
+    #[test]
+    fn test_validate_scan_roots() {
+        let root = |label: &str| ScanRoot {
+            label: label.to_string(),
+            path: "/shared/team".to_string(),
+        };
+        assert!(validate_scan_roots(&[root("alice"), root("bob")]).is_ok());
+        assert!(validate_scan_roots(&[root("alice"), root("alice")]).is_err());
+        assert!(validate_scan_roots(&[root("Local")]).is_err());
+        assert!(validate_scan_roots(&[root(" ")]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_token_histograms_session_scope() {
+        use crate::test_utils::{create_jsonl_content, MessageBuilder};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let session_path = temp.path().join("session.jsonl");
+        fs::write(
+            &session_path,
+            create_jsonl_content(&[
+                MessageBuilder::user(),
+                MessageBuilder::assistant().with_usage(2_000, 300),
+                MessageBuilder::assistant().with_usage(60_000, 40),
+            ]),
+        )
+        .unwrap();
+
+        let histograms = get_token_histograms(
+            "session".to_string(),
+            session_path.to_string_lossy().to_string(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(histograms.session_count, 1);
+        assert_eq!(histograms.input.message_count, 2);
+        assert_eq!(histograms.input.total_tokens, 62_000);
+        assert_eq!(histograms.output.total_tokens, 340);
+        assert!(get_token_histograms("galaxy".to_string(), String::new())
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_group_model_stats_merges_aliases() {
+        let usage = HashMap::from([
+            (
+                "claude-sonnet-4-20250514".to_string(),
+                (2, 300, 100, 200, 0, 0),
+            ),
+            (
+                "us.anthropic.claude-sonnet-4-20250514-v1:0".to_string(),
+                (1, 50, 20, 30, 0, 0),
+            ),
+            (
+                "claude-3-5-haiku-20241022".to_string(),
+                (1, 500, 400, 100, 0, 0),
+            ),
+        ]);
+
+        let cost = HashMap::from([
+            ("claude-sonnet-4-20250514".to_string(), 0.5),
+            (
+                "us.anthropic.claude-sonnet-4-20250514-v1:0".to_string(),
+                0.25,
+            ),
+        ]);
+
+        let distribution = group_model_stats(usage, &cost);
+
+        assert_eq!(distribution.len(), 2);
+        assert_eq!(distribution[0].model_name, "claude-3-5-haiku");
+        let sonnet = &distribution[1];
+        assert_eq!(sonnet.model_name, "claude-sonnet-4");
+        assert_eq!(sonnet.message_count, 3);
+        assert_eq!(sonnet.token_count, 350);
+        assert!((sonnet.cost_usd - 0.75).abs() < 1e-9);
+        assert_eq!(sonnet.variants.len(), 2);
+        assert_eq!(sonnet.variants[0].model_name, "claude-sonnet-4-20250514");
+    }
+
+    #[test]
+    fn test_response_usage_counted_once_per_id() {
+        let mut part = crate::test_utils::MessageBuilder::assistant()
+            .with_usage(100, 50)
+            .build();
+        part.message_id = Some("msg_1".to_string());
+        let mut responses = ResponseUsageTracker::default();
+
+        assert_eq!(responses.usage_of(&part).unwrap().output_tokens, Some(50));
+        assert!(responses.usage_of(&part).is_none());
+
+        part.message_id = None;
+        assert!(responses.usage_of(&part).is_some());
+        assert_eq!(responses.duplicates, 1);
+    }
+
+    #[tokio::test]
+    async fn test_session_token_stats_collapse_streamed_parts() {
+        use crate::test_utils::{create_jsonl_content, MessageBuilder};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let session_path = temp.path().join("session.jsonl");
+        // One streamed response split over three lines, each repeating its usage
+        fs::write(
+            &session_path,
+            create_jsonl_content(&[
+                MessageBuilder::user(),
+                MessageBuilder::assistant()
+                    .with_message_id("msg_1")
+                    .with_usage(1_000, 200),
+                MessageBuilder::assistant()
+                    .with_message_id("msg_1")
+                    .with_usage(1_000, 200),
+                MessageBuilder::assistant()
+                    .with_message_id("msg_1")
+                    .with_usage(1_000, 200),
+                MessageBuilder::assistant()
+                    .with_message_id("msg_2")
+                    .with_usage(10, 5),
+            ]),
+        )
+        .unwrap();
+
+        let stats = get_session_token_stats(session_path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(stats.total_input_tokens, 1_010);
+        assert_eq!(stats.total_output_tokens, 205);
+        assert_eq!(stats.message_count, 5);
+        assert_eq!(stats.duplicate_usage_entries, 2);
+        // No costUSD recorded: estimated at Opus rates, each response once
+        assert!((stats.total_cost_usd - 0.030_525).abs() < 1e-9);
+
+        let synced = extract_session_token_stats_sync(&session_path).unwrap();
+        assert_eq!(synced.total_tokens, stats.total_tokens);
+        assert_eq!(synced.duplicate_usage_entries, 2);
+        assert!((synced.total_cost_usd - stats.total_cost_usd).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_session_token_stats_counts_match_counting_rules() {
+        use crate::test_utils::{prompt_line, write_jsonl};
+        use serde_json::json;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let session_path = temp.path().join("session.jsonl");
+        let mut meta = prompt_line(
+            "u2",
+            "2025-01-01T00:00:02Z",
+            "<command-name>/clear</command-name>",
+        );
+        meta["isMeta"] = json!(true);
+        let lines = [
+            prompt_line("u1", "2025-01-01T00:00:00Z", "Hello"),
+            json!({"type": "progress", "sessionId": "s1", "timestamp": "2025-01-01T00:00:01Z"}),
+            meta,
+            json!({"type": "summary", "summary": "Greeting"}),
+        ];
+        write_jsonl(&session_path, &lines);
+
+        let stats = get_session_token_stats(session_path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        // Only the prompt counts: progress and meta entries are excluded by default
+        assert_eq!(stats.raw_message_count, 3);
+        assert_eq!(stats.message_count, 1);
+
+        let synced = extract_session_token_stats_sync(&session_path).unwrap();
+        assert_eq!(synced.message_count, stats.message_count);
+        assert_eq!(synced.raw_message_count, stats.raw_message_count);
+
+        let missing = temp.path().join("missing.jsonl");
+        assert!(matches!(
+            get_session_token_stats(missing.to_string_lossy().to_string()).await,
+            Err(AppError::NotFound { message }) if message.starts_with("Failed to open session file")
+        ));
+    }
 }
@@ -0,0 +1,467 @@
+//! Session timeline reconstruction
+//!
+//! Walks the main conversation chronologically and cuts it into turns, one
+//! per user prompt. Each turn is split into tool spans (`tool_use` to its
+//! `tool_result`, stretched by the tool's progress entries) and assistant
+//! spans covering the rest; the pause before a turn is user wait time, or
+//! idle time when it exceeds the idle gap.
+
+use crate::commands::focus::{DEFAULT_IDLE_GAP_MINUTES, MAX_IDLE_GAP_MINUTES};
+use crate::commands::prompt_quality::{prompt_text, INTERRUPT_PREFIX};
+use crate::commands::retry_loops::truncate_chars;
+use crate::commands::session::is_genuine_user_text;
+use crate::commands::stats::read_raw_log_entries;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{RawLogEntry, SessionTimeline, TimelineSpan, TimelineTurn};
+use crate::utils::resolve_session_file;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::collections::HashMap;
+
+/// Maximum characters kept for prompt previews
+const PROMPT_PREVIEW_CHARS: usize = 120;
+
+/// A tool call from its `tool_use` block to its last sign of life
+struct ToolRun {
+    id: String,
+    name: String,
+    start: DateTime<Utc>,
+    end: Option<DateTime<Utc>>, // Result or latest progress; None if neither was logged
+}
+
+struct Turn {
+    prompt_uuid: Option<String>,
+    prompt_preview: String,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    reported_duration_ms: Option<u64>,
+    tools: Vec<ToolRun>,
+}
+
+impl Turn {
+    fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            prompt_uuid: None,
+            prompt_preview: String::new(),
+            start,
+            end: start,
+            reported_duration_ms: None,
+            tools: Vec::new(),
+        }
+    }
+}
+
+fn millis(from: DateTime<Utc>, to: DateTime<Utc>) -> u64 {
+    u64::try_from((to - from).num_milliseconds()).unwrap_or(0)
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn span(kind: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> TimelineSpan {
+    TimelineSpan {
+        kind: kind.to_string(),
+        start_time: format_time(start),
+        end_time: format_time(end),
+        duration_ms: millis(start, end),
+        tool_name: None,
+        tool_use_id: None,
+    }
+}
+
+/// Prompt text of a user entry that starts a new turn
+fn turn_prompt(entry: &RawLogEntry) -> Option<String> {
+    if entry.is_meta == Some(true) {
+        return None;
+    }
+    let text = prompt_text(&entry.message.as_ref()?.content)?;
+    (!text.trim_start().starts_with(INTERRUPT_PREFIX) && is_genuine_user_text(&text))
+        .then_some(text)
+}
+
+fn content_blocks<'a>(
+    entry: &'a RawLogEntry,
+    block_type: &'a str,
+) -> impl Iterator<Item = &'a serde_json::Value> {
+    entry
+        .message
+        .as_ref()
+        .and_then(|message| message.content.as_array())
+        .into_iter()
+        .flatten()
+        .filter(move |item| item.get("type").and_then(|v| v.as_str()) == Some(block_type))
+}
+
+/// Group the main conversation into turns, in file order
+fn collect_turns(entries: &[RawLogEntry]) -> Vec<Turn> {
+    let mut turns: Vec<Turn> = Vec::new();
+    // Tool use ID -> (turn index, tool index)
+    let mut tool_index: HashMap<String, (usize, usize)> = HashMap::new();
+
+    for entry in entries {
+        if entry.is_sidechain == Some(true)
+            || !matches!(
+                entry.message_type.as_str(),
+                "user" | "assistant" | "system" | "progress"
+            )
+        {
+            continue;
+        }
+        let Some(time) = entry
+            .timestamp
+            .as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc))
+        else {
+            continue;
+        };
+
+        if entry.message_type == "user" {
+            if let Some(text) = turn_prompt(entry) {
+                turns.push(Turn {
+                    prompt_uuid: entry.uuid.clone(),
+                    prompt_preview: truncate_chars(text.trim(), PROMPT_PREVIEW_CHARS),
+                    ..Turn::new(time)
+                });
+                continue;
+            }
+        }
+        // Activity before the first prompt (e.g. a resumed session) forms its own turn
+        if turns.is_empty() {
+            turns.push(Turn::new(time));
+        }
+        let turn_idx = turns.len() - 1;
+        let turn = &mut turns[turn_idx];
+        // Slash commands and other non-prompt user entries don't extend the turn
+        if entry.message_type != "user" || content_blocks(entry, "tool_result").next().is_some() {
+            turn.end = turn.end.max(time);
+        }
+
+        match entry.message_type.as_str() {
+            "assistant" => {
+                for tool_use in content_blocks(entry, "tool_use") {
+                    let Some(id) = tool_use.get("id").and_then(|v| v.as_str()) else {
+                        continue;
+                    };
+                    tool_index.insert(id.to_string(), (turn_idx, turn.tools.len()));
+                    turn.tools.push(ToolRun {
+                        id: id.to_string(),
+                        name: tool_use
+                            .get("name")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("unknown")
+                            .to_string(),
+                        start: time,
+                        end: None,
+                    });
+                }
+            }
+            "user" => {
+                for result in content_blocks(entry, "tool_result") {
+                    let Some(&(t, i)) = result
+                        .get("tool_use_id")
+                        .and_then(|v| v.as_str())
+                        .and_then(|id| tool_index.get(id))
+                    else {
+                        continue;
+                    };
+                    let run = &mut turns[t].tools[i];
+                    run.end = Some(run.end.map_or(time, |end| end.max(time)));
+                }
+            }
+            "progress" => {
+                // Bash progress points at its tool through `parentToolUseID`,
+                // hook progress through `toolUseID`
+                let ids = [&entry.tool_use_id, &entry.parent_tool_use_id];
+                if let Some(&(t, i)) = ids
+                    .into_iter()
+                    .flatten()
+                    .find_map(|id| tool_index.get(id.as_str()))
+                {
+                    let run = &mut turns[t].tools[i];
+                    run.end = Some(run.end.map_or(time, |end| end.max(time)));
+                }
+            }
+            "system" if entry.subtype.as_deref() == Some("turn_duration") => {
+                turn.reported_duration_ms = entry.duration_ms;
+            }
+            _ => {}
+        }
+    }
+
+    turns
+}
+
+/// Union of the intervals, sorted and merged
+fn merge_intervals(
+    mut intervals: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    intervals.sort();
+    let mut merged: Vec<(DateTime<Utc>, DateTime<Utc>)> = Vec::new();
+    for (start, end) in intervals {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+fn timeline_turn(index: usize, turn: Turn, wait_span: Option<TimelineSpan>) -> TimelineTurn {
+    // A tool without result or progress was interrupted: it ran until the turn ended
+    let tool_intervals: Vec<(DateTime<Utc>, DateTime<Utc>)> = turn
+        .tools
+        .iter()
+        .map(|run| (run.start, run.end.unwrap_or(turn.end).min(turn.end)))
+        .collect();
+    let busy = merge_intervals(tool_intervals.clone());
+    let tool_ms: u64 = busy.iter().map(|&(start, end)| millis(start, end)).sum();
+
+    let mut work_spans: Vec<TimelineSpan> = turn
+        .tools
+        .iter()
+        .zip(&tool_intervals)
+        .map(|(run, &(start, end))| TimelineSpan {
+            tool_name: Some(run.name.clone()),
+            tool_use_id: Some(run.id.clone()),
+            ..span("tool", start, end)
+        })
+        .collect();
+    let mut cursor = turn.start;
+    for &(start, end) in busy.iter().chain([(turn.end, turn.end)].iter()) {
+        if start > cursor {
+            work_spans.push(span("assistant", cursor, start));
+        }
+        cursor = cursor.max(end);
+    }
+    work_spans.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+
+    let observed_ms = millis(turn.start, turn.end);
+    TimelineTurn {
+        index,
+        prompt_uuid: turn.prompt_uuid,
+        prompt_preview: turn.prompt_preview,
+        start_time: format_time(turn.start),
+        end_time: format_time(turn.end),
+        wait_before_ms: wait_span.as_ref().map_or(0, |span| span.duration_ms),
+        reported_duration_ms: turn.reported_duration_ms,
+        assistant_ms: turn
+            .reported_duration_ms
+            .unwrap_or(observed_ms)
+            .saturating_sub(tool_ms),
+        tool_ms,
+        tool_count: turn.tools.len(),
+        spans: wait_span.into_iter().chain(work_spans).collect(),
+    }
+}
+
+fn build_timeline(
+    session_id: String,
+    entries: &[RawLogEntry],
+    idle_gap_minutes: u32,
+) -> SessionTimeline {
+    let turns = collect_turns(entries);
+    let start = turns.first().map(|turn| turn.start);
+    let end = turns.iter().map(|turn| turn.end).max();
+    let idle_gap_ms = u64::from(idle_gap_minutes) * 60_000;
+
+    let mut user_wait_ms = 0;
+    let mut idle_ms = 0;
+    let mut previous_end: Option<DateTime<Utc>> = None;
+    let mut timeline_turns = Vec::with_capacity(turns.len());
+    for (index, turn) in turns.into_iter().enumerate() {
+        let wait_span = previous_end.filter(|&prev| turn.start > prev).map(|prev| {
+            let wait_ms = millis(prev, turn.start);
+            if wait_ms > idle_gap_ms {
+                idle_ms += wait_ms;
+                span("idle", prev, turn.start)
+            } else {
+                user_wait_ms += wait_ms;
+                span("user_wait", prev, turn.start)
+            }
+        });
+        previous_end = Some(previous_end.map_or(turn.end, |prev| prev.max(turn.end)));
+        timeline_turns.push(timeline_turn(index, turn, wait_span));
+    }
+
+    SessionTimeline {
+        session_id,
+        start_time: start.map(format_time),
+        end_time: end.map(format_time),
+        idle_gap_minutes,
+        total_ms: start.zip(end).map_or(0, |(start, end)| millis(start, end)),
+        user_wait_ms,
+        idle_ms,
+        assistant_ms: timeline_turns.iter().map(|turn| turn.assistant_ms).sum(),
+        tool_ms: timeline_turns.iter().map(|turn| turn.tool_ms).sum(),
+        turns: timeline_turns,
+    }
+}
+
+/// Turn-level timeline of a session for a Gantt-style view
+///
+/// Pauses between turns longer than `idle_gap_minutes` (default 15) count as
+/// idle rather than user wait time. A turn's assistant time is its
+/// `turn_duration` entry's `durationMs`, or its observed span when absent,
+/// minus tool time.
+#[tauri::command]
+pub async fn get_session_timeline(
+    session_id: String,
+    project_path: String,
+    idle_gap_minutes: Option<u32>,
+) -> Result<SessionTimeline, AppError> {
+    let _timer = OperationTimer::start("get_session_timeline");
+
+    let idle_gap_minutes = idle_gap_minutes.unwrap_or(DEFAULT_IDLE_GAP_MINUTES);
+    if !(1..=MAX_IDLE_GAP_MINUTES).contains(&idle_gap_minutes) {
+        return Err(AppError::invalid_input(format!(
+            "Idle gap must be between 1 and {MAX_IDLE_GAP_MINUTES} minutes"
+        )));
+    }
+    let session_path = resolve_session_file(&project_path, &session_id)?;
+
+    let entries = tauri::async_runtime::spawn_blocking(move || read_raw_log_entries(&session_path))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?;
+    Ok(build_timeline(session_id, &entries, idle_gap_minutes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::raw_entry;
+    use serde_json::json;
+
+    fn prompt(uuid: &str, ts: &str, text: &str) -> RawLogEntry {
+        raw_entry(json!({
+            "uuid": uuid, "timestamp": ts, "type": "user",
+            "message": {"role": "user", "content": text}
+        }))
+    }
+
+    fn tool_use(ts: &str, id: &str, name: &str) -> RawLogEntry {
+        raw_entry(json!({
+            "timestamp": ts, "type": "assistant",
+            "message": {"role": "assistant", "content": [{"type": "tool_use", "id": id, "name": name, "input": {}}]}
+        }))
+    }
+
+    fn tool_result(ts: &str, id: &str) -> RawLogEntry {
+        raw_entry(json!({
+            "timestamp": ts, "type": "user",
+            "message": {"role": "user", "content": [{"type": "tool_result", "tool_use_id": id, "content": "ok"}]}
+        }))
+    }
+
+    fn reply(ts: &str) -> RawLogEntry {
+        raw_entry(json!({
+            "timestamp": ts, "type": "assistant",
+            "message": {"role": "assistant", "content": [{"type": "text", "text": "done"}]}
+        }))
+    }
+
+    fn kinds(turn: &TimelineTurn) -> Vec<(&str, u64)> {
+        turn.spans
+            .iter()
+            .map(|span| (span.kind.as_str(), span.duration_ms))
+            .collect()
+    }
+
+    #[test]
+    fn test_timeline_splits_turns_into_spans() {
+        let entries = vec![
+            prompt("p1", "2025-01-01T10:00:00Z", "Fix the build"),
+            tool_use("2025-01-01T10:00:05Z", "t1", "Bash"),
+            raw_entry(json!({
+                "timestamp": "2025-01-01T10:00:20Z", "type": "progress",
+                "toolUseID": "bash-progress-0", "parentToolUseID": "t1",
+                "data": {"type": "bash_progress"}
+            })),
+            tool_result("2025-01-01T10:00:35Z", "t1"),
+            reply("2025-01-01T10:00:40Z"),
+            raw_entry(json!({
+                "timestamp": "2025-01-01T10:00:41Z", "type": "system",
+                "subtype": "turn_duration", "durationMs": 41000
+            })),
+            // Answered after two minutes, then left alone for an hour
+            prompt("p2", "2025-01-01T10:02:41Z", "Now run the tests"),
+            tool_use("2025-01-01T10:02:50Z", "t2", "Bash"),
+            tool_use("2025-01-01T10:02:50Z", "t3", "Read"),
+            tool_result("2025-01-01T10:03:00Z", "t3"),
+            tool_result("2025-01-01T10:03:10Z", "t2"),
+            reply("2025-01-01T10:03:20Z"),
+            prompt(
+                "p3",
+                "2025-01-01T11:03:20Z",
+                "<command-name>/clear</command-name>",
+            ),
+            prompt("p4", "2025-01-01T11:03:20Z", "Thanks"),
+        ];
+
+        let timeline = build_timeline("s1".to_string(), &entries, 15);
+
+        assert_eq!(timeline.turns.len(), 3);
+        let first = &timeline.turns[0];
+        assert_eq!(first.prompt_uuid.as_deref(), Some("p1"));
+        assert_eq!(
+            kinds(first),
+            [("assistant", 5000), ("tool", 30000), ("assistant", 6000)]
+        );
+        assert_eq!(first.spans[1].tool_name.as_deref(), Some("Bash"));
+        assert_eq!((first.tool_ms, first.assistant_ms), (30000, 11000));
+
+        let second = &timeline.turns[1];
+        assert_eq!(second.wait_before_ms, 120_000);
+        assert_eq!(
+            kinds(second),
+            [
+                ("user_wait", 120_000),
+                ("assistant", 9000),
+                ("tool", 20000),
+                ("tool", 10000),
+                ("assistant", 10000)
+            ]
+        );
+        // Parallel calls count once; no reported duration, so the observed span is used
+        assert_eq!((second.tool_ms, second.assistant_ms), (20000, 19000));
+
+        let third = &timeline.turns[2];
+        assert_eq!(third.prompt_uuid.as_deref(), Some("p4"));
+        assert_eq!(kinds(third)[0], ("idle", 3_600_000));
+
+        assert_eq!(timeline.total_ms, 3_800_000);
+        assert_eq!(timeline.user_wait_ms, 120_000);
+        assert_eq!(timeline.idle_ms, 3_600_000);
+        assert_eq!(timeline.tool_ms, 50000);
+        assert_eq!(timeline.assistant_ms, 30000);
+    }
+
+    #[test]
+    fn test_timeline_interrupted_tool_runs_to_turn_end() {
+        let entries = vec![
+            tool_use("2025-01-01T10:00:00Z", "t1", "Bash"),
+            prompt(
+                "p1",
+                "2025-01-01T10:00:30Z",
+                "[Request interrupted by user for tool use]",
+            ),
+            reply("2025-01-01T10:00:40Z"),
+        ];
+
+        let timeline = build_timeline("s1".to_string(), &entries, 15);
+
+        assert_eq!(timeline.turns.len(), 1);
+        let turn = &timeline.turns[0];
+        assert_eq!(turn.prompt_uuid, None);
+        assert_eq!(kinds(turn), [("tool", 40000)]);
+        assert_eq!((turn.tool_ms, turn.assistant_ms), (40000, 0));
+    }
+
+    #[tokio::test]
+    async fn test_session_timeline_validates_idle_gap() {
+        let result =
+            get_session_timeline("s1".to_string(), "/nonexistent".to_string(), Some(0)).await;
+        assert!(matches!(result, Err(AppError::InvalidInput { .. })));
+    }
+}
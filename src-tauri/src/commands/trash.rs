@@ -0,0 +1,225 @@
+//! Session and project deletion
+//!
+//! Deleted sessions go to the OS trash instead of being removed, so they can
+//! be restored from there. Only folders directly under the `projects` folder
+//! of the given Claude folder are accepted (after resolving symbolic links),
+//! which keeps a bad path from trashing anything else.
+
+use crate::commands::journal::record_operation;
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::TrashedItems;
+use crate::utils::{display_path, resolve_session_file};
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Check that `project_path` is a project folder directly under
+/// `<claude_path>/projects`
+fn validate_project_dir(claude_path: &str, project_path: &str) -> Result<PathBuf, AppError> {
+    let path = Path::new(project_path);
+    let not_a_project =
+        || AppError::invalid_input(format!("Not a Claude project folder: {project_path}"));
+    let relative_parts = path
+        .components()
+        .any(|c| matches!(c, Component::CurDir | Component::ParentDir));
+    if !path.is_absolute() || relative_parts {
+        return Err(not_a_project());
+    }
+    if !path.is_dir() {
+        return Err(AppError::not_found(format!(
+            "Project not found: {project_path}"
+        )));
+    }
+
+    let projects_dir = Path::new(claude_path)
+        .join("projects")
+        .canonicalize()
+        .map_err(|e| AppError::io("Failed to resolve the projects folder", &e))?;
+    let project_dir = path
+        .canonicalize()
+        .map_err(|e| AppError::io("Failed to resolve the project folder", &e))?;
+    if project_dir.parent() != Some(projects_dir.as_path()) {
+        return Err(not_a_project());
+    }
+    Ok(path.to_path_buf())
+}
+
+/// Session file and, when present, its folder of subagent transcripts
+fn session_trash_paths(
+    claude_path: &str,
+    project_path: &str,
+    session_id: &str,
+) -> Result<Vec<PathBuf>, AppError> {
+    let project_dir = validate_project_dir(claude_path, project_path)?;
+    let session_path = resolve_session_file(project_path, session_id)?;
+    let session_dir = project_dir.join(session_id);
+    Ok(std::iter::once(session_path)
+        .chain(session_dir.is_dir().then_some(session_dir))
+        .collect())
+}
+
+/// Top-level session files of a project folder
+fn count_project_sessions(project_dir: &Path) -> usize {
+    WalkDir::new(project_dir)
+        .min_depth(1)
+        .max_depth(1)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .count()
+}
+
+fn move_to_trash(paths: &[PathBuf]) -> Result<Vec<String>, AppError> {
+    trash::delete_all(paths).map_err(|e| format!("Failed to move to trash: {e}"))?;
+    Ok(paths.iter().map(|path| display_path(path)).collect())
+}
+
+/// Move a session file (and its subagent transcripts) to the OS trash
+#[tauri::command]
+pub async fn delete_session(
+    claude_path: String,
+    session_id: String,
+    project_path: String,
+) -> Result<TrashedItems, AppError> {
+    let _timer = OperationTimer::start("delete_session");
+    let paths = session_trash_paths(&claude_path, &project_path, &session_id)?;
+
+    let paths = tauri::async_runtime::spawn_blocking(move || move_to_trash(&paths))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))??;
+    record_operation(
+        "delete_session",
+        format!("Moved session {session_id} to the trash"),
+        None,
+    );
+    Ok(TrashedItems {
+        session_count: 1,
+        paths,
+    })
+}
+
+/// Move a whole project folder to the OS trash
+#[tauri::command]
+pub async fn delete_project(
+    claude_path: String,
+    project_path: String,
+) -> Result<TrashedItems, AppError> {
+    let _timer = OperationTimer::start("delete_project");
+    let project_dir = validate_project_dir(&claude_path, &project_path)?;
+
+    let (session_count, paths) = tauri::async_runtime::spawn_blocking(move || {
+        let session_count = count_project_sessions(&project_dir);
+        move_to_trash(&[project_dir]).map(|paths| (session_count, paths))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+    record_operation(
+        "delete_project",
+        format!("Moved project {project_path} ({session_count} sessions) to the trash"),
+        None,
+    );
+    Ok(TrashedItems {
+        session_count,
+        paths,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_validate_project_dir_requires_projects_parent() {
+        let temp = TempDir::new().unwrap();
+        let claude_path = temp.path().to_string_lossy().to_string();
+        let project = temp.path().join("projects").join("-Users-me-demo");
+        fs::create_dir_all(&project).unwrap();
+
+        assert!(validate_project_dir(&claude_path, &project.to_string_lossy()).is_ok());
+        assert!(matches!(
+            validate_project_dir(
+                &claude_path,
+                &temp.path().join("projects").to_string_lossy()
+            ),
+            Err(AppError::InvalidInput { .. })
+        ));
+        assert!(matches!(
+            validate_project_dir(&claude_path, "projects/-Users-me-demo"),
+            Err(AppError::InvalidInput { .. })
+        ));
+        assert!(matches!(
+            validate_project_dir(
+                &claude_path,
+                &project.with_file_name("missing").to_string_lossy()
+            ),
+            Err(AppError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_project_dir_rejects_foreign_projects_folder() {
+        let temp = TempDir::new().unwrap();
+        let claude = temp.path().join(".claude");
+        fs::create_dir_all(claude.join("projects").join("-Users-me-demo")).unwrap();
+        let foreign = temp.path().join("work").join("projects").join("src");
+        fs::create_dir_all(&foreign).unwrap();
+
+        assert!(matches!(
+            validate_project_dir(&claude.to_string_lossy(), &foreign.to_string_lossy()),
+            Err(AppError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_project_dir_rejects_parent_components() {
+        let temp = TempDir::new().unwrap();
+        let claude_path = temp.path().to_string_lossy().to_string();
+        fs::create_dir_all(temp.path().join("projects").join("-Users-me-demo")).unwrap();
+        let sneaky = temp
+            .path()
+            .join("projects")
+            .join("-Users-me-demo")
+            .join("..")
+            .join("..")
+            .join("projects");
+
+        assert!(matches!(
+            validate_project_dir(&claude_path, &sneaky.to_string_lossy()),
+            Err(AppError::InvalidInput { .. })
+        ));
+        let dotted = temp
+            .path()
+            .join("projects")
+            .join("..")
+            .join("projects")
+            .join("-Users-me-demo");
+        assert!(matches!(
+            validate_project_dir(&claude_path, &dotted.to_string_lossy()),
+            Err(AppError::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_session_trash_paths_include_subagents() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join("projects").join("-Users-me-demo");
+        fs::create_dir_all(project.join("abc").join("subagents")).unwrap();
+        fs::write(project.join("abc.jsonl"), "{}\n").unwrap();
+        fs::write(project.join("def.jsonl"), "{}\n").unwrap();
+        let claude_path = temp.path().to_string_lossy().to_string();
+        let project_path = project.to_string_lossy().to_string();
+
+        assert_eq!(
+            session_trash_paths(&claude_path, &project_path, "abc").unwrap(),
+            [project.join("abc.jsonl"), project.join("abc")]
+        );
+        assert_eq!(
+            session_trash_paths(&claude_path, &project_path, "def").unwrap(),
+            [project.join("def.jsonl")]
+        );
+        assert!(session_trash_paths(&claude_path, &project_path, "../def").is_err());
+        assert_eq!(count_project_sessions(&project), 2);
+    }
+}
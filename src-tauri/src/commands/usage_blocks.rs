@@ -0,0 +1,520 @@
+//! 5-hour usage blocks
+//!
+//! Subscription limits are enforced per 5-hour window that opens with the
+//! first request after the previous window closed. Blocks are rebuilt from
+//! response timestamps the same way ccusage does: a block starts at the
+//! hour of its first response and lasts 5 hours; a response after the end,
+//! or after 5 idle hours, opens the next block.
+
+use crate::commands::retry_loops::total_tokens;
+use crate::commands::stats::{
+    read_raw_log_entries, resolve_scope_session_files, ResponseUsageTracker,
+};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{
+    BurnRate, BurnRateWindow, ClaudeMessage, TokenUsage, UsageBlock, UsageBlockReport,
+};
+use chrono::{DateTime, Duration, DurationRound, SecondsFormat, Utc};
+use rayon::prelude::*;
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+const BLOCK_HOURS: i64 = 5;
+
+/// Share of the token limit at which a block counts as near the limit
+const NEAR_LIMIT_RATIO: f64 = 0.9;
+
+/// Shortest span a rate is computed over, so a lone response does not
+/// extrapolate to an absurd hourly rate
+const MIN_RATE_MINUTES: i64 = 5;
+
+/// One priced response
+struct BlockResponse {
+    timestamp: DateTime<Utc>,
+    session_id: String,
+    model: Option<String>,
+    usage: TokenUsage,
+    cost_usd: f64,
+}
+
+fn session_responses(session_path: &Path) -> Vec<BlockResponse> {
+    let mut tracker = ResponseUsageTracker::default();
+    read_raw_log_entries(session_path)
+        .into_iter()
+        .filter_map(|mut entry| {
+            let cwd = entry.cwd.take();
+            let message = ClaudeMessage::try_from(entry).ok()?;
+            let timestamp = DateTime::parse_from_rfc3339(&message.timestamp)
+                .ok()?
+                .with_timezone(&Utc);
+            let (usage, cost_usd) = tracker.usage_and_cost_of(&message, cwd.as_deref());
+            (cost_usd > 0.0 || total_tokens(&usage) > 0).then_some(BlockResponse {
+                timestamp,
+                session_id: message.session_id,
+                model: message.model,
+                usage,
+                cost_usd,
+            })
+        })
+        .collect()
+}
+
+fn rfc3339(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Block being filled while walking the responses
+struct OpenBlock<'a> {
+    block: UsageBlock,
+    start: DateTime<Utc>,
+    last_activity: DateTime<Utc>,
+    sessions: HashSet<&'a str>,
+    models: BTreeSet<&'a str>,
+}
+
+impl<'a> OpenBlock<'a> {
+    fn starting_at(first: DateTime<Utc>) -> Self {
+        let start = first.duration_trunc(Duration::hours(1)).unwrap_or(first);
+        Self {
+            block: UsageBlock {
+                start_time: rfc3339(start),
+                end_time: rfc3339(start + Duration::hours(BLOCK_HOURS)),
+                last_activity_time: String::new(),
+                is_active: false,
+                response_count: 0,
+                session_count: 0,
+                input_tokens: 0,
+                output_tokens: 0,
+                cache_creation_tokens: 0,
+                cache_read_tokens: 0,
+                total_tokens: 0,
+                cost_usd: 0.0,
+                models: Vec::new(),
+                limit_ratio: None,
+                near_limit: false,
+            },
+            start,
+            last_activity: first,
+            sessions: HashSet::new(),
+            models: BTreeSet::new(),
+        }
+    }
+
+    /// Whether `time` falls outside this block
+    fn is_over_at(&self, time: DateTime<Utc>) -> bool {
+        let window = Duration::hours(BLOCK_HOURS);
+        time >= self.start + window || time - self.last_activity >= window
+    }
+
+    fn add(&mut self, response: &'a BlockResponse) {
+        let usage = &response.usage;
+        let block = &mut self.block;
+        block.response_count += 1;
+        block.input_tokens += u64::from(usage.input_tokens.unwrap_or(0));
+        block.output_tokens += u64::from(usage.output_tokens.unwrap_or(0));
+        block.cache_creation_tokens += u64::from(usage.cache_creation_input_tokens.unwrap_or(0));
+        block.cache_read_tokens += u64::from(usage.cache_read_input_tokens.unwrap_or(0));
+        block.total_tokens += total_tokens(usage);
+        block.cost_usd += response.cost_usd;
+        self.last_activity = response.timestamp;
+        self.sessions.insert(&response.session_id);
+        if let Some(model) = response.model.as_deref() {
+            self.models.insert(model);
+        }
+    }
+
+    fn finish(self, now: DateTime<Utc>) -> UsageBlock {
+        UsageBlock {
+            last_activity_time: rfc3339(self.last_activity),
+            is_active: !self.is_over_at(now),
+            session_count: self.sessions.len(),
+            models: self.models.into_iter().map(ToString::to_string).collect(),
+            ..self.block
+        }
+    }
+}
+
+/// Group `responses` (sorted by timestamp) into 5-hour blocks
+fn build_blocks(responses: &[BlockResponse], now: DateTime<Utc>) -> Vec<UsageBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Option<OpenBlock> = None;
+
+    for response in responses {
+        let open = match current.take() {
+            Some(open) if !open.is_over_at(response.timestamp) => open,
+            finished => {
+                blocks.extend(finished.map(|open| open.finish(now)));
+                OpenBlock::starting_at(response.timestamp)
+            }
+        };
+        current.insert(open).add(response);
+    }
+    blocks.extend(current.map(|open| open.finish(now)));
+
+    blocks
+}
+
+/// Responses of a scope, oldest first
+fn scope_responses(scope: &str, path: &str) -> Result<Vec<BlockResponse>, AppError> {
+    let session_files = resolve_scope_session_files(scope, path)?;
+    let mut responses: Vec<BlockResponse> = session_files
+        .par_iter()
+        .flat_map_iter(|path| session_responses(path))
+        .collect();
+    responses.sort_by_key(|response| response.timestamp);
+    Ok(responses)
+}
+
+/// Token count of the largest completed block, the default token limit
+fn largest_completed_block(blocks: &[UsageBlock]) -> Option<u64> {
+    blocks
+        .iter()
+        .filter(|block| !block.is_active)
+        .map(|block| block.total_tokens)
+        .max()
+        .filter(|&max| max > 0)
+}
+
+/// Set `limit_ratio`/`near_limit` against `token_limit`
+fn flag_blocks(blocks: &mut [UsageBlock], token_limit: u64) {
+    for block in blocks {
+        #[allow(clippy::cast_precision_loss)]
+        let ratio = block.total_tokens as f64 / token_limit as f64;
+        block.limit_ratio = Some(ratio);
+        block.near_limit = ratio >= NEAR_LIMIT_RATIO;
+    }
+}
+
+/// Reconstruct 5-hour usage blocks with tokens and cost per block
+///
+/// `scope` is "session", "project" or "global" with `path` pointing at the
+/// session file, project folder or Claude folder; limits apply account-wide,
+/// so "global" is the meaningful one. Blocks reaching 90% of `token_limit`
+/// are flagged. Without a limit, the largest completed block is used.
+#[tauri::command]
+pub async fn get_usage_blocks(
+    scope: String,
+    path: String,
+    token_limit: Option<u64>,
+) -> Result<UsageBlockReport, AppError> {
+    let _timer = OperationTimer::start("get_usage_blocks");
+
+    if token_limit == Some(0) {
+        return Err(AppError::invalid_input("Token limit must be positive"));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let responses = scope_responses(&scope, &path)?;
+        let mut blocks = build_blocks(&responses, Utc::now());
+        let limit_is_estimated = token_limit.is_none();
+        let token_limit = token_limit.or_else(|| largest_completed_block(&blocks));
+        if let Some(limit) = token_limit {
+            flag_blocks(&mut blocks, limit);
+        }
+
+        Ok(UsageBlockReport {
+            scope,
+            token_limit,
+            limit_is_estimated,
+            blocks,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Totals and hourly rates of `responses` (sorted by timestamp)
+fn rate_window(responses: &[BlockResponse]) -> Option<BurnRateWindow> {
+    let first = responses.first()?.timestamp;
+    let last = responses.last()?.timestamp;
+    let total_tokens: u64 = responses.iter().map(|r| total_tokens(&r.usage)).sum();
+    let cost_usd: f64 = responses.iter().map(|r| r.cost_usd).sum();
+    let hours = (last - first).num_minutes().max(MIN_RATE_MINUTES) as f64 / 60.0;
+    Some(BurnRateWindow {
+        start_time: rfc3339(first),
+        last_activity_time: rfc3339(last),
+        response_count: responses.len() as u64,
+        total_tokens,
+        cost_usd,
+        tokens_per_hour: total_tokens as f64 / hours,
+        cost_per_hour: cost_usd / hours,
+    })
+}
+
+/// Responses at or after `start`
+fn responses_since(responses: &[BlockResponse], start: DateTime<Utc>) -> &[BlockResponse] {
+    &responses[responses.partition_point(|r| r.timestamp < start)..]
+}
+
+fn parse_time(time: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Burn rate of `responses` (sorted by timestamp) as of `now`
+fn burn_rate(
+    scope: String,
+    responses: &[BlockResponse],
+    token_limit: Option<u64>,
+    now: DateTime<Utc>,
+) -> BurnRate {
+    let day_start = now.duration_trunc(Duration::days(1)).unwrap_or(now);
+    let today = rate_window(responses_since(responses, day_start));
+
+    let blocks = build_blocks(responses, now);
+    let limit_is_estimated = token_limit.is_none();
+    let token_limit = token_limit.or_else(|| largest_completed_block(&blocks));
+    let mut active_block = blocks.into_iter().last().filter(|block| block.is_active);
+    if let (Some(block), Some(limit)) = (active_block.as_mut(), token_limit) {
+        flag_blocks(std::slice::from_mut(block), limit);
+    }
+
+    let block_bounds = active_block
+        .as_ref()
+        .and_then(|block| Some((parse_time(&block.start_time)?, parse_time(&block.end_time)?)));
+    let block_rate =
+        block_bounds.and_then(|(start, _)| rate_window(responses_since(responses, start)));
+
+    let mut projected_block_tokens = None;
+    let mut projected_limit_time = None;
+    if let (Some(rate), Some((_, end))) = (&block_rate, block_bounds) {
+        let hours_left = (end - now).num_seconds().max(0) as f64 / 3600.0;
+        let projected = rate.total_tokens as f64 + rate.tokens_per_hour * hours_left;
+        projected_block_tokens = Some(projected.round() as u64);
+
+        if let Some(limit) = token_limit.filter(|&limit| projected >= limit as f64) {
+            let hours_to_limit =
+                limit.saturating_sub(rate.total_tokens) as f64 / rate.tokens_per_hour;
+            let limit_time = now + Duration::seconds((hours_to_limit * 3600.0).round() as i64);
+            projected_limit_time = Some(rfc3339(limit_time.min(end)));
+        }
+    }
+
+    BurnRate {
+        scope,
+        generated_at: rfc3339(now),
+        today,
+        active_block,
+        block_rate,
+        token_limit,
+        limit_is_estimated,
+        projected_block_tokens,
+        projected_limit_time,
+    }
+}
+
+/// Tokens and cost per hour for the current UTC day and the active 5-hour
+/// block, with a projection of when the block reaches `token_limit`
+///
+/// Scopes and the default limit are as for `get_usage_blocks`. The block
+/// rate runs from its first to its last response and is assumed to hold
+/// until the block ends.
+#[tauri::command]
+pub async fn get_burn_rate(
+    scope: String,
+    path: String,
+    token_limit: Option<u64>,
+) -> Result<BurnRate, AppError> {
+    let _timer = OperationTimer::start("get_burn_rate");
+
+    if token_limit == Some(0) {
+        return Err(AppError::invalid_input("Token limit must be positive"));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let responses = scope_responses(&scope, &path)?;
+        Ok(burn_rate(scope, &responses, token_limit, Utc::now()))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn output(time: &str, session_id: &str, output_tokens: u32) -> BlockResponse {
+        BlockResponse {
+            timestamp: at(time),
+            session_id: session_id.to_string(),
+            model: Some("claude-sonnet-4-20250514".to_string()),
+            usage: TokenUsage {
+                input_tokens: Some(0),
+                output_tokens: Some(output_tokens),
+                cache_creation_input_tokens: None,
+                cache_read_input_tokens: None,
+                service_tier: None,
+            },
+            cost_usd: 0.01,
+        }
+    }
+
+    #[test]
+    fn test_blocks_start_on_the_hour_and_last_five_hours() {
+        let responses = [
+            output("2025-03-01T09:40:00Z", "s1", 100),
+            output("2025-03-01T13:30:00Z", "s2", 200),
+            // Past 14:00, the end of the first block
+            output("2025-03-01T14:10:00Z", "s2", 300),
+            // More than 5 idle hours later
+            output("2025-03-01T19:20:00Z", "s3", 400),
+        ];
+        let blocks = build_blocks(&responses, at("2025-03-10T00:00:00Z"));
+
+        assert_eq!(
+            blocks
+                .iter()
+                .map(|b| (b.start_time.as_str(), b.end_time.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                ("2025-03-01T09:00:00Z", "2025-03-01T14:00:00Z"),
+                ("2025-03-01T14:00:00Z", "2025-03-01T19:00:00Z"),
+                ("2025-03-01T19:00:00Z", "2025-03-02T00:00:00Z"),
+            ]
+        );
+        assert_eq!(blocks[0].total_tokens, 300);
+        assert_eq!(blocks[0].session_count, 2);
+        assert_eq!(blocks[0].last_activity_time, "2025-03-01T13:30:00Z");
+        assert_eq!(blocks[0].models, ["claude-sonnet-4-20250514"]);
+        assert!(blocks.iter().all(|b| !b.is_active));
+    }
+
+    #[test]
+    fn test_latest_block_is_active_until_it_ends() {
+        let responses = [output("2025-03-01T09:40:00Z", "s1", 100)];
+        assert!(build_blocks(&responses, at("2025-03-01T13:59:00Z"))[0].is_active);
+        assert!(!build_blocks(&responses, at("2025-03-01T14:00:00Z"))[0].is_active);
+    }
+
+    #[test]
+    fn test_flag_blocks_near_limit() {
+        let responses = [
+            output("2025-03-01T09:00:00Z", "s1", 950),
+            output("2025-03-02T09:00:00Z", "s1", 500),
+        ];
+        let mut blocks = build_blocks(&responses, at("2025-03-10T00:00:00Z"));
+        flag_blocks(&mut blocks, 1000);
+        assert!(blocks[0].near_limit);
+        assert!(!blocks[1].near_limit);
+        assert_eq!(blocks[1].limit_ratio, Some(0.5));
+    }
+
+    #[test]
+    fn test_burn_rate_projects_active_block() {
+        let responses = [
+            output("2025-03-01T09:00:00Z", "s1", 50_000),
+            output("2025-03-02T10:10:00Z", "s2", 10_000),
+            output("2025-03-02T11:10:00Z", "s2", 20_000),
+        ];
+        let rate = burn_rate(
+            "global".to_string(),
+            &responses,
+            Some(100_000),
+            at("2025-03-02T11:10:00Z"),
+        );
+
+        let today = rate.today.unwrap();
+        assert_eq!((today.response_count, today.total_tokens), (2, 30_000));
+        assert!((today.tokens_per_hour - 30_000.0).abs() < 1e-9);
+        assert!((today.cost_per_hour - 0.02).abs() < 1e-9);
+
+        let block = rate.active_block.unwrap();
+        assert_eq!(block.start_time, "2025-03-02T10:00:00Z");
+        assert_eq!(block.limit_ratio, Some(0.3));
+        // 30k tokens plus 30k/hour over the 3h50m left
+        assert_eq!(rate.projected_block_tokens, Some(145_000));
+        assert_eq!(
+            rate.projected_limit_time.as_deref(),
+            Some("2025-03-02T13:30:00Z")
+        );
+
+        let rate = burn_rate(
+            "global".to_string(),
+            &responses,
+            None,
+            at("2025-03-02T11:10:00Z"),
+        );
+        assert_eq!(rate.token_limit, Some(50_000));
+        assert!(rate.limit_is_estimated);
+        assert_eq!(
+            rate.projected_limit_time.as_deref(),
+            Some("2025-03-02T11:50:00Z")
+        );
+    }
+
+    #[test]
+    fn test_burn_rate_without_active_block() {
+        let responses = [output("2025-03-01T09:00:00Z", "s1", 500)];
+        let rate = burn_rate(
+            "global".to_string(),
+            &responses,
+            Some(1000),
+            at("2025-03-03T00:00:00Z"),
+        );
+        assert!(rate.today.is_none());
+        assert!(rate.active_block.is_none());
+        assert!(rate.block_rate.is_none());
+        assert!(rate.projected_block_tokens.is_none());
+        assert!(rate.projected_limit_time.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_usage_blocks_default_to_largest_block() {
+        let temp = TempDir::new().unwrap();
+        let project_dir = temp.path().join("-Users-me-demo");
+        fs::create_dir_all(&project_dir).unwrap();
+        let lines = [
+            ("msg_1", "2025-03-01T09:40:00Z", 1000),
+            ("msg_2", "2025-03-02T09:40:00Z", 400),
+        ]
+        .map(|(id, timestamp, output_tokens)| {
+            json!({
+                "uuid": id,
+                "sessionId": "s1",
+                "timestamp": timestamp,
+                "type": "assistant",
+                "message": {
+                    "id": id,
+                    "role": "assistant",
+                    "model": "claude-sonnet-4-20250514",
+                    "content": [{"type": "text", "text": "ok"}],
+                    "usage": {"input_tokens": 0, "output_tokens": output_tokens}
+                }
+            })
+            .to_string()
+        });
+        fs::write(project_dir.join("s1.jsonl"), lines.join("\n")).unwrap();
+        let path = project_dir.to_string_lossy().to_string();
+
+        let report = get_usage_blocks("project".to_string(), path.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(report.token_limit, Some(1000));
+        assert!(report.limit_is_estimated);
+        assert_eq!(report.blocks.len(), 2);
+        assert!(report.blocks[0].near_limit);
+        assert!(!report.blocks[1].near_limit);
+
+        let report = get_usage_blocks("project".to_string(), path.clone(), Some(2000))
+            .await
+            .unwrap();
+        assert!(!report.limit_is_estimated);
+        assert!(report.blocks.iter().all(|b| !b.near_limit));
+
+        assert!(matches!(
+            get_usage_blocks("project".to_string(), path, Some(0)).await,
+            Err(AppError::InvalidInput { .. })
+        ));
+    }
+}
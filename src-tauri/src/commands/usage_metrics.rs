@@ -0,0 +1,257 @@
+//! Telemetry-free local usage metrics
+//!
+//! Records which viewer features are used and how long backend operations
+//! take. Nothing is sent over the network: metrics are kept in memory,
+//! persisted to ~/.claude-history-viewer/usage-metrics.json on exit, and
+//! exported only on demand.
+
+use crate::commands::metadata::get_metadata_folder;
+use crate::errors::AppError;
+use crate::models::{LocalUsageMetrics, LocalUsageReport};
+use chrono::Utc;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+/// Maximum length of a feature name accepted from the frontend
+const MAX_FEATURE_NAME_LENGTH: usize = 64;
+
+/// Distinct features kept before new frontend feature names are rejected
+const MAX_TRACKED_FEATURES: usize = 512;
+
+/// Process-wide metrics registry (loaded lazily from disk)
+static USAGE_METRICS: OnceLock<Mutex<LocalUsageMetrics>> = OnceLock::new();
+
+/// Get the metrics file path (~/.claude-history-viewer/usage-metrics.json;
+/// a per-process temporary file in unit tests, which must not read or write
+/// the user's metrics)
+fn get_usage_metrics_path() -> Result<PathBuf, String> {
+    if cfg!(test) {
+        return Ok(std::env::temp_dir()
+            .join(format!("claude-history-viewer-test-{}", std::process::id()))
+            .join("usage-metrics.json"));
+    }
+    Ok(get_metadata_folder()?.join("usage-metrics.json"))
+}
+
+/// Load persisted metrics, falling back to an empty set
+fn load_usage_metrics() -> LocalUsageMetrics {
+    get_usage_metrics_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_else(|| LocalUsageMetrics::new(Utc::now().to_rfc3339()))
+}
+
+fn registry() -> &'static Mutex<LocalUsageMetrics> {
+    USAGE_METRICS.get_or_init(|| Mutex::new(load_usage_metrics()))
+}
+
+/// Record one use of a feature (best effort, never fails the caller)
+pub fn record_usage(feature: &str, duration_ms: Option<u64>) {
+    if let Ok(mut metrics) = registry().lock() {
+        metrics.record(feature, duration_ms, &Utc::now().to_rfc3339());
+    }
+}
+
+/// Times a backend operation and records it when dropped
+pub struct OperationTimer {
+    operation: &'static str,
+    start: Instant,
+}
+
+impl OperationTimer {
+    pub fn start(operation: &'static str) -> Self {
+        Self {
+            operation,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for OperationTimer {
+    fn drop(&mut self) {
+        record_usage(
+            self.operation,
+            Some(self.start.elapsed().as_millis() as u64),
+        );
+    }
+}
+
+/// Write a JSON value to disk using the temp-file + rename pattern
+pub(crate) fn write_json_atomic<T: serde::Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let temp_path = path.with_extension("json.tmp");
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize JSON: {e}"))?;
+
+    let mut file =
+        fs::File::create(&temp_path).map_err(|e| format!("Failed to create temp file: {e}"))?;
+    file.write_all(content.as_bytes())
+        .map_err(|e| format!("Failed to write temp file: {e}"))?;
+    file.sync_all()
+        .map_err(|e| format!("Failed to sync temp file: {e}"))?;
+
+    fs::rename(&temp_path, path).map_err(|e| format!("Failed to rename temp file: {e}"))
+}
+
+/// Persist the in-memory metrics to disk
+pub fn flush_usage_metrics() -> Result<(), String> {
+    let snapshot = registry()
+        .lock()
+        .map_err(|e| format!("Failed to lock usage metrics: {e}"))?
+        .clone();
+
+    let path = get_usage_metrics_path()?;
+    if let Some(folder) = path.parent() {
+        fs::create_dir_all(folder).map_err(|e| format!("Failed to create metadata folder: {e}"))?;
+    }
+    write_json_atomic(&path, &snapshot)
+}
+
+fn build_report() -> Result<LocalUsageReport, String> {
+    let metrics = registry()
+        .lock()
+        .map_err(|e| format!("Failed to lock usage metrics: {e}"))?;
+    Ok(LocalUsageReport::from_metrics(
+        &metrics,
+        Utc::now().to_rfc3339(),
+    ))
+}
+
+/// Record a frontend feature, unless it would grow the metrics past
+/// `MAX_TRACKED_FEATURES` distinct features
+fn record_frontend_feature(
+    metrics: &mut LocalUsageMetrics,
+    feature: &str,
+    duration_ms: Option<u64>,
+    timestamp: &str,
+) -> Result<(), AppError> {
+    if !metrics.features.contains_key(feature) && metrics.features.len() >= MAX_TRACKED_FEATURES {
+        return Err(AppError::invalid_input(format!(
+            "At most {MAX_TRACKED_FEATURES} distinct features are tracked; reset the usage metrics to record new ones"
+        )));
+    }
+    metrics.record(feature, duration_ms, timestamp);
+    Ok(())
+}
+
+/// Record a frontend feature usage event
+#[tauri::command]
+pub async fn record_feature_usage(
+    feature: String,
+    duration_ms: Option<u64>,
+) -> Result<(), AppError> {
+    let feature = feature.trim();
+    if feature.is_empty() || feature.len() > MAX_FEATURE_NAME_LENGTH {
+        return Err(AppError::invalid_input(format!(
+            "Feature name must be between 1 and {MAX_FEATURE_NAME_LENGTH} characters"
+        )));
+    }
+
+    let mut metrics = registry()
+        .lock()
+        .map_err(|e| format!("Failed to lock usage metrics: {e}"))?;
+    record_frontend_feature(&mut metrics, feature, duration_ms, &Utc::now().to_rfc3339())
+}
+
+/// Get the current local usage report
+#[tauri::command]
+pub async fn get_local_usage_report() -> Result<LocalUsageReport, AppError> {
+    Ok(build_report()?)
+}
+
+/// Export the local usage report to a user-chosen path
+#[tauri::command]
+pub async fn export_local_usage_report(path: String) -> Result<(), AppError> {
+    let target = PathBuf::from(&path);
+    if !target.is_absolute() {
+        return Err(AppError::invalid_input(
+            "Invalid export path: must be an absolute path",
+        ));
+    }
+
+    let report = build_report()?;
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        flush_usage_metrics()?;
+        write_json_atomic(&target, &report)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??)
+}
+
+/// Discard all collected usage metrics
+#[tauri::command]
+pub async fn reset_local_usage_metrics() -> Result<(), AppError> {
+    {
+        let mut metrics = registry()
+            .lock()
+            .map_err(|e| format!("Failed to lock usage metrics: {e}"))?;
+        *metrics = LocalUsageMetrics::new(Utc::now().to_rfc3339());
+    }
+
+    Ok(tauri::async_runtime::spawn_blocking(flush_usage_metrics)
+        .await
+        .map_err(|e| format!("Task join error: {e}"))??)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_feature_usage_rejects_empty_name() {
+        assert!(record_feature_usage("   ".to_string(), None).await.is_err());
+        assert!(record_feature_usage("x".repeat(65), None).await.is_err());
+    }
+
+    #[test]
+    fn test_record_frontend_feature_caps_distinct_features() {
+        let mut metrics = LocalUsageMetrics::new("2025-01-01T00:00:00Z".to_string());
+        let timestamp = "2025-01-02T00:00:00Z";
+        for index in 0..MAX_TRACKED_FEATURES {
+            record_frontend_feature(&mut metrics, &format!("feature-{index}"), None, timestamp)
+                .unwrap();
+        }
+
+        assert!(matches!(
+            record_frontend_feature(&mut metrics, "one-too-many", None, timestamp),
+            Err(AppError::InvalidInput { .. })
+        ));
+        // Known features keep counting
+        record_frontend_feature(&mut metrics, "feature-0", Some(5), timestamp).unwrap();
+        assert_eq!(metrics.features.len(), MAX_TRACKED_FEATURES);
+        assert_eq!(metrics.features["feature-0"].invocations, 2);
+    }
+
+    #[tokio::test]
+    async fn test_operation_timer_records_on_drop() {
+        {
+            let _timer = OperationTimer::start("test_operation_timer");
+        }
+
+        let report = get_local_usage_report().await.unwrap();
+        let entry = report
+            .features
+            .iter()
+            .find(|f| f.feature == "test_operation_timer")
+            .unwrap();
+        assert!(entry.invocations >= 1);
+    }
+
+    #[test]
+    fn test_write_json_atomic() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("report.json");
+        let metrics = LocalUsageMetrics::new("2025-01-01T00:00:00Z".to_string());
+
+        write_json_atomic(&path, &metrics).unwrap();
+
+        assert!(path.exists());
+        assert!(!path.with_extension("json.tmp").exists());
+        let loaded: LocalUsageMetrics =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(loaded, metrics);
+    }
+}
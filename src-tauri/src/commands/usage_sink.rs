@@ -0,0 +1,515 @@
+//! Continuous streaming of usage to `ClickHouse` or Timeplus
+//!
+//! With the `usageSink` setting enabled, a background task follows every
+//! session file of the Claude folder and posts the rows of newly appended
+//! entries as JSON lines, with the columns of the `usage` and `messages`
+//! tables of `export_warehouse`. Only entries written after the sink started
+//! are sent. Undelivered rows are retried on the next poll; beyond
+//! `MAX_PENDING_ROWS` per table the oldest are dropped.
+//!
+//! `ClickHouse` receives `INSERT INTO <table> FORMAT JSONEachRow` through its
+//! HTTP interface; Timeplus receives the rows through its stream ingest API.
+
+use crate::commands::export::{message_rows, session_project_name};
+use crate::commands::session::TailReader;
+use crate::commands::stats::{parse_raw_log_entry_simd, ResponseUsageTracker};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{ClaudeMessage, UsageSinkSettings, UsageSinkStatus};
+use crate::utils::collect_session_files;
+use chrono::Utc;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, PoisonError, RwLock};
+use std::time::Duration;
+
+/// Interval between two scans of the session files
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Rows kept per table while the server is unreachable
+const MAX_PENDING_ROWS: usize = 50_000;
+
+/// Rows posted in one request
+const BATCH_ROWS: usize = 5_000;
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+
+static SINK_SETTINGS: RwLock<Option<UsageSinkSettings>> = RwLock::new(None);
+
+static SINK_STATE: OnceLock<Mutex<SinkState>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SinkKind {
+    ClickHouse,
+    Timeplus,
+}
+
+impl SinkKind {
+    fn parse(kind: &str) -> Result<Self, String> {
+        match kind {
+            "clickhouse" => Ok(Self::ClickHouse),
+            "timeplus" => Ok(Self::Timeplus),
+            other => Err(format!("Unknown usage sink: {other}")),
+        }
+    }
+}
+
+/// Position in one followed session file
+struct FileCursor {
+    reader: TailReader,
+    tracker: ResponseUsageTracker,
+    project_name: Option<String>,
+}
+
+/// Followed files and rows waiting for delivery
+#[derive(Default)]
+struct SinkState {
+    projects_dir: Option<PathBuf>, // Folder the cursors belong to
+    cursors: HashMap<PathBuf, FileCursor>,
+    pending_usage: VecDeque<String>,
+    pending_messages: VecDeque<String>,
+    status: UsageSinkStatus,
+}
+
+fn sink_state() -> &'static Mutex<SinkState> {
+    SINK_STATE.get_or_init(Mutex::default)
+}
+
+/// Whether `name` is a plain identifier, optionally prefixed by a database
+fn is_table_name(name: &str) -> bool {
+    let parts: Vec<&str> = name.split('.').collect();
+    parts.len() <= 2
+        && parts.iter().all(|part| {
+            part.chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+}
+
+/// Check the `usageSink` setting
+pub fn validate_usage_sink_settings(settings: Option<&UsageSinkSettings>) -> Result<(), String> {
+    let Some(settings) = settings else {
+        return Ok(());
+    };
+    SinkKind::parse(&settings.kind)?;
+    if !settings.endpoint.starts_with("http://") && !settings.endpoint.starts_with("https://") {
+        return Err("The usage sink endpoint must be an http(s) URL".to_string());
+    }
+    for table in std::iter::once(&settings.usage_table).chain(&settings.messages_table) {
+        if !is_table_name(table) {
+            return Err(format!("Invalid usage sink table: {table}"));
+        }
+    }
+    Ok(())
+}
+
+/// Apply the `usageSink` setting
+pub fn set_usage_sink_settings(settings: Option<UsageSinkSettings>) {
+    if let Ok(mut current) = SINK_SETTINGS.write() {
+        *current = settings;
+    }
+}
+
+fn enabled_settings() -> Option<UsageSinkSettings> {
+    SINK_SETTINGS
+        .read()
+        .ok()
+        .and_then(|settings| settings.clone())
+        .filter(|settings| settings.enabled)
+}
+
+fn projects_directory(settings: &UsageSinkSettings) -> Result<PathBuf, String> {
+    let claude_path = match &settings.claude_path {
+        Some(path) => PathBuf::from(path),
+        None => dirs::home_dir()
+            .ok_or("Could not find home directory")?
+            .join(".claude"),
+    };
+    Ok(claude_path.join("projects"))
+}
+
+/// Append `row` to `queue`, dropping the oldest row when full
+fn enqueue(queue: &mut VecDeque<String>, row: String, dropped: &mut u64) {
+    if queue.len() >= MAX_PENDING_ROWS {
+        queue.pop_front();
+        *dropped += 1;
+    }
+    queue.push_back(row);
+}
+
+impl SinkState {
+    /// Queue the rows of the entries appended to the files of `projects_dir`
+    ///
+    /// Files already present when the folder is first scanned are followed
+    /// from their current end; files created later from their start.
+    fn collect(&mut self, projects_dir: &Path, include_messages: bool) -> Result<(), String> {
+        let seeding = self.projects_dir.as_deref() != Some(projects_dir);
+        if seeding {
+            self.projects_dir = Some(projects_dir.to_path_buf());
+            self.cursors.clear();
+        }
+
+        let session_files = collect_session_files(projects_dir)?;
+        let present: HashSet<&PathBuf> = session_files.iter().map(|(_, path)| path).collect();
+        self.cursors.retain(|path, _| present.contains(path));
+
+        for (_, path) in &session_files {
+            if !self.cursors.contains_key(path) {
+                let reader = if seeding {
+                    match TailReader::at_end(path) {
+                        Ok(reader) => reader,
+                        Err(_) => continue,
+                    }
+                } else {
+                    TailReader::default()
+                };
+                let cursor = FileCursor {
+                    reader,
+                    tracker: ResponseUsageTracker::default(),
+                    project_name: session_project_name(path),
+                };
+                self.cursors.insert(path.clone(), cursor);
+                if seeding {
+                    continue;
+                }
+            }
+            let Some(cursor) = self.cursors.get_mut(path) else {
+                continue;
+            };
+            let Ok(new) = cursor.reader.read_new_lines(path) else {
+                continue;
+            };
+
+            for line in new.lines {
+                let mut bytes = line.into_bytes();
+                let Some(mut entry) = parse_raw_log_entry_simd(&mut bytes) else {
+                    continue;
+                };
+                let cwd = entry.cwd.take();
+                let Ok(message) = ClaudeMessage::try_from(entry) else {
+                    continue;
+                };
+                let (usage_row, message_row) = message_rows(
+                    &message,
+                    cwd.as_deref(),
+                    cursor.project_name.as_deref(),
+                    &mut cursor.tracker,
+                );
+                let dropped = &mut self.status.dropped_rows;
+                if let Some(row) = usage_row {
+                    enqueue(&mut self.pending_usage, row.to_string(), dropped);
+                }
+                if include_messages {
+                    enqueue(&mut self.pending_messages, message_row.to_string(), dropped);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// HTTP request delivering rows to one table
+#[derive(Debug, PartialEq)]
+struct SinkRequest {
+    url: String,
+    query: Vec<(&'static str, String)>,
+    headers: Vec<(&'static str, String)>,
+}
+
+fn sink_request(settings: &UsageSinkSettings, kind: SinkKind, table: &str) -> SinkRequest {
+    let endpoint = settings.endpoint.trim_end_matches('/');
+    match kind {
+        SinkKind::ClickHouse => {
+            let mut headers = Vec::new();
+            if let Some(username) = &settings.username {
+                headers.push(("X-ClickHouse-User", username.clone()));
+            }
+            if let Some(password) = &settings.password {
+                headers.push(("X-ClickHouse-Key", password.clone()));
+            }
+            SinkRequest {
+                url: format!("{endpoint}/"),
+                query: vec![
+                    ("query", format!("INSERT INTO {table} FORMAT JSONEachRow")),
+                    ("date_time_input_format", "best_effort".to_string()),
+                    ("input_format_skip_unknown_fields", "1".to_string()),
+                ],
+                headers,
+            }
+        }
+        SinkKind::Timeplus => SinkRequest {
+            url: format!("{endpoint}/api/v1beta2/streams/{table}/ingest"),
+            query: vec![("format", "streaming".to_string())],
+            headers: settings
+                .password
+                .iter()
+                .map(|key| ("X-Api-Key", key.clone()))
+                .collect(),
+        },
+    }
+}
+
+/// Which pending queue a delivery drains
+#[derive(Clone, Copy)]
+enum SinkTable {
+    Usage,
+    Messages,
+}
+
+fn pending_queue(state: &mut SinkState, table: SinkTable) -> &mut VecDeque<String> {
+    match table {
+        SinkTable::Usage => &mut state.pending_usage,
+        SinkTable::Messages => &mut state.pending_messages,
+    }
+}
+
+/// Post the pending rows of `table` in batches until the queue is empty
+async fn deliver(
+    client: &tauri_plugin_http::reqwest::Client,
+    settings: &UsageSinkSettings,
+    kind: SinkKind,
+    table: SinkTable,
+    table_name: &str,
+) -> Result<(), String> {
+    let request = sink_request(settings, kind, table_name);
+    loop {
+        let batch: Vec<String> = {
+            let mut state = sink_state().lock().unwrap_or_else(PoisonError::into_inner);
+            let queue = pending_queue(&mut state, table);
+            queue.iter().take(BATCH_ROWS).cloned().collect()
+        };
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let mut builder = client
+            .post(&request.url)
+            .query(&request.query)
+            .header("Content-Type", "application/x-ndjson");
+        for (name, value) in &request.headers {
+            builder = builder.header(*name, value);
+        }
+        builder
+            .body(batch.join("\n"))
+            .send()
+            .await
+            .and_then(tauri_plugin_http::reqwest::Response::error_for_status)
+            .map_err(|e| format!("Failed to send rows to {table_name}: {e}"))?;
+
+        let mut state = sink_state().lock().unwrap_or_else(PoisonError::into_inner);
+        let queue = pending_queue(&mut state, table);
+        queue.drain(..batch.len().min(queue.len()));
+        let sent = batch.len() as u64;
+        match table {
+            SinkTable::Usage => state.status.usage_rows_sent += sent,
+            SinkTable::Messages => state.status.message_rows_sent += sent,
+        }
+        state.status.last_sent_at = Some(Utc::now().to_rfc3339());
+        state.status.last_error = None;
+    }
+}
+
+/// Queue the newly appended rows and deliver everything pending
+async fn poll_usage_sink() -> Result<(), String> {
+    let Some(settings) = enabled_settings() else {
+        *sink_state().lock().unwrap_or_else(PoisonError::into_inner) = SinkState::default();
+        return Ok(());
+    };
+    let kind = SinkKind::parse(&settings.kind)?;
+
+    let projects_dir = projects_directory(&settings)?;
+    let include_messages = settings.messages_table.is_some();
+    tauri::async_runtime::spawn_blocking(move || {
+        sink_state()
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .collect(&projects_dir, include_messages)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+
+    let client = tauri_plugin_http::reqwest::Client::builder()
+        .timeout(SEND_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+    deliver(
+        &client,
+        &settings,
+        kind,
+        SinkTable::Usage,
+        &settings.usage_table,
+    )
+    .await?;
+    if let Some(messages_table) = &settings.messages_table {
+        deliver(
+            &client,
+            &settings,
+            kind,
+            SinkTable::Messages,
+            messages_table,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Start streaming new usage to the configured sink in the background
+pub fn start_usage_sink() {
+    tauri::async_runtime::spawn(async {
+        loop {
+            if let Err(e) = poll_usage_sink().await {
+                sink_state()
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .status
+                    .last_error = Some(e);
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// Delivery state of the usage sink
+#[tauri::command]
+pub async fn get_usage_sink_status() -> Result<UsageSinkStatus, AppError> {
+    let _timer = OperationTimer::start("get_usage_sink_status");
+
+    let settings = enabled_settings();
+    let state = sink_state().lock().unwrap_or_else(PoisonError::into_inner);
+    Ok(UsageSinkStatus {
+        enabled: settings.is_some(),
+        kind: settings.map(|settings| settings.kind),
+        pending_rows: state.pending_usage.len() + state.pending_messages.len(),
+        ..state.status.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn settings(kind: &str) -> UsageSinkSettings {
+        UsageSinkSettings {
+            enabled: true,
+            kind: kind.to_string(),
+            endpoint: "http://localhost:8123/".to_string(),
+            usage_table: "claude.usage".to_string(),
+            messages_table: Some("claude.messages".to_string()),
+            username: Some("viewer".to_string()),
+            password: Some("secret".to_string()),
+            claude_path: None,
+        }
+    }
+
+    fn response_line(id: &str, output_tokens: u32) -> String {
+        json!({
+            "uuid": id,
+            "sessionId": "s1",
+            "timestamp": "2025-03-01T09:40:00Z",
+            "type": "assistant",
+            "message": {
+                "id": id,
+                "role": "assistant",
+                "model": "claude-sonnet-4-20250514",
+                "content": [{"type": "text", "text": "ok"}],
+                "usage": {"input_tokens": 10, "output_tokens": output_tokens}
+            }
+        })
+        .to_string()
+            + "\n"
+    }
+
+    fn append(path: &Path, content: &str) {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_collect_queues_only_appended_entries() {
+        let temp = TempDir::new().unwrap();
+        let project_dir = temp.path().join("-Users-me-demo");
+        fs::create_dir_all(&project_dir).unwrap();
+        let existing = project_dir.join("s1.jsonl");
+        append(&existing, &response_line("msg_1", 100));
+
+        let mut state = SinkState::default();
+        state.collect(temp.path(), true).unwrap();
+        assert!(state.pending_usage.is_empty());
+
+        append(&existing, &response_line("msg_2", 200));
+        // Same response written again for its next content block
+        append(&existing, &response_line("msg_2", 200));
+        append(&project_dir.join("s2.jsonl"), &response_line("msg_3", 300));
+        state.collect(temp.path(), true).unwrap();
+
+        let mut usage: Vec<Value> = state
+            .pending_usage
+            .iter()
+            .map(|row| serde_json::from_str(row).unwrap())
+            .collect();
+        usage.sort_by_key(|row| row["message_uuid"].as_str().unwrap().to_string());
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0]["message_uuid"], "msg_2");
+        assert_eq!(usage[0]["output_tokens"], 200);
+        assert_eq!(usage[0]["project_name"], "demo");
+        assert_eq!(usage[1]["message_uuid"], "msg_3");
+        assert_eq!(state.pending_messages.len(), 3);
+
+        state.collect(temp.path(), true).unwrap();
+        assert_eq!(state.pending_usage.len(), 2);
+    }
+
+    #[test]
+    fn test_clickhouse_request_inserts_json_rows() {
+        let request = sink_request(
+            &settings("clickhouse"),
+            SinkKind::ClickHouse,
+            "claude.usage",
+        );
+        assert_eq!(request.url, "http://localhost:8123/");
+        assert_eq!(
+            request.query[0],
+            (
+                "query",
+                "INSERT INTO claude.usage FORMAT JSONEachRow".to_string()
+            )
+        );
+        assert_eq!(
+            request.headers,
+            [
+                ("X-ClickHouse-User", "viewer".to_string()),
+                ("X-ClickHouse-Key", "secret".to_string()),
+            ]
+        );
+
+        let request = sink_request(&settings("timeplus"), SinkKind::Timeplus, "usage");
+        assert_eq!(
+            request.url,
+            "http://localhost:8123/api/v1beta2/streams/usage/ingest"
+        );
+        assert_eq!(request.headers, [("X-Api-Key", "secret".to_string())]);
+    }
+
+    #[test]
+    fn test_validate_usage_sink_settings() {
+        assert!(validate_usage_sink_settings(Some(&settings("clickhouse"))).is_ok());
+        assert!(validate_usage_sink_settings(Some(&settings("kafka"))).is_err());
+
+        let mut invalid = settings("clickhouse");
+        invalid.usage_table = "usage; DROP TABLE x".to_string();
+        assert!(validate_usage_sink_settings(Some(&invalid)).is_err());
+
+        let mut invalid = settings("timeplus");
+        invalid.endpoint = "localhost:8000".to_string();
+        assert!(validate_usage_sink_settings(Some(&invalid)).is_err());
+    }
+}
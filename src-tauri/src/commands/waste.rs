@@ -0,0 +1,352 @@
+//! "Wasted tokens" estimator
+//!
+//! Attributes tokens to four sources of waste — failing retry loops, other
+//! failed tool calls, responses truncated at `max_tokens` and edits that were
+//! later reverted — and rolls them up into a per-project estimate.
+
+use crate::commands::retry_loops::{
+    build_loop, extract_tool_calls, find_loop_runs, total_tokens, truncate_chars, ToolCall,
+};
+use crate::commands::stats::{read_raw_log_entries, resolve_scope_session_files};
+use crate::commands::usage_metrics::OperationTimer;
+use crate::errors::AppError;
+use crate::models::{RawLogEntry, WasteCategory, WasteExample, WasteReport};
+use crate::pricing::estimate_cost_usd;
+use crate::utils::{extract_project_name, file_name_string};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Maximum number of examples kept per category
+const MAX_EXAMPLES_PER_CATEGORY: usize = 5;
+
+const CATEGORY_RETRY_LOOPS: &str = "retry_loops";
+const CATEGORY_TOOL_ERRORS: &str = "tool_errors";
+const CATEGORY_TRUNCATION: &str = "max_tokens_truncation";
+const CATEGORY_REVERTED_EDITS: &str = "reverted_edits";
+
+/// Waste found in a single session
+#[derive(Default)]
+struct SessionWaste {
+    total_tokens: u64,
+    total_cost_usd: f64,
+    findings: Vec<(&'static str, WasteExample)>,
+}
+
+fn example(
+    session_id: &str,
+    call: &ToolCall,
+    description: String,
+    wasted_tokens: u64,
+    wasted_cost_usd: f64,
+) -> WasteExample {
+    WasteExample {
+        session_id: session_id.to_string(),
+        message_uuid: call.message_uuid.clone(),
+        timestamp: call.timestamp.clone(),
+        description,
+        wasted_tokens,
+        wasted_cost_usd,
+    }
+}
+
+/// Successful edits that a later edit undid exactly (old/new strings swapped)
+fn find_reverted_edits(calls: &[ToolCall]) -> Vec<(usize, usize)> {
+    let mut edits: HashMap<(&str, &str, &str), usize> = HashMap::new();
+    let mut reverted = Vec::new();
+
+    for (idx, call) in calls.iter().enumerate() {
+        if call.name != "Edit" || call.failed {
+            continue;
+        }
+        let field = |name: &str| call.input.get(name).and_then(|v| v.as_str());
+        let (Some(file_path), Some(old_string), Some(new_string)) =
+            (field("file_path"), field("old_string"), field("new_string"))
+        else {
+            continue;
+        };
+
+        if let Some(original) = edits.remove(&(file_path, new_string, old_string)) {
+            reverted.push((original, idx));
+        } else {
+            edits.insert((file_path, old_string, new_string), idx);
+        }
+    }
+
+    reverted
+}
+
+fn analyze_session(session_path: &Path) -> SessionWaste {
+    let entries: Vec<RawLogEntry> = read_raw_log_entries(session_path);
+    let session_id = entries
+        .iter()
+        .find_map(|e| e.session_id.clone())
+        .or_else(|| {
+            session_path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "unknown-session".to_string());
+
+    let mut waste = SessionWaste::default();
+
+    // Session totals and max_tokens truncations (one count per response id)
+    let mut counted_responses: HashSet<&str> = HashSet::new();
+    for (line_num, entry) in entries.iter().enumerate() {
+        let Some(message) = &entry.message else {
+            continue;
+        };
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+        if message
+            .id
+            .as_deref()
+            .is_some_and(|id| !counted_responses.insert(id))
+        {
+            continue;
+        }
+
+        let tokens = total_tokens(usage);
+        let cost = estimate_cost_usd(message.model.as_deref(), entry.cwd.as_deref(), usage);
+        waste.total_tokens += tokens;
+        waste.total_cost_usd += cost;
+
+        if message.stop_reason.as_deref() == Some("max_tokens") {
+            waste.findings.push((
+                CATEGORY_TRUNCATION,
+                WasteExample {
+                    session_id: session_id.clone(),
+                    message_uuid: entry
+                        .uuid
+                        .clone()
+                        .unwrap_or_else(|| format!("line-{}", line_num + 1)),
+                    timestamp: entry.timestamp.clone().unwrap_or_default(),
+                    description: "Response cut off at max_tokens".to_string(),
+                    wasted_tokens: tokens,
+                    wasted_cost_usd: cost,
+                },
+            ));
+        }
+    }
+
+    let calls = extract_tool_calls(&entries);
+
+    // Retry loops; their failed calls are not counted again as tool errors
+    let mut in_loop = vec![false; calls.len()];
+    for run in find_loop_runs(&calls) {
+        in_loop[run.clone()]
+            .iter_mut()
+            .for_each(|flag| *flag = true);
+        let retry_loop = build_loop(&calls[run.clone()]);
+        let description = format!(
+            "{} repeated {} times ({} failed)",
+            retry_loop.tool_name, retry_loop.attempts, retry_loop.failed_attempts
+        );
+        waste.findings.push((
+            CATEGORY_RETRY_LOOPS,
+            example(
+                &session_id,
+                &calls[run.start],
+                description,
+                retry_loop.wasted_tokens,
+                retry_loop.wasted_cost_usd,
+            ),
+        ));
+    }
+
+    for (call, _) in calls
+        .iter()
+        .zip(&in_loop)
+        .filter(|(call, in_loop)| call.failed && !**in_loop)
+    {
+        let description = match &call.error {
+            Some(error) => format!("{} failed: {}", call.name, truncate_chars(error, 80)),
+            None => format!("{} failed", call.name),
+        };
+        waste.findings.push((
+            CATEGORY_TOOL_ERRORS,
+            example(&session_id, call, description, call.tokens, call.cost_usd),
+        ));
+    }
+
+    for (original, revert) in find_reverted_edits(&calls) {
+        let (original, revert) = (&calls[original], &calls[revert]);
+        let file_path = original
+            .input
+            .get("file_path")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        waste.findings.push((
+            CATEGORY_REVERTED_EDITS,
+            example(
+                &session_id,
+                original,
+                format!("Edit to {file_path} was reverted"),
+                original.tokens + revert.tokens,
+                original.cost_usd + revert.cost_usd,
+            ),
+        ));
+    }
+
+    waste
+}
+
+fn build_waste_report(project_name: String, sessions: Vec<SessionWaste>) -> WasteReport {
+    let session_count = sessions.len();
+    let mut total_tokens = 0u64;
+    let mut total_cost_usd = 0.0;
+    let mut by_category: HashMap<&'static str, Vec<WasteExample>> = HashMap::new();
+
+    for session in sessions {
+        total_tokens += session.total_tokens;
+        total_cost_usd += session.total_cost_usd;
+        for (category, finding) in session.findings {
+            by_category.entry(category).or_default().push(finding);
+        }
+    }
+
+    let mut categories: Vec<WasteCategory> = by_category
+        .into_iter()
+        .map(|(category, mut findings)| {
+            findings.sort_by(|a, b| {
+                b.wasted_tokens
+                    .cmp(&a.wasted_tokens)
+                    .then_with(|| a.timestamp.cmp(&b.timestamp))
+            });
+            let occurrences = findings.len();
+            let wasted_tokens = findings.iter().map(|f| f.wasted_tokens).sum();
+            let wasted_cost_usd = findings.iter().map(|f| f.wasted_cost_usd).sum();
+            findings.truncate(MAX_EXAMPLES_PER_CATEGORY);
+
+            WasteCategory {
+                category: category.to_string(),
+                occurrences,
+                wasted_tokens,
+                wasted_cost_usd,
+                examples: findings,
+            }
+        })
+        .collect();
+    categories.sort_by(|a, b| {
+        b.wasted_tokens
+            .cmp(&a.wasted_tokens)
+            .then_with(|| a.category.cmp(&b.category))
+    });
+
+    let wasted_tokens: u64 = categories.iter().map(|c| c.wasted_tokens).sum();
+    let wasted_cost_usd: f64 = categories.iter().map(|c| c.wasted_cost_usd).sum();
+
+    WasteReport {
+        project_name,
+        session_count,
+        total_tokens,
+        total_cost_usd,
+        wasted_tokens,
+        wasted_cost_usd,
+        wasted_share: if total_tokens > 0 {
+            wasted_tokens as f64 / total_tokens as f64
+        } else {
+            0.0
+        },
+        categories,
+    }
+}
+
+/// Estimate wasted tokens and cost for a project, with contributing examples
+#[tauri::command]
+pub async fn get_wasted_token_estimate(project_path: String) -> Result<WasteReport, AppError> {
+    let _timer = OperationTimer::start("get_wasted_token_estimate");
+    let project_name = file_name_string(Path::new(&project_path))
+        .map(|name| extract_project_name(&name))
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    let session_files = resolve_scope_session_files("project", &project_path)?;
+    let sessions: Vec<SessionWaste> = session_files
+        .par_iter()
+        .map(|path| analyze_session(path))
+        .collect();
+
+    Ok(build_waste_report(project_name, sessions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{response_line, tool_result_line, tool_use_block, write_jsonl};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_get_wasted_token_estimate_categories() {
+        let temp = TempDir::new().unwrap();
+        let edit = json!({"file_path": "/src/a.rs", "old_string": "x", "new_string": "y"});
+        let revert = json!({"file_path": "/src/a.rs", "old_string": "y", "new_string": "x"});
+        let mut truncated = response_line(
+            "msg_trunc",
+            "2025-01-01T00:01:00Z",
+            json!([{"type": "text", "text": "..."}]),
+            500,
+            4000,
+        );
+        truncated["message"]["stop_reason"] = json!("max_tokens");
+
+        let time = |n: usize| format!("2025-01-01T00:00:{n:02}Z");
+        let assistant_tool_use = |n: usize, name: &str, input: serde_json::Value| {
+            let block = tool_use_block(&format!("toolu_{n}"), name, input);
+            response_line(&format!("msg_{n}"), &time(n), json!([block]), 1000, 0)
+        };
+        let tool_result = |n: usize, is_error: bool| {
+            tool_result_line(&time(n), &format!("toolu_{n}"), "boom", is_error)
+        };
+
+        write_jsonl(
+            &temp.path().join("s1.jsonl"),
+            &[
+                assistant_tool_use(1, "Bash", json!({"command": "make"})),
+                tool_result(1, true),
+                assistant_tool_use(2, "Bash", json!({"command": "make"})),
+                tool_result(2, true),
+                assistant_tool_use(3, "Bash", json!({"command": "make"})),
+                tool_result(3, true),
+                assistant_tool_use(4, "Read", json!({"file_path": "/missing.rs"})),
+                tool_result(4, true),
+                assistant_tool_use(5, "Edit", edit),
+                tool_result(5, false),
+                assistant_tool_use(6, "Edit", revert),
+                tool_result(6, false),
+                truncated,
+            ],
+        );
+
+        let report = get_wasted_token_estimate(temp.path().to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        let category = |name: &str| {
+            report
+                .categories
+                .iter()
+                .find(|c| c.category == name)
+                .unwrap_or_else(|| panic!("missing category {name}"))
+        };
+        assert_eq!(report.session_count, 1);
+        assert_eq!(report.total_tokens, 10_500);
+        assert_eq!(category(CATEGORY_RETRY_LOOPS).wasted_tokens, 2000);
+        assert_eq!(category(CATEGORY_TOOL_ERRORS).occurrences, 1);
+        assert_eq!(category(CATEGORY_TOOL_ERRORS).wasted_tokens, 1000);
+        assert_eq!(category(CATEGORY_REVERTED_EDITS).wasted_tokens, 2000);
+        assert_eq!(category(CATEGORY_TRUNCATION).wasted_tokens, 4500);
+        assert_eq!(report.wasted_tokens, 9500);
+        assert_eq!(report.categories[0].category, CATEGORY_TRUNCATION);
+        assert!(report.wasted_cost_usd > 0.0);
+    }
+
+    #[test]
+    fn test_build_waste_report_empty() {
+        let report = build_waste_report("empty".to_string(), Vec::new());
+        assert_eq!(report.total_tokens, 0);
+        assert!(report.categories.is_empty());
+        assert!(report.wasted_share.abs() < f64::EPSILON);
+    }
+}
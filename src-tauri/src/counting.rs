@@ -0,0 +1,111 @@
+//! Rules deciding which log entries count as messages
+//!
+//! Session lists, stats and exports report a message count adjusted by these
+//! rules next to the raw number of entries, so the numbers agree wherever they
+//! are shown. Progress, system and meta entries are excluded by default;
+//! sidechain (sub-agent) entries can be excluded as well via the
+//! `countExclusions` setting.
+
+use crate::models::RawLogEntry;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Entry types reported as system noise (besides `progress`)
+const SYSTEM_ENTRY_TYPES: [&str; 3] = ["system", "queue-operation", "file-history-snapshot"];
+
+/// Set of entry categories excluded from message counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountRules(u8);
+
+impl CountRules {
+    pub const PROGRESS: Self = Self(1);
+    pub const SYSTEM: Self = Self(1 << 1);
+    pub const META: Self = Self(1 << 2);
+    pub const SIDECHAIN: Self = Self(1 << 3);
+
+    /// Exclusions applied when the setting is unset
+    pub const DEFAULT: Self = Self(Self::PROGRESS.0 | Self::SYSTEM.0 | Self::META.0);
+
+    /// Parse category names ("progress", "system", "meta", "sidechain");
+    /// unknown names are ignored
+    pub fn from_names(names: &[String]) -> Self {
+        let bits = names.iter().fold(0, |bits, name| {
+            bits | match name.as_str() {
+                "progress" => Self::PROGRESS.0,
+                "system" => Self::SYSTEM.0,
+                "meta" => Self::META.0,
+                "sidechain" => Self::SIDECHAIN.0,
+                _ => 0,
+            }
+        });
+        Self(bits)
+    }
+
+    pub fn excludes(self, category: Self) -> bool {
+        self.0 & category.0 != 0
+    }
+
+    /// Stable representation, stored with cached counts
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Whether a (non-summary) entry counts as a message
+    pub fn counts(self, message_type: &str, is_meta: bool, is_sidechain: bool) -> bool {
+        !((message_type == "progress" && self.excludes(Self::PROGRESS))
+            || (SYSTEM_ENTRY_TYPES.contains(&message_type) && self.excludes(Self::SYSTEM))
+            || (is_meta && self.excludes(Self::META))
+            || (is_sidechain && self.excludes(Self::SIDECHAIN)))
+    }
+
+    /// Whether a parsed (non-summary) log entry counts as a message
+    pub fn counts_entry(self, entry: &RawLogEntry) -> bool {
+        self.counts(
+            &entry.message_type,
+            entry.is_meta.unwrap_or(false),
+            entry.is_sidechain.unwrap_or(false),
+        )
+    }
+}
+
+impl Default for CountRules {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+static COUNT_RULES: AtomicU8 = AtomicU8::new(CountRules::DEFAULT.0);
+
+/// Apply the `countExclusions` setting (defaults when unset)
+pub fn set_count_exclusions(exclusions: Option<&[String]>) {
+    let rules = exclusions.map_or(CountRules::DEFAULT, CountRules::from_names);
+    COUNT_RULES.store(rules.0, Ordering::Relaxed);
+}
+
+/// Counting rules currently in effect
+pub fn count_rules() -> CountRules {
+    CountRules(COUNT_RULES.load(Ordering::Relaxed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_rules_exclude_noise_but_keep_sidechains() {
+        let rules = CountRules::DEFAULT;
+        assert!(rules.counts("user", false, false));
+        assert!(rules.counts("assistant", false, true));
+        assert!(!rules.counts("progress", false, false));
+        assert!(!rules.counts("file-history-snapshot", false, false));
+        assert!(!rules.counts("user", true, false));
+    }
+
+    #[test]
+    fn test_rules_from_names() {
+        let rules = CountRules::from_names(&["sidechain".to_string(), "bogus".to_string()]);
+        assert!(!rules.counts("assistant", false, true));
+        assert!(rules.counts("progress", false, false));
+        assert!(rules.counts("user", true, false));
+        assert_eq!(CountRules::from_names(&[]).bits(), 0);
+    }
+}
@@ -0,0 +1,308 @@
+//! User-defined derived session fields
+//!
+//! The `derivedFields` setting lists jq expressions that are evaluated
+//! against the raw entries of each session file (an array of its parsed
+//! JSONL lines). The first value an expression produces is returned in
+//! `ClaudeSession::derived_fields` under the field name, so power users can
+//! add custom columns without waiting for a release. Expressions that fail or
+//! produce no value leave the field out for that session.
+
+use crate::freshness::FileStamp;
+use crate::io_limit::low_memory;
+use crate::models::{ClaudeSession, DerivedField};
+use crate::utils::long_path;
+use jaq_core::load::{self, Arena, File, Loader};
+use jaq_core::{compile, Compiler, Ctx, Native, RcIter};
+use jaq_json::Val;
+use rayon::prelude::*;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::{Mutex, OnceLock, RwLock};
+
+type Filter = jaq_core::Filter<Native<Val>>;
+
+/// Characters of the offending source shown in a syntax error
+const ERROR_SNIPPET_CHARS: usize = 20;
+
+static DERIVED_FIELDS: RwLock<Vec<DerivedField>> = RwLock::new(Vec::new());
+
+/// Values computed per session file, with the stamp and fields they were
+/// computed for
+type ComputedFields = (FileStamp, Vec<DerivedField>, BTreeMap<String, Value>);
+
+static COMPUTED: OnceLock<Mutex<HashMap<PathBuf, ComputedFields>>> = OnceLock::new();
+
+thread_local! {
+    /// Compiled filters keyed by expression (values are not `Send`)
+    static FILTERS: RefCell<HashMap<String, Rc<Filter>>> = RefCell::new(HashMap::new());
+}
+
+/// Apply the `derivedFields` setting
+pub fn set_derived_fields(fields: Vec<DerivedField>) {
+    if let Ok(mut current) = DERIVED_FIELDS.write() {
+        *current = fields;
+    }
+}
+
+/// Derived fields currently configured
+pub fn derived_fields() -> Vec<DerivedField> {
+    DERIVED_FIELDS
+        .read()
+        .map(|fields| fields.clone())
+        .unwrap_or_default()
+}
+
+fn snippet(source: &str) -> String {
+    let snippet: String = source.chars().take(ERROR_SNIPPET_CHARS).collect();
+    if snippet.is_empty() {
+        "end of expression".to_string()
+    } else {
+        format!("`{snippet}`")
+    }
+}
+
+fn load_error_message(error: load::Error<&str>) -> String {
+    let messages: Vec<String> = match error {
+        load::Error::Io(errors) => errors
+            .into_iter()
+            .map(|(path, message)| format!("cannot load {path}: {message}"))
+            .collect(),
+        load::Error::Lex(errors) => errors
+            .into_iter()
+            .map(|(expected, found)| {
+                format!("expected {} at {}", expected.as_str(), snippet(found))
+            })
+            .collect(),
+        load::Error::Parse(errors) => errors
+            .into_iter()
+            .map(|(expected, found)| {
+                format!("expected {} at {}", expected.as_str(), snippet(found))
+            })
+            .collect(),
+    };
+    messages.join("; ")
+}
+
+fn compile_error_message(errors: Vec<compile::Error<&str>>) -> String {
+    let messages: Vec<String> = errors
+        .into_iter()
+        .map(|(name, undefined)| format!("undefined {} {name}", undefined.as_str()))
+        .collect();
+    messages.join("; ")
+}
+
+/// Compile a jq expression with the standard library
+fn compile_filter(expression: &str) -> Result<Filter, String> {
+    let loader = Loader::new(jaq_std::defs().chain(jaq_json::defs()));
+    let arena = Arena::default();
+    let program = File {
+        code: expression,
+        path: (),
+    };
+    let modules = loader.load(&arena, program).map_err(|errors| {
+        let messages: Vec<String> = errors
+            .into_iter()
+            .map(|(_, error)| load_error_message(error))
+            .collect();
+        format!("Invalid expression: {}", messages.join("; "))
+    })?;
+    Compiler::default()
+        .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+        .compile(modules)
+        .map_err(|errors| {
+            let messages: Vec<String> = errors
+                .into_iter()
+                .map(|(_, errors)| compile_error_message(errors))
+                .collect();
+            format!("Invalid expression: {}", messages.join("; "))
+        })
+}
+
+fn cached_filter(expression: &str) -> Result<Rc<Filter>, String> {
+    FILTERS.with(|filters| {
+        if let Some(filter) = filters.borrow().get(expression) {
+            return Ok(Rc::clone(filter));
+        }
+        let filter = Rc::new(compile_filter(expression)?);
+        filters
+            .borrow_mut()
+            .insert(expression.to_string(), Rc::clone(&filter));
+        Ok(filter)
+    })
+}
+
+/// Check that every field has a unique name and a valid expression
+pub fn validate_derived_fields(fields: &[DerivedField]) -> Result<(), String> {
+    for (index, field) in fields.iter().enumerate() {
+        let name = field.name.trim();
+        if name.is_empty() {
+            return Err("Derived field names cannot be empty".to_string());
+        }
+        if fields[..index]
+            .iter()
+            .any(|other| other.name.trim() == name)
+        {
+            return Err(format!("Duplicate derived field name: {name}"));
+        }
+        compile_filter(&field.expression).map_err(|e| format!("{name}: {e}"))?;
+    }
+    Ok(())
+}
+
+/// First value produced by `expression` on `input`; None if it produces
+/// nothing or only `null`
+pub fn evaluate(expression: &str, input: Value) -> Result<Option<Value>, String> {
+    let filter = cached_filter(expression)?;
+    let inputs = RcIter::new(core::iter::empty());
+    let mut outputs = filter.run((Ctx::new([], &inputs), Val::from(input)));
+    match outputs.next() {
+        Some(Ok(value)) => Ok(Some(Value::from(value)).filter(|value| !value.is_null())),
+        Some(Err(e)) => Err(format!("Expression failed: {e}")),
+        None => Ok(None),
+    }
+}
+
+/// Parsed JSONL lines of a session file (invalid lines are skipped)
+pub fn read_session_entries(path: &Path) -> Result<Value, String> {
+    let content =
+        fs::read_to_string(long_path(path)).map_err(|e| format!("Failed to read session: {e}"))?;
+    let entries = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    Ok(Value::Array(entries))
+}
+
+fn compute(path: &Path, fields: &[DerivedField]) -> BTreeMap<String, Value> {
+    let Ok(entries) = read_session_entries(path) else {
+        return BTreeMap::new();
+    };
+    fields
+        .iter()
+        .filter_map(|field| {
+            let value = evaluate(&field.expression, entries.clone()).ok()??;
+            Some((field.name.trim().to_string(), value))
+        })
+        .collect()
+}
+
+fn computed() -> &'static Mutex<HashMap<PathBuf, ComputedFields>> {
+    COMPUTED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Derived field values of a session file, reusing values computed for the
+/// same file content and fields (not kept in low-memory mode)
+pub fn compute_derived_fields(path: &Path, fields: &[DerivedField]) -> BTreeMap<String, Value> {
+    if low_memory() {
+        if let Ok(mut computed) = computed().lock() {
+            computed.clear();
+        }
+        return compute(path, fields);
+    }
+    let Some(stamp) = FileStamp::of(path) else {
+        return BTreeMap::new();
+    };
+    if let Ok(computed) = computed().lock() {
+        if let Some((cached_stamp, cached_fields, values)) = computed.get(path) {
+            if *cached_stamp == stamp && cached_fields == fields {
+                return values.clone();
+            }
+        }
+    }
+
+    let values = compute(path, fields);
+    if let Ok(mut computed) = computed().lock() {
+        computed.insert(path.to_path_buf(), (stamp, fields.to_vec(), values.clone()));
+    }
+    values
+}
+
+/// Fill `derived_fields` of sessions with the configured fields
+pub fn apply_derived_fields(sessions: &mut [ClaudeSession]) {
+    let fields = derived_fields();
+    if fields.is_empty() {
+        return;
+    }
+    sessions.par_iter_mut().for_each(|session| {
+        session.derived_fields = compute_derived_fields(Path::new(&session.file_path), &fields);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Write;
+
+    fn field(name: &str, expression: &str) -> DerivedField {
+        DerivedField {
+            name: name.to_string(),
+            expression: expression.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_evaluate_returns_first_value() {
+        let entries = json!([
+            {"type": "user", "gitBranch": "main"},
+            {"type": "assistant", "message": {"model": "claude-opus-4"}},
+            {"type": "assistant", "message": {"model": "claude-opus-4"}}
+        ]);
+
+        assert_eq!(
+            evaluate(
+                r#"map(select(.type == "assistant")) | length"#,
+                entries.clone()
+            ),
+            Ok(Some(json!(2)))
+        );
+        assert_eq!(
+            evaluate(".[].gitBranch // empty", entries.clone()),
+            Ok(Some(json!("main")))
+        );
+        assert_eq!(evaluate(".[0].missing", entries.clone()), Ok(None));
+        assert!(evaluate(".[0] | error(\"boom\")", entries).is_err());
+    }
+
+    #[test]
+    fn test_validate_derived_fields() {
+        assert!(validate_derived_fields(&[field("turns", "length")]).is_ok());
+        assert!(validate_derived_fields(&[field(" ", "length")]).is_err());
+        assert!(validate_derived_fields(&[field("a", "length"), field("a", "length")]).is_err());
+
+        let error = validate_derived_fields(&[field("bad", "map(")]).unwrap_err();
+        assert!(error.starts_with("bad: Invalid expression"), "{error}");
+        let error = validate_derived_fields(&[field("bad", "nosuchfn")]).unwrap_err();
+        assert!(error.contains("undefined filter nosuchfn"), "{error}");
+    }
+
+    #[test]
+    fn test_compute_derived_fields_skips_failing_fields() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, r#"{{"type":"user","cwd":"/repo"}}"#).unwrap();
+        writeln!(file, "not json").unwrap();
+        writeln!(file, r#"{{"type":"assistant"}}"#).unwrap();
+
+        let values = compute_derived_fields(
+            file.path(),
+            &[
+                field("entries", "length"),
+                field("cwd", "first(.[].cwd // empty)"),
+                field("broken", "error"),
+            ],
+        );
+
+        assert_eq!(
+            values,
+            BTreeMap::from([
+                ("cwd".to_string(), json!("/repo")),
+                ("entries".to_string(), json!(2)),
+            ])
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Error type returned by Tauri commands
+//!
+//! Serialized as `{ "code": "NOT_FOUND", "message": "...", ... }` so the
+//! frontend can pick a recovery action per code (choose another folder,
+//! rebuild the index, retry...). Helpers that still fail with a plain
+//! `String` convert into `Internal` through `?`.
+
+use crate::utils::display_path;
+use serde::Serialize;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "code", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AppError {
+    /// A file, folder, session or other item does not exist
+    NotFound {
+        message: String,
+    },
+    /// A file could not be parsed; `line` is 1-based when known
+    ParseError {
+        message: String,
+        file: String,
+        line: Option<usize>,
+    },
+    PermissionDenied {
+        message: String,
+    },
+    /// The operation was stopped on request (e.g. `cancel_search`)
+    Cancelled {
+        message: String,
+    },
+    /// The persistent search index must be refreshed first
+    IndexStale {
+        message: String,
+    },
+    /// An argument was rejected (bad ID, unknown scope...)
+    InvalidInput {
+        message: String,
+    },
+    Internal {
+        message: String,
+    },
+}
+
+impl AppError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::NotFound {
+            message: message.into(),
+        }
+    }
+
+    pub fn permission_denied(message: impl Into<String>) -> Self {
+        Self::PermissionDenied {
+            message: message.into(),
+        }
+    }
+
+    pub fn cancelled(message: impl Into<String>) -> Self {
+        Self::Cancelled {
+            message: message.into(),
+        }
+    }
+
+    pub fn index_stale(message: impl Into<String>) -> Self {
+        Self::IndexStale {
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::InvalidInput {
+            message: message.into(),
+        }
+    }
+
+    /// Failure to parse `file` as JSON
+    pub fn parse(file: &Path, error: &serde_json::Error) -> Self {
+        Self::ParseError {
+            message: format!("Failed to parse {}: {error}", display_path(file)),
+            file: display_path(file),
+            line: Some(error.line()).filter(|&line| line > 0),
+        }
+    }
+
+    /// IO failure, classified by its kind; `context` says what was attempted
+    /// (e.g. "Failed to open session file")
+    pub fn io(context: &str, error: &io::Error) -> Self {
+        let message = format!("{context}: {error}");
+        match error.kind() {
+            io::ErrorKind::NotFound => Self::NotFound { message },
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied { message },
+            _ => Self::Internal { message },
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::NotFound { message }
+            | Self::ParseError { message, .. }
+            | Self::PermissionDenied { message }
+            | Self::Cancelled { message }
+            | Self::IndexStale { message }
+            | Self::InvalidInput { message }
+            | Self::Internal { message } => message,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::Internal { message }
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        Self::Internal {
+            message: message.to_string(),
+        }
+    }
+}
+
+/// For helpers that call commands but still report plain strings
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_serializes_with_code() {
+        assert_eq!(
+            serde_json::to_value(AppError::not_found("Session not found: abc")).unwrap(),
+            json!({"code": "NOT_FOUND", "message": "Session not found: abc"})
+        );
+
+        let error = serde_json::from_str::<serde_json::Value>("{\n  \"a\": }").unwrap_err();
+        assert_eq!(
+            serde_json::to_value(AppError::parse(Path::new("/tmp/user-data.json"), &error))
+                .unwrap()["line"],
+            json!(2)
+        );
+    }
+
+    #[test]
+    fn test_io_errors_are_classified() {
+        let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+        assert!(matches!(
+            AppError::io("Failed to open session file", &denied),
+            AppError::PermissionDenied { .. }
+        ));
+        let missing = io::Error::from(io::ErrorKind::NotFound);
+        assert!(matches!(
+            AppError::io("Failed to open session file", &missing),
+            AppError::NotFound { .. }
+        ));
+    }
+
+    #[test]
+    fn test_plain_strings_become_internal() {
+        let error: AppError = "Task join error".to_string().into();
+        assert_eq!(error.to_string(), "Task join error");
+        assert_eq!(String::from(error), "Task join error");
+    }
+}
@@ -0,0 +1,119 @@
+//! Detection of session files modified outside the app
+//!
+//! Remembers the modification time and size of every session file at the
+//! moment its data was sent to the frontend. When a file is accessed again
+//! and no longer matches, the displayed data is stale: the affected file is
+//! reindexed and the frontend is told through `SESSION_REFRESHED_EVENT`.
+
+use crate::models::SessionRefreshedEvent;
+use crate::utils::long_path;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+/// Event emitted when data already shown for a session file was refreshed
+pub const SESSION_REFRESHED_EVENT: &str = "session-data-refreshed";
+
+/// Identity of a file's content as far as the filesystem can tell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    pub modified: Option<SystemTime>,
+    pub size: u64,
+}
+
+impl FileStamp {
+    /// Current stamp of `path`, or None if it no longer exists
+    pub fn of(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(long_path(path)).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok(),
+            size: metadata.len(),
+        })
+    }
+}
+
+/// How a file changed since it was last served
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChange {
+    Modified,
+    Deleted,
+}
+
+impl FileChange {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Modified => "modified",
+            Self::Deleted => "deleted",
+        }
+    }
+}
+
+/// Stamps of the files whose data the frontend is currently showing
+static SERVED_FILES: OnceLock<Mutex<HashMap<PathBuf, FileStamp>>> = OnceLock::new();
+
+type RefreshListener = Box<dyn Fn(&SessionRefreshedEvent) + Send + Sync>;
+
+/// Forwards refresh events to the frontend (unset in tests)
+static REFRESH_LISTENER: OnceLock<RefreshListener> = OnceLock::new();
+
+fn served_files() -> &'static Mutex<HashMap<PathBuf, FileStamp>> {
+    SERVED_FILES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Register the callback that emits refresh events to the frontend
+pub fn set_refresh_listener(listener: impl Fn(&SessionRefreshedEvent) + Send + Sync + 'static) {
+    let _ = REFRESH_LISTENER.set(Box::new(listener));
+}
+
+/// Record that `path` is about to be served and report how it changed since
+/// the previous time it was served (None on first access or if unchanged)
+pub fn check_and_record(path: &Path) -> Option<FileChange> {
+    let current = FileStamp::of(path);
+    let mut served = served_files().lock().ok()?;
+
+    let previous = match current {
+        Some(stamp) => served.insert(path.to_path_buf(), stamp),
+        None => served.remove(path),
+    }?;
+
+    match current {
+        None => Some(FileChange::Deleted),
+        Some(stamp) if stamp != previous => Some(FileChange::Modified),
+        Some(_) => None,
+    }
+}
+
+/// Tell the frontend that data shown for a session file was refreshed
+pub fn notify_refreshed(event: &SessionRefreshedEvent) {
+    if let Some(listener) = REFRESH_LISTENER.get() {
+        listener(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_and_record_detects_changes() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("s1.jsonl");
+        fs::write(&path, "{}\n").unwrap();
+
+        assert_eq!(check_and_record(&path), None);
+        assert_eq!(check_and_record(&path), None);
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "{{}}").unwrap();
+        assert_eq!(check_and_record(&path), Some(FileChange::Modified));
+        assert_eq!(check_and_record(&path), None);
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(check_and_record(&path), Some(FileChange::Deleted));
+        assert_eq!(check_and_record(&path), None);
+    }
+}
@@ -0,0 +1,1218 @@
+//! Persistent full-text search index
+//!
+//! Scanning every session file on each search gets slow once a project holds
+//! hundreds of megabytes of logs. This optional index stores the text of user
+//! and assistant messages in a `SQLite` FTS5 table, keyed by
+//! (`file_path`, `line_offset`, `uuid`), and remembers the modification time and
+//! size of every indexed file so a refresh only re-reads files that changed.
+//! FTS5 cannot look up its unindexed columns, so the rows of each file are
+//! also listed in plain tables indexed by `file_path`; replacing or dropping
+//! a file goes through them instead of scanning the whole index.
+//!
+//! Error messages found in tool results (panics, compiler errors, exceptions
+//! and stack traces) are also extracted into a separate `errors` table, so an
+//! exact panic can be traced back to every session that hit it. Each error
+//! gets a fingerprint (kind, normalized message and top stack frames) that
+//! groups repeated occurrences the way error trackers do.
+
+use crate::io_limit::acquire_file_permit;
+use crate::models::RawLogEntry;
+use crate::utils::{display_path, find_line_ranges, long_path, stable_line_id};
+use memmap2::Mmap;
+use regex::Regex;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::UNIX_EPOCH;
+
+/// File name of the index inside the metadata folder
+pub const SEARCH_INDEX_FILE_NAME: &str = "search-index.sqlite";
+
+/// Bump when the schema or the indexed text changes to force a rebuild
+const INDEX_SCHEMA_VERSION: i64 = 4;
+
+/// Maximum characters kept of an error message or its context
+const MAX_ERROR_CHARS: usize = 500;
+
+/// Stack frames (innermost first) that take part in an error fingerprint
+const MAX_FINGERPRINT_FRAMES: usize = 5;
+
+/// A message found through the index
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedMessage {
+    pub file_path: String,
+    pub line_offset: u64,
+    pub uuid: String,
+    pub session_id: String,
+    pub timestamp: String,
+    pub message_type: String,
+    pub is_sidechain: bool,
+    pub text: String,
+}
+
+/// An error message extracted from a tool result
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedError {
+    pub file_path: String,
+    pub line_offset: u64,
+    pub uuid: String,
+    pub session_id: String,
+    pub timestamp: String,
+    pub tool_name: Option<String>,
+    pub kind: String,        // "panic", "compile_error", "exception" or "error"
+    pub message: String,     // Normalized error line
+    pub context: String,     // Location or first stack frame, may be empty
+    pub signature: String,   // Message without paths, numbers and addresses
+    pub fingerprint: String, // Groups occurrences of the same error
+}
+
+/// Occurrences of one error fingerprint
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedErrorGroup {
+    pub fingerprint: String,
+    pub kind: String,
+    pub message: String, // Of the latest occurrence
+    pub context: String, // Of the latest occurrence
+    pub tool_name: Option<String>,
+    pub occurrences: usize,
+    pub session_ids: Vec<String>, // Sorted
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+/// Outcome of a refresh
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RefreshStats {
+    pub indexed_files: usize,
+    pub updated_files: usize,
+    pub removed_files: usize,
+}
+
+pub struct SearchIndex {
+    connection: Connection,
+}
+
+/// Modification time (ns since epoch) and size identifying a file's content
+fn file_stamp(path: &Path) -> Option<(i64, i64)> {
+    let metadata = fs::metadata(long_path(path)).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| i64::try_from(d.as_nanos()).unwrap_or(i64::MAX));
+    Some((modified, i64::try_from(metadata.len()).unwrap_or(i64::MAX)))
+}
+
+/// Concatenate every string of a message content (text, tool inputs and results)
+fn collect_text(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::String(s) => {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(s);
+        }
+        serde_json::Value::Array(items) => items.iter().for_each(|v| collect_text(v, out)),
+        serde_json::Value::Object(map) => map.values().for_each(|v| collect_text(v, out)),
+        _ => {}
+    }
+}
+
+/// Searchable content of a session file
+#[derive(Default)]
+struct IndexableFile {
+    messages: Vec<IndexedMessage>,
+    errors: Vec<IndexedError>,
+}
+
+/// Text of a `tool_result` block content (a string or a list of text blocks)
+fn tool_result_content(content: Option<&serde_json::Value>) -> String {
+    match content {
+        Some(serde_json::Value::String(text)) => text.clone(),
+        Some(serde_json::Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|block| block.get("text").and_then(serde_json::Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Parse the searchable messages and tool errors of a session file
+#[allow(unsafe_code)] // Required for mmap performance optimization
+fn read_indexable_file(path: &Path) -> IndexableFile {
+    let _permit = acquire_file_permit();
+    let Ok(file) = fs::File::open(long_path(path)) else {
+        return IndexableFile::default();
+    };
+    // SAFETY: We're only reading the file, and the file handle is kept open
+    // for the duration of the mmap's lifetime. Session files are append-only.
+    let Ok(mmap) = (unsafe { Mmap::map(&file) }) else {
+        return IndexableFile::default();
+    };
+
+    let file_path = display_path(path);
+    let mut indexable = IndexableFile::default();
+    let mut tool_names: HashMap<String, String> = HashMap::new();
+    for (line_num, (start, end)) in find_line_ranges(&mmap).into_iter().enumerate() {
+        let mut line = mmap[start..end].to_vec();
+        let Ok(entry) = simd_json::serde::from_slice::<RawLogEntry>(&mut line) else {
+            continue;
+        };
+        if entry.message_type != "user" && entry.message_type != "assistant" {
+            continue;
+        }
+        let Some(message) = &entry.message else {
+            continue;
+        };
+
+        let mut text = String::new();
+        collect_text(&message.content, &mut text);
+        if text.is_empty() {
+            continue;
+        }
+
+        let uuid = entry
+            .uuid
+            .unwrap_or_else(|| stable_line_id(entry.session_id.as_deref(), path, line_num));
+        let session_id = entry
+            .session_id
+            .unwrap_or_else(|| "unknown-session".to_string());
+        let timestamp = entry.timestamp.unwrap_or_default();
+
+        for block in message.content.as_array().into_iter().flatten() {
+            let id = |key| block.get(key).and_then(serde_json::Value::as_str);
+            match id("type") {
+                Some("tool_use") => {
+                    if let (Some(tool_id), Some(name)) = (id("id"), id("name")) {
+                        tool_names.insert(tool_id.to_string(), name.to_string());
+                    }
+                }
+                Some("tool_result") => {
+                    let is_error = block.get("is_error") == Some(&serde_json::Value::Bool(true));
+                    let content = tool_result_content(block.get("content"));
+                    let tool_name = id("tool_use_id").and_then(|id| tool_names.get(id)).cloned();
+                    for error in extract_errors(&content, is_error) {
+                        indexable.errors.push(IndexedError {
+                            file_path: file_path.clone(),
+                            line_offset: start as u64,
+                            uuid: uuid.clone(),
+                            session_id: session_id.clone(),
+                            timestamp: timestamp.clone(),
+                            tool_name: tool_name.clone(),
+                            signature: error_signature(&error.message),
+                            fingerprint: error_fingerprint(&error),
+                            kind: error.kind.to_string(),
+                            message: error.message,
+                            context: error.context,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        indexable.messages.push(IndexedMessage {
+            file_path: file_path.clone(),
+            line_offset: start as u64,
+            uuid,
+            session_id,
+            timestamp,
+            message_type: entry.message_type,
+            is_sidechain: entry.is_sidechain == Some(true),
+            text,
+        });
+    }
+    indexable
+}
+
+/// An error found in tool output, before it is tied to a message
+#[derive(Debug, PartialEq)]
+struct ExtractedError {
+    kind: &'static str,
+    message: String,
+    context: String,
+    frames: Vec<String>, // Innermost first, without paths and line numbers
+}
+
+fn regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("valid error pattern"))
+}
+
+/// Collapse whitespace and cap the length of an error line
+fn clean_error_line(line: &str) -> String {
+    let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+    line.chars().take(MAX_ERROR_CHARS).collect()
+}
+
+/// Add an error unless it was already found in the same output
+fn push_error(
+    errors: &mut Vec<ExtractedError>,
+    kind: &'static str,
+    message: &str,
+    context: &str,
+    frames: Vec<String>,
+) {
+    let error = ExtractedError {
+        kind,
+        message: clean_error_line(message),
+        context: clean_error_line(context),
+        frames,
+    };
+    if !error.message.is_empty() && !errors.contains(&error) {
+        errors.push(error);
+    }
+}
+
+/// File name without directories or extension
+fn file_stem(path: &str) -> &str {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    name.split('.').next().unwrap_or(name)
+}
+
+/// Function names of a Python traceback, innermost first
+fn python_frames(traceback: &[&str]) -> Vec<String> {
+    static FRAME: OnceLock<Regex> = OnceLock::new();
+    let frame = regex(&FRAME, r#"^File "([^"]+)", line \d+, in (.+)$"#);
+    traceback
+        .iter()
+        .rev()
+        .filter_map(|line| frame.captures(line.trim()))
+        .map(|caps| format!("{}.{}", file_stem(&caps[1]), &caps[2]))
+        .take(MAX_FINGERPRINT_FRAMES)
+        .collect()
+}
+
+/// Function names of the `at …` lines of a JavaScript stack, innermost first
+fn javascript_frames(stack: &[&str]) -> Vec<String> {
+    static FRAME: OnceLock<Regex> = OnceLock::new();
+    let frame = regex(&FRAME, r"^at (?:async )?(?:(.+?) \((.+)\)|(.+))$");
+    stack
+        .iter()
+        .map(|line| line.trim())
+        .take_while(|line| line.starts_with("at "))
+        .filter_map(|line| frame.captures(line))
+        .map(|caps| match (caps.get(1), caps.get(3)) {
+            (Some(function), _) => function.as_str().to_string(),
+            (None, Some(location)) => file_stem(location.as_str()).to_string(),
+            (None, None) => String::new(),
+        })
+        .take(MAX_FINGERPRINT_FRAMES)
+        .collect()
+}
+
+/// Symbols of a Rust `stack backtrace:` outside the standard library,
+/// innermost first
+fn rust_frames(backtrace: &[&str]) -> Vec<String> {
+    static FRAME: OnceLock<Regex> = OnceLock::new();
+    static HASH: OnceLock<Regex> = OnceLock::new();
+    let frame = regex(&FRAME, r"^\d+: (.+)$");
+    backtrace
+        .iter()
+        .map(|line| line.trim())
+        .take_while(|line| line.starts_with("at ") || frame.is_match(line))
+        .filter_map(|line| frame.captures(line))
+        .map(|caps| {
+            regex(&HASH, r"::h[0-9a-f]{16}$")
+                .replace(&caps[1], "")
+                .into_owned()
+        })
+        .filter(|symbol| {
+            let symbol = symbol.trim_start_matches('<');
+            !["std::", "core::", "alloc::", "rust_begin_unwind", "__rust"]
+                .iter()
+                .any(|prefix| symbol.starts_with(prefix))
+        })
+        .take(MAX_FINGERPRINT_FRAMES)
+        .collect()
+}
+
+/// Panics, compiler errors, exceptions and error lines in tool output
+///
+/// Output of a failed tool call (`is_error`) without any recognizable error
+/// line contributes its first line instead.
+fn extract_errors(text: &str, is_error: bool) -> Vec<ExtractedError> {
+    static ANSI: OnceLock<Regex> = OnceLock::new();
+    static PANIC: OnceLock<Regex> = OnceLock::new();
+    static COMPILER: OnceLock<Regex> = OnceLock::new();
+    static EXCEPTION: OnceLock<Regex> = OnceLock::new();
+    static GENERIC: OnceLock<Regex> = OnceLock::new();
+
+    let text = regex(&ANSI, r"\x1b\[[0-9;]*[A-Za-z]").replace_all(text, "");
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let next_line = |from: usize| {
+        lines[from..]
+            .iter()
+            .map(|line| line.trim())
+            .find(|l| !l.is_empty())
+    };
+
+    let mut errors: Vec<ExtractedError> = Vec::new();
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        if let Some(caps) = regex(&PANIC, r"^thread '[^']*' panicked at (.+)$").captures(line) {
+            let rest = &caps[1];
+            // Before Rust 1.73: panicked at 'message', src/main.rs:2:5
+            let frames = lines[i + 1..]
+                .iter()
+                .take(4)
+                .position(|line| line.trim() == "stack backtrace:")
+                .map(|at| rust_frames(&lines[i + at + 2..]))
+                .unwrap_or_default();
+            if let Some((message, location)) = rest
+                .strip_prefix('\'')
+                .and_then(|rest| rest.rsplit_once("', "))
+            {
+                push_error(&mut errors, "panic", message, location, frames);
+            } else {
+                let message = next_line(i + 1).unwrap_or(rest);
+                let location = rest.trim_end_matches(':');
+                push_error(&mut errors, "panic", message, location, frames);
+            }
+        } else if line == "Traceback (most recent call last):" {
+            // The exception line is the first one back at column zero
+            let Some(end) = (i + 1..lines.len())
+                .find(|&j| !lines[j].is_empty() && !lines[j].starts_with(char::is_whitespace))
+            else {
+                break;
+            };
+            let frame = lines[i + 1..end]
+                .iter()
+                .rev()
+                .map(|line| line.trim())
+                .find(|line| line.starts_with("File "))
+                .unwrap_or("");
+            let frames = python_frames(&lines[i + 1..end]);
+            push_error(&mut errors, "exception", lines[end], frame, frames);
+            i = end;
+        } else if let Some(caps) = regex(&COMPILER, r"^error(\[E\d+\])?: (.+)$").captures(line) {
+            let location = next_line(i + 1)
+                .and_then(|next| next.strip_prefix("--> "))
+                .unwrap_or("");
+            let kind = if caps.get(1).is_some() {
+                "compile_error"
+            } else {
+                "error"
+            };
+            push_error(&mut errors, kind, line, location, Vec::new());
+        } else if regex(
+            &EXCEPTION,
+            r"^(?:Uncaught )?[\w.$]*(?:Error|Exception)(?:: .+)?$",
+        )
+        .is_match(line)
+        {
+            let frame = next_line(i + 1)
+                .filter(|next| next.starts_with("at "))
+                .unwrap_or("");
+            push_error(
+                &mut errors,
+                "exception",
+                line.trim_start_matches("Uncaught "),
+                frame,
+                javascript_frames(&lines[i + 1..]),
+            );
+        } else if regex(&GENERIC, r"^(?i:fatal|error)\b:? .+$").is_match(line) {
+            push_error(&mut errors, "error", line, "", Vec::new());
+        }
+        i += 1;
+    }
+
+    if errors.is_empty() && is_error {
+        if let Some(first) = next_line(0) {
+            push_error(&mut errors, "error", first, "", Vec::new());
+        }
+    }
+    errors
+}
+
+/// Error message without the details that differ between occurrences
+/// (paths, numbers, addresses and quoted values)
+fn error_signature(message: &str) -> String {
+    static ADDRESS: OnceLock<Regex> = OnceLock::new();
+    static PATH: OnceLock<Regex> = OnceLock::new();
+    static QUOTED: OnceLock<Regex> = OnceLock::new();
+    static NUMBER: OnceLock<Regex> = OnceLock::new();
+
+    let signature = regex(&ADDRESS, r"0x[0-9a-fA-F]+").replace_all(message, "<addr>");
+    let signature =
+        regex(&PATH, r"(?:[A-Za-z]:)?(?:[\w.~-]*[/\\])+[\w.-]+").replace_all(&signature, "<path>");
+    let signature = regex(&QUOTED, r#"`[^`]*`|"[^"]*"|'[^']*'"#).replace_all(&signature, "<value>");
+    regex(&NUMBER, r"\b\d+\b")
+        .replace_all(&signature, "<n>")
+        .into_owned()
+}
+
+/// 64-bit FNV-1a, stable across builds (unlike `DefaultHasher`)
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Grouping key of an error: its kind, message signature and top stack
+/// frames, or its location without line numbers when it has no stack
+fn error_fingerprint(error: &ExtractedError) -> String {
+    static LINE_NUMBERS: OnceLock<Regex> = OnceLock::new();
+
+    let mut key = format!("{}\n{}", error.kind, error_signature(&error.message));
+    if error.frames.is_empty() {
+        let location =
+            regex(&LINE_NUMBERS, r":\d+(?::\d+)?|, line \d+").replace_all(&error.context, "");
+        key.push('\n');
+        key.push_str(&location);
+    }
+    for frame in &error.frames {
+        key.push('\n');
+        key.push_str(frame);
+    }
+    format!("{:016x}", fnv1a(key.as_bytes()))
+}
+
+/// Prefix of the paths of files under directory `dir`
+///
+/// Ends in a separator so that a sibling directory whose name starts the
+/// same (`-Users-me-app-v2` for `-Users-me-app`) does not match.
+fn dir_prefix(dir: &str) -> String {
+    let dir = dir.trim_end_matches(['/', std::path::MAIN_SEPARATOR]);
+    format!("{dir}{}", std::path::MAIN_SEPARATOR)
+}
+
+/// Quote user input as an FTS5 phrase so operators in it are taken literally
+fn fts_phrase(query: &str) -> String {
+    format!("\"{}\"", query.replace('"', "\"\""))
+}
+
+impl SearchIndex {
+    /// Open (or create) the index at `path`
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let connection =
+            Connection::open(path).map_err(|e| format!("Failed to open search index: {e}"))?;
+        let index = Self { connection };
+        index.migrate()?;
+        Ok(index)
+    }
+
+    fn migrate(&self) -> Result<(), String> {
+        let version: i64 = self
+            .connection
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(|e| format!("Failed to read search index version: {e}"))?;
+        if version == INDEX_SCHEMA_VERSION {
+            return Ok(());
+        }
+
+        self.connection
+            .execute_batch(&format!(
+                "DROP TABLE IF EXISTS indexed_files;
+                 DROP TABLE IF EXISTS messages;
+                 DROP TABLE IF EXISTS errors;
+                 DROP TABLE IF EXISTS message_rows;
+                 DROP TABLE IF EXISTS error_rows;
+                 CREATE TABLE indexed_files (
+                     file_path TEXT PRIMARY KEY,
+                     modified_ns INTEGER NOT NULL,
+                     size INTEGER NOT NULL
+                 );
+                 CREATE VIRTUAL TABLE messages USING fts5(
+                     text,
+                     file_path UNINDEXED,
+                     line_offset UNINDEXED,
+                     uuid UNINDEXED,
+                     session_id UNINDEXED,
+                     timestamp UNINDEXED,
+                     message_type UNINDEXED,
+                     is_sidechain UNINDEXED,
+                     tokenize = 'unicode61'
+                 );
+                 CREATE VIRTUAL TABLE errors USING fts5(
+                     message,
+                     context,
+                     kind UNINDEXED,
+                     signature UNINDEXED,
+                     fingerprint UNINDEXED,
+                     tool_name UNINDEXED,
+                     file_path UNINDEXED,
+                     line_offset UNINDEXED,
+                     uuid UNINDEXED,
+                     session_id UNINDEXED,
+                     timestamp UNINDEXED,
+                     tokenize = 'unicode61'
+                 );
+                 CREATE TABLE message_rows (
+                     file_path TEXT NOT NULL,
+                     row_id INTEGER NOT NULL
+                 );
+                 CREATE INDEX message_rows_file ON message_rows (file_path);
+                 CREATE TABLE error_rows (
+                     file_path TEXT NOT NULL,
+                     row_id INTEGER NOT NULL
+                 );
+                 CREATE INDEX error_rows_file ON error_rows (file_path);
+                 PRAGMA user_version = {INDEX_SCHEMA_VERSION};"
+            ))
+            .map_err(|e| format!("Failed to create search index: {e}"))
+    }
+
+    /// Remove the messages and errors of one file, looked up by rowid
+    fn delete_file_rows(transaction: &Transaction, file_path: &str) -> rusqlite::Result<()> {
+        for (rows, table) in [("message_rows", "messages"), ("error_rows", "errors")] {
+            let row_ids: Vec<i64> = {
+                let mut select = transaction
+                    .prepare_cached(&format!("SELECT row_id FROM {rows} WHERE file_path = ?1"))?;
+                let ids = select.query_map([file_path], |row| row.get(0))?;
+                ids.collect::<Result<_, _>>()?
+            };
+            let mut delete =
+                transaction.prepare_cached(&format!("DELETE FROM {table} WHERE rowid = ?1"))?;
+            for row_id in row_ids {
+                delete.execute([row_id])?;
+            }
+            transaction.execute(
+                &format!("DELETE FROM {rows} WHERE file_path = ?1"),
+                [file_path],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Bring the index in line with `session_files`
+    ///
+    /// Files whose modification time or size changed are re-read; files no
+    /// longer in the list are dropped from the index.
+    pub fn refresh(&mut self, session_files: &[PathBuf]) -> Result<RefreshStats, String> {
+        let sql_err = |e: rusqlite::Error| format!("Failed to update search index: {e}");
+        let mut stats = RefreshStats::default();
+
+        let current: HashSet<String> = session_files.iter().map(|p| display_path(p)).collect();
+        let transaction = self.connection.transaction().map_err(sql_err)?;
+
+        let known: Vec<String> = {
+            let mut statement = transaction
+                .prepare("SELECT file_path FROM indexed_files")
+                .map_err(sql_err)?;
+            let rows = statement.query_map([], |row| row.get(0)).map_err(sql_err)?;
+            rows.collect::<Result<_, _>>().map_err(sql_err)?
+        };
+        for file_path in known.iter().filter(|p| !current.contains(*p)) {
+            Self::delete_file_rows(&transaction, file_path).map_err(sql_err)?;
+            transaction
+                .execute(
+                    "DELETE FROM indexed_files WHERE file_path = ?1",
+                    [file_path],
+                )
+                .map_err(sql_err)?;
+            stats.removed_files += 1;
+        }
+
+        for path in session_files {
+            let Some((modified_ns, size)) = file_stamp(path) else {
+                continue;
+            };
+            stats.indexed_files += 1;
+            let file_path = display_path(path);
+            let stored: Option<(i64, i64)> = transaction
+                .query_row(
+                    "SELECT modified_ns, size FROM indexed_files WHERE file_path = ?1",
+                    [&file_path],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()
+                .map_err(sql_err)?;
+            if stored == Some((modified_ns, size)) {
+                continue;
+            }
+
+            // Files indexed for the first time have no rows to replace
+            if stored.is_some() {
+                Self::delete_file_rows(&transaction, &file_path).map_err(sql_err)?;
+            }
+            let indexable = read_indexable_file(path);
+            {
+                let mut insert = transaction
+                    .prepare_cached(
+                        "INSERT INTO messages (text, file_path, line_offset, uuid, session_id,
+                             timestamp, message_type, is_sidechain)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    )
+                    .map_err(sql_err)?;
+                let mut insert_row = transaction
+                    .prepare_cached("INSERT INTO message_rows (file_path, row_id) VALUES (?1, ?2)")
+                    .map_err(sql_err)?;
+                for message in indexable.messages {
+                    insert
+                        .execute(params![
+                            message.text,
+                            message.file_path,
+                            i64::try_from(message.line_offset).unwrap_or(i64::MAX),
+                            message.uuid,
+                            message.session_id,
+                            message.timestamp,
+                            message.message_type,
+                            message.is_sidechain,
+                        ])
+                        .map_err(sql_err)?;
+                    insert_row
+                        .execute(params![file_path, transaction.last_insert_rowid()])
+                        .map_err(sql_err)?;
+                }
+                let mut insert = transaction
+                    .prepare_cached(
+                        "INSERT INTO errors (message, context, kind, signature, fingerprint,
+                             tool_name, file_path, line_offset, uuid, session_id, timestamp)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                    )
+                    .map_err(sql_err)?;
+                let mut insert_row = transaction
+                    .prepare_cached("INSERT INTO error_rows (file_path, row_id) VALUES (?1, ?2)")
+                    .map_err(sql_err)?;
+                for error in indexable.errors {
+                    insert
+                        .execute(params![
+                            error.message,
+                            error.context,
+                            error.kind,
+                            error.signature,
+                            error.fingerprint,
+                            error.tool_name,
+                            error.file_path,
+                            i64::try_from(error.line_offset).unwrap_or(i64::MAX),
+                            error.uuid,
+                            error.session_id,
+                            error.timestamp,
+                        ])
+                        .map_err(sql_err)?;
+                    insert_row
+                        .execute(params![file_path, transaction.last_insert_rowid()])
+                        .map_err(sql_err)?;
+                }
+            }
+            transaction
+                .execute(
+                    "INSERT OR REPLACE INTO indexed_files (file_path, modified_ns, size)
+                     VALUES (?1, ?2, ?3)",
+                    params![file_path, modified_ns, size],
+                )
+                .map_err(sql_err)?;
+            stats.updated_files += 1;
+        }
+
+        transaction.commit().map_err(sql_err)?;
+        Ok(stats)
+    }
+
+    /// Messages containing `query`, newest first
+    ///
+    /// `path_prefix` restricts results to files under a directory (a project).
+    pub fn search(
+        &self,
+        query: &str,
+        path_prefix: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<IndexedMessage>, String> {
+        let sql_err = |e: rusqlite::Error| format!("Failed to search index: {e}");
+        let mut statement = self
+            .connection
+            .prepare(
+                "SELECT file_path, line_offset, uuid, session_id, timestamp, message_type,
+                     is_sidechain, text
+                 FROM messages
+                 WHERE messages MATCH ?1
+                   AND (?2 IS NULL OR substr(file_path, 1, length(?2)) = ?2)
+                 ORDER BY timestamp DESC
+                 LIMIT ?3",
+            )
+            .map_err(sql_err)?;
+        let rows = statement
+            .query_map(
+                params![
+                    fts_phrase(query),
+                    path_prefix.map(dir_prefix),
+                    i64::try_from(limit).unwrap_or(i64::MAX)
+                ],
+                |row| {
+                    Ok(IndexedMessage {
+                        file_path: row.get(0)?,
+                        line_offset: u64::try_from(row.get::<_, i64>(1)?).unwrap_or(0),
+                        uuid: row.get(2)?,
+                        session_id: row.get(3)?,
+                        timestamp: row.get(4)?,
+                        message_type: row.get(5)?,
+                        is_sidechain: row.get(6)?,
+                        text: row.get(7)?,
+                    })
+                },
+            )
+            .map_err(sql_err)?;
+        rows.collect::<Result<_, _>>().map_err(sql_err)
+    }
+
+    /// Extracted errors containing `query` (all errors if empty), newest first
+    ///
+    /// `kind` keeps one kind of error and `fingerprint` the occurrences of
+    /// one error; `path_prefix` restricts results to files under a directory
+    /// (a project).
+    pub fn search_errors(
+        &self,
+        query: &str,
+        kind: Option<&str>,
+        fingerprint: Option<&str>,
+        path_prefix: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<IndexedError>, String> {
+        let sql_err = |e: rusqlite::Error| format!("Failed to search error index: {e}");
+        // MATCH cannot be combined with OR, so an empty query gets its own statement
+        let filter = if query.is_empty() {
+            "?1 IS NULL"
+        } else {
+            "errors MATCH ?1"
+        };
+        let mut statement = self
+            .connection
+            .prepare(&format!(
+                "SELECT file_path, line_offset, uuid, session_id, timestamp, tool_name, kind,
+                     message, context, signature, fingerprint
+                 FROM errors
+                 WHERE {filter}
+                   AND (?2 IS NULL OR kind = ?2)
+                   AND (?3 IS NULL OR fingerprint = ?3)
+                   AND (?4 IS NULL OR substr(file_path, 1, length(?4)) = ?4)
+                 ORDER BY timestamp DESC
+                 LIMIT ?5"
+            ))
+            .map_err(sql_err)?;
+        let query = (!query.is_empty()).then(|| fts_phrase(query));
+        let rows = statement
+            .query_map(
+                params![
+                    query,
+                    kind,
+                    fingerprint,
+                    path_prefix.map(dir_prefix),
+                    i64::try_from(limit).unwrap_or(i64::MAX)
+                ],
+                |row| {
+                    Ok(IndexedError {
+                        file_path: row.get(0)?,
+                        line_offset: u64::try_from(row.get::<_, i64>(1)?).unwrap_or(0),
+                        uuid: row.get(2)?,
+                        session_id: row.get(3)?,
+                        timestamp: row.get(4)?,
+                        tool_name: row.get(5)?,
+                        kind: row.get(6)?,
+                        message: row.get(7)?,
+                        context: row.get(8)?,
+                        signature: row.get(9)?,
+                        fingerprint: row.get(10)?,
+                    })
+                },
+            )
+            .map_err(sql_err)?;
+        rows.collect::<Result<_, _>>().map_err(sql_err)
+    }
+
+    /// Extracted errors grouped by fingerprint, most recently seen first
+    ///
+    /// Takes the same `kind` and `path_prefix` filters as `search_errors`.
+    pub fn error_groups(
+        &self,
+        kind: Option<&str>,
+        path_prefix: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<IndexedErrorGroup>, String> {
+        let sql_err = |e: rusqlite::Error| format!("Failed to group errors: {e}");
+        // `latest` relies on SQLite taking bare columns from the max() row
+        let mut statement = self
+            .connection
+            .prepare(
+                "WITH filtered AS (
+                     SELECT * FROM errors
+                     WHERE (?1 IS NULL OR kind = ?1)
+                       AND (?2 IS NULL OR substr(file_path, 1, length(?2)) = ?2)
+                 ),
+                 groups AS (
+                     SELECT fingerprint, count(*) AS occurrences,
+                         group_concat(DISTINCT session_id) AS session_ids,
+                         min(timestamp) AS first_seen, max(timestamp) AS last_seen
+                     FROM filtered GROUP BY fingerprint
+                 ),
+                 latest AS (
+                     SELECT fingerprint, kind, message, context, tool_name, max(timestamp)
+                     FROM filtered GROUP BY fingerprint
+                 )
+                 SELECT groups.fingerprint, latest.kind, latest.message, latest.context,
+                     latest.tool_name, occurrences, session_ids, first_seen, last_seen
+                 FROM groups JOIN latest ON latest.fingerprint = groups.fingerprint
+                 ORDER BY last_seen DESC, occurrences DESC
+                 LIMIT ?3",
+            )
+            .map_err(sql_err)?;
+        let rows = statement
+            .query_map(
+                params![
+                    kind,
+                    path_prefix.map(dir_prefix),
+                    i64::try_from(limit).unwrap_or(i64::MAX)
+                ],
+                |row| {
+                    let mut session_ids: Vec<String> = row
+                        .get::<_, String>(6)?
+                        .split(',')
+                        .map(str::to_string)
+                        .collect();
+                    session_ids.sort();
+                    Ok(IndexedErrorGroup {
+                        fingerprint: row.get(0)?,
+                        kind: row.get(1)?,
+                        message: row.get(2)?,
+                        context: row.get(3)?,
+                        tool_name: row.get(4)?,
+                        occurrences: usize::try_from(row.get::<_, i64>(5)?).unwrap_or(0),
+                        session_ids,
+                        first_seen: row.get(7)?,
+                        last_seen: row.get(8)?,
+                    })
+                },
+            )
+            .map_err(sql_err)?;
+        rows.collect::<Result<_, _>>().map_err(sql_err)
+    }
+
+    /// Number of extracted errors
+    pub fn error_count(&self) -> Result<usize, String> {
+        self.connection
+            .query_row("SELECT count(*) FROM errors", [], |row| {
+                row.get::<_, i64>(0)
+            })
+            .map(|count| usize::try_from(count).unwrap_or(0))
+            .map_err(|e| format!("Failed to read search index: {e}"))
+    }
+
+    /// Number of session files in the index
+    pub fn indexed_file_count(&self) -> Result<usize, String> {
+        self.connection
+            .query_row("SELECT count(*) FROM indexed_files", [], |row| {
+                row.get::<_, i64>(0)
+            })
+            .map(|count| usize::try_from(count).unwrap_or(0))
+            .map_err(|e| format!("Failed to read search index: {e}"))
+    }
+
+    /// Number of indexed messages
+    pub fn message_count(&self) -> Result<usize, String> {
+        self.connection
+            .query_row("SELECT count(*) FROM messages", [], |row| {
+                row.get::<_, i64>(0)
+            })
+            .map(|count| usize::try_from(count).unwrap_or(0))
+            .map_err(|e| format!("Failed to read search index: {e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn user_line(uuid: &str, ts: &str, text: &str) -> String {
+        format!(
+            r#"{{"uuid":"{uuid}","sessionId":"s1","timestamp":"{ts}","type":"user","message":{{"role":"user","content":"{text}"}}}}"#
+        )
+    }
+
+    #[test]
+    fn test_refresh_is_incremental() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join("project");
+        fs::create_dir_all(&project).unwrap();
+        let a = project.join("a.jsonl");
+        let b = project.join("b.jsonl");
+        fs::write(
+            &a,
+            user_line("u1", "2025-01-01T00:00:00Z", "fix the login bug") + "\n",
+        )
+        .unwrap();
+        fs::write(
+            &b,
+            user_line("u2", "2025-01-02T00:00:00Z", "unrelated") + "\n",
+        )
+        .unwrap();
+
+        let mut index = SearchIndex::open(&temp.path().join("index.sqlite")).unwrap();
+        let files = vec![a.clone(), b.clone()];
+        assert_eq!(index.refresh(&files).unwrap().updated_files, 2);
+        assert_eq!(index.refresh(&files).unwrap().updated_files, 0);
+
+        let mut file = fs::OpenOptions::new().append(true).open(&b).unwrap();
+        writeln!(
+            file,
+            "{}",
+            user_line("u3", "2025-01-03T00:00:00Z", "login works now")
+        )
+        .unwrap();
+        assert_eq!(index.refresh(&files).unwrap().updated_files, 1);
+        // The old rows of b were replaced, not duplicated
+        assert_eq!(index.message_count().unwrap(), 3);
+
+        let results = index.search("login", None, 10).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].uuid, "u3");
+        assert_eq!(results[1].line_offset, 0);
+
+        let stats = index.refresh(&[a]).unwrap();
+        assert_eq!(stats.removed_files, 1);
+        assert_eq!(index.message_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_search_quotes_operators() {
+        let temp = TempDir::new().unwrap();
+        let a = temp.path().join("a.jsonl");
+        fs::write(
+            &a,
+            user_line("u1", "2025-01-01T00:00:00Z", "why does NOT work") + "\n",
+        )
+        .unwrap();
+
+        let mut index = SearchIndex::open(&temp.path().join("index.sqlite")).unwrap();
+        index.refresh(&[a]).unwrap();
+
+        assert_eq!(index.search("does NOT", None, 10).unwrap().len(), 1);
+        assert!(index.search("\"unbalanced", None, 10).unwrap().is_empty());
+        assert!(index
+            .search("work", Some("/elsewhere"), 10)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_search_project_prefix_excludes_sibling_projects() {
+        let temp = TempDir::new().unwrap();
+        let app = temp.path().join("-Users-me-app");
+        let app_v2 = temp.path().join("-Users-me-app-v2");
+        fs::create_dir_all(&app).unwrap();
+        fs::create_dir_all(&app_v2).unwrap();
+        let a = app.join("a.jsonl");
+        let b = app_v2.join("b.jsonl");
+        fs::write(
+            &a,
+            user_line("u1", "2025-01-01T00:00:00Z", "login bug") + "\n",
+        )
+        .unwrap();
+        fs::write(
+            &b,
+            user_line("u2", "2025-01-02T00:00:00Z", "login bug") + "\n",
+        )
+        .unwrap();
+
+        let mut index = SearchIndex::open(&temp.path().join("index.sqlite")).unwrap();
+        index.refresh(&[a, b]).unwrap();
+
+        let app_path = app.to_string_lossy().to_string();
+        let results = index.search("login", Some(&app_path), 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].uuid, "u1");
+        let with_separator = format!("{app_path}{}", std::path::MAIN_SEPARATOR);
+        assert_eq!(
+            index
+                .search("login", Some(&with_separator), 10)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_extract_errors_recognizes_common_formats() {
+        let output = "running 1 test\n\
+            thread 'tests::parse' panicked at src/parser.rs:42:9:\n\
+            called `Option::unwrap()` on a `None` value\n\
+            error[E0308]: mismatched types\n  --> src/main.rs:3:5\n\
+            Traceback (most recent call last):\n  File \"app.py\", line 3, in <module>\n    main()\n\
+            KeyError: 'user_id'\n\
+            TypeError: Cannot read properties of undefined (reading 'map')\n    at render (app.js:10:5)";
+
+        let errors = extract_errors(output, false);
+        let found: Vec<(&str, &str, &str)> = errors
+            .iter()
+            .map(|e| (e.kind, e.message.as_str(), e.context.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "panic",
+                    "called `Option::unwrap()` on a `None` value",
+                    "src/parser.rs:42:9"
+                ),
+                (
+                    "compile_error",
+                    "error[E0308]: mismatched types",
+                    "src/main.rs:3:5"
+                ),
+                (
+                    "exception",
+                    "KeyError: 'user_id'",
+                    "File \"app.py\", line 3, in <module>"
+                ),
+                (
+                    "exception",
+                    "TypeError: Cannot read properties of undefined (reading 'map')",
+                    "at render (app.js:10:5)"
+                ),
+            ]
+        );
+
+        assert!(extract_errors("all 12 tests passed", false).is_empty());
+        let failed = extract_errors("\n\u{1b}[31mcommand not found: foo\u{1b}[0m", true);
+        assert_eq!(failed[0].message, "command not found: foo");
+    }
+
+    #[test]
+    fn test_error_signature_drops_varying_details() {
+        assert_eq!(
+            error_signature("index out of bounds: the len is 3 but the index is 7"),
+            error_signature("index out of bounds: the len is 10 but the index is 12"),
+        );
+        assert_eq!(
+            error_signature("No such file: /tmp/build-123/out.o at 0x7ffe12"),
+            "No such file: <path> at <addr>"
+        );
+        assert_eq!(error_signature("KeyError: 'user_id'"), "KeyError: <value>");
+    }
+
+    #[test]
+    fn test_refresh_indexes_tool_errors() {
+        let temp = TempDir::new().unwrap();
+        let a = temp.path().join("a.jsonl");
+        let lines = [
+            r#"{"uuid":"a1","sessionId":"s1","timestamp":"2025-01-01T00:00:00Z","type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"cargo test"}}]}}"#,
+            r#"{"uuid":"u1","sessionId":"s1","timestamp":"2025-01-01T00:00:05Z","type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","is_error":true,"content":"thread 'main' panicked at src/lib.rs:7:5:\nattempt to subtract with overflow"}]}}"#,
+        ];
+        fs::write(&a, lines.join("\n") + "\n").unwrap();
+
+        let mut index = SearchIndex::open(&temp.path().join("index.sqlite")).unwrap();
+        index.refresh(&[a]).unwrap();
+        assert_eq!(index.error_count().unwrap(), 1);
+
+        let found = index
+            .search_errors("subtract with overflow", None, None, None, 10)
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].uuid, "u1");
+        assert_eq!(found[0].kind, "panic");
+        assert_eq!(found[0].tool_name.as_deref(), Some("Bash"));
+        assert_eq!(found[0].context, "src/lib.rs:7:5");
+
+        assert_eq!(
+            index
+                .search_errors("", Some("panic"), None, None, 10)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(index
+            .search_errors("", Some("exception"), None, None, 10)
+            .unwrap()
+            .is_empty());
+
+        index.refresh(&[]).unwrap();
+        assert_eq!(index.error_count().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_stack_frames_are_normalized() {
+        let rust = [
+            "stack backtrace:",
+            "   0: rust_begin_unwind",
+            "             at /rustc/abc/library/std/src/panicking.rs:645:5",
+            "   1: core::panicking::panic_fmt",
+            "   2: app::parser::parse_line::h0123456789abcdef",
+            "             at ./src/parser.rs:42:9",
+            "   3: app::main",
+            "note: Some details are omitted",
+        ];
+        assert_eq!(
+            rust_frames(&rust[1..]),
+            vec!["app::parser::parse_line", "app::main"]
+        );
+
+        let python = [
+            "  File \"/srv/app/main.py\", line 10, in <module>",
+            "    run()",
+            "  File \"/srv/app/jobs.py\", line 3, in run",
+        ];
+        assert_eq!(python_frames(&python), vec!["jobs.run", "main.<module>"]);
+
+        let javascript = [
+            "    at async render (/app/src/view.js:10:5)",
+            "    at /app/src/index.js:3:1",
+            "Done in 2s",
+        ];
+        assert_eq!(javascript_frames(&javascript), vec!["render", "index"]);
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_line_numbers_and_values() {
+        let traceback = |line: u32, key: &str, function: &str| {
+            format!(
+                "Traceback (most recent call last):\n  File \"app.py\", line {line}, in {function}\n    load()\nKeyError: '{key}'"
+            )
+        };
+        let fingerprint = |text: &str| error_fingerprint(&extract_errors(text, true)[0]);
+
+        let first = fingerprint(&traceback(3, "user_id", "main"));
+        assert_eq!(first.len(), 16);
+        assert_eq!(first, fingerprint(&traceback(7, "email", "main")));
+        assert_ne!(first, fingerprint(&traceback(3, "user_id", "worker")));
+
+        // Without a stack, the location counts without its line numbers
+        let panic =
+            |location: &str| fingerprint(&format!("thread 'main' panicked at {location}:\nboom"));
+        assert_eq!(panic("src/a.rs:1:5"), panic("src/a.rs:9:1"));
+        assert_ne!(panic("src/a.rs:1:5"), panic("src/b.rs:1:5"));
+    }
+
+    #[test]
+    fn test_error_groups_merge_occurrences_across_sessions() {
+        let temp = TempDir::new().unwrap();
+        let failure = |session: &str, ts: &str, line: u32| {
+            format!(
+                r#"{{"uuid":"{session}-u","sessionId":"{session}","timestamp":"{ts}","type":"user","message":{{"role":"user","content":[{{"type":"tool_result","tool_use_id":"t","is_error":true,"content":"thread 'main' panicked at src/lib.rs:{line}:5:\nindex out of bounds: the len is {line} but the index is 9"}}]}}}}"#
+            ) + "\n"
+        };
+        let a = temp.path().join("a.jsonl");
+        let b = temp.path().join("b.jsonl");
+        fs::write(
+            &a,
+            failure("s1", "2025-01-01T00:00:00Z", 7) + &failure("s1", "2025-01-03T00:00:00Z", 8),
+        )
+        .unwrap();
+        fs::write(&b, failure("s2", "2025-01-02T00:00:00Z", 12)).unwrap();
+
+        let mut index = SearchIndex::open(&temp.path().join("index.sqlite")).unwrap();
+        index.refresh(&[a, b]).unwrap();
+
+        let groups = index.error_groups(None, None, 10).unwrap();
+        assert_eq!(groups.len(), 1);
+        let group = &groups[0];
+        assert_eq!(group.occurrences, 3);
+        assert_eq!(group.session_ids, vec!["s1", "s2"]);
+        assert_eq!(group.first_seen, "2025-01-01T00:00:00Z");
+        assert_eq!(group.last_seen, "2025-01-03T00:00:00Z");
+        assert_eq!(group.context, "src/lib.rs:8:5");
+
+        let occurrences = index
+            .search_errors("", None, Some(&group.fingerprint), None, 10)
+            .unwrap();
+        assert_eq!(occurrences.len(), 3);
+        assert!(index
+            .error_groups(Some("exception"), None, 10)
+            .unwrap()
+            .is_empty());
+    }
+}
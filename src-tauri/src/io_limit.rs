@@ -0,0 +1,209 @@
+//! Concurrency limit for open session files
+//!
+//! Several scans can run at once, each opening one file per worker thread and
+//! keeping it mapped while parsing. On machines with a low `ulimit -n` or on
+//! network filesystems this can exhaust file descriptors, so scanning code
+//! takes a permit from a process-wide semaphore before opening a file.
+//!
+//! Permits must only be held around sequential work: a thread that holds a
+//! permit and then waits on nested parallel work could steal a job that needs
+//! another permit and deadlock.
+//!
+//! The `lowMemory` setting trades speed for a smaller footprint: it lowers
+//! the file limit (which bounds how many sessions are parsed at once), lets
+//! only one search run at a time and makes other modules skip their
+//! in-memory caches and stream session files instead of reading them whole.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, OnceLock};
+
+/// Default limit per platform, well below the usual soft descriptor limit
+/// (256 on macOS, 1024 on most Linux distributions)
+#[cfg(target_os = "macos")]
+pub const DEFAULT_MAX_OPEN_FILES: usize = 64;
+#[cfg(target_os = "windows")]
+pub const DEFAULT_MAX_OPEN_FILES: usize = 128;
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub const DEFAULT_MAX_OPEN_FILES: usize = 256;
+
+/// File limit in low-memory mode (unless `maxOpenFiles` is lower)
+pub const LOW_MEMORY_MAX_OPEN_FILES: usize = 2;
+
+/// Searches running at once in low-memory mode
+pub const LOW_MEMORY_MAX_SEARCHES: usize = 1;
+
+/// Counting semaphore bounding the number of files open at once
+pub struct FileSemaphore {
+    state: Mutex<SemaphoreState>,
+    available: Condvar,
+}
+
+struct SemaphoreState {
+    in_use: usize,
+    limit: usize,
+}
+
+/// An acquired slot; released when dropped
+pub struct FilePermit<'a> {
+    semaphore: &'a FileSemaphore,
+}
+
+impl FileSemaphore {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            state: Mutex::new(SemaphoreState {
+                in_use: 0,
+                limit: limit.max(1),
+            }),
+            available: Condvar::new(),
+        }
+    }
+
+    /// Block until a slot is free
+    pub fn acquire(&self) -> FilePermit<'_> {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        while state.in_use >= state.limit {
+            state = self
+                .available
+                .wait(state)
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+        }
+        state.in_use += 1;
+        FilePermit { semaphore: self }
+    }
+
+    /// Change the limit; permits already handed out stay valid
+    pub fn set_limit(&self, limit: usize) {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        state.limit = limit.max(1);
+        self.available.notify_all();
+    }
+
+    pub fn limit(&self) -> usize {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .limit
+    }
+}
+
+impl Drop for FilePermit<'_> {
+    fn drop(&mut self) {
+        let mut state = self
+            .semaphore
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        state.in_use -= 1;
+        self.semaphore.available.notify_one();
+    }
+}
+
+static FILE_SEMAPHORE: OnceLock<FileSemaphore> = OnceLock::new();
+static SEARCH_SEMAPHORE: OnceLock<FileSemaphore> = OnceLock::new();
+
+/// The `maxOpenFiles` setting (0 when unset)
+static MAX_OPEN_FILES: AtomicUsize = AtomicUsize::new(0);
+static LOW_MEMORY: AtomicBool = AtomicBool::new(false);
+
+fn file_semaphore() -> &'static FileSemaphore {
+    FILE_SEMAPHORE.get_or_init(|| FileSemaphore::new(DEFAULT_MAX_OPEN_FILES))
+}
+
+fn search_semaphore() -> &'static FileSemaphore {
+    SEARCH_SEMAPHORE.get_or_init(|| FileSemaphore::new(usize::MAX))
+}
+
+/// Take a permit before opening a session file during a scan
+pub fn acquire_file_permit() -> FilePermit<'static> {
+    file_semaphore().acquire()
+}
+
+/// Take a permit for the duration of a search, outside the rayon pool
+pub fn acquire_search_permit() -> FilePermit<'static> {
+    search_semaphore().acquire()
+}
+
+fn apply_limits() {
+    let configured = match MAX_OPEN_FILES.load(Ordering::Relaxed) {
+        0 => DEFAULT_MAX_OPEN_FILES,
+        limit => limit,
+    };
+    if low_memory() {
+        file_semaphore().set_limit(configured.min(LOW_MEMORY_MAX_OPEN_FILES));
+        search_semaphore().set_limit(LOW_MEMORY_MAX_SEARCHES);
+    } else {
+        file_semaphore().set_limit(configured);
+        search_semaphore().set_limit(usize::MAX);
+    }
+}
+
+/// Apply the user's `maxOpenFiles` setting (None restores the platform default)
+pub fn set_max_open_files(limit: Option<usize>) {
+    MAX_OPEN_FILES.store(limit.map_or(0, |limit| limit.max(1)), Ordering::Relaxed);
+    apply_limits();
+}
+
+/// Apply the user's `lowMemory` setting
+pub fn set_low_memory(enabled: bool) {
+    LOW_MEMORY.store(enabled, Ordering::Relaxed);
+    apply_limits();
+}
+
+/// Whether low-memory mode is on
+pub fn low_memory() -> bool {
+    LOW_MEMORY.load(Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_semaphore_bounds_concurrency() {
+        let semaphore = Arc::new(FileSemaphore::new(2));
+        let current = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let (semaphore, current, peak) = (
+                    Arc::clone(&semaphore),
+                    Arc::clone(&current),
+                    Arc::clone(&peak),
+                );
+                std::thread::spawn(move || {
+                    let _permit = semaphore.acquire();
+                    let now = current.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(std::time::Duration::from_millis(5));
+                    current.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[test]
+    fn test_limit_is_at_least_one() {
+        let semaphore = FileSemaphore::new(0);
+        assert_eq!(semaphore.limit(), 1);
+
+        semaphore.set_limit(8);
+        let _a = semaphore.acquire();
+        semaphore.set_limit(0);
+        assert_eq!(semaphore.limit(), 1);
+    }
+}
@@ -1,28 +1,93 @@
 pub mod commands;
+pub mod counting;
+pub mod derived;
+pub mod errors;
+pub mod freshness;
+pub mod index;
+pub mod io_limit;
 pub mod models;
+pub mod pricing;
+pub mod redaction;
+pub mod summarizer;
 pub mod utils;
 
 #[cfg(test)]
 pub mod test_utils;
 
 use crate::commands::{
+    anomalies::{self, get_cost_anomalies},
+    archive::{get_archive_history, run_archive, start_archive_scheduler},
+    attention::{self, unwatch_session, watch_session},
+    branch_costs::get_branch_cost_report,
+    budget::{self, get_budget_status, start_budget_watcher},
+    changelog::generate_daily_changelog,
+    churn::{get_project_churn, get_session_churn},
+    compare::compare_sessions,
+    context_budget::get_session_budget,
+    cost_report::get_cost_report,
+    digest::{generate_weekly_digest, start_digest_scheduler},
+    entities::get_entity_graph,
+    error_report::get_error_report,
+    expensive_messages::get_top_expensive_messages,
+    export::{
+        export_daily_stats_csv, export_model_stats_csv, export_project, export_session_claude_ai,
+        export_session_html, export_session_json, export_session_markdown, export_session_pdf,
+        export_session_prompt, export_session_token_stats_csv, export_sidechain_transcript,
+        export_warehouse, import_shared_session, share_session,
+    },
     feedback::{get_system_info, open_github_issues, send_feedback},
+    file_history::{compact_file_history, get_file_history_usage},
+    focus::get_focus_report,
+    hooks::{get_hook_latency_stats, get_hook_stats},
+    journal::{get_operation_journal, undo_operation},
+    lint::lint_session_file,
+    local_file::read_local_file,
+    message_types::get_message_type_stats,
     metadata::{
-        get_metadata_folder_path, get_session_display_name, is_project_hidden, load_user_metadata,
-        save_user_metadata, update_project_metadata, update_session_metadata, update_user_settings,
-        MetadataState,
+        archive_session, export_metadata, get_metadata_folder_path, get_session_display_name,
+        import_metadata, is_project_hidden, load_user_metadata, preview_derived_field,
+        save_user_metadata, set_project_budget, update_project_metadata, update_session_metadata,
+        update_user_settings, MetadataState,
+    },
+    pricing_catalog::{load_cached_pricing_catalog, sync_pricing_catalog},
+    pricing_file::{self, get_pricing_file_status, load_pricing_file, start_pricing_file_watcher},
+    profile::profile_operation,
+    project::{
+        self, get_claude_folder_path, scan_projects, start_project_watcher, validate_claude_folder,
     },
-    project::{get_claude_folder_path, scan_projects, validate_claude_folder},
+    prompt_quality::{get_prompt_length_stats, get_prompt_quality_report},
+    retry_loops::get_retry_loops,
+    reveal::reveal_path,
     session::{
-        get_recent_edits, get_session_message_count, load_project_sessions, load_session_messages,
-        load_session_messages_paginated, restore_file, search_messages,
+        self, cancel_search, find_sessions_by_file, fuzzy_find_sessions, get_error_groups,
+        get_file_activity_stats, get_history_source_folder_path, get_project_summaries,
+        get_raw_entry, get_recent_edits, get_session_graph, get_session_message_count,
+        get_session_personas, get_session_tree, load_project_sessions, load_session_messages,
+        load_session_messages_paginated, load_source_session_messages, load_source_sessions,
+        refresh_search_index, repair_session_links, restore_file, search_all_projects,
+        search_errors, search_in_session, search_indexed_messages, search_messages,
+        search_project_messages, search_tool_invocations, stop_tail_raw, summarize_session,
+        tail_raw,
     },
+    sidechains::get_sidechain_stats,
+    slash_commands::get_slash_command_stats,
     stats::{
         get_global_stats_summary, get_project_stats_summary, get_project_token_stats,
-        get_session_comparison, get_session_token_stats,
+        get_session_comparison, get_session_token_stats, get_token_histograms,
+    },
+    timeline::get_session_timeline,
+    trash::{delete_project, delete_session},
+    usage_blocks::{get_burn_rate, get_usage_blocks},
+    usage_metrics::{
+        export_local_usage_report, flush_usage_metrics, get_local_usage_report,
+        record_feature_usage, reset_local_usage_metrics,
     },
+    usage_sink::{get_usage_sink_status, start_usage_sink},
+    waste::get_wasted_token_estimate,
 };
 
+use tauri::{Emitter, Manager, UserAttentionType};
+
 #[cfg(not(debug_assertions))]
 use dotenvy_macro::dotenv;
 #[cfg(not(debug_assertions))]
@@ -49,6 +114,76 @@ pub fn run() {
     }
     builder
         .manage(MetadataState::default())
+        .manage(session::SearchCancellations::default())
+        .setup(|app| {
+            load_cached_pricing_catalog();
+            load_pricing_file();
+            let handle = app.handle().clone();
+            freshness::set_refresh_listener(move |event| {
+                if let Err(e) = handle.emit(freshness::SESSION_REFRESHED_EVENT, event) {
+                    eprintln!("Failed to emit session refresh event: {e}");
+                }
+            });
+            let handle = app.handle().clone();
+            session::set_project_search_listener(move |event| {
+                if let Err(e) = handle.emit(session::PROJECT_SEARCH_RESULTS_EVENT, event) {
+                    eprintln!("Failed to emit project search results: {e}");
+                }
+            });
+            let handle = app.handle().clone();
+            session::set_raw_tail_listener(move |event| {
+                if let Err(e) = handle.emit(session::RAW_TAIL_EVENT, event) {
+                    eprintln!("Failed to emit raw tail lines: {e}");
+                }
+            });
+            let handle = app.handle().clone();
+            anomalies::set_anomaly_listener(move |anomaly| {
+                if let Err(e) = handle.emit(anomalies::COST_ANOMALY_EVENT, anomaly) {
+                    eprintln!("Failed to emit cost anomaly event: {e}");
+                }
+            });
+            let handle = app.handle().clone();
+            attention::set_attention_listener(move |event| {
+                if let Err(e) = handle.emit(attention::SESSION_AWAITING_INPUT_EVENT, event) {
+                    eprintln!("Failed to emit session awaiting input event: {e}");
+                }
+                if let Some(window) = handle.get_webview_window("main") {
+                    let _ = window.request_user_attention(Some(UserAttentionType::Informational));
+                }
+            });
+            let handle = app.handle().clone();
+            attention::set_run_summary_listener(move |summary| {
+                if let Err(e) = handle.emit(attention::SESSION_RUN_FINISHED_EVENT, summary) {
+                    eprintln!("Failed to emit session run finished event: {e}");
+                }
+            });
+            let handle = app.handle().clone();
+            pricing_file::set_pricing_file_listener(move |status| {
+                if let Err(e) = handle.emit(pricing_file::PRICING_FILE_RELOADED_EVENT, status) {
+                    eprintln!("Failed to emit pricing file reload event: {e}");
+                }
+            });
+            let handle = app.handle().clone();
+            budget::set_budget_listener(move |crossed| {
+                if let Err(e) = handle.emit(budget::BUDGET_THRESHOLD_EVENT, crossed) {
+                    eprintln!("Failed to emit budget threshold event: {e}");
+                }
+            });
+            let handle = app.handle().clone();
+            project::set_project_added_listener(move |project| {
+                if let Err(e) = handle.emit(project::PROJECT_ADDED_EVENT, project) {
+                    eprintln!("Failed to emit project added event: {e}");
+                }
+            });
+            attention::start_attention_watcher();
+            start_pricing_file_watcher();
+            start_archive_scheduler();
+            start_digest_scheduler();
+            start_budget_watcher();
+            start_usage_sink();
+            start_project_watcher();
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             get_claude_folder_path,
             validate_claude_folder,
@@ -57,14 +192,96 @@ pub fn run() {
             load_session_messages,
             load_session_messages_paginated,
             get_session_message_count,
+            get_history_source_folder_path,
+            load_source_sessions,
+            load_source_session_messages,
             search_messages,
+            search_project_messages,
+            refresh_search_index,
+            search_errors,
+            get_error_groups,
+            search_indexed_messages,
+            search_all_projects,
+            search_tool_invocations,
+            search_in_session,
+            cancel_search,
+            tail_raw,
+            stop_tail_raw,
+            fuzzy_find_sessions,
+            get_session_graph,
+            get_session_tree,
+            get_session_personas,
+            get_raw_entry,
+            repair_session_links,
+            get_project_summaries,
+            summarize_session,
             get_recent_edits,
+            get_file_activity_stats,
+            find_sessions_by_file,
             restore_file,
             get_session_token_stats,
             get_project_token_stats,
             get_project_stats_summary,
             get_session_comparison,
+            compare_sessions,
+            get_session_budget,
             get_global_stats_summary,
+            get_token_histograms,
+            get_prompt_quality_report,
+            get_prompt_length_stats,
+            get_retry_loops,
+            get_sidechain_stats,
+            get_slash_command_stats,
+            get_message_type_stats,
+            get_error_report,
+            reveal_path,
+            delete_session,
+            delete_project,
+            get_wasted_token_estimate,
+            get_session_churn,
+            get_project_churn,
+            generate_daily_changelog,
+            get_cost_anomalies,
+            watch_session,
+            unwatch_session,
+            get_top_expensive_messages,
+            get_cost_report,
+            generate_weekly_digest,
+            get_branch_cost_report,
+            get_usage_blocks,
+            get_burn_rate,
+            get_focus_report,
+            get_session_timeline,
+            get_budget_status,
+            get_usage_sink_status,
+            set_project_budget,
+            get_hook_latency_stats,
+            get_hook_stats,
+            get_operation_journal,
+            undo_operation,
+            get_file_history_usage,
+            compact_file_history,
+            run_archive,
+            get_archive_history,
+            export_session_claude_ai,
+            export_session_markdown,
+            export_session_html,
+            export_session_json,
+            export_session_pdf,
+            export_session_prompt,
+            export_project,
+            export_session_token_stats_csv,
+            export_daily_stats_csv,
+            export_model_stats_csv,
+            export_sidechain_transcript,
+            export_warehouse,
+            share_session,
+            import_shared_session,
+            lint_session_file,
+            read_local_file,
+            sync_pricing_catalog,
+            get_pricing_file_status,
+            get_entity_graph,
             send_feedback,
             get_system_info,
             open_github_issues,
@@ -73,17 +290,34 @@ pub fn run() {
             load_user_metadata,
             save_user_metadata,
             update_session_metadata,
+            archive_session,
             update_project_metadata,
             update_user_settings,
+            export_metadata,
+            import_metadata,
             is_project_hidden,
-            get_session_display_name
+            get_session_display_name,
+            preview_derived_field,
+            // Local usage metrics commands
+            record_feature_usage,
+            get_local_usage_report,
+            export_local_usage_report,
+            reset_local_usage_metrics,
+            profile_operation
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
-        .run(|_handler, _event| {
+        .run(|_handler, event| {
+            // Persist local usage metrics (never leaves the machine)
+            if matches!(event, tauri::RunEvent::Exit) {
+                if let Err(e) = flush_usage_metrics() {
+                    eprintln!("Failed to save local usage metrics: {e}");
+                }
+            }
+
             // Production only: track app lifecycle events
             #[cfg(not(debug_assertions))]
-            match _event {
+            match event {
                 tauri::RunEvent::Ready { .. } => {
                     let _ = _handler.track_event("app_started", None);
                 }
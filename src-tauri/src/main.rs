@@ -3,6 +3,32 @@
     windows_subsystem = "windows"
 )]
 
+use claude_code_history_viewer_lib::commands::digest::run_weekly_digest_cli;
+
+/// Release builds on Windows start without a console: attach to the one of
+/// the launching terminal so the printed digest and errors show up there
+#[cfg(target_os = "windows")]
+#[allow(unsafe_code)] // Single Win32 call, no bindings crate needed
+fn attach_parent_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    // Fails harmlessly when there is no parent console or one is attached
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("--weekly-digest") {
+        #[cfg(target_os = "windows")]
+        attach_parent_console();
+        std::process::exit(run_weekly_digest_cli(&args[1..]));
+    }
     claude_code_history_viewer_lib::run();
 }
@@ -2,18 +2,76 @@
 //!
 //! This module contains all the data structures used throughout the application.
 
+mod anomaly;
+mod archive;
+mod budget;
+mod churn;
+mod context_budget;
+mod cost_report;
+mod digest;
 mod edit;
+mod entity;
+mod error_report;
+mod expensive_message;
+mod export;
+mod file_history;
+mod focus;
+mod graph;
+mod hooks;
+mod journal;
+mod lint;
 mod message;
+mod message_type;
 mod metadata;
+mod persona;
+mod pricing;
+mod prompt_quality;
+mod retry_loop;
 mod session;
+mod slash_command;
 mod stats;
+mod timeline;
+mod trash;
+mod usage_block;
+mod usage_metrics;
+mod usage_sink;
+mod waste;
 
 #[cfg(test)]
 mod snapshot_tests;
 
 // Re-export all types for backward compatibility
+pub use anomaly::*;
+pub use archive::*;
+pub use budget::*;
+pub use churn::*;
+pub use context_budget::*;
+pub use cost_report::*;
+pub use digest::*;
 pub use edit::*;
+pub use entity::*;
+pub use error_report::*;
+pub use expensive_message::*;
+pub use export::*;
+pub use file_history::*;
+pub use focus::*;
+pub use graph::*;
+pub use hooks::*;
+pub use journal::*;
+pub use lint::*;
 pub use message::*;
+pub use message_type::*;
 pub use metadata::*;
+pub use persona::*;
+pub use pricing::*;
+pub use prompt_quality::*;
+pub use retry_loop::*;
 pub use session::*;
+pub use slash_command::*;
 pub use stats::*;
+pub use timeline::*;
+pub use trash::*;
+pub use usage_block::*;
+pub use usage_metrics::*;
+pub use usage_sink::*;
+pub use waste::*;
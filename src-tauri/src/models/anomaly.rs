@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// Estimated spend and token usage of one calendar day (UTC)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DailyCost {
+    pub date: String, // YYYY-MM-DD
+    pub cost_usd: f64,
+    pub total_tokens: u64,
+}
+
+/// A day whose spend or token usage is far above its rolling baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostAnomaly {
+    pub date: String,
+    pub cost_usd: f64,
+    pub total_tokens: u64,
+    pub baseline_cost_usd: f64, // Mean daily cost over the preceding window
+    pub baseline_tokens: f64,
+    pub cost_z_score: Option<f64>, // None when the baseline had no spend
+    pub token_z_score: Option<f64>,
+    pub cost_ratio: Option<f64>, // cost_usd / baseline_cost_usd
+}
+
+/// Daily cost series of a date range with its anomalous days
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostAnomalyReport {
+    pub window_days: usize,
+    pub z_threshold: f64,
+    pub daily: Vec<DailyCost>,       // Every day of the range, oldest first
+    pub anomalies: Vec<CostAnomaly>, // Oldest first
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// A session file stored in an archive
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArchivedSession {
+    pub file_path: String,  // Original location
+    pub entry_name: String, // Path inside the archive (`<project>/<file>`)
+    pub size: u64,
+    pub last_modified: String,
+    pub removed: bool, // Original deleted after archiving
+}
+
+/// One archiving run, as recorded in the archive folder's manifest
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArchiveRun {
+    pub archive_path: Option<String>, // None when nothing was due
+    pub format: String,               // "zip" or "tar.zst"
+    pub trigger: String,              // "startup", "interval" or "manual"
+    pub started_at: String,
+    pub cutoff: String, // Sessions modified before this were archived
+    pub session_count: usize,
+    pub original_bytes: u64,
+    pub archive_bytes: u64,
+    pub sessions: Vec<ArchivedSession>,
+    pub failed: Vec<String>, // "path: error" of sessions left out
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+/// Consumption of one budgeted quantity (tokens or USD) this month
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BudgetUsage {
+    pub used: f64,
+    pub budget: f64,
+    pub percent: f64,           // used / budget * 100
+    pub projected: f64,         // Month-end total at the recent daily rate
+    pub projected_percent: f64, // projected / budget * 100
+}
+
+/// Budget consumption of one project in the current month
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BudgetStatus {
+    pub project_path: String,
+    pub project_name: String,
+    pub month: String,     // "2025-03" (UTC)
+    pub days_elapsed: u32, // Including today
+    pub days_in_month: u32,
+    pub tokens: Option<BudgetUsage>, // Unset without a token budget
+    pub cost: Option<BudgetUsage>,   // Unset without a cost budget
+    pub crossed_threshold: Option<u32>, // Highest threshold reached by either
+}
+
+/// Payload of the event emitted when a project crosses a budget threshold
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BudgetThresholdCrossed {
+    pub project_path: String,
+    pub project_name: String,
+    pub month: String,
+    pub metric: String, // "tokens" or "cost"
+    pub threshold: u32, // Percent
+    pub usage: BudgetUsage,
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// Lines written by edits and how many of them were taken back again
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ChurnStats {
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub churned_lines: usize, // Added lines removed again within the window
+    pub churn_ratio: f64,     // churned_lines / lines_added (0 when nothing was added)
+}
+
+/// Churn of a single edited file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileChurn {
+    pub file_path: String,
+    pub stats: ChurnStats,
+}
+
+/// Churn of the edits made within one session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionChurn {
+    pub session_id: String,
+    pub file_path: String, // Session JSONL file
+    pub edit_count: usize,
+    pub stats: ChurnStats,
+    pub files: Vec<FileChurn>, // Most churned first
+}
+
+/// Churn of a project, where lines removed by a later session within
+/// `window_hours` also count
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectChurn {
+    pub project_name: String,
+    pub window_hours: u32,
+    pub session_count: usize, // Sessions with at least one edit
+    pub stats: ChurnStats,
+    pub sessions: Vec<SessionChurn>, // Most churned first
+    pub files: Vec<FileChurn>,       // Most churned first, capped
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// A compaction of the conversation context
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactionEvent {
+    pub uuid: Option<String>,
+    pub timestamp: Option<String>,
+    pub kind: String,            // "compact" or "microcompact"
+    pub trigger: Option<String>, // "manual", "auto", ...
+    pub pre_tokens: Option<u64>, // Context size before compacting
+}
+
+/// Context usage of a session, for a live budget bar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionBudget {
+    pub session_id: String,
+    pub model: Option<String>, // Model of the last response
+    pub context_tokens: u64,   // Estimated current context size
+    pub context_limit: u64,    // Context window of the model
+    pub percent_used: f64,     // 0-100
+    pub remaining_tokens: u64,
+    pub since_compaction: bool, // No response since the last compaction (context_tokens is 0)
+    pub last_auto_compact_tokens: Option<u64>, // Context size the last auto-compaction ran at
+    pub compactions: Vec<CompactionEvent>,
+    pub last_updated: Option<String>, // Timestamp of the last response
+}
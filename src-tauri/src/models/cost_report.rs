@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+/// Cost of one project within a period
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProjectCost {
+    pub project_name: String,
+    pub cost_usd: f64,
+    pub total_tokens: u64,
+}
+
+/// Cost of one calendar month, ISO week or billing cycle
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CostPeriod {
+    pub period: String,     // "2025-01", "2025-W03" or the cycle's first day
+    pub start_date: String, // First day (YYYY-MM-DD, UTC)
+    pub end_date: String,   // Last day, inclusive
+    pub cost_usd: f64,
+    pub total_tokens: u64,
+    pub response_count: u64,
+    pub session_count: usize,
+    pub projects: Vec<ProjectCost>, // Most expensive first
+}
+
+/// Cost rolled up by month, ISO week and billing cycle, oldest period first
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CostReport {
+    pub scope: String,
+    pub billing_anchor_day: u32, // Day of the month billing cycles start on
+    pub total_cost_usd: f64,
+    pub months: Vec<CostPeriod>,
+    pub weeks: Vec<CostPeriod>,
+    pub billing_periods: Vec<CostPeriod>,
+}
+
+/// Usage and cost of the responses written on one git branch
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BranchCost {
+    pub branch: Option<String>, // None when the log records no branch
+    pub project_names: Vec<String>,
+    pub session_count: usize,
+    pub response_count: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+    pub first_activity: String, // RFC 3339
+    pub last_activity: String,
+}
+
+/// Usage and cost by git branch, most expensive branch first
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BranchCostReport {
+    pub scope: String,
+    pub total_cost_usd: f64,
+    pub branches: Vec<BranchCost>,
+}
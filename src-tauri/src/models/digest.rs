@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// Digest of one week of activity, rendered for email or chat
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyDigest {
+    pub start_date: String, // YYYY-MM-DD (Monday)
+    pub end_date: String,   // YYYY-MM-DD (Sunday)
+    pub format: String,     // "markdown" or "html"
+    pub content: String,
+    pub session_count: usize,
+    pub project_count: usize,
+    pub total_cost_usd: f64,
+    pub files_changed: usize,
+    pub error_count: usize,
+    pub current_streak_days: u32, // Consecutive active days up to the end of the week
+}
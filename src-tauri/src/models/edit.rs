@@ -23,6 +23,67 @@ pub struct RecentEditsResult {
     pub project_cwd: Option<String>, // Most common working directory for this project
 }
 
+/// Edit activity on one file across a project's history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileActivity {
+    pub file_path: String,
+    pub relative_path: String, // Relative to the project cwd when inside it
+    pub edit_count: usize,     // Edit and MultiEdit operations
+    pub write_count: usize,    // Write operations (new files and full rewrites)
+    pub session_count: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub first_touched: String,
+    pub last_touched: String,
+}
+
+/// Files of a project ranked by how often they were edited
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileActivityStats {
+    pub project_cwd: Option<String>,
+    pub total_edits: usize, // All Edit/Write operations, including files past the limit
+    pub unique_files: usize,
+    pub files: Vec<FileActivity>, // Most edited first
+}
+
+/// One Read/Edit/Write tool call on a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileTouch {
+    pub message_uuid: String,
+    pub timestamp: String,
+    pub tool_name: String,
+    pub operation: String, // "read", "edit" or "write"
+}
+
+/// A session that read or modified a given file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSessionMatch {
+    pub project_name: String,
+    pub session_id: String,
+    pub file_path: String,    // Session JSONL file
+    pub touched_path: String, // File path as written in the tool call
+    pub first_touched: String,
+    pub last_touched: String,
+    pub read_count: usize,
+    pub modify_count: usize,
+    pub touches: Vec<FileTouch>, // Oldest first
+}
+
+/// Current content of a local file (or a line range of it)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalFileContent {
+    pub path: String,
+    pub exists: bool, // False when the file has been deleted since
+    pub is_binary: bool,
+    pub language: String, // Syntax highlighting language ("text" when unknown)
+    pub size_bytes: u64,
+    pub modified: Option<String>, // RFC 3339
+    pub total_lines: usize,
+    pub start_line: usize, // 1-based, inclusive; 0 when no line is returned
+    pub end_line: usize,
+    pub content: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
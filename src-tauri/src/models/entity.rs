@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// An entity mentioned in conversations
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EntityRef {
+    pub kind: String, // "file", "package", "url" or "issue"
+    pub value: String,
+}
+
+/// A session that mentions the queried entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntitySessionRef {
+    pub session_id: String,
+    pub project_name: String,
+    pub file_path: String,
+    pub mention_count: usize,
+    pub first_mentioned_at: Option<String>,
+    pub matched: Vec<EntityRef>, // Entities in this session that matched the query
+}
+
+/// An entity that co-occurs with the queried entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedEntity {
+    pub entity: EntityRef,
+    pub shared_session_count: usize,
+}
+
+/// Sessions linked through a shared entity, plus neighbouring entities to explore next
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityGraph {
+    pub query: String,
+    pub sessions: Vec<EntitySessionRef>, // Sorted by mention count (descending)
+    pub related_entities: Vec<RelatedEntity>, // Sorted by shared session count (descending)
+    pub total_sessions_scanned: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entity_ref_ordering() {
+        let file = EntityRef {
+            kind: "file".to_string(),
+            value: "src/main.rs".to_string(),
+        };
+        let url = EntityRef {
+            kind: "url".to_string(),
+            value: "https://example.com".to_string(),
+        };
+
+        assert!(file < url);
+        let json = serde_json::to_value(&file).unwrap();
+        assert_eq!(json["kind"], "file");
+        assert_eq!(json["value"], "src/main.rs");
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+/// One error found in a session log
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ErrorExample {
+    pub session_id: String,
+    pub message_uuid: Option<String>, // Entry reporting the error
+    pub timestamp: Option<String>,
+    pub tool_name: Option<String>, // Failed tool, for tool and hook failures
+    pub message: String,           // Truncated to 200 characters
+}
+
+/// Errors of one category: "`tool_failure`", "`api_error`", "`rate_limit`",
+/// "`hook_failure`" or "`system_error`"
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ErrorCategoryCount {
+    pub category: String,
+    pub count: usize,
+    pub examples: Vec<ErrorExample>, // First few distinct messages, in log order
+}
+
+/// Failed calls of one tool
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolErrorCount {
+    pub tool_name: String,
+    pub call_count: usize,
+    pub error_count: usize,
+    pub error_rate: f64, // error_count / call_count (0-1)
+}
+
+/// Errors of one session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionErrors {
+    pub session_id: String,
+    pub project_name: Option<String>,
+    pub file_path: String,
+    pub error_count: usize,
+    pub categories: Vec<ErrorCategoryCount>, // Most errors first
+}
+
+/// Categorized errors of a session, project or all projects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorReport {
+    pub scope: String,
+    pub session_count: usize,
+    pub sessions_with_errors: usize,
+    pub total_errors: usize,
+    pub categories: Vec<ErrorCategoryCount>, // Most errors first
+    pub tools: Vec<ToolErrorCount>,          // Tools with failures, most failures first
+    pub sessions: Vec<SessionErrors>,        // Sessions with errors, most errors first
+}
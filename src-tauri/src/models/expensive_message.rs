@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// A single API response ranked by its estimated cost
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpensiveMessage {
+    pub project_name: String,
+    pub session_id: String,
+    pub file_path: String,    // Session file, to open the message in context
+    pub message_uuid: String, // First entry of the response
+    pub timestamp: String,
+    pub model: Option<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cost_usd: f64,
+    pub dominant_cost: String, // "input", "output", "cache_write" or "cache_read"
+    pub preview: String,       // Truncated text and tool calls of the response
+}
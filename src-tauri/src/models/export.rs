@@ -0,0 +1,217 @@
+use super::ClaudeMessage;
+use serde::{Deserialize, Serialize};
+
+/// A content block of a claude.ai chat message
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClaudeAiContent {
+    #[serde(rename = "type")]
+    pub content_type: String, // Always "text"
+    pub text: String,
+}
+
+/// A single chat message in the claude.ai conversation export format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudeAiChatMessage {
+    pub uuid: String,
+    pub text: String,
+    pub content: Vec<ClaudeAiContent>,
+    pub sender: String, // "human" or "assistant"
+    pub created_at: String,
+    pub updated_at: String,
+    pub attachments: Vec<serde_json::Value>,
+    pub files: Vec<serde_json::Value>,
+}
+
+/// A conversation in the claude.ai export format (one element of `conversations.json`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudeAiConversation {
+    pub uuid: String,
+    pub name: String,
+    pub summary: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub chat_messages: Vec<ClaudeAiChatMessage>,
+}
+
+/// A single sub-agent run exported as a standalone transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SidechainTranscript {
+    pub session_id: String,
+    pub root_uuid: String,
+    pub persona: String,
+    pub description: Option<String>,
+    pub format: String, // "markdown" or "jsonl"
+    pub message_count: usize,
+    pub content: String,
+}
+
+/// Token totals of one or more API responses
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct NormalizedUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+}
+
+/// A content block of a normalized message
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NormalizedBlock {
+    Text {
+        text: String,
+    },
+    Thinking {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        tool_name: Option<String>, // Name of the matching tool_use
+        is_error: bool,
+        text: String,                       // Text parts of the result, joined
+        details: Option<serde_json::Value>, // Structured `toolUseResult` of the entry
+    },
+    Image {
+        media_type: Option<String>,
+    },
+}
+
+/// A user, assistant or system entry in the normalized session schema
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NormalizedMessage {
+    pub uuid: String,
+    pub parent_uuid: Option<String>, // Nearest exported ancestor (None for roots)
+    pub children: Vec<String>,       // In export order
+    pub depth: usize,                // 0 for roots
+    pub role: String,                // "user", "assistant" or "system"
+    pub timestamp: String,
+    pub is_sidechain: bool,
+    pub model: Option<String>,
+    pub response_id: Option<String>, // API message ID shared by the entries of one response
+    pub usage: Option<NormalizedUsage>, // Whole response, on its first entry only
+    pub blocks: Vec<NormalizedBlock>,
+}
+
+/// A session in the viewer's normalized JSON schema
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NormalizedSession {
+    pub schema_version: u32,
+    pub session_id: String,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    pub roots: Vec<String>,
+    pub usage: NormalizedUsage, // One count per API response
+    pub cost_usd: f64,
+    pub messages: Vec<NormalizedMessage>, // Canonical export order
+}
+
+/// A session listed in the index of a project export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectExportIndexEntry {
+    pub session_id: String,
+    pub file_name: String,       // Exported file, relative to the index
+    pub summary: Option<String>, // Session summary, else its first prompt
+    pub first_message_time: String,
+    pub last_message_time: String,
+    pub message_count: usize,
+}
+
+/// Outcome of exporting every session of a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectExportResult {
+    pub directory: String,
+    pub format: String, // "markdown", "html" or "json"
+    pub index_file: String,
+    pub exported: usize,
+    pub failed: Vec<String>, // "<session_id>: <error>"
+}
+
+/// Outcome of a date-partitioned warehouse export
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WarehouseExportResult {
+    pub directory: String,
+    pub format: String,       // "parquet" or "ndjson"
+    pub partition_by: String, // "date" or "month"
+    pub partition_count: usize,
+    pub message_rows: usize,
+    pub usage_rows: usize,
+    pub files: Vec<String>, // Written files, relative to `directory`
+}
+
+/// Part of a session to export instead of the whole session
+///
+/// `uuids` keeps only the listed messages; `from_uuid`/`to_uuid` keep an
+/// inclusive range in conversation order (an unset end extends to the start
+/// or end of the session). Both can be combined.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MessageSelection {
+    #[serde(default)]
+    pub uuids: Option<Vec<String>>,
+    #[serde(default)]
+    pub from_uuid: Option<String>,
+    #[serde(default)]
+    pub to_uuid: Option<String>,
+}
+
+/// Options of `share_session`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShareOptions {
+    pub passphrase: String,  // At least 8 characters; needed to import the bundle
+    pub output_path: String, // Bundle file to write
+    #[serde(default)]
+    pub redact: Option<bool>, // Mask secrets (default true)
+    #[serde(default)]
+    pub title: Option<String>, // Shown instead of the first prompt
+    #[serde(default)]
+    pub note: Option<String>, // For the reviewer
+    #[serde(default)]
+    pub shared_by: Option<String>,
+    #[serde(default)]
+    pub selection: Option<MessageSelection>,
+}
+
+/// Content of a shared session bundle, once decrypted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedSessionBundle {
+    pub bundle_id: String,
+    pub shared_at: String, // RFC 3339
+    pub shared_by: Option<String>,
+    pub title: Option<String>,
+    pub note: Option<String>,
+    pub session_id: String,   // Session ID on the sharer's machine
+    pub project_name: String, // Project on the sharer's machine
+    pub redacted: bool,
+    pub messages: Vec<ClaudeMessage>,
+}
+
+/// Outcome of `share_session`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ShareResult {
+    pub bundle_id: String,
+    pub path: String,
+    pub bytes: u64, // Size of the encrypted bundle
+    pub message_count: usize,
+    pub redacted: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claude_ai_content_serialization() {
+        let content = ClaudeAiContent {
+            content_type: "text".to_string(),
+            text: "Hello".to_string(),
+        };
+
+        let json = serde_json::to_value(&content).unwrap();
+        assert_eq!(json["type"], "text");
+        assert_eq!(json["text"], "Hello");
+    }
+}
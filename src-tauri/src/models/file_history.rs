@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+/// Backup storage of one session under `~/.claude/file-history`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionFileHistoryUsage {
+    pub session_id: String,
+    pub file_count: usize,
+    pub total_bytes: u64,
+    pub reclaimable_bytes: u64, // Bytes duplicating content stored elsewhere
+    pub orphaned: bool,         // No session log references this session anymore
+}
+
+/// Identical backup contents stored several times
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DuplicateBackupGroup {
+    pub size: u64,
+    pub copies: usize,
+    pub reclaimable_bytes: u64,
+    pub sample_path: String,
+}
+
+/// Disk usage of Claude Code's file-history backups and how much
+/// content-hash deduplication would save
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileHistoryUsage {
+    pub directory: String,
+    pub session_count: usize,
+    pub file_count: usize,
+    pub total_bytes: u64, // Physical bytes (hard-linked files counted once)
+    pub unique_contents: usize,
+    pub unique_bytes: u64, // Bytes needed with every content stored once
+    pub reclaimable_bytes: u64,
+    pub orphaned_sessions: usize,
+    pub orphaned_bytes: u64,
+    pub sessions: Vec<SessionFileHistoryUsage>, // Sorted by total bytes (descending)
+    pub largest_duplicates: Vec<DuplicateBackupGroup>, // Sorted by reclaimable bytes (descending)
+}
+
+/// Outcome of deduplicating file-history backups into hard links
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileHistoryCompaction {
+    pub dry_run: bool,
+    pub linked_files: usize, // Duplicates replaced (or that would be) by hard links
+    pub reclaimed_bytes: u64,
+    pub failed: Vec<String>, // "path: error" of files left untouched
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/// A stretch of work: turns no further apart than the idle gap
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FocusBlock {
+    pub start_time: String, // First turn (RFC 3339, UTC)
+    pub end_time: String,   // Last turn
+    pub focus_minutes: f64, // end - start, plus the trailing allowance
+    pub turn_count: usize,
+    pub session_count: usize,
+    pub project_names: Vec<String>, // Sorted, distinct
+}
+
+/// Focus blocks of one UTC day (a block running past midnight is split)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FocusDay {
+    pub date: String, // YYYY-MM-DD
+    pub focus_minutes: f64,
+    pub span_minutes: f64, // First to last turn of the day, idle time included
+    pub blocks: Vec<FocusBlock>,
+}
+
+/// Estimated focused time per day, oldest day first
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FocusReport {
+    pub scope: String,
+    pub idle_gap_minutes: u32, // A longer pause between turns ends a block
+    pub trailing_minutes: u32, // Credited after the last turn of each block
+    pub total_focus_minutes: f64,
+    pub session_span_minutes: f64, // Sum of first-to-last message spans of the sessions
+    pub days: Vec<FocusDay>,
+}
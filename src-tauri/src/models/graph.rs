@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+/// A single message in the session graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionGraphNode {
+    pub id: String, // Message uuid
+    pub message_type: String,
+    pub label: String, // Truncated preview of the message content
+    pub timestamp: String,
+    pub is_sidechain: bool,
+    pub tool_names: Vec<String>, // Tools invoked by this message (assistant only)
+    pub depth: usize,            // Distance from the root along parent links
+    pub lane: usize,             // Branch column; the first child stays in its parent's lane
+}
+
+/// A directed link between two messages (source -> target)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionGraphEdge {
+    pub source: String,
+    pub target: String,
+    pub kind: String, // "parent", "sidechain", "tool_result" or "repaired"
+}
+
+/// Layout-friendly DAG of a session's conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionGraph {
+    pub session_id: String,
+    pub nodes: Vec<SessionGraphNode>, // Sorted by (depth, lane)
+    pub edges: Vec<SessionGraphEdge>,
+    pub root_ids: Vec<String>,
+    pub max_depth: usize,
+    pub lane_count: usize,
+}
+
+/// A parent link rebuilt for an entry whose `parentUuid` is missing from the file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RepairedLink {
+    pub uuid: String,
+    pub missing_parent_uuid: String,
+    pub repaired_parent_uuid: Option<String>, // None if the entry becomes a root
+}
+
+/// Broken parent links of a session, with their display-only repairs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionLinkRepair {
+    pub session_id: String,
+    pub orphan_count: usize,
+    pub links: Vec<RepairedLink>, // In timestamp order
+}
+
+/// A message in the session tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTreeMessage {
+    pub id: String, // Message uuid
+    pub message_type: String,
+    pub label: String, // Truncated preview of the message content
+    pub timestamp: String,
+    pub tool_names: Vec<String>,
+    pub child_count: usize,    // Replies to this message, across all branches
+    pub is_branch_point: bool, // More than one reply (edit, retry or sub-agent)
+}
+
+/// A linear run of messages, with the branches forking off it
+///
+/// Each run follows the active reply of every message (the one leading to
+/// the most recent message); other replies start nested branches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTreeBranch {
+    pub kind: String,            // "main", "branch" (edited or retried) or "sidechain"
+    pub fork_id: Option<String>, // Message the branch replies to (None for roots)
+    pub messages: Vec<SessionTreeMessage>,
+    pub branches: Vec<SessionTreeBranch>, // In order of their fork message
+}
+
+/// Nested tree of a session's conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTree {
+    pub session_id: String,
+    pub roots: Vec<SessionTreeBranch>,
+    pub message_count: usize,
+    pub branch_point_count: usize,
+    pub branch_count: usize,    // Edited or retried branches
+    pub sidechain_count: usize, // Sub-agent threads
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_graph_edge_serialization() {
+        let edge = SessionGraphEdge {
+            source: "a".to_string(),
+            target: "b".to_string(),
+            kind: "tool_result".to_string(),
+        };
+
+        let json = serde_json::to_value(&edge).unwrap();
+        assert_eq!(json["source"], "a");
+        assert_eq!(json["target"], "b");
+        assert_eq!(json["kind"], "tool_result");
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+/// Latency aggregated per hook event (`PreToolUse`, `PostToolUse`, `Stop`, ...)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HookEventLatency {
+    pub event: String,
+    pub invocations: usize, // Groups of hooks that ran together for one event
+    pub total_ms: u64,
+    pub avg_ms: f64,
+    pub max_ms: u64,
+}
+
+/// Latency of a single hook command
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HookCommandLatency {
+    pub command: String,
+    pub event: String,
+    pub invocations: usize,
+    pub total_ms: u64,
+    pub avg_ms: f64,
+    pub max_ms: u64,
+}
+
+/// Hook overhead of one session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionHookLatency {
+    pub session_id: String,
+    pub project_name: String,
+    pub hook_invocations: usize,
+    pub total_hook_ms: u64,
+}
+
+/// Estimated wall-clock time added by hooks
+///
+/// Latencies are estimated from the timestamps around hook log entries, so they
+/// are approximate upper bounds rather than measured execution times.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookLatencyStats {
+    pub session_count: usize,
+    pub sessions_with_hooks: usize,
+    pub total_hook_ms: u64,
+    pub avg_hook_ms_per_session: f64, // Averaged over sessions that ran hooks
+    pub by_event: Vec<HookEventLatency>, // Sorted by total time (descending)
+    pub slowest_commands: Vec<HookCommandLatency>, // Sorted by average time (descending)
+    pub sessions: Vec<SessionHookLatency>, // Sorted by total hook time (descending)
+}
+
+/// How often one hook command ran, blocked and failed
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HookUsage {
+    pub command: String,
+    pub event: String,
+    pub runs: usize,
+    pub sessions: usize,
+    pub prevented_continuations: usize, // Runs in a Stop hook summary that prevented continuation
+    pub failures: usize,
+    pub prevent_rate: f64, // prevented_continuations / runs
+    pub failure_rate: f64, // failures / runs
+}
+
+/// Hook activity of one project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectHookStats {
+    pub project_name: String,
+    pub sessions_with_hooks: usize,
+    pub total_runs: usize,
+    pub stop_summaries: usize,          // Stop hook rounds
+    pub prevented_continuations: usize, // Stop hook rounds that prevented continuation
+    pub failed_summaries: usize,        // Stop hook rounds that reported errors
+    pub hooks: Vec<HookUsage>,          // Sorted by runs (descending)
+}
+
+/// Which hooks run most, how often they block and how often they fail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookStats {
+    pub session_count: usize,
+    pub sessions_with_hooks: usize,
+    pub total_runs: usize,
+    pub projects: Vec<ProjectHookStats>, // Sorted by runs (descending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_command_latency_serialization() {
+        let latency = HookCommandLatency {
+            command: "npm run lint".to_string(),
+            event: "PostToolUse".to_string(),
+            invocations: 2,
+            total_ms: 3000,
+            avg_ms: 1500.0,
+            max_ms: 2000,
+        };
+
+        let json = serde_json::to_value(&latency).unwrap();
+        assert_eq!(json["command"], "npm run lint");
+        assert_eq!(json["avg_ms"], 1500.0);
+    }
+}
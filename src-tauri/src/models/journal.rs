@@ -0,0 +1,110 @@
+use super::{ProjectMetadata, SessionMetadata, UserMetadata, UserSettings};
+use serde::{Deserialize, Serialize};
+
+/// State an operation changed, before and after it ran
+///
+/// Undoing puts `before` back, and only while the current state still
+/// equals `after`. Settings are journaled without their credentials; undoing
+/// keeps the current ones.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum UndoAction {
+    SessionMetadata {
+        session_id: String,
+        before: Option<SessionMetadata>,
+        after: Option<SessionMetadata>,
+    },
+    ProjectMetadata {
+        project_path: String,
+        before: Option<ProjectMetadata>,
+        after: Option<ProjectMetadata>,
+    },
+    Settings {
+        before: Box<UserSettings>,
+        after: Box<UserSettings>,
+    },
+    Metadata {
+        before: Box<UserMetadata>,
+        after: Box<UserMetadata>,
+    },
+    FileContent {
+        path: String,
+        before: Option<String>, // None when the file did not exist
+        after: Option<String>,
+    },
+}
+
+impl UndoAction {
+    /// This action with credentials stripped from the settings it holds
+    #[must_use]
+    pub fn without_secrets(self) -> Self {
+        match self {
+            Self::Settings { before, after } => Self::Settings {
+                before: Box::new(before.without_secrets()),
+                after: Box::new(after.without_secrets()),
+            },
+            Self::Metadata { before, after } => Self::Metadata {
+                before: Box::new(before.without_secrets()),
+                after: Box::new(after.without_secrets()),
+            },
+            other => other,
+        }
+    }
+
+    /// The action undoing this one's undo (a redo)
+    #[must_use]
+    pub fn inverse(self) -> Self {
+        match self {
+            Self::SessionMetadata {
+                session_id,
+                before,
+                after,
+            } => Self::SessionMetadata {
+                session_id,
+                before: after,
+                after: before,
+            },
+            Self::ProjectMetadata {
+                project_path,
+                before,
+                after,
+            } => Self::ProjectMetadata {
+                project_path,
+                before: after,
+                after: before,
+            },
+            Self::Settings { before, after } => Self::Settings {
+                before: after,
+                after: before,
+            },
+            Self::Metadata { before, after } => Self::Metadata {
+                before: after,
+                after: before,
+            },
+            Self::FileContent {
+                path,
+                before,
+                after,
+            } => Self::FileContent {
+                path,
+                before: after,
+                after: before,
+            },
+        }
+    }
+}
+
+/// A mutating operation recorded in the operation journal
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JournalEntry {
+    pub op_id: String,
+    pub timestamp: String, // RFC 3339
+    pub operation: String, // Command name, or "undo"
+    pub summary: String,
+    #[serde(default)]
+    pub undoes: Option<String>, // Operation reverted by an "undo" entry
+    #[serde(default)]
+    pub undo: Option<UndoAction>, // None when the operation cannot be undone
+    #[serde(default)]
+    pub undone_by: Option<String>, // Filled in when listing
+}
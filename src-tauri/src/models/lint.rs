@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+/// A single structural problem found in a session file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintViolation {
+    pub line_number: usize, // 1-based file line
+    pub severity: String,   // "error" or "warning"
+    pub rule: String, // "invalid_json", "missing_field", "duplicate_uuid", "unresolved_parent", "invalid_timestamp", "timestamp_regression" or "unknown_type"
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+}
+
+/// Result of validating a session JSONL file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintReport {
+    pub path: String,
+    pub line_count: usize, // Non-empty lines
+    pub error_count: usize,
+    pub warning_count: usize,
+    pub violations: Vec<LintViolation>, // In file order, capped (see `truncated`)
+    pub truncated: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_violation_skips_missing_uuid() {
+        let violation = LintViolation {
+            line_number: 3,
+            severity: "error".to_string(),
+            rule: "invalid_json".to_string(),
+            message: "expected value".to_string(),
+            uuid: None,
+        };
+
+        let json = serde_json::to_value(&violation).unwrap();
+        assert_eq!(json["rule"], "invalid_json");
+        assert!(json.get("uuid").is_none());
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TokenUsage {
     pub input_tokens: Option<u32>,
     pub output_tokens: Option<u32>,
@@ -45,6 +45,8 @@ pub struct RawLogEntry {
     #[serde(rename = "isSidechain")]
     pub is_sidechain: Option<bool>,
     pub cwd: Option<String>,
+    #[serde(rename = "gitBranch")]
+    pub git_branch: Option<String>,
 
     // Cost and performance metrics (2025 additions)
     #[serde(rename = "costUSD")]
@@ -84,11 +86,18 @@ pub struct RawLogEntry {
     pub compact_metadata: Option<serde_json::Value>,
     #[serde(rename = "microcompactMetadata")]
     pub microcompact_metadata: Option<serde_json::Value>,
+    #[serde(rename = "hookErrors")]
+    pub hook_errors: Option<serde_json::Value>, // Failed Stop hooks of `stop_hook_summary`
+    pub error: Option<serde_json::Value>, // Failed request of `api_error` entries
     pub content: Option<serde_json::Value>,
 
     // Meta message flag (internal/command-related messages)
     #[serde(rename = "isMeta")]
     pub is_meta: Option<bool>,
+
+    // Set on the synthetic assistant message reporting a failed API request
+    #[serde(rename = "isApiErrorMessage")]
+    pub is_api_error_message: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -166,6 +175,10 @@ pub struct ClaudeMessage {
         skip_serializing_if = "Option::is_none"
     )]
     pub microcompact_metadata: Option<serde_json::Value>,
+
+    // Branch checked out when the entry was written
+    #[serde(rename = "gitBranch", skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -176,6 +189,17 @@ pub struct MessagePage {
     pub next_offset: usize,
 }
 
+/// The original JSONL line behind a parsed message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawEntry {
+    pub session_id: String,
+    pub uuid: String,
+    pub line_number: usize, // 1-based, counting non-empty lines
+    pub byte_offset: u64,
+    pub raw: String,
+    pub pretty: bool, // Whether `raw` was re-indented
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -341,6 +365,7 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            git_branch: None,
         };
 
         let serialized = serde_json::to_string(&message).unwrap();
@@ -384,6 +409,7 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            git_branch: None,
         };
 
         let serialized = serde_json::to_string(&message).unwrap();
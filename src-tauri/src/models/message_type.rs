@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// Entries of one type (e.g. `assistant`, `system:turn_duration`) and the
+/// log volume they take up
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MessageTypeCount {
+    pub message_type: String, // `type`, with the subtype after a colon when present
+    pub entry_count: usize,
+    pub bytes: u64,
+    pub byte_share: f64, // Share of the bytes of the group (0-1)
+}
+
+/// Message type breakdown of a session or a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageTypeGroup {
+    pub name: String, // Session ID or project name
+    pub path: String, // Session file or project folder
+    pub entry_count: usize,
+    pub bytes: u64,
+    pub conversation_share: f64, // Share of the bytes in user and assistant entries
+    pub types: Vec<MessageTypeCount>, // By bytes (descending)
+}
+
+/// How much of the log volume is conversation and how much machinery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageTypeStats {
+    pub scope: String,
+    pub totals: MessageTypeGroup,
+    pub projects: Vec<MessageTypeGroup>, // By bytes (descending)
+    pub sessions: Vec<MessageTypeGroup>, // By bytes (descending); sub-agent files count towards their session
+}
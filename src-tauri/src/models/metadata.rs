@@ -66,6 +66,15 @@ impl UserMetadata {
         self.projects.entry(project_path.to_string()).or_default()
     }
 
+    /// This metadata with credentials stripped from its settings
+    #[must_use]
+    pub fn without_secrets(&self) -> Self {
+        Self {
+            settings: self.settings.without_secrets(),
+            ..self.clone()
+        }
+    }
+
     /// Check if a project should be hidden based on settings
     pub fn is_project_hidden(&self, project_path: &str) -> bool {
         // Check explicit hidden flag
@@ -92,7 +101,7 @@ impl UserMetadata {
 
     /// Simple glob pattern matching (supports * and ?)
     /// Returns false for patterns that exceed safety limits
-    fn matches_glob_pattern(text: &str, pattern: &str) -> bool {
+    pub(crate) fn matches_glob_pattern(text: &str, pattern: &str) -> bool {
         // ReDoS protection: reject overly long patterns
         if pattern.len() > Self::MAX_PATTERN_LENGTH {
             return false;
@@ -158,6 +167,10 @@ pub struct SessionMetadata {
     /// User notes about the session
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
+
+    /// When the session was archived (hidden from session lists)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<String>,
 }
 
 impl SessionMetadata {
@@ -167,6 +180,7 @@ impl SessionMetadata {
             && self.starred.is_none()
             && self.tags.is_empty()
             && self.notes.is_none()
+            && self.archived_at.is_none()
     }
 }
 
@@ -185,15 +199,39 @@ pub struct ProjectMetadata {
     /// Parent project path for worktree grouping
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent_project: Option<String>,
+
+    /// Monthly usage budget
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget: Option<ProjectBudget>,
 }
 
 impl ProjectMetadata {
     /// Check if metadata has any values set
     pub fn is_empty(&self) -> bool {
-        self.hidden.is_none() && self.alias.is_none() && self.parent_project.is_none()
+        self.hidden.is_none()
+            && self.alias.is_none()
+            && self.parent_project.is_none()
+            && self.budget.is_none()
     }
 }
 
+/// Monthly token and/or cost budget of a project
+///
+/// Months are calendar months in UTC, like the cost report.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectBudget {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monthly_tokens: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monthly_cost_usd: Option<f64>,
+
+    /// Percentages of the budget that trigger a warning (80 and 100 when empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub thresholds: Vec<u32>,
+}
+
 /// Global user settings
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -205,6 +243,256 @@ pub struct UserSettings {
     /// Whether to automatically group worktrees under their parent repos
     #[serde(skip_serializing_if = "Option::is_none")]
     pub worktree_grouping: Option<bool>,
+
+    /// Maximum number of session files opened at once while scanning
+    /// (platform default when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_open_files: Option<usize>,
+
+    /// Smaller memory footprint at the cost of speed: fewer files parsed at
+    /// once, one search at a time, no in-memory caches and session files
+    /// streamed instead of read whole (off when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub low_memory: Option<bool>,
+
+    /// Custom prices replacing list prices in cost estimates; the first
+    /// matching override wins
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pricing_overrides: Vec<PricingOverride>,
+
+    /// Whether the current content of local files may be read for
+    /// then-vs-now previews (off when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_file_preview: Option<bool>,
+
+    /// Entry categories left out of message counts ("progress", "system",
+    /// "meta", "sidechain"); progress, system and meta when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count_exclusions: Option<Vec<String>>,
+
+    /// Custom per-session fields computed from the raw entries
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub derived_fields: Vec<DerivedField>,
+
+    /// Extra regular expressions masked by redacted exports (besides the
+    /// built-in API key, credential and token detectors)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redaction_patterns: Vec<String>,
+
+    /// Automatic archiving of old sessions (off when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive: Option<ArchiveSettings>,
+
+    /// Weekly digest written after each week (off when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weekly_digest: Option<DigestSettings>,
+
+    /// Streaming of new usage to `ClickHouse` or Timeplus (off when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage_sink: Option<UsageSinkSettings>,
+
+    /// Model generating session summaries (summaries unavailable when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summarizer: Option<SummarizerSettings>,
+
+    /// Extra Claude folders (or copied `projects` folders) included in the
+    /// global stats, e.g. one per team member
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scan_roots: Vec<ScanRoot>,
+}
+
+impl UserSettings {
+    /// These settings without stored credentials (summarizer API key, usage
+    /// sink password), as kept in the operation journal
+    #[must_use]
+    pub fn without_secrets(&self) -> Self {
+        let mut settings = self.clone();
+        if let Some(summarizer) = &mut settings.summarizer {
+            summarizer.api_key = None;
+        }
+        if let Some(usage_sink) = &mut settings.usage_sink {
+            usage_sink.password = None;
+        }
+        settings
+    }
+
+    /// Take over the credentials of `current` (settings restored from the
+    /// journal carry none)
+    pub fn keep_secrets_of(&mut self, current: &Self) {
+        if let Some(summarizer) = &mut self.summarizer {
+            summarizer.api_key = current.summarizer.as_ref().and_then(|s| s.api_key.clone());
+        }
+        if let Some(usage_sink) = &mut self.usage_sink {
+            usage_sink.password = current.usage_sink.as_ref().and_then(|s| s.password.clone());
+        }
+    }
+}
+
+/// Labelled folder scanned for global stats besides the local Claude folder
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanRoot {
+    pub label: String,
+    pub path: String,
+}
+
+/// Background export of old session files into compressed archives
+///
+/// Runs once after startup and then every `interval_hours` (if set).
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveSettings {
+    pub enabled: bool,
+
+    /// Sessions not modified for this many days are archived
+    pub older_than_days: u32,
+
+    /// "zip" or "tar.zst"
+    pub format: String,
+
+    /// Folder the archives are written to (`archives` in the metadata
+    /// folder when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub directory: Option<String>,
+
+    /// Claude folder whose projects are archived (`~/.claude` when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claude_path: Option<String>,
+
+    /// Hours between runs; only archived at startup when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval_hours: Option<u32>,
+
+    /// Delete session files once they are safely archived
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub remove_originals: bool,
+}
+
+/// Weekly digest written to a folder once each week is over
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestSettings {
+    pub enabled: bool,
+
+    /// "markdown" or "html"
+    pub format: String,
+
+    /// Folder the digests are written to (`digests` in the metadata folder
+    /// when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub directory: Option<String>,
+
+    /// Claude folder the digest covers (`~/.claude` when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claude_path: Option<String>,
+}
+
+/// Continuous streaming of new usage rows to an external database
+///
+/// Rows have the columns of the `usage` (and `messages`) tables written by
+/// `export_warehouse`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageSinkSettings {
+    pub enabled: bool,
+
+    /// "clickhouse" or "timeplus"
+    pub kind: String,
+
+    /// HTTP interface of the server (e.g. `http://localhost:8123`)
+    pub endpoint: String,
+
+    /// Table (`ClickHouse`, optionally `db.table`) or stream (Timeplus)
+    /// receiving usage rows
+    pub usage_table: String,
+
+    /// Table or stream receiving every message; messages are not streamed
+    /// when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages_table: Option<String>,
+
+    /// `ClickHouse` user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+
+    /// `ClickHouse` password or Timeplus API key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+
+    /// Claude folder whose sessions are streamed (`~/.claude` when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claude_path: Option<String>,
+}
+
+/// Model used by `summarize_session`
+///
+/// The "ollama" and "llamacpp" backends run locally, so summaries can be
+/// generated without the transcript leaving the machine.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SummarizerSettings {
+    /// "anthropic", "ollama" or "llamacpp"
+    pub backend: String,
+
+    /// Model name as the backend knows it (e.g. `llama3.1:8b`)
+    pub model: String,
+
+    /// Base URL of the server (the backend's usual local port, or the
+    /// Anthropic API, when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    /// Anthropic API key, or bearer token of a llama.cpp server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+
+    /// Transcript characters sent to the model (48,000 when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_input_chars: Option<usize>,
+
+    /// Mask secrets in the transcript before sending it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub redact: bool,
+}
+
+/// Custom session column computed by a jq expression
+///
+/// The expression receives the array of raw entries of the session file;
+/// its first value is shown under `name`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DerivedField {
+    pub name: String,
+    pub expression: String, // e.g. `map(select(.type == "assistant")) | length`
+}
+
+/// Negotiated pricing for some models and/or projects (e.g. Bedrock contracts)
+///
+/// Prices are in USD per million tokens; unset prices keep the list price.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PricingOverride {
+    /// Glob pattern matched against the model ID (e.g. "*opus*"); any model when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_pattern: Option<String>,
+
+    /// Glob pattern matched against the working directory of the session
+    /// (e.g. "/work/acme/*"); any project when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_pattern: Option<String>,
+
+    /// Usage is free (internal deployment): cost is always zero
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub free: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_write: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_read: Option<f64>,
 }
 
 #[cfg(test)]
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+/// A participant of a session: the main agent or a sub-agent persona
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SessionPersona {
+    pub name: String, // "main", the sub-agent type (e.g. "code-reviewer") or "sidechain" if unknown
+    pub descriptions: Vec<String>, // Task descriptions the persona was launched with
+    pub invocation_count: usize, // Task calls launching it (0 for "main")
+    pub message_count: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+}
+
+/// One sidechain (a single sub-agent run) of a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SidechainSummary {
+    pub root_uuid: String, // First message of the sidechain
+    pub persona: String,
+    pub description: Option<String>,
+    pub message_count: usize,
+    pub start_time: String,
+}
+
+/// Cast list of a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionCast {
+    pub session_id: String,
+    pub personas: Vec<SessionPersona>, // "main" first, then by total tokens (descending)
+    pub sidechains: Vec<SidechainSummary>, // In order of appearance
+}
+
+/// Sub-agent usage of one agent type across sessions
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AgentUsage {
+    pub agent_type: String, // Sub-agent type, or "sidechain" if the Task call is unknown
+    pub run_count: usize,   // Sidechains of this agent
+    pub session_count: usize,
+    pub message_count: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+}
+
+/// Main-thread and sidechain usage of one session with sub-agent runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSidechainUsage {
+    pub session_id: String,
+    pub file_path: String,
+    pub project_name: Option<String>,
+    pub run_count: usize,
+    pub main_tokens: u64,
+    pub sidechain_tokens: u64,
+    pub agents: Vec<String>, // Agent types invoked, by tokens (descending)
+}
+
+/// Main-thread vs sidechain (sub-agent) usage over a scope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SidechainUsageReport {
+    pub scope: String,
+    pub session_count: usize,
+    pub sessions_with_sidechains: usize,
+    pub run_count: usize,
+    pub main_message_count: usize,
+    pub sidechain_message_count: usize,
+    pub main_tokens: u64,
+    pub sidechain_tokens: u64,
+    pub sidechain_share: f64,    // Sidechain share of all tokens (0-1)
+    pub agents: Vec<AgentUsage>, // By total tokens (descending)
+    pub sessions: Vec<SessionSidechainUsage>, // Sidechain tokens (descending)
+}
@@ -0,0 +1,46 @@
+use super::PricingOverride;
+use crate::pricing::ModelPricing;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// State of the locally cached pricing catalog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PricingCatalogStatus {
+    pub source_url: String,
+    pub fetched_at: String,
+    pub model_count: usize,
+}
+
+/// User pricing file (`pricing.toml` or `pricing.json` in the metadata folder)
+///
+/// Prices are in USD per million tokens.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PricingFile {
+    /// Currency costs are shown in ("USD" when unset)
+    pub currency: Option<String>,
+
+    /// Factor applied to every cost (exchange rate or contract discount); 1 when unset
+    pub currency_multiplier: Option<f64>,
+
+    /// Prices of custom models, keyed by model ID; used ahead of the catalog
+    #[serde(default)]
+    pub models: HashMap<String, ModelPricing>,
+
+    /// Overrides in the format of the `pricingOverrides` setting, consulted after it
+    #[serde(default)]
+    pub overrides: Vec<PricingOverride>,
+}
+
+/// State of the user pricing file
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct PricingFileStatus {
+    pub path: String, // Loaded file, or where one is looked for
+    pub exists: bool,
+    pub loaded_at: Option<String>,
+    pub currency: String,
+    pub currency_multiplier: f64,
+    pub custom_model_count: usize,
+    pub override_count: usize,
+    pub error: Option<String>, // Why the file was rejected; the previous prices stay in effect
+}
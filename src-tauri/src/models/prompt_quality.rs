@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+/// Downstream friction of a group of prompts (by length or by structure)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PromptQualityGroup {
+    pub label: String, // e.g. "1-10" words, "with_code_block"
+    pub prompt_count: usize,
+    pub avg_words: f64,
+    pub avg_tool_errors: f64,        // Failed tool results per prompt
+    pub error_turn_rate: f64,        // Fraction of prompts whose turn hit at least one tool error
+    pub retry_rate: f64,             // Fraction of prompts followed by a correction or interrupt
+    pub avg_assistant_messages: f64, // Back-and-forth needed to answer the prompt
+    pub avg_output_tokens: f64,
+}
+
+/// Report correlating prompt length/structure with errors and retries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptQualityReport {
+    pub scope: String,
+    pub session_count: usize,
+    pub prompt_count: usize,
+    pub length_buckets: Vec<PromptQualityGroup>,
+    pub structure: Vec<PromptQualityGroup>,
+    /// Pearson correlation between prompt word count and friction
+    /// (tool errors + retry); None when there is not enough data
+    pub length_friction_correlation: Option<f64>,
+}
+
+/// Response size, time and cost of the prompts in one length bucket
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PromptLengthBucket {
+    pub label: String, // Word count, e.g. "1-10"
+    pub prompt_count: usize,
+    pub avg_words: f64,
+    pub avg_output_tokens: f64,
+    pub avg_duration_seconds: f64, // Prompt to the last response or tool result of its turn
+    pub avg_tool_calls: f64,
+    pub avg_cost_usd: f64,
+    pub retry_rate: f64, // Fraction of prompts followed by a correction or interrupt
+}
+
+/// Prompt length against what the prompts produced and cost
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptLengthReport {
+    pub scope: String,
+    pub session_count: usize,
+    pub prompt_count: usize,
+    pub buckets: Vec<PromptLengthBucket>,
+    /// Pearson correlations between prompt word count and each measure;
+    /// None when there is not enough data
+    pub output_tokens_correlation: Option<f64>,
+    pub duration_correlation: Option<f64>,
+    pub tool_calls_correlation: Option<f64>,
+    pub cost_correlation: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_quality_report_serialization() {
+        let report = PromptQualityReport {
+            scope: "project".to_string(),
+            session_count: 1,
+            prompt_count: 0,
+            length_buckets: vec![PromptQualityGroup {
+                label: "1-10".to_string(),
+                ..Default::default()
+            }],
+            structure: Vec::new(),
+            length_friction_correlation: None,
+        };
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["length_buckets"][0]["label"], "1-10");
+        assert!(json["length_friction_correlation"].is_null());
+    }
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+/// A run of near-identical tool calls that kept failing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryLoop {
+    pub tool_name: String,
+    pub attempts: usize,
+    pub failed_attempts: usize,
+    pub first_message_uuid: String,
+    pub last_message_uuid: String,
+    pub start_time: String,
+    pub end_time: String,
+    pub wasted_tokens: u64,   // Tokens spent on every attempt after the first
+    pub wasted_cost_usd: f64, // Estimated cost of those tokens
+    pub sample_input: String, // Truncated input of the first attempt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// Retry loops detected in one session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRetryLoops {
+    pub session_id: String,
+    pub loops: Vec<RetryLoop>,
+    pub total_loop_attempts: usize,
+    pub total_wasted_tokens: u64,
+    pub total_wasted_cost_usd: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_loop_skips_missing_error() {
+        let retry_loop = RetryLoop {
+            tool_name: "Bash".to_string(),
+            attempts: 3,
+            failed_attempts: 3,
+            first_message_uuid: "a".to_string(),
+            last_message_uuid: "c".to_string(),
+            start_time: "2025-01-01T00:00:00Z".to_string(),
+            end_time: "2025-01-01T00:01:00Z".to_string(),
+            wasted_tokens: 1200,
+            wasted_cost_usd: 0.01,
+            sample_input: "{\"command\":\"cargo test\"}".to_string(),
+            last_error: None,
+        };
+
+        let json = serde_json::to_value(&retry_loop).unwrap();
+        assert_eq!(json["tool_name"], "Bash");
+        assert!(json.get("last_error").is_none());
+    }
+}
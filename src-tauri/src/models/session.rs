@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClaudeProject {
@@ -15,13 +16,237 @@ pub struct ClaudeSession {
     pub actual_session_id: String, // Actual session ID from the messages
     pub file_path: String,
     pub project_name: String,
-    pub message_count: usize,
+    pub message_count: usize, // Adjusted by the counting rules
+    #[serde(default)]
+    pub raw_message_count: usize, // Every entry, including excluded ones
     pub first_message_time: String,
     pub last_message_time: String,
     pub last_modified: String,
     pub has_tool_use: bool,
     pub has_errors: bool,
     pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_user_message: Option<String>, // Truncated first prompt, even when a summary exists
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub derived_fields: BTreeMap<String, serde_json::Value>, // Values of the `derivedFields` setting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>, // History source of chats from other tools (None for Claude Code)
+}
+
+/// Payload of the event emitted when a session file changed outside the app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRefreshedEvent {
+    pub file_path: String,
+    pub change: String,                 // "modified" or "deleted"
+    pub session: Option<ClaudeSession>, // Reindexed metadata (None if deleted)
+}
+
+/// Payload of the event emitted when a watched session is blocked on the user
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionAwaitingInput {
+    pub session_id: String,
+    pub file_path: String,
+    pub reason: String, // "permission", "plan_approval" or "question"
+    pub tool_name: String,
+    pub tool_use_id: String,
+    pub since: String, // Timestamp of the blocked tool call
+}
+
+/// Payload of the event emitted when a watched session finishes an agent run
+///
+/// A run spans from the last user prompt to the final assistant response.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionRunSummary {
+    pub session_id: String,
+    pub file_path: String,
+    pub last_message_uuid: String, // Final assistant response of the run
+    pub started_at: String,        // Timestamp of the prompt
+    pub ended_at: String,          // Timestamp of the final response
+    pub duration_seconds: i64,
+    pub response_count: u64,
+    pub total_tokens: u64,
+    pub cost_usd: f64, // Recorded where available, estimated otherwise
+    pub tool_call_count: usize,
+    pub error_count: usize,         // Failed tool calls
+    pub files_changed: Vec<String>, // Edited or written files, sorted
+}
+
+/// Summary of a session generated by the configured summarizer
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GeneratedSessionSummary {
+    pub session_id: String,
+    pub backend: String, // "anthropic", "ollama" or "llamacpp"
+    pub model: String,
+    pub summary: String,
+    pub generated_at: String,
+    pub transcript_chars: usize, // Characters sent to the model
+    pub truncated: bool,         // Middle of the transcript left out
+}
+
+/// Position of one match inside an open session, for find-next/previous
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionSearchHit {
+    pub message_uuid: String,
+    pub block_type: String,         // "text", "thinking" or "tool_result"
+    pub block_index: Option<usize>, // None when the content is a plain string
+    pub start: usize,               // UTF-16 offsets into the block text
+    pub end: usize,
+}
+
+/// Every match of a query in one session, in transcript order
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionSearchHits {
+    pub session_id: String,
+    pub total_hits: usize,
+    pub hits: Vec<SessionSearchHit>,
+    pub truncated: bool, // More than the returned hits exist
+}
+
+/// Raw lines of a tailed session file, as appended
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RawTailEvent {
+    pub session_id: String,
+    pub file_path: String,
+    pub first_line: usize, // 1-based line number of `lines[0]`
+    pub lines: Vec<String>,
+    pub reset: bool, // File was truncated or replaced; line numbers restart
+}
+
+/// A `summary` entry resolved to the session and branch its `leafUuid` belongs to
+///
+/// Claude Code often writes summaries into a later session's file, so the file
+/// a summary appears in is not necessarily the session it describes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectSummary {
+    pub summary: String,
+    pub leaf_uuid: String,
+    pub session_id: Option<String>, // Session containing the leaf (None if unresolved)
+    pub file_path: Option<String>,  // File containing the leaf
+    pub leaf_timestamp: Option<String>,
+    pub source_file_paths: Vec<String>, // Files the summary was written to
+}
+
+/// Text around a search match, split so the frontend can highlight `matched`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SearchSnippet {
+    pub before: String,
+    pub matched: String,
+    pub after: String,
+}
+
+/// State of the persistent search index after a refresh
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchIndexStatus {
+    pub index_path: String,
+    pub indexed_files: usize,
+    pub updated_files: usize, // Files re-read because they changed
+    pub removed_files: usize,
+    pub message_count: usize,
+    pub error_count: usize, // Errors extracted from tool results
+}
+
+/// An error message seen in a tool result, found through the search index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorSearchMatch {
+    pub message_uuid: String,
+    pub session_id: String,
+    pub file_path: String,
+    pub timestamp: String,
+    pub tool_name: Option<String>,
+    pub kind: String,        // "panic", "compile_error", "exception" or "error"
+    pub message: String,     // Normalized error line
+    pub context: String,     // Location or first stack frame, may be empty
+    pub signature: String,   // Message without paths, numbers and addresses
+    pub fingerprint: String, // Shared by occurrences of the same error
+}
+
+/// Occurrences of one error fingerprint across sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorGroup {
+    pub fingerprint: String,
+    pub kind: String,
+    pub message: String, // Of the latest occurrence
+    pub context: String, // Of the latest occurrence
+    pub tool_name: Option<String>,
+    pub occurrences: usize,
+    pub session_ids: Vec<String>, // Affected sessions
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+/// A message of a project matching a full-text search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectSearchMatch {
+    pub message_uuid: String,
+    pub session_id: String,
+    pub file_path: String,
+    pub timestamp: String,
+    pub message_type: String, // "user" or "assistant"
+    pub is_sidechain: bool,
+    pub snippet: SearchSnippet,     // First match in the message
+    pub block_type: Option<String>, // "text", "thinking", "tool_use", "tool_result"; None from the index
+    pub block_index: Option<usize>, // None when the content is a plain string
+}
+
+/// Condition on one input parameter of a tool call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolInputFilter {
+    pub field: String, // Input parameter; dots reach nested values ("edits.0.old_string")
+    pub op: Option<String>, // "contains" (default), "equals", "regex", "domain" or "exists"
+    pub value: Option<String>,
+}
+
+/// Structured query over tool calls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolQuery {
+    pub tool_name: Option<String>, // Exact name or glob, ignoring case
+    #[serde(default)]
+    pub input: Vec<ToolInputFilter>, // All must match
+}
+
+/// A tool call matching a `ToolQuery`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolInvocationMatch {
+    pub project_name: String,
+    pub session_id: String,
+    pub file_path: String,
+    pub message_uuid: String,
+    pub timestamp: String,
+    pub is_sidechain: bool,
+    pub tool_use_id: Option<String>,
+    pub tool_name: String,
+    pub input: serde_json::Value,
+    pub is_error: Option<bool>, // None when no result was recorded
+    pub result_preview: Option<String>,
+}
+
+/// A session found by `fuzzy_find_sessions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuzzySessionMatch {
+    pub session: ClaudeSession,
+    pub score: i64,
+    pub matched_field: String,       // "summary" or "first_user_message"
+    pub matched_indices: Vec<usize>, // Character indices of the matched letters
+}
+
+/// Matches of one project, emitted while `search_all_projects` runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectSearchResultsEvent {
+    pub search_id: String,
+    pub project_name: String,
+    pub project_path: String,
+    pub matches: Vec<ProjectSearchMatch>, // Newest first
+    pub completed_projects: usize,
+    pub total_projects: usize,
+}
+
+/// Outcome of a `search_all_projects` run once every project was searched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlobalSearchSummary {
+    pub search_id: String,
+    pub total_projects: usize,
+    pub projects_with_matches: usize,
+    pub total_matches: usize,
 }
 
 #[cfg(test)]
@@ -36,12 +261,16 @@ mod tests {
             file_path: "/path/to/file.jsonl".to_string(),
             project_name: "my-project".to_string(),
             message_count: 42,
+            raw_message_count: 50,
             first_message_time: "2025-06-01T10:00:00Z".to_string(),
             last_message_time: "2025-06-01T12:00:00Z".to_string(),
             last_modified: "2025-06-01T12:00:00Z".to_string(),
             has_tool_use: true,
             has_errors: false,
             summary: Some("Test conversation".to_string()),
+            first_user_message: None,
+            derived_fields: BTreeMap::new(),
+            source: None,
         };
 
         let serialized = serde_json::to_string(&session).unwrap();
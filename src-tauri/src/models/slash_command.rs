@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// One run of a slash command, parsed from its `<command-name>` tags
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SlashCommandUse {
+    pub name: String, // With its leading slash, e.g. "/compact"
+    pub args: Option<String>,
+    pub session_id: String,
+    pub project_name: String,
+    pub timestamp: Option<String>,
+    pub uuid: Option<String>,
+}
+
+/// Runs of a slash command (or of all commands) in one project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashCommandProjectCount {
+    pub project_name: String,
+    pub uses: usize,
+}
+
+/// Usage of one slash command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashCommandCount {
+    pub name: String,
+    pub uses: usize,
+    pub sessions: usize,
+    pub last_used: Option<String>,
+    pub projects: Vec<SlashCommandProjectCount>, // Sorted by uses (descending)
+}
+
+/// Which slash commands run most, and where
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashCommandStats {
+    pub session_count: usize,
+    pub sessions_with_commands: usize,
+    pub total_uses: usize,
+    pub commands: Vec<SlashCommandCount>, // Sorted by uses (descending)
+    pub projects: Vec<SlashCommandProjectCount>, // Sorted by uses (descending)
+    pub recent: Vec<SlashCommandUse>,     // Latest runs first
+}
@@ -8,6 +8,7 @@
 use super::*;
 use insta::{assert_json_snapshot, assert_snapshot};
 use serde_json::json;
+use std::collections::BTreeMap;
 
 /// Snapshot tests for `ClaudeMessage` serialization
 mod claude_message_snapshots {
@@ -46,6 +47,7 @@ mod claude_message_snapshots {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            git_branch: None,
         };
 
         assert_json_snapshot!("user_message", message);
@@ -92,6 +94,7 @@ mod claude_message_snapshots {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            git_branch: None,
         };
 
         assert_json_snapshot!("assistant_message", message);
@@ -148,6 +151,7 @@ mod claude_message_snapshots {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            git_branch: None,
         };
 
         assert_json_snapshot!("message_with_tool_use", message);
@@ -215,12 +219,16 @@ mod session_snapshots {
             file_path: "/path/to/session.jsonl".to_string(),
             project_name: "test-project".to_string(),
             message_count: 100,
+            raw_message_count: 120,
             first_message_time: "2025-01-01T00:00:00Z".to_string(),
             last_message_time: "2025-01-01T12:00:00Z".to_string(),
             last_modified: "2025-01-01T12:00:00Z".to_string(),
             has_tool_use: true,
             has_errors: false,
             summary: Some("Test conversation summary".to_string()),
+            first_user_message: None,
+            derived_fields: BTreeMap::new(),
+            source: None,
         };
 
         assert_json_snapshot!("claude_session", session);
@@ -242,9 +250,12 @@ mod stats_snapshots {
             total_cache_read_tokens: 500,
             total_tokens: 9000,
             message_count: 50,
+            raw_message_count: 64,
             first_message_time: "2025-01-01T08:00:00Z".to_string(),
             last_message_time: "2025-01-01T17:00:00Z".to_string(),
             summary: None,
+            duplicate_usage_entries: 0,
+            total_cost_usd: 0.0525,
         };
 
         assert_json_snapshot!("session_token_stats", stats);
@@ -9,11 +9,20 @@ pub struct SessionTokenStats {
     pub total_cache_creation_tokens: u32,
     pub total_cache_read_tokens: u32,
     pub total_tokens: u32,
-    pub message_count: usize,
+    pub message_count: usize, // Adjusted by the counting rules
+    /// Every entry, including those excluded by the counting rules
+    #[serde(default)]
+    pub raw_message_count: usize,
     pub first_message_time: String,
     pub last_message_time: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub summary: Option<String>,
+    /// Entries whose usage repeated an already counted API response
+    #[serde(default)]
+    pub duplicate_usage_entries: usize,
+    /// Recorded `costUSD`, or the estimate from token usage where missing
+    #[serde(default)]
+    pub total_cost_usd: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -48,6 +57,8 @@ pub struct ProjectStatsSummary {
     pub project_name: String,
     pub total_sessions: usize,
     pub total_messages: usize,
+    #[serde(default)]
+    pub raw_total_messages: usize, // Including entries excluded by the counting rules
     pub total_tokens: u64,
     pub avg_tokens_per_session: u64,
     pub avg_session_duration: u32,
@@ -57,6 +68,54 @@ pub struct ProjectStatsSummary {
     pub daily_stats: Vec<DailyStats>,
     pub activity_heatmap: Vec<ActivityHeatmap>,
     pub token_distribution: TokenDistribution,
+    #[serde(default)]
+    pub total_cost_usd: f64, // Recorded or estimated, see SessionTokenStats
+    #[serde(default)]
+    pub service_tier_breakdown: Vec<ServiceTierStats>, // Most tokens first
+    #[serde(default)]
+    pub model_daily_stats: Vec<ModelDailyStats>, // By date, then most tokens first
+    #[serde(default)]
+    pub thinking_stats: Vec<ModelThinkingStats>, // Most thinking turns first
+}
+
+/// Usage of one model on one day (UTC)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelDailyStats {
+    pub date: String,       // YYYY-MM-DD
+    pub model_name: String, // Aliases grouped as in ModelStats
+    pub message_count: u32,
+    pub token_count: u64,
+    pub cost_usd: f64,
+}
+
+/// Extended thinking usage of one model
+///
+/// Thinking tokens are estimated from the logged thinking text, which newer
+/// models summarize, so they are a lower bound.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelThinkingStats {
+    pub model_name: String, // Aliases grouped as in ModelStats
+    pub turn_count: u32,    // Assistant responses
+    pub thinking_turn_count: u32,
+    pub thinking_block_count: u32, // Including redacted blocks
+    pub estimated_thinking_tokens: u64,
+    pub output_tokens: u64,
+    pub thinking_token_share: f64, // Percent of output tokens
+    pub avg_thinking_tokens: f64,  // Per thinking turn
+}
+
+/// Claude Code usage found under one scan root (e.g. one team member)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RootStats {
+    pub label: String, // "local" for the Claude folder of the request
+    pub path: String,
+    pub total_projects: u32,
+    pub total_sessions: u32,
+    pub total_messages: u32,
+    pub total_tokens: u64,
+    pub total_cost_usd: f64,
+    pub first_message: Option<String>,
+    pub last_message: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -77,6 +136,44 @@ pub struct SessionComparison {
     pub is_above_average: bool,
 }
 
+/// One side of a `compare_sessions` comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparedSession {
+    pub session_id: String,
+    pub project_name: Option<String>,
+    pub file_path: String,
+    pub message_count: usize, // Adjusted by the counting rules
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+    pub first_message_time: Option<String>,
+    pub last_message_time: Option<String>,
+    pub duration_seconds: i64,
+    pub models: Vec<String>,
+    pub tools: Vec<ToolUsageStats>, // Most used first
+    pub files_touched: Vec<String>, // Read or modified through file tools
+}
+
+/// Side-by-side comparison of two sessions; differences are `b - a`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionPairComparison {
+    pub a: ComparedSession,
+    pub b: ComparedSession,
+    pub token_difference: i64,
+    pub cost_difference_usd: f64,
+    pub duration_difference_seconds: i64,
+    pub message_difference: i64,
+    pub shared_tools: Vec<String>,
+    pub tools_only_in_a: Vec<String>,
+    pub tools_only_in_b: Vec<String>,
+    pub shared_files: Vec<String>,
+    pub files_only_in_a: Vec<String>,
+    pub files_only_in_b: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DateRange {
     pub first_message: Option<String>,
@@ -84,6 +181,7 @@ pub struct DateRange {
     pub days_span: u32,
 }
 
+/// Usage of one model, with its aliases grouped under the canonical name
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelStats {
     pub model_name: String,
@@ -93,14 +191,31 @@ pub struct ModelStats {
     pub output_tokens: u64,
     pub cache_creation_tokens: u64,
     pub cache_read_tokens: u64,
+    #[serde(default)]
+    pub cost_usd: f64,
+    pub variants: Vec<ModelVariantStats>, // Raw model names, most tokens first
+}
+
+/// Usage under one raw model name (dated snapshot, Bedrock ID, alias...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelVariantStats {
+    pub model_name: String,
+    pub message_count: u32,
+    pub token_count: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectRanking {
     pub project_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>, // None for Claude Code projects
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>, // Scan root label, set when scan roots are registered
     pub sessions: u32,
     pub messages: u32,
     pub tokens: u64,
+    #[serde(default)]
+    pub cost_usd: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -108,7 +223,11 @@ pub struct GlobalStatsSummary {
     pub total_projects: u32,
     pub total_sessions: u32,
     pub total_messages: u32,
+    #[serde(default)]
+    pub raw_total_messages: u32, // Including entries excluded by the counting rules
     pub total_tokens: u64,
+    #[serde(default)]
+    pub total_cost_usd: f64, // Recorded or estimated, see SessionTokenStats
     pub total_session_duration_minutes: u64,
     pub date_range: DateRange,
     pub token_distribution: TokenDistribution,
@@ -117,6 +236,94 @@ pub struct GlobalStatsSummary {
     pub most_used_tools: Vec<ToolUsageStats>,
     pub model_distribution: Vec<ModelStats>,
     pub top_projects: Vec<ProjectRanking>,
+    #[serde(default)]
+    pub source_breakdown: Vec<SourceStats>, // In the order the sources were requested
+    #[serde(default)]
+    pub service_tier_breakdown: Vec<ServiceTierStats>, // Most tokens first
+    #[serde(default)]
+    pub root_breakdown: Vec<RootStats>, // Local folder first; empty without scan roots
+    #[serde(default)]
+    pub work_patterns: WorkPatternStats,
+    #[serde(default)]
+    pub model_daily_stats: Vec<ModelDailyStats>, // By date, then most tokens first
+}
+
+/// Average length of the sessions started in one part of the day (UTC)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimeOfDaySessionLength {
+    pub period: String, // "night" (0-6h), "morning", "afternoon" or "evening" (18-24h)
+    pub session_count: u32,
+    pub avg_duration_minutes: f64, // Active minutes, as in total_session_duration_minutes
+}
+
+/// Session with the most active minutes
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LongestSession {
+    pub project_name: String,
+    pub file_path: String,
+    pub started_at: String, // RFC 3339
+    pub duration_minutes: u64,
+}
+
+/// Insights derived from daily activity: streaks, busiest weekday and
+/// session lengths
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct WorkPatternStats {
+    pub active_days: u32,         // Days (UTC) with at least one message
+    pub current_streak_days: u32, // Consecutive active days up to today or yesterday
+    pub longest_streak_days: u32,
+    pub longest_streak_start: Option<String>, // YYYY-MM-DD, the most recent on ties
+    pub longest_streak_end: Option<String>,
+    pub busiest_weekday: Option<u8>, // Most messages; 0 = Sunday as in ActivityHeatmap
+    pub session_length_by_time_of_day: Vec<TimeOfDaySessionLength>, // Night first
+    pub longest_session: Option<LongestSession>,
+}
+
+/// Usage billed under one service tier ("standard", "priority", "batch"...)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ServiceTierStats {
+    pub service_tier: String, // As recorded (lowercase); "unknown" if not recorded
+    pub response_count: u32,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+}
+
+/// Usage of one history source (`claude-code`, `cursor`...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceStats {
+    pub source: String,
+    pub total_sessions: u32,
+    pub total_messages: u32,
+    pub total_tokens: Option<u64>, // None if the source records no usage
+    pub total_cost_usd: Option<f64>, // None if the source records no usage
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TokenHistogramBucket {
+    pub min_tokens: u64,
+    pub max_tokens: Option<u64>, // None for the open-ended last bucket
+    pub message_count: u64,
+    pub total_tokens: u64,
+    pub token_share: f64, // Fraction of all tokens that fall in this bucket (0.0-1.0)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TokenHistogram {
+    pub buckets: Vec<TokenHistogramBucket>,
+    pub message_count: u64,
+    pub total_tokens: u64,
+    pub median_tokens: u64,
+    pub p90_tokens: u64,
+    pub max_tokens: u64,
+    pub top_decile_token_share: f64, // Share of tokens used by the largest 10% of messages
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenHistograms {
+    pub scope: String,
+    pub session_count: usize,
+    pub input: TokenHistogram, // Context size per message (input + cache tokens)
+    pub output: TokenHistogram,
 }
 
 #[cfg(test)]
@@ -134,9 +341,12 @@ mod tests {
             total_cache_read_tokens: 100,
             total_tokens: 1800,
             message_count: 50,
+            raw_message_count: 64,
             first_message_time: "2025-06-01T10:00:00Z".to_string(),
             last_message_time: "2025-06-01T12:00:00Z".to_string(),
             summary: Some("Test session summary".to_string()),
+            duplicate_usage_entries: 2,
+            total_cost_usd: 0.01,
         };
 
         let serialized = serde_json::to_string(&stats).unwrap();
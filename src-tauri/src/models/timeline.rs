@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+/// A bar of the session timeline
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimelineSpan {
+    pub kind: String,       // "user_wait", "idle", "assistant" or "tool"
+    pub start_time: String, // RFC 3339, UTC
+    pub end_time: String,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>, // Tool spans only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_use_id: Option<String>,
+}
+
+/// A user prompt and everything up to the next one
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimelineTurn {
+    pub index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_uuid: Option<String>, // None for activity before the first prompt
+    pub prompt_preview: String,
+    pub start_time: String,
+    pub end_time: String,
+    pub wait_before_ms: u64, // Pause since the previous turn ended
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reported_duration_ms: Option<u64>, // `durationMs` of the turn's `turn_duration` entry
+    pub assistant_ms: u64,   // Turn duration minus tool time
+    pub tool_ms: u64,        // Parallel tool calls counted once
+    pub tool_count: usize,
+    pub spans: Vec<TimelineSpan>, // Pause before the turn first, then by start time
+}
+
+/// Turn-level timeline of a session's main conversation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionTimeline {
+    pub session_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<String>,
+    pub idle_gap_minutes: u32, // Longer pauses between turns are idle, not user wait
+    pub total_ms: u64,
+    pub user_wait_ms: u64,
+    pub idle_ms: u64,
+    pub assistant_ms: u64,
+    pub tool_ms: u64,
+    pub turns: Vec<TimelineTurn>,
+}
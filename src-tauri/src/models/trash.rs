@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+/// Files and folders moved to the OS trash by a delete command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedItems {
+    pub session_count: usize,
+    pub paths: Vec<String>, // Session files, subagent folders or the project folder
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+/// One 5-hour usage window, as enforced by Claude subscription limits
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UsageBlock {
+    pub start_time: String, // First activity floored to the hour (RFC 3339, UTC)
+    pub end_time: String,   // start_time + 5 hours
+    pub last_activity_time: String, // Timestamp of the last response in the block
+    pub is_active: bool,    // The window is still open
+    pub response_count: u64,
+    pub session_count: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+    pub models: Vec<String>,      // Sorted, distinct
+    pub limit_ratio: Option<f64>, // total_tokens / token_limit
+    pub near_limit: bool,         // limit_ratio reached the warning threshold
+}
+
+/// 5-hour blocks of a scope, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UsageBlockReport {
+    pub scope: String,
+    pub token_limit: Option<u64>, // Given by the caller, else the largest completed block
+    pub limit_is_estimated: bool, // token_limit was derived from history
+    pub blocks: Vec<UsageBlock>,
+}
+
+/// Tokens and cost spent over a time window, and their hourly rate
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BurnRateWindow {
+    pub start_time: String,         // First response in the window (RFC 3339, UTC)
+    pub last_activity_time: String, // Last response in the window
+    pub response_count: u64,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+    pub tokens_per_hour: f64, // Between the first and last response
+    pub cost_per_hour: f64,
+}
+
+/// Current burn rate of a scope and projection of the active block
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BurnRate {
+    pub scope: String,
+    pub generated_at: String,
+    pub today: Option<BurnRateWindow>, // Current UTC day
+    pub active_block: Option<UsageBlock>,
+    pub block_rate: Option<BurnRateWindow>,
+    pub token_limit: Option<u64>, // As in UsageBlockReport
+    pub limit_is_estimated: bool,
+    pub projected_block_tokens: Option<u64>, // At the block rate until the block ends
+    pub projected_limit_time: Option<String>, // When the limit is hit, if before the block ends
+}
@@ -0,0 +1,190 @@
+//! Local usage metrics of the viewer itself
+//!
+//! These records never leave the machine. They are stored in
+//! ~/.claude-history-viewer/usage-metrics.json and only exported when the
+//! user explicitly asks for a report (e.g. to attach to a bug report).
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Current schema version of the persisted metrics file
+pub const USAGE_METRICS_SCHEMA_VERSION: u32 = 1;
+
+/// Aggregated usage of a single viewer feature or backend operation
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct FeatureUsage {
+    pub invocations: u64,
+    pub total_duration_ms: u64,
+    pub max_duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<String>,
+}
+
+/// Root structure of the persisted metrics file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LocalUsageMetrics {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    /// When collection started (RFC 3339)
+    pub collected_since: String,
+    /// Usage keyed by feature name (sorted for stable output)
+    #[serde(default)]
+    pub features: BTreeMap<String, FeatureUsage>,
+}
+
+fn default_version() -> u32 {
+    USAGE_METRICS_SCHEMA_VERSION
+}
+
+impl LocalUsageMetrics {
+    /// Create empty metrics starting at the given timestamp
+    pub fn new(collected_since: String) -> Self {
+        Self {
+            version: USAGE_METRICS_SCHEMA_VERSION,
+            collected_since,
+            features: BTreeMap::new(),
+        }
+    }
+
+    /// Record one invocation of a feature
+    pub fn record(&mut self, feature: &str, duration_ms: Option<u64>, timestamp: &str) {
+        let entry = self.features.entry(feature.to_string()).or_default();
+        entry.invocations += 1;
+        if let Some(duration) = duration_ms {
+            entry.total_duration_ms += duration;
+            entry.max_duration_ms = entry.max_duration_ms.max(duration);
+        }
+        entry.last_used = Some(timestamp.to_string());
+    }
+}
+
+/// Per-feature line of a usage report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureUsageSummary {
+    pub feature: String,
+    pub invocations: u64,
+    pub total_duration_ms: u64,
+    pub avg_duration_ms: f64,
+    pub max_duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<String>,
+}
+
+/// Exportable report of local usage metrics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalUsageReport {
+    pub generated_at: String,
+    pub app_version: String,
+    pub os_type: String,
+    pub arch: String,
+    pub collected_since: String,
+    /// Features sorted by invocation count (descending), then name
+    pub features: Vec<FeatureUsageSummary>,
+}
+
+impl LocalUsageReport {
+    /// Build a report from the collected metrics
+    pub fn from_metrics(metrics: &LocalUsageMetrics, generated_at: String) -> Self {
+        let mut features: Vec<FeatureUsageSummary> = metrics
+            .features
+            .iter()
+            .map(|(name, usage)| FeatureUsageSummary {
+                feature: name.clone(),
+                invocations: usage.invocations,
+                total_duration_ms: usage.total_duration_ms,
+                avg_duration_ms: if usage.invocations > 0 {
+                    usage.total_duration_ms as f64 / usage.invocations as f64
+                } else {
+                    0.0
+                },
+                max_duration_ms: usage.max_duration_ms,
+                last_used: usage.last_used.clone(),
+            })
+            .collect();
+        features.sort_by(|a, b| {
+            b.invocations
+                .cmp(&a.invocations)
+                .then_with(|| a.feature.cmp(&b.feature))
+        });
+
+        Self {
+            generated_at,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os_type: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            collected_since: metrics.collected_since.clone(),
+            features,
+        }
+    }
+}
+
+/// Wall time of one phase of a profiled operation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PhaseTiming {
+    pub phase: String, // "scan", "io", "parse", "aggregate" or "serialize"
+    pub duration_ms: f64,
+    pub share: f64, // Fraction of the summed phases
+}
+
+/// Timing breakdown of one backend operation, for performance reports
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OperationProfile {
+    pub operation: String,
+    pub app_version: String,
+    pub os_type: String,
+    pub worker_threads: usize,
+    pub file_count: usize,
+    pub bytes_read: u64,
+    pub entry_count: usize,
+    pub result_bytes: usize, // Size of the serialized command result
+    pub command_ms: f64,     // Run time of the command itself, files cached
+    pub total_ms: f64,       // Sum of the phases
+    pub phases: Vec<PhaseTiming>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_accumulates_durations() {
+        let mut metrics = LocalUsageMetrics::new("2025-01-01T00:00:00Z".to_string());
+        metrics.record("scan_projects", Some(120), "2025-01-01T00:00:01Z");
+        metrics.record("scan_projects", Some(80), "2025-01-01T00:00:02Z");
+        metrics.record("open_settings", None, "2025-01-01T00:00:03Z");
+
+        let scan = metrics.features.get("scan_projects").unwrap();
+        assert_eq!(scan.invocations, 2);
+        assert_eq!(scan.total_duration_ms, 200);
+        assert_eq!(scan.max_duration_ms, 120);
+        assert_eq!(scan.last_used, Some("2025-01-01T00:00:02Z".to_string()));
+
+        let settings = metrics.features.get("open_settings").unwrap();
+        assert_eq!(settings.invocations, 1);
+        assert_eq!(settings.total_duration_ms, 0);
+    }
+
+    #[test]
+    fn test_report_sorted_by_invocations() {
+        let mut metrics = LocalUsageMetrics::new("2025-01-01T00:00:00Z".to_string());
+        metrics.record("b_feature", Some(10), "2025-01-01T00:00:01Z");
+        metrics.record("a_feature", Some(10), "2025-01-01T00:00:01Z");
+        metrics.record("c_feature", Some(30), "2025-01-01T00:00:01Z");
+        metrics.record("c_feature", Some(10), "2025-01-01T00:00:01Z");
+
+        let report = LocalUsageReport::from_metrics(&metrics, "2025-01-02T00:00:00Z".to_string());
+        let names: Vec<&str> = report.features.iter().map(|f| f.feature.as_str()).collect();
+        assert_eq!(names, vec!["c_feature", "a_feature", "b_feature"]);
+        assert!((report.features[0].avg_duration_ms - 20.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_metrics_serialization_roundtrip() {
+        let mut metrics = LocalUsageMetrics::new("2025-01-01T00:00:00Z".to_string());
+        metrics.record("search_messages", Some(42), "2025-01-01T00:00:01Z");
+
+        let json = serde_json::to_string(&metrics).unwrap();
+        let deserialized: LocalUsageMetrics = serde_json::from_str(&json).unwrap();
+        assert_eq!(metrics, deserialized);
+    }
+}
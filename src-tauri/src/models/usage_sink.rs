@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// State of the usage sink since startup
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct UsageSinkStatus {
+    pub enabled: bool,
+    pub kind: Option<String>,
+    pub usage_rows_sent: u64,
+    pub message_rows_sent: u64,
+    pub pending_rows: usize,          // Waiting for the next delivery
+    pub dropped_rows: u64,            // Oldest rows discarded while undeliverable
+    pub last_sent_at: Option<String>, // RFC 3339
+    pub last_error: Option<String>,   // Cleared by the next successful delivery
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+/// A concrete instance of wasted tokens, for drilling into a category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasteExample {
+    pub session_id: String,
+    pub message_uuid: String,
+    pub timestamp: String,
+    pub description: String,
+    pub wasted_tokens: u64,
+    pub wasted_cost_usd: f64,
+}
+
+/// Wasted tokens attributed to one source of waste
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasteCategory {
+    pub category: String, // "retry_loops", "tool_errors", "max_tokens_truncation" or "reverted_edits"
+    pub occurrences: usize,
+    pub wasted_tokens: u64,
+    pub wasted_cost_usd: f64,
+    pub examples: Vec<WasteExample>, // Largest examples first
+}
+
+/// Per-project estimate of tokens spent without moving the work forward
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasteReport {
+    pub project_name: String,
+    pub session_count: usize,
+    pub total_tokens: u64,
+    pub total_cost_usd: f64,
+    pub wasted_tokens: u64,
+    pub wasted_cost_usd: f64,
+    pub wasted_share: f64, // wasted_tokens / total_tokens (0.0-1.0)
+    pub categories: Vec<WasteCategory>, // Sorted by wasted tokens (descending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_waste_report_serialization() {
+        let report = WasteReport {
+            project_name: "demo".to_string(),
+            session_count: 1,
+            total_tokens: 1000,
+            total_cost_usd: 0.5,
+            wasted_tokens: 100,
+            wasted_cost_usd: 0.05,
+            wasted_share: 0.1,
+            categories: vec![WasteCategory {
+                category: "tool_errors".to_string(),
+                occurrences: 1,
+                wasted_tokens: 100,
+                wasted_cost_usd: 0.05,
+                examples: Vec::new(),
+            }],
+        };
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["categories"][0]["category"], "tool_errors");
+        assert_eq!(json["wasted_tokens"], 100);
+    }
+}
@@ -0,0 +1,419 @@
+//! Model pricing used for cost estimates
+//!
+//! Prices are public list prices in USD per million tokens, taken from the
+//! synced pricing catalog when available (see `sync_pricing_catalog`) and
+//! from the built-in table otherwise, unless the user
+//! configured `pricingOverrides` for negotiated (e.g. Bedrock) contracts or
+//! internal deployments. Estimates are approximate: they ignore batch
+//! discounts, long-context surcharges and subscription plans.
+//!
+//! A user pricing file (see `commands::pricing_file`) can add custom models
+//! and overrides, and scale every cost by a currency multiplier.
+
+use crate::models::{PricingFile, PricingOverride, TokenUsage, UserMetadata};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+/// List price of a model in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+    #[serde(alias = "cacheWrite")]
+    pub cache_write: f64,
+    #[serde(alias = "cacheRead")]
+    pub cache_read: f64,
+}
+
+const OPUS_4_5: ModelPricing = ModelPricing {
+    input: 5.0,
+    output: 25.0,
+    cache_write: 6.25,
+    cache_read: 0.50,
+};
+
+const OPUS: ModelPricing = ModelPricing {
+    input: 15.0,
+    output: 75.0,
+    cache_write: 18.75,
+    cache_read: 1.50,
+};
+
+const SONNET: ModelPricing = ModelPricing {
+    input: 3.0,
+    output: 15.0,
+    cache_write: 3.75,
+    cache_read: 0.30,
+};
+
+const HAIKU_4_5: ModelPricing = ModelPricing {
+    input: 1.0,
+    output: 5.0,
+    cache_write: 1.25,
+    cache_read: 0.10,
+};
+
+const HAIKU_3_5: ModelPricing = ModelPricing {
+    input: 0.80,
+    output: 4.0,
+    cache_write: 1.0,
+    cache_read: 0.08,
+};
+
+const HAIKU_3: ModelPricing = ModelPricing {
+    input: 0.25,
+    output: 1.25,
+    cache_write: 0.30,
+    cache_read: 0.03,
+};
+
+/// Pricing used when the model is missing or unrecognized
+pub const DEFAULT_PRICING: ModelPricing = SONNET;
+
+/// Synced catalog prices keyed by lowercase model ID
+static CATALOG_PRICING: OnceLock<RwLock<HashMap<String, ModelPricing>>> = OnceLock::new();
+
+fn catalog_pricing() -> &'static RwLock<HashMap<String, ModelPricing>> {
+    CATALOG_PRICING.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Replace the catalog prices used ahead of the built-in table
+pub fn set_catalog_pricing(catalog: impl IntoIterator<Item = (String, ModelPricing)>) {
+    if let Ok(mut current) = catalog_pricing().write() {
+        *current = catalog.into_iter().collect();
+    }
+}
+
+/// Find a model in the catalog, also trying the ID without a provider prefix
+/// (e.g. "bedrock/claude-...")
+fn catalog_lookup(catalog: &HashMap<String, ModelPricing>, model: &str) -> Option<ModelPricing> {
+    catalog.get(model).copied().or_else(|| {
+        model
+            .rsplit_once('/')
+            .and_then(|(_, id)| catalog.get(id).copied())
+    })
+}
+
+/// Look up pricing by model ID (e.g. "claude-opus-4-20250514")
+///
+/// Custom models of the pricing file come first, then the synced catalog,
+/// then the built-in table. Returns None for synthetic entries that were
+/// never billed.
+pub fn pricing_for_model(model: &str) -> Option<ModelPricing> {
+    let model = model.to_lowercase();
+    if model == "<synthetic>" {
+        return None;
+    }
+    if let Some(pricing) = pricing_file()
+        .read()
+        .ok()
+        .and_then(|file| catalog_lookup(&file.models, &model))
+    {
+        return Some(pricing);
+    }
+    if let Some(pricing) = catalog_pricing()
+        .read()
+        .ok()
+        .and_then(|catalog| catalog_lookup(&catalog, &model))
+    {
+        return Some(pricing);
+    }
+
+    let pricing = if model.contains("opus") {
+        if model.contains("opus-4-5") || model.contains("opus-4.5") {
+            OPUS_4_5
+        } else {
+            OPUS
+        }
+    } else if model.contains("haiku") {
+        if model.contains("haiku-4-5") || model.contains("haiku-4.5") {
+            HAIKU_4_5
+        } else if model.contains("3-5-haiku") || model.contains("haiku-3-5") {
+            HAIKU_3_5
+        } else if model.contains("3-haiku") || model.contains("haiku-3") {
+            HAIKU_3
+        } else {
+            HAIKU_4_5
+        }
+    } else {
+        DEFAULT_PRICING
+    };
+    Some(pricing)
+}
+
+const FREE: ModelPricing = ModelPricing {
+    input: 0.0,
+    output: 0.0,
+    cache_write: 0.0,
+    cache_read: 0.0,
+};
+
+impl PricingOverride {
+    /// Whether this override applies; unset patterns match anything
+    fn matches(&self, model: Option<&str>, project: Option<&str>) -> bool {
+        let pattern_matches = |pattern: &Option<String>, value: Option<&str>| match pattern {
+            None => true,
+            Some(pattern) => value.is_some_and(|value| {
+                UserMetadata::matches_glob_pattern(&value.to_lowercase(), &pattern.to_lowercase())
+            }),
+        };
+        pattern_matches(&self.model_pattern, model)
+            && pattern_matches(&self.project_pattern, project)
+    }
+
+    fn apply(&self, base: ModelPricing) -> ModelPricing {
+        if self.free {
+            return FREE;
+        }
+        ModelPricing {
+            input: self.input.unwrap_or(base.input),
+            output: self.output.unwrap_or(base.output),
+            cache_write: self.cache_write.unwrap_or(base.cache_write),
+            cache_read: self.cache_read.unwrap_or(base.cache_read),
+        }
+    }
+}
+
+/// User-configured overrides, in priority order
+static PRICING_OVERRIDES: OnceLock<RwLock<Vec<PricingOverride>>> = OnceLock::new();
+
+fn pricing_overrides() -> &'static RwLock<Vec<PricingOverride>> {
+    PRICING_OVERRIDES.get_or_init(|| RwLock::new(Vec::new()))
+}
+
+/// Apply the user's `pricingOverrides` setting
+pub fn set_pricing_overrides(overrides: Vec<PricingOverride>) {
+    if let Ok(mut current) = pricing_overrides().write() {
+        *current = overrides;
+    }
+}
+
+/// Contents of the user pricing file (empty when there is none)
+static PRICING_FILE: OnceLock<RwLock<PricingFile>> = OnceLock::new();
+
+fn pricing_file() -> &'static RwLock<PricingFile> {
+    PRICING_FILE.get_or_init(|| RwLock::new(PricingFile::default()))
+}
+
+/// Apply a (re)loaded pricing file
+pub fn set_pricing_file(mut file: PricingFile) {
+    file.models = file
+        .models
+        .into_iter()
+        .map(|(model, pricing)| (model.to_lowercase(), pricing))
+        .collect();
+    if let Ok(mut current) = pricing_file().write() {
+        *current = file;
+    }
+}
+
+/// Factor applied to every cost by the pricing file
+fn currency_multiplier() -> f64 {
+    pricing_file()
+        .read()
+        .ok()
+        .and_then(|file| file.currency_multiplier)
+        .unwrap_or(1.0)
+}
+
+/// Pricing of a response, taking the first matching override into account
+///
+/// `project` is the working directory the session ran in. Returns None for
+/// synthetic entries that were never billed.
+pub fn resolve_pricing<'a>(
+    overrides: impl IntoIterator<Item = &'a PricingOverride>,
+    model: Option<&str>,
+    project: Option<&str>,
+) -> Option<ModelPricing> {
+    let base = match model {
+        Some(model) => pricing_for_model(model)?,
+        None => DEFAULT_PRICING,
+    };
+    Some(
+        overrides
+            .into_iter()
+            .find(|o| o.matches(model, project))
+            .map_or(base, |o| o.apply(base)),
+    )
+}
+
+/// Estimate the cost of a single response's token usage
+///
+/// In USD unless the pricing file sets a currency multiplier.
+pub fn estimate_cost_usd(model: Option<&str>, project: Option<&str>, usage: &TokenUsage) -> f64 {
+    let pricing = {
+        let settings = pricing_overrides().read().ok();
+        let file = pricing_file().read().ok();
+        let settings = settings.as_deref().map_or(&[][..], Vec::as_slice);
+        let file = file
+            .as_deref()
+            .map_or(&[][..], |file| file.overrides.as_slice());
+        resolve_pricing(settings.iter().chain(file), model, project)
+    };
+    let Some(pricing) = pricing else {
+        return 0.0;
+    };
+
+    let per_token = |tokens: Option<u32>, price_per_mtok: f64| {
+        f64::from(tokens.unwrap_or(0)) * price_per_mtok / 1_000_000.0
+    };
+
+    (per_token(usage.input_tokens, pricing.input)
+        + per_token(usage.output_tokens, pricing.output)
+        + per_token(usage.cache_creation_input_tokens, pricing.cache_write)
+        + per_token(usage.cache_read_input_tokens, pricing.cache_read))
+        * currency_multiplier()
+}
+
+/// Cost of a response: the `costUSD` the log recorded, or the estimate from
+/// its token usage when the entry has none
+///
+/// Both are scaled by the pricing file's currency multiplier.
+pub fn message_cost_usd(
+    recorded: Option<f64>,
+    model: Option<&str>,
+    project: Option<&str>,
+    usage: &TokenUsage,
+) -> f64 {
+    recorded
+        .filter(|cost| cost.is_finite() && *cost >= 0.0)
+        .map_or_else(
+            || estimate_cost_usd(model, project, usage),
+            |cost| cost * currency_multiplier(),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(input: u32, output: u32, cache_write: u32, cache_read: u32) -> TokenUsage {
+        TokenUsage {
+            input_tokens: Some(input),
+            output_tokens: Some(output),
+            cache_creation_input_tokens: Some(cache_write),
+            cache_read_input_tokens: Some(cache_read),
+            service_tier: None,
+        }
+    }
+
+    #[test]
+    fn test_pricing_for_model_families() {
+        assert_eq!(pricing_for_model("claude-opus-4-20250514"), Some(OPUS));
+        assert_eq!(
+            pricing_for_model("claude-opus-4-5-20251101"),
+            Some(OPUS_4_5)
+        );
+        assert_eq!(
+            pricing_for_model("claude-sonnet-4-5-20250929"),
+            Some(SONNET)
+        );
+        assert_eq!(
+            pricing_for_model("claude-3-5-haiku-20241022"),
+            Some(HAIKU_3_5)
+        );
+        assert_eq!(
+            pricing_for_model("claude-haiku-4-5-20251001"),
+            Some(HAIKU_4_5)
+        );
+        assert_eq!(pricing_for_model("<synthetic>"), None);
+    }
+
+    #[test]
+    fn test_estimate_cost_usd() {
+        let cost = estimate_cost_usd(
+            Some("claude-sonnet-4-20250514"),
+            None,
+            &usage(1_000_000, 1_000_000, 0, 0),
+        );
+        assert!((cost - 18.0).abs() < 1e-9);
+
+        let cost = estimate_cost_usd(None, None, &usage(0, 0, 1_000_000, 1_000_000));
+        assert!((cost - 4.05).abs() < 1e-9);
+
+        assert!(
+            estimate_cost_usd(Some("<synthetic>"), None, &usage(10, 10, 0, 0)).abs() < f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn test_message_cost_usd_prefers_recorded_cost() {
+        let usage = usage(1_000_000, 0, 0, 0);
+        let model = Some("claude-opus-4-20250514");
+        assert!((message_cost_usd(Some(0.25), model, None, &usage) - 0.25).abs() < 1e-9);
+        assert!((message_cost_usd(None, model, None, &usage) - 15.0).abs() < 1e-9);
+        assert!((message_cost_usd(Some(f64::NAN), model, None, &usage) - 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_resolve_pricing_overrides() {
+        let overrides = vec![
+            PricingOverride {
+                project_pattern: Some("/work/internal/*".to_string()),
+                free: true,
+                ..Default::default()
+            },
+            PricingOverride {
+                model_pattern: Some("*OPUS*".to_string()),
+                input: Some(12.0),
+                ..Default::default()
+            },
+        ];
+
+        let opus = resolve_pricing(
+            &overrides,
+            Some("claude-opus-4-20250514"),
+            Some("/work/acme"),
+        )
+        .unwrap();
+        assert!((opus.input - 12.0).abs() < f64::EPSILON);
+        assert!((opus.output - OPUS.output).abs() < f64::EPSILON);
+
+        assert_eq!(
+            resolve_pricing(
+                &overrides,
+                Some("claude-opus-4-20250514"),
+                Some("/work/internal/tool")
+            ),
+            Some(FREE)
+        );
+        assert_eq!(
+            resolve_pricing(&overrides, Some("claude-sonnet-4-20250514"), None),
+            Some(SONNET)
+        );
+        assert_eq!(
+            resolve_pricing(&overrides, Some("<synthetic>"), Some("/work/acme")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_resolve_pricing_checks_setting_overrides_before_file_overrides() {
+        let settings = [PricingOverride {
+            model_pattern: Some("*opus*".to_string()),
+            input: Some(10.0),
+            ..Default::default()
+        }];
+        let file = [PricingOverride {
+            input: Some(1.0),
+            ..Default::default()
+        }];
+        let resolve = |model| resolve_pricing(settings.iter().chain(&file), Some(model), None);
+
+        assert!((resolve("claude-opus-4-20250514").unwrap().input - 10.0).abs() < f64::EPSILON);
+        assert!((resolve("claude-sonnet-4-20250514").unwrap().input - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_catalog_lookup_strips_provider_prefix() {
+        let catalog = HashMap::from([("claude-sonnet-5".to_string(), HAIKU_3)]);
+
+        assert_eq!(catalog_lookup(&catalog, "claude-sonnet-5"), Some(HAIKU_3));
+        assert_eq!(
+            catalog_lookup(&catalog, "bedrock/claude-sonnet-5"),
+            Some(HAIKU_3)
+        );
+        assert_eq!(catalog_lookup(&catalog, "claude-sonnet-6"), None);
+    }
+}
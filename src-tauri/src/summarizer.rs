@@ -0,0 +1,398 @@
+//! Session summaries generated by a language model
+//!
+//! The `summarizer` setting picks the backend: the Anthropic API, a local
+//! Ollama server or a llama.cpp server (its OpenAI-compatible endpoint).
+//! Backends only describe their HTTP request and where the text is in the
+//! response; `summarize` does the sending, so adding one means adding a
+//! `SummarizerBackend` implementation and its name in `backend_for`.
+
+use crate::commands::prompt_quality::prompt_text;
+use crate::commands::session::is_genuine_user_text;
+use crate::models::{ClaudeMessage, SummarizerSettings};
+use crate::redaction::redact_text;
+use serde_json::{json, Value};
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// Transcript characters sent when `maxInputChars` is unset
+pub const DEFAULT_MAX_INPUT_CHARS: usize = 48_000;
+
+/// Share of the character budget kept from the start of a long transcript;
+/// the rest comes from its end
+const HEAD_SHARE: usize = 4;
+
+/// Length limit of the generated summary
+const MAX_SUMMARY_TOKENS: u32 = 1024;
+
+/// Local models can take minutes on long transcripts
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+const ANTHROPIC_API_URL: &str = "https://api.anthropic.com";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const OLLAMA_URL: &str = "http://localhost:11434";
+const LLAMACPP_URL: &str = "http://localhost:8080";
+
+const SYSTEM_PROMPT: &str = "You summarize coding sessions between a developer and an AI \
+coding assistant. Write a short title line, then a few bullet points covering the goal, \
+what was changed, and anything left unfinished. Only use facts from the transcript.";
+
+static SUMMARIZER_SETTINGS: RwLock<Option<SummarizerSettings>> = RwLock::new(None);
+
+/// HTTP request asking a backend for a summary
+#[derive(Debug, PartialEq)]
+pub struct BackendRequest {
+    pub url: String,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Value,
+}
+
+/// A model server able to summarize a transcript
+pub trait SummarizerBackend: Send + Sync {
+    /// Request for a summary of `transcript`
+    fn request(&self, transcript: &str) -> BackendRequest;
+
+    /// Generated text of a successful response
+    fn summary_of(&self, response: &Value) -> Option<String>;
+}
+
+fn base_url(endpoint: Option<&String>, default: &str) -> String {
+    endpoint
+        .map_or(default, String::as_str)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Anthropic Messages API
+pub struct AnthropicBackend {
+    endpoint: String,
+    model: String,
+    api_key: String,
+}
+
+impl SummarizerBackend for AnthropicBackend {
+    fn request(&self, transcript: &str) -> BackendRequest {
+        BackendRequest {
+            url: format!("{}/v1/messages", self.endpoint),
+            headers: vec![
+                ("x-api-key", self.api_key.clone()),
+                ("anthropic-version", ANTHROPIC_VERSION.to_string()),
+            ],
+            body: json!({
+                "model": self.model,
+                "max_tokens": MAX_SUMMARY_TOKENS,
+                "system": SYSTEM_PROMPT,
+                "messages": [{"role": "user", "content": transcript}],
+            }),
+        }
+    }
+
+    fn summary_of(&self, response: &Value) -> Option<String> {
+        let text: Vec<&str> = response
+            .get("content")?
+            .as_array()?
+            .iter()
+            .filter(|block| block.get("type").and_then(Value::as_str) == Some("text"))
+            .filter_map(|block| block.get("text").and_then(Value::as_str))
+            .collect();
+        (!text.is_empty()).then(|| text.join(""))
+    }
+}
+
+/// Ollama chat API
+pub struct OllamaBackend {
+    endpoint: String,
+    model: String,
+}
+
+impl SummarizerBackend for OllamaBackend {
+    fn request(&self, transcript: &str) -> BackendRequest {
+        BackendRequest {
+            url: format!("{}/api/chat", self.endpoint),
+            headers: Vec::new(),
+            body: json!({
+                "model": self.model,
+                "stream": false,
+                "options": {"num_predict": MAX_SUMMARY_TOKENS},
+                "messages": [
+                    {"role": "system", "content": SYSTEM_PROMPT},
+                    {"role": "user", "content": transcript},
+                ],
+            }),
+        }
+    }
+
+    fn summary_of(&self, response: &Value) -> Option<String> {
+        response
+            .pointer("/message/content")
+            .and_then(Value::as_str)
+            .map(ToString::to_string)
+    }
+}
+
+/// llama.cpp server, through its OpenAI-compatible chat completions API
+pub struct LlamaCppBackend {
+    endpoint: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+impl SummarizerBackend for LlamaCppBackend {
+    fn request(&self, transcript: &str) -> BackendRequest {
+        BackendRequest {
+            url: format!("{}/v1/chat/completions", self.endpoint),
+            headers: self
+                .api_key
+                .iter()
+                .map(|key| ("Authorization", format!("Bearer {key}")))
+                .collect(),
+            body: json!({
+                "model": self.model,
+                "max_tokens": MAX_SUMMARY_TOKENS,
+                "messages": [
+                    {"role": "system", "content": SYSTEM_PROMPT},
+                    {"role": "user", "content": transcript},
+                ],
+            }),
+        }
+    }
+
+    fn summary_of(&self, response: &Value) -> Option<String> {
+        response
+            .pointer("/choices/0/message/content")
+            .and_then(Value::as_str)
+            .map(ToString::to_string)
+    }
+}
+
+/// Backend described by `settings`
+pub fn backend_for(settings: &SummarizerSettings) -> Result<Box<dyn SummarizerBackend>, String> {
+    let endpoint = settings.endpoint.as_ref();
+    let model = settings.model.clone();
+    match settings.backend.as_str() {
+        "anthropic" => Ok(Box::new(AnthropicBackend {
+            endpoint: base_url(endpoint, ANTHROPIC_API_URL),
+            model,
+            api_key: settings
+                .api_key
+                .clone()
+                .ok_or("The Anthropic summarizer needs an API key")?,
+        })),
+        "ollama" => Ok(Box::new(OllamaBackend {
+            endpoint: base_url(endpoint, OLLAMA_URL),
+            model,
+        })),
+        "llamacpp" => Ok(Box::new(LlamaCppBackend {
+            endpoint: base_url(endpoint, LLAMACPP_URL),
+            model,
+            api_key: settings.api_key.clone(),
+        })),
+        other => Err(format!("Unknown summarizer backend: {other}")),
+    }
+}
+
+/// Check the `summarizer` setting
+pub fn validate_summarizer_settings(settings: Option<&SummarizerSettings>) -> Result<(), String> {
+    let Some(settings) = settings else {
+        return Ok(());
+    };
+    if settings.model.trim().is_empty() {
+        return Err("The summarizer model is required".to_string());
+    }
+    if let Some(endpoint) = &settings.endpoint {
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            return Err("The summarizer endpoint must be an http(s) URL".to_string());
+        }
+    }
+    if settings.max_input_chars == Some(0) {
+        return Err("The summarizer input limit must be positive".to_string());
+    }
+    backend_for(settings).map(|_| ())
+}
+
+/// Apply the `summarizer` setting
+pub fn set_summarizer_settings(settings: Option<SummarizerSettings>) {
+    if let Ok(mut current) = SUMMARIZER_SETTINGS.write() {
+        *current = settings;
+    }
+}
+
+pub fn summarizer_settings() -> Option<SummarizerSettings> {
+    SUMMARIZER_SETTINGS
+        .read()
+        .ok()
+        .and_then(|settings| settings.clone())
+}
+
+/// Text of the assistant's text blocks and the names of the tools it called
+fn assistant_text(content: &Value) -> Option<String> {
+    let lines: Vec<String> = content
+        .as_array()?
+        .iter()
+        .filter_map(|block| match block.get("type").and_then(Value::as_str) {
+            Some("text") => block
+                .get("text")
+                .and_then(Value::as_str)
+                .map(ToString::to_string),
+            Some("tool_use") => block
+                .get("name")
+                .and_then(Value::as_str)
+                .map(|name| format!("[used {name}]")),
+            _ => None,
+        })
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Conversation text of `messages` (prompts, replies and tool names), and
+/// whether its middle was cut to fit `max_chars`
+pub fn session_transcript(messages: &[ClaudeMessage], max_chars: usize) -> (String, bool) {
+    let turns: Vec<String> = messages
+        .iter()
+        .filter(|message| !message.is_sidechain.unwrap_or(false))
+        .filter_map(|message| {
+            let content = message.content.as_ref()?;
+            match message.message_type.as_str() {
+                "user" => prompt_text(content)
+                    .filter(|text| is_genuine_user_text(text))
+                    .map(|text| format!("User: {text}")),
+                "assistant" => assistant_text(content).map(|text| format!("Assistant: {text}")),
+                _ => None,
+            }
+        })
+        .collect();
+    let transcript = turns.join("\n\n");
+
+    let length = transcript.chars().count();
+    if length <= max_chars {
+        return (transcript, false);
+    }
+    let head = max_chars / HEAD_SHARE;
+    let tail = max_chars - head;
+    let start: String = transcript.chars().take(head).collect();
+    let end: String = transcript.chars().skip(length - tail).collect();
+    (format!("{start}\n\n[...]\n\n{end}"), true)
+}
+
+/// Ask the configured backend to summarize `transcript`
+pub async fn summarize(settings: &SummarizerSettings, transcript: &str) -> Result<String, String> {
+    let backend = backend_for(settings)?;
+    let transcript = if settings.redact {
+        redact_text(transcript)
+    } else {
+        transcript.to_string()
+    };
+    let request = backend.request(&transcript);
+
+    let client = tauri_plugin_http::reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+    let mut builder = client.post(&request.url).json(&request.body);
+    for (name, value) in &request.headers {
+        builder = builder.header(*name, value);
+    }
+    let response: Value = builder
+        .send()
+        .await
+        .and_then(tauri_plugin_http::reqwest::Response::error_for_status)
+        .map_err(|e| format!("Summarizer request failed: {e}"))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid summarizer response: {e}"))?;
+
+    backend
+        .summary_of(&response)
+        .map(|summary| summary.trim().to_string())
+        .filter(|summary| !summary.is_empty())
+        .ok_or_else(|| "The summarizer returned no text".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MessageBuilder;
+
+    fn settings(backend: &str) -> SummarizerSettings {
+        SummarizerSettings {
+            backend: backend.to_string(),
+            model: "llama3.1:8b".to_string(),
+            endpoint: None,
+            api_key: Some("key".to_string()),
+            max_input_chars: None,
+            redact: false,
+        }
+    }
+
+    #[test]
+    fn test_backends_build_their_requests() {
+        let ollama = backend_for(&settings("ollama")).unwrap();
+        let request = ollama.request("User: hi");
+        assert_eq!(request.url, "http://localhost:11434/api/chat");
+        assert_eq!(request.body["stream"], false);
+        assert_eq!(request.body["messages"][1]["content"], "User: hi");
+        assert_eq!(
+            ollama.summary_of(&json!({"message": {"role": "assistant", "content": "Done"}})),
+            Some("Done".to_string())
+        );
+
+        let mut llamacpp_settings = settings("llamacpp");
+        llamacpp_settings.endpoint = Some("http://gpu-box:9000/".to_string());
+        let llamacpp = backend_for(&llamacpp_settings).unwrap();
+        let request = llamacpp.request("User: hi");
+        assert_eq!(request.url, "http://gpu-box:9000/v1/chat/completions");
+        assert_eq!(
+            request.headers,
+            [("Authorization", "Bearer key".to_string())]
+        );
+        assert_eq!(
+            llamacpp.summary_of(&json!({"choices": [{"message": {"content": "Done"}}]})),
+            Some("Done".to_string())
+        );
+
+        let anthropic = backend_for(&settings("anthropic")).unwrap();
+        let request = anthropic.request("User: hi");
+        assert_eq!(request.url, "https://api.anthropic.com/v1/messages");
+        assert_eq!(request.headers[0], ("x-api-key", "key".to_string()));
+        assert_eq!(
+            anthropic.summary_of(&json!({"content": [{"type": "text", "text": "Done"}]})),
+            Some("Done".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_summarizer_settings() {
+        assert!(validate_summarizer_settings(Some(&settings("ollama"))).is_ok());
+        assert!(validate_summarizer_settings(Some(&settings("openai"))).is_err());
+
+        let mut no_key = settings("anthropic");
+        no_key.api_key = None;
+        assert!(validate_summarizer_settings(Some(&no_key)).is_err());
+    }
+
+    #[test]
+    fn test_transcript_keeps_conversation_and_cuts_the_middle() {
+        let messages = vec![
+            MessageBuilder::user()
+                .with_text_content("Fix the login bug")
+                .build(),
+            MessageBuilder::assistant()
+                .with_content(json!([
+                    {"type": "text", "text": "Looking at auth.rs"},
+                    {"type": "tool_use", "id": "t1", "name": "Edit", "input": {}}
+                ]))
+                .build(),
+        ];
+
+        let (transcript, truncated) = session_transcript(&messages, 1000);
+        assert_eq!(
+            transcript,
+            "User: Fix the login bug\n\nAssistant: Looking at auth.rs\n[used Edit]"
+        );
+        assert!(!truncated);
+
+        let (transcript, truncated) = session_transcript(&messages, 20);
+        assert!(truncated);
+        assert!(transcript.starts_with("User:"));
+        assert!(transcript.ends_with("[used Edit]"));
+    }
+}
@@ -9,7 +9,7 @@ use crate::models::*;
 use serde_json::json;
 use std::fs::{self, File};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tempfile::TempDir;
 
 // Re-export commonly used test utilities
@@ -80,6 +80,7 @@ pub struct MessageBuilder {
     role: Option<String>,
     model: Option<String>,
     usage: Option<TokenUsage>,
+    message_id: Option<String>,
 }
 
 impl MessageBuilder {
@@ -162,6 +163,19 @@ impl MessageBuilder {
         self
     }
 
+    /// Set the service tier of the usage (call after `with_usage`)
+    pub fn with_service_tier(mut self, service_tier: &str) -> Self {
+        if let Some(usage) = &mut self.usage {
+            usage.service_tier = Some(service_tier.to_string());
+        }
+        self
+    }
+
+    pub fn with_message_id(mut self, message_id: &str) -> Self {
+        self.message_id = Some(message_id.to_string());
+        self
+    }
+
     pub fn build(self) -> ClaudeMessage {
         ClaudeMessage {
             uuid: self
@@ -185,7 +199,7 @@ impl MessageBuilder {
             stop_reason: None,
             cost_usd: None,
             duration_ms: None,
-            message_id: None,
+            message_id: self.message_id,
             snapshot: None,
             is_snapshot_update: None,
             data: None,
@@ -200,6 +214,7 @@ impl MessageBuilder {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            git_branch: None,
         }
     }
 
@@ -230,11 +245,18 @@ impl MessageBuilder {
             msg["message"]["model"] = json!(model);
         }
 
+        if let Some(message_id) = &self.message_id {
+            msg["message"]["id"] = json!(message_id);
+        }
+
         if let Some(usage) = &self.usage {
             msg["message"]["usage"] = json!({
                 "input_tokens": usage.input_tokens,
                 "output_tokens": usage.output_tokens
             });
+            if let Some(service_tier) = &usage.service_tier {
+                msg["message"]["usage"]["service_tier"] = json!(service_tier);
+            }
         }
 
         serde_json::to_string(&msg).expect("Failed to serialize message")
@@ -250,6 +272,77 @@ pub fn create_jsonl_content(messages: &[MessageBuilder]) -> String {
         .join("\n")
 }
 
+/// Parse a raw log entry from its JSON form
+pub fn raw_entry(value: serde_json::Value) -> RawLogEntry {
+    serde_json::from_value(value).expect("Invalid raw log entry")
+}
+
+/// Raw user prompt line of session `s1`
+pub fn prompt_line(uuid: &str, timestamp: &str, text: &str) -> serde_json::Value {
+    json!({
+        "uuid": uuid,
+        "sessionId": "s1",
+        "timestamp": timestamp,
+        "type": "user",
+        "message": {"role": "user", "content": text}
+    })
+}
+
+/// Raw Claude Sonnet 4 response line of session `s1`
+///
+/// The `uuid` is derived from `message_id` and `timestamp`, so streamed
+/// parts of the same response get distinct line IDs.
+pub fn response_line(
+    message_id: &str,
+    timestamp: &str,
+    content: serde_json::Value,
+    input_tokens: u32,
+    output_tokens: u32,
+) -> serde_json::Value {
+    json!({
+        "uuid": format!("{message_id}-{timestamp}"),
+        "sessionId": "s1",
+        "timestamp": timestamp,
+        "type": "assistant",
+        "message": {
+            "id": message_id,
+            "role": "assistant",
+            "model": "claude-sonnet-4-20250514",
+            "content": content,
+            "usage": {"input_tokens": input_tokens, "output_tokens": output_tokens}
+        }
+    })
+}
+
+/// `tool_use` content block of an assistant response
+pub fn tool_use_block(id: &str, name: &str, input: serde_json::Value) -> serde_json::Value {
+    json!({"type": "tool_use", "id": id, "name": name, "input": input})
+}
+
+/// Raw line of session `s1` returning the result of tool call `tool_use_id`
+pub fn tool_result_line(
+    timestamp: &str,
+    tool_use_id: &str,
+    content: &str,
+    is_error: bool,
+) -> serde_json::Value {
+    json!({
+        "uuid": format!("result-{tool_use_id}"),
+        "sessionId": "s1",
+        "timestamp": timestamp,
+        "type": "user",
+        "message": {"role": "user", "content": [
+            {"type": "tool_result", "tool_use_id": tool_use_id, "content": content, "is_error": is_error}
+        ]}
+    })
+}
+
+/// Write raw log lines to `path` as a JSONL file
+pub fn write_jsonl(path: &Path, lines: &[serde_json::Value]) {
+    let content: Vec<String> = lines.iter().map(ToString::to_string).collect();
+    fs::write(path, content.join("\n")).expect("Failed to write JSONL file");
+}
+
 /// Proptest strategies for generating test data
 pub mod strategies {
     use super::*;
@@ -365,4 +458,36 @@ mod tests {
 
         assert_eq!(lines.len(), 2);
     }
+
+    #[test]
+    fn test_raw_line_helpers() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("s1.jsonl");
+        write_jsonl(
+            &path,
+            &[
+                prompt_line("u1", "2025-01-01T00:00:00Z", "Hello"),
+                response_line(
+                    "msg_1",
+                    "2025-01-01T00:00:01Z",
+                    json!([tool_use_block("t1", "Read", json!({"file_path": "a.rs"}))]),
+                    10,
+                    5,
+                ),
+                tool_result_line("2025-01-01T00:00:02Z", "t1", "ok", false),
+            ],
+        );
+
+        let content = fs::read_to_string(&path).unwrap();
+        let entries: Vec<RawLogEntry> = content
+            .lines()
+            .map(|line| raw_entry(serde_json::from_str(line).unwrap()))
+            .collect();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            entries[1].uuid.as_deref(),
+            Some("msg_1-2025-01-01T00:00:01Z")
+        );
+        assert_eq!(entries[2].message_type, "user");
+    }
 }
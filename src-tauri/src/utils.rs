@@ -1,4 +1,9 @@
+use crate::errors::AppError;
 use memchr::memchr_iter;
+use std::borrow::Cow;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
 
 /// Estimated average bytes per JSONL line (used for capacity pre-allocation)
 /// Based on typical Claude message sizes (800-1200 bytes average)
@@ -59,6 +64,233 @@ pub fn extract_project_name(raw_project_name: &str) -> String {
     }
 }
 
+/// Aliases that do not follow the dated-snapshot naming scheme
+const MODEL_ALIASES: &[(&str, &str)] = &[
+    ("claude-opus-4-0", "claude-opus-4"),
+    ("claude-sonnet-4-0", "claude-sonnet-4"),
+    ("claude-3-5-sonnet-v2", "claude-3-5-sonnet"),
+];
+
+/// Provider prefixes in front of the Anthropic model ID
+const MODEL_PROVIDER_PREFIXES: &[&str] = &["bedrock/", "vertex_ai/", "anthropic/"];
+
+/// Canonical name of a model, grouping its dated snapshots, `-latest`
+/// aliases and Bedrock/Vertex IDs
+/// (e.g. "us.anthropic.claude-sonnet-4-20250514-v1:0" -> "claude-sonnet-4")
+pub fn normalize_model_name(model: &str) -> String {
+    let mut name = model.trim().to_lowercase();
+
+    // Bedrock ARNs: "arn:aws:bedrock:<region>:<account>:inference-profile/<id>"
+    if name.starts_with("arn:") {
+        if let Some((_, id)) = name.rsplit_once('/') {
+            name = id.to_string();
+        }
+    }
+    for prefix in MODEL_PROVIDER_PREFIXES {
+        if let Some(rest) = name.strip_prefix(prefix) {
+            name = rest.to_string();
+        }
+    }
+    // Bedrock IDs: "[us.|eu.|apac.]anthropic.<id>-v1:0"
+    if let Some(pos) = name.find("anthropic.") {
+        name = name[pos + "anthropic.".len()..].to_string();
+    }
+    if let Some(pos) = name.rfind("-v") {
+        let version = &name[pos + 2..];
+        if version.contains(':') && version.chars().all(|c| c.is_ascii_digit() || c == ':') {
+            name.truncate(pos);
+        }
+    }
+    // Vertex snapshots: "claude-sonnet-4@20250514"
+    if let Some(pos) = name.find('@') {
+        name.truncate(pos);
+    }
+    if let Some(stripped) = name.strip_suffix("-latest") {
+        name = stripped.to_string();
+    }
+    // Dated snapshots: "claude-opus-4-20250514"
+    if let Some((base, date)) = name.rsplit_once('-') {
+        if date.len() == 8 && date.chars().all(|c| c.is_ascii_digit()) {
+            name = base.to_string();
+        }
+    }
+
+    MODEL_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map_or(name, |(_, canonical)| (*canonical).to_string())
+}
+
+/// Derive a stable ID for an entry that has no `uuid` of its own
+///
+/// Built from the session ID and the 1-based line number so that repeated
+/// loads and exports of the same file always yield the same ID. Entries
+/// without a `sessionId` (e.g. summaries) use the file stem instead, so
+/// lines of different files never share an ID.
+pub fn stable_line_id(session_id: Option<&str>, session_path: &Path, line_num: usize) -> String {
+    let prefix = session_id.map_or_else(
+        || {
+            session_path
+                .file_stem()
+                .map_or(Cow::Borrowed("unknown-session"), |stem| {
+                    stem.to_string_lossy()
+                })
+        },
+        Cow::Borrowed,
+    );
+    format!("{prefix}-line-{}", line_num + 1)
+}
+
+/// Collect all session files (*.jsonl) of every project under `projects_path`
+///
+/// Returns `(project_name, session_path)` pairs, where `project_name` is the
+/// raw project directory name.
+pub fn collect_session_files(projects_path: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    let mut session_files = Vec::new();
+
+    for project_entry in fs::read_dir(projects_path).map_err(|e| e.to_string())? {
+        let project_entry = project_entry.map_err(|e| e.to_string())?;
+        let project_path = project_entry.path();
+
+        if !project_path.is_dir() {
+            continue;
+        }
+
+        let project_name = file_name_string(&project_path).unwrap_or_else(|| "Unknown".to_string());
+
+        for entry in WalkDir::new(&project_path)
+            .into_iter()
+            .filter_map(std::result::Result::ok)
+            .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        {
+            session_files.push((project_name.clone(), entry.path().to_path_buf()));
+        }
+    }
+
+    Ok(session_files)
+}
+
+/// Session files (`.jsonl`) under `root` with their metadata, listed with
+/// async IO so a slow filesystem does not hold up a runtime worker
+///
+/// Like `WalkDir`, symbolic links are not followed; unreadable directories
+/// are skipped.
+pub async fn collect_jsonl_files_async(root: &Path) -> Vec<(PathBuf, fs::Metadata)> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(mut entries) = tokio::fs::read_dir(&dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Ok(file_type) = entry.file_type().await else {
+                continue;
+            };
+            let path = entry.path();
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
+                if let Ok(metadata) = entry.metadata().await {
+                    files.push((path, metadata));
+                }
+            }
+        }
+    }
+
+    files
+}
+
+/// Resolve `<project_path>/<session_id>.jsonl`, rejecting IDs that could escape the project
+pub fn resolve_session_file(project_path: &str, session_id: &str) -> Result<PathBuf, AppError> {
+    if session_id.is_empty() || session_id.contains(['/', '\\']) || session_id.contains("..") {
+        return Err(AppError::invalid_input(format!(
+            "Invalid session ID: {session_id}"
+        )));
+    }
+
+    let session_path = Path::new(project_path).join(format!("{session_id}.jsonl"));
+    if !session_path.is_file() {
+        return Err(AppError::not_found(format!(
+            "Session not found: {session_id}"
+        )));
+    }
+    Ok(session_path)
+}
+
+/// Paths at least this long need the `\\?\` prefix for some Win32 APIs (`MAX_PATH`
+/// minus room for an 8.3 file name)
+const LONG_PATH_THRESHOLD: usize = 248;
+
+/// Convert an absolute Windows path to its extended-length (`\\?\`) form
+///
+/// Verbatim paths do not go through normalization, so `/` separators and
+/// `.`/`..` components are resolved here. Returns None for relative paths
+/// and for paths that already carry a `\\?\` or `\\.\` prefix.
+pub fn to_extended_length_path(path: &str) -> Option<String> {
+    if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") || path.starts_with("//?/") {
+        return None;
+    }
+
+    let path = path.replace('/', "\\");
+    let (prefix, rest) = if let Some(unc) = path.strip_prefix(r"\\") {
+        (r"\\?\UNC\".to_string(), unc.to_string())
+    } else {
+        let bytes = path.as_bytes();
+        if bytes.len() < 3
+            || !bytes[0].is_ascii_alphabetic()
+            || bytes[1] != b':'
+            || bytes[2] != b'\\'
+        {
+            return None;
+        }
+        (format!(r"\\?\{}\", &path[..2]), path[3..].to_string())
+    };
+
+    let mut components: Vec<&str> = Vec::new();
+    for component in rest.split('\\') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            other => components.push(other),
+        }
+    }
+
+    Some(prefix + &components.join("\\"))
+}
+
+/// Path to hand to filesystem calls: long paths get the `\\?\` prefix on
+/// Windows so files under deeply nested project folders can still be opened
+pub fn long_path(path: &Path) -> Cow<'_, Path> {
+    if cfg!(windows) && path.as_os_str().len() >= LONG_PATH_THRESHOLD {
+        if let Some(extended) = path.to_str().and_then(to_extended_length_path) {
+            return Cow::Owned(PathBuf::from(extended));
+        }
+    }
+    Cow::Borrowed(path)
+}
+
+/// Path as shown to the frontend: extended-length prefixes are stripped and
+/// non-UTF-8 sequences replaced instead of dropping the whole path
+pub fn display_path(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    if let Some(unc) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{unc}")
+    } else if let Some(local) = path.strip_prefix(r"\\?\") {
+        local.to_string()
+    } else {
+        path.into_owned()
+    }
+}
+
+/// Final component of a path as a string, lossily converted if not UTF-8
+pub fn file_name_string(path: &Path) -> Option<String> {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
 /// Estimate message count from file size (more accurate calculation)
 pub fn estimate_message_count_from_size(file_size: u64) -> usize {
     // Average JSON message is 800-1200 bytes (using AVERAGE_MESSAGE_SIZE_BYTES)
@@ -136,6 +368,32 @@ mod tests {
         assert_eq!(starts, vec![0, 6, 12]);
     }
 
+    // ===== Model Name Tests =====
+
+    #[test]
+    fn test_normalize_model_name() {
+        for raw in [
+            "claude-sonnet-4-20250514",
+            "claude-sonnet-4-0",
+            "us.anthropic.claude-sonnet-4-20250514-v1:0",
+            "arn:aws:bedrock:us-east-1:123456789012:inference-profile/us.anthropic.claude-sonnet-4-20250514-v1:0",
+            "claude-sonnet-4@20250514",
+            "bedrock/anthropic.claude-sonnet-4-20250514-v1:0",
+        ] {
+            assert_eq!(normalize_model_name(raw), "claude-sonnet-4", "{raw}");
+        }
+        assert_eq!(
+            normalize_model_name("claude-3-5-haiku-latest"),
+            "claude-3-5-haiku"
+        );
+        assert_eq!(
+            normalize_model_name("claude-3-5-sonnet-v2@20241022"),
+            "claude-3-5-sonnet"
+        );
+        assert_eq!(normalize_model_name("claude-opus-4-5"), "claude-opus-4-5");
+        assert_eq!(normalize_model_name("<synthetic>"), "<synthetic>");
+    }
+
     // ===== Project Name Tests =====
 
     #[test]
@@ -178,6 +436,76 @@ mod tests {
         assert_eq!(result, "c");
     }
 
+    #[test]
+    fn test_stable_line_id_is_deterministic() {
+        let path = Path::new("/projects/app/file-a.jsonl");
+        assert_eq!(
+            stable_line_id(Some("session-1"), path, 0),
+            "session-1-line-1"
+        );
+        assert_eq!(
+            stable_line_id(Some("session-1"), path, 41),
+            stable_line_id(Some("session-1"), path, 41)
+        );
+        assert_eq!(stable_line_id(None, path, 2), "file-a-line-3");
+    }
+
+    #[test]
+    fn test_stable_line_id_without_session_differs_per_file() {
+        let a = stable_line_id(None, Path::new("/projects/app/file-a.jsonl"), 0);
+        let b = stable_line_id(None, Path::new("/projects/app/file-b.jsonl"), 0);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_collect_session_files() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let project = temp.path().join("-Users-me-project");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(project.join("a.jsonl"), "").unwrap();
+        fs::write(project.join("notes.txt"), "").unwrap();
+        fs::write(temp.path().join("stray.jsonl"), "").unwrap();
+
+        let files = collect_session_files(temp.path()).unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].0, "-Users-me-project");
+        assert!(files[0].1.ends_with("a.jsonl"));
+    }
+
+    #[tokio::test]
+    async fn test_collect_jsonl_files_async() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let nested = temp.path().join("abc").join("subagents");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(temp.path().join("abc.jsonl"), "{}\n").unwrap();
+        fs::write(nested.join("agent-1.jsonl"), "").unwrap();
+        fs::write(temp.path().join("notes.txt"), "").unwrap();
+
+        let mut files = collect_jsonl_files_async(temp.path()).await;
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(files.len(), 2);
+        assert!(files[0].0.ends_with("abc/subagents/agent-1.jsonl"));
+        assert!(files[1].0.ends_with("abc.jsonl"));
+        assert_eq!(files[1].1.len(), 3);
+        assert!(collect_jsonl_files_async(&temp.path().join("missing"))
+            .await
+            .is_empty());
+    }
+
+    #[test]
+    fn test_resolve_session_file() {
+        let temp = tempfile::TempDir::new().unwrap();
+        fs::write(temp.path().join("abc.jsonl"), "").unwrap();
+        let project_path = temp.path().to_string_lossy().to_string();
+
+        assert!(resolve_session_file(&project_path, "abc").is_ok());
+        assert!(resolve_session_file(&project_path, "missing").is_err());
+        assert!(resolve_session_file(&project_path, "../abc").is_err());
+        assert!(resolve_session_file(&project_path, "").is_err());
+    }
+
     #[test]
     fn test_estimate_message_count_zero_size() {
         // Minimum should be 1
@@ -212,4 +540,36 @@ mod tests {
         let result = estimate_message_count_from_size(1000);
         assert_eq!(result, 1);
     }
+
+    // ===== Path Handling Tests =====
+
+    #[test]
+    fn test_to_extended_length_path() {
+        assert_eq!(
+            to_extended_length_path(r"C:\Users\dev/projects\.\app\..\日本語").as_deref(),
+            Some(r"\\?\C:\Users\dev\projects\日本語")
+        );
+        assert_eq!(
+            to_extended_length_path(r"\\server\share\repo").as_deref(),
+            Some(r"\\?\UNC\server\share\repo")
+        );
+        assert_eq!(to_extended_length_path(r"\\?\C:\already"), None);
+        assert_eq!(to_extended_length_path(r"relative\path"), None);
+    }
+
+    #[test]
+    fn test_display_path_strips_extended_prefix() {
+        assert_eq!(
+            display_path(Path::new(r"\\?\C:\项目\a.jsonl")),
+            r"C:\项目\a.jsonl"
+        );
+        assert_eq!(
+            display_path(Path::new(r"\\?\UNC\server\share")),
+            r"\\server\share"
+        );
+        assert_eq!(
+            display_path(Path::new("/home/dev/a.jsonl")),
+            "/home/dev/a.jsonl"
+        );
+    }
 }
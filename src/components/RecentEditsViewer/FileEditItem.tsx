@@ -22,6 +22,7 @@ import {
 } from "lucide-react";
 import { Highlight, themes } from "prism-react-renderer";
 import { cn } from "@/lib/utils";
+import { commandErrorMessage } from "@/types";
 import { layout } from "@/components/renderers";
 import type { FileEditItemProps, RestoreStatus } from "./types";
 import { getLanguageFromPath, formatTimestamp, getRelativeTime } from "./utils";
@@ -66,8 +67,7 @@ export const FileEditItem: React.FC<FileEditItemProps> = ({ edit, isDarkMode })
       setTimeout(() => setRestoreStatus("idle"), 2000);
     } catch (err) {
       console.error("Failed to restore file:", err);
-      const message = err instanceof Error ? err.message : String(err);
-      setErrorMessage(message);
+      setErrorMessage(commandErrorMessage(err));
       setRestoreStatus("error");
       setTimeout(() => {
         setRestoreStatus("idle");
@@ -10,6 +10,13 @@ import { useCallback, useMemo, useEffect } from "react";
 import { useTranslation } from "react-i18next";
 import { useAppStore } from "../store/useAppStore";
 import type { UseAnalyticsReturn } from "../types/analytics";
+import { commandErrorMessage, isCommandError } from "../types";
+
+/** Message of a command or JS error, or `fallback` for anything else */
+const errorMessageOr = (error: unknown, fallback: string): string =>
+  isCommandError(error) || error instanceof Error
+    ? commandErrorMessage(error)
+    : fallback;
 
 export const useAnalytics = (): UseAnalyticsReturn => {
   const { t } = useTranslation();
@@ -148,7 +155,7 @@ export const useAnalytics = (): UseAnalyticsReturn => {
           setAnalyticsProjectSummary(summary);
         } catch (error) {
           const errorMessage =
-            error instanceof Error ? error.message : t('common.hooks.projectSummaryLoadFailed');
+            errorMessageOr(error, t('common.hooks.projectSummaryLoadFailed'));
           setAnalyticsProjectSummaryError(errorMessage);
           throw error;
         } finally {
@@ -183,7 +190,7 @@ export const useAnalytics = (): UseAnalyticsReturn => {
           }
         } catch (error) {
           const errorMessage =
-            error instanceof Error ? error.message : t('common.hooks.sessionComparisonLoadFailed');
+            errorMessageOr(error, t('common.hooks.sessionComparisonLoadFailed'));
           setAnalyticsSessionComparisonError(errorMessage);
           // 세션 비교 실패는 치명적이지 않으므로 throw하지 않음
         } finally {
@@ -263,7 +270,7 @@ export const useAnalytics = (): UseAnalyticsReturn => {
       }));
     } catch (error) {
       const errorMessage =
-        error instanceof Error ? error.message : t('common.hooks.recentEditsLoadFailed');
+        errorMessageOr(error, t('common.hooks.recentEditsLoadFailed'));
       setAnalyticsRecentEditsError(errorMessage);
       console.error("Failed to load recent edits:", error);
       throw error;
@@ -414,7 +421,7 @@ export const useAnalytics = (): UseAnalyticsReturn => {
 
           await Promise.all(promises);
         } catch (error) {
-          const errorMessage = error instanceof Error ? error.message : t('common.hooks.sessionComparisonLoadFailed');
+          const errorMessage = errorMessageOr(error, t('common.hooks.sessionComparisonLoadFailed'));
           setAnalyticsSessionComparisonError(errorMessage);
           console.error("Failed to update session data:", error);
         } finally {
@@ -173,14 +173,21 @@ export async function fetchRecentEdits(
 
 /**
  * Fetch global statistics across all projects
+ *
+ * @param sources - History sources to include (default: only "claude-code")
+ * @param sourcePaths - Root folder per source, where not the default one
  */
 export async function fetchGlobalStatsSummary(
-  claudePath: string
+  claudePath: string,
+  sources?: string[],
+  sourcePaths?: Record<string, string>
 ): Promise<GlobalStatsSummary> {
   const start = performance.now();
 
   const summary = await invoke<GlobalStatsSummary>("get_global_stats_summary", {
     claudePath,
+    sources,
+    sourcePaths,
   });
 
   if (import.meta.env.DEV) {
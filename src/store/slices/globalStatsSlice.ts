@@ -5,7 +5,7 @@
  */
 
 import type { GlobalStatsSummary } from "../../types";
-import { AppErrorType } from "../../types";
+import { AppErrorType, commandErrorMessage } from "../../types";
 import type { StateCreator } from "zustand";
 import type { FullAppStore } from "./types";
 import { fetchGlobalStatsSummary } from "../../services/analyticsApi";
@@ -59,7 +59,7 @@ export const createGlobalStatsSlice: StateCreator<
       set({ globalSummary: summary });
     } catch (error) {
       console.error("Failed to load global stats:", error);
-      get().setError({ type: AppErrorType.UNKNOWN, message: String(error) });
+      get().setError({ type: AppErrorType.UNKNOWN, message: commandErrorMessage(error) });
       set({ globalSummary: null });
     } finally {
       set({ isLoadingGlobalStats: false });
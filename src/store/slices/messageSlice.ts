@@ -13,7 +13,7 @@ import type {
   ProjectStatsSummary,
   SessionComparison,
 } from "../../types";
-import { AppErrorType } from "../../types";
+import { AppErrorType, commandErrorMessage } from "../../types";
 import type { StateCreator } from "zustand";
 import { buildSearchIndex, clearSearchIndex } from "../../utils/searchIndex";
 import type { FullAppStore } from "./types";
@@ -124,7 +124,7 @@ export const createMessageSlice: StateCreator<
 
       const allMessages = await invoke<ClaudeMessage[]>(
         "load_session_messages",
-        { sessionPath }
+        { sessionPath, mergeParts: true }
       );
 
       // Apply sidechain filter
@@ -177,7 +177,7 @@ export const createMessageSlice: StateCreator<
       }
     } catch (error) {
       console.error("Failed to load session messages:", error);
-      get().setError({ type: AppErrorType.UNKNOWN, message: String(error) });
+      get().setError({ type: AppErrorType.UNKNOWN, message: commandErrorMessage(error) });
       set({ isLoadingMessages: false });
     }
   },
@@ -241,7 +241,7 @@ export const createMessageSlice: StateCreator<
       console.log("새로고침 완료");
     } catch (error) {
       console.error("새로고침 실패:", error);
-      get().setError({ type: AppErrorType.UNKNOWN, message: String(error) });
+      get().setError({ type: AppErrorType.UNKNOWN, message: commandErrorMessage(error) });
     }
   },
 
@@ -256,7 +256,7 @@ export const createMessageSlice: StateCreator<
       console.error("Failed to load session token stats:", error);
       get().setError({
         type: AppErrorType.UNKNOWN,
-        message: `Failed to load token stats: ${error}`,
+        message: `Failed to load token stats: ${commandErrorMessage(error)}`,
       });
       set({ sessionTokenStats: null });
     } finally {
@@ -294,7 +294,7 @@ export const createMessageSlice: StateCreator<
       console.error("Failed to load project token stats:", error);
       get().setError({
         type: AppErrorType.UNKNOWN,
-        message: `Failed to load project token stats: ${error}`,
+        message: `Failed to load project token stats: ${commandErrorMessage(error)}`,
       });
       set({ projectTokenStats: [] });
     } finally {
@@ -11,7 +11,7 @@ import type {
   ProjectMetadata,
   UserSettings,
 } from "../../types";
-import { DEFAULT_USER_METADATA } from "../../types";
+import { DEFAULT_USER_METADATA, commandErrorMessage } from "../../types";
 import { matchGlobPattern } from "../../utils/globUtils";
 import type { StoreSet, StoreGet, FullAppStore } from "./types";
 
@@ -101,7 +101,7 @@ export const createMetadataSlice = (
         userMetadata: DEFAULT_USER_METADATA,
         isMetadataLoaded: true,
         isMetadataLoading: false,
-        metadataError: String(error),
+        metadataError: commandErrorMessage(error),
       });
     }
   },
@@ -113,7 +113,7 @@ export const createMetadataSlice = (
       await invoke("save_user_metadata", { metadata: userMetadata });
     } catch (error) {
       console.error("Failed to save user metadata:", error);
-      set({ metadataError: String(error) });
+      set({ metadataError: commandErrorMessage(error) });
     }
   },
 
@@ -136,7 +136,7 @@ export const createMetadataSlice = (
       set({ userMetadata: updatedMetadata });
     } catch (error) {
       console.error("Failed to update session metadata:", error);
-      set({ metadataError: String(error) });
+      set({ metadataError: commandErrorMessage(error) });
     }
   },
 
@@ -159,7 +159,7 @@ export const createMetadataSlice = (
       set({ userMetadata: updatedMetadata });
     } catch (error) {
       console.error("Failed to update project metadata:", error);
-      set({ metadataError: String(error) });
+      set({ metadataError: commandErrorMessage(error) });
     }
   },
 
@@ -177,7 +177,7 @@ export const createMetadataSlice = (
       set({ userMetadata: updatedMetadata });
     } catch (error) {
       console.error("Failed to update user settings:", error);
-      set({ metadataError: String(error) });
+      set({ metadataError: commandErrorMessage(error) });
     }
   },
 
@@ -7,7 +7,11 @@
 import { invoke } from "@tauri-apps/api/core";
 import { load } from "@tauri-apps/plugin-store";
 import type { ClaudeProject, ClaudeSession, AppError } from "../../types";
-import { AppErrorType } from "../../types";
+import {
+  AppErrorType,
+  commandErrorMessage,
+  isCommandError,
+} from "../../types";
 import type { StateCreator } from "zustand";
 import type { FullAppStore } from "./types";
 
@@ -118,19 +122,14 @@ export const createProjectSlice: StateCreator<
       await get().scanProjects();
     } catch (error) {
       console.error("Failed to initialize app:", error);
-      const errorMessage =
-        error instanceof Error ? error.message : String(error);
+      const message = commandErrorMessage(error);
 
       let errorType = AppErrorType.UNKNOWN;
-      let message = errorMessage;
-
-      if (errorMessage.includes("CLAUDE_FOLDER_NOT_FOUND:")) {
+      if (isCommandError(error) && error.code === "NOT_FOUND") {
         errorType = AppErrorType.CLAUDE_FOLDER_NOT_FOUND;
-        message = errorMessage.split(":")[1] || errorMessage;
-      } else if (errorMessage.includes("PERMISSION_DENIED:")) {
+      } else if (isCommandError(error) && error.code === "PERMISSION_DENIED") {
         errorType = AppErrorType.PERMISSION_DENIED;
-        message = errorMessage.split(":")[1] || errorMessage;
-      } else if (errorMessage.includes("Tauri API")) {
+      } else if (message.includes("Tauri API")) {
         errorType = AppErrorType.TAURI_NOT_AVAILABLE;
       }
 
@@ -159,7 +158,7 @@ export const createProjectSlice: StateCreator<
       set({ projects });
     } catch (error) {
       console.error("Failed to scan projects:", error);
-      set({ error: { type: AppErrorType.UNKNOWN, message: String(error) } });
+      set({ error: { type: AppErrorType.UNKNOWN, message: commandErrorMessage(error) } });
     } finally {
       set({ isLoadingProjects: false });
     }
@@ -180,7 +179,7 @@ export const createProjectSlice: StateCreator<
       set({ sessions });
     } catch (error) {
       console.error("Failed to load project sessions:", error);
-      set({ error: { type: AppErrorType.UNKNOWN, message: String(error) } });
+      set({ error: { type: AppErrorType.UNKNOWN, message: commandErrorMessage(error) } });
     } finally {
       set({ isLoadingSessions: false });
     }
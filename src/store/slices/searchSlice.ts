@@ -6,7 +6,7 @@
 
 import { invoke } from "@tauri-apps/api/core";
 import type { ClaudeMessage, SearchFilters } from "../../types";
-import { AppErrorType } from "../../types";
+import { AppErrorType, commandErrorMessage } from "../../types";
 import type { StateCreator } from "zustand";
 import { searchMessages as searchMessagesFromIndex } from "../../utils/searchIndex";
 import {
@@ -93,7 +93,7 @@ export const createSearchSlice: StateCreator<
       set({ searchResults: results });
     } catch (error) {
       console.error("Failed to search messages:", error);
-      get().setError({ type: AppErrorType.UNKNOWN, message: String(error) });
+      get().setError({ type: AppErrorType.UNKNOWN, message: commandErrorMessage(error) });
     }
   },
 
@@ -43,3 +43,75 @@ export interface PaginatedRecentEdits {
   limit: number;
   has_more: boolean;
 }
+
+// ============================================================================
+// File Activity
+// ============================================================================
+
+/**
+ * Edit activity on one file (get_file_activity_stats)
+ */
+export interface FileActivity {
+  file_path: string;
+  relative_path: string; // Relative to the project cwd when inside it
+  edit_count: number;
+  write_count: number;
+  session_count: number;
+  lines_added: number;
+  lines_removed: number;
+  first_touched: string;
+  last_touched: string;
+}
+
+export interface FileActivityStats {
+  project_cwd?: string;
+  total_edits: number;
+  unique_files: number;
+  files: FileActivity[]; // Most edited first
+}
+
+// ============================================================================
+// File History
+// ============================================================================
+
+export interface FileTouch {
+  message_uuid: string;
+  timestamp: string;
+  tool_name: string;
+  operation: "read" | "edit" | "write";
+}
+
+/**
+ * A session that read or modified a file (find_sessions_by_file)
+ */
+export interface FileSessionMatch {
+  project_name: string;
+  session_id: string;
+  file_path: string; // Session JSONL file
+  touched_path: string; // File path as written in the tool call
+  first_touched: string;
+  last_touched: string;
+  read_count: number;
+  modify_count: number;
+  touches: FileTouch[];
+}
+
+// ============================================================================
+// Local File Preview
+// ============================================================================
+
+/**
+ * Current content of a local file (read_local_file)
+ */
+export interface LocalFileContent {
+  path: string;
+  exists: boolean; // False when the file has been deleted since
+  is_binary: boolean;
+  language: string; // Syntax highlighting language ("text" when unknown)
+  size_bytes: number;
+  modified?: string;
+  total_lines: number;
+  start_line: number; // 1-based, inclusive; 0 when no line is returned
+  end_line: number;
+  content: string;
+}
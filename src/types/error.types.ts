@@ -24,3 +24,36 @@ export interface AppError {
   type: AppErrorType;
   message: string;
 }
+
+// ============================================================================
+// Command Error (rejection value of `invoke`)
+// ============================================================================
+
+export type CommandErrorCode =
+  | "NOT_FOUND"
+  | "PARSE_ERROR"
+  | "PERMISSION_DENIED"
+  | "CANCELLED"
+  | "INDEX_STALE"
+  | "INVALID_INPUT"
+  | "INTERNAL";
+
+export interface CommandError {
+  code: CommandErrorCode;
+  message: string;
+  file?: string; // PARSE_ERROR only
+  line?: number | null; // PARSE_ERROR only, 1-based
+}
+
+export const isCommandError = (error: unknown): error is CommandError =>
+  typeof error === "object" &&
+  error !== null &&
+  "code" in error &&
+  "message" in error;
+
+/** Human-readable message for anything thrown by `invoke` */
+export const commandErrorMessage = (error: unknown): string => {
+  if (isCommandError(error)) return error.message;
+  if (error instanceof Error) return error.message;
+  return String(error);
+};
@@ -97,6 +97,18 @@ export type {
 export type {
   ClaudeProject,
   ClaudeSession,
+  GeneratedSessionSummary,
+  SessionSearchHit,
+  SessionSearchHits,
+  SessionTree,
+  SessionTreeBranch,
+  SessionTreeMessage,
+  ShareOptions,
+  SharedSessionBundle,
+  ShareResult,
+  CompactionEvent,
+  SessionBudget,
+  TrashedItems,
   SearchFilters,
   AppState,
 } from "./session.types";
@@ -113,15 +125,75 @@ export type {
   ModelStats,
   DateRange,
   ProjectStatsSummary,
+  ModelDailyStats,
+  ModelThinkingStats,
   ProjectRanking,
   SessionComparison,
+  ComparedSession,
+  SessionPairComparison,
   GlobalStatsSummary,
+  TimeOfDaySessionLength,
+  LongestSession,
+  WorkPatternStats,
+  SourceStats,
+  RootStats,
+  ServiceTierStats,
+  ProjectCost,
+  CostPeriod,
+  CostReport,
+  BranchCost,
+  BranchCostReport,
+  AgentUsage,
+  SessionSidechainUsage,
+  SidechainUsageReport,
+  MessageTypeCount,
+  MessageTypeGroup,
+  MessageTypeStats,
+  ErrorExample,
+  ErrorCategoryCount,
+  ToolErrorCount,
+  SessionErrors,
+  ErrorReport,
+  PromptLengthBucket,
+  PromptLengthReport,
+  HookUsage,
+  ProjectHookStats,
+  HookStats,
+  SlashCommandUse,
+  SlashCommandProjectCount,
+  SlashCommandCount,
+  SlashCommandStats,
+  WeeklyDigest,
+  UsageBlock,
+  UsageBlockReport,
+  BurnRateWindow,
+  BurnRate,
+  FocusBlock,
+  FocusDay,
+  FocusReport,
+  TimelineSpan,
+  TimelineTurn,
+  SessionTimeline,
+  BudgetUsage,
+  BudgetStatus,
+  BudgetThresholdCrossed,
+  PhaseTiming,
+  OperationProfile,
 } from "./stats.types";
 
 // ============================================================================
 // Edit Types
 // ============================================================================
-export type { RecentFileEdit, RecentEditsResult, PaginatedRecentEdits } from "./edit.types";
+export type {
+  RecentFileEdit,
+  RecentEditsResult,
+  PaginatedRecentEdits,
+  FileActivity,
+  FileActivityStats,
+  FileTouch,
+  FileSessionMatch,
+  LocalFileContent,
+} from "./edit.types";
 
 // ============================================================================
 // Update Types
@@ -137,8 +209,16 @@ export type {
 // ============================================================================
 // Error Types
 // ============================================================================
-export { AppErrorType } from "./error.types";
-export type { AppError } from "./error.types";
+export {
+  AppErrorType,
+  isCommandError,
+  commandErrorMessage,
+} from "./error.types";
+export type {
+  AppError,
+  CommandError,
+  CommandErrorCode,
+} from "./error.types";
 
 // ============================================================================
 // Metadata Types
@@ -146,8 +226,16 @@ export type { AppError } from "./error.types";
 export type {
   SessionMetadata,
   ProjectMetadata,
+  ProjectBudget,
   UserSettings,
   UserMetadata,
+  UsageSinkSettings,
+  UsageSinkStatus,
+  SummarizerSettings,
+  DigestSettings,
+  ScanRoot,
+  UndoAction,
+  JournalEntry,
 } from "./metadata.types";
 export {
   METADATA_SCHEMA_VERSION,
@@ -195,6 +195,8 @@ export interface ClaudeMessage {
   compactMetadata?: { trigger?: string; preTokens?: number };
   // microcompact_boundary fields
   microcompactMetadata?: { trigger?: string; preTokens?: number };
+  // Branch checked out when the entry was written
+  gitBranch?: string;
 }
 
 // ============================================================================
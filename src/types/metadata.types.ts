@@ -21,6 +21,8 @@ export interface SessionMetadata {
   tags?: string[];
   /** User notes about the session */
   notes?: string;
+  /** When the session was archived (hidden from session lists) */
+  archivedAt?: string;
 }
 
 /** Metadata for individual projects */
@@ -31,6 +33,16 @@ export interface ProjectMetadata {
   alias?: string;
   /** Parent project path for worktree grouping */
   parentProject?: string;
+  /** Monthly usage budget */
+  budget?: ProjectBudget;
+}
+
+/** Monthly token and/or cost budget of a project (UTC calendar months) */
+export interface ProjectBudget {
+  monthlyTokens?: number;
+  monthlyCostUsd?: number;
+  /** Percentages of the budget that trigger a warning (80 and 100 when empty) */
+  thresholds?: number[];
 }
 
 /** Global user settings */
@@ -39,6 +51,147 @@ export interface UserSettings {
   hiddenPatterns?: string[];
   /** Whether to automatically group worktrees under their parent repos */
   worktreeGrouping?: boolean;
+  /** Maximum number of session files opened at once while scanning */
+  maxOpenFiles?: number;
+  /** Fewer parallel file reads and searches, streamed session loading and no caches */
+  lowMemory?: boolean;
+  /** Custom prices replacing list prices in cost estimates (first match wins) */
+  pricingOverrides?: PricingOverride[];
+  /** Allow reading the current content of local files (then-vs-now previews) */
+  localFilePreview?: boolean;
+  /** Entry categories left out of message counts (default: progress, system, meta) */
+  countExclusions?: Array<"progress" | "system" | "meta" | "sidechain">;
+  /** Custom session columns computed by jq expressions */
+  derivedFields?: DerivedField[];
+  /** Extra regular expressions masked by redacted exports */
+  redactionPatterns?: string[];
+  /** Automatic archiving of old sessions (off when unset) */
+  archive?: ArchiveSettings;
+  /** Weekly digest written after each week (off when unset) */
+  weeklyDigest?: DigestSettings;
+  /** Streaming of new usage to ClickHouse or Timeplus (off when unset) */
+  usageSink?: UsageSinkSettings;
+  /** Model generating session summaries (unavailable when unset) */
+  summarizer?: SummarizerSettings;
+  /** Extra Claude folders (or copied projects folders) included in the global stats */
+  scanRoots?: ScanRoot[];
+}
+
+/** Labelled folder scanned for global stats, e.g. one per team member */
+export interface ScanRoot {
+  label: string;
+  /** Claude folder or a copied projects folder */
+  path: string;
+}
+
+/** Background export of old session files into compressed archives */
+export interface ArchiveSettings {
+  enabled: boolean;
+  /** Sessions not modified for this many days are archived */
+  olderThanDays: number;
+  format: "zip" | "tar.zst";
+  /** Archive folder (archives in the metadata folder when unset) */
+  directory?: string;
+  /** Claude folder whose projects are archived (~/.claude when unset) */
+  claudePath?: string;
+  /** Hours between runs; only archived at startup when unset */
+  intervalHours?: number;
+  /** Delete session files once they are safely archived */
+  removeOriginals?: boolean;
+}
+
+/** Weekly digest written to a folder once each week is over */
+export interface DigestSettings {
+  enabled: boolean;
+  format: "markdown" | "html";
+  /** Digest folder (digests in the metadata folder when unset) */
+  directory?: string;
+  /** Claude folder the digest covers (~/.claude when unset) */
+  claudePath?: string;
+}
+
+/** Model used by summarize_session; ollama and llamacpp run fully offline */
+export interface SummarizerSettings {
+  backend: "anthropic" | "ollama" | "llamacpp";
+  /** Model name as the backend knows it, e.g. llama3.1:8b */
+  model: string;
+  /** Server base URL (the backend's usual local port or the Anthropic API when unset) */
+  endpoint?: string;
+  /** Anthropic API key, or bearer token of a llama.cpp server */
+  apiKey?: string;
+  /** Transcript characters sent to the model (48,000 when unset) */
+  maxInputChars?: number;
+  /** Mask secrets in the transcript before sending it */
+  redact?: boolean;
+}
+
+/** Continuous streaming of new usage rows (export_warehouse columns) */
+export interface UsageSinkSettings {
+  enabled: boolean;
+  kind: "clickhouse" | "timeplus";
+  /** HTTP interface of the server, e.g. http://localhost:8123 */
+  endpoint: string;
+  /** Table (ClickHouse, optionally db.table) or stream (Timeplus) for usage rows */
+  usageTable: string;
+  /** Table or stream receiving every message; messages are not streamed when unset */
+  messagesTable?: string;
+  /** ClickHouse user */
+  username?: string;
+  /** ClickHouse password or Timeplus API key */
+  password?: string;
+  /** Claude folder whose sessions are streamed (~/.claude when unset) */
+  claudePath?: string;
+}
+
+/** State of the usage sink since startup */
+export interface UsageSinkStatus {
+  enabled: boolean;
+  kind: string | null;
+  usage_rows_sent: number;
+  message_rows_sent: number;
+  /** Rows waiting for the next delivery */
+  pending_rows: number;
+  /** Oldest rows discarded while the server was unreachable */
+  dropped_rows: number;
+  last_sent_at: string | null;
+  /** Cleared by the next successful delivery */
+  last_error: string | null;
+}
+
+/** Custom session column; the jq expression receives the session's raw entries */
+export interface DerivedField {
+  name: string;
+  expression: string;
+}
+
+/** Negotiated pricing (USD per million tokens) for some models and/or projects */
+export interface PricingOverride {
+  /** Glob pattern matched against the model ID; any model when unset */
+  modelPattern?: string;
+  /** Glob pattern matched against the session working directory; any project when unset */
+  projectPattern?: string;
+  /** Usage is free (internal deployment) */
+  free?: boolean;
+  input?: number;
+  output?: number;
+  cacheWrite?: number;
+  cacheRead?: number;
+}
+
+/** State of the user pricing file (`pricing.toml` or `pricing.json` in the metadata folder) */
+export interface PricingFileStatus {
+  /** Loaded file, or where one is looked for */
+  path: string;
+  exists: boolean;
+  loaded_at?: string;
+  /** Currency costs are shown in */
+  currency: string;
+  /** Factor applied to every cost */
+  currency_multiplier: number;
+  custom_model_count: number;
+  override_count: number;
+  /** Why the file was rejected; the previous prices stay in effect */
+  error?: string;
 }
 
 /** Root structure for all user metadata */
@@ -53,6 +206,40 @@ export interface UserMetadata {
   settings: UserSettings;
 }
 
+/** State an operation changed, before and after it ran */
+export type UndoAction =
+  | {
+      kind: "session_metadata";
+      session_id: string;
+      before?: SessionMetadata;
+      after?: SessionMetadata;
+    }
+  | {
+      kind: "project_metadata";
+      project_path: string;
+      before?: ProjectMetadata;
+      after?: ProjectMetadata;
+    }
+  | { kind: "settings"; before: UserSettings; after: UserSettings }
+  | { kind: "metadata"; before: UserMetadata; after: UserMetadata }
+  | {
+      kind: "file_content";
+      path: string;
+      before?: string; // Unset when the file did not exist
+      after?: string;
+    };
+
+/** A mutating operation recorded in the operation journal */
+export interface JournalEntry {
+  op_id: string;
+  timestamp: string;
+  operation: string; // Command name, "undo" or "redo"
+  summary: string;
+  undoes?: string; // Operation reverted by an undo/redo entry
+  undo?: UndoAction; // Unset when the operation cannot be undone
+  undone_by?: string;
+}
+
 /** Default user metadata for initialization */
 export const DEFAULT_USER_METADATA: UserMetadata = {
   version: METADATA_SCHEMA_VERSION,
@@ -73,7 +260,12 @@ export const isSessionMetadataEmpty = (metadata: SessionMetadata): boolean => {
 
 /** Helper to check if project metadata is empty */
 export const isProjectMetadataEmpty = (metadata: ProjectMetadata): boolean => {
-  return !metadata.hidden && !metadata.alias && !metadata.parentProject;
+  return (
+    !metadata.hidden &&
+    !metadata.alias &&
+    !metadata.parentProject &&
+    !metadata.budget
+  );
 };
 
 /** Helper to get session display name (custom name or fallback) */
@@ -25,13 +25,142 @@ export interface ClaudeSession {
   actual_session_id: string; // Actual session ID from the messages
   file_path: string; // JSONL file full path
   project_name: string;
-  message_count: number;
+  message_count: number; // Adjusted by the countExclusions setting
+  raw_message_count?: number; // Every entry, including excluded ones
   first_message_time: string;
   last_message_time: string;
   last_modified: string; // File last modified time
   has_tool_use: boolean;
   has_errors: boolean;
   summary?: string;
+  first_user_message?: string; // First prompt, even when a summary exists
+  derived_fields?: Record<string, unknown>; // Values of the derivedFields setting
+  source?: "claude-desktop" | "cursor" | "aider"; // Set for chats read from other tools
+}
+
+/** Session summary generated by the configured summarizer */
+export interface GeneratedSessionSummary {
+  session_id: string;
+  backend: "anthropic" | "ollama" | "llamacpp";
+  model: string;
+  summary: string;
+  generated_at: string;
+  transcript_chars: number; // Characters sent to the model
+  truncated: boolean; // Middle of the transcript left out
+}
+
+/** Position of one match inside an open session (find next / previous) */
+export interface SessionSearchHit {
+  message_uuid: string;
+  block_type: "text" | "thinking" | "tool_result";
+  block_index: number | null; // null when the content is a plain string
+  start: number; // UTF-16 offsets into the block text
+  end: number;
+}
+
+/** Every match of a query in one session, in transcript order */
+export interface SessionSearchHits {
+  session_id: string;
+  total_hits: number;
+  hits: SessionSearchHit[];
+  truncated: boolean; // More than the returned hits exist
+}
+
+/** A message in the session tree */
+export interface SessionTreeMessage {
+  id: string; // Message uuid
+  message_type: string;
+  label: string; // Truncated preview of the message content
+  timestamp: string;
+  tool_names: string[];
+  child_count: number; // Replies to this message, across all branches
+  is_branch_point: boolean; // More than one reply (edit, retry or sub-agent)
+}
+
+/** A linear run of messages, with the branches forking off it */
+export interface SessionTreeBranch {
+  kind: "main" | "branch" | "sidechain";
+  fork_id: string | null; // Message the branch replies to (null for roots)
+  messages: SessionTreeMessage[];
+  branches: SessionTreeBranch[]; // In order of their fork message
+}
+
+/** Nested tree of a session's conversation */
+export interface SessionTree {
+  session_id: string;
+  roots: SessionTreeBranch[];
+  message_count: number;
+  branch_point_count: number;
+  branch_count: number; // Edited or retried branches
+  sidechain_count: number; // Sub-agent threads
+}
+
+/** Options of `share_session` */
+export interface ShareOptions {
+  passphrase: string; // At least 8 characters; needed to import the bundle
+  output_path: string; // Bundle file to write
+  redact?: boolean; // Mask secrets (default true)
+  title?: string; // Shown instead of the first prompt
+  note?: string; // For the reviewer
+  shared_by?: string;
+  selection?: {
+    uuids?: string[];
+    from_uuid?: string;
+    to_uuid?: string;
+  };
+}
+
+/** Content of a shared session bundle, once decrypted */
+export interface SharedSessionBundle {
+  bundle_id: string;
+  shared_at: string;
+  shared_by?: string;
+  title?: string;
+  note?: string;
+  session_id: string; // Session ID on the sharer's machine
+  project_name: string; // Project on the sharer's machine
+  redacted: boolean;
+  messages: ClaudeMessage[];
+}
+
+/** Outcome of `share_session` */
+export interface ShareResult {
+  bundle_id: string;
+  path: string;
+  bytes: number; // Size of the encrypted bundle
+  message_count: number;
+  redacted: boolean;
+}
+
+/** A compaction of the conversation context */
+export interface CompactionEvent {
+  uuid?: string;
+  timestamp?: string;
+  kind: "compact" | "microcompact";
+  trigger?: string; // "manual", "auto", ...
+  pre_tokens?: number; // Context size before compacting
+}
+
+/** Context usage of a session (`get_session_budget`) */
+export interface SessionBudget {
+  session_id: string;
+  model?: string; // Model of the last response
+  context_tokens: number; // Estimated current context size
+  context_limit: number; // Context window of the model
+  percent_used: number; // 0-100
+  remaining_tokens: number;
+  since_compaction: boolean; // No response since the last compaction
+  last_auto_compact_tokens?: number; // Context size the last auto-compaction ran at
+  compactions: CompactionEvent[];
+  last_updated?: string; // Timestamp of the last response
+}
+
+/**
+ * Files and folders moved to the OS trash (delete_session, delete_project)
+ */
+export interface TrashedItems {
+  session_count: number;
+  paths: string[]; // Session files, subagent folders or the project folder
 }
 
 // ============================================================================
@@ -45,6 +174,10 @@ export interface SearchFilters {
   hasToolCalls?: boolean;
   hasErrors?: boolean;
   hasFileChanges?: boolean;
+  /** Parts of a message to search (default: all) */
+  fields?: Array<"text" | "thinking" | "tool_input" | "tool_result">;
+  /** Claude desktop app folder whose conversations are searched too */
+  desktopPath?: string;
 }
 
 // ============================================================================
@@ -16,10 +16,16 @@ export interface SessionTokenStats {
   total_cache_creation_tokens: number;
   total_cache_read_tokens: number;
   total_tokens: number;
-  message_count: number;
+  message_count: number; // Adjusted by the countExclusions setting
+  /** Every entry, including those excluded by the counting rules */
+  raw_message_count: number;
   first_message_time: string;
   last_message_time: string;
   summary?: string;
+  /** Entries whose usage repeated an already counted API response */
+  duplicate_usage_entries: number;
+  /** Recorded costUSD, or the estimate from token usage where missing */
+  total_cost_usd: number;
 }
 
 /**
@@ -77,6 +83,15 @@ export interface ModelStats {
   output_tokens: number;
   cache_creation_tokens: number;
   cache_read_tokens: number;
+  cost_usd: number;
+  /** Raw model names grouped under model_name, most tokens first */
+  variants: ModelVariantStats[];
+}
+
+export interface ModelVariantStats {
+  model_name: string;
+  message_count: number;
+  token_count: number;
 }
 
 // ============================================================================
@@ -97,6 +112,7 @@ export interface ProjectStatsSummary {
   project_name: string;
   total_sessions: number;
   total_messages: number;
+  raw_total_messages: number; // Including entries excluded by the counting rules
   total_tokens: number;
   avg_tokens_per_session: number;
   avg_session_duration: number; // in minutes
@@ -111,13 +127,46 @@ export interface ProjectStatsSummary {
     cache_creation: number;
     cache_read: number;
   };
+  total_cost_usd: number; // Recorded or estimated, see SessionTokenStats
+  service_tier_breakdown: ServiceTierStats[]; // Most tokens first
+  model_daily_stats: ModelDailyStats[]; // By date, then most tokens first
+  thinking_stats: ModelThinkingStats[]; // Most thinking turns first
+}
+
+/**
+ * Usage of one model on one day (UTC)
+ */
+export interface ModelDailyStats {
+  date: string; // YYYY-MM-DD
+  model_name: string; // Aliases grouped as in ModelStats
+  message_count: number;
+  token_count: number;
+  cost_usd: number;
+}
+
+/**
+ * Extended thinking usage of one model; thinking tokens are estimated from
+ * the logged (possibly summarized) thinking text
+ */
+export interface ModelThinkingStats {
+  model_name: string; // Aliases grouped as in ModelStats
+  turn_count: number; // Assistant responses
+  thinking_turn_count: number;
+  thinking_block_count: number; // Including redacted blocks
+  estimated_thinking_tokens: number;
+  output_tokens: number;
+  thinking_token_share: number; // Percent of output tokens
+  avg_thinking_tokens: number; // Per thinking turn
 }
 
 export interface ProjectRanking {
   project_name: string;
+  source?: string; // Absent for Claude Code projects
+  root?: string; // Scan root label, set when scan roots are registered
   sessions: number;
   messages: number;
   tokens: number;
+  cost_usd: number;
 }
 
 // ============================================================================
@@ -133,6 +182,42 @@ export interface SessionComparison {
   is_above_average: boolean;
 }
 
+/** One side of a `compare_sessions` comparison */
+export interface ComparedSession {
+  session_id: string;
+  project_name: string | null;
+  file_path: string;
+  message_count: number; // Adjusted by the counting rules
+  input_tokens: number;
+  output_tokens: number;
+  cache_creation_tokens: number;
+  cache_read_tokens: number;
+  total_tokens: number;
+  cost_usd: number;
+  first_message_time: string | null;
+  last_message_time: string | null;
+  duration_seconds: number;
+  models: string[];
+  tools: ToolUsageStats[]; // Most used first
+  files_touched: string[]; // Read or modified through file tools
+}
+
+/** Side-by-side comparison of two sessions; differences are `b - a` */
+export interface SessionPairComparison {
+  a: ComparedSession;
+  b: ComparedSession;
+  token_difference: number;
+  cost_difference_usd: number;
+  duration_difference_seconds: number;
+  message_difference: number;
+  shared_tools: string[];
+  tools_only_in_a: string[];
+  tools_only_in_b: string[];
+  shared_files: string[];
+  files_only_in_a: string[];
+  files_only_in_b: string[];
+}
+
 // ============================================================================
 // Global Stats Summary
 // ============================================================================
@@ -141,7 +226,9 @@ export interface GlobalStatsSummary {
   total_projects: number;
   total_sessions: number;
   total_messages: number;
+  raw_total_messages: number; // Including entries excluded by the counting rules
   total_tokens: number;
+  total_cost_usd: number; // Recorded or estimated, see SessionTokenStats
   total_session_duration_minutes: number;
   date_range: DateRange;
   token_distribution: {
@@ -155,4 +242,582 @@ export interface GlobalStatsSummary {
   most_used_tools: ToolUsageStats[];
   model_distribution: ModelStats[];
   top_projects: ProjectRanking[];
+  /** In the order the sources were requested */
+  source_breakdown: SourceStats[];
+  /** Most tokens first */
+  service_tier_breakdown: ServiceTierStats[];
+  /** Local folder first, then the scanRoots setting; empty without scan roots */
+  root_breakdown: RootStats[];
+  work_patterns: WorkPatternStats;
+  /** By date, then most tokens first */
+  model_daily_stats: ModelDailyStats[];
+}
+
+/**
+ * Average length of the sessions started in one part of the day (UTC)
+ */
+export interface TimeOfDaySessionLength {
+  period: "night" | "morning" | "afternoon" | "evening"; // 6-hour periods from midnight
+  session_count: number;
+  avg_duration_minutes: number; // Active minutes
+}
+
+/**
+ * Session with the most active minutes
+ */
+export interface LongestSession {
+  project_name: string;
+  file_path: string;
+  started_at: string;
+  duration_minutes: number;
+}
+
+/**
+ * Insights derived from daily activity: streaks, busiest weekday and
+ * session lengths
+ */
+export interface WorkPatternStats {
+  active_days: number; // Days (UTC) with at least one message
+  current_streak_days: number; // Consecutive active days up to today or yesterday
+  longest_streak_days: number;
+  longest_streak_start: string | null; // YYYY-MM-DD, the most recent on ties
+  longest_streak_end: string | null;
+  busiest_weekday: number | null; // 0 = Sunday, as in ActivityHeatmap
+  session_length_by_time_of_day: TimeOfDaySessionLength[]; // Night first
+  longest_session: LongestSession | null;
+}
+
+/**
+ * Claude Code usage found under one scan root (e.g. one team member)
+ */
+export interface RootStats {
+  label: string; // "local" for the Claude folder of the request
+  path: string;
+  total_projects: number;
+  total_sessions: number;
+  total_messages: number;
+  total_tokens: number;
+  total_cost_usd: number;
+  first_message: string | null;
+  last_message: string | null;
+}
+
+/**
+ * Usage billed under one service tier ("standard", "priority", "batch"...)
+ */
+export interface ServiceTierStats {
+  service_tier: string; // "unknown" if not recorded
+  response_count: number;
+  total_tokens: number;
+  cost_usd: number;
+}
+
+/**
+ * Usage of one history source ("claude-code", "cursor"...)
+ */
+export interface SourceStats {
+  source: string;
+  total_sessions: number;
+  total_messages: number;
+  total_tokens: number | null; // null if the source records no usage
+  total_cost_usd: number | null; // null if the source records no usage
+}
+
+/**
+ * Cost of one project within a cost period
+ */
+export interface ProjectCost {
+  project_name: string;
+  cost_usd: number;
+  total_tokens: number;
+}
+
+/**
+ * Cost of one calendar month, ISO week or billing cycle
+ */
+export interface CostPeriod {
+  period: string; // "2025-01", "2025-W03" or the cycle's first day
+  start_date: string; // YYYY-MM-DD, UTC
+  end_date: string; // Last day, inclusive
+  cost_usd: number;
+  total_tokens: number;
+  response_count: number;
+  session_count: number;
+  projects: ProjectCost[]; // Most expensive first
+}
+
+/**
+ * Cost rolled up by month, ISO week and billing cycle, oldest period first
+ */
+export interface CostReport {
+  scope: string;
+  billing_anchor_day: number;
+  total_cost_usd: number;
+  months: CostPeriod[];
+  weeks: CostPeriod[];
+  billing_periods: CostPeriod[];
+}
+
+/**
+ * Usage and cost of the responses written on one git branch
+ */
+export interface BranchCost {
+  branch: string | null; // null when the log records no branch
+  project_names: string[];
+  session_count: number;
+  response_count: number;
+  input_tokens: number;
+  output_tokens: number;
+  cache_creation_tokens: number;
+  cache_read_tokens: number;
+  total_tokens: number;
+  cost_usd: number;
+  first_activity: string;
+  last_activity: string;
+}
+
+/**
+ * Usage and cost by git branch, most expensive branch first
+ */
+export interface BranchCostReport {
+  scope: string;
+  total_cost_usd: number;
+  branches: BranchCost[];
+}
+
+/**
+ * Sub-agent usage of one agent type across sessions
+ */
+export interface AgentUsage {
+  agent_type: string; // "sidechain" when the launching Task call is unknown
+  run_count: number;
+  session_count: number;
+  message_count: number;
+  input_tokens: number;
+  output_tokens: number;
+  cache_creation_tokens: number;
+  cache_read_tokens: number;
+  total_tokens: number;
+}
+
+/**
+ * Main-thread and sidechain usage of one session with sub-agent runs
+ */
+export interface SessionSidechainUsage {
+  session_id: string;
+  file_path: string;
+  project_name: string | null;
+  run_count: number;
+  main_tokens: number;
+  sidechain_tokens: number;
+  agents: string[]; // By tokens (descending)
+}
+
+/**
+ * Main-thread vs sidechain (sub-agent) usage over a scope
+ */
+export interface SidechainUsageReport {
+  scope: string;
+  session_count: number;
+  sessions_with_sidechains: number;
+  run_count: number;
+  main_message_count: number;
+  sidechain_message_count: number;
+  main_tokens: number;
+  sidechain_tokens: number;
+  sidechain_share: number; // 0-1
+  agents: AgentUsage[];
+  sessions: SessionSidechainUsage[];
+}
+
+/**
+ * Entries of one type (e.g. "assistant", "system:turn_duration") and their log volume
+ */
+export interface MessageTypeCount {
+  message_type: string; // `type`, with the subtype after a colon when present
+  entry_count: number;
+  bytes: number;
+  byte_share: number; // Share of the bytes of the group (0-1)
+}
+
+/**
+ * Message type breakdown of a session or a project
+ */
+export interface MessageTypeGroup {
+  name: string; // Session ID or project name
+  path: string; // Session file or project folder
+  entry_count: number;
+  bytes: number;
+  conversation_share: number; // Share of the bytes in user and assistant entries
+  types: MessageTypeCount[]; // By bytes (descending)
+}
+
+/**
+ * How much of the log volume is conversation and how much machinery
+ */
+export interface MessageTypeStats {
+  scope: string;
+  totals: MessageTypeGroup;
+  projects: MessageTypeGroup[];
+  sessions: MessageTypeGroup[]; // Sub-agent files count towards their session
+}
+
+/**
+ * One error found in a session log
+ */
+export interface ErrorExample {
+  session_id: string;
+  message_uuid: string | null; // Entry reporting the error
+  timestamp: string | null;
+  tool_name: string | null; // Failed tool, for tool and hook failures
+  message: string; // Truncated to 200 characters
+}
+
+/**
+ * Errors of one category, with the first few distinct messages
+ */
+export interface ErrorCategoryCount {
+  category: "tool_failure" | "api_error" | "rate_limit" | "hook_failure" | "system_error";
+  count: number;
+  examples: ErrorExample[];
+}
+
+/**
+ * Failed calls of one tool
+ */
+export interface ToolErrorCount {
+  tool_name: string;
+  call_count: number;
+  error_count: number;
+  error_rate: number; // error_count / call_count (0-1)
+}
+
+/**
+ * Errors of one session
+ */
+export interface SessionErrors {
+  session_id: string;
+  project_name: string | null;
+  file_path: string;
+  error_count: number;
+  categories: ErrorCategoryCount[]; // Most errors first
+}
+
+/**
+ * Categorized errors of a session, project or all projects
+ */
+export interface ErrorReport {
+  scope: string;
+  session_count: number;
+  sessions_with_errors: number;
+  total_errors: number;
+  categories: ErrorCategoryCount[]; // Most errors first
+  tools: ToolErrorCount[]; // Tools with failures, most failures first
+  sessions: SessionErrors[]; // Sessions with errors, most errors first
+}
+
+/** Response size, time and cost of the prompts in one length bucket */
+export interface PromptLengthBucket {
+  label: string; // Word count, e.g. "1-10"
+  prompt_count: number;
+  avg_words: number;
+  avg_output_tokens: number;
+  avg_duration_seconds: number; // Prompt to the last response or tool result of its turn
+  avg_tool_calls: number;
+  avg_cost_usd: number;
+  retry_rate: number; // Fraction of prompts followed by a correction or interrupt (0-1)
+}
+
+/** Prompt length against what the prompts produced and cost */
+export interface PromptLengthReport {
+  scope: string;
+  session_count: number;
+  prompt_count: number;
+  buckets: PromptLengthBucket[];
+  /** Pearson correlations with prompt word count; null without enough data */
+  output_tokens_correlation: number | null;
+  duration_correlation: number | null;
+  tool_calls_correlation: number | null;
+  cost_correlation: number | null;
+}
+
+/** How often one hook command ran, blocked and failed */
+export interface HookUsage {
+  command: string;
+  event: string; // "PreToolUse", "PostToolUse", "Stop", ...
+  runs: number;
+  sessions: number;
+  prevented_continuations: number; // Runs in a Stop hook round that prevented continuation
+  failures: number;
+  prevent_rate: number; // 0-1
+  failure_rate: number; // 0-1
+}
+
+/** Hook activity of one project */
+export interface ProjectHookStats {
+  project_name: string;
+  sessions_with_hooks: number;
+  total_runs: number;
+  stop_summaries: number; // Stop hook rounds
+  prevented_continuations: number;
+  failed_summaries: number; // Stop hook rounds that reported errors
+  hooks: HookUsage[]; // Most runs first
+}
+
+/** Hook usage, blocking and failures per project (`get_hook_stats`) */
+export interface HookStats {
+  session_count: number;
+  sessions_with_hooks: number;
+  total_runs: number;
+  projects: ProjectHookStats[]; // Most runs first
+}
+
+/** One run of a slash command */
+export interface SlashCommandUse {
+  name: string; // With its leading slash, e.g. "/compact"
+  args?: string;
+  session_id: string;
+  project_name: string;
+  timestamp?: string;
+  uuid?: string;
+}
+
+/** Runs of a slash command (or of all commands) in one project */
+export interface SlashCommandProjectCount {
+  project_name: string;
+  uses: number;
+}
+
+/** Usage of one slash command */
+export interface SlashCommandCount {
+  name: string;
+  uses: number;
+  sessions: number;
+  last_used?: string;
+  projects: SlashCommandProjectCount[]; // Most uses first
+}
+
+/** Slash command usage per command and project (`get_slash_command_stats`) */
+export interface SlashCommandStats {
+  session_count: number;
+  sessions_with_commands: number;
+  total_uses: number;
+  commands: SlashCommandCount[]; // Most uses first
+  projects: SlashCommandProjectCount[]; // Most uses first
+  recent: SlashCommandUse[]; // Latest runs first
+}
+
+/** Digest of one week (`generate_weekly_digest`) */
+export interface WeeklyDigest {
+  start_date: string; // YYYY-MM-DD (Monday)
+  end_date: string; // YYYY-MM-DD (Sunday)
+  format: "markdown" | "html";
+  content: string;
+  session_count: number;
+  project_count: number;
+  total_cost_usd: number;
+  files_changed: number;
+  error_count: number;
+  current_streak_days: number; // Consecutive active days up to the end of the week
+}
+
+/**
+ * One 5-hour usage window, as enforced by Claude subscription limits
+ */
+export interface UsageBlock {
+  start_time: string; // First activity floored to the hour (RFC 3339, UTC)
+  end_time: string; // start_time + 5 hours
+  last_activity_time: string;
+  is_active: boolean; // The window is still open
+  response_count: number;
+  session_count: number;
+  input_tokens: number;
+  output_tokens: number;
+  cache_creation_tokens: number;
+  cache_read_tokens: number;
+  total_tokens: number;
+  cost_usd: number;
+  models: string[];
+  limit_ratio: number | null; // total_tokens / token_limit
+  near_limit: boolean; // Reached 90% of token_limit
+}
+
+/**
+ * 5-hour blocks of a scope, oldest first
+ */
+export interface UsageBlockReport {
+  scope: string;
+  token_limit: number | null; // Given by the caller, else the largest completed block
+  limit_is_estimated: boolean;
+  blocks: UsageBlock[];
+}
+
+/**
+ * Tokens and cost spent over a time window, and their hourly rate
+ */
+export interface BurnRateWindow {
+  start_time: string; // First response in the window (RFC 3339, UTC)
+  last_activity_time: string;
+  response_count: number;
+  total_tokens: number;
+  cost_usd: number;
+  tokens_per_hour: number; // Between the first and last response
+  cost_per_hour: number;
+}
+
+/**
+ * Current burn rate of a scope and projection of the active block (get_burn_rate)
+ */
+export interface BurnRate {
+  scope: string;
+  generated_at: string;
+  today: BurnRateWindow | null; // Current UTC day
+  active_block: UsageBlock | null;
+  block_rate: BurnRateWindow | null;
+  token_limit: number | null; // As in UsageBlockReport
+  limit_is_estimated: boolean;
+  projected_block_tokens: number | null; // At the block rate until the block ends
+  projected_limit_time: string | null; // When the limit is hit, if before the block ends
+}
+
+/**
+ * A stretch of work: turns no further apart than the idle gap
+ */
+export interface FocusBlock {
+  start_time: string; // First turn (RFC 3339, UTC)
+  end_time: string; // Last turn
+  focus_minutes: number; // end - start, plus the trailing allowance
+  turn_count: number;
+  session_count: number;
+  project_names: string[]; // Sorted, distinct
+}
+
+/**
+ * Focus blocks of one UTC day
+ */
+export interface FocusDay {
+  date: string; // YYYY-MM-DD
+  focus_minutes: number;
+  span_minutes: number; // First to last turn of the day, idle time included
+  blocks: FocusBlock[];
+}
+
+/**
+ * Estimated focused time per day, oldest day first
+ */
+export interface FocusReport {
+  scope: string;
+  idle_gap_minutes: number; // A longer pause between turns ends a block
+  trailing_minutes: number; // Credited after the last turn of each block
+  total_focus_minutes: number;
+  session_span_minutes: number; // Sum of first-to-last message spans of the sessions
+  days: FocusDay[];
+}
+
+/**
+ * A bar of the session timeline
+ */
+export interface TimelineSpan {
+  kind: "user_wait" | "idle" | "assistant" | "tool";
+  start_time: string; // RFC 3339, UTC
+  end_time: string;
+  duration_ms: number;
+  tool_name?: string; // Tool spans only
+  tool_use_id?: string;
+}
+
+/**
+ * A user prompt and everything up to the next one
+ */
+export interface TimelineTurn {
+  index: number;
+  prompt_uuid?: string; // Absent for activity before the first prompt
+  prompt_preview: string;
+  start_time: string;
+  end_time: string;
+  wait_before_ms: number; // Pause since the previous turn ended
+  reported_duration_ms?: number; // `durationMs` of the turn's `turn_duration` entry
+  assistant_ms: number; // Turn duration minus tool time
+  tool_ms: number; // Parallel tool calls counted once
+  tool_count: number;
+  spans: TimelineSpan[]; // Pause before the turn first, then by start time
+}
+
+/**
+ * Turn-level timeline of a session's main conversation
+ */
+export interface SessionTimeline {
+  session_id: string;
+  start_time?: string;
+  end_time?: string;
+  idle_gap_minutes: number; // Longer pauses between turns are idle, not user wait
+  total_ms: number;
+  user_wait_ms: number;
+  idle_ms: number;
+  assistant_ms: number;
+  tool_ms: number;
+  turns: TimelineTurn[];
+}
+
+/**
+ * Consumption of one budgeted quantity (tokens or USD) this month
+ */
+export interface BudgetUsage {
+  used: number;
+  budget: number;
+  percent: number;
+  projected: number; // Month-end total at the recent daily rate
+  projected_percent: number;
+}
+
+/**
+ * Budget consumption of one project in the current month
+ */
+export interface BudgetStatus {
+  project_path: string;
+  project_name: string;
+  month: string; // "2025-03" (UTC)
+  days_elapsed: number; // Including today
+  days_in_month: number;
+  tokens: BudgetUsage | null; // Null without a token budget
+  cost: BudgetUsage | null; // Null without a cost budget
+  crossed_threshold: number | null; // Highest threshold reached by either
+}
+
+/**
+ * Payload of the "budget-threshold-crossed" event
+ */
+export interface BudgetThresholdCrossed {
+  project_path: string;
+  project_name: string;
+  month: string;
+  metric: "tokens" | "cost";
+  threshold: number; // Percent
+  usage: BudgetUsage;
+}
+
+/**
+ * Wall time of one phase of a profiled operation
+ */
+export interface PhaseTiming {
+  phase: "scan" | "io" | "parse" | "aggregate" | "serialize";
+  duration_ms: number;
+  share: number; // Fraction of the summed phases
+}
+
+/**
+ * Timing breakdown of one backend operation, for performance reports
+ */
+export interface OperationProfile {
+  operation: string;
+  app_version: string;
+  os_type: string;
+  worker_threads: number;
+  file_count: number;
+  bytes_read: number;
+  entry_count: number;
+  result_bytes: number; // Size of the serialized command result
+  command_ms: number; // Run time of the command itself, files cached
+  total_ms: number; // Sum of the phases
+  phases: PhaseTiming[];
 }